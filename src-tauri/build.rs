@@ -1,4 +1,45 @@
+use std::{env, fs, path::Path};
+
 fn main() {
     println!("cargo:rerun-if-changed=migrations/");
-    tauri_build::build()
+
+    embed_migrations();
+
+    // The headless server is built without Tauri
+    if env::var_os("CARGO_FEATURE_DESKTOP").is_some() {
+        tauri_build::build()
+    }
+}
+
+// Writes `$OUT_DIR/migrations.rs`, the `migrations/*.sql` scripts in order, each with the
+// `.down.sql` script next to it when there is one. See `core::db::migrations`.
+fn embed_migrations() {
+    let dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("migrations");
+
+    let mut names: Vec<String> = fs::read_dir(&dir)
+        .expect("Failed to read migrations/")
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".sql") && !name.ends_with(".down.sql"))
+        .collect();
+    names.sort();
+
+    let mut code = String::from("&[\n");
+    for name in names {
+        let up = dir.join(&name);
+        let down = dir.join(name.replace(".sql", ".down.sql"));
+        let down = match down.exists() {
+            true => format!("Some(include_str!({:?}))", down.display().to_string()),
+            false => "None".to_string(),
+        };
+        code.push_str(&format!(
+            "    Migration {{ id: {:?}, up: include_str!({:?}), down: {} }},\n",
+            name,
+            up.display().to_string(),
+            down
+        ));
+    }
+    code.push_str("]\n");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("migrations.rs");
+    fs::write(out, code).expect("Failed to write migrations.rs");
 }
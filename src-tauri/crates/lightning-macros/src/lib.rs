@@ -14,6 +14,9 @@ use proc_macro::TokenStream;
 /// 1. Generate an enum with the pluralized form of the struct name
 /// 2. Add variants for the table and all fields
 /// 3. Implement `sea_query::Iden` for the enum
+/// 4. For models with an `id`, generate the filter and order-by inputs and the
+///    `select`/`find_by_id`/`list`/`count` statements on the enum
+/// 5. With `#[sea_query_model(soft_delete)]`, leave rows with a `deleted_at` out of lists
 ///
 /// # Example
 ///
@@ -1,5 +1,6 @@
 // Re-export all macro modules
 pub mod sea_query_model;
+pub mod sea_query_list;
 pub mod sea_query_crud;
 pub mod sea_query_enum;
 pub mod sea_query_type;
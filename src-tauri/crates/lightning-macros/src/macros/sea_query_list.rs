@@ -0,0 +1,402 @@
+use inflector::Inflector;
+use quote::{format_ident, quote};
+use syn::{GenericArgument, PathArguments, Type};
+
+/// How a column can be filtered, decided from the Rust type of its field
+enum FieldKind {
+    /// `DbUuid`: matched by equality or membership
+    Uuid,
+    /// `String`: matched by equality, membership or substring
+    Text,
+    /// `NaiveDateTime`: matched by a from/to range
+    DateTime,
+    /// `bool`: matched by equality
+    Flag,
+    /// `i32`: matched by a min/max range
+    Number,
+    /// `Money`: matched by a min/max range on the stored base units
+    Money,
+    /// Enums and other scalars: matched by equality or membership
+    Other,
+}
+
+/// Generates the filter, order-by and pagination support for a model with an `id` column.
+///
+/// For a struct `SalesOrder` this produces:
+/// - `SalesOrderFilter`, a GraphQL input with optional fields per column: `id`, `id_in`,
+///   `name_contains`, `total_amount_min`/`_max`, `order_date_from`/`_to` and so on
+/// - `SalesOrderOrderField` and `SalesOrderOrderBy` to sort by any non-null column
+/// - `impl Node for SalesOrder` so rows can be turned into cursors
+/// - `SalesOrders::select`, `find_by_id`, `list` and `count`, the statements behind plain
+///   lookups and `first`/`offset` lists
+///
+/// Fields marked `#[sea_query_model(skip_filter)]` are left out of the filter and order-by.
+///
+/// With `soft_delete` the filter also gets `deleted` and the model implements `SoftDelete`,
+/// so `list`, `count` and paged lists leave deleted rows out unless asked for them.
+pub fn generate_list_types(
+    struct_name: &syn::Ident,
+    enum_name: &syn::Ident,
+    fields: &syn::punctuated::Punctuated<syn::Field, syn::token::Comma>,
+    soft_delete: bool,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let filter_name = format_ident!("{}Filter", struct_name);
+    let order_field_name = format_ident!("{}OrderField", struct_name);
+    let order_by_name = format_ident!("{}OrderBy", struct_name);
+
+    let mut filter_fields = Vec::new();
+    let mut filter_clauses = Vec::new();
+    let mut order_variants = Vec::new();
+    let mut order_arms = Vec::new();
+
+    for field in fields.iter() {
+        if has_skip_filter(field)? {
+            continue;
+        }
+
+        let ident = field.ident.as_ref().unwrap();
+        let variant = format_ident!("{}", ident.to_string().to_pascal_case());
+        let column = quote! { (#enum_name::Table, #enum_name::#variant) };
+
+        let (inner, optional) = unwrap_option(&field.ty);
+        let kind = match field_kind(inner) {
+            Some(kind) => kind,
+            None => continue,
+        };
+
+        match kind {
+            FieldKind::Uuid => {
+                let is_in = format_ident!("{}_in", ident);
+                filter_fields.push(quote! { pub #ident: Option<#inner> });
+                filter_fields.push(quote! { pub #is_in: Option<Vec<#inner>> });
+                filter_clauses.push(quote! {
+                    if let Some(value) = &self.#ident {
+                        query.and_where(sea_query::Expr::col(#column).eq(value.to_string()));
+                    }
+                    if let Some(values) = &self.#is_in {
+                        query.and_where(sea_query::Expr::col(#column).is_in(values.iter().map(|value| value.to_string())));
+                    }
+                });
+            }
+            FieldKind::Text => {
+                let is_in = format_ident!("{}_in", ident);
+                let contains = format_ident!("{}_contains", ident);
+                filter_fields.push(quote! { pub #ident: Option<String> });
+                filter_fields.push(quote! { pub #is_in: Option<Vec<String>> });
+                filter_fields.push(quote! { pub #contains: Option<String> });
+                filter_clauses.push(quote! {
+                    if let Some(value) = &self.#ident {
+                        query.and_where(sea_query::Expr::col(#column).eq(value.as_str()));
+                    }
+                    if let Some(values) = &self.#is_in {
+                        query.and_where(sea_query::Expr::col(#column).is_in(values.iter().map(String::as_str)));
+                    }
+                    if let Some(value) = &self.#contains {
+                        let escaped = value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+                        query.and_where(
+                            sea_query::Expr::col(#column)
+                                .like(sea_query::LikeExpr::new(format!("%{}%", escaped)).escape('\\')),
+                        );
+                    }
+                });
+            }
+            FieldKind::DateTime => {
+                let from = format_ident!("{}_from", ident);
+                let to = format_ident!("{}_to", ident);
+                filter_fields.push(quote! { pub #from: Option<#inner> });
+                filter_fields.push(quote! { pub #to: Option<#inner> });
+                // Timestamps are stored as text, so they are compared in the same format
+                filter_clauses.push(quote! {
+                    if let Some(value) = &self.#from {
+                        query.and_where(sea_query::Expr::col(#column).gte(value.to_string()));
+                    }
+                    if let Some(value) = &self.#to {
+                        query.and_where(sea_query::Expr::col(#column).lt(value.to_string()));
+                    }
+                });
+            }
+            FieldKind::Flag => {
+                filter_fields.push(quote! { pub #ident: Option<bool> });
+                // Some tables store flags as 0/1 and others as 'true'/'false'
+                filter_clauses.push(quote! {
+                    if let Some(value) = self.#ident {
+                        query.and_where(sea_query::Expr::col(#column).is_in([
+                            sea_query::Value::from(value),
+                            sea_query::Value::from(value.to_string()),
+                        ]));
+                    }
+                });
+            }
+            FieldKind::Number | FieldKind::Money => {
+                let min = format_ident!("{}_min", ident);
+                let max = format_ident!("{}_max", ident);
+                let stored = match kind {
+                    FieldKind::Money => quote! { value.to_base_unit() },
+                    _ => quote! { *value },
+                };
+                filter_fields.push(quote! { pub #min: Option<#inner> });
+                filter_fields.push(quote! { pub #max: Option<#inner> });
+                filter_clauses.push(quote! {
+                    if let Some(value) = &self.#min {
+                        query.and_where(sea_query::Expr::col(#column).gte(#stored));
+                    }
+                    if let Some(value) = &self.#max {
+                        query.and_where(sea_query::Expr::col(#column).lte(#stored));
+                    }
+                });
+            }
+            FieldKind::Other => {
+                let is_in = format_ident!("{}_in", ident);
+                filter_fields.push(quote! { pub #ident: Option<#inner> });
+                filter_fields.push(quote! { pub #is_in: Option<Vec<#inner>> });
+                filter_clauses.push(quote! {
+                    if let Some(value) = &self.#ident {
+                        query.and_where(sea_query::Expr::col(#column).eq(sea_query::Value::from(value.clone())));
+                    }
+                    if let Some(values) = &self.#is_in {
+                        query.and_where(
+                            sea_query::Expr::col(#column).is_in(values.iter().cloned().map(sea_query::Value::from)),
+                        );
+                    }
+                });
+            }
+        }
+
+        // Cursors compare the sort column, which only works when it is never NULL.
+        // Foreign keys are not a meaningful order and `id` is always the tiebreaker.
+        if !optional && !matches!(kind, FieldKind::Uuid) {
+            order_variants.push(quote! { #variant });
+            order_arms.push(quote! { #order_field_name::#variant => #enum_name::#variant });
+        }
+    }
+
+    let soft_delete_types = if soft_delete {
+        for name in ["deleted_at", "deleted_by"] {
+            if !fields.iter().any(|field| field.ident.as_ref().is_some_and(|ident| ident == name)) {
+                return Err(syn::Error::new_spanned(struct_name, format!("soft_delete needs a `{}` field", name)));
+            }
+        }
+        filter_fields.push(quote! {
+            /// Whether deleted rows are listed, leaving them out when not given
+            pub deleted: Option<crate::core::db::soft_delete::DeletedRows>
+        });
+
+        quote! {
+            impl crate::core::db::soft_delete::SoftDelete for #struct_name {
+                fn deleted_at_column() -> #enum_name {
+                    #enum_name::DeletedAt
+                }
+
+                fn deleted_by_column() -> #enum_name {
+                    #enum_name::DeletedBy
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let (deleted_rows, soft_delete_column) = if soft_delete {
+        (
+            quote! {
+                fn deleted_rows(&self) -> crate::core::db::soft_delete::DeletedRows {
+                    self.deleted.unwrap_or_default()
+                }
+            },
+            quote! {
+                fn soft_delete_column() -> Option<#enum_name> {
+                    Some(#enum_name::DeletedAt)
+                }
+            },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    let order_types = if order_variants.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, juniper::GraphQLEnum)]
+            pub enum #order_field_name {
+                #(#order_variants),*
+            }
+
+            impl #order_field_name {
+                pub fn column(&self) -> #enum_name {
+                    match self {
+                        #(#order_arms),*
+                    }
+                }
+            }
+
+            #[derive(Debug, Clone, juniper::GraphQLInputObject)]
+            pub struct #order_by_name {
+                pub field: #order_field_name,
+                pub direction: Option<crate::core::db::pagination::SortDirection>,
+            }
+
+            impl crate::core::db::pagination::Sort<#enum_name> for #order_by_name {
+                fn sort(&self) -> (#enum_name, crate::core::db::pagination::SortDirection) {
+                    (self.field.column(), self.direction.unwrap_or_default())
+                }
+            }
+        }
+    };
+
+    Ok(quote! {
+        #[derive(Debug, Clone, Default, juniper::GraphQLInputObject)]
+        pub struct #filter_name {
+            #(#filter_fields),*
+        }
+
+        impl crate::core::db::pagination::Filter for #filter_name {
+            fn apply(&self, query: &mut sea_query::SelectStatement) {
+                #(#filter_clauses)*
+            }
+
+            #deleted_rows
+        }
+
+        #order_types
+
+        #soft_delete_types
+
+        impl crate::core::db::pagination::Node for #struct_name {
+            type Iden = #enum_name;
+
+            fn table() -> #enum_name {
+                #enum_name::Table
+            }
+
+            fn id_column() -> #enum_name {
+                #enum_name::Id
+            }
+
+            fn columns() -> Vec<#enum_name> {
+                #enum_name::all_columns()
+            }
+
+            fn node_id(&self) -> crate::core::types::db_uuid::DbUuid {
+                self.id
+            }
+
+            #soft_delete_column
+        }
+
+        impl #enum_name {
+            /// Every column of every row
+            pub fn select() -> sea_query::SelectStatement {
+                let mut query = sea_query::Query::select();
+                query.from(#enum_name::Table).columns(#enum_name::all_columns());
+                query
+            }
+
+            pub fn find_by_id(id: crate::core::types::db_uuid::DbUuid) -> sea_query::SelectStatement {
+                let mut query = Self::select();
+                query.and_where(sea_query::Expr::col((#enum_name::Table, #enum_name::Id)).eq(id.to_string()));
+                query
+            }
+
+            /// Rows matching `filter`, sorted by `sort` and then by id (creation order when
+            /// there is no sort), skipping `offset` rows and returning at most `first`.
+            /// Soft deleted rows are left out unless `filter` asks for them.
+            pub fn list(
+                filter: Option<&#filter_name>,
+                sort: Option<(#enum_name, crate::core::db::pagination::SortDirection)>,
+                first: Option<i32>,
+                offset: Option<i32>,
+            ) -> sea_query::SelectStatement {
+                let mut query = Self::select();
+                Self::apply_filter(&mut query, filter);
+
+                let direction = sort.map_or(crate::core::db::pagination::SortDirection::Asc, |(_, direction)| direction);
+                if let Some((column, _)) = sort {
+                    query.order_by((#enum_name::Table, column), direction.order());
+                }
+                query.order_by((#enum_name::Table, #enum_name::Id), direction.order());
+
+                if let Some(first) = first {
+                    query.limit(first.max(0) as u64);
+                }
+                if let Some(offset) = offset {
+                    query.offset(offset.max(0) as u64);
+                }
+                query
+            }
+
+            /// Number of rows matching `filter`, like `list`
+            pub fn count(filter: Option<&#filter_name>) -> sea_query::SelectStatement {
+                let mut query = sea_query::Query::select();
+                query
+                    .from(#enum_name::Table)
+                    .expr(sea_query::Expr::col((#enum_name::Table, #enum_name::Id)).count());
+                Self::apply_filter(&mut query, filter);
+                query
+            }
+
+            fn apply_filter(query: &mut sea_query::SelectStatement, filter: Option<&#filter_name>) {
+                use crate::core::db::pagination::Filter;
+
+                if let Some(filter) = filter {
+                    filter.apply(query);
+                }
+                let rows = filter.map(|filter| filter.deleted_rows()).unwrap_or_default();
+                crate::core::db::soft_delete::restrict_deleted::<#struct_name>(query, rows);
+            }
+        }
+    })
+}
+
+fn has_skip_filter(field: &syn::Field) -> syn::Result<bool> {
+    let mut skip_filter = false;
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("sea_query_model")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip_filter") {
+                skip_filter = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported sea_query_model field option"))
+            }
+        })?;
+    }
+    Ok(skip_filter)
+}
+
+// Returns the type inside `Option<...>` and whether it was wrapped
+fn unwrap_option(ty: &Type) -> (&Type, bool) {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if segment.ident == "Option" {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                        return (inner, true);
+                    }
+                }
+            }
+        }
+    }
+    (ty, false)
+}
+
+// Collections, nested options and 64-bit integers (not a GraphQL input type) are not filterable
+fn field_kind(ty: &Type) -> Option<FieldKind> {
+    let segment = match ty {
+        Type::Path(type_path) => type_path.path.segments.last()?,
+        _ => return None,
+    };
+    if !matches!(segment.arguments, PathArguments::None) {
+        return None;
+    }
+
+    let kind = match segment.ident.to_string().as_str() {
+        "DbUuid" => FieldKind::Uuid,
+        "String" => FieldKind::Text,
+        "NaiveDateTime" => FieldKind::DateTime,
+        "bool" => FieldKind::Flag,
+        "i32" => FieldKind::Number,
+        "Money" => FieldKind::Money,
+        "i64" | "u64" => return None,
+        _ => FieldKind::Other,
+    };
+    Some(kind)
+}
@@ -11,12 +11,23 @@ use inflector::Inflector;
 /// 3. Implement `sea_query::Iden` for the enum
 /// 4. Optionally generate NewInput and UpdateInput structs when specified
 /// 5. Optionally generate a queries module with CRUD operations
+/// 6. For models with an `id`, generate `{Model}Filter`, `{Model}OrderBy`, the `Node`
+///    impl used by cursor pagination and the `select`, `find_by_id`, `list` and `count`
+///    statements (see `sea_query_list`)
 ///
 /// # Configuration Options
 ///
 /// - `new_input`: Generate the NewInput struct
 /// - `update_input`: Generate the UpdateInput struct
 /// - `queries`: Generate the queries module with CRUD operations
+/// - `soft_delete`: Rows are soft deleted through the `deleted_at` and `deleted_by` fields.
+///   Implements `SoftDelete`, adds `deleted` to the filter and leaves deleted rows out of
+///   `list` and `count` unless the filter asks for them
+///
+/// Fields can be left out of the generated filter and order-by with
+/// `#[sea_query_model(skip_filter)]`, e.g. for secrets such as PIN hashes.
+///
+/// Unknown options are reported as compile errors.
 ///
 /// # Example
 ///
@@ -61,9 +72,16 @@ use inflector::Inflector;
 /// ```
 pub fn sea_query_model_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(e) => TokenStream::from(e.to_compile_error()),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let struct_name = input.ident.clone();
 
-    let (generate_new_input, generate_update_input, generate_queries) = parse_sea_query_model_attrs(&input.attrs);
+    let options = ModelOptions::parse(&input.attrs)?;
 
     let enum_name = format_ident!("{}", struct_name.to_string().to_plural());
 
@@ -82,14 +100,28 @@ pub fn sea_query_model_derive(input: TokenStream) -> TokenStream {
         &all_column_variants,
     );
 
+    // Only models with an id can be paged through with cursors
+    let has_id = fields.iter().any(|field| field.ident.as_ref().is_some_and(|ident| ident == "id"));
+    if options.soft_delete && !has_id {
+        return Err(syn::Error::new_spanned(&input.ident, "soft_delete needs an `id` field"));
+    }
+    if has_id {
+        let list_types =
+            super::sea_query_list::generate_list_types(&struct_name, &enum_name, &fields, options.soft_delete)?;
+        expanded = quote! {
+            #expanded
+            #list_types
+        };
+    }
+
     // Generate the queries module only if requested
-    if generate_queries {
+    if options.queries {
         let queries_module = generate_queries_module(
             &struct_name,
             &enum_name,
             &field_idents,
-            generate_new_input,
-            generate_update_input,
+            has_id,
+            &options,
         );
 
         expanded = quote! {
@@ -98,7 +130,7 @@ pub fn sea_query_model_derive(input: TokenStream) -> TokenStream {
         };
     }
 
-    if generate_new_input {
+    if options.new_input {
         expanded = quote! {
             #expanded
             #[derive(Debug, Clone, juniper::GraphQLInputObject)]
@@ -107,7 +139,7 @@ pub fn sea_query_model_derive(input: TokenStream) -> TokenStream {
             }
         };
     }
-    if generate_update_input {
+    if options.update_input {
         expanded = quote! {
             #expanded
             #[derive(Debug, Clone, juniper::GraphQLInputObject)]
@@ -116,28 +148,39 @@ pub fn sea_query_model_derive(input: TokenStream) -> TokenStream {
             }
         };
     }
-    TokenStream::from(expanded)
+    Ok(expanded)
 }
 
-fn parse_sea_query_model_attrs(attrs: &[syn::Attribute]) -> (bool, bool, bool) {
-    let mut generate_new_input = false;
-    let mut generate_update_input = false;
-    let mut generate_queries = false;
-    for attr in attrs {
-        let attr_str = format!("{:?}", attr);
-        if attr_str.contains("sea_query_model") {
-            if attr_str.contains("new_input") {
-                generate_new_input = true;
-            }
-            if attr_str.contains("update_input") {
-                generate_update_input = true;
-            }
-            if attr_str.contains("queries") {
-                generate_queries = true;
-            }
+/// The options of a `#[sea_query_model(...)]` attribute on the struct
+#[derive(Default)]
+struct ModelOptions {
+    new_input: bool,
+    update_input: bool,
+    queries: bool,
+    soft_delete: bool,
+}
+
+impl ModelOptions {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut options = ModelOptions::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("sea_query_model")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("new_input") {
+                    options.new_input = true;
+                } else if meta.path.is_ident("update_input") {
+                    options.update_input = true;
+                } else if meta.path.is_ident("queries") {
+                    options.queries = true;
+                } else if meta.path.is_ident("soft_delete") {
+                    options.soft_delete = true;
+                } else {
+                    return Err(meta.error("unsupported sea_query_model option"));
+                }
+                Ok(())
+            })?;
         }
+        Ok(options)
     }
-    (generate_new_input, generate_update_input, generate_queries)
 }
 
 fn extract_named_fields(data: &syn::Data) -> syn::punctuated::Punctuated<syn::Field, syn::token::Comma> {
@@ -162,7 +205,7 @@ fn process_fields(
     Vec<proc_macro2::TokenStream>,
     Vec<proc_macro2::TokenStream>,
     Vec<proc_macro2::TokenStream>,
-    Vec<proc_macro2::TokenStream>,
+    Vec<syn::Ident>,
 ) {
     let enum_name = format_ident!("{}", struct_name.to_string().to_plural());
     let table_name = struct_name.to_string().to_snake_case().to_plural();
@@ -175,7 +218,7 @@ fn process_fields(
     for field in fields.iter() {
         let ident = field.ident.as_ref().unwrap();
         let field_name = ident.to_string();
-        field_idents.push(quote! { #ident });
+        field_idents.push(ident.clone());
         let pascal = field_name.to_pascal_case();
         let var_ident = format_ident!("{}", pascal);
         let field_type = &field.ty;
@@ -214,6 +257,7 @@ fn generate_iden_enum_and_impl(
     all_column_variants: &[proc_macro2::TokenStream],
 ) -> proc_macro2::TokenStream {
     quote! {
+        #[derive(Clone, Copy)]
         pub enum #enum_name {
             #(#variants),*
         }
@@ -237,15 +281,10 @@ fn generate_iden_enum_and_impl(
 fn generate_queries_module(
     struct_name: &proc_macro2::Ident,
     enum_name: &proc_macro2::Ident,
-    field_idents: &[proc_macro2::TokenStream],
-    generate_new_input: bool,
-    generate_update_input: bool,
+    field_idents: &[syn::Ident],
+    has_id_field: bool,
+    options: &ModelOptions,
 ) -> proc_macro2::TokenStream {
-    // Check if this model has an id field
-    let has_id_field = field_idents.iter().any(|field_ident| {
-        let field_str = format!("{}", quote! { #field_ident });
-        field_str.contains("id")
-    });
     let new_input_name = format_ident!("{}{}", struct_name, "NewInput");
     let update_input_name = format_ident!("{}{}", struct_name, "UpdateInput");
 
@@ -265,7 +304,7 @@ fn generate_queries_module(
     };
 
     // Check if this is a User model (only User model should have find_by_username)
-    let is_user_model = struct_name.to_string() == "User";
+    let is_user_model = struct_name == "User";
 
     // Generate find_by_username function only for User model
     let find_by_username = if is_user_model {
@@ -283,14 +322,13 @@ fn generate_queries_module(
     };
 
     // Generate insert function if new_input is enabled
-    let insert = if generate_new_input {
+    let insert = if options.new_input {
         // Create field variant identifiers for each field
         let mut field_variants = Vec::new();
         let mut field_accessors = Vec::new();
 
         for field_ident in field_idents {
-            let field_str = format!("{}", quote! { #field_ident });
-            let field_name = field_str.trim_matches(|c| c == ' ' || c == '"');
+            let field_name = field_ident.to_string();
 
             // Skip id, created_at, and updated_at as they are handled separately
             if field_name != "id" && field_name != "created_at" && field_name != "updated_at" {
@@ -298,8 +336,7 @@ fn generate_queries_module(
                 let field_variant = format_ident!("{}", field_pascal);
                 field_variants.push(quote! { #enum_name::#field_variant });
 
-                let field_ident_parsed = format_ident!("{}", field_name);
-                field_accessors.push(quote! { input.#field_ident_parsed.clone().into() });
+                field_accessors.push(quote! { input.#field_ident.clone().into() });
             }
         }
 
@@ -335,24 +372,22 @@ fn generate_queries_module(
     };
 
     // Generate update function if update_input is enabled
-    let update = if generate_update_input {
+    let update = if options.update_input {
         // Process each field for conditional updates
         let mut field_updates = Vec::new();
 
         for field_ident in field_idents {
-            let field_str = format!("{}", quote! { #field_ident });
-            let field_name = field_str.trim_matches(|c| c == ' ' || c == '"');
+            let field_name = field_ident.to_string();
 
             // Skip id, created_at, and updated_at as they are handled separately
             if field_name != "id" && field_name != "created_at" && field_name != "updated_at" {
                 let field_pascal = field_name.to_pascal_case();
                 let field_variant = format_ident!("{}", field_pascal);
-                let field_ident_parsed = format_ident!("{}", field_name);
 
                 // Handle Option<Option<T>> fields specially (like last_login_at)
                 if field_name.contains("_at") || field_name.starts_with("opt_") {
                     field_updates.push(quote! {
-                        if let Some(value) = &input.#field_ident_parsed {
+                        if let Some(value) = &input.#field_ident {
                             match value {
                                 Some(v) => stmt.value(#enum_name::#field_variant, v.clone()),
                                 None => stmt.value(#enum_name::#field_variant, Expr::value(Value::String(None))),
@@ -361,7 +396,7 @@ fn generate_queries_module(
                     });
                 } else {
                     field_updates.push(quote! {
-                        if let Some(value) = &input.#field_ident_parsed {
+                        if let Some(value) = &input.#field_ident {
                             stmt.value(#enum_name::#field_variant, value.clone());
                         }
                     });
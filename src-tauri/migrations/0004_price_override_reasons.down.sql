@@ -0,0 +1,9 @@
+DROP TRIGGER IF EXISTS update_price_override_reasons_updated_at;
+DROP INDEX IF EXISTS idx_sales_order_items_override_reason_id;
+
+ALTER TABLE sales_order_items DROP COLUMN override_approved_by;
+ALTER TABLE sales_order_items DROP COLUMN override_note;
+ALTER TABLE sales_order_items DROP COLUMN override_reason_id;
+ALTER TABLE sales_order_items DROP COLUMN original_price_amount;
+
+DROP TABLE IF EXISTS price_override_reasons;
@@ -0,0 +1,26 @@
+-- Reason codes for manual price overrides and line-level discounts
+CREATE TABLE price_override_reasons (
+    id TEXT PRIMARY KEY NOT NULL,
+    code TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    description TEXT,
+    requires_approval BOOLEAN NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Record the override on the order line
+ALTER TABLE sales_order_items ADD COLUMN original_price_amount BIGINT;
+ALTER TABLE sales_order_items ADD COLUMN override_reason_id TEXT REFERENCES price_override_reasons(id) ON DELETE RESTRICT ON UPDATE CASCADE;
+ALTER TABLE sales_order_items ADD COLUMN override_note TEXT;
+ALTER TABLE sales_order_items ADD COLUMN override_approved_by TEXT REFERENCES users(id) ON DELETE RESTRICT ON UPDATE CASCADE;
+
+CREATE INDEX idx_sales_order_items_override_reason_id ON sales_order_items(override_reason_id);
+
+CREATE TRIGGER update_price_override_reasons_updated_at
+AFTER UPDATE ON price_override_reasons
+FOR EACH ROW
+BEGIN
+    UPDATE price_override_reasons SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
@@ -0,0 +1,15 @@
+DROP TRIGGER IF EXISTS update_price_list_items_updated_at;
+DROP TRIGGER IF EXISTS update_price_lists_updated_at;
+DROP TRIGGER IF EXISTS update_customer_groups_updated_at;
+
+DROP INDEX IF EXISTS idx_price_list_assignments_price_list_id;
+DROP INDEX IF EXISTS idx_price_list_items_item_id;
+DROP INDEX IF EXISTS idx_price_list_items_tier;
+DROP INDEX IF EXISTS idx_customers_customer_group_id;
+
+DROP TABLE IF EXISTS price_list_assignments;
+DROP TABLE IF EXISTS price_list_items;
+DROP TABLE IF EXISTS price_lists;
+
+ALTER TABLE customers DROP COLUMN customer_group_id;
+DROP TABLE IF EXISTS customer_groups;
@@ -0,0 +1,79 @@
+-- Customer groups, used to target price lists at wholesale or loyalty customers
+CREATE TABLE customer_groups (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE customers ADD COLUMN customer_group_id TEXT REFERENCES customer_groups(id) ON DELETE SET NULL ON UPDATE CASCADE;
+
+-- Price lists override the catalog price for the channels, locations or groups they are assigned to
+CREATE TABLE price_lists (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    priority INTEGER NOT NULL DEFAULT 0, -- Higher priority wins when several lists apply
+    valid_from TIMESTAMP,
+    valid_to TIMESTAMP,
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE price_list_items (
+    id TEXT PRIMARY KEY NOT NULL,
+    price_list_id TEXT NOT NULL,
+    item_id TEXT NOT NULL,
+    variant_id TEXT, -- NULL applies to every variant of the item
+    min_quantity INTEGER NOT NULL DEFAULT 1,
+    price BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (price_list_id) REFERENCES price_lists(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (variant_id) REFERENCES item_variants(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- Every column that is set must match the order for the list to apply
+CREATE TABLE price_list_assignments (
+    id TEXT PRIMARY KEY NOT NULL,
+    price_list_id TEXT NOT NULL,
+    channel_id TEXT,
+    location_id TEXT,
+    customer_group_id TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (channel_id IS NOT NULL OR location_id IS NOT NULL OR customer_group_id IS NOT NULL),
+    FOREIGN KEY (price_list_id) REFERENCES price_lists(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (channel_id) REFERENCES channels(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (location_id) REFERENCES locations(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (customer_group_id) REFERENCES customer_groups(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX idx_customers_customer_group_id ON customers(customer_group_id);
+CREATE UNIQUE INDEX idx_price_list_items_tier ON price_list_items(price_list_id, item_id, COALESCE(variant_id, ''), min_quantity);
+CREATE INDEX idx_price_list_items_item_id ON price_list_items(item_id);
+CREATE INDEX idx_price_list_assignments_price_list_id ON price_list_assignments(price_list_id);
+
+CREATE TRIGGER update_customer_groups_updated_at
+AFTER UPDATE ON customer_groups
+FOR EACH ROW
+BEGIN
+    UPDATE customer_groups SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER update_price_lists_updated_at
+AFTER UPDATE ON price_lists
+FOR EACH ROW
+BEGIN
+    UPDATE price_lists SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER update_price_list_items_updated_at
+AFTER UPDATE ON price_list_items
+FOR EACH ROW
+BEGIN
+    UPDATE price_list_items SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
@@ -0,0 +1,12 @@
+-- Amounts are already kept in the base currency, so only what was tendered is lost
+DROP TRIGGER IF EXISTS update_exchange_rates_updated_at;
+DROP TRIGGER IF EXISTS update_currencies_updated_at;
+DROP INDEX IF EXISTS idx_exchange_rates_currency_code;
+
+ALTER TABLE sales_order_payments DROP COLUMN exchange_rate;
+ALTER TABLE sales_order_payments DROP COLUMN original_amount;
+ALTER TABLE sales_order_payments DROP COLUMN currency_code;
+ALTER TABLE sales_orders DROP COLUMN currency_code;
+
+DROP TABLE IF EXISTS exchange_rates;
+DROP TABLE IF EXISTS currencies;
@@ -0,0 +1,57 @@
+-- Currencies with their minor-unit precision. Exactly one currency is the base currency
+-- that orders are priced and reported in.
+CREATE TABLE currencies (
+    id TEXT PRIMARY KEY NOT NULL,
+    code TEXT NOT NULL UNIQUE, -- ISO 4217 code, e.g. INR, JPY, KWD
+    name TEXT NOT NULL,
+    symbol TEXT,
+    minor_units INTEGER NOT NULL DEFAULT 2,
+    is_base BOOLEAN NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT 1,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- The starting base currency, which can be changed until the first order is recorded
+INSERT INTO currencies (id, code, name, symbol, minor_units, is_base, is_active)
+VALUES ('01930000-0000-7000-8000-000000000001', 'INR', 'Indian Rupee', '₹', 2, 'true', 'true');
+
+-- Base currency units per one unit of the currency, effective from a point in time
+CREATE TABLE exchange_rates (
+    id TEXT PRIMARY KEY NOT NULL,
+    currency_code TEXT NOT NULL,
+    rate BIGINT NOT NULL, -- Scaled by 1,000,000
+    effective_from TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (currency_code) REFERENCES currencies(code) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- Orders are recorded in the base currency at the time of sale. The code is always written;
+-- existing rows take the seeded base currency.
+ALTER TABLE sales_orders ADD COLUMN currency_code TEXT REFERENCES currencies(code) ON DELETE RESTRICT ON UPDATE CASCADE;
+
+-- Payments keep what was tendered alongside the converted base-currency amount
+ALTER TABLE sales_order_payments ADD COLUMN currency_code TEXT REFERENCES currencies(code) ON DELETE RESTRICT ON UPDATE CASCADE;
+ALTER TABLE sales_order_payments ADD COLUMN original_amount BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE sales_order_payments ADD COLUMN exchange_rate BIGINT NOT NULL DEFAULT 1000000;
+
+UPDATE sales_orders SET currency_code = (SELECT code FROM currencies WHERE is_base = 'true');
+UPDATE sales_order_payments
+SET currency_code = (SELECT code FROM currencies WHERE is_base = 'true'), original_amount = amount;
+
+CREATE INDEX idx_exchange_rates_currency_code ON exchange_rates(currency_code, effective_from);
+
+CREATE TRIGGER update_currencies_updated_at
+AFTER UPDATE ON currencies
+FOR EACH ROW
+BEGIN
+    UPDATE currencies SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER update_exchange_rates_updated_at
+AFTER UPDATE ON exchange_rates
+FOR EACH ROW
+BEGIN
+    UPDATE exchange_rates SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
@@ -0,0 +1,44 @@
+-- Carts go back to keeping their lines as JSON in cart_data, in the shape 0007 imports.
+-- Line discounts, notes and overrides, cart promotions and charges are not kept.
+CREATE TABLE carts_old (
+    id TEXT PRIMARY KEY NOT NULL,
+    customer_id TEXT,
+    cart_data TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (customer_id) REFERENCES customers(id) ON DELETE SET NULL ON UPDATE CASCADE
+);
+
+INSERT INTO carts_old (id, customer_id, cart_data, created_at, updated_at)
+SELECT
+    c.id,
+    c.customer_id,
+    CASE
+        WHEN EXISTS (SELECT 1 FROM cart_lines l WHERE l.cart_id = c.id) THEN (
+            SELECT json_object('items', json_group_array(
+                json_object('itemId', l.item_id, 'variantId', l.variant_id, 'quantity', l.quantity)
+            ))
+            FROM (SELECT * FROM cart_lines WHERE cart_id = c.id ORDER BY id) l
+        )
+        ELSE COALESCE(c.legacy_cart_data, json_object('items', json_array()))
+    END,
+    c.created_at,
+    c.updated_at
+FROM carts c;
+
+DROP TRIGGER IF EXISTS update_cart_charges_updated_at;
+DROP TRIGGER IF EXISTS update_cart_lines_updated_at;
+DROP TRIGGER IF EXISTS update_carts_updated_at;
+DROP TABLE IF EXISTS cart_charges;
+DROP TABLE IF EXISTS cart_lines;
+DROP TABLE carts;
+ALTER TABLE carts_old RENAME TO carts;
+
+CREATE INDEX idx_carts_customer_id ON carts(customer_id);
+
+CREATE TRIGGER update_carts_updated_at
+AFTER UPDATE ON carts
+FOR EACH ROW
+BEGIN
+    UPDATE carts SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
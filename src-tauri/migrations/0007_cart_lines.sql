@@ -0,0 +1,116 @@
+-- Carts keep their lines and charges in tables instead of an opaque JSON string.
+-- The old cart_data column becomes nullable legacy_cart_data: lines that can be read from
+-- it are imported below and the rest is kept for the cashier to re-enter.
+CREATE TABLE carts_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    customer_id TEXT,
+    discount_id TEXT, -- Promotion applied to the whole cart
+    notes TEXT,
+    legacy_cart_data TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (customer_id) REFERENCES customers(id) ON DELETE SET NULL ON UPDATE CASCADE,
+    FOREIGN KEY (discount_id) REFERENCES discounts(id) ON DELETE SET NULL ON UPDATE CASCADE
+);
+
+INSERT INTO carts_new (id, customer_id, legacy_cart_data, created_at, updated_at)
+SELECT id, customer_id, cart_data, created_at, updated_at FROM carts;
+
+DROP TABLE carts;
+ALTER TABLE carts_new RENAME TO carts;
+
+CREATE TABLE cart_lines (
+    id TEXT PRIMARY KEY NOT NULL,
+    cart_id TEXT NOT NULL,
+    item_id TEXT NOT NULL,
+    variant_id TEXT,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    discount_id TEXT, -- Promotion applied to this line only
+    note TEXT,
+    -- Manual price, set together with a reason code and approval
+    override_price BIGINT,
+    override_reason_id TEXT,
+    override_note TEXT,
+    override_approved_by TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((override_price IS NULL) = (override_reason_id IS NULL)),
+    FOREIGN KEY (cart_id) REFERENCES carts(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (variant_id) REFERENCES item_variants(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (discount_id) REFERENCES discounts(id) ON DELETE SET NULL ON UPDATE CASCADE,
+    FOREIGN KEY (override_reason_id) REFERENCES price_override_reasons(id) ON DELETE RESTRICT ON UPDATE CASCADE,
+    FOREIGN KEY (override_approved_by) REFERENCES users(id) ON DELETE SET NULL ON UPDATE CASCADE
+);
+
+CREATE TABLE cart_charges (
+    id TEXT PRIMARY KEY NOT NULL,
+    cart_id TEXT NOT NULL,
+    charge_type_id TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    tax_group_id TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (cart_id) REFERENCES carts(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (charge_type_id) REFERENCES sales_charge_types(id) ON DELETE RESTRICT ON UPDATE CASCADE,
+    FOREIGN KEY (tax_group_id) REFERENCES tax_groups(id) ON DELETE SET NULL ON UPDATE CASCADE
+);
+
+CREATE INDEX idx_carts_customer_id ON carts(customer_id);
+CREATE INDEX idx_cart_lines_cart_id ON cart_lines(cart_id);
+CREATE INDEX idx_cart_charges_cart_id ON cart_charges(cart_id);
+
+-- Import lines from carts saved as {"items": [{"itemId": ..., "variantId": ..., "quantity": ...}]}
+-- (snake_case keys are accepted too). Entries for unknown items are skipped.
+INSERT INTO cart_lines (id, cart_id, item_id, variant_id, quantity, created_at, updated_at)
+WITH legacy_carts AS MATERIALIZED (
+    SELECT id, legacy_cart_data, created_at, updated_at FROM carts
+    WHERE CASE WHEN json_valid(legacy_cart_data)
+        THEN json_type(legacy_cart_data, '$.items') = 'array' ELSE 0 END
+)
+SELECT
+    lower(hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-7' || substr(hex(randomblob(2)), 2) || '-8' ||
+          substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6))),
+    c.id,
+    i.id,
+    v.id,
+    CAST(COALESCE(json_extract(e.value, '$.quantity'), 1) AS INTEGER),
+    c.created_at,
+    c.updated_at
+FROM legacy_carts c
+JOIN json_each(c.legacy_cart_data, '$.items') e
+JOIN items i ON i.id = COALESCE(json_extract(e.value, '$.itemId'), json_extract(e.value, '$.item_id'))
+LEFT JOIN item_variants v ON v.item_id = i.id
+    AND v.id = COALESCE(json_extract(e.value, '$.variantId'), json_extract(e.value, '$.variant_id'))
+WHERE e.type = 'object'
+    AND CAST(COALESCE(json_extract(e.value, '$.quantity'), 1) AS INTEGER) > 0;
+
+-- Carts that converted completely no longer need the legacy copy
+UPDATE carts SET legacy_cart_data = NULL
+WHERE CASE WHEN json_valid(legacy_cart_data)
+    THEN json_type(legacy_cart_data, '$.items') = 'array'
+        AND json_array_length(legacy_cart_data, '$.items') = (
+            SELECT COUNT(*) FROM cart_lines l WHERE l.cart_id = carts.id
+        )
+    ELSE 0 END;
+
+CREATE TRIGGER update_carts_updated_at
+AFTER UPDATE ON carts
+FOR EACH ROW
+BEGIN
+    UPDATE carts SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER update_cart_lines_updated_at
+AFTER UPDATE ON cart_lines
+FOR EACH ROW
+BEGIN
+    UPDATE cart_lines SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER update_cart_charges_updated_at
+AFTER UPDATE ON cart_charges
+FOR EACH ROW
+BEGIN
+    UPDATE cart_charges SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
@@ -0,0 +1,10 @@
+DROP INDEX IF EXISTS idx_carts_updated_at;
+DROP INDEX IF EXISTS idx_carts_status_location_id;
+
+ALTER TABLE carts DROP COLUMN held_since;
+ALTER TABLE carts DROP COLUMN location_id;
+ALTER TABLE carts DROP COLUMN terminal_id;
+ALTER TABLE carts DROP COLUMN parked_by;
+ALTER TABLE carts DROP COLUMN hold_reason;
+ALTER TABLE carts DROP COLUMN label;
+ALTER TABLE carts DROP COLUMN status;
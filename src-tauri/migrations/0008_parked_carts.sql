@@ -0,0 +1,12 @@
+-- Carts can be parked on one terminal and recalled on another.
+-- A cart is either Open (being worked on by the terminal in terminal_id) or Parked.
+ALTER TABLE carts ADD COLUMN status TEXT NOT NULL DEFAULT 'Open'; -- Corresponds to CartStatus
+ALTER TABLE carts ADD COLUMN label TEXT;
+ALTER TABLE carts ADD COLUMN hold_reason TEXT;
+ALTER TABLE carts ADD COLUMN parked_by TEXT; -- No foreign key, same as sales_orders.created_by
+ALTER TABLE carts ADD COLUMN terminal_id TEXT;
+ALTER TABLE carts ADD COLUMN location_id TEXT REFERENCES locations(id) ON DELETE SET NULL ON UPDATE CASCADE;
+ALTER TABLE carts ADD COLUMN held_since TIMESTAMP;
+
+CREATE INDEX idx_carts_status_location_id ON carts(status, location_id);
+CREATE INDEX idx_carts_updated_at ON carts(updated_at);
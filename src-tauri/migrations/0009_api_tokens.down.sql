@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS update_api_tokens_updated_at;
+DROP INDEX IF EXISTS idx_api_tokens_user_id;
+DROP TABLE IF EXISTS api_tokens;
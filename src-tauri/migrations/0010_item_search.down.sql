@@ -0,0 +1,10 @@
+DROP TRIGGER IF EXISTS item_search_after_variant_delete;
+DROP TRIGGER IF EXISTS item_search_after_variant_update;
+DROP TRIGGER IF EXISTS item_search_after_variant_insert;
+DROP TRIGGER IF EXISTS item_search_after_item_delete;
+DROP TRIGGER IF EXISTS item_search_after_item_update;
+DROP TRIGGER IF EXISTS item_search_after_item_insert;
+DROP TABLE IF EXISTS item_search;
+
+DROP INDEX IF EXISTS idx_item_variants_barcode;
+ALTER TABLE item_variants DROP COLUMN barcode;
@@ -0,0 +1,81 @@
+-- Barcodes identify one variant of an item, as printed on its label
+ALTER TABLE item_variants ADD COLUMN barcode TEXT;
+
+CREATE UNIQUE INDEX idx_item_variants_barcode ON item_variants(barcode);
+
+-- SKUs were stored as the text 'NULL' when left out
+UPDATE item_variants SET sku = NULL WHERE sku = 'NULL';
+
+-- Full-text index for item search, one row per item. `codes` holds the SKUs and barcodes of
+-- the item's variants. The triggers below keep it in step with items and item_variants.
+CREATE VIRTUAL TABLE item_search USING fts5(
+    item_id UNINDEXED,
+    name,
+    description,
+    codes,
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '2 3'
+);
+
+INSERT INTO item_search (item_id, name, description, codes)
+SELECT
+    items.id,
+    items.name,
+    items.description,
+    (SELECT group_concat(concat_ws(' ', item_variants.sku, item_variants.barcode), ' ')
+     FROM item_variants WHERE item_variants.item_id = items.id)
+FROM items;
+
+CREATE TRIGGER item_search_after_item_insert
+AFTER INSERT ON items
+FOR EACH ROW
+BEGIN
+    INSERT INTO item_search (item_id, name, description, codes)
+    VALUES (NEW.id, NEW.name, NEW.description, NULL);
+END;
+
+CREATE TRIGGER item_search_after_item_update
+AFTER UPDATE OF id, name, description ON items
+FOR EACH ROW
+BEGIN
+    UPDATE item_search
+    SET item_id = NEW.id, name = NEW.name, description = NEW.description
+    WHERE item_id = OLD.id;
+END;
+
+CREATE TRIGGER item_search_after_item_delete
+AFTER DELETE ON items
+FOR EACH ROW
+BEGIN
+    DELETE FROM item_search WHERE item_id = OLD.id;
+END;
+
+CREATE TRIGGER item_search_after_variant_insert
+AFTER INSERT ON item_variants
+FOR EACH ROW
+BEGIN
+    UPDATE item_search
+    SET codes = (SELECT group_concat(concat_ws(' ', sku, barcode), ' ')
+                 FROM item_variants WHERE item_id = NEW.item_id)
+    WHERE item_id = NEW.item_id;
+END;
+
+CREATE TRIGGER item_search_after_variant_update
+AFTER UPDATE OF item_id, sku, barcode ON item_variants
+FOR EACH ROW
+BEGIN
+    UPDATE item_search
+    SET codes = (SELECT group_concat(concat_ws(' ', sku, barcode), ' ')
+                 FROM item_variants WHERE item_id = item_search.item_id)
+    WHERE item_id IN (OLD.item_id, NEW.item_id);
+END;
+
+CREATE TRIGGER item_search_after_variant_delete
+AFTER DELETE ON item_variants
+FOR EACH ROW
+BEGIN
+    UPDATE item_search
+    SET codes = (SELECT group_concat(concat_ws(' ', sku, barcode), ' ')
+                 FROM item_variants WHERE item_id = OLD.item_id)
+    WHERE item_id = OLD.item_id;
+END;
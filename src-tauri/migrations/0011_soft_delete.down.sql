@@ -0,0 +1,34 @@
+DROP INDEX IF EXISTS idx_items_deleted_at;
+DROP INDEX IF EXISTS idx_item_categories_deleted_at;
+DROP INDEX IF EXISTS idx_customers_deleted_at;
+DROP INDEX IF EXISTS idx_suppliers_deleted_at;
+DROP INDEX IF EXISTS idx_brands_deleted_at;
+DROP INDEX IF EXISTS idx_channels_deleted_at;
+DROP INDEX IF EXISTS idx_locations_deleted_at;
+DROP INDEX IF EXISTS idx_cost_centers_deleted_at;
+DROP INDEX IF EXISTS idx_payment_methods_deleted_at;
+DROP INDEX IF EXISTS idx_purchase_categories_deleted_at;
+DROP INDEX IF EXISTS idx_sales_charge_types_deleted_at;
+
+ALTER TABLE items DROP COLUMN deleted_by;
+ALTER TABLE items DROP COLUMN deleted_at;
+ALTER TABLE item_categories DROP COLUMN deleted_by;
+ALTER TABLE item_categories DROP COLUMN deleted_at;
+ALTER TABLE customers DROP COLUMN deleted_by;
+ALTER TABLE customers DROP COLUMN deleted_at;
+ALTER TABLE suppliers DROP COLUMN deleted_by;
+ALTER TABLE suppliers DROP COLUMN deleted_at;
+ALTER TABLE brands DROP COLUMN deleted_by;
+ALTER TABLE brands DROP COLUMN deleted_at;
+ALTER TABLE channels DROP COLUMN deleted_by;
+ALTER TABLE channels DROP COLUMN deleted_at;
+ALTER TABLE locations DROP COLUMN deleted_by;
+ALTER TABLE locations DROP COLUMN deleted_at;
+ALTER TABLE cost_centers DROP COLUMN deleted_by;
+ALTER TABLE cost_centers DROP COLUMN deleted_at;
+ALTER TABLE payment_methods DROP COLUMN deleted_by;
+ALTER TABLE payment_methods DROP COLUMN deleted_at;
+ALTER TABLE purchase_categories DROP COLUMN deleted_by;
+ALTER TABLE purchase_categories DROP COLUMN deleted_at;
+ALTER TABLE sales_charge_types DROP COLUMN deleted_by;
+ALTER TABLE sales_charge_types DROP COLUMN deleted_at;
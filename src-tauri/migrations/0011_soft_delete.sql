@@ -0,0 +1,53 @@
+-- Master data is soft deleted: historic orders and expenses keep referring to it, so rows
+-- are only marked as deleted and can be restored. See `core::db::soft_delete`.
+-- `deleted_by` holds the id of the user who deleted the row. It has no foreign key, as SQLite
+-- cannot drop a column that has one in the down migration.
+
+ALTER TABLE items ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE items ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE item_categories ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE item_categories ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE customers ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE customers ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE suppliers ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE suppliers ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE brands ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE brands ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE channels ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE channels ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE locations ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE locations ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE cost_centers ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE cost_centers ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE payment_methods ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE payment_methods ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE purchase_categories ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE purchase_categories ADD COLUMN deleted_by TEXT;
+
+ALTER TABLE sales_charge_types ADD COLUMN deleted_at TIMESTAMP;
+ALTER TABLE sales_charge_types ADD COLUMN deleted_by TEXT;
+
+-- Items and categories could be marked as deleted through their state before
+UPDATE items SET deleted_at = updated_at WHERE state = 'Deleted';
+UPDATE item_categories SET deleted_at = updated_at WHERE state = 'Deleted';
+
+CREATE INDEX idx_items_deleted_at ON items(deleted_at);
+CREATE INDEX idx_item_categories_deleted_at ON item_categories(deleted_at);
+CREATE INDEX idx_customers_deleted_at ON customers(deleted_at);
+CREATE INDEX idx_suppliers_deleted_at ON suppliers(deleted_at);
+CREATE INDEX idx_brands_deleted_at ON brands(deleted_at);
+CREATE INDEX idx_channels_deleted_at ON channels(deleted_at);
+CREATE INDEX idx_locations_deleted_at ON locations(deleted_at);
+CREATE INDEX idx_cost_centers_deleted_at ON cost_centers(deleted_at);
+CREATE INDEX idx_payment_methods_deleted_at ON payment_methods(deleted_at);
+CREATE INDEX idx_purchase_categories_deleted_at ON purchase_categories(deleted_at);
+CREATE INDEX idx_sales_charge_types_deleted_at ON sales_charge_types(deleted_at);
@@ -0,0 +1,3 @@
+ALTER TABLE items DROP COLUMN version;
+ALTER TABLE customers DROP COLUMN version;
+ALTER TABLE discounts DROP COLUMN version;
@@ -0,0 +1,5 @@
+-- Row versions for optimistic concurrency: an update names the version it was based on and
+-- bumps it, so a stale write from another terminal is detected instead of applied
+ALTER TABLE items ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE customers ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE discounts ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_stock_movements_item_variant_id;
+DROP INDEX IF EXISTS idx_stock_movements_item_id;
+DROP TABLE IF EXISTS stock_movements;
@@ -0,0 +1,14 @@
+-- Stock is kept as a ledger of movements; the quantity on hand of an item (or one of its
+-- variants) is the sum of its movements. Movements are never updated.
+CREATE TABLE stock_movements (
+    id TEXT PRIMARY KEY NOT NULL,
+    item_id TEXT NOT NULL REFERENCES items(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    item_variant_id TEXT REFERENCES item_variants(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    quantity INTEGER NOT NULL,
+    reason TEXT NOT NULL, -- Corresponds to StockMovementReason
+    created_by TEXT, -- No foreign key, same as sales_orders.created_by
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_stock_movements_item_id ON stock_movements(item_id);
+CREATE INDEX idx_stock_movements_item_variant_id ON stock_movements(item_variant_id);
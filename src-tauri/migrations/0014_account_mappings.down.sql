@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_account_mappings_kind_source;
+DROP TABLE IF EXISTS account_mappings;
@@ -0,0 +1,14 @@
+-- The chart of accounts the journal posts to. Each mapping names the ledger account for one
+-- item category, tax, charge type, payment method or purchase category; a mapping without a
+-- source is the account for everything of its kind that has no mapping of its own.
+CREATE TABLE account_mappings (
+    id TEXT PRIMARY KEY NOT NULL,
+    kind TEXT NOT NULL, -- Corresponds to AccountMappingKind
+    source_id TEXT, -- No foreign key, as it refers to a different table for each kind
+    account_code TEXT NOT NULL,
+    account_name TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX idx_account_mappings_kind_source ON account_mappings(kind, COALESCE(source_id, ''));
@@ -0,0 +1,2 @@
+UPDATE items SET price = price / 100.0;
+UPDATE taxes SET rate = rate / 10000.0;
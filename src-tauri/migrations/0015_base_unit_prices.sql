@@ -0,0 +1,8 @@
+-- Item prices and tax rates used to be written as decimal text in whole units, which SQLite
+-- kept as the integer 120 for "120" and the real 12.5 for "12.50". They are read as base
+-- units: cents for prices and ten-thousandths of a percent for rates.
+UPDATE items SET price = CAST(ROUND(price * 100) AS INTEGER)
+WHERE typeof(price) IN ('integer', 'real');
+
+UPDATE taxes SET rate = CAST(ROUND(rate * 10000) AS INTEGER)
+WHERE typeof(rate) IN ('integer', 'real');
@@ -0,0 +1,3 @@
+-- What a user may do at the register. Supervisors approve price overrides. Existing users
+-- start as cashiers and are promoted with updateUser.
+ALTER TABLE users ADD COLUMN role TEXT NOT NULL DEFAULT 'Cashier';
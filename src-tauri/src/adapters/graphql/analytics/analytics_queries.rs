@@ -1,24 +1,26 @@
 use bigdecimal::BigDecimal;
 use chrono::{Duration, Utc};
-use sea_query::{Alias, Expr, Func, Query};
-use juniper::FieldResult;
+use sea_query::{Alias, Expr, Func, Order, Query};
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     adapters::outgoing::database::DatabaseAdapter,
     core::models::{
+        auth::user_model::Users,
         catalog::item_model::Items,
         sales::{
             customer_model::Customers,
+            sales_order_item_model::SalesOrderItems,
             sales_order_model::SalesOrders,
         },
     },
     AppState,
 };
 
-use super::analytics_overview_model::AnalyticsOverview;
+use super::{analytics_overview_model::AnalyticsOverview, price_override_stat_model::PriceOverrideStat};
 
 pub async fn analytics_overview(days: Option<i32>, context: &AppState) -> FieldResult<AnalyticsOverview> {
-    let service = context.service.lock().await;
+    let service = &context.service;
 
     // Calculate the start date based on the days parameter
     let start_date = match days {
@@ -68,3 +70,54 @@ pub async fn analytics_overview(days: Option<i32>, context: &AppState) -> FieldR
         total_products: total_products as i32,
     })
 }
+
+pub async fn price_override_stats(days: Option<i32>, context: &AppState) -> FieldResult<Vec<PriceOverrideStat>> {
+    let service = &context.service;
+
+    // Calculate the start date based on the days parameter
+    let start_date = match days {
+        Some(d) => Utc::now() - Duration::days(d as i64),
+        None => Utc::now() - Duration::days(365 * 10), // Default to 10 years if no days specified
+    };
+
+    // Overridden lines grouped by the cashier who rang up the order
+    let mut stats_query = Query::select();
+    let stats_stmt = stats_query
+        .expr_as(Expr::col((SalesOrders::Table, SalesOrders::CreatedBy)), Alias::new("user_id"))
+        .expr_as(Expr::col((Users::Table, Users::FullName)), Alias::new("full_name"))
+        .expr_as(
+            Func::count(Expr::col((SalesOrderItems::Table, SalesOrderItems::Id))),
+            Alias::new("override_count"),
+        )
+        .expr_as(
+            Func::count(Expr::col((SalesOrderItems::Table, SalesOrderItems::OverrideApprovedBy))),
+            Alias::new("approved_count"),
+        )
+        .expr_as(
+            Func::sum(Expr::cust(
+                "(COALESCE(sales_order_items.original_price_amount, sales_order_items.price_amount) \
+                 - sales_order_items.price_amount) * sales_order_items.quantity \
+                 + sales_order_items.disc_amount",
+            )),
+            Alias::new("price_reduction"),
+        )
+        .from(SalesOrderItems::Table)
+        .inner_join(
+            SalesOrders::Table,
+            Expr::col((SalesOrders::Table, SalesOrders::Id))
+                .equals((SalesOrderItems::Table, SalesOrderItems::OrderId)),
+        )
+        .inner_join(
+            Users::Table,
+            Expr::col((Users::Table, Users::Id)).equals((SalesOrders::Table, SalesOrders::CreatedBy)),
+        )
+        .and_where(Expr::col((SalesOrderItems::Table, SalesOrderItems::OverrideReasonId)).is_not_null())
+        .and_where(Expr::col((SalesOrders::Table, SalesOrders::CreatedAt)).gte(start_date.naive_utc().to_string()))
+        .group_by_col((SalesOrders::Table, SalesOrders::CreatedBy))
+        .group_by_col((Users::Table, Users::FullName))
+        .order_by(Alias::new("override_count"), Order::Desc);
+
+    let stats = service.db_adapter.query_many::<PriceOverrideStat>(&stats_stmt).await?;
+
+    Ok(stats)
+}
@@ -1,2 +1,3 @@
 pub mod analytics_overview_model;
 pub mod analytics_queries;
+pub mod price_override_stat_model;
@@ -0,0 +1,17 @@
+use juniper::GraphQLObject;
+use lightning_macros::LibsqlFromRow;
+
+use crate::{
+    adapters::outgoing::database::{FromLibsqlValue, FromRow},
+    core::types::{db_uuid::DbUuid, money::Money},
+};
+
+/// How often a cashier overrode line prices or discounts
+#[derive(GraphQLObject, LibsqlFromRow)]
+pub struct PriceOverrideStat {
+    pub user_id: DbUuid,
+    pub full_name: String,
+    pub override_count: i32,
+    pub approved_count: i32,
+    pub price_reduction: Money,
+}
@@ -0,0 +1,35 @@
+use crate::{
+    core::{
+        commands::{
+            auth::api_token_commands::{ApiTokenSecret, CreateApiTokenCommand, RevokeApiTokenCommand},
+            Command,
+        },
+        models::auth::api_token_model::ApiToken,
+        types::db_uuid::DbUuid,
+    },
+    error::Error,
+    AppState,
+};
+use crate::adapters::graphql::errors::FieldResult;
+
+pub async fn create_api_token(
+    user_id: Option<DbUuid>,
+    name: String,
+    context: &AppState,
+) -> FieldResult<ApiTokenSecret> {
+    let service = context.service.write().await;
+    let issued_by = context.user.or(service.current_user());
+    let user_id = user_id
+        .or(issued_by)
+        .ok_or_else(|| Error::ValidationError("userId is required".to_string()))?;
+    let cmd = CreateApiTokenCommand { user_id, name, issued_by };
+    let res = cmd.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn revoke_api_token(id: DbUuid, context: &AppState) -> FieldResult<ApiToken> {
+    let cmd = RevokeApiTokenCommand { id };
+    let service = context.service.write().await;
+    let res = cmd.exec(&service).await?;
+    Ok(res)
+}
@@ -0,0 +1,47 @@
+use chrono::NaiveDateTime;
+use juniper::graphql_object;
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    adapters::graphql::auth::user_queries,
+    core::{
+        models::auth::{api_token_model::ApiToken, user_model::User},
+        types::db_uuid::DbUuid,
+    },
+    AppState,
+};
+
+#[graphql_object(context = AppState)]
+impl ApiToken {
+    pub fn id(&self) -> DbUuid {
+        self.id
+    }
+
+    pub fn user_id(&self) -> DbUuid {
+        self.user_id
+    }
+
+    pub async fn user(&self, context: &AppState) -> FieldResult<User> {
+        user_queries::user(self.user_id, context).await
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn last_used_at(&self) -> Option<NaiveDateTime> {
+        self.last_used_at
+    }
+
+    pub fn revoked_at(&self) -> Option<NaiveDateTime> {
+        self.revoked_at
+    }
+
+    pub fn created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+}
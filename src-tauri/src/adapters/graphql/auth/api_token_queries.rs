@@ -0,0 +1,31 @@
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    adapters::graphql::connection::{connection, list},
+    core::{
+        commands::{auth::api_token_commands::ListApiTokensCommand, Command},
+        db::pagination::PageRequest,
+        models::auth::api_token_model::{ApiToken, ApiTokenFilter, ApiTokenOrderBy},
+        types::db_uuid::DbUuid,
+    },
+    AppState,
+};
+
+pub async fn api_tokens(user_id: DbUuid, context: &AppState) -> FieldResult<Vec<ApiToken>> {
+    let service = &context.service;
+    let res = ListApiTokensCommand { user_id }.exec(service).await?;
+    Ok(res)
+}
+
+connection!(ApiTokenConnection, ApiTokenEdge, ApiToken);
+
+pub async fn api_tokens_connection(
+    page: PageRequest,
+    filter: Option<ApiTokenFilter>,
+    order_by: Option<ApiTokenOrderBy>,
+    context: &AppState,
+) -> FieldResult<ApiTokenConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
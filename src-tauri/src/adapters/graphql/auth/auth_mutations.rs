@@ -4,17 +4,17 @@ use crate::{
     },
     AppState,
 };
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 pub async fn login(username: String, password: String, context: &AppState) -> FieldResult<LoginResponse> {
     let cmd = LoginCommand { username, password };
-    let mut service = context.service.lock().await;
-    let res = cmd.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = cmd.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn logout(context: &AppState) -> FieldResult<()> {
-    let mut service = context.service.lock().await;
-    let res = LogoutCommand.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = LogoutCommand.exec(&service).await?;
     Ok(res)
 }
@@ -1,3 +1,6 @@
+pub mod api_token_mutations;
+pub mod api_token_object;
+pub mod api_token_queries;
 pub mod auth_mutations;
 pub mod user_mutations;
 pub mod user_object;
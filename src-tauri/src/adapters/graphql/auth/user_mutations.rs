@@ -9,25 +9,25 @@ use crate::{
     },
     AppState,
 };
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 pub async fn add_user(user: UserNewInput, context: &AppState) -> FieldResult<User> {
     let cmd = AddUserCommand { user };
-    let mut service = context.service.lock().await;
-    let res = cmd.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = cmd.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn update_user(user: UserUpdateInput, context: &AppState) -> FieldResult<User> {
     let cmd = UpdateUserCommand { user };
-    let mut service = context.service.lock().await;
-    let res = cmd.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = cmd.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn delete_user(id: DbUuid, context: &AppState) -> FieldResult<i32> {
     let cmd = DeleteUserCommand { id };
-    let mut service = context.service.lock().await;
-    let res = cmd.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = cmd.exec(&service).await?;
     Ok(res as i32)
 }
@@ -3,7 +3,7 @@ use juniper::graphql_object;
 
 use crate::{
     core::{
-        models::auth::user_model::{User, UserState},
+        models::auth::user_model::{User, UserRole, UserState},
         types::db_uuid::DbUuid,
     },
     AppState,
@@ -27,6 +27,10 @@ impl User {
         self.state.clone()
     }
 
+    pub fn role(&self) -> UserRole {
+        self.role
+    }
+
     pub fn last_login_at(&self) -> Option<NaiveDateTime> {
         self.last_login_at
     }
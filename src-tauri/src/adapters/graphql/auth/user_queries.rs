@@ -1,10 +1,14 @@
 use sea_query::{Expr, Query};
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
     core::{
-        models::auth::user_model::{User, Users},
+        db::pagination::PageRequest,
+        models::auth::user_model::{User, UserFilter, UserOrderBy, Users},
         types::db_uuid::DbUuid,
     },
     AppState,
@@ -39,7 +43,7 @@ pub async fn users(
     }
 
     // Execute the query
-    let service = context.service.lock().await;
+    let service = &context.service;
     let result = service.db_adapter.query_many::<User>(&stmt).await?;
 
     Ok(result)
@@ -63,8 +67,21 @@ pub async fn user(id: DbUuid, context: &AppState) -> FieldResult<User> {
         .and_where(Expr::col(Users::Id).eq(id.to_string()));
 
     // Execute the query
-    let service = context.service.lock().await;
+    let service = &context.service;
     let result = service.db_adapter.query_one::<User>(&stmt).await?;
 
     Ok(result)
 }
+
+connection!(UserConnection, UserEdge, User);
+
+pub async fn users_connection(
+    page: PageRequest,
+    filter: Option<UserFilter>,
+    order_by: Option<UserOrderBy>,
+    context: &AppState,
+) -> FieldResult<UserConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
@@ -0,0 +1,14 @@
+use crate::{
+    core::{
+        commands::{catalog::catalog_import_commands::ImportCatalogCommand, Command},
+        models::catalog::catalog_import_model::{CatalogImportInput, CatalogImportReport},
+    },
+    AppState,
+};
+use crate::adapters::graphql::errors::FieldResult;
+
+pub async fn import_catalog(import: CatalogImportInput, context: &AppState) -> FieldResult<CatalogImportReport> {
+    let service = context.service.write().await;
+    let res = ImportCatalogCommand { import, created_by: context.user.or(service.current_user()) }.exec(&service).await?;
+    Ok(res)
+}
@@ -1,4 +1,4 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     core::{
@@ -16,21 +16,21 @@ use crate::{
 
 /// Create a new discount
 pub async fn create_discount(discount: DiscountNewInput, context: &AppState) -> FieldResult<Discount> {
-    let mut service = context.service.lock().await;
-    let result = CreateDiscountCommand { discount }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let result = CreateDiscountCommand { discount }.exec(&service).await?;
     Ok(result)
 }
 
 /// Update an existing discount
 pub async fn update_discount(discount: DiscountUpdateInput, context: &AppState) -> FieldResult<Discount> {
-    let mut service = context.service.lock().await;
-    let result = UpdateDiscountCommand { discount }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let result = UpdateDiscountCommand { discount }.exec(&service).await?;
     Ok(result)
 }
 
 /// Delete a discount by its ID
 pub async fn delete_discount(id: DbUuid, context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
-    let result = DeleteDiscountCommand { id }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let result = DeleteDiscountCommand { id }.exec(&service).await?;
     Ok(result as i32)
 }
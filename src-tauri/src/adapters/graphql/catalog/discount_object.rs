@@ -54,4 +54,9 @@ impl Discount {
     pub fn updated_at(&self) -> NaiveDateTime {
         self.updated_at
     }
+
+    /// Pass back as `version` when updating
+    pub fn version(&self) -> i32 {
+        self.version
+    }
 }
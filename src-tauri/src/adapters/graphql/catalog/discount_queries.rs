@@ -1,9 +1,14 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
     core::{
-        commands::{catalog::discount_commands::{GetDiscountCommand, ListDiscountsCommand}, Command},
-        models::catalog::discount_model::{Discount, DiscountState},
+        commands::{catalog::discount_commands::GetDiscountCommand, Command},
+        db::pagination::PageRequest,
+        models::catalog::discount_model::{Discount, DiscountFilter, DiscountOrderBy, DiscountState, Discounts},
         types::db_uuid::DbUuid,
     },
     AppState,
@@ -16,44 +21,34 @@ pub async fn discounts(
     state: Option<DiscountState>,
     context: &AppState,
 ) -> FieldResult<Vec<Discount>> {
-    let mut service = context.service.lock().await;
-
-    // Using the command pattern
-    let result = ListDiscountsCommand.exec(&mut service).await?;
-
-    // Filter by state if provided
-    let filtered_result = match state {
-        Some(filter_state) => result.into_iter().filter(|d| d.state == filter_state).collect(),
-        None => result,
-    };
-
-    // Apply pagination if parameters are provided
-    let paginated_result = match (first, offset) {
-        (Some(limit), Some(off)) => filtered_result
-            .into_iter()
-            .skip(off as usize)
-            .take(limit as usize)
-            .collect(),
-        (Some(limit), None) => filtered_result
-            .into_iter()
-            .take(limit as usize)
-            .collect(),
-        (None, Some(off)) => filtered_result
-            .into_iter()
-            .skip(off as usize)
-            .collect(),
-        (None, None) => filtered_result,
-    };
-
-    Ok(paginated_result)
+    let service = &context.service;
+
+    let filter = DiscountFilter { state, ..Default::default() };
+    let query = Discounts::list(Some(&filter), None, first, offset);
+    let result = service.db_adapter.query_many::<Discount>(&query).await?;
+
+    Ok(result)
 }
 
 /// Fetch a single discount by its ID
 pub async fn discount(id: DbUuid, context: &AppState) -> FieldResult<Discount> {
-    let mut service = context.service.lock().await;
+    let service = &context.service;
 
     // Using our command pattern
-    let result = GetDiscountCommand { id }.exec(&mut service).await?;
+    let result = GetDiscountCommand { id }.exec(&service).await?;
 
     Ok(result)
 }
+
+connection!(DiscountConnection, DiscountEdge, Discount);
+
+pub async fn discounts_connection(
+    page: PageRequest,
+    filter: Option<DiscountFilter>,
+    order_by: Option<DiscountOrderBy>,
+    context: &AppState,
+) -> FieldResult<DiscountConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
@@ -1,11 +1,12 @@
-use juniper::{graphql_object, FieldResult};
+use juniper::graphql_object;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
+    adapters::outgoing::database::DatabaseAdapter,
     core::{
         commands::{
             catalog::{
                 discount_commands::GetDiscountCommand,
-                item_commands::UpdateItemCommand,
                 item_discount_commands::{
                     AddItemDiscountCommand, GetDiscountItemsCommand, GetItemDiscountsCommand,
                     RemoveItemDiscountCommand,
@@ -13,7 +14,10 @@ use crate::{
             },
             Command,
         },
-        models::catalog::item_discount_model::{ItemDiscount, ItemDiscountNewInput},
+        models::catalog::{
+            item_discount_model::{ItemDiscount, ItemDiscountNewInput},
+            item_model::{Item, Items},
+        },
         types::db_uuid::DbUuid,
     },
     AppState,
@@ -40,9 +44,9 @@ impl ItemDiscountQuery {
         context: &AppState,
         item_id: DbUuid,
     ) -> FieldResult<Vec<ItemDiscount>> {
-        let mut service = context.service.lock().await;
+        let service = &context.service;
         let cmd: GetItemDiscountsCommand = GetItemDiscountsCommand { item_id };
-        let item_discounts = cmd.exec(&mut service).await?;
+        let item_discounts = cmd.exec(&service).await?;
         Ok(item_discounts)
     }
 
@@ -51,9 +55,9 @@ impl ItemDiscountQuery {
         context: &AppState,
         discount_id: DbUuid,
     ) -> FieldResult<Vec<ItemDiscount>> {
-        let mut service = context.service.lock().await;
+        let service = &context.service;
         let cmd = GetDiscountItemsCommand { discount_id };
-        let discount_items = cmd.exec(&mut service).await?;
+        let discount_items = cmd.exec(&service).await?;
         Ok(discount_items.into_iter().map(Into::into).collect())
     }
 }
@@ -68,31 +72,20 @@ impl ItemDiscountMutation {
         context: &AppState,
         item_discount: ItemDiscountNewInput,
     ) -> FieldResult<ItemDiscount> {
-        let mut service = context.service.lock().await;
+        let service = context.service.write().await;
 
         // Verify that the item exists
-        let get_item_cmd = UpdateItemCommand {
-            item: crate::core::models::catalog::item_model::UpdateItem {
-                id: item_discount.item_id,
-                name: None,
-                description: None,
-                nature: None,
-                state: None,
-                price: None,
-                category_id: None,
-            },
-        };
-        get_item_cmd.exec(&mut service).await?;
+        service.db_adapter.query_one::<Item>(&Items::find_by_id(item_discount.item_id)).await?;
 
         // Verify that the discount exists
         let get_discount_cmd = GetDiscountCommand {
             id: item_discount.discount_id,
         };
-        get_discount_cmd.exec(&mut service).await?;
+        get_discount_cmd.exec(&service).await?;
 
         // Add the relationship
         let cmd = AddItemDiscountCommand { item_discount };
-        let item_discount = cmd.exec(&mut service).await?;
+        let item_discount = cmd.exec(&service).await?;
         Ok(item_discount.into())
     }
 
@@ -102,12 +95,12 @@ impl ItemDiscountMutation {
         item_id: DbUuid,
         discount_id: DbUuid,
     ) -> FieldResult<bool> {
-        let mut service = context.service.lock().await;
+        let service = context.service.write().await;
         let cmd = RemoveItemDiscountCommand {
             item_id,
             discount_id,
         };
-        let deleted_count = cmd.exec(&mut service).await?;
+        let deleted_count = cmd.exec(&service).await?;
         Ok(deleted_count > 0)
     }
 }
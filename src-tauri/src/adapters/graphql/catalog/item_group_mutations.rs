@@ -2,7 +2,7 @@ use crate::{
     core::{
         commands::{
             catalog::item_group_commands::{
-                CreateItemGroupCommand, DeleteItemGroupCommand, UpdateItemGroupCommand,
+                CreateItemGroupCommand, DeleteItemGroupCommand, PurgeItemGroupCommand, RestoreItemGroupCommand, UpdateItemGroupCommand,
             },
             Command,
         },
@@ -11,11 +11,11 @@ use crate::{
     },
     AppState,
 };
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 pub async fn create_item_category(category: ItemCategoryNew, context: &AppState) -> FieldResult<ItemCategory> {
-    let mut service = context.service.lock().await;
-    let res = CreateItemGroupCommand { category }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = CreateItemGroupCommand { category }.exec(&service).await?;
     Ok(res)
 }
 
@@ -23,13 +23,25 @@ pub async fn update_item_category(
     category: ItemCategoryUpdate,
     context: &AppState,
 ) -> FieldResult<ItemCategory> {
-    let mut service = context.service.lock().await;
-    let res = UpdateItemGroupCommand { category }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = UpdateItemGroupCommand { category }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn delete_item_category(id: DbUuid, context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
-    let res = DeleteItemGroupCommand { id }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = DeleteItemGroupCommand { id, deleted_by: context.user.or(service.current_user()) }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn restore_item_category(id: DbUuid, context: &AppState) -> FieldResult<ItemCategory> {
+    let service = context.service.write().await;
+    let res = RestoreItemGroupCommand { id }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn purge_item_category(id: DbUuid, context: &AppState) -> FieldResult<i32> {
+    let service = context.service.write().await;
+    let res = PurgeItemGroupCommand { id }.exec(&service).await?;
     Ok(res)
 }
@@ -1,10 +1,13 @@
-use sea_query::{Expr, Query};
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
     core::{
-        models::catalog::item_group_model::{ItemCategories, ItemCategory},
+        db::pagination::PageRequest,
+        models::catalog::item_group_model::{ItemCategory, ItemCategoryFilter, ItemCategoryOrderBy, ItemCategories},
         types::db_uuid::DbUuid,
     },
     AppState,
@@ -15,54 +18,31 @@ pub async fn item_categories(
     offset: Option<i32>,
     context: &AppState,
 ) -> FieldResult<Vec<ItemCategory>> {
-    let service = context.service.lock().await;
-
-    // Build the query with SeaQuery
-    let mut query_builder = Query::select();
-    let query = query_builder
-        .from(ItemCategories::Table)
-        .columns([
-            ItemCategories::Id,
-            ItemCategories::Name,
-            ItemCategories::Description,
-            ItemCategories::State,
-            ItemCategories::CreatedAt,
-            ItemCategories::UpdatedAt,
-        ]);
-
-    // Apply pagination if parameters are provided
-    if let Some(limit) = first {
-        query.limit(limit as u64);
-    }
-    if let Some(off) = offset {
-        query.offset(off as u64);
-    }
+    let service = &context.service;
 
-    // Execute the query
-    let result = service.db_adapter.query_many::<ItemCategory>(&&query).await?;
+    let query = ItemCategories::list(None, None, first, offset);
+    let result = service.db_adapter.query_many::<ItemCategory>(&query).await?;
 
     Ok(result)
 }
 
 pub async fn items_category(id: DbUuid, context: &AppState) -> FieldResult<ItemCategory> {
-    let service = context.service.lock().await;
+    let service = &context.service;
 
-    // Build the query with SeaQuery
-    let mut query_builder = Query::select();
-    let query = query_builder
-        .from(ItemCategories::Table)
-        .columns([
-            ItemCategories::Id,
-            ItemCategories::Name,
-            ItemCategories::Description,
-            ItemCategories::State,
-            ItemCategories::CreatedAt,
-            ItemCategories::UpdatedAt,
-        ])
-        .and_where(Expr::col(ItemCategories::Id).eq(id.to_string()));
-
-    // Execute the query
-    let result = service.db_adapter.query_one::<ItemCategory>(&query).await?;
+    let result = service.db_adapter.query_one::<ItemCategory>(&ItemCategories::find_by_id(id)).await?;
 
     Ok(result)
 }
+
+connection!(ItemCategoryConnection, ItemCategoryEdge, ItemCategory);
+
+pub async fn item_categories_connection(
+    page: PageRequest,
+    filter: Option<ItemCategoryFilter>,
+    order_by: Option<ItemCategoryOrderBy>,
+    context: &AppState,
+) -> FieldResult<ItemCategoryConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
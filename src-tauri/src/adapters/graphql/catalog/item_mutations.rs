@@ -1,7 +1,7 @@
 use crate::{
     core::{
         commands::{
-            catalog::item_commands::{CreateItemCommand, DeleteItemCommand, UpdateItemCommand},
+            catalog::item_commands::{CreateItemCommand, DeleteItemCommand, PurgeItemCommand, RestoreItemCommand, UpdateItemCommand},
             Command,
         },
         models::catalog::item_model::{Item, NewItem, UpdateItem},
@@ -9,22 +9,34 @@ use crate::{
     },
     AppState,
 };
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 pub async fn create_item(item: NewItem, context: &AppState) -> FieldResult<Item> {
-    let mut service = context.service.lock().await;
-    let res = CreateItemCommand { item }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = CreateItemCommand { item }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn update_item(item: UpdateItem, context: &AppState) -> FieldResult<Item> {
-    let mut service = context.service.lock().await;
-    let res = UpdateItemCommand { item }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = UpdateItemCommand { item }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn delete_item(id: DbUuid, context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
-    let res = DeleteItemCommand { id }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = DeleteItemCommand { id, deleted_by: context.user.or(service.current_user()) }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn restore_item(id: DbUuid, context: &AppState) -> FieldResult<Item> {
+    let service = context.service.write().await;
+    let res = RestoreItemCommand { id }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn purge_item(id: DbUuid, context: &AppState) -> FieldResult<i32> {
+    let service = context.service.write().await;
+    let res = PurgeItemCommand { id }.exec(&service).await?;
     Ok(res)
 }
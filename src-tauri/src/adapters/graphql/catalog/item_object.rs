@@ -1,20 +1,23 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDateTime, Utc};
 use sea_query::{Expr, Func, Query};
-use juniper::{graphql_object, FieldResult};
+use juniper::graphql_object;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::{graphql::loaders, outgoing::database::DatabaseAdapter},
     core::{
+        commands::catalog::price_list_commands::{price_context_for, resolve_item_price},
         models::{
             catalog::{
-                item_group_model::{ItemCategories, ItemCategory},
+                item_group_model::ItemCategory,
                 item_model::{Item, ItemNature, ItemState},
                 item_variant_model::{ItemVariant, ItemVariants},
             },
-            common::tax_model::{ItemTaxes, Tax, Taxes},
+            common::tax_model::Tax,
         },
         types::{db_uuid::DbUuid, money::Money},
     },
+    error::Error,
     AppState,
 };
 
@@ -40,8 +43,22 @@ impl Item {
         self.state
     }
 
-    pub fn price(&self) -> Money {
-        self.price
+    /// The catalog price, or the price list price when sold on `channel_id` or to `customer_id`
+    pub async fn price(
+        &self,
+        channel_id: Option<DbUuid>,
+        customer_id: Option<DbUuid>,
+        context: &AppState,
+    ) -> FieldResult<Money> {
+        if channel_id.is_none() && customer_id.is_none() {
+            return Ok(self.price);
+        }
+
+        let service = &context.service;
+        let price_context = price_context_for(&service, channel_id, None, customer_id).await?;
+        let price = resolve_item_price(&service, self.id, None, 1, &price_context, Utc::now().naive_utc()).await?;
+
+        Ok(price)
     }
 
     pub fn created_at(&self) -> NaiveDateTime {
@@ -52,88 +69,34 @@ impl Item {
         self.updated_at
     }
 
-    pub async fn category(&self, context: &AppState) -> FieldResult<ItemCategory> {
-        let service = context.service.lock().await;
-
-        let mut query_builder = Query::select();
-        let query = query_builder
-            .from(ItemCategories::Table)
-            .columns([
-                ItemCategories::Id,
-                ItemCategories::Name,
-                ItemCategories::Description,
-                ItemCategories::State,
-                ItemCategories::CreatedAt,
-                ItemCategories::UpdatedAt,
-            ])
-            .and_where(Expr::col(ItemCategories::Id).eq(self.category_id.to_string()));
-        let result = service.db_adapter.query_one::<ItemCategory>(&query).await?;
-        Ok(result)
+    pub fn deleted_at(&self) -> Option<NaiveDateTime> {
+        self.deleted_at
     }
 
-    pub async fn taxes(&self, context: &AppState) -> FieldResult<Vec<Tax>> {
-        let service = context.service.lock().await;
-
-        // First, get the tax IDs for this item
-        let mut tax_ids_query_builder = Query::select();
-        let tax_ids_query = tax_ids_query_builder
-            .from(ItemTaxes::Table)
-            .column(ItemTaxes::TaxId)
-            .and_where(Expr::col(ItemTaxes::ItemId).eq(self.id.to_string()));
-
-        let tax_ids = service.db_adapter.query_many::<DbUuid>(&tax_ids_query).await?;
-
-        // If no tax IDs found, return empty vector
-        if tax_ids.is_empty() {
-            return Ok(vec![]);
-        }
-
-        // Convert tax IDs to strings for the IN clause
-        let tax_id_strings: Vec<String> = tax_ids.iter().map(|id| id.to_string()).collect();
+    pub fn deleted_by(&self) -> Option<DbUuid> {
+        self.deleted_by
+    }
 
-        // Then get the taxes with those IDs
-        let mut taxes_query_builder = Query::select();
-        let taxes_query = taxes_query_builder
-            .from(Taxes::Table)
-            .columns([
-                Taxes::Id,
-                Taxes::Name,
-                Taxes::Rate,
-                Taxes::Description,
-                Taxes::CreatedAt,
-                Taxes::UpdatedAt,
-            ])
-            .and_where(Expr::col(Taxes::Id).is_in(tax_id_strings));
+    /// Pass back as `version` when updating
+    pub fn version(&self) -> i32 {
+        self.version
+    }
 
-        let taxes = service.db_adapter.query_many::<Tax>(&taxes_query).await?;
+    pub async fn category(&self, context: &AppState) -> FieldResult<ItemCategory> {
+        let category = loaders::item_category(context, self.category_id).await?;
+        Ok(category.ok_or(Error::NotFoundError)?)
+    }
 
-        Ok(taxes)
+    pub async fn taxes(&self, context: &AppState) -> FieldResult<Vec<Tax>> {
+        Ok(loaders::item_taxes(context, self.id).await?)
     }
 
     pub async fn variants(&self, context: &AppState) -> FieldResult<Vec<ItemVariant>> {
-        let service = context.service.lock().await;
-
-        let mut query = Query::select();
-        let query = query
-            .from(ItemVariants::Table)
-            .columns([
-                ItemVariants::Id,
-                ItemVariants::ItemId,
-                ItemVariants::Sku,
-                ItemVariants::PriceAdjustment,
-                ItemVariants::IsDefault,
-                ItemVariants::CreatedAt,
-                ItemVariants::UpdatedAt,
-            ])
-            .and_where(Expr::col(ItemVariants::ItemId).eq(self.id.to_string()));
-
-        let variants = service.db_adapter.query_many::<ItemVariant>(&query).await?;
-
-        Ok(variants)
+        Ok(loaders::item_variants(context, self.id).await?)
     }
 
     pub async fn has_variants(&self, context: &AppState) -> FieldResult<bool> {
-        let service = context.service.lock().await;
+        let service = &context.service;
 
         // Count query to check if variants exist
         let mut count_query_builder = Query::select();
@@ -148,7 +111,7 @@ impl Item {
     }
 
     pub async fn default_variant(&self, context: &AppState) -> FieldResult<Option<ItemVariant>> {
-        let service = context.service.lock().await;
+        let service = &context.service;
 
         let mut query_builder = Query::select();
         let query = query_builder
@@ -157,6 +120,7 @@ impl Item {
                 ItemVariants::Id,
                 ItemVariants::ItemId,
                 ItemVariants::Sku,
+                ItemVariants::Barcode,
                 ItemVariants::PriceAdjustment,
                 ItemVariants::IsDefault,
                 ItemVariants::CreatedAt,
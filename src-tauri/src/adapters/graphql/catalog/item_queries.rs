@@ -1,10 +1,14 @@
-use sea_query::{Expr, Query};
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
     core::{
-        models::catalog::item_model::{Item, Items},
+        commands::{catalog::item_search_commands::SearchItemsCommand, Command},
+        db::pagination::PageRequest,
+        models::catalog::item_model::{Item, ItemFilter, ItemOrderBy, Items},
         types::db_uuid::DbUuid,
     },
     AppState,
@@ -15,60 +19,45 @@ pub async fn items(
     offset: Option<i32>,
     context: &AppState,
 ) -> FieldResult<Vec<Item>> {
-    let service = context.service.lock().await;
+    let service = &context.service;
 
-    // Build the query with SeaQuery
-    let mut query_builder = Query::select();
-    let query = query_builder
-        .from(Items::Table)
-        .columns([
-            Items::Id,
-            Items::Name,
-            Items::Description,
-            Items::Nature,
-            Items::State,
-            Items::Price,
-            Items::CategoryId,
-            Items::CreatedAt,
-            Items::UpdatedAt,
-        ]);
+    let query = Items::list(None, None, first, offset);
+    let result = service.db_adapter.query_many::<Item>(&query).await?;
 
-    if let Some(limit) = first {
-        query.limit(limit as u64);
-    }
+    Ok(result)
+}
 
-    if let Some(off) = offset {
-        query.offset(off as u64);
-    }
+pub async fn item(id: DbUuid, context: &AppState) -> FieldResult<Item> {
+    let service = &context.service;
 
-    // Execute the query
-    let result = service.db_adapter.query_many::<Item>(&query).await?;
+    let result = service.db_adapter.query_one::<Item>(&Items::find_by_id(id)).await?;
 
     Ok(result)
 }
 
-pub async fn item(id: DbUuid, context: &AppState) -> FieldResult<Item> {
-    let service = context.service.lock().await;
+pub async fn search_items(
+    query: String,
+    category_id: Option<DbUuid>,
+    limit: Option<i32>,
+    context: &AppState,
+) -> FieldResult<Vec<Item>> {
+    let service = &context.service;
 
-    // Build the query with SeaQuery
-    let mut query_builder = Query::select();
-    let query = query_builder
-        .from(Items::Table)
-        .columns([
-            Items::Id,
-            Items::Name,
-            Items::Description,
-            Items::Nature,
-            Items::State,
-            Items::Price,
-            Items::CategoryId,
-            Items::CreatedAt,
-            Items::UpdatedAt,
-        ])
-        .and_where(Expr::col(Items::Id).eq(id.to_string()));
+    let command = SearchItemsCommand { query, category_id, limit };
+    let items = command.exec(service).await?;
 
-    // Execute the query
-    let result = service.db_adapter.query_one::<Item>(&query).await?;
+    Ok(items)
+}
 
-    Ok(result)
+connection!(ItemConnection, ItemEdge, Item);
+
+pub async fn items_connection(
+    page: PageRequest,
+    filter: Option<ItemFilter>,
+    order_by: Option<ItemOrderBy>,
+    context: &AppState,
+) -> FieldResult<ItemConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
 }
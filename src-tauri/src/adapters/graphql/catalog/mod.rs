@@ -1,3 +1,4 @@
+pub mod catalog_import_mutations;
 pub mod discount_mutations;
 pub mod discount_object;
 pub mod discount_queries;
@@ -8,4 +9,9 @@ pub mod item_group_queries;
 pub mod item_mutations;
 pub mod item_object;
 pub mod item_queries;
+pub mod price_list_mutations;
+pub mod price_list_object;
+pub mod price_list_queries;
+pub mod stock_movement_mutations;
+pub mod stock_movement_queries;
 pub mod variants;
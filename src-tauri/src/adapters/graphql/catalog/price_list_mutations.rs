@@ -0,0 +1,64 @@
+use crate::{
+    core::{
+        commands::{
+            catalog::price_list_commands::{
+                AssignPriceListCommand, CreatePriceListCommand, DeletePriceListCommand,
+                RemovePriceListItemCommand, SetPriceListItemCommand, UnassignPriceListCommand,
+                UpdatePriceListCommand,
+            },
+            Command,
+        },
+        models::catalog::price_list_model::{
+            PriceList, PriceListAssignment, PriceListAssignmentInput, PriceListItem, PriceListItemInput,
+            PriceListNewInput, PriceListUpdateInput,
+        },
+        types::db_uuid::DbUuid,
+    },
+    AppState,
+};
+use crate::adapters::graphql::errors::FieldResult;
+
+pub async fn create_price_list(price_list: PriceListNewInput, context: &AppState) -> FieldResult<PriceList> {
+    let service = context.service.write().await;
+    let result = CreatePriceListCommand { price_list }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn update_price_list(price_list: PriceListUpdateInput, context: &AppState) -> FieldResult<PriceList> {
+    let service = context.service.write().await;
+    let result = UpdatePriceListCommand { price_list }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn delete_price_list(id: DbUuid, context: &AppState) -> FieldResult<bool> {
+    let service = context.service.write().await;
+    let result = DeletePriceListCommand { id }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn set_price_list_item(price: PriceListItemInput, context: &AppState) -> FieldResult<PriceListItem> {
+    let service = context.service.write().await;
+    let result = SetPriceListItemCommand { price }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn remove_price_list_item(id: DbUuid, context: &AppState) -> FieldResult<bool> {
+    let service = context.service.write().await;
+    let result = RemovePriceListItemCommand { id }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn assign_price_list(
+    assignment: PriceListAssignmentInput,
+    context: &AppState,
+) -> FieldResult<PriceListAssignment> {
+    let service = context.service.write().await;
+    let result = AssignPriceListCommand { assignment }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn unassign_price_list(id: DbUuid, context: &AppState) -> FieldResult<bool> {
+    let service = context.service.write().await;
+    let result = UnassignPriceListCommand { id }.exec(&service).await?;
+    Ok(result)
+}
@@ -0,0 +1,150 @@
+use chrono::NaiveDateTime;
+use juniper::graphql_object;
+use crate::adapters::graphql::errors::FieldResult;
+use sea_query::{Expr, Order, Query};
+
+use crate::{
+    adapters::outgoing::database::DatabaseAdapter,
+    core::{
+        models::catalog::price_list_model::{
+            PriceList, PriceListAssignment, PriceListAssignments, PriceListItem, PriceListItems,
+        },
+        types::{db_uuid::DbUuid, money::Money},
+    },
+    AppState,
+};
+
+#[graphql_object(context = AppState)]
+impl PriceList {
+    pub fn id(&self) -> DbUuid {
+        self.id
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    pub fn valid_from(&self) -> Option<NaiveDateTime> {
+        self.valid_from
+    }
+
+    pub fn valid_to(&self) -> Option<NaiveDateTime> {
+        self.valid_to
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    pub fn created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+
+    pub async fn items(&self, context: &AppState) -> FieldResult<Vec<PriceListItem>> {
+        let service = &context.service;
+
+        let mut query = Query::select();
+        let stmt = query
+            .from(PriceListItems::Table)
+            .columns(PriceListItems::all_columns())
+            .and_where(Expr::col(PriceListItems::PriceListId).eq(self.id.to_string()))
+            .order_by(PriceListItems::ItemId, Order::Asc)
+            .order_by(PriceListItems::MinQuantity, Order::Asc);
+
+        let items = service.db_adapter.query_many::<PriceListItem>(&stmt).await?;
+
+        Ok(items)
+    }
+
+    pub async fn assignments(&self, context: &AppState) -> FieldResult<Vec<PriceListAssignment>> {
+        let service = &context.service;
+
+        let mut query = Query::select();
+        let stmt = query
+            .from(PriceListAssignments::Table)
+            .columns(PriceListAssignments::all_columns())
+            .and_where(Expr::col(PriceListAssignments::PriceListId).eq(self.id.to_string()));
+
+        let assignments = service.db_adapter.query_many::<PriceListAssignment>(&stmt).await?;
+
+        Ok(assignments)
+    }
+}
+
+#[graphql_object(context = AppState)]
+impl PriceListItem {
+    pub fn id(&self) -> DbUuid {
+        self.id
+    }
+
+    pub fn price_list_id(&self) -> DbUuid {
+        self.price_list_id
+    }
+
+    pub fn item_id(&self) -> DbUuid {
+        self.item_id
+    }
+
+    pub fn variant_id(&self) -> Option<DbUuid> {
+        self.variant_id
+    }
+
+    pub fn min_quantity(&self) -> i32 {
+        self.min_quantity
+    }
+
+    pub fn price(&self) -> Money {
+        self.price
+    }
+
+    pub fn created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+}
+
+#[graphql_object(context = AppState)]
+impl PriceListAssignment {
+    pub fn id(&self) -> DbUuid {
+        self.id
+    }
+
+    pub fn price_list_id(&self) -> DbUuid {
+        self.price_list_id
+    }
+
+    pub fn channel_id(&self) -> Option<DbUuid> {
+        self.channel_id
+    }
+
+    pub fn location_id(&self) -> Option<DbUuid> {
+        self.location_id
+    }
+
+    pub fn customer_group_id(&self) -> Option<DbUuid> {
+        self.customer_group_id
+    }
+
+    pub fn created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+}
@@ -0,0 +1,87 @@
+use sea_query::Order;
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
+    core::{
+        commands::{catalog::price_list_commands::ResolveItemPriceCommand, Command},
+        db::pagination::PageRequest,
+        models::catalog::price_list_model::{PriceList, PriceListFilter, PriceListOrderBy, PriceLists},
+        types::{db_uuid::DbUuid, money::Money},
+    },
+    AppState,
+};
+
+pub async fn price_lists(
+    first: Option<i32>,
+    offset: Option<i32>,
+    context: &AppState,
+) -> FieldResult<Vec<PriceList>> {
+    let service = &context.service;
+
+    // Highest priority first, then by name
+    let mut query = PriceLists::select();
+    query
+        .order_by(PriceLists::Priority, Order::Desc)
+        .order_by(PriceLists::Name, Order::Asc);
+
+    // Apply pagination if parameters are provided
+    if let Some(limit) = first {
+        query.limit(limit as u64);
+    }
+    if let Some(off) = offset {
+        query.offset(off as u64);
+    }
+
+    // Execute the query
+    let result = service.db_adapter.query_many::<PriceList>(&query).await?;
+
+    Ok(result)
+}
+
+pub async fn price_list(id: DbUuid, context: &AppState) -> FieldResult<PriceList> {
+    let service = &context.service;
+
+    let result = service.db_adapter.query_one::<PriceList>(&PriceLists::find_by_id(id)).await?;
+
+    Ok(result)
+}
+
+pub async fn item_price(
+    item_id: DbUuid,
+    variant_id: Option<DbUuid>,
+    quantity: Option<i32>,
+    channel_id: Option<DbUuid>,
+    location_id: Option<DbUuid>,
+    customer_id: Option<DbUuid>,
+    context: &AppState,
+) -> FieldResult<Money> {
+    let service = &context.service;
+    let result = ResolveItemPriceCommand {
+        item_id,
+        variant_id,
+        quantity: quantity.unwrap_or(1),
+        channel_id,
+        location_id,
+        customer_id,
+    }
+    .exec(&service)
+    .await?;
+    Ok(result)
+}
+
+connection!(PriceListConnection, PriceListEdge, PriceList);
+
+pub async fn price_lists_connection(
+    page: PageRequest,
+    filter: Option<PriceListFilter>,
+    order_by: Option<PriceListOrderBy>,
+    context: &AppState,
+) -> FieldResult<PriceListConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
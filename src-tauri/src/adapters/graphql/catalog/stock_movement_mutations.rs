@@ -0,0 +1,16 @@
+use crate::{
+    core::{
+        commands::{catalog::stock_movement_commands::RecordStockMovementCommand, Command},
+        models::catalog::stock_movement_model::{StockMovement, StockMovementNewInput},
+    },
+    AppState,
+};
+use crate::adapters::graphql::errors::FieldResult;
+
+pub async fn record_stock_movement(movement: StockMovementNewInput, context: &AppState) -> FieldResult<StockMovement> {
+    let service = context.service.write().await;
+    let res = RecordStockMovementCommand { movement, created_by: context.user.or(service.current_user()) }
+        .exec(&service)
+        .await?;
+    Ok(res)
+}
@@ -0,0 +1,18 @@
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    core::{
+        commands::{catalog::stock_movement_commands::GetStockOnHandCommand, Command},
+        types::db_uuid::DbUuid,
+    },
+    AppState,
+};
+
+pub async fn stock_on_hand(item_id: DbUuid, item_variant_id: Option<DbUuid>, context: &AppState) -> FieldResult<i32> {
+    let service = &context.service;
+
+    let command = GetStockOnHandCommand { item_id, item_variant_id };
+    let quantity = command.exec(service).await?;
+
+    Ok(quantity)
+}
@@ -1,4 +1,4 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::core::commands::catalog::item_variant_commands::{
     AssignVariantValueCommand, CreateItemVariantCommand, DeleteItemVariantCommand,
@@ -15,13 +15,13 @@ pub async fn create_item_variant(
     input: ItemVariantNewInput,
     context: &AppState,
 ) -> FieldResult<ItemVariant> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
 
     let command = CreateItemVariantCommand {
         item_variant: input,
     };
 
-    let item_variant = command.exec(&mut service).await?;
+    let item_variant = command.exec(&service).await?;
     Ok(item_variant)
 }
 
@@ -29,28 +29,23 @@ pub async fn update_item_variant(
     input: ItemVariantUpdateInput,
     context: &AppState,
 ) -> FieldResult<ItemVariant> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
 
     let command = UpdateItemVariantCommand {
         item_variant: input,
     };
 
-    let updated_item_variant = command.exec(&mut service).await?;
+    let updated_item_variant = command.exec(&service).await?;
     Ok(updated_item_variant)
 }
 
 pub async fn delete_item_variant(id: DbUuid, context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
 
     let command = DeleteItemVariantCommand { id };
 
-    match command.exec(&mut service).await {
-        Ok(count) => Ok(count as i32),
-        Err(e) => Err(juniper::FieldError::new(
-            format!("Failed to delete item variant: {}", e),
-            juniper::Value::null(),
-        )),
-    }
+    let count = command.exec(&service).await?;
+    Ok(count as i32)
 }
 
 pub async fn assign_variant_value_to_item_variant(
@@ -58,14 +53,14 @@ pub async fn assign_variant_value_to_item_variant(
     variant_value_id: DbUuid,
     context: &AppState,
 ) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
 
     let command = AssignVariantValueCommand {
         item_variant_id,
         variant_value_id,
     };
 
-    let result = command.exec(&mut service).await?;
+    let result = command.exec(&service).await?;
     Ok(result as i32)
 }
 
@@ -74,13 +69,13 @@ pub async fn remove_variant_value_from_item_variant(
     variant_value_id: DbUuid,
     context: &AppState,
 ) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
 
     let command = RemoveVariantValueCommand {
         item_variant_id,
         variant_value_id,
     };
 
-    let result = command.exec(&mut service).await?;
+    let result = command.exec(&service).await?;
     Ok(result as i32)
 }
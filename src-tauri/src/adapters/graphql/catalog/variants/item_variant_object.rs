@@ -1,6 +1,7 @@
 use chrono::NaiveDateTime;
 use sea_query::{Expr, Query};
-use juniper::{graphql_object, FieldResult};
+use juniper::graphql_object;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     adapters::outgoing::database::DatabaseAdapter,
@@ -26,6 +27,10 @@ impl ItemVariant {
         self.sku.clone()
     }
 
+    pub fn barcode(&self) -> Option<String> {
+        self.barcode.clone()
+    }
+
     pub fn price_adjustment(&self) -> Option<Money> {
         self.price_adjustment
     }
@@ -43,7 +48,7 @@ impl ItemVariant {
     }
 
     pub async fn item(&self, context: &AppState) -> FieldResult<Item> {
-        let service = context.service.lock().await;
+        let service = &context.service;
 
         let mut query = Query::select();
         let query = query
@@ -58,6 +63,7 @@ impl ItemVariant {
                 Items::CategoryId,
                 Items::CreatedAt,
                 Items::UpdatedAt,
+                Items::Version,
             ])
             .and_where(Expr::col(Items::Id).eq(self.item_id.to_string()));
 
@@ -67,7 +73,7 @@ impl ItemVariant {
     }
 
     pub async fn variant_values(&self, context: &AppState) -> FieldResult<Vec<VariantValue>> {
-        let service = context.service.lock().await;
+        let service = &context.service;
 
         // First, get the variant value IDs for this item variant
         let mut value_ids_query = Query::select();
@@ -106,7 +112,7 @@ impl ItemVariant {
     }
 
     pub async fn final_price(&self, context: &AppState) -> FieldResult<Money> {
-        let service = context.service.lock().await;
+        let service = &context.service;
 
         let mut query = Query::select();
         let query = query
@@ -121,6 +127,7 @@ impl ItemVariant {
                 Items::CategoryId,
                 Items::CreatedAt,
                 Items::UpdatedAt,
+                Items::Version,
             ])
             .and_where(Expr::col(Items::Id).eq(self.item_id.to_string()));
 
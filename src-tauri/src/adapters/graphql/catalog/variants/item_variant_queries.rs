@@ -1,18 +1,34 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
+use crate::adapters::graphql::connection::{connection, list};
+use crate::adapters::outgoing::database::DatabaseAdapter;
 use crate::core::commands::catalog::item_variant_commands::{
-    GetItemVariantCommand, ListItemVariantsCommand,
+    GetItemVariantByBarcodeCommand, GetItemVariantCommand,
 };
 use crate::core::commands::Command;
-use crate::core::models::catalog::item_variant_model::ItemVariant;
+use crate::core::db::pagination::PageRequest;
+use crate::core::models::catalog::item_variant_model::{
+    ItemVariant, ItemVariantFilter, ItemVariantOrderBy, ItemVariants,
+};
 use crate::core::types::db_uuid::DbUuid;
 use crate::AppState;
 
 pub async fn get_item_variant(id: DbUuid, context: &AppState) -> FieldResult<ItemVariant> {
-    let mut service = context.service.lock().await;
+    let service = &context.service;
 
     let command = GetItemVariantCommand { id };
-    let item_variant = command.exec(&mut service).await?;
+    let item_variant = command.exec(&service).await?;
+    Ok(item_variant)
+}
+
+pub async fn get_item_variant_by_barcode(
+    barcode: String,
+    context: &AppState,
+) -> FieldResult<Option<ItemVariant>> {
+    let service = &context.service;
+
+    let command = GetItemVariantByBarcodeCommand { barcode };
+    let item_variant = command.exec(service).await?;
     Ok(item_variant)
 }
 
@@ -22,24 +38,24 @@ pub async fn get_item_variants(
     offset: Option<i32>,
     context: &AppState,
 ) -> FieldResult<Vec<ItemVariant>> {
-    let mut service = context.service.lock().await;
-
-    let command = ListItemVariantsCommand { item_id };
-    let item_variants = command.exec(&mut service).await?;
-
-    // Apply pagination in memory
-    let offset_val = offset.unwrap_or(0) as usize;
-    let limit = first.map(|f| f as usize);
-
-    let paginated_variants = if let Some(limit) = limit {
-        item_variants
-            .into_iter()
-            .skip(offset_val)
-            .take(limit)
-            .collect()
-    } else {
-        item_variants.into_iter().skip(offset_val).collect()
-    };
-
-    Ok(paginated_variants)
+    let service = &context.service;
+
+    let filter = ItemVariantFilter { item_id, ..Default::default() };
+    let query = ItemVariants::list(Some(&filter), None, first, offset);
+    let item_variants = service.db_adapter.query_many::<ItemVariant>(&query).await?;
+
+    Ok(item_variants)
+}
+
+connection!(ItemVariantConnection, ItemVariantEdge, ItemVariant);
+
+pub async fn item_variants_connection(
+    page: PageRequest,
+    filter: Option<ItemVariantFilter>,
+    order_by: Option<ItemVariantOrderBy>,
+    context: &AppState,
+) -> FieldResult<ItemVariantConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
 }
@@ -1,5 +1,6 @@
 use chrono::NaiveDateTime;
-use juniper::{graphql_object, FieldResult};
+use juniper::graphql_object;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::core::commands::catalog::variant_value_commands::ListVariantValuesCommand;
 use crate::core::commands::Command;
@@ -31,11 +32,11 @@ impl VariantType {
     }
 
     pub async fn values(&self, context: &AppState) -> FieldResult<Vec<VariantValue>> {
-        let mut service = context.service.lock().await;
+        let service = &context.service;
         let command = ListVariantValuesCommand {
             variant_type_id: Some(self.id),
         };
-        let values = command.exec(&mut service).await?;
+        let values = command.exec(&service).await?;
         Ok(values)
     }
 }
@@ -1,18 +1,21 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
-use crate::core::commands::catalog::variant_type_commands::{
-    GetVariantTypeCommand, ListVariantTypesCommand,
-};
+use crate::adapters::graphql::connection::{connection, list};
+use crate::adapters::outgoing::database::DatabaseAdapter;
+use crate::core::commands::catalog::variant_type_commands::GetVariantTypeCommand;
 use crate::core::commands::Command;
-use crate::core::models::catalog::variant_type_model::VariantType;
+use crate::core::db::pagination::PageRequest;
+use crate::core::models::catalog::variant_type_model::{
+    VariantType, VariantTypeFilter, VariantTypeOrderBy, VariantTypes,
+};
 use crate::core::types::db_uuid::DbUuid;
 use crate::AppState;
 
 pub async fn get_variant_type(id: DbUuid, context: &AppState) -> FieldResult<VariantType> {
-    let mut service = context.service.lock().await;
+    let service = &context.service;
 
     let command = GetVariantTypeCommand { id };
-    let variant_type = command.exec(&mut service).await?;
+    let variant_type = command.exec(&service).await?;
     Ok(variant_type)
 }
 
@@ -21,32 +24,31 @@ pub async fn get_variant_types(
     offset: Option<i32>,
     context: &AppState,
 ) -> FieldResult<Vec<VariantType>> {
-    let mut service = context.service.lock().await;
-
-    let command = ListVariantTypesCommand;
-    let variant_types = command.exec(&mut service).await?;
-
-    // Apply pagination in memory since our command doesn't support it directly
-    let offset_val = offset.unwrap_or(0) as usize;
-    let limit = first.map(|f| f as usize);
-
-    let paginated_types = if let Some(limit) = limit {
-        variant_types
-            .into_iter()
-            .skip(offset_val)
-            .take(limit)
-            .collect()
-    } else {
-        variant_types.into_iter().skip(offset_val).collect()
-    };
-
-    Ok(paginated_types)
+    let service = &context.service;
+
+    let query = VariantTypes::list(None, None, first, offset);
+    let variant_types = service.db_adapter.query_many::<VariantType>(&query).await?;
+
+    Ok(variant_types)
 }
 
 pub async fn get_total_variant_types(context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
+    let service = &context.service;
+
+    let total = service.db_adapter.query_one::<i64>(&VariantTypes::count(None)).await?;
+
+    Ok(total as i32)
+}
+
+connection!(VariantTypeConnection, VariantTypeEdge, VariantType);
+
+pub async fn variant_types_connection(
+    page: PageRequest,
+    filter: Option<VariantTypeFilter>,
+    order_by: Option<VariantTypeOrderBy>,
+    context: &AppState,
+) -> FieldResult<VariantTypeConnection> {
+    let page = list(context, filter, order_by, page).await?;
 
-    let command = ListVariantTypesCommand;
-    let variant_types = command.exec(&mut service).await?;
-    Ok(variant_types.len() as i32)
+    Ok(page.into())
 }
@@ -1,4 +1,4 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::core::commands::catalog::variant_value_commands::{
     CreateVariantValueCommand, DeleteVariantValueCommand, UpdateVariantValueCommand,
@@ -14,13 +14,13 @@ pub async fn create_variant_value(
     input: VariantValueNewInput,
     context: &AppState,
 ) -> FieldResult<VariantValue> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
 
     let command = CreateVariantValueCommand {
         variant_value: input,
     };
 
-    let variant_value = command.exec(&mut service).await?;
+    let variant_value = command.exec(&service).await?;
     Ok(variant_value)
 }
 
@@ -28,30 +28,21 @@ pub async fn update_variant_value(
     input: VariantValueUpdateInput,
     context: &AppState,
 ) -> FieldResult<VariantValue> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
 
     let command = UpdateVariantValueCommand {
         variant_value: input,
     };
 
-    let updated_variant_value = command.exec(&mut service).await?;
+    let updated_variant_value = command.exec(&service).await?;
     Ok(updated_variant_value)
 }
 
 pub async fn delete_variant_value(id: DbUuid, context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
 
     let command = DeleteVariantValueCommand { id };
 
-    match command.exec(&mut service).await {
-        Ok(count) => Ok(count as i32),
-        Err(crate::error::Error::HasChildrenError) => Err(juniper::FieldError::new(
-            "Cannot delete variant value that is used by item variants",
-            juniper::Value::null(),
-        )),
-        Err(e) => Err(juniper::FieldError::new(
-            format!("Failed to delete variant value: {}", e),
-            juniper::Value::null(),
-        )),
-    }
+    let count = command.exec(&service).await?;
+    Ok(count as i32)
 }
@@ -1,6 +1,7 @@
 use chrono::NaiveDateTime;
 use sea_query::{Expr, Query};
-use juniper::{graphql_object, FieldResult};
+use juniper::graphql_object;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     adapters::outgoing::database::DatabaseAdapter,
@@ -37,7 +38,7 @@ impl VariantValue {
     }
 
     pub async fn variant_type(&self, context: &AppState) -> FieldResult<VariantType> {
-        let service = context.service.lock().await;
+        let service = &context.service;
 
         let mut query = Query::select();
         let query = query
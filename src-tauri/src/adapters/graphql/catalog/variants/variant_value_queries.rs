@@ -1,18 +1,21 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
-use crate::core::commands::catalog::variant_value_commands::{
-    GetVariantValueCommand, ListVariantValuesCommand,
-};
+use crate::adapters::graphql::connection::{connection, list};
+use crate::adapters::outgoing::database::DatabaseAdapter;
+use crate::core::commands::catalog::variant_value_commands::GetVariantValueCommand;
 use crate::core::commands::Command;
-use crate::core::models::catalog::variant_value_model::VariantValue;
+use crate::core::db::pagination::{PageRequest, SortDirection};
+use crate::core::models::catalog::variant_value_model::{
+    VariantValue, VariantValueFilter, VariantValueOrderBy, VariantValues,
+};
 use crate::core::types::db_uuid::DbUuid;
 use crate::AppState;
 
 pub async fn get_variant_value(id: DbUuid, context: &AppState) -> FieldResult<VariantValue> {
-    let mut service = context.service.lock().await;
+    let service = &context.service;
 
     let command = GetVariantValueCommand { id };
-    let variant_value = command.exec(&mut service).await?;
+    let variant_value = command.exec(&service).await?;
     Ok(variant_value)
 }
 
@@ -22,24 +25,26 @@ pub async fn get_variant_values(
     offset: Option<i32>,
     context: &AppState,
 ) -> FieldResult<Vec<VariantValue>> {
-    let mut service = context.service.lock().await;
-
-    let command = ListVariantValuesCommand { variant_type_id };
-    let variant_values = command.exec(&mut service).await?;
-
-    // Apply pagination in memory
-    let offset_val = offset.unwrap_or(0) as usize;
-    let limit = first.map(|f| f as usize);
-
-    let paginated_values = if let Some(limit) = limit {
-        variant_values
-            .into_iter()
-            .skip(offset_val)
-            .take(limit)
-            .collect()
-    } else {
-        variant_values.into_iter().skip(offset_val).collect()
-    };
-
-    Ok(paginated_values)
+    let service = &context.service;
+
+    // In display order, as the values are shown to the cashier
+    let filter = VariantValueFilter { variant_type_id, ..Default::default() };
+    let sort = (VariantValues::DisplayOrder, SortDirection::Asc);
+    let query = VariantValues::list(Some(&filter), Some(sort), first, offset);
+    let variant_values = service.db_adapter.query_many::<VariantValue>(&query).await?;
+
+    Ok(variant_values)
+}
+
+connection!(VariantValueConnection, VariantValueEdge, VariantValue);
+
+pub async fn variant_values_connection(
+    page: PageRequest,
+    filter: Option<VariantValueFilter>,
+    order_by: Option<VariantValueOrderBy>,
+    context: &AppState,
+) -> FieldResult<VariantValueConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
 }
@@ -0,0 +1,18 @@
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    core::{
+        commands::{common::backup_commands::CreateBackupCommand, Command},
+        db::backup::BackupConfig,
+        models::common::backup_model::{Backup, BackupKind},
+    },
+    AppState,
+};
+
+pub async fn create_snapshot(label: Option<String>, context: &AppState) -> FieldResult<Backup> {
+    // Holding the write lock means no transaction is open on the connection being copied
+    let service = context.service.write().await;
+    let config = BackupConfig::load(&service.conn_path, service.secrets.as_ref())?;
+    let res = CreateBackupCommand { config, kind: BackupKind::Snapshot, label }.exec(&service).await?;
+    Ok(res)
+}
@@ -0,0 +1,17 @@
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    core::{
+        commands::{common::backup_commands::ListBackupsCommand, Command},
+        db::backup::BackupConfig,
+        models::common::backup_model::Backup,
+    },
+    AppState,
+};
+
+pub async fn backups(context: &AppState) -> FieldResult<Vec<Backup>> {
+    let service = &context.service;
+    let config = BackupConfig::load(&service.conn_path, service.secrets.as_ref())?;
+    let res = ListBackupsCommand { config }.exec(service).await?;
+    Ok(res)
+}
@@ -1,9 +1,9 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     core::{
         commands::{
-            common::brand_commands::{CreateBrandCommand, DeleteBrandCommand, UpdateBrandCommand},
+            common::brand_commands::{CreateBrandCommand, DeleteBrandCommand, PurgeBrandCommand, RestoreBrandCommand, UpdateBrandCommand},
             Command,
         },
         models::common::brand_model::{Brand, BrandNewInput, BrandUpdateInput},
@@ -13,19 +13,31 @@ use crate::{
 };
 
 pub async fn create_brand(input: BrandNewInput, context: &AppState) -> FieldResult<Brand> {
-    let mut service = context.service.lock().await;
-    let res = CreateBrandCommand { brand: input }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = CreateBrandCommand { brand: input }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn update_brand(input: BrandUpdateInput, context: &AppState) -> FieldResult<Brand> {
-    let mut service = context.service.lock().await;
-    let res = UpdateBrandCommand { brand: input }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = UpdateBrandCommand { brand: input }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn delete_brand(id: DbUuid, context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
-    let res = DeleteBrandCommand { id }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = DeleteBrandCommand { id, deleted_by: context.user.or(service.current_user()) }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn restore_brand(id: DbUuid, context: &AppState) -> FieldResult<Brand> {
+    let service = context.service.write().await;
+    let res = RestoreBrandCommand { id }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn purge_brand(id: DbUuid, context: &AppState) -> FieldResult<i32> {
+    let service = context.service.write().await;
+    let res = PurgeBrandCommand { id }.exec(&service).await?;
     Ok(res)
 }
@@ -31,4 +31,12 @@ impl Brand {
     pub fn updated_at(&self) -> NaiveDateTime {
         self.updated_at
     }
+
+    pub fn deleted_at(&self) -> Option<NaiveDateTime> {
+        self.deleted_at
+    }
+
+    pub fn deleted_by(&self) -> Option<DbUuid> {
+        self.deleted_by
+    }
 }
@@ -1,73 +1,55 @@
-use sea_query::{Expr, Query};
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
     core::{
-        models::common::brand_model::{Brand, Brands},
+        db::pagination::PageRequest,
+        models::common::brand_model::{Brand, BrandFilter, BrandOrderBy, Brands},
         types::db_uuid::DbUuid,
     },
     AppState,
 };
 
 pub async fn get_brand(id: DbUuid, context: &AppState) -> FieldResult<Brand> {
-    let service = context.service.lock().await;
-
-    let mut query_builder = Query::select();
-    let query = query_builder
-        .from(Brands::Table)
-        .columns([
-            Brands::Id,
-            Brands::Name,
-            Brands::Description,
-            Brands::IsActive,
-            Brands::CreatedAt,
-            Brands::UpdatedAt,
-        ])
-        .and_where(Expr::col(Brands::Id).eq(id.to_string()));
+    let service = &context.service;
 
+    let query = Brands::find_by_id(id);
     let brand = service.db_adapter.query_one::<Brand>(&query).await?;
 
     Ok(brand)
 }
 
 pub async fn get_brands(context: &AppState) -> FieldResult<Vec<Brand>> {
-    let service = context.service.lock().await;
-
-    let mut query_builder = Query::select();
-    let query = query_builder
-        .from(Brands::Table)
-        .columns([
-            Brands::Id,
-            Brands::Name,
-            Brands::Description,
-            Brands::IsActive,
-            Brands::CreatedAt,
-            Brands::UpdatedAt,
-        ]);
+    let service = &context.service;
 
+    let query = Brands::list(None, None, None, None);
     let brands_list = service.db_adapter.query_many::<Brand>(&query).await?;
 
     Ok(brands_list)
 }
 
 pub async fn get_active_brands(context: &AppState) -> FieldResult<Vec<Brand>> {
-    let service = context.service.lock().await;
-
-    let mut query_builder = Query::select();
-    let query = query_builder
-        .from(Brands::Table)
-        .columns([
-            Brands::Id,
-            Brands::Name,
-            Brands::Description,
-            Brands::IsActive,
-            Brands::CreatedAt,
-            Brands::UpdatedAt,
-        ])
-        .and_where(Expr::col(Brands::IsActive).eq(true));
+    let service = &context.service;
 
+    let filter = BrandFilter { is_active: Some(true), ..Default::default() };
+    let query = Brands::list(Some(&filter), None, None, None);
     let brands_list = service.db_adapter.query_many::<Brand>(&query).await?;
 
     Ok(brands_list)
 }
+
+connection!(BrandConnection, BrandEdge, Brand);
+
+pub async fn brands_connection(
+    page: PageRequest,
+    filter: Option<BrandFilter>,
+    order_by: Option<BrandOrderBy>,
+    context: &AppState,
+) -> FieldResult<BrandConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
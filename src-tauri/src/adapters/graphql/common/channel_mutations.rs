@@ -1,10 +1,10 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     core::{
         commands::{
             common::channel_commands::{
-                CreateChannelCommand, DeleteChannelCommand, UpdateChannelCommand,
+                CreateChannelCommand, DeleteChannelCommand, PurgeChannelCommand, RestoreChannelCommand, UpdateChannelCommand,
             },
             Command,
         },
@@ -15,19 +15,31 @@ use crate::{
 };
 
 pub async fn create_channel(input: ChannelNewInput, context: &AppState) -> FieldResult<Channel> {
-    let mut service = context.service.lock().await;
-    let res = CreateChannelCommand { channel: input }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = CreateChannelCommand { channel: input }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn update_channel(input: ChannelUpdateInput, context: &AppState) -> FieldResult<Channel> {
-    let mut service = context.service.lock().await;
-    let res = UpdateChannelCommand { channel: input }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = UpdateChannelCommand { channel: input }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn delete_channel(id: DbUuid, context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
-    let res = DeleteChannelCommand { id }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = DeleteChannelCommand { id, deleted_by: context.user.or(service.current_user()) }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn restore_channel(id: DbUuid, context: &AppState) -> FieldResult<Channel> {
+    let service = context.service.write().await;
+    let res = RestoreChannelCommand { id }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn purge_channel(id: DbUuid, context: &AppState) -> FieldResult<i32> {
+    let service = context.service.write().await;
+    let res = PurgeChannelCommand { id }.exec(&service).await?;
     Ok(res)
 }
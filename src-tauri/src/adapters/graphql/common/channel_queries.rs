@@ -1,73 +1,55 @@
-use sea_query::{Expr, Query};
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
     core::{
-        models::common::channel_model::{Channel, Channels},
+        db::pagination::PageRequest,
+        models::common::channel_model::{Channel, ChannelFilter, ChannelOrderBy, Channels},
         types::db_uuid::DbUuid,
     },
     AppState,
 };
 
 pub async fn get_channel(id: DbUuid, context: &AppState) -> FieldResult<Channel> {
-    let service = context.service.lock().await;
-
-    let mut query_builder = Query::select();
-    let query = query_builder
-        .from(Channels::Table)
-        .columns([
-            Channels::Id,
-            Channels::Name,
-            Channels::Description,
-            Channels::IsActive,
-            Channels::CreatedAt,
-            Channels::UpdatedAt,
-        ])
-        .and_where(Expr::col(Channels::Id).eq(id.to_string()));
+    let service = &context.service;
 
+    let query = Channels::find_by_id(id);
     let channel = service.db_adapter.query_one::<Channel>(&query).await?;
 
     Ok(channel)
 }
 
 pub async fn get_channels(context: &AppState) -> FieldResult<Vec<Channel>> {
-    let service = context.service.lock().await;
-
-    let mut query_builder = Query::select();
-    let query = query_builder
-        .from(Channels::Table)
-        .columns([
-            Channels::Id,
-            Channels::Name,
-            Channels::Description,
-            Channels::IsActive,
-            Channels::CreatedAt,
-            Channels::UpdatedAt,
-        ]);
+    let service = &context.service;
 
+    let query = Channels::list(None, None, None, None);
     let channels_list = service.db_adapter.query_many::<Channel>(&query).await?;
 
     Ok(channels_list)
 }
 
 pub async fn get_active_channels(context: &AppState) -> FieldResult<Vec<Channel>> {
-    let service = context.service.lock().await;
-
-    let mut query_builder = Query::select();
-    let query = query_builder
-        .from(Channels::Table)
-        .columns([
-            Channels::Id,
-            Channels::Name,
-            Channels::Description,
-            Channels::IsActive,
-            Channels::CreatedAt,
-            Channels::UpdatedAt,
-        ])
-        .and_where(Expr::col(Channels::IsActive).eq(true));
+    let service = &context.service;
 
+    let filter = ChannelFilter { is_active: Some(true), ..Default::default() };
+    let query = Channels::list(Some(&filter), None, None, None);
     let channels_list = service.db_adapter.query_many::<Channel>(&query).await?;
 
     Ok(channels_list)
 }
+
+connection!(ChannelConnection, ChannelEdge, Channel);
+
+pub async fn channels_connection(
+    page: PageRequest,
+    filter: Option<ChannelFilter>,
+    order_by: Option<ChannelOrderBy>,
+    context: &AppState,
+) -> FieldResult<ChannelConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
@@ -0,0 +1,46 @@
+use chrono::NaiveDateTime;
+use juniper::graphql_object;
+
+use crate::{
+    core::{models::common::location_model::Location, types::db_uuid::DbUuid},
+    AppState,
+};
+
+#[graphql_object(context = AppState)]
+impl Location {
+    pub fn id(&self) -> DbUuid {
+        self.id
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    pub fn address(&self) -> Option<String> {
+        self.address.clone()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    pub fn created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+
+    pub fn deleted_at(&self) -> Option<NaiveDateTime> {
+        self.deleted_at
+    }
+
+    pub fn deleted_by(&self) -> Option<DbUuid> {
+        self.deleted_by
+    }
+}
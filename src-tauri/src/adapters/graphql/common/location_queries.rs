@@ -0,0 +1,36 @@
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
+    core::{
+        db::pagination::PageRequest,
+        models::common::location_model::{Location, LocationFilter, LocationOrderBy, Locations},
+        types::db_uuid::DbUuid,
+    },
+    AppState,
+};
+
+pub async fn get_location(id: DbUuid, context: &AppState) -> FieldResult<Location> {
+    let service = &context.service;
+
+    let query = Locations::find_by_id(id);
+    let location = service.db_adapter.query_one::<Location>(&query).await?;
+
+    Ok(location)
+}
+
+connection!(LocationConnection, LocationEdge, Location);
+
+pub async fn locations_connection(
+    page: PageRequest,
+    filter: Option<LocationFilter>,
+    order_by: Option<LocationOrderBy>,
+    context: &AppState,
+) -> FieldResult<LocationConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
@@ -1,9 +1,13 @@
+pub mod backup_mutations;
+pub mod backup_queries;
 pub mod brand_mutations;
 pub mod brand_object;
 pub mod brand_queries;
 pub mod channel_mutations;
 pub mod channel_object;
 pub mod channel_queries;
+pub mod location_object;
+pub mod location_queries;
 pub mod tax_group_mutations;
 pub mod tax_group_object;
 pub mod tax_group_queries;
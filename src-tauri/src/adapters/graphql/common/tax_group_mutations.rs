@@ -1,4 +1,4 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     core::{
@@ -16,20 +16,20 @@ use crate::{
 };
 
 pub async fn create_tax_group(input: TaxGroupNewInput, context: &AppState) -> FieldResult<TaxGroup> {
-    let mut service = context.service.lock().await;
-    let res = CreateTaxGroupCommand { tax_group: input }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = CreateTaxGroupCommand { tax_group: input }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn update_tax_group(input: TaxGroupUpdateInput, context: &AppState) -> FieldResult<TaxGroup> {
-    let mut service = context.service.lock().await;
-    let res = UpdateTaxGroupCommand { tax_group: input }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = UpdateTaxGroupCommand { tax_group: input }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn delete_tax_group(id: DbUuid, context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
-    let res = DeleteTaxGroupCommand { id }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = DeleteTaxGroupCommand { id }.exec(&service).await?;
     Ok(res)
 }
 
@@ -38,12 +38,12 @@ pub async fn assign_tax_to_group(
     tax_id: DbUuid,
     context: &AppState,
 ) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
     let res = AssignTaxToGroupCommand {
         tax_group_id,
         tax_id,
     }
-    .exec(&mut service).await?;
+    .exec(&service).await?;
     Ok(res)
 }
 
@@ -52,11 +52,11 @@ pub async fn remove_tax_from_group(
     tax_id: DbUuid,
     context: &AppState,
 ) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
     let res = RemoveTaxFromGroupCommand {
         tax_group_id,
         tax_id,
     }
-    .exec(&mut service).await?;
+    .exec(&service).await?;
     Ok(res)
 }
@@ -1,6 +1,7 @@
 use chrono::NaiveDateTime;
 use sea_query::{Expr, Query};
-use juniper::{graphql_object, FieldResult};
+use juniper::graphql_object;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     adapters::outgoing::database::DatabaseAdapter,
@@ -37,7 +38,7 @@ impl TaxGroup {
     }
 
     pub async fn taxes(&self, context: &AppState) -> FieldResult<Vec<Tax>> {
-        let service = context.service.lock().await;
+        let service = &context.service;
 
         // First, get the tax IDs for this tax group
         let mut tax_ids_query_builder = Query::select();
@@ -1,10 +1,14 @@
 use sea_query::{Alias, Expr, Order, Query, Func};
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
     core::{
-        models::common::tax_group_model::{TaxGroup, TaxGroups},
+        db::pagination::PageRequest,
+        models::common::tax_group_model::{TaxGroup, TaxGroupFilter, TaxGroupOrderBy, TaxGroups},
         types::db_uuid::DbUuid,
     },
     AppState,
@@ -15,7 +19,7 @@ pub async fn tax_groups(
     offset: Option<i32>,
     context: &AppState,
 ) -> FieldResult<Vec<TaxGroup>> {
-    let service = context.service.lock().await;
+    let service = &context.service;
 
     // Build the query with SeaQuery
     let mut query_builder = Query::select();
@@ -45,7 +49,7 @@ pub async fn tax_groups(
 }
 
 pub async fn tax_group(id: DbUuid, context: &AppState) -> FieldResult<TaxGroup> {
-    let service = context.service.lock().await;
+    let service = &context.service;
 
     // Build the query with SeaQuery
     let mut query_builder = Query::select();
@@ -67,7 +71,7 @@ pub async fn tax_group(id: DbUuid, context: &AppState) -> FieldResult<TaxGroup>
 }
 
 pub async fn total_tax_groups(context: &AppState) -> FieldResult<i32> {
-    let service = context.service.lock().await;
+    let service = &context.service;
 
     // Build the count query with SeaQuery
     let mut query_builder = Query::select();
@@ -80,3 +84,16 @@ pub async fn total_tax_groups(context: &AppState) -> FieldResult<i32> {
 
     Ok(result as i32)
 }
+
+connection!(TaxGroupConnection, TaxGroupEdge, TaxGroup);
+
+pub async fn tax_groups_connection(
+    page: PageRequest,
+    filter: Option<TaxGroupFilter>,
+    order_by: Option<TaxGroupOrderBy>,
+    context: &AppState,
+) -> FieldResult<TaxGroupConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
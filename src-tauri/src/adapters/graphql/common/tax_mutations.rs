@@ -1,4 +1,4 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     core::{
@@ -16,26 +16,26 @@ use crate::{
 };
 
 pub async fn create_tax(input: TaxNewInput, context: &AppState) -> FieldResult<Tax> {
-    let mut service = context.service.lock().await;
-    let res = CreateTaxCommand { tax: input }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = CreateTaxCommand { tax: input }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn update_tax(input: TaxUpdateInput, context: &AppState) -> FieldResult<Tax> {
-    let mut service = context.service.lock().await;
-    let res = UpdateTaxCommand { tax: input }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = UpdateTaxCommand { tax: input }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn delete_tax(id: DbUuid, context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
-    let res = DeleteTaxCommand { id }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = DeleteTaxCommand { id }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn assign_tax_to_item(input: ItemTaxNewInput, context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
-    let res = AssignTaxToItemCommand { item_tax: input }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = AssignTaxToItemCommand { item_tax: input }.exec(&service).await?;
     Ok(res)
 }
 
@@ -44,7 +44,7 @@ pub async fn remove_tax_from_item(
     tax_id: DbUuid,
     context: &AppState,
 ) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
-    let res = RemoveTaxFromItemCommand { item_id, tax_id }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = RemoveTaxFromItemCommand { item_id, tax_id }.exec(&service).await?;
     Ok(res)
 }
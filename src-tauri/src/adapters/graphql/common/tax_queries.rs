@@ -1,17 +1,21 @@
 use sea_query::{Alias, Expr, Order, Query, Func};
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
     core::{
-        models::common::tax_model::{Tax, Taxes},
+        db::pagination::PageRequest,
+        models::common::tax_model::{Tax, TaxFilter, TaxOrderBy, Taxes},
         types::db_uuid::DbUuid,
     },
     AppState,
 };
 
 pub async fn taxes(first: Option<i32>, offset: Option<i32>, context: &AppState) -> FieldResult<Vec<Tax>> {
-    let service = context.service.lock().await;
+    let service = &context.service;
 
     // Build the query with SeaQuery
     let mut query_builder = Query::select();
@@ -42,7 +46,7 @@ pub async fn taxes(first: Option<i32>, offset: Option<i32>, context: &AppState)
 }
 
 pub async fn tax(id: DbUuid, context: &AppState) -> FieldResult<Tax> {
-    let service = context.service.lock().await;
+    let service = &context.service;
 
     // Build the query with SeaQuery
     let mut query_builder = Query::select();
@@ -65,7 +69,7 @@ pub async fn tax(id: DbUuid, context: &AppState) -> FieldResult<Tax> {
 }
 
 pub async fn total_taxes(context: &AppState) -> FieldResult<i32> {
-    let service = context.service.lock().await;
+    let service = &context.service;
 
     // Build the count query with SeaQuery
     let mut query_builder = Query::select();
@@ -78,3 +82,16 @@ pub async fn total_taxes(context: &AppState) -> FieldResult<i32> {
 
     Ok(result as i32)
 }
+
+connection!(TaxConnection, TaxEdge, Tax);
+
+pub async fn taxes_connection(
+    page: PageRequest,
+    filter: Option<TaxFilter>,
+    order_by: Option<TaxOrderBy>,
+    context: &AppState,
+) -> FieldResult<TaxConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
@@ -0,0 +1,227 @@
+//! Relay style connections for list queries
+//!
+//! `connection!(SalesOrderConnection, SalesOrderEdge, SalesOrder)` declares the connection
+//! and edge objects for a model, and `list` runs the paged query behind them.
+
+use juniper::GraphQLObject;
+use crate::adapters::graphql::errors::FieldResult;
+use sea_query::Query;
+
+use crate::{
+    adapters::outgoing::database::FromRow,
+    core::db::{
+        pagination::{paginate, Filter, Node, Page, PageRequest, Sort},
+        soft_delete::restrict_deleted,
+    },
+    AppState,
+};
+
+#[derive(Debug, Clone, GraphQLObject)]
+pub struct PageInfo {
+    pub has_next_page: bool,
+    pub has_previous_page: bool,
+    pub start_cursor: Option<String>,
+    pub end_cursor: Option<String>,
+}
+
+impl<T> From<&Page<T>> for PageInfo {
+    fn from(page: &Page<T>) -> Self {
+        PageInfo {
+            has_next_page: page.has_next_page,
+            has_previous_page: page.has_previous_page,
+            start_cursor: page.edges.first().map(|(cursor, _)| cursor.clone()),
+            end_cursor: page.edges.last().map(|(cursor, _)| cursor.clone()),
+        }
+    }
+}
+
+/// Reads one page of `T`, narrowed by `filter` and sorted by `order_by` (creation order when
+/// not given). Soft deleted rows are left out unless `filter` asks for them.
+pub async fn list<T, F, S>(
+    context: &AppState,
+    filter: Option<F>,
+    order_by: Option<S>,
+    page: PageRequest,
+) -> FieldResult<Page<T>>
+where
+    T: FromRow<libsql::Row> + Node + Send,
+    F: Filter,
+    S: Sort<T::Iden>,
+{
+    let service = &context.service;
+
+    let mut query = Query::select();
+    query.from(T::table()).columns(T::columns());
+    if let Some(filter) = &filter {
+        filter.apply(&mut query);
+    }
+    restrict_deleted::<T>(&mut query, filter.map(|filter| filter.deleted_rows()).unwrap_or_default());
+
+    let sort = order_by.map(|order_by| order_by.sort());
+    let page = paginate(&service.db_adapter, query, sort, &page).await?;
+
+    Ok(page)
+}
+
+/// Declares `$connection` and `$edge` GraphQL objects for `$node`, built from a `Page<$node>`
+macro_rules! connection {
+    ($connection:ident, $edge:ident, $node:ty) => {
+        pub struct $edge {
+            cursor: String,
+            node: $node,
+        }
+
+        #[juniper::graphql_object(context = crate::AppState)]
+        impl $edge {
+            pub fn cursor(&self) -> &str {
+                &self.cursor
+            }
+
+            pub fn node(&self) -> &$node {
+                &self.node
+            }
+        }
+
+        pub struct $connection {
+            edges: Vec<$edge>,
+            page_info: crate::adapters::graphql::connection::PageInfo,
+            total_count: i64,
+        }
+
+        #[juniper::graphql_object(context = crate::AppState)]
+        impl $connection {
+            pub fn edges(&self) -> &[$edge] {
+                &self.edges
+            }
+
+            /// The nodes of `edges`, for clients that do not need the cursors
+            pub fn nodes(&self) -> Vec<&$node> {
+                self.edges.iter().map(|edge| &edge.node).collect()
+            }
+
+            pub fn page_info(&self) -> &crate::adapters::graphql::connection::PageInfo {
+                &self.page_info
+            }
+
+            /// Number of rows matching the filter, across all pages
+            pub fn total_count(&self) -> i32 {
+                self.total_count as i32
+            }
+        }
+
+        impl From<crate::core::db::pagination::Page<$node>> for $connection {
+            fn from(page: crate::core::db::pagination::Page<$node>) -> Self {
+                let page_info = crate::adapters::graphql::connection::PageInfo::from(&page);
+                $connection {
+                    edges: page
+                        .edges
+                        .into_iter()
+                        .map(|(cursor, node)| $edge { cursor, node })
+                        .collect(),
+                    page_info,
+                    total_count: page.total_count,
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use connection;
+
+#[cfg(test)]
+mod tests {
+    use juniper::{graphql_value, Variables};
+
+    use crate::{
+        adapters::graphql::{Mutation, Query, Schema, Subscription},
+        core::{
+            commands::{
+                common::location_commands::CreateLocationCommand, sales::customer_commands::CreateCustomerCommand,
+                tests::setup_service, Command,
+            },
+            models::{common::location_model::LocationNewInput, sales::customer_model::CustomerNewInput},
+        },
+        AppState,
+    };
+
+    #[tokio::test]
+    async fn test_customers_connection() {
+        let service = setup_service().await;
+        for name in ["Alice", "Bob", "Bobby"] {
+            let command = CreateCustomerCommand {
+                customer: CustomerNewInput {
+                    full_name: name.to_string(),
+                    email: None,
+                    phone: None,
+                    address: None,
+                    customer_group_id: None,
+                },
+            };
+            command.exec(&service).await.unwrap();
+        }
+        let context = AppState::new(service);
+
+        let query = r#"{
+            customersConnection(first: 1, filter: { fullNameContains: "bob" }, orderBy: { field: FULL_NAME, direction: DESC }) {
+                totalCount
+                pageInfo { hasNextPage hasPreviousPage }
+                nodes { fullName }
+            }
+        }"#;
+        let schema = Schema::new(Query, Mutation, Subscription);
+        let (result, errors) = juniper::execute(query, None, &schema, &Variables::new(), &context)
+            .await
+            .unwrap();
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            result,
+            graphql_value!({
+                "customersConnection": {
+                    "totalCount": 2,
+                    "pageInfo": { "hasNextPage": true, "hasPreviousPage": false },
+                    "nodes": [{ "fullName": "Bobby" }],
+                },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_locations_connection() {
+        let service = setup_service().await;
+        for (name, is_active) in [("North Store", true), ("North Depot", false), ("South Store", true)] {
+            let command = CreateLocationCommand {
+                location: LocationNewInput {
+                    name: name.to_string(),
+                    description: None,
+                    address: None,
+                    is_active: Some(is_active),
+                },
+            };
+            command.exec(&service).await.unwrap();
+        }
+        let context = AppState::new(service);
+
+        let query = r#"{
+            locationsConnection(filter: { nameContains: "north", isActive: true }) {
+                totalCount
+                nodes { name isActive }
+            }
+        }"#;
+        let schema = Schema::new(Query, Mutation, Subscription);
+        let (result, errors) = juniper::execute(query, None, &schema, &Variables::new(), &context)
+            .await
+            .unwrap();
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            result,
+            graphql_value!({
+                "locationsConnection": {
+                    "totalCount": 1,
+                    "nodes": [{ "name": "North Store", "isActive": true }],
+                },
+            })
+        );
+    }
+}
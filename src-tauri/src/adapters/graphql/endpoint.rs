@@ -0,0 +1,222 @@
+//! What the Tauri command and the HTTP server run every operation through
+//!
+//! A request names its document directly, by persisted query hash, or both. The document is
+//! checked against the depth and complexity limits before it runs, and every operation is
+//! logged once it finishes.
+
+use std::time::Instant;
+
+use juniper::{http::GraphQLResponse, ExecutionError, IntoFieldError, Value, Variables};
+use serde::Deserialize;
+
+use crate::{
+    adapters::graphql::{
+        limits::QueryLimits,
+        logging::OperationLog,
+        persisted_queries::{hash_document, PersistedQueries},
+        Mutation, Query, Schema, Subscription,
+    },
+    error::{Error, Result},
+    AppState,
+};
+
+/// A GraphQL request, with the persisted query extension used by Apollo clients
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQLRequest {
+    pub query: Option<String>,
+    pub operation_name: Option<String>,
+    pub variables: Option<Variables>,
+    pub extensions: Option<RequestExtensions>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestExtensions {
+    pub persisted_query: Option<PersistedQuery>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedQuery {
+    pub sha256_hash: String,
+}
+
+impl GraphQLRequest {
+    /// The hash of a persisted query, when the request names one
+    pub fn hash(&self) -> Option<&str> {
+        self.extensions
+            .as_ref()
+            .and_then(|extensions| extensions.persisted_query.as_ref())
+            .map(|persisted_query| persisted_query.sha256_hash.as_str())
+    }
+
+    pub fn variables(&self) -> Variables {
+        self.variables.clone().unwrap_or_default()
+    }
+}
+
+pub struct GraphQLEndpoint {
+    pub schema: Schema,
+    pub persisted_queries: PersistedQueries,
+    pub limits: QueryLimits,
+}
+
+impl Default for GraphQLEndpoint {
+    fn default() -> Self {
+        Self::new(PersistedQueries::default(), QueryLimits::default())
+    }
+}
+
+impl GraphQLEndpoint {
+    pub fn new(persisted_queries: PersistedQueries, limits: QueryLimits) -> Self {
+        Self { schema: Schema::new(Query, Mutation, Subscription), persisted_queries, limits }
+    }
+
+    /// Configured from the environment, see `PersistedQueries::from_env` and `QueryLimits::from_env`
+    pub fn from_env() -> Self {
+        let persisted_queries = PersistedQueries::from_env().expect("Failed to load persisted queries");
+        Self::new(persisted_queries, QueryLimits::from_env())
+    }
+
+    /// The document a request runs, once it is known to be within the limits
+    pub fn prepare(&self, request: &GraphQLRequest) -> Result<String> {
+        let document = self.persisted_queries.resolve(request.query.as_deref(), request.hash())?;
+        self.limits.check(&document, request.operation_name.as_deref(), &request.variables(), &self.schema)?;
+        Ok(document)
+    }
+
+    /// Rejects a batch whose operations together pass the complexity limit, so splitting a
+    /// query across a batch does not get around it. Every operation costs at least 1; each is
+    /// still checked on its own when it runs, which also reports the ones that fail here.
+    pub fn check_batch(&self, requests: &[GraphQLRequest]) -> Result<()> {
+        let mut total: u64 = 0;
+        for request in requests {
+            let cost = self
+                .persisted_queries
+                .resolve(request.query.as_deref(), request.hash())
+                .and_then(|document| {
+                    let operation_name = request.operation_name.as_deref();
+                    self.limits.complexity(&document, operation_name, &request.variables(), &self.schema)
+                })
+                .unwrap_or(0);
+            total = total.saturating_add(cost.max(1));
+        }
+
+        if total > self.limits.max_complexity {
+            return Err(Error::ValidationError(format!(
+                "Batch complexity {} exceeds the limit of {}",
+                total, self.limits.max_complexity
+            )));
+        }
+        Ok(())
+    }
+
+    pub async fn execute(
+        &self,
+        request: &GraphQLRequest,
+        context: &AppState,
+    ) -> Result<(Value, Vec<ExecutionError<juniper::DefaultScalarValue>>)> {
+        let started = Instant::now();
+        let variables = request.variables();
+        let operation_name = request.operation_name.as_deref();
+
+        let result = match self.prepare(request) {
+            Ok(document) => juniper::execute(&document, operation_name, &self.schema, &variables, context)
+                .await
+                .map_err(Error::from),
+            Err(err) => Err(err),
+        };
+
+        let document_hash = match (request.hash(), &request.query) {
+            (Some(hash), _) => hash.to_string(),
+            (None, Some(query)) => hash_document(query),
+            (None, None) => String::new(),
+        };
+        let variables = serde_json::to_value(&variables).unwrap_or_default();
+        let mut log = OperationLog::new(operation_name, &document_hash, variables, started.elapsed());
+        log.user = context.user;
+        log.errors = match &result {
+            Ok((_, errors)) => errors.iter().map(|error| error.error().message().to_string()).collect(),
+            Err(err) => vec![err.to_string()],
+        };
+        log.write();
+
+        result
+    }
+}
+
+/// The response sent for the result of `GraphQLEndpoint::execute`
+pub fn into_response(result: Result<(Value, Vec<ExecutionError<juniper::DefaultScalarValue>>)>) -> GraphQLResponse {
+    match result {
+        Ok(result) => GraphQLResponse::from_result(Ok(result)),
+        Err(Error::GraphQLError(error)) => GraphQLResponse::from_result(Err(error)),
+        Err(err) => GraphQLResponse::error(err.into_field_error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use juniper::graphql_value;
+
+    use super::*;
+    use crate::core::commands::tests::setup_service;
+
+    fn request(query: Option<&str>, hash: Option<&str>) -> GraphQLRequest {
+        GraphQLRequest {
+            query: query.map(str::to_string),
+            extensions: Some(RequestExtensions {
+                persisted_query: hash.map(|hash| PersistedQuery { sha256_hash: hash.to_string() }),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_persisted_query() {
+        let document = "{ usersConnection { totalCount } }";
+        let endpoint = GraphQLEndpoint::new(PersistedQueries::new([document.to_string()], true), QueryLimits::default());
+        let context = AppState::new(setup_service().await);
+
+        let (data, errors) = endpoint
+            .execute(&request(None, Some(&hash_document(document))), &context)
+            .await
+            .unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(data, graphql_value!({ "usersConnection": { "totalCount": 0 } }));
+
+        let err = endpoint.execute(&request(Some("{ usersConnection { totalCount } } "), None), &context).await;
+        assert_eq!(err.unwrap_err().to_string(), "Only persisted queries are allowed");
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_deep_queries() {
+        let limits = QueryLimits { max_depth: 2, max_complexity: 1000 };
+        let endpoint = GraphQLEndpoint::new(PersistedQueries::default(), limits);
+        let context = AppState::new(setup_service().await);
+
+        let result = endpoint
+            .execute(&request(Some("{ itemsConnection { nodes { category { name } } } }"), None), &context)
+            .await;
+
+        let response = serde_json::to_value(into_response(result)).unwrap();
+        assert_eq!(response["errors"][0]["message"], "Query depth exceeds the limit of 2");
+    }
+
+    #[test]
+    fn test_deserialize_apollo_request() {
+        let request: GraphQLRequest = serde_json::from_str(
+            r#"{
+                "operationName": "Users",
+                "variables": { "first": 5 },
+                "extensions": { "persistedQuery": { "version": 1, "sha256Hash": "abc" } }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(request.query, None);
+        assert_eq!(request.operation_name.as_deref(), Some("Users"));
+        assert_eq!(request.hash(), Some("abc"));
+        assert_eq!(request.variables().get("first").and_then(|first| first.as_int_value()), Some(5));
+    }
+}
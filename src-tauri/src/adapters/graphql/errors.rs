@@ -0,0 +1,122 @@
+//! How errors reach GraphQL clients
+//!
+//! Resolvers return `crate::error::Error` rather than a `juniper::FieldError`, so every error
+//! carries a stable `code` in its extensions along with a message that is safe to show:
+//!
+//! ```json
+//! {
+//!   "message": "name must be unique",
+//!   "extensions": { "code": "UNIQUE_VIOLATION", "table": "items", "fields": ["name"] }
+//! }
+//! ```
+//!
+//! A `CONFLICT` from an update based on a stale `version` also carries the row as it is now in
+//! `current`, for the client to merge with its changes.
+
+use juniper::{FieldError, IntoFieldError, Object, ScalarValue, Value};
+
+use crate::{adapters::graphql::logging, error::Error};
+
+/// What resolvers return
+pub type FieldResult<T> = std::result::Result<T, Error>;
+
+impl<S: ScalarValue> IntoFieldError<S> for Error {
+    fn into_field_error(self) -> FieldError<S> {
+        if self.code() == "INTERNAL" {
+            logging::write_internal_error(&self);
+        }
+
+        let mut extensions = Object::with_capacity(3);
+        extensions.add_field("code", Value::scalar(self.code().to_string()));
+        if let Some(constraint) = self.constraint() {
+            extensions.add_field("table", Value::scalar(constraint.table.clone()));
+            let fields = constraint.columns.iter().map(|column| Value::scalar(to_camel_case(column)));
+            extensions.add_field("fields", Value::list(fields.collect()));
+        }
+        if let Some(conflict) = self.conflict() {
+            extensions.add_field("table", Value::scalar(conflict.table.clone()));
+            extensions.add_field("current", to_value(&conflict.current));
+        }
+
+        FieldError::new(self.message(), Value::Object(extensions))
+    }
+}
+
+fn to_value<S: ScalarValue>(json: &serde_json::Value) -> Value<S> {
+    match json {
+        serde_json::Value::Null => Value::null(),
+        serde_json::Value::Bool(b) => Value::scalar(*b),
+        serde_json::Value::Number(n) => match n.as_i64().and_then(|n| i32::try_from(n).ok()) {
+            Some(n) => Value::scalar(n),
+            None => Value::scalar(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => Value::scalar(s.clone()),
+        serde_json::Value::Array(values) => Value::list(values.iter().map(to_value).collect()),
+        serde_json::Value::Object(fields) => {
+            let mut object = Object::with_capacity(fields.len());
+            for (name, value) in fields {
+                object.add_field(name.as_str(), to_value(value));
+            }
+            Value::Object(object)
+        }
+    }
+}
+
+// Columns are named as the GraphQL fields they back, `category_id` as `categoryId`
+fn to_camel_case(column: &str) -> String {
+    let mut words = column.split('_');
+    let mut name = words.next().unwrap_or_default().to_string();
+    for word in words {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            name.extend(first.to_uppercase());
+            name.push_str(chars.as_str());
+        }
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use juniper::{graphql_value, DefaultScalarValue};
+
+    use super::*;
+    use crate::error::{Conflict, Constraint};
+
+    #[test]
+    fn test_constraint_errors_name_their_fields() {
+        let err = Error::ForeignKeyConstraintError(Constraint::new("items", "category_id"));
+        let field_error: FieldError<DefaultScalarValue> = err.into_field_error();
+
+        assert_eq!(field_error.message(), "A related record does not exist or is still in use");
+        assert_eq!(
+            field_error.extensions(),
+            &graphql_value!({ "code": "FOREIGN_KEY_VIOLATION", "table": "items", "fields": ["categoryId"] })
+        );
+    }
+
+    #[test]
+    fn test_conflicts_carry_the_current_row() {
+        let current = serde_json::json!({ "id": "1", "name": "Cola", "price": "12.50", "version": 3 });
+        let err = Error::ConflictError(Conflict { table: "items".to_string(), current });
+        let field_error: FieldError<DefaultScalarValue> = err.into_field_error();
+
+        assert_eq!(
+            field_error.extensions(),
+            &graphql_value!({
+                "code": "CONFLICT",
+                "table": "items",
+                "current": { "id": "1", "name": "Cola", "price": "12.50", "version": 3 },
+            })
+        );
+    }
+
+    #[test]
+    fn test_internal_errors_hide_details() {
+        let err = Error::DatabaseError("Failed to execute query: no such column: pin".to_string());
+        let field_error: FieldError<DefaultScalarValue> = err.into_field_error();
+
+        assert_eq!(field_error.message(), "Internal error");
+        assert_eq!(field_error.extensions(), &graphql_value!({ "code": "INTERNAL" }));
+    }
+}
@@ -0,0 +1,25 @@
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    core::{
+        commands::{
+            finance::account_mapping_commands::{DeleteAccountMappingCommand, SetAccountMappingCommand},
+            Command,
+        },
+        models::finance::account_mapping_model::{AccountMapping, AccountMappingInput},
+        types::db_uuid::DbUuid,
+    },
+    AppState,
+};
+
+pub async fn set_account_mapping(mapping: AccountMappingInput, context: &AppState) -> FieldResult<AccountMapping> {
+    let service = context.service.write().await;
+    let res = SetAccountMappingCommand { mapping }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn delete_account_mapping(id: DbUuid, context: &AppState) -> FieldResult<i32> {
+    let service = context.service.write().await;
+    let res = DeleteAccountMappingCommand { id }.exec(&service).await?;
+    Ok(res)
+}
@@ -0,0 +1,15 @@
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    core::{
+        commands::{finance::account_mapping_commands::ListAccountMappingsCommand, Command},
+        models::finance::account_mapping_model::AccountMapping,
+    },
+    AppState,
+};
+
+pub async fn account_mappings(context: &AppState) -> FieldResult<Vec<AccountMapping>> {
+    let service = &context.service;
+    let mappings = ListAccountMappingsCommand.exec(service).await?;
+    Ok(mappings)
+}
@@ -1,10 +1,10 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     core::{
         commands::{
             finance::cost_center_commands::{
-                CreateCostCenterCommand, DeleteCostCenterCommand, UpdateCostCenterCommand,
+                CreateCostCenterCommand, DeleteCostCenterCommand, PurgeCostCenterCommand, RestoreCostCenterCommand, UpdateCostCenterCommand,
             },
             Command,
         },
@@ -23,7 +23,7 @@ pub async fn create_cost_center(
     state: Option<CostCenterState>,
     context: &AppState,
 ) -> FieldResult<CostCenter> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
     let command = CreateCostCenterCommand {
         cost_center: CostCenterNewInput {
             name,
@@ -32,7 +32,7 @@ pub async fn create_cost_center(
             state,
         },
     };
-    let result = command.exec(&mut service).await?;
+    let result = command.exec(&service).await?;
     Ok(result)
 }
 
@@ -44,7 +44,7 @@ pub async fn update_cost_center(
     state: Option<CostCenterState>,
     context: &AppState,
 ) -> FieldResult<CostCenter> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
     let command = UpdateCostCenterCommand {
         cost_center: CostCenterUpdateInput {
             id,
@@ -54,14 +54,27 @@ pub async fn update_cost_center(
             state,
         },
     };
-    let result = command.exec(&mut service).await?;
+    let result = command.exec(&service).await?;
     Ok(result)
 }
 
 pub async fn delete_cost_center(id: DbUuid, context: &AppState) -> FieldResult<DbUuid> {
-    let mut service = context.service.lock().await;
-    let command = DeleteCostCenterCommand { id };
-    let _ = command.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let command = DeleteCostCenterCommand { id, deleted_by: context.user.or(service.current_user()) };
+    let _ = command.exec(&service).await?;
     // Return the id of the deleted cost center
     Ok(id)
 }
+
+pub async fn restore_cost_center(id: DbUuid, context: &AppState) -> FieldResult<CostCenter> {
+    let service = context.service.write().await;
+    let res = RestoreCostCenterCommand { id }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn purge_cost_center(id: DbUuid, context: &AppState) -> FieldResult<DbUuid> {
+    let service = context.service.write().await;
+    let command = PurgeCostCenterCommand { id };
+    let _ = command.exec(&service).await?;
+    Ok(id)
+}
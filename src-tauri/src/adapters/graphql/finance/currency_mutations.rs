@@ -0,0 +1,59 @@
+use crate::{
+    core::{
+        commands::{
+            finance::{
+                currency_commands::{
+                    CreateCurrencyCommand, DeleteCurrencyCommand, SetBaseCurrencyCommand, UpdateCurrencyCommand,
+                },
+                exchange_rate_commands::{CreateExchangeRateCommand, DeleteExchangeRateCommand},
+            },
+            Command,
+        },
+        models::finance::{
+            currency_model::{Currency, CurrencyNewInput, CurrencyUpdateInput},
+            exchange_rate_model::{ExchangeRate, ExchangeRateNewInput},
+        },
+        types::db_uuid::DbUuid,
+    },
+    AppState,
+};
+use crate::adapters::graphql::errors::FieldResult;
+
+pub async fn create_currency(currency: CurrencyNewInput, context: &AppState) -> FieldResult<Currency> {
+    let service = context.service.write().await;
+    let result = CreateCurrencyCommand { currency }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn update_currency(currency: CurrencyUpdateInput, context: &AppState) -> FieldResult<Currency> {
+    let service = context.service.write().await;
+    let result = UpdateCurrencyCommand { currency }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn delete_currency(id: DbUuid, context: &AppState) -> FieldResult<bool> {
+    let service = context.service.write().await;
+    let result = DeleteCurrencyCommand { id }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn set_base_currency(id: DbUuid, context: &AppState) -> FieldResult<Currency> {
+    let service = context.service.write().await;
+    let result = SetBaseCurrencyCommand { id }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn create_exchange_rate(
+    exchange_rate: ExchangeRateNewInput,
+    context: &AppState,
+) -> FieldResult<ExchangeRate> {
+    let service = context.service.write().await;
+    let result = CreateExchangeRateCommand { exchange_rate }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn delete_exchange_rate(id: DbUuid, context: &AppState) -> FieldResult<bool> {
+    let service = context.service.write().await;
+    let result = DeleteExchangeRateCommand { id }.exec(&service).await?;
+    Ok(result)
+}
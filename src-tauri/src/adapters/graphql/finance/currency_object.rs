@@ -0,0 +1,76 @@
+use chrono::NaiveDateTime;
+use juniper::graphql_object;
+
+use crate::{
+    core::{
+        models::finance::{currency_model::Currency, exchange_rate_model::ExchangeRate},
+        types::{db_uuid::DbUuid, rate::Rate},
+    },
+    AppState,
+};
+
+#[graphql_object(context = AppState)]
+impl Currency {
+    pub fn id(&self) -> DbUuid {
+        self.id
+    }
+
+    pub fn code(&self) -> String {
+        self.code.clone()
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn symbol(&self) -> Option<String> {
+        self.symbol.clone()
+    }
+
+    pub fn minor_units(&self) -> i32 {
+        self.minor_units
+    }
+
+    pub fn is_base(&self) -> bool {
+        self.is_base
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    pub fn created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+}
+
+#[graphql_object(context = AppState)]
+impl ExchangeRate {
+    pub fn id(&self) -> DbUuid {
+        self.id
+    }
+
+    pub fn currency_code(&self) -> String {
+        self.currency_code.clone()
+    }
+
+    pub fn rate(&self) -> Rate {
+        self.rate
+    }
+
+    pub fn effective_from(&self) -> NaiveDateTime {
+        self.effective_from
+    }
+
+    pub fn created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+}
@@ -0,0 +1,103 @@
+use chrono::Utc;
+use sea_query::{Expr, Order, Query};
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
+    core::{
+        commands::finance::exchange_rate_commands::rate_at,
+        db::pagination::PageRequest,
+        models::finance::{
+            currency_model::{Currencies, Currency, CurrencyFilter, CurrencyOrderBy},
+            exchange_rate_model::{ExchangeRate, ExchangeRateFilter, ExchangeRateOrderBy, ExchangeRates},
+        },
+        types::rate::Rate,
+    },
+    AppState,
+};
+
+pub async fn currencies(active_only: Option<bool>, context: &AppState) -> FieldResult<Vec<Currency>> {
+    let service = &context.service;
+
+    // Build the query with SeaQuery
+    let mut query_builder = Query::select();
+    let stmt = query_builder
+        .from(Currencies::Table)
+        .columns(Currencies::all_columns())
+        .order_by(Currencies::IsBase, Order::Desc)
+        .order_by(Currencies::Code, Order::Asc);
+
+    if active_only.unwrap_or(false) {
+        stmt.and_where(Expr::col(Currencies::IsActive).eq(true.to_string()));
+    }
+
+    // Execute the query
+    let result = service.db_adapter.query_many::<Currency>(&stmt).await?;
+
+    Ok(result)
+}
+
+pub async fn exchange_rates(
+    currency_code: String,
+    first: Option<i32>,
+    offset: Option<i32>,
+    context: &AppState,
+) -> FieldResult<Vec<ExchangeRate>> {
+    let service = &context.service;
+
+    // Most recent rates first
+    let mut query_builder = Query::select();
+    let stmt = query_builder
+        .from(ExchangeRates::Table)
+        .columns(ExchangeRates::all_columns())
+        .and_where(Expr::col(ExchangeRates::CurrencyCode).eq(currency_code.to_uppercase()))
+        .order_by(ExchangeRates::EffectiveFrom, Order::Desc);
+
+    // Apply pagination if parameters are provided
+    if let Some(limit) = first {
+        stmt.limit(limit as u64);
+    }
+    if let Some(off) = offset {
+        stmt.offset(off as u64);
+    }
+
+    // Execute the query
+    let result = service.db_adapter.query_many::<ExchangeRate>(&stmt).await?;
+
+    Ok(result)
+}
+
+pub async fn current_exchange_rate(currency_code: String, context: &AppState) -> FieldResult<Rate> {
+    let service = &context.service;
+    let result = rate_at(&service, &currency_code, Utc::now().naive_utc()).await?;
+    Ok(result)
+}
+
+connection!(ExchangeRateConnection, ExchangeRateEdge, ExchangeRate);
+
+pub async fn exchange_rates_connection(
+    page: PageRequest,
+    filter: Option<ExchangeRateFilter>,
+    order_by: Option<ExchangeRateOrderBy>,
+    context: &AppState,
+) -> FieldResult<ExchangeRateConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
+
+connection!(CurrencyConnection, CurrencyEdge, Currency);
+
+pub async fn currencies_connection(
+    page: PageRequest,
+    filter: Option<CurrencyFilter>,
+    order_by: Option<CurrencyOrderBy>,
+    context: &AppState,
+) -> FieldResult<CurrencyConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
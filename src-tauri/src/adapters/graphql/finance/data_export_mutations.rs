@@ -0,0 +1,18 @@
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    core::{
+        commands::{finance::data_export_commands::ExportDataCommand, Command},
+        models::finance::data_export_model::{DataExport, DataExportInput},
+        utils::export::ExportConfig,
+    },
+    AppState,
+};
+
+pub async fn export_data(export: DataExportInput, context: &AppState) -> FieldResult<DataExport> {
+    // Only reads, so other writes are not held up while the file is written
+    let service = &context.service;
+    let config = ExportConfig::load(&service.conn_path);
+    let res = ExportDataCommand { export, config }.exec(service).await?;
+    Ok(res)
+}
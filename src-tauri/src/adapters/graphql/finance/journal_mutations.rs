@@ -0,0 +1,18 @@
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    core::{
+        commands::{finance::journal_commands::ExportJournalCommand, Command},
+        models::finance::{data_export_model::DataExport, journal_model::JournalExportInput},
+        utils::export::ExportConfig,
+    },
+    AppState,
+};
+
+pub async fn export_journal(export: JournalExportInput, context: &AppState) -> FieldResult<DataExport> {
+    // Only reads, like `export_data`
+    let service = &context.service;
+    let config = ExportConfig::load(&service.conn_path);
+    let res = ExportJournalCommand { export, config }.exec(service).await?;
+    Ok(res)
+}
@@ -0,0 +1,26 @@
+use chrono::NaiveDateTime;
+
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    core::{
+        commands::{finance::journal_commands::GenerateJournalCommand, Command},
+        models::finance::journal_model::JournalEntry,
+        types::db_uuid::DbUuid,
+    },
+    AppState,
+};
+
+pub async fn journal(
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+    cost_center_id: Option<DbUuid>,
+    context: &AppState,
+) -> FieldResult<Vec<JournalEntry>> {
+    let service = &context.service;
+
+    let command = GenerateJournalCommand { from, to, cost_center_id };
+    let entries = command.exec(service).await?;
+
+    Ok(entries)
+}
@@ -1,6 +1,14 @@
+pub mod account_mapping_mutations;
+pub mod account_mapping_queries;
 pub mod cost_center_mutations;
 pub mod cost_center_object;
 pub mod cost_center_queries;
+pub mod currency_mutations;
+pub mod currency_object;
+pub mod currency_queries;
+pub mod data_export_mutations;
+pub mod journal_mutations;
+pub mod journal_queries;
 pub mod payment_method_mutations;
 pub mod payment_method_object;
 pub mod payment_method_queries;
@@ -1,10 +1,10 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     core::{
         commands::{
             finance::payment_method_commands::{
-                CreatePaymentMethodCommand, DeletePaymentMethodCommand, UpdatePaymentMethodCommand,
+                CreatePaymentMethodCommand, DeletePaymentMethodCommand, PurgePaymentMethodCommand, RestorePaymentMethodCommand, UpdatePaymentMethodCommand,
             },
             Command,
         },
@@ -23,7 +23,7 @@ pub async fn create_payment_method(
     state: Option<PaymentMethodState>,
     context: &AppState,
 ) -> FieldResult<PaymentMethod> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
     let command = CreatePaymentMethodCommand {
         payment_method: PaymentMethodNewInput {
             name,
@@ -32,7 +32,7 @@ pub async fn create_payment_method(
             state,
         },
     };
-    let result = command.exec(&mut service).await?;
+    let result = command.exec(&service).await?;
     Ok(result)
 }
 
@@ -44,7 +44,7 @@ pub async fn update_payment_method(
     state: Option<PaymentMethodState>,
     context: &AppState,
 ) -> FieldResult<PaymentMethod> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
     let command = UpdatePaymentMethodCommand {
         payment_method: PaymentMethodUpdateInput {
             id,
@@ -54,14 +54,27 @@ pub async fn update_payment_method(
             state,
         },
     };
-    let result = command.exec(&mut service).await?;
+    let result = command.exec(&service).await?;
     Ok(result)
 }
 
 pub async fn delete_payment_method(id: DbUuid, context: &AppState) -> FieldResult<DbUuid> {
-    let mut service = context.service.lock().await;
-    let command = DeletePaymentMethodCommand { id };
-    let _ = command.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let command = DeletePaymentMethodCommand { id, deleted_by: context.user.or(service.current_user()) };
+    let _ = command.exec(&service).await?;
     // Return the id of the deleted payment method
     Ok(id)
 }
+
+pub async fn restore_payment_method(id: DbUuid, context: &AppState) -> FieldResult<PaymentMethod> {
+    let service = context.service.write().await;
+    let res = RestorePaymentMethodCommand { id }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn purge_payment_method(id: DbUuid, context: &AppState) -> FieldResult<DbUuid> {
+    let service = context.service.write().await;
+    let command = PurgePaymentMethodCommand { id };
+    let _ = command.exec(&service).await?;
+    Ok(id)
+}
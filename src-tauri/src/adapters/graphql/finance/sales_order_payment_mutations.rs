@@ -1,4 +1,4 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     core::{
@@ -21,9 +21,9 @@ pub async fn create_sales_order_payment(
     context: &AppState,
     payment: SalesOrderPaymentNewInput,
 ) -> FieldResult<SalesOrderPayment> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
     let cmd = CreateSalesOrderPaymentCommand { payment };
-    let result = cmd.exec(&mut service).await?;
+    let result = cmd.exec(&service).await?;
     Ok(result)
 }
 
@@ -31,15 +31,15 @@ pub async fn update_sales_order_payment(
     context: &AppState,
     payment: SalesOrderPaymentUpdateInput,
 ) -> FieldResult<SalesOrderPayment> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
     let cmd = UpdateSalesOrderPaymentCommand { payment };
-    let result = cmd.exec(&mut service).await?;
+    let result = cmd.exec(&service).await?;
     Ok(result)
 }
 
 pub async fn void_sales_order_payment(context: &AppState, id: DbUuid) -> FieldResult<SalesOrderPayment> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
     let cmd = VoidSalesOrderPaymentCommand { id };
-    let result = cmd.exec(&mut service).await?;
+    let result = cmd.exec(&service).await?;
     Ok(result)
 }
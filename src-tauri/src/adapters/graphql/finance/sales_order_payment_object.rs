@@ -1,8 +1,10 @@
 use juniper::graphql_object;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{core::{
+    commands::finance::currency_commands::find_currency_by_code,
     models::finance::sales_order_payment_model::{SalesOrderPayment, SalesOrderPaymentState},
-    types::{db_uuid::DbUuid, money::Money},
+    types::{db_uuid::DbUuid, money::Money, rate::Rate},
 }, AppState};
 
 #[graphql_object(context = AppState, description = "Sales Order Payment")]
@@ -38,4 +40,20 @@ impl SalesOrderPayment {
     fn state(&self) -> SalesOrderPaymentState {
         self.state
     }
+
+    fn currency_code(&self) -> &str {
+        &self.currency_code
+    }
+
+    /// The tendered amount, formatted in the precision of its currency, even once that
+    /// currency is deactivated
+    async fn original_amount(&self, context: &AppState) -> FieldResult<String> {
+        let service = &context.service;
+        let currency = find_currency_by_code(service, &self.currency_code).await?;
+        Ok(self.original_amount.to_string_with_minor_units(currency.precision()))
+    }
+
+    fn exchange_rate(&self) -> Rate {
+        self.exchange_rate
+    }
 }
@@ -1,9 +1,13 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
+    adapters::graphql::connection::{connection, list},
     core::{
         commands::{finance::sales_order_payment_commands::GetSalesOrderPaymentsCommand, Command},
-        models::finance::sales_order_payment_model::SalesOrderPayment,
+        db::pagination::PageRequest,
+        models::finance::sales_order_payment_model::{
+            SalesOrderPayment, SalesOrderPaymentFilter, SalesOrderPaymentOrderBy,
+        },
         types::db_uuid::DbUuid,
     },
     AppState,
@@ -13,10 +17,23 @@ pub async fn sales_order_payments(
     context: &AppState,
     order_id: DbUuid,
 ) -> FieldResult<Vec<SalesOrderPayment>> {
-    let mut service = context.service.lock().await;
+    let service = &context.service;
 
     let cmd = GetSalesOrderPaymentsCommand { order_id };
-    let result = cmd.exec(&mut service).await?;
+    let result = cmd.exec(&service).await?;
 
     Ok(result)
 }
+
+connection!(SalesOrderPaymentConnection, SalesOrderPaymentEdge, SalesOrderPayment);
+
+pub async fn sales_order_payments_connection(
+    page: PageRequest,
+    filter: Option<SalesOrderPaymentFilter>,
+    order_by: Option<SalesOrderPaymentOrderBy>,
+    context: &AppState,
+) -> FieldResult<SalesOrderPaymentConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
@@ -0,0 +1,267 @@
+//! Depth and complexity limits, checked before a document is executed
+//!
+//! The depth of a document is its deepest chain of nested fields. Its complexity counts every
+//! field once, multiplied by the `first` or `last` argument of the connection or list it is
+//! selected from, so `itemsConnection(first: 100) { nodes { id name } }` costs 1 + 100 * 3.
+//! A connection without either is charged the default page size it returns.
+//! Introspection fields are left out of both, as they never reach the database.
+
+use std::collections::HashMap;
+
+use juniper::{
+    parser::parse_document_source, Definition, InputValue, Selection, Spanning, Variables,
+};
+
+use crate::{
+    adapters::graphql::Schema,
+    core::db::pagination::DEFAULT_PAGE_SIZE,
+    error::{Error, Result},
+};
+
+/// Deepest nesting of fields allowed unless `GRAPHQL_MAX_DEPTH` is set
+const DEFAULT_MAX_DEPTH: usize = 10;
+
+/// Highest complexity allowed unless `GRAPHQL_MAX_COMPLEXITY` is set
+const DEFAULT_MAX_COMPLEXITY: u64 = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+pub struct QueryLimits {
+    pub max_depth: usize,
+    pub max_complexity: u64,
+}
+
+impl Default for QueryLimits {
+    fn default() -> Self {
+        Self { max_depth: DEFAULT_MAX_DEPTH, max_complexity: DEFAULT_MAX_COMPLEXITY }
+    }
+}
+
+impl QueryLimits {
+    /// Reads `GRAPHQL_MAX_DEPTH` and `GRAPHQL_MAX_COMPLEXITY`, falling back to the defaults
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|value| value.parse().ok())
+        }
+
+        let defaults = Self::default();
+        Self {
+            max_depth: var("GRAPHQL_MAX_DEPTH").unwrap_or(defaults.max_depth),
+            max_complexity: var("GRAPHQL_MAX_COMPLEXITY").unwrap_or(defaults.max_complexity),
+        }
+    }
+
+    /// Rejects the operation in `document` when it is too deep or too complex. Documents that
+    /// don't parse are left for execution to report.
+    pub fn check(
+        &self,
+        document: &str,
+        operation_name: Option<&str>,
+        variables: &Variables,
+        schema: &Schema,
+    ) -> Result<()> {
+        let cost = self.complexity(document, operation_name, variables, schema)?;
+        if cost > self.max_complexity {
+            return Err(Error::ValidationError(format!(
+                "Query complexity {} exceeds the limit of {}",
+                cost, self.max_complexity
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// The complexity of the operation in `document`, failing when it is too deep. Counting
+    /// stops once the limit is passed. Documents that don't parse cost nothing.
+    pub fn complexity(
+        &self,
+        document: &str,
+        operation_name: Option<&str>,
+        variables: &Variables,
+        schema: &Schema,
+    ) -> Result<u64> {
+        let Ok(definitions) = parse_document_source(document, &schema.schema) else {
+            return Ok(0);
+        };
+
+        let mut fragments = HashMap::new();
+        let mut operations = Vec::new();
+        for definition in &definitions {
+            match definition {
+                Definition::Fragment(fragment) => {
+                    fragments.insert(fragment.item.name.item, &fragment.item.selection_set);
+                }
+                Definition::Operation(operation) => operations.push(&operation.item),
+            }
+        }
+
+        let operation = match operation_name {
+            Some(name) => operations
+                .into_iter()
+                .find(|operation| operation.name.as_ref().is_some_and(|n| n.item == name)),
+            None => operations.into_iter().next(),
+        };
+        let Some(operation) = operation else {
+            return Ok(0);
+        };
+
+        let mut analysis = Analysis { limits: self, fragments: &fragments, variables, visiting: Vec::new() };
+        analysis.cost(&operation.selection_set, 1)
+    }
+}
+
+struct Analysis<'a, 'd> {
+    limits: &'a QueryLimits,
+    fragments: &'a HashMap<&'d str, &'a Vec<Selection<'d>>>,
+    variables: &'a Variables,
+    /// Fragments being expanded, so a cycle is not followed forever
+    visiting: Vec<&'d str>,
+}
+
+impl<'d> Analysis<'_, 'd> {
+    // The cost of a selection set whose fields are at `depth`
+    fn cost(&mut self, selections: &[Selection<'d>], depth: usize) -> Result<u64> {
+        let mut total: u64 = 0;
+
+        for selection in selections {
+            let cost = match selection {
+                Selection::Field(field) => {
+                    let field = &field.item;
+                    if field.name.item.starts_with("__") {
+                        continue;
+                    }
+                    if depth > self.limits.max_depth {
+                        return Err(Error::ValidationError(format!(
+                            "Query depth exceeds the limit of {}",
+                            self.limits.max_depth
+                        )));
+                    }
+
+                    let children = match &field.selection_set {
+                        Some(selections) => self.cost(selections, depth + 1)?,
+                        None => 0,
+                    };
+                    // A connection given neither `first` nor `last` returns a page of the default size
+                    let default = if field.name.item.ends_with("Connection") { DEFAULT_PAGE_SIZE as u64 } else { 1 };
+                    let multiplier = field
+                        .arguments
+                        .as_ref()
+                        .and_then(|arguments| {
+                            arguments.item.items.iter().find_map(|(name, value)| {
+                                matches!(name.item, "first" | "last").then(|| self.int_value(value))
+                            })
+                        })
+                        .flatten()
+                        .unwrap_or(default);
+
+                    children.saturating_mul(multiplier).saturating_add(1)
+                }
+                Selection::InlineFragment(fragment) => {
+                    self.cost(&fragment.item.selection_set, depth)?
+                }
+                Selection::FragmentSpread(spread) => {
+                    let name = spread.item.name.item;
+                    let Some(selections) = self.fragments.get(name).copied() else {
+                        continue;
+                    };
+                    if self.visiting.contains(&name) {
+                        continue;
+                    }
+                    self.visiting.push(name);
+                    let cost = self.cost(selections, depth)?;
+                    self.visiting.pop();
+                    cost
+                }
+            };
+
+            total = total.saturating_add(cost);
+            if total > self.limits.max_complexity {
+                return Ok(total);
+            }
+        }
+
+        Ok(total)
+    }
+
+    // A page size given inline or through a variable
+    fn int_value(&self, value: &Spanning<InputValue>) -> Option<u64> {
+        let value = match &value.item {
+            InputValue::Variable(name) => self.variables.get(name)?,
+            value => value,
+        };
+        value.as_int_value().and_then(|value| u64::try_from(value).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::graphql::{Mutation, Query, Subscription};
+
+    fn check(limits: QueryLimits, document: &str, variables: &Variables) -> Result<()> {
+        limits.check(document, None, variables, &Schema::new(Query, Mutation, Subscription))
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let limits = QueryLimits { max_depth: 3, max_complexity: 1000 };
+        let shallow = "{ itemsConnection { nodes { name } } }";
+        let deep = "{ itemsConnection { nodes { category { name } } } }";
+
+        assert!(check(limits, shallow, &Variables::new()).is_ok());
+        let err = check(limits, deep, &Variables::new()).unwrap_err();
+        assert_eq!(err.to_string(), "Query depth exceeds the limit of 3");
+    }
+
+    #[test]
+    fn test_fragments_count_towards_depth() {
+        let limits = QueryLimits { max_depth: 3, max_complexity: 1000 };
+        let document = "
+            { itemsConnection { nodes { ...ItemFields } } }
+            fragment ItemFields on Item { category { name } }
+        ";
+
+        assert!(check(limits, document, &Variables::new()).is_err());
+    }
+
+    #[test]
+    fn test_complexity_multiplies_page_sizes() {
+        let limits = QueryLimits { max_depth: 10, max_complexity: 500 };
+        let document = "query($first: Int) { itemsConnection(first: $first) { nodes { id name } } }";
+
+        let mut variables = Variables::new();
+        variables.insert("first".to_string(), InputValue::scalar(100));
+        assert!(check(limits, document, &variables).is_ok());
+
+        variables.insert("first".to_string(), InputValue::scalar(1000));
+        let err = check(limits, document, &variables).unwrap_err();
+        assert_eq!(err.to_string(), "Query complexity 3001 exceeds the limit of 500");
+    }
+
+    #[test]
+    fn test_complexity_charges_the_default_page_size() {
+        let limits = QueryLimits { max_depth: 10, max_complexity: 100 };
+        let document = "{ itemsConnection { nodes { id name } } }";
+
+        let err = check(limits, document, &Variables::new()).unwrap_err();
+        assert_eq!(err.to_string(), format!("Query complexity {} exceeds the limit of 100", 1 + DEFAULT_PAGE_SIZE * 3));
+    }
+
+    #[test]
+    fn test_introspection_is_not_limited() {
+        let limits = QueryLimits { max_depth: 2, max_complexity: 10 };
+        let document = "{ __schema { types { fields { type { ofType { ofType { name } } } } } } }";
+
+        assert!(check(limits, document, &Variables::new()).is_ok());
+    }
+
+    #[test]
+    fn test_fragment_cycles_terminate() {
+        let document = "
+            { ...A }
+            fragment A on Query { ...B }
+            fragment B on Query { ...A }
+        ";
+
+        assert!(check(QueryLimits::default(), document, &Variables::new()).is_ok());
+    }
+}
@@ -0,0 +1,444 @@
+//! Batched relationship loading for GraphQL objects
+//!
+//! Juniper resolves the fields of sibling objects concurrently, so when 100 orders each ask
+//! for their customer, 100 `load` calls are in flight at once. A `Loader` collects their keys,
+//! lets the first caller fetch all of them with one `IN (...)` query and caches the results
+//! for the rest of the request. Every request gets fresh loaders (see `AppState::for_request`),
+//! so nothing is cached across requests. A subscription clears its loaders before each event.
+
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    hash::Hash,
+    sync::Mutex as SyncMutex,
+};
+
+use sea_query::{Expr, Query};
+use tokio::sync::Mutex;
+
+use crate::{
+    adapters::outgoing::database::DatabaseAdapter,
+    core::{
+        models::{
+            catalog::{
+                item_group_model::{ItemCategories, ItemCategory},
+                item_variant_model::{ItemVariant, ItemVariants},
+            },
+            common::tax_model::{ItemTax, ItemTaxes, Tax, Taxes},
+            sales::{
+                customer_model::{Customer, Customers},
+                sales_order_item_model::{SalesOrderItem, SalesOrderItems},
+            },
+        },
+        types::db_uuid::DbUuid,
+    },
+    error::Result,
+    AppState,
+};
+
+/// How many times a load yields before running its batch, so sibling fields can add their keys
+const BATCH_YIELDS: usize = 8;
+
+/// Caches values by key for one request and loads missing keys in batches
+pub struct Loader<K, V> {
+    state: SyncMutex<LoaderState<K, V>>,
+    // Held while a batch is fetched, so concurrent loads wait for it instead of fetching again
+    batch: Mutex<()>,
+}
+
+struct LoaderState<K, V> {
+    cache: HashMap<K, V>,
+    pending: HashSet<K>,
+}
+
+impl<K, V> Default for Loader<K, V> {
+    fn default() -> Self {
+        Loader {
+            state: SyncMutex::new(LoaderState { cache: HashMap::new(), pending: HashSet::new() }),
+            batch: Mutex::new(()),
+        }
+    }
+}
+
+impl<K, V> Loader<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone + Default,
+{
+    /// Returns the value for `key`, fetching it together with every other pending key.
+    ///
+    /// `fetch` gets the keys of a batch and returns the values it found; keys it leaves out
+    /// resolve to `V::default()` (no row, or no children).
+    pub async fn load<F, Fut>(&self, key: K, fetch: F) -> Result<V>
+    where
+        F: FnOnce(Vec<K>) -> Fut,
+        Fut: Future<Output = Result<HashMap<K, V>>>,
+    {
+        if let Some(value) = self.cached(&key) {
+            return Ok(value);
+        }
+        self.state.lock().unwrap().pending.insert(key.clone());
+
+        for _ in 0..BATCH_YIELDS {
+            tokio::task::yield_now().await;
+        }
+
+        let _batch = self.batch.lock().await;
+        if let Some(value) = self.cached(&key) {
+            return Ok(value);
+        }
+
+        let keys: Vec<K> = self.state.lock().unwrap().pending.drain().collect();
+        let mut found = match fetch(keys.clone()).await {
+            Ok(found) => found,
+            Err(err) => {
+                // Leave the keys of a failed batch to be retried by their own loads
+                self.state.lock().unwrap().pending.extend(keys);
+                return Err(err);
+            },
+        };
+
+        let mut state = self.state.lock().unwrap();
+        for key in keys {
+            let value = found.remove(&key).unwrap_or_default();
+            state.cache.insert(key, value);
+        }
+        Ok(state.cache.get(&key).cloned().unwrap_or_default())
+    }
+
+    /// Forgets the cached values, so later loads read them again
+    pub fn clear(&self) {
+        self.state.lock().unwrap().cache.clear();
+    }
+
+    fn cached(&self, key: &K) -> Option<V> {
+        self.state.lock().unwrap().cache.get(key).cloned()
+    }
+}
+
+/// The loaders of one GraphQL request
+#[derive(Default)]
+pub struct Loaders {
+    customers: Loader<DbUuid, Option<Customer>>,
+    item_categories: Loader<DbUuid, Option<ItemCategory>>,
+    item_taxes: Loader<DbUuid, Vec<Tax>>,
+    item_variants: Loader<DbUuid, Vec<ItemVariant>>,
+    sales_order_items: Loader<DbUuid, Vec<SalesOrderItem>>,
+}
+
+impl Loaders {
+    pub fn clear(&self) {
+        self.customers.clear();
+        self.item_categories.clear();
+        self.item_taxes.clear();
+        self.item_variants.clear();
+        self.sales_order_items.clear();
+    }
+}
+
+fn id_strings(ids: &[DbUuid]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+}
+
+pub async fn customer(context: &AppState, id: DbUuid) -> Result<Option<Customer>> {
+    context
+        .loaders
+        .customers
+        .load(id, |ids| async move {
+            let service = &context.service;
+
+            let query = Query::select()
+                .from(Customers::Table)
+                .columns(Customers::all_columns())
+                .and_where(Expr::col(Customers::Id).is_in(id_strings(&ids)))
+                .to_owned();
+            let customers = service.db_adapter.query_many::<Customer>(&query).await?;
+
+            Ok(customers.into_iter().map(|customer| (customer.id, Some(customer))).collect())
+        })
+        .await
+}
+
+pub async fn item_category(context: &AppState, id: DbUuid) -> Result<Option<ItemCategory>> {
+    context
+        .loaders
+        .item_categories
+        .load(id, |ids| async move {
+            let service = &context.service;
+
+            let query = Query::select()
+                .from(ItemCategories::Table)
+                .columns(ItemCategories::all_columns())
+                .and_where(Expr::col(ItemCategories::Id).is_in(id_strings(&ids)))
+                .to_owned();
+            let categories = service.db_adapter.query_many::<ItemCategory>(&query).await?;
+
+            Ok(categories.into_iter().map(|category| (category.id, Some(category))).collect())
+        })
+        .await
+}
+
+pub async fn item_taxes(context: &AppState, item_id: DbUuid) -> Result<Vec<Tax>> {
+    context
+        .loaders
+        .item_taxes
+        .load(item_id, |item_ids| async move {
+            let service = &context.service;
+
+            let links_query = Query::select()
+                .from(ItemTaxes::Table)
+                .columns([ItemTaxes::ItemId, ItemTaxes::TaxId])
+                .and_where(Expr::col(ItemTaxes::ItemId).is_in(id_strings(&item_ids)))
+                .to_owned();
+            let links = service.db_adapter.query_many::<ItemTax>(&links_query).await?;
+            if links.is_empty() {
+                return Ok(HashMap::new());
+            }
+
+            let tax_ids: HashSet<DbUuid> = links.iter().map(|link| link.tax_id).collect();
+            let tax_ids: Vec<DbUuid> = tax_ids.into_iter().collect();
+            let taxes_query = Query::select()
+                .from(Taxes::Table)
+                .columns(Taxes::all_columns())
+                .and_where(Expr::col(Taxes::Id).is_in(id_strings(&tax_ids)))
+                .to_owned();
+            let taxes: HashMap<DbUuid, Tax> = service
+                .db_adapter
+                .query_many::<Tax>(&taxes_query)
+                .await?
+                .into_iter()
+                .map(|tax| (tax.id, tax))
+                .collect();
+
+            let mut by_item: HashMap<DbUuid, Vec<Tax>> = HashMap::new();
+            for link in links {
+                if let Some(tax) = taxes.get(&link.tax_id) {
+                    by_item.entry(link.item_id).or_default().push(tax.clone());
+                }
+            }
+            Ok(by_item)
+        })
+        .await
+}
+
+pub async fn item_variants(context: &AppState, item_id: DbUuid) -> Result<Vec<ItemVariant>> {
+    context
+        .loaders
+        .item_variants
+        .load(item_id, |item_ids| async move {
+            let service = &context.service;
+
+            let query = Query::select()
+                .from(ItemVariants::Table)
+                .columns(ItemVariants::all_columns())
+                .and_where(Expr::col(ItemVariants::ItemId).is_in(id_strings(&item_ids)))
+                .to_owned();
+            let variants = service.db_adapter.query_many::<ItemVariant>(&query).await?;
+
+            let mut by_item: HashMap<DbUuid, Vec<ItemVariant>> = HashMap::new();
+            for variant in variants {
+                by_item.entry(variant.item_id).or_default().push(variant);
+            }
+            Ok(by_item)
+        })
+        .await
+}
+
+pub async fn sales_order_items(context: &AppState, order_id: DbUuid) -> Result<Vec<SalesOrderItem>> {
+    context
+        .loaders
+        .sales_order_items
+        .load(order_id, |order_ids| async move {
+            let service = &context.service;
+
+            let query = Query::select()
+                .from(SalesOrderItems::Table)
+                .columns(SalesOrderItems::all_columns())
+                .and_where(Expr::col(SalesOrderItems::OrderId).is_in(id_strings(&order_ids)))
+                .to_owned();
+            let items = service.db_adapter.query_many::<SalesOrderItem>(&query).await?;
+
+            let mut by_order: HashMap<DbUuid, Vec<SalesOrderItem>> = HashMap::new();
+            for item in items {
+                by_order.entry(item.order_id).or_default().push(item);
+            }
+            Ok(by_order)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use juniper::Variables;
+
+    use super::*;
+    use crate::{
+        adapters::graphql::{Mutation, Query, Schema, Subscription},
+        core::{
+            commands::{
+                auth::user_commands::AddUserCommand,
+                common::{
+                    channel_commands::CreateChannelCommand,
+                    location_commands::CreateLocationCommand,
+                },
+                finance::cost_center_commands::CreateCostCenterCommand,
+                sales::{
+                    customer_commands::CreateCustomerCommand,
+                    sales_order_commands::CreateSalesOrderCommand,
+                },
+                tests::setup_service,
+                Command,
+            },
+            models::{
+                auth::user_model::{UserNewInput, UserRole, UserState},
+                common::{channel_model::ChannelNewInput, location_model::LocationNewInput},
+                finance::cost_center_model::{CostCenterNewInput, CostCenterState},
+                sales::{
+                    customer_model::CustomerNewInput,
+                    sales_order_item_model::SalesOrderItemInput,
+                    sales_order_model::SalesOrderNewInput,
+                },
+            },
+        },
+    };
+
+    fn order_item(name: &str) -> SalesOrderItemInput {
+        SalesOrderItemInput {
+            item_id: None,
+            variant_id: None,
+            item_name: name.to_string(),
+            quantity: 1,
+            sku: None,
+            price_amount: 100.into(),
+            disc_amount: 0.into(),
+            taxable_amount: 100.into(),
+            tax_amount: 0.into(),
+            total_amount: 100.into(),
+            price_override: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nested_order_listing_is_batched() {
+        let service = setup_service().await;
+        let user = AddUserCommand {
+            user: UserNewInput {
+                username: "cashier".to_string(),
+                pin_hash: "1234".to_string(),
+                full_name: "Cashier".to_string(),
+                state: UserState::Active,
+                role: UserRole::Cashier,
+                last_login_at: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        let channel = CreateChannelCommand {
+            channel: ChannelNewInput { name: "Store".to_string(), description: None, is_active: Some(true) },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        let location = CreateLocationCommand {
+            location: LocationNewInput {
+                name: "Main".to_string(),
+                description: None,
+                address: None,
+                is_active: Some(true),
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        let cost_center = CreateCostCenterCommand {
+            cost_center: CostCenterNewInput {
+                name: "Sales".to_string(),
+                code: "SALES".to_string(),
+                description: None,
+                state: Some(CostCenterState::Active),
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let mut customers = Vec::new();
+        for name in ["Alice", "Bob", "Carol"] {
+            let customer = CreateCustomerCommand {
+                customer: CustomerNewInput {
+                    full_name: name.to_string(),
+                    email: None,
+                    phone: None,
+                    address: None,
+                    customer_group_id: None,
+                },
+            }
+            .exec(&service)
+            .await
+            .unwrap();
+            customers.push(customer);
+        }
+
+        for i in 0..10 {
+            let customer = &customers[i % customers.len()];
+            CreateSalesOrderCommand {
+                sales_order: SalesOrderNewInput {
+                    order_date: Utc::now().naive_utc(),
+                    customer_id: Some(customer.id),
+                    customer_name: Some(customer.full_name.clone()),
+                    customer_phone_number: None,
+                    billing_address: None,
+                    shipping_address: None,
+                    net_amount: 200.into(),
+                    disc_amount: 0.into(),
+                    taxable_amount: 200.into(),
+                    tax_amount: 0.into(),
+                    total_amount: 200.into(),
+                    notes: None,
+                    channel_id: channel.id,
+                    location_id: location.id,
+                    cost_center_id: cost_center.id,
+                    discount_id: None,
+                    items: vec![order_item("Tea"), order_item("Cake")],
+                    charges: None,
+                },
+                created_by_user_id: user.id,
+            }
+            .exec(&service)
+            .await
+            .unwrap();
+        }
+
+        let context = AppState::new(service);
+        let queries_before = context.service.db_adapter.query_count();
+
+        let query = r#"{
+            salesOrdersConnection(first: 10) {
+                nodes {
+                    customer { fullName }
+                    items { itemName }
+                }
+            }
+        }"#;
+        let schema = Schema::new(Query, Mutation, Subscription);
+        let (result, errors) = juniper::execute(query, None, &schema, &Variables::new(), &context)
+            .await
+            .unwrap();
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let nodes = result
+            .as_object_value()
+            .and_then(|data| data.get_field_value("salesOrdersConnection"))
+            .and_then(|connection| connection.as_object_value())
+            .and_then(|connection| connection.get_field_value("nodes"))
+            .and_then(|nodes| nodes.as_list_value())
+            .unwrap();
+        assert_eq!(nodes.len(), 10);
+
+        // Count and page of orders, then one query each for all customers and all items
+        let queries_run = context.service.db_adapter.query_count() - queries_before;
+        assert_eq!(queries_run, 4);
+    }
+}
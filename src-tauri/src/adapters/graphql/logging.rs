@@ -0,0 +1,129 @@
+//! One JSON line on stderr per GraphQL operation
+//!
+//! Documents are logged by hash and variables are logged with secrets redacted: any key made
+//! of a word such as `pin`, `password` or `token` (`pinHash`, `turso_token`, ...).
+
+use std::time::Duration;
+
+use chrono::Utc;
+use serde_json::Value as Json;
+
+use crate::{core::types::db_uuid::DbUuid, error::Error};
+
+/// Words that mark a variable as secret
+const SECRET_WORDS: [&str; 5] = ["pin", "password", "secret", "token", "hash"];
+
+const REDACTED: &str = "[REDACTED]";
+
+#[derive(Debug)]
+pub struct OperationLog<'a> {
+    pub operation: Option<&'a str>,
+    pub document_hash: &'a str,
+    pub variables: Json,
+    pub user: Option<DbUuid>,
+    pub duration_ms: u128,
+    pub errors: Vec<String>,
+}
+
+impl<'a> OperationLog<'a> {
+    pub fn new(operation: Option<&'a str>, document_hash: &'a str, variables: Json, duration: Duration) -> Self {
+        Self {
+            operation,
+            document_hash,
+            variables: redact(variables),
+            user: None,
+            duration_ms: duration.as_millis(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn write(&self) {
+        let level = if self.errors.is_empty() { "info" } else { "warn" };
+        let line = serde_json::json!({
+            "timestamp": Utc::now().to_rfc3339(),
+            "level": level,
+            "event": "graphql.operation",
+            "operation": self.operation,
+            "documentHash": self.document_hash,
+            "variables": self.variables,
+            "user": self.user.map(|user| user.to_string()),
+            "durationMs": self.duration_ms,
+            "errors": self.errors,
+        });
+        eprintln!("{}", line);
+    }
+}
+
+/// Logs an error whose details are kept from clients
+pub fn write_internal_error(error: &Error) {
+    let line = serde_json::json!({
+        "timestamp": Utc::now().to_rfc3339(),
+        "level": "error",
+        "event": "graphql.internal_error",
+        "error": error.to_string(),
+    });
+    eprintln!("{}", line);
+}
+
+/// Replaces the values of secret keys, at any depth
+pub fn redact(value: Json) -> Json {
+    match value {
+        Json::Object(fields) => Json::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = if is_secret(&key) { Json::String(REDACTED.to_string()) } else { redact(value) };
+                    (key, value)
+                })
+                .collect(),
+        ),
+        Json::Array(values) => Json::Array(values.into_iter().map(redact).collect()),
+        value => value,
+    }
+}
+
+// Splits camelCase and snake_case keys into words, so `shippingAddress` is not a `pin`
+fn is_secret(key: &str) -> bool {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for c in key.chars() {
+        if (c == '_' || c == '-' || c.is_uppercase()) && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        if c.is_alphanumeric() {
+            word.extend(c.to_lowercase());
+        }
+    }
+    words.push(word);
+
+    words.iter().any(|word| SECRET_WORDS.contains(&word.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_redact_secret_variables() {
+        let variables = json!({
+            "username": "cashier",
+            "password": "hunter2",
+            "user": { "pinHash": "1234", "fullName": "Cashier" },
+            "tokens": [{ "turso_token": "abc" }],
+            "shippingAddress": "12 Pine Road",
+        });
+
+        assert_eq!(
+            redact(variables),
+            json!({
+                "username": "cashier",
+                "password": REDACTED,
+                "user": { "pinHash": REDACTED, "fullName": "Cashier" },
+                "tokens": [{ "turso_token": REDACTED }],
+                "shippingAddress": "12 Pine Road",
+            })
+        );
+    }
+}
@@ -6,13 +6,24 @@ pub mod finance;
 pub mod purchases;
 pub mod sales;
 
+pub mod connection;
+pub mod endpoint;
+pub mod errors;
+pub mod limits;
+pub mod loaders;
+pub mod logging;
+pub mod persisted_queries;
+
 pub mod mutations;
 pub mod queries;
-use juniper::{Context, EmptySubscription, RootNode};
+pub mod subscriptions;
+use juniper::{Context, RootNode};
 
 use crate::AppState;
 
-pub type Schema = RootNode<'static, Query, Mutation, EmptySubscription<AppState>>;
+pub use subscriptions::Subscription;
+
+pub type Schema = RootNode<'static, Query, Mutation, Subscription>;
 
 impl Context for AppState {}
 
@@ -1,29 +1,45 @@
-use juniper::{graphql_object, FieldResult};
+use juniper::graphql_object;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     adapters::graphql::catalog::variants::{item_variant_mutations, variant_type_mutations, variant_value_mutations},
     core::{
-        commands::auth_commands::LoginResponse, models::{
-            auth::user_model::{User, UserNewInput, UserUpdateInput},
+        commands::{api_token_commands::ApiTokenSecret, auth_commands::LoginResponse}, models::{
+            auth::{
+                api_token_model::ApiToken,
+                user_model::{User, UserNewInput, UserUpdateInput},
+            },
             catalog::{
+                catalog_import_model::{CatalogImportInput, CatalogImportReport},
                 discount_model::{Discount, DiscountNewInput, DiscountUpdateInput},
                 item_discount_model::{ItemDiscount, ItemDiscountNewInput},
                 item_group_model::{ItemCategory, ItemCategoryNew, ItemCategoryUpdate},
                 item_model::{Item, NewItem, UpdateItem},
                 item_variant_model::{ItemVariant, ItemVariantNewInput, ItemVariantUpdateInput},
+                price_list_model::{
+                    PriceList, PriceListAssignment, PriceListAssignmentInput, PriceListItem,
+                    PriceListItemInput, PriceListNewInput, PriceListUpdateInput,
+                },
+                stock_movement_model::{StockMovement, StockMovementNewInput},
                 variant_type_model::{VariantType, VariantTypeNewInput, VariantTypeUpdateInput},
                 variant_value_model::{
                     VariantValue, VariantValueNewInput, VariantValueUpdateInput,
                 },
             },
             common::{
+                backup_model::Backup,
                 brand_model::{Brand, BrandNewInput, BrandUpdateInput},
                 channel_model::{Channel, ChannelNewInput, ChannelUpdateInput},
                 tax_group_model::{TaxGroup, TaxGroupNewInput, TaxGroupUpdateInput},
                 tax_model::{ItemTaxNewInput, Tax, TaxNewInput, TaxUpdateInput},
             },
             finance::{
+                account_mapping_model::{AccountMapping, AccountMappingInput},
                 cost_center_model::{CostCenter, CostCenterState},
+                currency_model::{Currency, CurrencyNewInput, CurrencyUpdateInput},
+                data_export_model::{DataExport, DataExportInput},
+                exchange_rate_model::{ExchangeRate, ExchangeRateNewInput},
+                journal_model::JournalExportInput,
                 payment_method_model::{PaymentMethod, PaymentMethodState},
             },
             purchases::{
@@ -32,8 +48,16 @@ use crate::{
                 supplier_model::{Supplier, SupplierNewInput, SupplierUpdateInput},
             },
             sales::{
-                cart_model::{Cart, CartNewInput, CartUpdateInput},
+                cart_model::{
+                    Cart, CartCharge, CartChargeNewInput, CartLine, CartLineNewInput,
+                    CartLineUpdateInput, CartNewInput, CartUpdateInput, CheckoutCartInput,
+                    ParkCartInput, RecallCartInput,
+                },
+                customer_group_model::{CustomerGroup, CustomerGroupNewInput, CustomerGroupUpdateInput},
                 customer_model::{Customer, CustomerNewInput, CustomerUpdateInput},
+                price_override_reason_model::{
+                    PriceOverrideReason, PriceOverrideReasonNewInput, PriceOverrideReasonUpdateInput,
+                },
                 sales_charge_type_model::{
                     SalesChargeType, SalesChargeTypeNewInput, SalesChargeTypeUpdateInput,
                 },
@@ -69,6 +93,16 @@ impl Mutation {
         super::auth::user_mutations::delete_user(id, context).await
     }
 
+    /// Issues a bearer token for the HTTP server, for the caller unless `userId` names someone
+    /// else; only supervisors may do that. The token itself is only returned here.
+    async fn create_api_token(user_id: Option<DbUuid>, name: String, context: &AppState) -> FieldResult<ApiTokenSecret> {
+        super::auth::api_token_mutations::create_api_token(user_id, name, context).await
+    }
+
+    async fn revoke_api_token(id: DbUuid, context: &AppState) -> FieldResult<ApiToken> {
+        super::auth::api_token_mutations::revoke_api_token(id, context).await
+    }
+
     async fn create_item(item: NewItem, context: &AppState) -> FieldResult<Item> {
         super::catalog::item_mutations::create_item(item, context).await
     }
@@ -81,6 +115,27 @@ impl Mutation {
         super::catalog::item_mutations::delete_item(id, context).await
     }
 
+    async fn restore_item(id: DbUuid, context: &AppState) -> FieldResult<Item> {
+        super::catalog::item_mutations::restore_item(id, context).await
+    }
+
+    async fn purge_item(id: DbUuid, context: &AppState) -> FieldResult<i32> {
+        super::catalog::item_mutations::purge_item(id, context).await
+    }
+
+    /// Creates or updates items, categories, taxes, variants and opening stock from a CSV or
+    /// XLSX file. Nothing is saved when any row has an error, or for a dry run.
+    async fn import_catalog(import: CatalogImportInput, context: &AppState) -> FieldResult<CatalogImportReport> {
+        super::catalog::catalog_import_mutations::import_catalog(import, context).await
+    }
+
+    async fn record_stock_movement(
+        movement: StockMovementNewInput,
+        context: &AppState,
+    ) -> FieldResult<StockMovement> {
+        super::catalog::stock_movement_mutations::record_stock_movement(movement, context).await
+    }
+
     async fn create_item_category(
         new_category: ItemCategoryNew,
         context: &AppState,
@@ -99,6 +154,14 @@ impl Mutation {
         super::catalog::item_group_mutations::delete_item_category(id, context).await
     }
 
+    async fn restore_item_category(id: DbUuid, context: &AppState) -> FieldResult<ItemCategory> {
+        super::catalog::item_group_mutations::restore_item_category(id, context).await
+    }
+
+    async fn purge_item_category(id: DbUuid, context: &AppState) -> FieldResult<i32> {
+        super::catalog::item_group_mutations::purge_item_category(id, context).await
+    }
+
     async fn create_purchase_category(
         &self,
         name: String,
@@ -135,6 +198,14 @@ impl Mutation {
         super::purchases::purchase_category_mutations::delete_purchase_category(id, context).await
     }
 
+    async fn restore_purchase_category(&self, id: DbUuid, context: &AppState) -> FieldResult<PurchaseCategory> {
+        super::purchases::purchase_category_mutations::restore_purchase_category(id, context).await
+    }
+
+    async fn purge_purchase_category(&self, id: DbUuid, context: &AppState) -> FieldResult<DbUuid> {
+        super::purchases::purchase_category_mutations::purge_purchase_category(id, context).await
+    }
+
     // Sales Order Mutations
     async fn create_sales_order(
         sales_order: SalesOrderNewInput,
@@ -160,6 +231,38 @@ impl Mutation {
         super::sales::cart_mutations::delete_cart(id, context).await
     }
 
+    async fn add_cart_line(line: CartLineNewInput, context: &AppState) -> FieldResult<CartLine> {
+        super::sales::cart_mutations::add_cart_line(line, context).await
+    }
+
+    async fn update_cart_line(line: CartLineUpdateInput, context: &AppState) -> FieldResult<CartLine> {
+        super::sales::cart_mutations::update_cart_line(line, context).await
+    }
+
+    async fn remove_cart_line(id: DbUuid, context: &AppState) -> FieldResult<CartLine> {
+        super::sales::cart_mutations::remove_cart_line(id, context).await
+    }
+
+    async fn add_cart_charge(charge: CartChargeNewInput, context: &AppState) -> FieldResult<CartCharge> {
+        super::sales::cart_mutations::add_cart_charge(charge, context).await
+    }
+
+    async fn remove_cart_charge(id: DbUuid, context: &AppState) -> FieldResult<DbUuid> {
+        super::sales::cart_mutations::remove_cart_charge(id, context).await
+    }
+
+    async fn checkout_cart(checkout: CheckoutCartInput, context: &AppState) -> FieldResult<SalesOrder> {
+        super::sales::cart_mutations::checkout_cart(checkout, context).await
+    }
+
+    async fn park_cart(cart: ParkCartInput, context: &AppState) -> FieldResult<Cart> {
+        super::sales::cart_mutations::park_cart(cart, context).await
+    }
+
+    async fn recall_cart(cart: RecallCartInput, context: &AppState) -> FieldResult<Cart> {
+        super::sales::cart_mutations::recall_cart(cart, context).await
+    }
+
     // Customer Mutations
     async fn create_customer(customer: CustomerNewInput, context: &AppState) -> FieldResult<Customer> {
         super::sales::customer_mutations::create_customer(customer, context).await
@@ -173,6 +276,14 @@ impl Mutation {
         super::sales::customer_mutations::delete_customer(id, context).await
     }
 
+    async fn restore_customer(id: DbUuid, context: &AppState) -> FieldResult<Customer> {
+        super::sales::customer_mutations::restore_customer(id, context).await
+    }
+
+    async fn purge_customer(id: DbUuid, context: &AppState) -> FieldResult<i32> {
+        super::sales::customer_mutations::purge_customer(id, context).await
+    }
+
     // Tax Mutations
     async fn create_tax(input: TaxNewInput, context: &AppState) -> FieldResult<Tax> {
         super::common::tax_mutations::create_tax(input, context).await
@@ -240,6 +351,14 @@ impl Mutation {
         super::purchases::supplier_mutations::delete_supplier(id, context).await
     }
 
+    async fn restore_supplier(id: DbUuid, context: &AppState) -> FieldResult<Supplier> {
+        super::purchases::supplier_mutations::restore_supplier(id, context).await
+    }
+
+    async fn purge_supplier(id: DbUuid, context: &AppState) -> FieldResult<i32> {
+        super::purchases::supplier_mutations::purge_supplier(id, context).await
+    }
+
     // Expense Mutations
     async fn create_expense(expense: ExpenseNewInput, context: &AppState) -> FieldResult<Expense> {
         super::purchases::expense_mutations::create_expense(expense, context).await
@@ -266,6 +385,14 @@ impl Mutation {
         super::common::channel_mutations::delete_channel(id, context).await
     }
 
+    async fn restore_channel(id: DbUuid, context: &AppState) -> FieldResult<Channel> {
+        super::common::channel_mutations::restore_channel(id, context).await
+    }
+
+    async fn purge_channel(id: DbUuid, context: &AppState) -> FieldResult<i32> {
+        super::common::channel_mutations::purge_channel(id, context).await
+    }
+
     // Brand Mutations
     async fn create_brand(input: BrandNewInput, context: &AppState) -> FieldResult<Brand> {
         super::common::brand_mutations::create_brand(input, context).await
@@ -279,6 +406,14 @@ impl Mutation {
         super::common::brand_mutations::delete_brand(id, context).await
     }
 
+    async fn restore_brand(id: DbUuid, context: &AppState) -> FieldResult<Brand> {
+        super::common::brand_mutations::restore_brand(id, context).await
+    }
+
+    async fn purge_brand(id: DbUuid, context: &AppState) -> FieldResult<i32> {
+        super::common::brand_mutations::purge_brand(id, context).await
+    }
+
     // Cost Center Mutations
     async fn create_cost_center(
         &self,
@@ -320,6 +455,14 @@ impl Mutation {
         super::finance::cost_center_mutations::delete_cost_center(id, context).await
     }
 
+    async fn restore_cost_center(&self, id: DbUuid, context: &AppState) -> FieldResult<CostCenter> {
+        super::finance::cost_center_mutations::restore_cost_center(id, context).await
+    }
+
+    async fn purge_cost_center(&self, id: DbUuid, context: &AppState) -> FieldResult<DbUuid> {
+        super::finance::cost_center_mutations::purge_cost_center(id, context).await
+    }
+
     // Payment Method Mutations
     async fn create_payment_method(
         &self,
@@ -361,6 +504,52 @@ impl Mutation {
         super::finance::payment_method_mutations::delete_payment_method(id, context).await
     }
 
+    async fn restore_payment_method(&self, id: DbUuid, context: &AppState) -> FieldResult<PaymentMethod> {
+        super::finance::payment_method_mutations::restore_payment_method(id, context).await
+    }
+
+    async fn purge_payment_method(&self, id: DbUuid, context: &AppState) -> FieldResult<DbUuid> {
+        super::finance::payment_method_mutations::purge_payment_method(id, context).await
+    }
+
+    // Currency Mutations
+    async fn create_currency(
+        &self,
+        currency: CurrencyNewInput,
+        context: &AppState,
+    ) -> FieldResult<Currency> {
+        super::finance::currency_mutations::create_currency(currency, context).await
+    }
+
+    async fn update_currency(
+        &self,
+        currency: CurrencyUpdateInput,
+        context: &AppState,
+    ) -> FieldResult<Currency> {
+        super::finance::currency_mutations::update_currency(currency, context).await
+    }
+
+    async fn delete_currency(&self, id: DbUuid, context: &AppState) -> FieldResult<bool> {
+        super::finance::currency_mutations::delete_currency(id, context).await
+    }
+
+    /// Makes another currency the base currency, until the first order is recorded
+    async fn set_base_currency(&self, id: DbUuid, context: &AppState) -> FieldResult<Currency> {
+        super::finance::currency_mutations::set_base_currency(id, context).await
+    }
+
+    async fn create_exchange_rate(
+        &self,
+        exchange_rate: ExchangeRateNewInput,
+        context: &AppState,
+    ) -> FieldResult<ExchangeRate> {
+        super::finance::currency_mutations::create_exchange_rate(exchange_rate, context).await
+    }
+
+    async fn delete_exchange_rate(&self, id: DbUuid, context: &AppState) -> FieldResult<bool> {
+        super::finance::currency_mutations::delete_exchange_rate(id, context).await
+    }
+
     // Sales Order Payment Mutations
     async fn create_sales_order_payment(
         &self,
@@ -389,6 +578,33 @@ impl Mutation {
         super::finance::sales_order_payment_mutations::void_sales_order_payment(context, id).await
     }
 
+    /// Writes the orders, order lines, payments or expenses of a date range to a CSV, JSON Lines
+    /// or XLSX file on the server
+    async fn export_data(export: DataExportInput, context: &AppState) -> FieldResult<DataExport> {
+        super::finance::data_export_mutations::export_data(export, context).await
+    }
+
+    /// Maps a kind of posting, or one category, tax, charge type, payment method or purchase
+    /// category, to a ledger account
+    async fn set_account_mapping(mapping: AccountMappingInput, context: &AppState) -> FieldResult<AccountMapping> {
+        super::finance::account_mapping_mutations::set_account_mapping(mapping, context).await
+    }
+
+    async fn delete_account_mapping(id: DbUuid, context: &AppState) -> FieldResult<i32> {
+        super::finance::account_mapping_mutations::delete_account_mapping(id, context).await
+    }
+
+    /// Writes the journal of a date range as a CSV journal, a QuickBooks IIF file or Tally XML
+    async fn export_journal(export: JournalExportInput, context: &AppState) -> FieldResult<DataExport> {
+        super::finance::journal_mutations::export_journal(export, context).await
+    }
+
+    // Backup Mutations
+    /// Copies the database to the backup directory now, for example before a bulk import
+    async fn create_snapshot(label: Option<String>, context: &AppState) -> FieldResult<Backup> {
+        super::common::backup_mutations::create_snapshot(label, context).await
+    }
+
     // Discount Mutations
     async fn create_discount(discount: DiscountNewInput, context: &AppState) -> FieldResult<Discount> {
         super::catalog::discount_mutations::create_discount(discount, context).await
@@ -421,6 +637,78 @@ impl Mutation {
         super::sales::sales_charge_type_mutations::delete_sales_charge_type(id, context).await
     }
 
+    async fn restore_sales_charge_type(id: DbUuid, context: &AppState) -> FieldResult<SalesChargeType> {
+        super::sales::sales_charge_type_mutations::restore_sales_charge_type(id, context).await
+    }
+
+    async fn purge_sales_charge_type(id: DbUuid, context: &AppState) -> FieldResult<bool> {
+        super::sales::sales_charge_type_mutations::purge_sales_charge_type(id, context).await
+    }
+
+    // Price Override Reason Mutations
+    async fn create_price_override_reason(
+        reason: PriceOverrideReasonNewInput,
+        context: &AppState,
+    ) -> FieldResult<PriceOverrideReason> {
+        super::sales::price_override_reason_mutations::create_price_override_reason(reason, context).await
+    }
+
+    async fn update_price_override_reason(
+        reason: PriceOverrideReasonUpdateInput,
+        context: &AppState,
+    ) -> FieldResult<PriceOverrideReason> {
+        super::sales::price_override_reason_mutations::update_price_override_reason(reason, context).await
+    }
+
+    async fn delete_price_override_reason(id: DbUuid, context: &AppState) -> FieldResult<bool> {
+        super::sales::price_override_reason_mutations::delete_price_override_reason(id, context).await
+    }
+
+    // Customer Group Mutations
+    async fn create_customer_group(group: CustomerGroupNewInput, context: &AppState) -> FieldResult<CustomerGroup> {
+        super::sales::customer_group_mutations::create_customer_group(group, context).await
+    }
+
+    async fn update_customer_group(group: CustomerGroupUpdateInput, context: &AppState) -> FieldResult<CustomerGroup> {
+        super::sales::customer_group_mutations::update_customer_group(group, context).await
+    }
+
+    async fn delete_customer_group(id: DbUuid, context: &AppState) -> FieldResult<bool> {
+        super::sales::customer_group_mutations::delete_customer_group(id, context).await
+    }
+
+    // Price List Mutations
+    async fn create_price_list(price_list: PriceListNewInput, context: &AppState) -> FieldResult<PriceList> {
+        super::catalog::price_list_mutations::create_price_list(price_list, context).await
+    }
+
+    async fn update_price_list(price_list: PriceListUpdateInput, context: &AppState) -> FieldResult<PriceList> {
+        super::catalog::price_list_mutations::update_price_list(price_list, context).await
+    }
+
+    async fn delete_price_list(id: DbUuid, context: &AppState) -> FieldResult<bool> {
+        super::catalog::price_list_mutations::delete_price_list(id, context).await
+    }
+
+    async fn set_price_list_item(price: PriceListItemInput, context: &AppState) -> FieldResult<PriceListItem> {
+        super::catalog::price_list_mutations::set_price_list_item(price, context).await
+    }
+
+    async fn remove_price_list_item(id: DbUuid, context: &AppState) -> FieldResult<bool> {
+        super::catalog::price_list_mutations::remove_price_list_item(id, context).await
+    }
+
+    async fn assign_price_list(
+        assignment: PriceListAssignmentInput,
+        context: &AppState,
+    ) -> FieldResult<PriceListAssignment> {
+        super::catalog::price_list_mutations::assign_price_list(assignment, context).await
+    }
+
+    async fn unassign_price_list(id: DbUuid, context: &AppState) -> FieldResult<bool> {
+        super::catalog::price_list_mutations::unassign_price_list(id, context).await
+    }
+
     // Variant Type Mutations
     async fn create_variant_type(
         input: VariantTypeNewInput,
@@ -0,0 +1,143 @@
+//! Persisted queries, looked up by the SHA-256 hash of their document
+//!
+//! Documents are registered ahead of time from a manifest, a JSON object of hashes to
+//! documents named by `PERSISTED_QUERIES`. Unless `PERSISTED_QUERIES_ONLY` is set, clients
+//! may also send a document along with its hash the first time and only the hash after that,
+//! as Apollo's automatic persisted queries do. With it set, only manifest documents run.
+
+use std::{collections::HashMap, sync::RwLock};
+
+use ring::digest::{digest, SHA256};
+
+use crate::error::{Error, Result};
+
+/// Documents registered by clients at runtime are kept up to this many
+const MAX_REGISTERED: usize = 1000;
+
+/// The message Apollo clients expect before they resend a hash with its document
+pub const NOT_FOUND_MESSAGE: &str = "PersistedQueryNotFound";
+
+#[derive(Default)]
+pub struct PersistedQueries {
+    documents: RwLock<HashMap<String, String>>,
+    only_persisted: bool,
+}
+
+impl PersistedQueries {
+    /// Registers `documents` under their hashes. When `only_persisted` is set, no other
+    /// document is executed.
+    pub fn new(documents: impl IntoIterator<Item = String>, only_persisted: bool) -> Self {
+        let documents = documents.into_iter().map(|document| (hash_document(&document), document));
+        Self { documents: RwLock::new(documents.collect()), only_persisted }
+    }
+
+    /// Loads the manifest named by `PERSISTED_QUERIES`, if any
+    pub fn from_env() -> Result<Self> {
+        let only_persisted = std::env::var("PERSISTED_QUERIES_ONLY").is_ok_and(|value| value == "true");
+        let Ok(path) = std::env::var("PERSISTED_QUERIES") else {
+            return Ok(Self::new([], only_persisted));
+        };
+
+        let manifest = std::fs::read_to_string(&path)
+            .map_err(|e| Error::ValidationError(format!("Failed to read {}: {}", path, e)))?;
+        let manifest: HashMap<String, String> = serde_json::from_str(&manifest)?;
+        for (hash, document) in &manifest {
+            if *hash != hash_document(document) {
+                return Err(Error::ValidationError(format!("Persisted query {} does not match its hash", hash)));
+            }
+        }
+
+        Ok(Self::new(manifest.into_values(), only_persisted))
+    }
+
+    /// The document to execute for a request carrying a document, a hash or both
+    pub fn resolve(&self, query: Option<&str>, hash: Option<&str>) -> Result<String> {
+        let documents = self.documents.read().expect("Persisted queries lock poisoned");
+
+        match (query, hash) {
+            (None, Some(hash)) => documents
+                .get(hash)
+                .cloned()
+                .ok_or_else(|| Error::ValidationError(NOT_FOUND_MESSAGE.to_string())),
+            (Some(query), Some(hash)) => {
+                if hash_document(query) != hash {
+                    return Err(Error::ValidationError("Persisted query hash does not match the query".to_string()));
+                }
+                if documents.contains_key(hash) {
+                    return Ok(query.to_string());
+                }
+                if self.only_persisted {
+                    return Err(Error::ValidationError(NOT_FOUND_MESSAGE.to_string()));
+                }
+                drop(documents);
+
+                let mut documents = self.documents.write().expect("Persisted queries lock poisoned");
+                if documents.len() < MAX_REGISTERED {
+                    documents.insert(hash.to_string(), query.to_string());
+                }
+                Ok(query.to_string())
+            }
+            (Some(query), None) => {
+                if self.only_persisted && !documents.contains_key(&hash_document(query)) {
+                    return Err(Error::ValidationError("Only persisted queries are allowed".to_string()));
+                }
+                Ok(query.to_string())
+            }
+            (None, None) => Err(Error::ValidationError("A query or a persisted query hash is required".to_string())),
+        }
+    }
+}
+
+/// The lowercase hex SHA-256 of a document, as clients compute it
+pub fn hash_document(document: &str) -> String {
+    digest(&SHA256, document.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = "{ usersConnection { totalCount } }";
+
+    #[test]
+    fn test_hash_document() {
+        assert_eq!(
+            hash_document("{ __typename }"),
+            "7f56e67dd21ab3f30d1ff8b7bed08893f0a0db86449836189b361dd1e56ddb4b"
+        );
+    }
+
+    #[test]
+    fn test_resolve_registered_hash() {
+        let queries = PersistedQueries::new([DOCUMENT.to_string()], true);
+
+        assert_eq!(queries.resolve(None, Some(&hash_document(DOCUMENT))).unwrap(), DOCUMENT);
+        let err = queries.resolve(None, Some("unknown")).unwrap_err();
+        assert_eq!(err.to_string(), NOT_FOUND_MESSAGE);
+    }
+
+    #[test]
+    fn test_only_persisted_rejects_other_documents() {
+        let queries = PersistedQueries::new([DOCUMENT.to_string()], true);
+
+        assert!(queries.resolve(Some(DOCUMENT), None).is_ok());
+        assert!(queries.resolve(Some("{ __typename }"), None).is_err());
+        let hash = hash_document("{ __typename }");
+        assert!(queries.resolve(Some("{ __typename }"), Some(&hash)).is_err());
+    }
+
+    #[test]
+    fn test_documents_are_registered_on_first_use() {
+        let queries = PersistedQueries::default();
+        let hash = hash_document(DOCUMENT);
+
+        assert!(queries.resolve(None, Some(&hash)).is_err());
+        assert!(queries.resolve(Some(DOCUMENT), Some("0000")).is_err());
+        assert_eq!(queries.resolve(Some(DOCUMENT), Some(&hash)).unwrap(), DOCUMENT);
+        assert_eq!(queries.resolve(None, Some(&hash)).unwrap(), DOCUMENT);
+    }
+}
@@ -11,22 +11,22 @@ use crate::{
     },
     AppState,
 };
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 pub async fn create_expense(expense: ExpenseNewInput, context: &AppState) -> FieldResult<Expense> {
-    let mut service = context.service.lock().await;
-    let res = CreateExpenseCommand { expense }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = CreateExpenseCommand { expense }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn update_expense(expense: ExpenseUpdateInput, context: &AppState) -> FieldResult<Expense> {
-    let mut service = context.service.lock().await;
-    let res = UpdateExpenseCommand { expense }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = UpdateExpenseCommand { expense }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn delete_expense(id: DbUuid, context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
-    let res = DeleteExpenseCommand { id }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = DeleteExpenseCommand { id }.exec(&service).await?;
     Ok(res)
 }
@@ -1,6 +1,7 @@
 use chrono::NaiveDateTime;
 use sea_query::{Expr, Query};
-use juniper::{graphql_object, FieldResult};
+use juniper::graphql_object;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     adapters::outgoing::database::DatabaseAdapter,
@@ -56,7 +57,7 @@ impl Expense {
     }
 
     pub async fn category(&self, context: &AppState) -> FieldResult<PurchaseCategory> {
-        let service = context.service.lock().await;
+        let service = &context.service;
 
         let mut query = Query::select();
         let query = query
@@ -77,7 +78,7 @@ impl Expense {
     }
 
     pub async fn cost_center(&self, context: &AppState) -> FieldResult<CostCenter> {
-        let service = context.service.lock().await;
+        let service = &context.service;
 
         let mut query = Query::select();
         let query = query
@@ -1,11 +1,14 @@
 use chrono::NaiveDateTime;
-use sea_query::{Alias, Expr, Order, Query};
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
     core::{
-        models::purchases::expense_model::{Expense, Expenses},
+        db::pagination::{PageRequest, SortDirection},
+        models::purchases::expense_model::{Expense, ExpenseFilter, ExpenseOrderBy, Expenses},
         types::db_uuid::DbUuid,
     },
     AppState,
@@ -19,58 +22,12 @@ pub async fn expenses(
     end_date: Option<String>,
     context: &AppState,
 ) -> FieldResult<Vec<Expense>> {
-    let service = context.service.lock().await;
-
-    // Build the query with SeaQuery
-    let mut query_builder = Query::select();
-    let mut query = query_builder
-        .from(Expenses::Table)
-        .columns([
-            Expenses::Id,
-            Expenses::Title,
-            Expenses::Amount,
-            Expenses::ExpenseDate,
-            Expenses::CategoryId,
-            Expenses::CostCenterId,
-            Expenses::Description,
-            Expenses::CreatedAt,
-            Expenses::UpdatedAt,
-        ]);
-
-    // Apply cost center filter if provided
-    if let Some(cc_id) = cost_center_id {
-        query = query.and_where(Expr::col(Expenses::CostCenterId).eq(cc_id.to_string()));
-    }
-
-    // Apply date range filters if provided
-    if let Some(start) = start_date {
-        if let Ok(date) = NaiveDateTime::parse_from_str(&start, "%Y-%m-%dT%H:%M:%S%.f%z") {
-            query = query.and_where(Expr::col(Expenses::ExpenseDate).gte(date.to_string()));
-        } else if let Ok(date) = NaiveDateTime::parse_from_str(&start, "%Y-%m-%dT%H:%M:%S%.f") {
-            query = query.and_where(Expr::col(Expenses::ExpenseDate).gte(date.to_string()));
-        }
-    }
-
-    if let Some(end) = end_date {
-        if let Ok(date) = NaiveDateTime::parse_from_str(&end, "%Y-%m-%dT%H:%M:%S%.f%z") {
-            query = query.and_where(Expr::col(Expenses::ExpenseDate).lte(date.to_string()));
-        } else if let Ok(date) = NaiveDateTime::parse_from_str(&end, "%Y-%m-%dT%H:%M:%S%.f") {
-            query = query.and_where(Expr::col(Expenses::ExpenseDate).lte(date.to_string()));
-        }
-    }
-
-    // Order by expense date descending (newest first)
-    query = query.order_by(Expenses::ExpenseDate, Order::Desc);
+    let service = &context.service;
 
-    // Apply pagination if parameters are provided
-    if let Some(limit) = first {
-        query = query.limit(limit as u64);
-    }
-    if let Some(off) = offset {
-        query = query.offset(off as u64);
-    }
-
-    // Execute the query
+    // Newest first
+    let filter = expense_filter(cost_center_id, start_date, end_date);
+    let sort = (Expenses::ExpenseDate, SortDirection::Desc);
+    let query = Expenses::list(Some(&filter), Some(sort), first, offset);
     let result = service.db_adapter.query_many::<Expense>(&query).await?;
 
     Ok(result)
@@ -82,64 +39,18 @@ pub async fn total_expenses(
     end_date: Option<String>,
     context: &AppState,
 ) -> FieldResult<i32> {
-    let service = context.service.lock().await;
-
-    // Build the query with SeaQuery
-    let mut query_builder = Query::select();
-    let mut query = query_builder
-        .from(Expenses::Table)
-        .expr_as(Expr::col(Expenses::Id).count(), Alias::new("count"));
-
-    // Apply cost center filter if provided
-    if let Some(cc_id) = cost_center_id {
-        query = query.and_where(Expr::col(Expenses::CostCenterId).eq(cc_id.to_string()));
-    }
-
-    // Apply date range filters if provided
-    if let Some(start) = start_date {
-        if let Ok(date) = NaiveDateTime::parse_from_str(&start, "%Y-%m-%dT%H:%M:%S%.f%z") {
-            query = query.and_where(Expr::col(Expenses::ExpenseDate).gte(date.to_string()));
-        } else if let Ok(date) = NaiveDateTime::parse_from_str(&start, "%Y-%m-%dT%H:%M:%S%.f") {
-            query = query.and_where(Expr::col(Expenses::ExpenseDate).gte(date.to_string()));
-        }
-    }
-
-    if let Some(end) = end_date {
-        if let Ok(date) = NaiveDateTime::parse_from_str(&end, "%Y-%m-%dT%H:%M:%S%.f%z") {
-            query = query.and_where(Expr::col(Expenses::ExpenseDate).lte(date.to_string()));
-        } else if let Ok(date) = NaiveDateTime::parse_from_str(&end, "%Y-%m-%dT%H:%M:%S%.f") {
-            query = query.and_where(Expr::col(Expenses::ExpenseDate).lte(date.to_string()));
-        }
-    }
+    let service = &context.service;
 
-    // Execute the query
-    let result = service.db_adapter.query_one::<i64>(&query).await?;
+    let filter = expense_filter(cost_center_id, start_date, end_date);
+    let result = service.db_adapter.query_one::<i64>(&Expenses::count(Some(&filter))).await?;
 
     Ok(result as i32)
 }
 
 pub async fn expense(id: DbUuid, context: &AppState) -> FieldResult<Expense> {
-    let service = context.service.lock().await;
-
-    // Build the query with SeaQuery
-    let mut query = Query::select();
-    let query = query
-        .from(Expenses::Table)
-        .columns([
-            Expenses::Id,
-            Expenses::Title,
-            Expenses::Amount,
-            Expenses::ExpenseDate,
-            Expenses::CategoryId,
-            Expenses::CostCenterId,
-            Expenses::Description,
-            Expenses::CreatedAt,
-            Expenses::UpdatedAt,
-        ])
-        .and_where(Expr::col(Expenses::Id).eq(id.to_string()));
-
-    // Execute the query
-    let result = service.db_adapter.query_one::<Expense>(&query).await?;
+    let service = &context.service;
+
+    let result = service.db_adapter.query_one::<Expense>(&Expenses::find_by_id(id)).await?;
 
     Ok(result)
 }
@@ -150,36 +61,43 @@ pub async fn expenses_by_category(
     offset: Option<i32>,
     context: &AppState,
 ) -> FieldResult<Vec<Expense>> {
-    let service = context.service.lock().await;
-
-    // Build the query with SeaQuery
-    let mut query_builder = Query::select();
-    let mut query = query_builder
-        .from(Expenses::Table)
-        .columns([
-            Expenses::Id,
-            Expenses::Title,
-            Expenses::Amount,
-            Expenses::ExpenseDate,
-            Expenses::CategoryId,
-            Expenses::CostCenterId,
-            Expenses::Description,
-            Expenses::CreatedAt,
-            Expenses::UpdatedAt,
-        ])
-        .and_where(Expr::col(Expenses::CategoryId).eq(category_id.to_string()))
-        .order_by(Expenses::ExpenseDate, Order::Desc);
-
-    // Apply pagination if parameters are provided
-    if let Some(limit) = first {
-        query = query.limit(limit as u64);
-    }
-    if let Some(off) = offset {
-        query = query.offset(off as u64);
-    }
+    let service = &context.service;
 
-    // Execute the query
+    let filter = ExpenseFilter { category_id: Some(category_id), ..Default::default() };
+    let sort = (Expenses::ExpenseDate, SortDirection::Desc);
+    let query = Expenses::list(Some(&filter), Some(sort), first, offset);
     let result = service.db_adapter.query_many::<Expense>(&query).await?;
 
     Ok(result)
 }
+
+// Expenses of one cost center between two ISO 8601 timestamps. Dates that cannot be read
+// are left out of the filter.
+fn expense_filter(cost_center_id: Option<DbUuid>, start_date: Option<String>, end_date: Option<String>) -> ExpenseFilter {
+    let parse = |date: String| {
+        NaiveDateTime::parse_from_str(&date, "%Y-%m-%dT%H:%M:%S%.f%z")
+            .or_else(|_| NaiveDateTime::parse_from_str(&date, "%Y-%m-%dT%H:%M:%S%.f"))
+            .ok()
+    };
+
+    ExpenseFilter {
+        cost_center_id,
+        expense_date_from: start_date.and_then(parse),
+        // The end date is inclusive
+        expense_date_to: end_date.and_then(parse).map(|date| date + chrono::Duration::nanoseconds(1)),
+        ..Default::default()
+    }
+}
+
+connection!(ExpenseConnection, ExpenseEdge, Expense);
+
+pub async fn expenses_connection(
+    page: PageRequest,
+    filter: Option<ExpenseFilter>,
+    order_by: Option<ExpenseOrderBy>,
+    context: &AppState,
+) -> FieldResult<ExpenseConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
@@ -1,10 +1,10 @@
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     core::{
         commands::{
             purchases::purchase_category_commands::{
-                CreatePurchaseCategoryCommand, DeletePurchaseCategoryCommand,
+                CreatePurchaseCategoryCommand, DeletePurchaseCategoryCommand, PurgePurchaseCategoryCommand, RestorePurchaseCategoryCommand,
                 UpdatePurchaseCategoryCommand,
             },
             Command,
@@ -23,7 +23,7 @@ pub async fn create_purchase_category(
     state: Option<PurchaseCategoryState>,
     context: &AppState,
 ) -> FieldResult<PurchaseCategory> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
     let command = CreatePurchaseCategoryCommand {
         category: PurchaseCategoryNew {
             name,
@@ -31,7 +31,7 @@ pub async fn create_purchase_category(
             state,
         },
     };
-    let result = command.exec(&mut service).await?;
+    let result = command.exec(&service).await?;
     Ok(result)
 }
 
@@ -42,7 +42,7 @@ pub async fn update_purchase_category(
     state: Option<PurchaseCategoryState>,
     context: &AppState,
 ) -> FieldResult<PurchaseCategory> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
     let command = UpdatePurchaseCategoryCommand {
         category: PurchaseCategoryUpdate {
             id,
@@ -52,14 +52,27 @@ pub async fn update_purchase_category(
             updated_at: None,
         },
     };
-    let result = command.exec(&mut service).await?;
+    let result = command.exec(&service).await?;
     Ok(result)
 }
 
 pub async fn delete_purchase_category(id: DbUuid, context: &AppState) -> FieldResult<DbUuid> {
-    let mut service = context.service.lock().await;
-    let command = DeletePurchaseCategoryCommand { id };
-    let _ = command.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let command = DeletePurchaseCategoryCommand { id, deleted_by: context.user.or(service.current_user()) };
+    let _ = command.exec(&service).await?;
     // Return the id of the deleted category
     Ok(id)
 }
+
+pub async fn restore_purchase_category(id: DbUuid, context: &AppState) -> FieldResult<PurchaseCategory> {
+    let service = context.service.write().await;
+    let res = RestorePurchaseCategoryCommand { id }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn purge_purchase_category(id: DbUuid, context: &AppState) -> FieldResult<DbUuid> {
+    let service = context.service.write().await;
+    let command = PurgePurchaseCategoryCommand { id };
+    let _ = command.exec(&service).await?;
+    Ok(id)
+}
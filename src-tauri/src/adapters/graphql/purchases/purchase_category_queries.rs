@@ -1,10 +1,16 @@
-use sea_query::{Expr, Query};
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
     core::{
-        models::purchases::purchase_category_model::{PurchaseCategory, PurchaseCategoryState, PurchaseCategories},
+        db::pagination::PageRequest,
+        models::purchases::purchase_category_model::{
+            PurchaseCategories, PurchaseCategory, PurchaseCategoryFilter, PurchaseCategoryOrderBy,
+            PurchaseCategoryState,
+        },
         types::db_uuid::DbUuid,
     },
     AppState,
@@ -15,76 +21,45 @@ pub async fn purchase_categories(
     offset: Option<i32>,
     context: &AppState,
 ) -> FieldResult<Vec<PurchaseCategory>> {
-    let service = context.service.lock().await;
-
-    // Build the query with SeaQuery
-    let mut query_builder = Query::select();
-    let query = query_builder
-        .from(PurchaseCategories::Table)
-        .columns([
-            PurchaseCategories::Id,
-            PurchaseCategories::Name,
-            PurchaseCategories::Description,
-            PurchaseCategories::State,
-            PurchaseCategories::CreatedAt,
-            PurchaseCategories::UpdatedAt,
-        ]);
-
-    // Apply pagination if parameters are provided
-    if let Some(limit) = first {
-        query.limit(limit as u64);
-    }
-    if let Some(off) = offset {
-        query.offset(off as u64);
-    }
+    let service = &context.service;
 
+    let query = PurchaseCategories::list(None, None, first, offset);
     let result = service.db_adapter.query_many::<PurchaseCategory>(&query).await?;
 
     Ok(result)
 }
 
 pub async fn purchase_category(id: DbUuid, context: &AppState) -> FieldResult<PurchaseCategory> {
-    let service = context.service.lock().await;
+    let service = &context.service;
 
-    // Build the query with SeaQuery
-    let mut query = Query::select();
-    let query = query
-        .from(PurchaseCategories::Table)
-        .columns([
-            PurchaseCategories::Id,
-            PurchaseCategories::Name,
-            PurchaseCategories::Description,
-            PurchaseCategories::State,
-            PurchaseCategories::CreatedAt,
-            PurchaseCategories::UpdatedAt,
-        ])
-        .and_where(Expr::col(PurchaseCategories::Id).eq(id.to_string()));
-
-    // Execute the query
+    let query = PurchaseCategories::find_by_id(id);
     let result = service.db_adapter.query_one::<PurchaseCategory>(&query).await?;
 
     Ok(result)
 }
 
 pub async fn all_purchase_categories(context: &AppState) -> FieldResult<Vec<PurchaseCategory>> {
-    let service = context.service.lock().await;
-
-    // Build the query with SeaQuery
-    let mut query = Query::select();
-    let query = query
-        .from(PurchaseCategories::Table)
-        .columns([
-            PurchaseCategories::Id,
-            PurchaseCategories::Name,
-            PurchaseCategories::Description,
-            PurchaseCategories::State,
-            PurchaseCategories::CreatedAt,
-            PurchaseCategories::UpdatedAt,
-        ])
-        .and_where(Expr::col(PurchaseCategories::State).eq(PurchaseCategoryState::Active.to_string()));
+    let service = &context.service;
 
-    // Execute the query
+    let filter = PurchaseCategoryFilter {
+        state: Some(PurchaseCategoryState::Active),
+        ..Default::default()
+    };
+    let query = PurchaseCategories::list(Some(&filter), None, None, None);
     let result = service.db_adapter.query_many::<PurchaseCategory>(&query).await?;
 
     Ok(result)
 }
+
+connection!(PurchaseCategoryConnection, PurchaseCategoryEdge, PurchaseCategory);
+
+pub async fn purchase_categories_connection(
+    page: PageRequest,
+    filter: Option<PurchaseCategoryFilter>,
+    order_by: Option<PurchaseCategoryOrderBy>,
+    context: &AppState,
+) -> FieldResult<PurchaseCategoryConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
@@ -2,7 +2,7 @@ use crate::{
     core::{
         commands::{
             purchases::supplier_commands::{
-                CreateSupplierCommand, DeleteSupplierCommand, UpdateSupplierCommand,
+                CreateSupplierCommand, DeleteSupplierCommand, PurgeSupplierCommand, RestoreSupplierCommand, UpdateSupplierCommand,
             },
             Command,
         },
@@ -11,22 +11,34 @@ use crate::{
     },
     AppState,
 };
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 pub async fn create_supplier(supplier: SupplierNewInput, context: &AppState) -> FieldResult<Supplier> {
-    let mut service = context.service.lock().await;
-    let res = CreateSupplierCommand { supplier }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = CreateSupplierCommand { supplier }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn update_supplier(supplier: SupplierUpdateInput, context: &AppState) -> FieldResult<Supplier> {
-    let mut service = context.service.lock().await;
-    let res = UpdateSupplierCommand { supplier }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = UpdateSupplierCommand { supplier }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn delete_supplier(id: DbUuid, context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
-    let res = DeleteSupplierCommand { id }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = DeleteSupplierCommand { id, deleted_by: context.user.or(service.current_user()) }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn restore_supplier(id: DbUuid, context: &AppState) -> FieldResult<Supplier> {
+    let service = context.service.write().await;
+    let res = RestoreSupplierCommand { id }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn purge_supplier(id: DbUuid, context: &AppState) -> FieldResult<i32> {
+    let service = context.service.write().await;
+    let res = PurgeSupplierCommand { id }.exec(&service).await?;
     Ok(res)
 }
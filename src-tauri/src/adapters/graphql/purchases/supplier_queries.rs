@@ -1,10 +1,13 @@
-use sea_query::{Alias, Expr, Query};
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
     core::{
-        models::purchases::supplier_model::{Supplier, Suppliers},
+        db::pagination::PageRequest,
+        models::purchases::supplier_model::{Supplier, SupplierFilter, SupplierOrderBy, Suppliers},
         types::db_uuid::DbUuid,
     },
     AppState,
@@ -15,68 +18,39 @@ pub async fn suppliers(
     offset: Option<i32>,
     context: &AppState,
 ) -> FieldResult<Vec<Supplier>> {
-    let service = context.service.lock().await;
-
-    // Build the query with SeaQuery
-    let mut query_builder = Query::select();
-    let query = query_builder
-        .from(Suppliers::Table)
-        .columns([
-            Suppliers::Id,
-            Suppliers::Name,
-            Suppliers::Address,
-            Suppliers::Phone,
-            Suppliers::CreatedAt,
-            Suppliers::UpdatedAt,
-        ]);
-
-    // Apply pagination if parameters are provided
-    if let Some(limit) = first {
-        query.limit(limit as u64);
-    }
-    if let Some(off) = offset {
-        query.offset(off as u64);
-    }
+    let service = &context.service;
 
-    // Execute the query
+    let query = Suppliers::list(None, None, first, offset);
     let result = service.db_adapter.query_many::<Supplier>(&query).await?;
 
     Ok(result)
 }
 
 pub async fn total_suppliers(context: &AppState) -> FieldResult<i32> {
-    let service = context.service.lock().await;
+    let service = &context.service;
 
-    // Build the count query with SeaQuery
-    let mut query = Query::select();
-    let query = query
-        .from(Suppliers::Table)
-        .expr_as(Expr::col(Suppliers::Id).count(), Alias::new("count"));
-
-    // Execute the query
-    let result = service.db_adapter.query_one::<i64>(&query).await?;
+    let result = service.db_adapter.query_one::<i64>(&Suppliers::count(None)).await?;
 
     Ok(result as i32)
 }
 
 pub async fn supplier(id: DbUuid, context: &AppState) -> FieldResult<Supplier> {
-    let service = context.service.lock().await;
-
-    let mut query = Query::select();
-    let query = query
-        .from(Suppliers::Table)
-        .columns([
-            Suppliers::Id,
-            Suppliers::Name,
-            Suppliers::Address,
-            Suppliers::Phone,
-            Suppliers::CreatedAt,
-            Suppliers::UpdatedAt,
-        ])
-        .and_where(Expr::col(Suppliers::Id).eq(id.to_string()));
+    let service = &context.service;
 
-    // Execute the query
-    let result = service.db_adapter.query_one::<Supplier>(&query).await?;
+    let result = service.db_adapter.query_one::<Supplier>(&Suppliers::find_by_id(id)).await?;
 
     Ok(result)
 }
+
+connection!(SupplierConnection, SupplierEdge, Supplier);
+
+pub async fn suppliers_connection(
+    page: PageRequest,
+    filter: Option<SupplierFilter>,
+    order_by: Option<SupplierOrderBy>,
+    context: &AppState,
+) -> FieldResult<SupplierConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
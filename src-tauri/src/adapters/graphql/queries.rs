@@ -1,33 +1,107 @@
-use juniper::{graphql_object, FieldResult};
+use chrono::NaiveDateTime;
+use juniper::graphql_object;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     adapters::graphql::{catalog::variants::{item_variant_queries, variant_type_queries, variant_value_queries}, Query},
     core::{
+        db::pagination::PageRequest,
         models::{
-            auth::user_model::User,
+            auth::{
+                api_token_model::{ApiToken, ApiTokenFilter, ApiTokenOrderBy},
+                user_model::{User, UserFilter, UserOrderBy},
+            },
             catalog::{
-                discount_model::{Discount, DiscountState}, item_discount_model::ItemDiscount, item_group_model::ItemCategory, item_model::Item, item_variant_model::ItemVariant, variant_type_model::VariantType, variant_value_model::VariantValue
+                discount_model::{Discount, DiscountFilter, DiscountOrderBy, DiscountState},
+                item_discount_model::ItemDiscount,
+                item_group_model::{ItemCategory, ItemCategoryFilter, ItemCategoryOrderBy},
+                item_model::{Item, ItemFilter, ItemOrderBy},
+                item_variant_model::{ItemVariant, ItemVariantFilter, ItemVariantOrderBy},
+                price_list_model::{PriceList, PriceListFilter, PriceListOrderBy},
+                variant_type_model::{VariantType, VariantTypeFilter, VariantTypeOrderBy},
+                variant_value_model::{VariantValue, VariantValueFilter, VariantValueOrderBy},
             },
             common::{
-                brand_model::Brand, channel_model::Channel, tax_group_model::TaxGroup,
-                tax_model::Tax,
+                backup_model::Backup,
+                brand_model::{Brand, BrandFilter, BrandOrderBy},
+                channel_model::{Channel, ChannelFilter, ChannelOrderBy},
+                location_model::{Location, LocationFilter, LocationOrderBy},
+                tax_group_model::{TaxGroup, TaxGroupFilter, TaxGroupOrderBy},
+                tax_model::{Tax, TaxFilter, TaxOrderBy},
+            },
+            finance::{
+                account_mapping_model::AccountMapping,
+                cost_center_model::{CostCenter, CostCenterFilter, CostCenterOrderBy},
+                currency_model::{Currency, CurrencyFilter, CurrencyOrderBy},
+                exchange_rate_model::{ExchangeRate, ExchangeRateFilter, ExchangeRateOrderBy},
+                journal_model::JournalEntry,
+                payment_method_model::{PaymentMethod, PaymentMethodFilter, PaymentMethodOrderBy},
+                sales_order_payment_model::{
+                    SalesOrderPayment, SalesOrderPaymentFilter, SalesOrderPaymentOrderBy,
+                },
             },
-            finance::{cost_center_model::CostCenter, payment_method_model::PaymentMethod},
             purchases::{
-                expense_model::Expense, purchase_category_model::PurchaseCategory,
-                supplier_model::Supplier,
+                expense_model::{Expense, ExpenseFilter, ExpenseOrderBy},
+                purchase_category_model::{
+                    PurchaseCategory, PurchaseCategoryFilter, PurchaseCategoryOrderBy,
+                },
+                supplier_model::{Supplier, SupplierFilter, SupplierOrderBy},
             },
             sales::{
-                cart_model::Cart, customer_model::Customer,
-                sales_charge_type_model::SalesChargeType, sales_order_model::SalesOrder,
+                cart_model::{Cart, CartFilter, CartOrderBy},
+                customer_group_model::{CustomerGroup, CustomerGroupFilter, CustomerGroupOrderBy},
+                customer_model::{Customer, CustomerFilter, CustomerOrderBy},
+                price_override_reason_model::{
+                    PriceOverrideReason, PriceOverrideReasonFilter, PriceOverrideReasonOrderBy,
+                },
+                sales_charge_type_model::{
+                    SalesChargeType, SalesChargeTypeFilter, SalesChargeTypeOrderBy,
+                },
+                sales_order_model::{SalesOrder, SalesOrderFilter, SalesOrderOrderBy},
             },
         },
-        types::db_uuid::DbUuid,
+        types::{db_uuid::DbUuid, money::Money, rate::Rate},
     },
     AppState,
 };
 
-use super::analytics::analytics_overview_model::AnalyticsOverview;
+use super::{
+    analytics::{analytics_overview_model::AnalyticsOverview, price_override_stat_model::PriceOverrideStat},
+    auth::{api_token_queries::ApiTokenConnection, user_queries::UserConnection},
+    catalog::{
+        discount_queries::DiscountConnection,
+        item_group_queries::ItemCategoryConnection,
+        item_queries::ItemConnection,
+        price_list_queries::PriceListConnection,
+        variants::{
+            item_variant_queries::ItemVariantConnection,
+            variant_type_queries::VariantTypeConnection,
+            variant_value_queries::VariantValueConnection,
+        },
+    },
+    common::{
+        brand_queries::BrandConnection, channel_queries::ChannelConnection,
+        location_queries::LocationConnection, tax_group_queries::TaxGroupConnection,
+        tax_queries::TaxConnection,
+    },
+    finance::{
+        cost_center_queries::CostCenterConnection,
+        currency_queries::{CurrencyConnection, ExchangeRateConnection},
+        payment_method_queries::PaymentMethodConnection,
+        sales_order_payment_queries::SalesOrderPaymentConnection,
+    },
+    purchases::{
+        expense_queries::ExpenseConnection, purchase_category_queries::PurchaseCategoryConnection,
+        supplier_queries::SupplierConnection,
+    },
+    sales::{
+        cart_queries::CartConnection, customer_group_queries::CustomerGroupConnection,
+        customer_queries::CustomerConnection,
+        price_override_reason_queries::PriceOverrideReasonConnection,
+        sales_charge_type_queries::SalesChargeTypeConnection,
+        sales_order_queries::SalesOrderConnection,
+    },
+};
 
 #[graphql_object(context = AppState)]
 impl Query {
@@ -35,6 +109,7 @@ impl Query {
         "1.0.0"
     }
 
+    #[graphql(deprecated = "Use `itemCategoriesConnection`")]
     async fn item_categories(
         &self,
         first: Option<i32>,
@@ -44,10 +119,25 @@ impl Query {
         super::catalog::item_group_queries::item_categories(first, offset, context).await
     }
 
+    async fn item_categories_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<ItemCategoryFilter>,
+        order_by: Option<ItemCategoryOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<ItemCategoryConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::catalog::item_group_queries::item_categories_connection(page, filter, order_by, context).await
+    }
+
     async fn items_category(&self, id: DbUuid, context: &AppState) -> FieldResult<ItemCategory> {
         super::catalog::item_group_queries::items_category(id, context).await
     }
 
+    #[graphql(deprecated = "Use `itemsConnection`")]
     async fn items(
         &self,
         first: Option<i32>,
@@ -57,10 +147,47 @@ impl Query {
         super::catalog::item_queries::items(first, offset, context).await
     }
 
+    async fn items_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<ItemFilter>,
+        order_by: Option<ItemOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<ItemConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::catalog::item_queries::items_connection(page, filter, order_by, context).await
+    }
+
     async fn item(&self, id: DbUuid, context: &AppState) -> FieldResult<Item> {
         super::catalog::item_queries::item(id, context).await
     }
 
+    /// Items matching what was typed, best matches first. Every word is matched as a prefix of
+    /// the name, description, SKUs or barcodes.
+    async fn search_items(
+        &self,
+        query: String,
+        category_id: Option<DbUuid>,
+        limit: Option<i32>,
+        context: &AppState,
+    ) -> FieldResult<Vec<Item>> {
+        super::catalog::item_queries::search_items(query, category_id, limit, context).await
+    }
+
+    /// The quantity in stock of an item, or of one of its variants
+    async fn stock_on_hand(
+        &self,
+        item_id: DbUuid,
+        item_variant_id: Option<DbUuid>,
+        context: &AppState,
+    ) -> FieldResult<i32> {
+        super::catalog::stock_movement_queries::stock_on_hand(item_id, item_variant_id, context).await
+    }
+
+    #[graphql(deprecated = "Use `purchaseCategoriesConnection`")]
     async fn purchase_categories(
         &self,
         first: Option<i32>,
@@ -70,6 +197,20 @@ impl Query {
         super::purchases::purchase_category_queries::purchase_categories(first, offset, context).await
     }
 
+    async fn purchase_categories_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<PurchaseCategoryFilter>,
+        order_by: Option<PurchaseCategoryOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<PurchaseCategoryConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::purchases::purchase_category_queries::purchase_categories_connection(page, filter, order_by, context).await
+    }
+
     async fn purchase_category(&self, id: DbUuid, context: &AppState) -> FieldResult<PurchaseCategory> {
         super::purchases::purchase_category_queries::purchase_category(id, context).await
     }
@@ -78,6 +219,7 @@ impl Query {
         super::purchases::purchase_category_queries::all_purchase_categories(context).await
     }
 
+    #[graphql(deprecated = "Use `usersConnection`")]
     async fn users(
         &self,
         first: Option<i32>,
@@ -87,10 +229,44 @@ impl Query {
         super::auth::user_queries::users(first, offset, context).await
     }
 
+    async fn users_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<UserFilter>,
+        order_by: Option<UserOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<UserConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::auth::user_queries::users_connection(page, filter, order_by, context).await
+    }
+
     async fn user(&self, id: DbUuid, context: &AppState) -> FieldResult<User> {
         super::auth::user_queries::user(id, context).await
     }
 
+    /// The API tokens issued to a user, including revoked ones
+    async fn api_tokens(&self, user_id: DbUuid, context: &AppState) -> FieldResult<Vec<ApiToken>> {
+        super::auth::api_token_queries::api_tokens(user_id, context).await
+    }
+
+    async fn api_tokens_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<ApiTokenFilter>,
+        order_by: Option<ApiTokenOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<ApiTokenConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::auth::api_token_queries::api_tokens_connection(page, filter, order_by, context).await
+    }
+
+    #[graphql(deprecated = "Use `customersConnection`")]
     async fn customers(
         &self,
         first: Option<i32>,
@@ -100,6 +276,21 @@ impl Query {
         super::sales::customer_queries::customers(first, offset, context).await
     }
 
+    async fn customers_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<CustomerFilter>,
+        order_by: Option<CustomerOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<CustomerConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::sales::customer_queries::customers_connection(page, filter, order_by, context).await
+    }
+
+    #[graphql(deprecated = "Use `totalCount` on `customersConnection`")]
     async fn total_customers(&self, context: &AppState) -> FieldResult<i32> {
         super::sales::customer_queries::total_customers(context).await
     }
@@ -112,6 +303,7 @@ impl Query {
         super::sales::customer_queries::customer_by_phone(phone, context).await
     }
 
+    #[graphql(deprecated = "Use `salesOrdersConnection`")]
     async fn sales_orders(
         &self,
         first: Option<i32>,
@@ -121,6 +313,21 @@ impl Query {
         super::sales::sales_order_queries::sales_orders(first, offset, context).await
     }
 
+    async fn sales_orders_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<SalesOrderFilter>,
+        order_by: Option<SalesOrderOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<SalesOrderConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::sales::sales_order_queries::sales_orders_connection(page, filter, order_by, context).await
+    }
+
+    #[graphql(deprecated = "Use `totalCount` on `salesOrdersConnection`")]
     async fn total_sales_orders(&self, context: &AppState) -> FieldResult<i32> {
         super::sales::sales_order_queries::total_sales_orders(context).await
     }
@@ -129,6 +336,7 @@ impl Query {
         super::sales::sales_order_queries::sales_order(id, context).await
     }
 
+    #[graphql(deprecated = "Use `cartsConnection`")]
     async fn carts(
         &self,
         first: Option<i32>,
@@ -138,6 +346,31 @@ impl Query {
         super::sales::cart_queries::carts(first, offset, context).await
     }
 
+    async fn carts_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<CartFilter>,
+        order_by: Option<CartOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<CartConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::sales::cart_queries::carts_connection(page, filter, order_by, context).await
+    }
+
+    async fn parked_carts(
+        &self,
+        location_id: Option<DbUuid>,
+        first: Option<i32>,
+        offset: Option<i32>,
+        context: &AppState,
+    ) -> FieldResult<Vec<Cart>> {
+        super::sales::cart_queries::parked_carts(location_id, first, offset, context).await
+    }
+
+    #[graphql(deprecated = "Use `totalCount` on `cartsConnection`")]
     async fn total_carts(&self, context: &AppState) -> FieldResult<i32> {
         super::sales::cart_queries::total_carts(context).await
     }
@@ -146,6 +379,7 @@ impl Query {
         super::sales::cart_queries::cart(id, context).await
     }
 
+    #[graphql(deprecated = "Use `taxesConnection`")]
     async fn taxes(
         &self,
         first: Option<i32>,
@@ -155,6 +389,21 @@ impl Query {
         super::common::tax_queries::taxes(first, offset, context).await
     }
 
+    async fn taxes_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<TaxFilter>,
+        order_by: Option<TaxOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<TaxConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::common::tax_queries::taxes_connection(page, filter, order_by, context).await
+    }
+
+    #[graphql(deprecated = "Use `totalCount` on `taxesConnection`")]
     async fn total_taxes(&self, context: &AppState) -> FieldResult<i32> {
         super::common::tax_queries::total_taxes(context).await
     }
@@ -163,6 +412,7 @@ impl Query {
         super::common::tax_queries::tax(id, context).await
     }
 
+    #[graphql(deprecated = "Use `taxGroupsConnection`")]
     async fn tax_groups(
         &self,
         first: Option<i32>,
@@ -172,6 +422,21 @@ impl Query {
         super::common::tax_group_queries::tax_groups(first, offset, context).await
     }
 
+    async fn tax_groups_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<TaxGroupFilter>,
+        order_by: Option<TaxGroupOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<TaxGroupConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::common::tax_group_queries::tax_groups_connection(page, filter, order_by, context).await
+    }
+
+    #[graphql(deprecated = "Use `totalCount` on `taxGroupsConnection`")]
     async fn total_tax_groups(&self, context: &AppState) -> FieldResult<i32> {
         super::common::tax_group_queries::total_tax_groups(context).await
     }
@@ -184,6 +449,20 @@ impl Query {
         super::common::channel_queries::get_channels(context).await
     }
 
+    async fn channels_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<ChannelFilter>,
+        order_by: Option<ChannelOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<ChannelConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::common::channel_queries::channels_connection(page, filter, order_by, context).await
+    }
+
     async fn active_channels(&self, context: &AppState) -> FieldResult<Vec<Channel>> {
         super::common::channel_queries::get_active_channels(context).await
     }
@@ -196,6 +475,20 @@ impl Query {
         super::common::brand_queries::get_brands(context).await
     }
 
+    async fn brands_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<BrandFilter>,
+        order_by: Option<BrandOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<BrandConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::common::brand_queries::brands_connection(page, filter, order_by, context).await
+    }
+
     async fn active_brands(&self, context: &AppState) -> FieldResult<Vec<Brand>> {
         super::common::brand_queries::get_active_brands(context).await
     }
@@ -204,6 +497,24 @@ impl Query {
         super::common::brand_queries::get_brand(id, context).await
     }
 
+    async fn locations_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<LocationFilter>,
+        order_by: Option<LocationOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<LocationConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::common::location_queries::locations_connection(page, filter, order_by, context).await
+    }
+
+    async fn location(&self, id: DbUuid, context: &AppState) -> FieldResult<Location> {
+        super::common::location_queries::get_location(id, context).await
+    }
+
     async fn analytics_overview(
         &self,
         days: Option<i32>,
@@ -212,6 +523,15 @@ impl Query {
         super::analytics::analytics_queries::analytics_overview(days, context).await
     }
 
+    async fn price_override_stats(
+        &self,
+        days: Option<i32>,
+        context: &AppState,
+    ) -> FieldResult<Vec<PriceOverrideStat>> {
+        super::analytics::analytics_queries::price_override_stats(days, context).await
+    }
+
+    #[graphql(deprecated = "Use `suppliersConnection`")]
     async fn suppliers(
         &self,
         first: Option<i32>,
@@ -221,6 +541,21 @@ impl Query {
         super::purchases::supplier_queries::suppliers(first, offset, context).await
     }
 
+    async fn suppliers_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<SupplierFilter>,
+        order_by: Option<SupplierOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<SupplierConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::purchases::supplier_queries::suppliers_connection(page, filter, order_by, context).await
+    }
+
+    #[graphql(deprecated = "Use `totalCount` on `suppliersConnection`")]
     async fn total_suppliers(&self, context: &AppState) -> FieldResult<i32> {
         super::purchases::supplier_queries::total_suppliers(context).await
     }
@@ -229,6 +564,7 @@ impl Query {
         super::purchases::supplier_queries::supplier(id, context).await
     }
 
+    #[graphql(deprecated = "Use `expensesConnection`")]
     async fn expenses(
         &self,
         first: Option<i32>,
@@ -248,6 +584,21 @@ impl Query {
         ).await
     }
 
+    async fn expenses_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<ExpenseFilter>,
+        order_by: Option<ExpenseOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<ExpenseConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::purchases::expense_queries::expenses_connection(page, filter, order_by, context).await
+    }
+
+    #[graphql(deprecated = "Use `totalCount` on `expensesConnection`")]
     async fn total_expenses(
         &self,
         cost_center_id: Option<DbUuid>,
@@ -278,6 +629,7 @@ impl Query {
     }
 
     // Cost Center Queries
+    #[graphql(deprecated = "Use `costCentersConnection`")]
     async fn cost_centers(
         &self,
         first: Option<i32>,
@@ -287,6 +639,20 @@ impl Query {
         super::finance::cost_center_queries::cost_centers(first, offset, context).await
     }
 
+    async fn cost_centers_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<CostCenterFilter>,
+        order_by: Option<CostCenterOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<CostCenterConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::finance::cost_center_queries::cost_centers_connection(page, filter, order_by, context).await
+    }
+
     async fn cost_center(&self, id: DbUuid, context: &AppState) -> FieldResult<CostCenter> {
         super::finance::cost_center_queries::cost_center(id, context).await
     }
@@ -295,11 +661,13 @@ impl Query {
         super::finance::cost_center_queries::all_cost_centers(context).await
     }
 
+    #[graphql(deprecated = "Use `totalCount` on `costCentersConnection`")]
     async fn total_cost_centers(&self, context: &AppState) -> FieldResult<i32> {
         super::finance::cost_center_queries::total_cost_centers(context).await
     }
 
     // Payment Method Queries
+    #[graphql(deprecated = "Use `paymentMethodsConnection`")]
     async fn payment_methods(
         &self,
         first: Option<i32>,
@@ -309,6 +677,20 @@ impl Query {
         super::finance::payment_method_queries::payment_methods(first, offset, context).await
     }
 
+    async fn payment_methods_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<PaymentMethodFilter>,
+        order_by: Option<PaymentMethodOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<PaymentMethodConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::finance::payment_method_queries::payment_methods_connection(page, filter, order_by, context).await
+    }
+
     async fn payment_method(&self, id: DbUuid, context: &AppState) -> FieldResult<PaymentMethod> {
         super::finance::payment_method_queries::payment_method(id, context).await
     }
@@ -317,20 +699,112 @@ impl Query {
         super::finance::payment_method_queries::all_payment_methods(context).await
     }
 
+    #[graphql(deprecated = "Use `totalCount` on `paymentMethodsConnection`")]
     async fn total_payment_methods(&self, context: &AppState) -> FieldResult<i32> {
         super::finance::payment_method_queries::total_payment_methods(context).await
     }
 
+    // Currency Queries
+    async fn currencies(
+        &self,
+        active_only: Option<bool>,
+        context: &AppState,
+    ) -> FieldResult<Vec<Currency>> {
+        super::finance::currency_queries::currencies(active_only, context).await
+    }
+
+    async fn currencies_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<CurrencyFilter>,
+        order_by: Option<CurrencyOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<CurrencyConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::finance::currency_queries::currencies_connection(page, filter, order_by, context).await
+    }
+
+    #[graphql(deprecated = "Use `exchangeRatesConnection`")]
+    async fn exchange_rates(
+        &self,
+        currency_code: String,
+        first: Option<i32>,
+        offset: Option<i32>,
+        context: &AppState,
+    ) -> FieldResult<Vec<ExchangeRate>> {
+        super::finance::currency_queries::exchange_rates(currency_code, first, offset, context)
+            .await
+    }
+
+    async fn exchange_rates_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<ExchangeRateFilter>,
+        order_by: Option<ExchangeRateOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<ExchangeRateConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::finance::currency_queries::exchange_rates_connection(page, filter, order_by, context).await
+    }
+
+    async fn current_exchange_rate(
+        &self,
+        currency_code: String,
+        context: &AppState,
+    ) -> FieldResult<Rate> {
+        super::finance::currency_queries::current_exchange_rate(currency_code, context).await
+    }
+
     async fn sales_order_payments(
         &self,
         order_id: DbUuid,
         context: &AppState,
-    ) -> FieldResult<Vec<crate::core::models::finance::sales_order_payment_model::SalesOrderPayment>>
-    {
+    ) -> FieldResult<Vec<SalesOrderPayment>> {
         super::finance::sales_order_payment_queries::sales_order_payments(context, order_id).await
     }
 
+    async fn sales_order_payments_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<SalesOrderPaymentFilter>,
+        order_by: Option<SalesOrderPaymentOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<SalesOrderPaymentConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::finance::sales_order_payment_queries::sales_order_payments_connection(page, filter, order_by, context).await
+    }
+
+    async fn account_mappings(&self, context: &AppState) -> FieldResult<Vec<AccountMapping>> {
+        super::finance::account_mapping_queries::account_mappings(context).await
+    }
+
+    /// The balanced entries for the sales, voids, payments and expenses of `[from, to)`
+    async fn journal(
+        &self,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        cost_center_id: Option<DbUuid>,
+        context: &AppState,
+    ) -> FieldResult<Vec<JournalEntry>> {
+        super::finance::journal_queries::journal(from, to, cost_center_id, context).await
+    }
+
+    /// The backups in the backup directory, newest first
+    async fn backups(&self, context: &AppState) -> FieldResult<Vec<Backup>> {
+        super::common::backup_queries::backups(context).await
+    }
+
     // Add new discount queries
+    #[graphql(deprecated = "Use `discountsConnection`")]
     async fn discounts(
         &self,
         first: Option<i32>,
@@ -341,11 +815,26 @@ impl Query {
         super::catalog::discount_queries::discounts(first, offset, state, context).await
     }
 
+    async fn discounts_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<DiscountFilter>,
+        order_by: Option<DiscountOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<DiscountConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::catalog::discount_queries::discounts_connection(page, filter, order_by, context).await
+    }
+
     async fn discount(&self, id: DbUuid, context: &AppState) -> FieldResult<Discount> {
         super::catalog::discount_queries::discount(id, context).await
     }
 
     // Sales Charge Type Queries
+    #[graphql(deprecated = "Use `salesChargeTypesConnection`")]
     async fn sales_charge_types(
         &self,
         first: Option<i32>,
@@ -355,15 +844,135 @@ impl Query {
         super::sales::sales_charge_type_queries::sales_charge_types(first, offset, context).await
     }
 
+    async fn sales_charge_types_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<SalesChargeTypeFilter>,
+        order_by: Option<SalesChargeTypeOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<SalesChargeTypeConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::sales::sales_charge_type_queries::sales_charge_types_connection(page, filter, order_by, context).await
+    }
+
     async fn sales_charge_type(&self, id: DbUuid, context: &AppState) -> FieldResult<SalesChargeType> {
         super::sales::sales_charge_type_queries::sales_charge_type(id, context).await
     }
 
+    #[graphql(deprecated = "Use `totalCount` on `salesChargeTypesConnection`")]
     async fn sales_charge_types_count(&self, context: &AppState) -> FieldResult<i32> {
         super::sales::sales_charge_type_queries::sales_charge_types_count(context).await
     }
 
+    // Price Override Reason Queries
+    #[graphql(deprecated = "Use `priceOverrideReasonsConnection`")]
+    async fn price_override_reasons(
+        &self,
+        first: Option<i32>,
+        offset: Option<i32>,
+        active_only: Option<bool>,
+        context: &AppState,
+    ) -> FieldResult<Vec<PriceOverrideReason>> {
+        super::sales::price_override_reason_queries::price_override_reasons(first, offset, active_only, context).await
+    }
+
+    async fn price_override_reasons_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<PriceOverrideReasonFilter>,
+        order_by: Option<PriceOverrideReasonOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<PriceOverrideReasonConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::sales::price_override_reason_queries::price_override_reasons_connection(page, filter, order_by, context).await
+    }
+
+    async fn price_override_reason(&self, id: DbUuid, context: &AppState) -> FieldResult<PriceOverrideReason> {
+        super::sales::price_override_reason_queries::price_override_reason(id, context).await
+    }
+
+    // Customer Group Queries
+    #[graphql(deprecated = "Use `customerGroupsConnection`")]
+    async fn customer_groups(
+        &self,
+        first: Option<i32>,
+        offset: Option<i32>,
+        context: &AppState,
+    ) -> FieldResult<Vec<CustomerGroup>> {
+        super::sales::customer_group_queries::customer_groups(first, offset, context).await
+    }
+
+    async fn customer_groups_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<CustomerGroupFilter>,
+        order_by: Option<CustomerGroupOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<CustomerGroupConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::sales::customer_group_queries::customer_groups_connection(page, filter, order_by, context).await
+    }
+
+    async fn customer_group(&self, id: DbUuid, context: &AppState) -> FieldResult<CustomerGroup> {
+        super::sales::customer_group_queries::customer_group(id, context).await
+    }
+
+    // Price List Queries
+    #[graphql(deprecated = "Use `priceListsConnection`")]
+    async fn price_lists(
+        &self,
+        first: Option<i32>,
+        offset: Option<i32>,
+        context: &AppState,
+    ) -> FieldResult<Vec<PriceList>> {
+        super::catalog::price_list_queries::price_lists(first, offset, context).await
+    }
+
+    async fn price_lists_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<PriceListFilter>,
+        order_by: Option<PriceListOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<PriceListConnection> {
+        let page = PageRequest { first, after, last, before };
+        super::catalog::price_list_queries::price_lists_connection(page, filter, order_by, context).await
+    }
+
+    async fn price_list(&self, id: DbUuid, context: &AppState) -> FieldResult<PriceList> {
+        super::catalog::price_list_queries::price_list(id, context).await
+    }
+
+    async fn item_price(
+        &self,
+        item_id: DbUuid,
+        variant_id: Option<DbUuid>,
+        quantity: Option<i32>,
+        channel_id: Option<DbUuid>,
+        location_id: Option<DbUuid>,
+        customer_id: Option<DbUuid>,
+        context: &AppState,
+    ) -> FieldResult<Money> {
+        super::catalog::price_list_queries::item_price(
+            item_id, variant_id, quantity, channel_id, location_id, customer_id, context,
+        )
+        .await
+    }
+
     // Variant Type Queries
+    #[graphql(deprecated = "Use `variantTypesConnection`")]
     async fn variant_types(
         &self,
         first: Option<i32>,
@@ -373,15 +982,31 @@ impl Query {
         variant_type_queries::get_variant_types(first, offset, context).await
     }
 
+    async fn variant_types_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<VariantTypeFilter>,
+        order_by: Option<VariantTypeOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<VariantTypeConnection> {
+        let page = PageRequest { first, after, last, before };
+        variant_type_queries::variant_types_connection(page, filter, order_by, context).await
+    }
+
     async fn variant_type(&self, id: DbUuid, context: &AppState) -> FieldResult<VariantType> {
         variant_type_queries::get_variant_type(id, context).await
     }
 
+    #[graphql(deprecated = "Use `totalCount` on `variantTypesConnection`")]
     async fn total_variant_types(&self, context: &AppState) -> FieldResult<i32> {
         variant_type_queries::get_total_variant_types(context).await
     }
 
     // Variant Value Queries
+    #[graphql(deprecated = "Use `variantValuesConnection`")]
     async fn variant_values(
         &self,
         variant_type_id: Option<DbUuid>,
@@ -392,11 +1017,26 @@ impl Query {
         variant_value_queries::get_variant_values(variant_type_id, first, offset, context).await
     }
 
+    async fn variant_values_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<VariantValueFilter>,
+        order_by: Option<VariantValueOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<VariantValueConnection> {
+        let page = PageRequest { first, after, last, before };
+        variant_value_queries::variant_values_connection(page, filter, order_by, context).await
+    }
+
     async fn variant_value(&self, id: DbUuid, context: &AppState) -> FieldResult<VariantValue> {
         variant_value_queries::get_variant_value(id, context).await
     }
 
     // Item Variant Queries
+    #[graphql(deprecated = "Use `itemVariantsConnection`")]
     async fn item_variants(
         &self,
         item_id: Option<DbUuid>,
@@ -407,10 +1047,33 @@ impl Query {
         item_variant_queries::get_item_variants(item_id, first, offset, context).await
     }
 
+    async fn item_variants_connection(
+        &self,
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        filter: Option<ItemVariantFilter>,
+        order_by: Option<ItemVariantOrderBy>,
+        context: &AppState,
+    ) -> FieldResult<ItemVariantConnection> {
+        let page = PageRequest { first, after, last, before };
+        item_variant_queries::item_variants_connection(page, filter, order_by, context).await
+    }
+
     async fn item_variant(&self, id: DbUuid, context: &AppState) -> FieldResult<ItemVariant> {
         item_variant_queries::get_item_variant(id, context).await
     }
 
+    /// The variant a scanned barcode belongs to, or null when none has it
+    async fn item_by_barcode(
+        &self,
+        barcode: String,
+        context: &AppState,
+    ) -> FieldResult<Option<ItemVariant>> {
+        item_variant_queries::get_item_variant_by_barcode(barcode, context).await
+    }
+
     // Item Discount Queries
     async fn item_discounts(
         &self,
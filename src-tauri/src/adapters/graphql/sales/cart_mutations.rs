@@ -1,30 +1,108 @@
 use crate::{
     core::{
         commands::{
-            sales::cart_commands::{CreateCartCommand, DeleteCartCommand, UpdateCartCommand},
+            sales::cart_commands::{
+                AddCartChargeCommand, AddCartLineCommand, CheckoutCartCommand, CreateCartCommand,
+                DeleteCartCommand, ParkCartCommand, RecallCartCommand, RemoveCartChargeCommand,
+                RemoveCartLineCommand, UpdateCartCommand, UpdateCartLineCommand,
+            },
             Command,
         },
-        models::sales::cart_model::{Cart, CartNewInput, CartUpdateInput},
+        models::sales::{
+            cart_model::{
+                Cart, CartCharge, CartChargeNewInput, CartLine, CartLineNewInput,
+                CartLineUpdateInput, CartNewInput, CartUpdateInput, CheckoutCartInput, ParkCartInput,
+                RecallCartInput,
+            },
+            sales_order_model::SalesOrder,
+        },
         types::db_uuid::DbUuid,
     },
     AppState,
 };
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
+use uuid::Uuid;
+
+// The user of an API token, otherwise a placeholder until the desktop session keeps one
+fn get_current_user_id(context: &AppState) -> DbUuid {
+    // TODO: Implement user ID retrieval from the desktop session
+    context.user.unwrap_or_else(|| Uuid::nil().into())
+}
 
 pub async fn create_cart(cart: CartNewInput, context: &AppState) -> FieldResult<Cart> {
-    let mut service = context.service.lock().await;
-    let res = CreateCartCommand { cart }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = CreateCartCommand { cart }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn update_cart(cart: CartUpdateInput, context: &AppState) -> FieldResult<Cart> {
-    let mut service = context.service.lock().await;
-    let res = UpdateCartCommand { cart }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = UpdateCartCommand { cart }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn delete_cart(id: DbUuid, context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
-    let res = DeleteCartCommand { id }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = DeleteCartCommand { id }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn add_cart_line(line: CartLineNewInput, context: &AppState) -> FieldResult<CartLine> {
+    let service = context.service.write().await;
+    let res = AddCartLineCommand { line }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn update_cart_line(line: CartLineUpdateInput, context: &AppState) -> FieldResult<CartLine> {
+    let service = context.service.write().await;
+    let res = UpdateCartLineCommand { line }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn remove_cart_line(id: DbUuid, context: &AppState) -> FieldResult<CartLine> {
+    let service = context.service.write().await;
+    let res = RemoveCartLineCommand { id }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn add_cart_charge(charge: CartChargeNewInput, context: &AppState) -> FieldResult<CartCharge> {
+    let service = context.service.write().await;
+    let res = AddCartChargeCommand { charge }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn remove_cart_charge(id: DbUuid, context: &AppState) -> FieldResult<DbUuid> {
+    let service = context.service.write().await;
+    let res = RemoveCartChargeCommand { id }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn checkout_cart(checkout: CheckoutCartInput, context: &AppState) -> FieldResult<SalesOrder> {
+    let service = context.service.write().await;
+    let current_user_id = get_current_user_id(context);
+    let res = CheckoutCartCommand {
+        checkout,
+        created_by_user_id: current_user_id,
+    }
+    .exec(&service)
+    .await?;
+    Ok(res)
+}
+
+pub async fn park_cart(cart: ParkCartInput, context: &AppState) -> FieldResult<Cart> {
+    let service = context.service.write().await;
+    let current_user_id = get_current_user_id(context);
+    let res = ParkCartCommand {
+        cart,
+        parked_by_user_id: current_user_id,
+    }
+    .exec(&service)
+    .await?;
+    Ok(res)
+}
+
+pub async fn recall_cart(cart: RecallCartInput, context: &AppState) -> FieldResult<Cart> {
+    let service = context.service.write().await;
+    let res = RecallCartCommand { cart }.exec(&service).await?;
     Ok(res)
 }
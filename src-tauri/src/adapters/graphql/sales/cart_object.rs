@@ -1,15 +1,21 @@
 use chrono::NaiveDateTime;
 use sea_query::{Expr, Query};
-use juniper::{graphql_object, FieldResult};
+use juniper::graphql_object;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
     adapters::outgoing::database::DatabaseAdapter,
     core::{
-        models::sales::{
-            cart_model::Cart,
-            customer_model::{Customer, Customers},
+        commands::sales::cart_commands::{cart_charges, cart_lines},
+        models::{
+            catalog::item_model::{Item, Items},
+            sales::{
+                cart_model::{Cart, CartCharge, CartLine, CartStatus},
+                customer_model::{Customer, Customers},
+                sales_charge_type_model::{SalesChargeType, SalesChargeTypes},
+            },
         },
-        types::db_uuid::DbUuid,
+        types::{db_uuid::DbUuid, money::Money},
     },
     AppState,
 };
@@ -20,14 +26,52 @@ impl Cart {
         self.id
     }
 
-    pub fn cart_data(&self) -> String {
-        self.cart_data.clone()
-    }
-
     pub fn customer_id(&self) -> Option<DbUuid> {
         self.customer_id
     }
 
+    pub fn discount_id(&self) -> Option<DbUuid> {
+        self.discount_id
+    }
+
+    pub fn notes(&self) -> Option<String> {
+        self.notes.clone()
+    }
+
+    /// Cart contents saved before carts had lines, kept when they could not be converted
+    pub fn legacy_cart_data(&self) -> Option<String> {
+        self.legacy_cart_data.clone()
+    }
+
+    pub fn status(&self) -> CartStatus {
+        self.status
+    }
+
+    pub fn label(&self) -> Option<String> {
+        self.label.clone()
+    }
+
+    pub fn hold_reason(&self) -> Option<String> {
+        self.hold_reason.clone()
+    }
+
+    pub fn parked_by(&self) -> Option<DbUuid> {
+        self.parked_by
+    }
+
+    /// Terminal the cart is open on, or was parked from
+    pub fn terminal_id(&self) -> Option<String> {
+        self.terminal_id.clone()
+    }
+
+    pub fn location_id(&self) -> Option<DbUuid> {
+        self.location_id
+    }
+
+    pub fn held_since(&self) -> Option<NaiveDateTime> {
+        self.held_since
+    }
+
     pub fn created_at(&self) -> NaiveDateTime {
         self.created_at
     }
@@ -39,7 +83,7 @@ impl Cart {
     // Relationships
     pub async fn customer(&self, context: &AppState) -> FieldResult<Option<Customer>> {
         if let Some(customer_id) = self.customer_id {
-            let service = context.service.lock().await;
+            let service = &context.service;
 
             let mut query = Query::select();
             let query = query
@@ -50,8 +94,10 @@ impl Cart {
                     Customers::Email,
                     Customers::Phone,
                     Customers::Address,
+                    Customers::CustomerGroupId,
                     Customers::CreatedAt,
                     Customers::UpdatedAt,
+                    Customers::Version,
                 ])
                 .and_where(Expr::col(Customers::Id).eq(customer_id.to_string()));
 
@@ -62,4 +108,132 @@ impl Cart {
             Ok(None)
         }
     }
+
+    pub async fn lines(&self, context: &AppState) -> FieldResult<Vec<CartLine>> {
+        let service = &context.service;
+        let lines = cart_lines(&service, self.id).await?;
+        Ok(lines)
+    }
+
+    pub async fn charges(&self, context: &AppState) -> FieldResult<Vec<CartCharge>> {
+        let service = &context.service;
+        let charges = cart_charges(&service, self.id).await?;
+        Ok(charges)
+    }
+}
+
+#[graphql_object(context = AppState)]
+impl CartLine {
+    pub fn id(&self) -> DbUuid {
+        self.id
+    }
+
+    pub fn cart_id(&self) -> DbUuid {
+        self.cart_id
+    }
+
+    pub fn item_id(&self) -> DbUuid {
+        self.item_id
+    }
+
+    pub fn variant_id(&self) -> Option<DbUuid> {
+        self.variant_id
+    }
+
+    pub fn quantity(&self) -> i32 {
+        self.quantity
+    }
+
+    pub fn discount_id(&self) -> Option<DbUuid> {
+        self.discount_id
+    }
+
+    pub fn note(&self) -> Option<String> {
+        self.note.clone()
+    }
+
+    pub fn override_price(&self) -> Option<Money> {
+        self.override_price
+    }
+
+    pub fn override_reason_id(&self) -> Option<DbUuid> {
+        self.override_reason_id
+    }
+
+    pub fn override_note(&self) -> Option<String> {
+        self.override_note.clone()
+    }
+
+    pub fn override_approved_by(&self) -> Option<DbUuid> {
+        self.override_approved_by
+    }
+
+    pub fn created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+
+    // Relationships
+    pub async fn item(&self, context: &AppState) -> FieldResult<Item> {
+        let service = &context.service;
+
+        let mut query = Query::select();
+        let stmt = query
+            .from(Items::Table)
+            .columns(Items::all_columns())
+            .and_where(Expr::col(Items::Id).eq(self.item_id.to_string()));
+
+        let item = service.db_adapter.query_one::<Item>(&stmt).await?;
+
+        Ok(item)
+    }
+}
+
+#[graphql_object(context = AppState)]
+impl CartCharge {
+    pub fn id(&self) -> DbUuid {
+        self.id
+    }
+
+    pub fn cart_id(&self) -> DbUuid {
+        self.cart_id
+    }
+
+    pub fn charge_type_id(&self) -> DbUuid {
+        self.charge_type_id
+    }
+
+    pub fn amount(&self) -> Money {
+        self.amount
+    }
+
+    pub fn tax_group_id(&self) -> Option<DbUuid> {
+        self.tax_group_id
+    }
+
+    pub fn created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+
+    // Relationships
+    pub async fn charge_type(&self, context: &AppState) -> FieldResult<SalesChargeType> {
+        let service = &context.service;
+
+        let mut query = Query::select();
+        let stmt = query
+            .from(SalesChargeTypes::Table)
+            .columns(SalesChargeTypes::all_columns())
+            .and_where(Expr::col(SalesChargeTypes::Id).eq(self.charge_type_id.to_string()));
+
+        let charge_type = service.db_adapter.query_one::<SalesChargeType>(&stmt).await?;
+
+        Ok(charge_type)
+    }
 }
@@ -1,10 +1,13 @@
-use sea_query::{Alias, Expr, Query};
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
     core::{
-        models::sales::cart_model::{Cart, Carts},
+        db::pagination::{PageRequest, SortDirection},
+        models::sales::cart_model::{Cart, CartFilter, CartOrderBy, CartStatus, Carts},
         types::db_uuid::DbUuid,
     },
     AppState,
@@ -15,67 +18,60 @@ pub async fn carts(
     offset: Option<i32>,
     context: &AppState,
 ) -> FieldResult<Vec<Cart>> {
-    let service = context.service.lock().await;
-
-    // Build the query with SeaQuery
-    let mut query_builder = Query::select();
-    let query = query_builder
-        .from(Carts::Table)
-        .columns([
-            Carts::Id,
-            Carts::CartData,
-            Carts::CustomerId,
-            Carts::CreatedAt,
-            Carts::UpdatedAt,
-        ]);
-
-    // Apply pagination if parameters are provided
-    if let Some(limit) = first {
-        query.limit(limit as u64);
-    }
-    if let Some(off) = offset {
-        query.offset(off as u64);
-    }
-
-    // Execute the query
+    let service = &context.service;
+
+    let query = Carts::list(None, None, first, offset);
     let result = service.db_adapter.query_many::<Cart>(&query).await?;
 
     Ok(result)
 }
 
-pub async fn total_carts(context: &AppState) -> FieldResult<i32> {
-    let service = context.service.lock().await;
+/// Parked carts, oldest first, optionally only those parked at one location
+pub async fn parked_carts(
+    location_id: Option<DbUuid>,
+    first: Option<i32>,
+    offset: Option<i32>,
+    context: &AppState,
+) -> FieldResult<Vec<Cart>> {
+    let service = &context.service;
 
-    // Build the count query with SeaQuery
-    let mut query = Query::select();
-    let query = query
-        .from(Carts::Table)
-        .expr_as(Expr::col(Carts::Id).count(), Alias::new("count"));
+    let filter = CartFilter {
+        status: Some(CartStatus::Parked),
+        location_id,
+        ..Default::default()
+    };
+    let sort = (Carts::HeldSince, SortDirection::Asc);
+    let query = Carts::list(Some(&filter), Some(sort), first, offset);
+    let result = service.db_adapter.query_many::<Cart>(&query).await?;
 
-    // Execute the query
-    let result = service.db_adapter.query_one::<i64>(&query).await?;
+    Ok(result)
+}
+
+pub async fn total_carts(context: &AppState) -> FieldResult<i32> {
+    let service = &context.service;
+
+    let result = service.db_adapter.query_one::<i64>(&Carts::count(None)).await?;
 
     Ok(result as i32)
 }
 
 pub async fn cart(id: DbUuid, context: &AppState) -> FieldResult<Cart> {
-    let service = context.service.lock().await;
-
-    // Build the query with SeaQuery
-    let mut query = Query::select();
-    let query = query
-        .from(Carts::Table)
-        .columns([
-            Carts::Id,
-            Carts::CartData,
-            Carts::CustomerId,
-            Carts::CreatedAt,
-            Carts::UpdatedAt,
-        ])
-        .and_where(Expr::col(Carts::Id).eq(id.to_string()));
-
-    // Execute the query
-    let result = service.db_adapter.query_one::<Cart>(&query).await?;
+    let service = &context.service;
+
+    let result = service.db_adapter.query_one::<Cart>(&Carts::find_by_id(id)).await?;
 
     Ok(result)
 }
+
+connection!(CartConnection, CartEdge, Cart);
+
+pub async fn carts_connection(
+    page: PageRequest,
+    filter: Option<CartFilter>,
+    order_by: Option<CartOrderBy>,
+    context: &AppState,
+) -> FieldResult<CartConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
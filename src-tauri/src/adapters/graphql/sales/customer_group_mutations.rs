@@ -0,0 +1,32 @@
+use crate::{
+    core::{
+        commands::{
+            sales::customer_group_commands::{
+                CreateCustomerGroupCommand, DeleteCustomerGroupCommand, UpdateCustomerGroupCommand,
+            },
+            Command,
+        },
+        models::sales::customer_group_model::{CustomerGroup, CustomerGroupNewInput, CustomerGroupUpdateInput},
+        types::db_uuid::DbUuid,
+    },
+    AppState,
+};
+use crate::adapters::graphql::errors::FieldResult;
+
+pub async fn create_customer_group(group: CustomerGroupNewInput, context: &AppState) -> FieldResult<CustomerGroup> {
+    let service = context.service.write().await;
+    let result = CreateCustomerGroupCommand { group }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn update_customer_group(group: CustomerGroupUpdateInput, context: &AppState) -> FieldResult<CustomerGroup> {
+    let service = context.service.write().await;
+    let result = UpdateCustomerGroupCommand { group }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn delete_customer_group(id: DbUuid, context: &AppState) -> FieldResult<bool> {
+    let service = context.service.write().await;
+    let result = DeleteCustomerGroupCommand { id }.exec(&service).await?;
+    Ok(result)
+}
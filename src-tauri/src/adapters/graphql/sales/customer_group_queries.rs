@@ -0,0 +1,49 @@
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
+    core::{
+        db::pagination::{PageRequest, SortDirection},
+        models::sales::customer_group_model::{CustomerGroup, CustomerGroupFilter, CustomerGroupOrderBy, CustomerGroups},
+        types::db_uuid::DbUuid,
+    },
+    AppState,
+};
+
+pub async fn customer_groups(
+    first: Option<i32>,
+    offset: Option<i32>,
+    context: &AppState,
+) -> FieldResult<Vec<CustomerGroup>> {
+    let service = &context.service;
+
+    let sort = (CustomerGroups::Name, SortDirection::Asc);
+    let query = CustomerGroups::list(None, Some(sort), first, offset);
+    let result = service.db_adapter.query_many::<CustomerGroup>(&query).await?;
+
+    Ok(result)
+}
+
+pub async fn customer_group(id: DbUuid, context: &AppState) -> FieldResult<CustomerGroup> {
+    let service = &context.service;
+
+    let result = service.db_adapter.query_one::<CustomerGroup>(&CustomerGroups::find_by_id(id)).await?;
+
+    Ok(result)
+}
+
+connection!(CustomerGroupConnection, CustomerGroupEdge, CustomerGroup);
+
+pub async fn customer_groups_connection(
+    page: PageRequest,
+    filter: Option<CustomerGroupFilter>,
+    order_by: Option<CustomerGroupOrderBy>,
+    context: &AppState,
+) -> FieldResult<CustomerGroupConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
@@ -1,7 +1,7 @@
 use crate::{
     core::{
         commands::{
-            sales::customer_commands::{CreateCustomerCommand, DeleteCustomerCommand, UpdateCustomerCommand},
+            sales::customer_commands::{CreateCustomerCommand, DeleteCustomerCommand, PurgeCustomerCommand, RestoreCustomerCommand, UpdateCustomerCommand},
             Command,
         },
         models::sales::customer_model::{Customer, CustomerNewInput, CustomerUpdateInput},
@@ -9,22 +9,34 @@ use crate::{
     },
     AppState,
 };
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 pub async fn create_customer(customer: CustomerNewInput, context: &AppState) -> FieldResult<Customer> {
-    let mut service = context.service.lock().await;
-    let res = CreateCustomerCommand { customer }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = CreateCustomerCommand { customer }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn update_customer(customer: CustomerUpdateInput, context: &AppState) -> FieldResult<Customer> {
-    let mut service = context.service.lock().await;
-    let res = UpdateCustomerCommand { customer }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = UpdateCustomerCommand { customer }.exec(&service).await?;
     Ok(res)
 }
 
 pub async fn delete_customer(id: DbUuid, context: &AppState) -> FieldResult<i32> {
-    let mut service = context.service.lock().await;
-    let res = DeleteCustomerCommand { id }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let res = DeleteCustomerCommand { id, deleted_by: context.user.or(service.current_user()) }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn restore_customer(id: DbUuid, context: &AppState) -> FieldResult<Customer> {
+    let service = context.service.write().await;
+    let res = RestoreCustomerCommand { id }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn purge_customer(id: DbUuid, context: &AppState) -> FieldResult<i32> {
+    let service = context.service.write().await;
+    let res = PurgeCustomerCommand { id }.exec(&service).await?;
     Ok(res)
 }
@@ -28,6 +28,10 @@ impl Customer {
         self.address.clone()
     }
 
+    pub fn customer_group_id(&self) -> Option<DbUuid> {
+        self.customer_group_id
+    }
+
     pub fn created_at(&self) -> NaiveDateTime {
         self.created_at
     }
@@ -35,4 +39,17 @@ impl Customer {
     pub fn updated_at(&self) -> NaiveDateTime {
         self.updated_at
     }
+
+    pub fn deleted_at(&self) -> Option<NaiveDateTime> {
+        self.deleted_at
+    }
+
+    pub fn deleted_by(&self) -> Option<DbUuid> {
+        self.deleted_by
+    }
+
+    /// Pass back as `version` when updating
+    pub fn version(&self) -> i32 {
+        self.version
+    }
 }
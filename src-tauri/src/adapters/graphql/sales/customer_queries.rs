@@ -1,10 +1,14 @@
-use sea_query::{Alias, Expr, Query};
-use juniper::FieldResult;
+use sea_query::Expr;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
     core::{
-        models::sales::customer_model::{Customer, Customers},
+        db::pagination::PageRequest,
+        models::sales::customer_model::{Customer, CustomerFilter, CustomerOrderBy, Customers},
         types::db_uuid::DbUuid,
     },
     AppState,
@@ -15,95 +19,51 @@ pub async fn customers(
     offset: Option<i32>,
     context: &AppState,
 ) -> FieldResult<Vec<Customer>> {
-    let service = context.service.lock().await;
-
-    // Build the query with SeaQuery
-    let mut query_builder = Query::select();
-    let query = query_builder
-        .from(Customers::Table)
-        .columns([
-            Customers::Id,
-            Customers::FullName,
-            Customers::Email,
-            Customers::Phone,
-            Customers::Address,
-            Customers::CreatedAt,
-            Customers::UpdatedAt,
-        ]);
-
-    // Apply pagination if parameters are provided
-    if let Some(limit) = first {
-        query.limit(limit as u64);
-    }
-    if let Some(off) = offset {
-        query.offset(off as u64);
-    }
-
-    // Execute the query
+    let service = &context.service;
+
+    let query = Customers::list(None, None, first, offset);
     let result = service.db_adapter.query_many::<Customer>(&query).await?;
 
     Ok(result)
 }
 
 pub async fn total_customers(context: &AppState) -> FieldResult<i32> {
-    let service = context.service.lock().await;
-
-    // Build the count query with SeaQuery
-    let mut query = Query::select();
-    let query = query
-        .from(Customers::Table)
-        .expr_as(Expr::col(Customers::Id).count(), Alias::new("count"));
+    let service = &context.service;
 
-    // Execute the query
-    let result = service.db_adapter.query_one::<i64>(&query).await?;
+    let result = service.db_adapter.query_one::<i64>(&Customers::count(None)).await?;
 
     Ok(result as i32)
 }
 
 pub async fn customer(id: DbUuid, context: &AppState) -> FieldResult<Customer> {
-    let service = context.service.lock().await;
-
-    // Build the query with SeaQuery
-    let mut query = Query::select();
-    let query = query
-        .from(Customers::Table)
-        .columns([
-            Customers::Id,
-            Customers::FullName,
-            Customers::Email,
-            Customers::Phone,
-            Customers::Address,
-            Customers::CreatedAt,
-            Customers::UpdatedAt,
-        ])
-        .and_where(Expr::col(Customers::Id).eq(id.to_string()));
-
-    // Execute the query
-    let result = service.db_adapter.query_one::<Customer>(&query).await?;
+    let service = &context.service;
+
+    let result = service.db_adapter.query_one::<Customer>(&Customers::find_by_id(id)).await?;
 
     Ok(result)
 }
 
 pub async fn customer_by_phone(phone: String, context: &AppState) -> FieldResult<Customer> {
-    let service = context.service.lock().await;
-
-    // Build the query with SeaQuery
-    let mut query = Query::select();
-    let query = query
-        .from(Customers::Table)
-        .columns([
-            Customers::Id,
-            Customers::FullName,
-            Customers::Email,
-            Customers::Phone,
-            Customers::Address,
-            Customers::CreatedAt,
-            Customers::UpdatedAt,
-        ])
-        .and_where(Expr::col(Customers::Phone).eq(phone));
-
-    // Execute the query
+    let service = &context.service;
+
+    let mut query = Customers::select();
+    query
+        .and_where(Expr::col(Customers::Phone).eq(phone))
+        .and_where(Expr::col(Customers::DeletedAt).is_null());
     let result = service.db_adapter.query_one::<Customer>(&query).await?;
 
     Ok(result)
 }
+
+connection!(CustomerConnection, CustomerEdge, Customer);
+
+pub async fn customers_connection(
+    page: PageRequest,
+    filter: Option<CustomerFilter>,
+    order_by: Option<CustomerOrderBy>,
+    context: &AppState,
+) -> FieldResult<CustomerConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
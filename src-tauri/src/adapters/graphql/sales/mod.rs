@@ -1,9 +1,15 @@
 pub mod cart_mutations;
 pub mod cart_object;
 pub mod cart_queries;
+pub mod customer_group_mutations;
+pub mod customer_group_object;
+pub mod customer_group_queries;
 pub mod customer_mutations;
 pub mod customer_object;
 pub mod customer_queries;
+pub mod price_override_reason_mutations;
+pub mod price_override_reason_object;
+pub mod price_override_reason_queries;
 pub mod sales_charge_type_mutations;
 pub mod sales_charge_type_object;
 pub mod sales_charge_type_queries;
@@ -0,0 +1,41 @@
+use crate::{
+    core::{
+        commands::{
+            sales::price_override_reason_commands::{
+                CreatePriceOverrideReasonCommand, DeletePriceOverrideReasonCommand,
+                UpdatePriceOverrideReasonCommand,
+            },
+            Command,
+        },
+        models::sales::price_override_reason_model::{
+            PriceOverrideReason, PriceOverrideReasonNewInput, PriceOverrideReasonUpdateInput,
+        },
+        types::db_uuid::DbUuid,
+    },
+    AppState,
+};
+use crate::adapters::graphql::errors::FieldResult;
+
+pub async fn create_price_override_reason(
+    reason: PriceOverrideReasonNewInput,
+    context: &AppState,
+) -> FieldResult<PriceOverrideReason> {
+    let service = context.service.write().await;
+    let result = CreatePriceOverrideReasonCommand { reason }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn update_price_override_reason(
+    reason: PriceOverrideReasonUpdateInput,
+    context: &AppState,
+) -> FieldResult<PriceOverrideReason> {
+    let service = context.service.write().await;
+    let result = UpdatePriceOverrideReasonCommand { reason }.exec(&service).await?;
+    Ok(result)
+}
+
+pub async fn delete_price_override_reason(id: DbUuid, context: &AppState) -> FieldResult<bool> {
+    let service = context.service.write().await;
+    let result = DeletePriceOverrideReasonCommand { id }.exec(&service).await?;
+    Ok(result)
+}
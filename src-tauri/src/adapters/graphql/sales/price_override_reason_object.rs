@@ -0,0 +1,42 @@
+use chrono::NaiveDateTime;
+use juniper::graphql_object;
+
+use crate::{
+    core::{models::sales::price_override_reason_model::PriceOverrideReason, types::db_uuid::DbUuid},
+    AppState,
+};
+
+#[graphql_object(context = AppState)]
+impl PriceOverrideReason {
+    pub fn id(&self) -> DbUuid {
+        self.id
+    }
+
+    pub fn code(&self) -> String {
+        self.code.clone()
+    }
+
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    pub fn requires_approval(&self) -> bool {
+        self.requires_approval
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    pub fn created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+}
@@ -0,0 +1,55 @@
+use crate::adapters::graphql::errors::FieldResult;
+
+use crate::{
+    adapters::{
+        graphql::connection::{connection, list},
+        outgoing::database::DatabaseAdapter,
+    },
+    core::{
+        db::pagination::{PageRequest, SortDirection},
+        models::sales::price_override_reason_model::{PriceOverrideReason, PriceOverrideReasonFilter, PriceOverrideReasonOrderBy, PriceOverrideReasons},
+        types::db_uuid::DbUuid,
+    },
+    AppState,
+};
+
+pub async fn price_override_reasons(
+    first: Option<i32>,
+    offset: Option<i32>,
+    active_only: Option<bool>,
+    context: &AppState,
+) -> FieldResult<Vec<PriceOverrideReason>> {
+    let service = &context.service;
+
+    let filter = PriceOverrideReasonFilter {
+        is_active: active_only.unwrap_or(false).then_some(true),
+        ..Default::default()
+    };
+    let sort = (PriceOverrideReasons::Code, SortDirection::Asc);
+    let query = PriceOverrideReasons::list(Some(&filter), Some(sort), first, offset);
+    let result = service.db_adapter.query_many::<PriceOverrideReason>(&query).await?;
+
+    Ok(result)
+}
+
+pub async fn price_override_reason(id: DbUuid, context: &AppState) -> FieldResult<PriceOverrideReason> {
+    let service = &context.service;
+
+    let query = PriceOverrideReasons::find_by_id(id);
+    let result = service.db_adapter.query_one::<PriceOverrideReason>(&query).await?;
+
+    Ok(result)
+}
+
+connection!(PriceOverrideReasonConnection, PriceOverrideReasonEdge, PriceOverrideReason);
+
+pub async fn price_override_reasons_connection(
+    page: PageRequest,
+    filter: Option<PriceOverrideReasonFilter>,
+    order_by: Option<PriceOverrideReasonOrderBy>,
+    context: &AppState,
+) -> FieldResult<PriceOverrideReasonConnection> {
+    let page = list(context, filter, order_by, page).await?;
+
+    Ok(page.into())
+}
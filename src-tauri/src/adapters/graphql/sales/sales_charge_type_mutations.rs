@@ -2,7 +2,7 @@ use crate::{
     core::{
         commands::{
             sales::sales_charge_type_commands::{
-                CreateSalesChargeTypeCommand, DeleteSalesChargeTypeCommand,
+                CreateSalesChargeTypeCommand, DeleteSalesChargeTypeCommand, PurgeSalesChargeTypeCommand, RestoreSalesChargeTypeCommand,
                 UpdateSalesChargeTypeCommand,
             },
             Command,
@@ -14,14 +14,14 @@ use crate::{
     },
     AppState,
 };
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 
 pub async fn create_sales_charge_type(
     charge_type: SalesChargeTypeNewInput,
     context: &AppState,
 ) -> FieldResult<SalesChargeType> {
-    let mut service = context.service.lock().await;
-    let result = CreateSalesChargeTypeCommand { charge_type }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let result = CreateSalesChargeTypeCommand { charge_type }.exec(&service).await?;
     Ok(result)
 }
 
@@ -29,13 +29,25 @@ pub async fn update_sales_charge_type(
     charge_type: SalesChargeTypeUpdateInput,
     context: &AppState,
 ) -> FieldResult<SalesChargeType> {
-    let mut service = context.service.lock().await;
-    let result = UpdateSalesChargeTypeCommand { charge_type }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let result = UpdateSalesChargeTypeCommand { charge_type }.exec(&service).await?;
     Ok(result)
 }
 
 pub async fn delete_sales_charge_type(id: DbUuid, context: &AppState) -> FieldResult<bool> {
-    let mut service = context.service.lock().await;
-    let result = DeleteSalesChargeTypeCommand { id }.exec(&mut service).await?;
+    let service = context.service.write().await;
+    let result = DeleteSalesChargeTypeCommand { id, deleted_by: context.user.or(service.current_user()) }.exec(&service).await?;
     Ok(result)
 }
+
+pub async fn restore_sales_charge_type(id: DbUuid, context: &AppState) -> FieldResult<SalesChargeType> {
+    let service = context.service.write().await;
+    let res = RestoreSalesChargeTypeCommand { id }.exec(&service).await?;
+    Ok(res)
+}
+
+pub async fn purge_sales_charge_type(id: DbUuid, context: &AppState) -> FieldResult<bool> {
+    let service = context.service.write().await;
+    let res = PurgeSalesChargeTypeCommand { id }.exec(&service).await?;
+    Ok(res)
+}
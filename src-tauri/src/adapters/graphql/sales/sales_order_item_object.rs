@@ -43,6 +43,22 @@ impl SalesOrderItem {
         self.total_amount
     }
 
+    pub fn original_price_amount(&self) -> Option<Money> {
+        self.original_price_amount
+    }
+
+    pub fn override_reason_id(&self) -> Option<DbUuid> {
+        self.override_reason_id
+    }
+
+    pub fn override_note(&self) -> Option<String> {
+        self.override_note.clone()
+    }
+
+    pub fn override_approved_by(&self) -> Option<DbUuid> {
+        self.override_approved_by
+    }
+
     pub fn created_at(&self) -> NaiveDateTime {
         self.created_at
     }
@@ -9,36 +9,36 @@ use crate::{
     },
     AppState,
 };
-use juniper::FieldResult;
+use crate::adapters::graphql::errors::FieldResult;
 use uuid::Uuid;
 
-// Placeholder function to get user ID (replace with actual logic)
-fn get_current_user_id(_context: &AppState) -> DbUuid {
-    // TODO: Implement actual user ID retrieval from context/session
-    Uuid::nil().into() // Using Nil UUID as a placeholder
+// The user of an API token, otherwise a placeholder until the desktop session keeps one
+fn get_current_user_id(context: &AppState) -> DbUuid {
+    // TODO: Implement user ID retrieval from the desktop session
+    context.user.unwrap_or_else(|| Uuid::nil().into())
 }
 
 pub async fn create_sales_order(
     sales_order: SalesOrderNewInput,
     context: &AppState,
 ) -> FieldResult<SalesOrder> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
     let current_user_id = get_current_user_id(context);
     let res = CreateSalesOrderCommand {
         sales_order,
         created_by_user_id: current_user_id,
     }
-    .exec(&mut service).await?;
+    .exec(&service).await?;
     Ok(res)
 }
 
 pub async fn void_sales_order(id: DbUuid, context: &AppState) -> FieldResult<SalesOrder> {
-    let mut service = context.service.lock().await;
+    let service = context.service.write().await;
     let current_user_id = get_current_user_id(context);
     let res = VoidSalesOrderCommand {
         id,
         updated_by_user_id: current_user_id,
     }
-    .exec(&mut service).await?;
+    .exec(&service).await?;
     Ok(res)
 }
@@ -1,9 +1,10 @@
 use chrono::NaiveDateTime;
 use sea_query::{Expr, Query};
-use juniper::{graphql_object, FieldResult};
+use juniper::graphql_object;
+use crate::adapters::graphql::errors::FieldResult;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::{graphql::loaders, outgoing::database::DatabaseAdapter},
     core::{
         commands::{finance::sales_order_payment_commands::GetSalesOrderPaymentsCommand, Command},
         models::{
@@ -12,9 +13,9 @@ use crate::{
                 sales_order_payment_model::{SalesOrderPayment, SalesOrderPaymentState},
             },
             sales::{
-                customer_model::{Customer, Customers},
+                customer_model::Customer,
                 sales_order_charge_model::{SalesOrderCharge, SalesOrderCharges},
-                sales_order_item_model::{SalesOrderItem, SalesOrderItems},
+                sales_order_item_model::SalesOrderItem,
                 sales_order_model::{SalesOrder, SalesOrderPaymentState as OrderPaymentState, SalesOrderState},
             },
         },
@@ -77,6 +78,10 @@ impl SalesOrder {
         self.total_amount
     }
 
+    pub fn currency_code(&self) -> String {
+        self.currency_code.clone()
+    }
+
     pub fn order_state(&self) -> SalesOrderState {
         self.order_state
     }
@@ -123,33 +128,14 @@ impl SalesOrder {
 
     // Relationships
     pub async fn customer(&self, context: &AppState) -> FieldResult<Option<Customer>> {
-        if let Some(customer_id) = self.customer_id {
-            let service = context.service.lock().await;
-
-            let mut query = Query::select();
-            let query = query
-                .from(Customers::Table)
-                .columns([
-                    Customers::Id,
-                    Customers::FullName,
-                    Customers::Email,
-                    Customers::Phone,
-                    Customers::Address,
-                    Customers::CreatedAt,
-                    Customers::UpdatedAt,
-                ])
-                .and_where(Expr::col(Customers::Id).eq(customer_id.to_string()));
-
-            let customer = service.db_adapter.query_one::<Customer>(&query).await?;
-
-            Ok(Some(customer))
-        } else {
-            Ok(None)
+        match self.customer_id {
+            Some(customer_id) => Ok(loaders::customer(context, customer_id).await?),
+            None => Ok(None),
         }
     }
 
     pub async fn cost_center(&self, context: &AppState) -> FieldResult<CostCenter> {
-        let service = context.service.lock().await;
+        let service = &context.service;
 
         let mut query = Query::select();
         let query = query
@@ -171,35 +157,11 @@ impl SalesOrder {
     }
 
     pub async fn items(&self, context: &AppState) -> FieldResult<Vec<SalesOrderItem>> {
-        let service = context.service.lock().await;
-
-        let mut query = Query::select();
-        let query = query
-            .from(SalesOrderItems::Table)
-            .columns([
-                SalesOrderItems::Id,
-                SalesOrderItems::OrderId,
-                SalesOrderItems::ItemId,
-                SalesOrderItems::ItemName,
-                SalesOrderItems::Quantity,
-                SalesOrderItems::Sku,
-                SalesOrderItems::PriceAmount,
-                SalesOrderItems::DiscAmount,
-                SalesOrderItems::TaxableAmount,
-                SalesOrderItems::TaxAmount,
-                SalesOrderItems::TotalAmount,
-                SalesOrderItems::CreatedAt,
-                SalesOrderItems::UpdatedAt,
-            ])
-            .and_where(Expr::col(SalesOrderItems::OrderId).eq(self.id.to_string()));
-
-        let items = service.db_adapter.query_many::<SalesOrderItem>(&query).await?;
-
-        Ok(items)
+        Ok(loaders::sales_order_items(context, self.id).await?)
     }
 
     pub async fn charges(&self, context: &AppState) -> FieldResult<Vec<SalesOrderCharge>> {
-        let service = context.service.lock().await;
+        let service = &context.service;
 
         let mut query = Query::select();
         let query = query
@@ -223,16 +185,16 @@ impl SalesOrder {
     }
 
     pub async fn payments(&self, context: &AppState) -> FieldResult<Vec<SalesOrderPayment>> {
-        let mut service = context.service.lock().await;
+        let service = &context.service;
         let cmd = GetSalesOrderPaymentsCommand { order_id: self.id };
-        let payments = cmd.exec(&mut service).await?;
+        let payments = cmd.exec(&service).await?;
         Ok(payments)
     }
 
     pub async fn total_paid_amount(&self, context: &AppState) -> FieldResult<Money> {
-        let mut service = context.service.lock().await;
+        let service = &context.service;
         let cmd = GetSalesOrderPaymentsCommand { order_id: self.id };
-        let payments = cmd.exec(&mut service).await?;
+        let payments = cmd.exec(&service).await?;
 
         let total: Money = payments
             .iter()
@@ -0,0 +1,275 @@
+//! Subscription root, fed by the event bus on `AppService`
+//!
+//! Each field streams one kind of event. `subscribe` runs a subscription document and yields
+//! one response per event, which the Tauri adapter forwards to the UI.
+
+use std::{pin::Pin, sync::Arc};
+
+use juniper::{
+    futures::{stream, Stream, StreamExt},
+    graphql_subscription, DefaultScalarValue, ExecutionError, FieldResult, GraphQLError, Object,
+    Value, Variables,
+};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    adapters::graphql::Schema,
+    core::{
+        events::{Event, StockChange, SyncFinished},
+        models::{
+            finance::sales_order_payment_model::SalesOrderPayment,
+            sales::sales_order_model::SalesOrder,
+        },
+        types::db_uuid::DbUuid,
+    },
+    AppState,
+};
+
+type EventStream<T> = Pin<Box<dyn Stream<Item = FieldResult<T>> + Send>>;
+
+/// The data for one event and the errors raised while resolving it
+pub type SubscriptionResponse = (Value, Vec<ExecutionError<DefaultScalarValue>>);
+
+pub struct Subscription;
+
+#[graphql_subscription(context = AppState)]
+impl Subscription {
+    /// Orders as they are placed, including checked out carts
+    async fn order_created(context: &AppState) -> EventStream<SalesOrder> {
+        events(context, |event| match event {
+            Event::OrderCreated(order) => Some(order),
+            _ => None,
+        })
+    }
+
+    async fn order_voided(context: &AppState) -> EventStream<SalesOrder> {
+        events(context, |event| match event {
+            Event::OrderVoided(order) => Some(order),
+            _ => None,
+        })
+    }
+
+    /// Payments as they are recorded, optionally for one order only
+    async fn payment_recorded(
+        context: &AppState,
+        order_id: Option<DbUuid>,
+    ) -> EventStream<SalesOrderPayment> {
+        events(context, move |event| match event {
+            Event::PaymentRecorded(payment) if order_id.is_none_or(|id| id == payment.order_id) => {
+                Some(payment)
+            }
+            _ => None,
+        })
+    }
+
+    /// Items sold or returned, optionally for one item only
+    async fn stock_changed(context: &AppState, item_id: Option<DbUuid>) -> EventStream<StockChange> {
+        events(context, move |event| match event {
+            Event::StockChanged(change) if item_id.is_none_or(|id| id == change.item_id) => {
+                Some(change)
+            }
+            _ => None,
+        })
+    }
+
+    async fn sync_finished(context: &AppState) -> EventStream<SyncFinished> {
+        events(context, |event| match event {
+            Event::SyncFinished(sync) => Some(sync),
+            _ => None,
+        })
+    }
+}
+
+// Streams the events `select` picks out, starting from the moment of subscribing
+fn events<T, F>(context: &AppState, select: F) -> EventStream<T>
+where
+    T: Send + 'static,
+    F: Fn(Event) -> Option<T> + Send + 'static,
+{
+    let receiver = context.service.events.subscribe();
+    let loaders = Arc::clone(&context.loaders);
+
+    Box::pin(stream::unfold((receiver, select), move |(mut receiver, select)| {
+        let loaders = Arc::clone(&loaders);
+        async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        if let Some(value) = select(event) {
+                            // Each event is resolved against fresh data
+                            loaders.clear();
+                            return Some((Ok(value), (receiver, select)));
+                        }
+                    }
+                    // A subscriber that fell behind skips the events it missed
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }
+    }))
+}
+
+/// Starts the subscription in `query` and yields a response for every event
+pub async fn subscribe<'a>(
+    query: &'a str,
+    variables: &Variables,
+    schema: &'a Schema,
+    context: &'a AppState,
+) -> Result<Pin<Box<dyn Stream<Item = SubscriptionResponse> + Send + 'a>>, GraphQLError> {
+    let (value, errors) = juniper::resolve_into_stream(query, None, schema, variables, context).await?;
+
+    // A subscription has a single root field, whose value is the stream of its events
+    let field = match value {
+        Value::Object(fields) => fields.into_iter().next(),
+        _ => None,
+    };
+    let (name, events) = match field {
+        Some((name, Value::Scalar(events))) => (name, events),
+        _ => return Ok(Box::pin(stream::once(async { (Value::null(), errors) }))),
+    };
+
+    Ok(Box::pin(events.map(move |result| match result {
+        Ok(value) => {
+            let mut data = Object::with_capacity(1);
+            data.add_field(name.clone(), value);
+            (Value::Object(data), Vec::new())
+        }
+        Err(error) => (Value::null(), vec![error]),
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use juniper::{graphql_value, InputValue};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{
+        adapters::graphql::{Mutation, Query},
+        core::{
+            commands::{
+                auth::user_commands::AddUserCommand,
+                common::{channel_commands::CreateChannelCommand, location_commands::CreateLocationCommand},
+                finance::cost_center_commands::CreateCostCenterCommand,
+                sales::sales_order_commands::CreateSalesOrderCommand,
+                tests::setup_service,
+                AppService, Command,
+            },
+            models::{
+                auth::user_model::{UserNewInput, UserRole, UserState},
+                common::{channel_model::ChannelNewInput, location_model::LocationNewInput},
+                finance::cost_center_model::{CostCenterNewInput, CostCenterState},
+                sales::{sales_order_item_model::SalesOrderItemInput, sales_order_model::SalesOrderNewInput},
+            },
+        },
+    };
+
+    async fn create_order(service: &AppService, notes: &str) {
+        let channel = CreateChannelCommand {
+            channel: ChannelNewInput { name: "Counter".to_string(), description: None, is_active: Some(true) },
+        };
+        let location = CreateLocationCommand {
+            location: LocationNewInput {
+                name: "Main".to_string(),
+                description: None,
+                address: None,
+                is_active: Some(true),
+            },
+        };
+        let cost_center = CreateCostCenterCommand {
+            cost_center: CostCenterNewInput {
+                name: "Sales".to_string(),
+                code: "SAL".to_string(),
+                description: None,
+                state: Some(CostCenterState::Active),
+            },
+        };
+        let user = AddUserCommand {
+            user: UserNewInput {
+                username: "cashier".to_string(),
+                pin_hash: "1234".to_string(),
+                full_name: "Cashier".to_string(),
+                state: UserState::Active,
+                role: UserRole::Cashier,
+                last_login_at: None,
+            },
+        };
+
+        let command = CreateSalesOrderCommand {
+            sales_order: SalesOrderNewInput {
+                customer_id: None,
+                customer_name: None,
+                customer_phone_number: None,
+                billing_address: None,
+                shipping_address: None,
+                order_date: Utc::now().naive_utc(),
+                net_amount: 1000.into(),
+                disc_amount: 0.into(),
+                taxable_amount: 1000.into(),
+                tax_amount: 0.into(),
+                total_amount: 1000.into(),
+                notes: Some(notes.to_string()),
+                channel_id: channel.exec(service).await.unwrap().id,
+                location_id: location.exec(service).await.unwrap().id,
+                cost_center_id: cost_center.exec(service).await.unwrap().id,
+                discount_id: None,
+                items: vec![SalesOrderItemInput {
+                    item_id: None,
+                    variant_id: None,
+                    item_name: "Coffee".to_string(),
+                    quantity: 1,
+                    sku: None,
+                    price_amount: 1000.into(),
+                    disc_amount: 0.into(),
+                    taxable_amount: 1000.into(),
+                    tax_amount: 0.into(),
+                    total_amount: 1000.into(),
+                    price_override: None,
+                }],
+                charges: None,
+            },
+            created_by_user_id: user.exec(service).await.unwrap().id,
+        };
+        command.exec(service).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_order_created_subscription() {
+        let schema = Schema::new(Query, Mutation, Subscription);
+        let context = AppState::new(setup_service().await);
+
+        let query = "subscription { orderCreated { notes totalAmount } }";
+        let mut orders = subscribe(query, &Variables::new(), &schema, &context).await.unwrap();
+
+        create_order(&context.service, "Table 4").await;
+
+        let (data, errors) = orders.next().await.unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(
+            data,
+            graphql_value!({ "orderCreated": { "notes": "Table 4", "totalAmount": "10" } })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stock_changed_subscription_filters_by_item() {
+        let schema = Schema::new(Query, Mutation, Subscription);
+        let context = AppState::new(setup_service().await);
+        let (item_id, other_item_id): (DbUuid, DbUuid) = (Uuid::now_v7().into(), Uuid::now_v7().into());
+
+        let mut variables = Variables::new();
+        variables.insert("itemId".to_string(), InputValue::scalar(item_id.to_string()));
+        let query = "subscription($itemId: DbUuid) { stockChanged(itemId: $itemId) { quantity } }";
+        let mut changes = subscribe(query, &variables, &schema, &context).await.unwrap();
+
+        let order_id: DbUuid = Uuid::now_v7().into();
+        for (item_id, quantity) in [(other_item_id, -1), (item_id, -3)] {
+            context.service.events.publish(Event::StockChanged(StockChange { item_id, order_id, quantity }));
+        }
+
+        let (data, _) = changes.next().await.unwrap();
+        assert_eq!(data, graphql_value!({ "stockChanged": { "quantity": -3 } }));
+    }
+}
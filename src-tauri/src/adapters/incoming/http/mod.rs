@@ -0,0 +1,368 @@
+//! Headless GraphQL server for back-office PCs, kitchen tablets and scripts on the LAN
+//!
+//! Serves the same `Schema` as the Tauri adapter:
+//!
+//! - `POST /graphql` runs queries and mutations, one or a batch per request
+//! - `GET /subscriptions` upgrades to a WebSocket speaking `graphql-transport-ws`
+//! - `GET /graphiql` serves GraphiQL, in debug builds only
+//!
+//! Every request needs an API token, sent as `Authorization: Bearer <token>`. WebSocket
+//! clients that can't set headers send it as `Authorization` in the `connection_init` payload.
+
+mod websocket;
+
+use std::{convert::Infallible, sync::Arc};
+
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::{
+    body::{Bytes, Incoming},
+    header::{self, HeaderValue},
+    server::conn::http1,
+    service::service_fn,
+    Method, Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::net::TcpListener;
+
+use crate::{
+    adapters::graphql::endpoint::{into_response, GraphQLEndpoint, GraphQLRequest},
+    core::{
+        commands::{auth::api_token_commands::AuthenticateApiTokenCommand, Command},
+        types::db_uuid::DbUuid,
+    },
+    error::{Error, Result},
+    AppState,
+};
+
+/// Largest request body accepted on `/graphql`
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+type HttpResponse = Response<Full<Bytes>>;
+
+/// What every connection shares: the app state and the endpoint built once at startup
+struct Server {
+    state: AppState,
+    endpoint: GraphQLEndpoint,
+}
+
+/// One operation or a batch of them
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Batch {
+    Single(GraphQLRequest),
+    Many(Vec<GraphQLRequest>),
+}
+
+/// Accepts connections on `listener` until the listener fails
+pub async fn serve(listener: TcpListener, state: AppState, endpoint: GraphQLEndpoint) -> std::io::Result<()> {
+    let server = Arc::new(Server { state, endpoint });
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let server = Arc::clone(&server);
+
+        tokio::spawn(async move {
+            let service = service_fn(move |request| handle(request, Arc::clone(&server)));
+            let connection = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .with_upgrades();
+            if let Err(e) = connection.await {
+                eprintln!("HTTP connection failed: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle(request: Request<Incoming>, server: Arc<Server>) -> std::result::Result<HttpResponse, Infallible> {
+    let response = match (request.method(), request.uri().path()) {
+        (&Method::POST, "/graphql") => graphql(request, &server).await,
+        (&Method::GET, "/subscriptions") => websocket::upgrade(request, server).await,
+        #[cfg(debug_assertions)]
+        (&Method::GET, "/graphiql") => Ok(graphiql()),
+        (_, "/graphql") => {
+            Ok(error_response(StatusCode::METHOD_NOT_ALLOWED, "METHOD_NOT_ALLOWED", "Use POST"))
+        }
+        _ => Ok(error_response(StatusCode::NOT_FOUND, "NOT_FOUND", "Not found")),
+    };
+
+    Ok(response.unwrap_or_else(|err| match err {
+        Error::AuthenticationError => {
+            let message = "Invalid or missing API token";
+            let mut response = error_response(StatusCode::UNAUTHORIZED, err.code(), message);
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            response
+        }
+        Error::AuthorizationError => error_response(StatusCode::FORBIDDEN, err.code(), "User is not active"),
+        Error::ValidationError(_) => error_response(StatusCode::BAD_REQUEST, err.code(), &err.message()),
+        err => {
+            eprintln!("HTTP request failed: {}", err);
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, err.code(), "Internal server error")
+        }
+    }))
+}
+
+async fn graphql(request: Request<Incoming>, server: &Server) -> Result<HttpResponse> {
+    let user = authenticate(request.headers().get(header::AUTHORIZATION), server).await?;
+
+    let body = Limited::new(request.into_body(), MAX_BODY_SIZE)
+        .collect()
+        .await
+        .map_err(|e| Error::ValidationError(format!("Failed to read request body: {}", e)))?
+        .to_bytes();
+    let batch: Batch = serde_json::from_slice(&body)
+        .map_err(|e| Error::ValidationError(format!("Invalid GraphQL request: {}", e)))?;
+
+    let context = server.state.for_user(user);
+    let (body, is_ok) = match batch {
+        Batch::Single(request) => {
+            let response = into_response(server.endpoint.execute(&request, &context).await);
+            (serde_json::to_vec(&response)?, response.is_ok())
+        }
+        Batch::Many(requests) => {
+            server.endpoint.check_batch(&requests)?;
+            let mut responses = Vec::with_capacity(requests.len());
+            for request in &requests {
+                responses.push(into_response(server.endpoint.execute(request, &context).await));
+            }
+            (serde_json::to_vec(&responses)?, responses.iter().all(|response| response.is_ok()))
+        }
+    };
+    let status = if is_ok { StatusCode::OK } else { StatusCode::BAD_REQUEST };
+
+    Ok(json_response(status, body))
+}
+
+#[cfg(debug_assertions)]
+fn graphiql() -> HttpResponse {
+    let html = juniper::http::graphiql::graphiql_source("/graphql", Some("/subscriptions"));
+    let mut response = Response::new(Full::new(Bytes::from(html)));
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static("text/html; charset=utf-8"));
+    response
+}
+
+/// Resolves an `Authorization: Bearer <token>` value to the user the token was issued to
+async fn authenticate(authorization: Option<&HeaderValue>, server: &Server) -> Result<DbUuid> {
+    let token = authorization
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .ok_or(Error::AuthenticationError)?;
+
+    let command = AuthenticateApiTokenCommand { token: token.to_string() };
+    let user = command.exec(&server.state.service).await?;
+
+    Ok(user.id)
+}
+
+fn json_response(status: StatusCode, body: Vec<u8>) -> HttpResponse {
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+// Errors use the shape of a GraphQL response, so clients only handle one format
+fn error_response(status: StatusCode, code: &str, message: &str) -> HttpResponse {
+    let body = json!({ "errors": [{ "message": message, "extensions": { "code": code } }] });
+    json_response(status, body.to_string().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use serde_json::Value;
+
+    use super::*;
+    use crate::core::{
+        commands::{
+            auth::{api_token_commands::CreateApiTokenCommand, user_commands::AddUserCommand},
+            tests::setup_service,
+            AppService,
+        },
+        models::auth::user_model::{UserNewInput, UserRole, UserState},
+    };
+
+    /// Starts a server on a free port and returns its address, a token for an active user
+    /// and the service behind it
+    pub(super) async fn start_server() -> (SocketAddr, String, Arc<AppService>) {
+        let service = setup_service().await;
+        let user = AddUserCommand {
+            user: UserNewInput {
+                username: "backoffice".to_string(),
+                pin_hash: "1234".to_string(),
+                full_name: "Back Office".to_string(),
+                state: UserState::Active,
+                role: UserRole::Cashier,
+                last_login_at: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        let created = CreateApiTokenCommand { user_id: user.id, name: "Tests".to_string(), issued_by: None }
+            .exec(&service)
+            .await
+            .unwrap();
+
+        let state = AppState::new(service);
+        let service = Arc::clone(&state.service);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, state, GraphQLEndpoint::default()));
+
+        (addr, created.token, service)
+    }
+
+    async fn post_graphql(addr: SocketAddr, token: Option<&str>, body: Value) -> (StatusCode, Value) {
+        let mut request = reqwest::Client::new()
+            .post(format!("http://{}/graphql", addr))
+            .header("Content-Type", "application/json")
+            .body(body.to_string());
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await.unwrap();
+        let status = StatusCode::from_u16(response.status().as_u16()).unwrap();
+        let body = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+        (status, body)
+    }
+
+    #[tokio::test]
+    async fn test_query_with_token() {
+        let (addr, token, _) = start_server().await;
+
+        let (status, body) = post_graphql(
+            addr,
+            Some(&token),
+            json!({ "query": "{ usersConnection(first: 5) { nodes { username } } }" }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            json!({ "data": { "usersConnection": { "nodes": [{ "username": "backoffice" }] } } })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mutation_with_token() {
+        let (addr, token, _) = start_server().await;
+
+        let (status, body) = post_graphql(
+            addr,
+            Some(&token),
+            json!({
+                "query": "mutation($notes: String) { createCart(cart: { notes: $notes }) { notes } }",
+                "variables": { "notes": "Table 7" },
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["data"]["createCart"]["notes"], "Table 7");
+    }
+
+    #[tokio::test]
+    async fn test_cashier_cannot_issue_tokens_for_others() {
+        let (addr, token, service) = start_server().await;
+        let supervisor = AddUserCommand {
+            user: UserNewInput {
+                username: "manager".to_string(),
+                pin_hash: "9999".to_string(),
+                full_name: "Manager".to_string(),
+                state: UserState::Active,
+                role: UserRole::Supervisor,
+                last_login_at: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        let mutation = "mutation($userId: DbUuid, $name: String!) { \
+                        createApiToken(userId: $userId, name: $name) { apiToken { userId } } }";
+
+        let (_, body) = post_graphql(
+            addr,
+            Some(&token),
+            json!({ "query": mutation, "variables": { "userId": supervisor.id.to_string(), "name": "Mine now" } }),
+        )
+        .await;
+        assert_eq!(body["errors"][0]["extensions"]["code"], "FORBIDDEN", "{}", body);
+        assert!(body["data"].is_null());
+
+        // Without a user the token is the caller's own
+        let (status, body) =
+            post_graphql(addr, Some(&token), json!({ "query": mutation, "variables": { "name": "Spare" } })).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_ne!(body["data"]["createApiToken"]["apiToken"]["userId"], supervisor.id.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_missing_or_invalid_token_is_rejected() {
+        let (addr, _, _) = start_server().await;
+        let query = json!({ "query": "{ usersConnection { totalCount } }" });
+
+        let (status, body) = post_graphql(addr, None, query.clone()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["errors"][0]["message"], "Invalid or missing API token");
+        assert_eq!(body["errors"][0]["extensions"]["code"], "UNAUTHENTICATED");
+
+        let (status, _) = post_graphql(addr, Some("lpos_not-a-token"), query).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_document_is_a_bad_request() {
+        let (addr, token, _) = start_server().await;
+
+        let (status, body) = post_graphql(addr, Some(&token), json!({ "query": "{ noSuchField }" })).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["errors"][0]["message"].as_str().unwrap().contains("noSuchField"));
+    }
+
+    #[tokio::test]
+    async fn test_batched_queries() {
+        let (addr, token, _) = start_server().await;
+
+        let (status, body) = post_graphql(
+            addr,
+            Some(&token),
+            json!([
+                { "query": "{ usersConnection { totalCount } }" },
+                { "query": "{ cartsConnection { totalCount } }" },
+            ]),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body[0]["data"]["usersConnection"]["totalCount"], 1);
+        assert_eq!(body[1]["data"]["cartsConnection"]["totalCount"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_batches_share_the_complexity_limit() {
+        let (addr, token, _) = start_server().await;
+        // Each costs 2001, within the default limit of 10000 on its own
+        let request = json!({ "query": "{ usersConnection(first: 1000) { nodes { id } } }" });
+
+        let (status, _) = post_graphql(addr, Some(&token), json!(vec![request.clone(); 4])).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = post_graphql(addr, Some(&token), json!(vec![request; 5])).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["errors"][0]["message"], "Batch complexity 10005 exceeds the limit of 10000");
+    }
+}
@@ -0,0 +1,515 @@
+//! GraphQL over WebSocket, using the `graphql-transport-ws` protocol
+//!
+//! WebSocket framing, pings and close handshakes are left to `tokio-tungstenite`; this module
+//! speaks the protocol's messages on top of it. Every `subscribe` message runs in a task of its
+//! own; queries and mutations get a single `next` before their `complete`.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use http_body_util::Full;
+use hyper::{
+    body::{Bytes, Incoming},
+    header::{self, HeaderValue},
+    Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use juniper::{
+    futures::{
+        stream::{SplitSink, SplitStream},
+        SinkExt, StreamExt,
+    },
+    http::GraphQLResponse,
+    GraphQLError,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as Json;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::mpsc,
+    task::JoinHandle,
+};
+use tokio_tungstenite::{
+    tungstenite::{
+        handshake::derive_accept_key,
+        protocol::{frame::coding::CloseCode, CloseFrame, Role, WebSocketConfig},
+        Error as WsError, Message,
+    },
+    WebSocketStream,
+};
+
+use super::{authenticate, error_response, HttpResponse, Server};
+use crate::{
+    adapters::graphql::{
+        endpoint::{into_response, GraphQLRequest},
+        subscriptions,
+    },
+    core::types::db_uuid::DbUuid,
+    error::{Error, Result},
+};
+
+/// The only subprotocol spoken on `/subscriptions`
+const PROTOCOL: &str = "graphql-transport-ws";
+
+/// Largest message accepted from a client, fragments included
+const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// How long a client has to send `connection_init` after connecting
+const INIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Close codes of the protocol
+const CLOSE_NORMAL: u16 = 1000;
+const CLOSE_PROTOCOL_ERROR: u16 = 1002;
+const CLOSE_TOO_BIG: u16 = 1009;
+const CLOSE_BAD_REQUEST: u16 = 4400;
+const CLOSE_UNAUTHORIZED: u16 = 4401;
+const CLOSE_FORBIDDEN: u16 = 4403;
+const CLOSE_INIT_TIMEOUT: u16 = 4408;
+const CLOSE_DUPLICATE_SUBSCRIBER: u16 = 4409;
+const CLOSE_TOO_MANY_INITS: u16 = 4429;
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    ConnectionInit { payload: Option<Json> },
+    Ping { payload: Option<Json> },
+    Pong {},
+    Subscribe { id: String, payload: GraphQLRequest },
+    Complete { id: String },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    ConnectionAck,
+    Pong {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        payload: Option<Json>,
+    },
+    Next { id: String, payload: GraphQLResponse },
+    Error { id: String, payload: Vec<Json> },
+    Complete { id: String },
+}
+
+/// What the connection's writer task sends to the client
+enum Frame {
+    Message(ServerMessage),
+    Close(u16, String),
+}
+
+/// Checks the handshake and answers it. The socket itself is served once hyper hands the
+/// connection over.
+pub(super) async fn upgrade(mut request: Request<Incoming>, server: Arc<Server>) -> Result<HttpResponse> {
+    let headers = request.headers();
+    let is_upgrade = headers
+        .get(header::UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+    let accept = match headers.get(header::SEC_WEBSOCKET_KEY) {
+        Some(key) if is_upgrade => derive_accept_key(key.as_bytes()),
+        _ => return Ok(error_response(StatusCode::UPGRADE_REQUIRED, "UPGRADE_REQUIRED", "Expected a WebSocket upgrade")),
+    };
+    if headers.get(header::SEC_WEBSOCKET_VERSION).map(HeaderValue::as_bytes) != Some(b"13") {
+        return Err(Error::ValidationError("Unsupported WebSocket version".to_string()));
+    }
+    let speaks_protocol = headers
+        .get_all(header::SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|protocol| protocol.trim() == PROTOCOL);
+    if !speaks_protocol {
+        return Err(Error::ValidationError(format!("Only the {} subprotocol is supported", PROTOCOL)));
+    }
+
+    // A token on the upgrade request must be valid; without one, `connection_init` has to
+    // carry it
+    let user = match headers.get(header::AUTHORIZATION) {
+        Some(authorization) => Some(authenticate(Some(authorization), &server).await?),
+        None => None,
+    };
+
+    let upgrade = hyper::upgrade::on(&mut request);
+    tokio::spawn(async move {
+        match upgrade.await {
+            Ok(upgraded) => {
+                let config = WebSocketConfig {
+                    max_message_size: Some(MAX_MESSAGE_SIZE),
+                    max_frame_size: Some(MAX_MESSAGE_SIZE),
+                    ..Default::default()
+                };
+                let socket = WebSocketStream::from_raw_socket(TokioIo::new(upgraded), Role::Server, Some(config)).await;
+                serve_socket(socket, server, user).await
+            }
+            Err(e) => eprintln!("WebSocket upgrade failed: {}", e),
+        }
+    });
+
+    let mut response = Response::new(Full::new(Bytes::new()));
+    *response.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+    let headers = response.headers_mut();
+    headers.insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+    headers.insert(header::CONNECTION, HeaderValue::from_static("Upgrade"));
+    headers.insert(header::SEC_WEBSOCKET_PROTOCOL, HeaderValue::from_static(PROTOCOL));
+    headers.insert(
+        header::SEC_WEBSOCKET_ACCEPT,
+        HeaderValue::from_str(&accept).expect("Base64 is a valid header value"),
+    );
+
+    Ok(response)
+}
+
+async fn serve_socket<S>(socket: WebSocketStream<S>, server: Arc<Server>, header_user: Option<DbUuid>)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (sink, mut reader) = socket.split();
+    let (frames, frames_rx) = mpsc::unbounded_channel();
+    let writer_task = tokio::spawn(write_frames(sink, frames_rx));
+
+    let mut user: Option<DbUuid> = None;
+    let mut running: HashMap<String, JoinHandle<()>> = HashMap::new();
+
+    let close = loop {
+        let message = if user.is_none() {
+            match tokio::time::timeout(INIT_TIMEOUT, read_message(&mut reader)).await {
+                Ok(message) => message,
+                Err(_) => break (CLOSE_INIT_TIMEOUT, "Connection initialisation timeout"),
+            }
+        } else {
+            read_message(&mut reader).await
+        };
+
+        let text = match message {
+            Ok(Some(text)) => text,
+            Ok(None) => break (CLOSE_NORMAL, ""),
+            Err(close) => break close,
+        };
+        let message = match serde_json::from_str::<ClientMessage>(&text) {
+            Ok(message) => message,
+            Err(_) => break (CLOSE_BAD_REQUEST, "Invalid message received"),
+        };
+
+        match message {
+            ClientMessage::ConnectionInit { payload } => {
+                if user.is_some() {
+                    break (CLOSE_TOO_MANY_INITS, "Too many initialisation requests");
+                }
+                let authorization = payload
+                    .as_ref()
+                    .and_then(|payload| payload.get("Authorization").or_else(|| payload.get("authorization")))
+                    .and_then(Json::as_str)
+                    .and_then(|value| HeaderValue::from_str(value).ok());
+                user = match (header_user, authorization) {
+                    (_, Some(authorization)) => authenticate(Some(&authorization), &server).await.ok(),
+                    (Some(header_user), None) => Some(header_user),
+                    (None, None) => None,
+                };
+                if user.is_none() {
+                    break (CLOSE_FORBIDDEN, "Forbidden");
+                }
+                let _ = frames.send(Frame::Message(ServerMessage::ConnectionAck));
+            }
+            ClientMessage::Ping { payload } => {
+                let _ = frames.send(Frame::Message(ServerMessage::Pong { payload }));
+            }
+            ClientMessage::Pong {} => {}
+            ClientMessage::Subscribe { id, payload } => {
+                let Some(user) = user else {
+                    break (CLOSE_UNAUTHORIZED, "Unauthorized");
+                };
+                if running.get(&id).is_some_and(|task| !task.is_finished()) {
+                    break (CLOSE_DUPLICATE_SUBSCRIBER, "Subscriber already exists");
+                }
+                let task = tokio::spawn(run_operation(
+                    id.clone(),
+                    payload,
+                    Arc::clone(&server),
+                    user,
+                    frames.clone(),
+                ));
+                running.insert(id, task);
+            }
+            ClientMessage::Complete { id } => {
+                if let Some(task) = running.remove(&id) {
+                    task.abort();
+                }
+            }
+        }
+    };
+
+    for task in running.into_values() {
+        task.abort();
+    }
+    // A client that went away without a close frame just misses this one
+    let (code, reason) = close;
+    let _ = frames.send(Frame::Close(code, reason.to_string()));
+    drop(frames);
+    let _ = writer_task.await;
+}
+
+// Runs one operation of the socket, sending its results as `next` messages
+async fn run_operation(
+    id: String,
+    request: GraphQLRequest,
+    server: Arc<Server>,
+    user: DbUuid,
+    frames: mpsc::UnboundedSender<Frame>,
+) {
+    let context = server.state.for_user(user);
+    let variables = request.variables();
+    let send_next = |response: GraphQLResponse| {
+        frames.send(Frame::Message(ServerMessage::Next { id: id.clone(), payload: response })).is_ok()
+    };
+    let send_error = |message: Json| {
+        let payload = message.get("errors").and_then(Json::as_array).cloned().unwrap_or_default();
+        let _ = frames.send(Frame::Message(ServerMessage::Error { id: id.clone(), payload }));
+    };
+
+    let document = match server.endpoint.prepare(&request) {
+        Ok(document) => document,
+        Err(err) => {
+            send_error(serde_json::to_value(into_response(Err(err))).unwrap_or_default());
+            return;
+        }
+    };
+
+    match subscriptions::subscribe(&document, &variables, &server.endpoint.schema, &context).await {
+        Ok(mut responses) => {
+            while let Some((data, errors)) = responses.next().await {
+                if !send_next(GraphQLResponse::from_result(Ok((data, errors)))) {
+                    return;
+                }
+            }
+        }
+        // Queries and mutations are answered once
+        Err(GraphQLError::NotSubscription) => {
+            let response = into_response(server.endpoint.execute(&request, &context).await);
+            send_next(response);
+        }
+        Err(error) => {
+            let response: GraphQLResponse = GraphQLResponse::from_result(Err(error));
+            send_error(serde_json::to_value(response).unwrap_or_default());
+            return;
+        }
+    }
+
+    let _ = frames.send(Frame::Message(ServerMessage::Complete { id }));
+}
+
+/// Reads the next text message; tungstenite answers pings on the way. `None` means the client
+/// closed the connection; an error carries the close code and reason to send.
+async fn read_message<S>(
+    reader: &mut SplitStream<WebSocketStream<S>>,
+) -> std::result::Result<Option<String>, (u16, &'static str)>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    loop {
+        let message = match reader.next().await {
+            Some(Ok(message)) => message,
+            Some(Err(WsError::Capacity(_))) => return Err((CLOSE_TOO_BIG, "Message too big")),
+            Some(Err(WsError::Utf8)) => return Err((CLOSE_BAD_REQUEST, "Messages must be UTF-8")),
+            Some(Err(WsError::Protocol(_))) => return Err((CLOSE_PROTOCOL_ERROR, "Unexpected frame")),
+            Some(Err(_)) | None => return Ok(None),
+        };
+
+        match message {
+            Message::Text(text) => return Ok(Some(text)),
+            Message::Binary(_) => return Err((CLOSE_BAD_REQUEST, "Binary messages are not supported")),
+            Message::Close(_) => return Ok(None),
+            Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => {}
+        }
+    }
+}
+
+async fn write_frames<S>(mut sink: SplitSink<WebSocketStream<S>, Message>, mut frames: mpsc::UnboundedReceiver<Frame>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    while let Some(frame) = frames.recv().await {
+        let result = match frame {
+            Frame::Message(message) => {
+                let text = serde_json::to_string(&message).expect("Server messages serialize to JSON");
+                sink.send(Message::Text(text)).await
+            }
+            Frame::Close(code, reason) => {
+                let frame = CloseFrame { code: CloseCode::from(code), reason: reason.into() };
+                let _ = sink.send(Message::Close(Some(frame))).await;
+                break;
+            }
+        };
+        if result.is_err() {
+            break;
+        }
+    }
+
+    let _ = sink.close().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use serde_json::json;
+    use tokio::net::TcpStream;
+    use tokio_tungstenite::{client_async, tungstenite::client::IntoClientRequest};
+
+    use super::*;
+    use crate::{
+        adapters::incoming::http::tests::start_server,
+        core::events::{Event, SyncFinished},
+    };
+
+    struct Client {
+        socket: WebSocketStream<TcpStream>,
+    }
+
+    impl Client {
+        /// Connects to `/subscriptions`; a refused handshake gives its status
+        async fn connect(addr: std::net::SocketAddr, authorization: Option<&str>) -> std::result::Result<Self, u16> {
+            let mut request = format!("ws://{}/subscriptions", addr).into_client_request().unwrap();
+            let headers = request.headers_mut();
+            headers.insert(header::SEC_WEBSOCKET_PROTOCOL, HeaderValue::from_static(PROTOCOL));
+            if let Some(token) = authorization {
+                headers.insert(header::AUTHORIZATION, format!("Bearer {}", token).parse().unwrap());
+            }
+
+            let stream = TcpStream::connect(addr).await.unwrap();
+            match client_async(request, stream).await {
+                Ok((socket, _)) => Ok(Client { socket }),
+                Err(WsError::Http(response)) => Err(response.status().as_u16()),
+                Err(e) => panic!("Handshake failed: {}", e),
+            }
+        }
+
+        async fn send(&mut self, message: Json) {
+            self.socket.send(Message::Text(message.to_string())).await.unwrap();
+        }
+
+        async fn receive(&mut self) -> Message {
+            self.socket.next().await.unwrap().unwrap()
+        }
+
+        async fn receive_json(&mut self) -> Json {
+            match self.receive().await {
+                Message::Text(text) => serde_json::from_str(&text).unwrap(),
+                message => panic!("Expected a text message, got {:?}", message),
+            }
+        }
+
+        async fn receive_close(&mut self) -> u16 {
+            match self.receive().await {
+                Message::Close(Some(frame)) => frame.code.into(),
+                message => panic!("Expected a close frame, got {:?}", message),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscription_over_websocket() {
+        let (addr, token, _) = start_server().await;
+        let mut client = Client::connect(addr, None).await.ok().unwrap();
+
+        client
+            .send(json!({ "type": "connection_init", "payload": { "Authorization": format!("Bearer {}", token) } }))
+            .await;
+        assert_eq!(client.receive_json().await, json!({ "type": "connection_ack" }));
+
+        client
+            .send(json!({
+                "type": "subscribe",
+                "id": "1",
+                "payload": { "query": "subscription { syncFinished { finishedAt } }" },
+            }))
+            .await;
+        client
+            .send(json!({
+                "type": "subscribe",
+                "id": "2",
+                "payload": { "query": "mutation { createCart(cart: { notes: \"Bar\" }) { notes } }" },
+            }))
+            .await;
+
+        // The mutation is answered once and completed
+        assert_eq!(
+            client.receive_json().await,
+            json!({ "type": "next", "id": "2", "payload": { "data": { "createCart": { "notes": "Bar" } } } })
+        );
+        assert_eq!(client.receive_json().await, json!({ "type": "complete", "id": "2" }));
+
+        client.send(json!({ "type": "ping" })).await;
+        assert_eq!(client.receive_json().await, json!({ "type": "pong" }));
+
+        client.send(json!({ "type": "complete", "id": "1" })).await;
+    }
+
+    #[tokio::test]
+    async fn test_subscription_receives_events() {
+        let (addr, token, service) = start_server().await;
+        let mut client = Client::connect(addr, Some(&token)).await.ok().unwrap();
+
+        client.send(json!({ "type": "connection_init" })).await;
+        assert_eq!(client.receive_json().await, json!({ "type": "connection_ack" }));
+
+        client
+            .send(json!({
+                "type": "subscribe",
+                "id": "sync",
+                "payload": { "query": "subscription { syncFinished { finishedAt } }" },
+            }))
+            .await;
+
+        // Events published before the subscription starts are not delivered, so keep
+        // publishing until one arrives
+        let finished_at = NaiveDate::from_ymd_opt(2026, 3, 14).unwrap().and_hms_opt(9, 30, 0).unwrap();
+        let message = loop {
+            service.events.publish(Event::SyncFinished(SyncFinished { finished_at }));
+            if let Ok(message) = tokio::time::timeout(Duration::from_millis(50), client.receive_json()).await {
+                break message;
+            }
+        };
+        assert_eq!(message["type"], "next");
+        assert_eq!(message["id"], "sync");
+        assert_eq!(message["payload"]["data"]["syncFinished"]["finishedAt"], "2026-03-14 09:30:00");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_token_is_forbidden() {
+        let (addr, _, _) = start_server().await;
+
+        assert_eq!(Client::connect(addr, Some("lpos_not-a-token")).await.err(), Some(401));
+
+        let mut client = Client::connect(addr, None).await.ok().unwrap();
+        client
+            .send(json!({ "type": "connection_init", "payload": { "Authorization": "Bearer lpos_not-a-token" } }))
+            .await;
+        assert_eq!(client.receive_close().await, CLOSE_FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_before_init_is_unauthorized() {
+        let (addr, _, _) = start_server().await;
+        let mut client = Client::connect(addr, None).await.ok().unwrap();
+
+        client
+            .send(json!({ "type": "subscribe", "id": "1", "payload": { "query": "{ usersConnection { totalCount } }" } }))
+            .await;
+        assert_eq!(client.receive_close().await, CLOSE_UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_document_is_an_error_message() {
+        let (addr, token, _) = start_server().await;
+        let mut client = Client::connect(addr, Some(&token)).await.ok().unwrap();
+        client.send(json!({ "type": "connection_init" })).await;
+        client.receive_json().await;
+
+        client
+            .send(json!({ "type": "subscribe", "id": "1", "payload": { "query": "subscription { noSuchEvent }" } }))
+            .await;
+
+        let message = client.receive_json().await;
+        assert_eq!(message["type"], "error");
+        assert_eq!(message["id"], "1");
+        assert!(message["payload"][0]["message"].as_str().unwrap().contains("noSuchEvent"));
+    }
+}
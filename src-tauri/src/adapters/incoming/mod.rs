@@ -1 +1,4 @@
+#[cfg(feature = "desktop")]
 pub mod tauri;
+#[cfg(feature = "server")]
+pub mod http;
@@ -1,28 +1,125 @@
-use crate::adapters::graphql::{Mutation, Query, Schema};
-use crate::error::Result;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use crate::adapters::graphql::{
+    endpoint::{GraphQLEndpoint, GraphQLRequest, PersistedQuery, RequestExtensions},
+    subscriptions, Mutation, Query, Schema, Subscription,
+};
+use crate::error::{Error, Result};
 use crate::AppState;
-use juniper::{DefaultScalarValue, EmptySubscription, ExecutionError, Variables};
-use tauri::State;
+use juniper::{futures::StreamExt, DefaultScalarValue, ExecutionError, Variables};
+use serde::Serialize;
+use tauri::{async_runtime::JoinHandle, AppHandle, Emitter, State};
+use tokio::sync::oneshot;
+
+/// Tauri event that carries the responses of every running subscription
+pub const SUBSCRIPTION_EVENT: &str = "graphql-subscription";
+
+/// The subscriptions started by the UI, so they can be stopped again
+#[derive(Default)]
+pub struct Subscriptions {
+    next_id: AtomicU64,
+    running: Mutex<HashMap<u64, JoinHandle<()>>>,
+}
+
+/// One subscription response, tagged with the id returned by `subscribe`
+#[derive(Clone, Serialize)]
+struct SubscriptionPayload {
+    id: u64,
+    data: juniper::Value,
+    errors: Vec<ExecutionError<DefaultScalarValue>>,
+}
 
+/// Runs `query`, or the persisted query whose SHA-256 is `hash`
 #[tauri::command]
 pub async fn graphql(
-    query: String,
+    query: Option<String>,
+    hash: Option<String>,
+    operation_name: Option<String>,
     vars: Option<Variables<DefaultScalarValue>>,
     state: State<'_, AppState>,
+    endpoint: State<'_, GraphQLEndpoint>,
 ) -> Result<(juniper::Value, Vec<ExecutionError<DefaultScalarValue>>)> {
-    println!("yoyo query: {:?}", query);
-    juniper::execute(
-        &query,
-        None,
-        &Schema::new(Query, Mutation, EmptySubscription::new()),
-        vars.as_ref().unwrap_or(&Variables::new()),
-        &state,
-    )
-    .await
-    .map_err(|err| err.into())
+    let request = request(query, hash, operation_name, vars);
+    endpoint.execute(&request, &state.for_request()).await
+}
+
+/// Starts a subscription and returns its id. Every event is emitted as `SUBSCRIPTION_EVENT`
+/// until `unsubscribe` is called with the id.
+#[tauri::command]
+pub async fn subscribe(
+    query: Option<String>,
+    hash: Option<String>,
+    vars: Option<Variables<DefaultScalarValue>>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    endpoint: State<'_, GraphQLEndpoint>,
+    subscriptions: State<'_, Subscriptions>,
+) -> Result<u64> {
+    let id = subscriptions.next_id.fetch_add(1, Ordering::Relaxed);
+    let context = state.for_request();
+    let request = request(query, hash, None, vars);
+    let query = endpoint.prepare(&request)?;
+    let (started, started_rx) = oneshot::channel::<Result<()>>();
+
+    let task = tauri::async_runtime::spawn(async move {
+        let schema = Schema::new(Query, Mutation, Subscription);
+        let variables = request.variables();
+        let mut responses = match subscriptions::subscribe(&query, &variables, &schema, &context).await {
+            Ok(responses) => responses,
+            Err(err) => {
+                let _ = started.send(Err(err.into()));
+                return;
+            }
+        };
+        let _ = started.send(Ok(()));
+
+        while let Some((data, errors)) = responses.next().await {
+            let payload = SubscriptionPayload { id, data, errors };
+            if let Err(e) = app.emit(SUBSCRIPTION_EVENT, payload) {
+                eprintln!("Failed to emit subscription event: {}", e);
+            }
+        }
+    });
+
+    // Invalid documents are reported to the caller instead of being emitted
+    started_rx.await.unwrap_or_else(|_| {
+        Err(Error::ValidationError("Subscription could not be started".to_string()))
+    })?;
+    subscriptions.running.lock().unwrap().insert(id, task);
+
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn unsubscribe(id: u64, subscriptions: State<'_, Subscriptions>) {
+    if let Some(task) = subscriptions.running.lock().unwrap().remove(&id) {
+        task.abort();
+    }
+}
+
+fn request(
+    query: Option<String>,
+    hash: Option<String>,
+    operation_name: Option<String>,
+    variables: Option<Variables<DefaultScalarValue>>,
+) -> GraphQLRequest {
+    GraphQLRequest {
+        query,
+        operation_name,
+        variables,
+        extensions: Some(RequestExtensions {
+            persisted_query: hash.map(|sha256_hash| PersistedQuery { sha256_hash }),
+        }),
+    }
 }
 
 #[tauri::command]
 pub fn schema() -> String {
-    Schema::new(Query, Mutation, EmptySubscription::new()).as_sdl()
+    Schema::new(Query, Mutation, Subscription).as_sdl()
 }
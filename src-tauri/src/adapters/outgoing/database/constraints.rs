@@ -0,0 +1,182 @@
+//! Translates SQLite constraint failures into `Error::UniqueConstraintError` and
+//! `Error::ForeignKeyConstraintError`
+//!
+//! SQLite names the table and columns of a failed unique constraint in its message, but says
+//! nothing about which foreign key failed. For those the statement is run again inside a
+//! savepoint with foreign keys deferred, `PRAGMA foreign_key_check` reports the row that broke
+//! one, and the savepoint is rolled back.
+
+use crate::error::{Constraint, Error};
+
+const UNIQUE_FAILED: &str = "UNIQUE constraint failed: ";
+const FOREIGN_KEY_FAILED: &str = "FOREIGN KEY constraint failed";
+
+/// The error for a write that failed with `err`, described by `context` when it is not a
+/// constraint violation
+pub(super) async fn write_error(
+    conn: &libsql::Connection,
+    sql: &str,
+    err: libsql::Error,
+    context: &str,
+) -> Error {
+    let message = err.to_string();
+
+    if let Some((_, columns)) = message.split_once(UNIQUE_FAILED) {
+        return Error::UniqueConstraintError(unique_constraint(columns));
+    }
+    if message.contains(FOREIGN_KEY_FAILED) {
+        let table = written_table(sql).unwrap_or_default();
+        let constraint = match foreign_key_constraint(conn, sql, &table).await {
+            Ok(Some(constraint)) => constraint,
+            _ => Constraint { table, columns: Vec::new() },
+        };
+        return Error::ForeignKeyConstraintError(constraint);
+    }
+
+    Error::DatabaseError(format!("{}: {}", context, err))
+}
+
+// Parses the `items.name` or `prices.item_id, prices.currency` after UNIQUE_FAILED
+fn unique_constraint(columns: &str) -> Constraint {
+    let mut constraint = Constraint::default();
+    for column in columns.trim_end_matches(['`', '"']).split(", ") {
+        if let Some((table, column)) = column.trim().split_once('.') {
+            constraint.table = table.to_string();
+            constraint.columns.push(column.to_string());
+        }
+    }
+    constraint
+}
+
+// The table an INSERT, UPDATE or DELETE built by sea-query writes to
+fn written_table(sql: &str) -> Option<String> {
+    let rest = ["INSERT INTO ", "UPDATE ", "DELETE FROM "]
+        .iter()
+        .find_map(|prefix| sql.strip_prefix(prefix))?;
+    let table = rest.split_whitespace().next()?;
+    Some(table.trim_matches('"').to_string())
+}
+
+async fn foreign_key_constraint(
+    conn: &libsql::Connection,
+    sql: &str,
+    table: &str,
+) -> libsql::Result<Option<Constraint>> {
+    conn.execute("SAVEPOINT foreign_key_check", ()).await?;
+    let result = find_violation(conn, sql, table).await;
+    conn.execute("ROLLBACK TO foreign_key_check", ()).await?;
+    conn.execute("RELEASE foreign_key_check", ()).await?;
+    conn.execute("PRAGMA defer_foreign_keys = OFF", ()).await?;
+    result
+}
+
+async fn find_violation(
+    conn: &libsql::Connection,
+    sql: &str,
+    table: &str,
+) -> libsql::Result<Option<Constraint>> {
+    conn.execute("PRAGMA defer_foreign_keys = ON", ()).await?;
+    let mut rows = conn.query(sql, ()).await?;
+    while rows.next().await?.is_some() {}
+
+    // A write breaks a foreign key of its own table, a delete one of the tables referring to it
+    let mut violations = Vec::new();
+    let mut rows = conn.query("PRAGMA foreign_key_check", ()).await?;
+    while let Some(row) = rows.next().await? {
+        violations.push((row.get::<String>(0)?, row.get::<i64>(3)?));
+    }
+    let violation = violations
+        .iter()
+        .find(|(violating_table, _)| violating_table == table)
+        .or_else(|| violations.first());
+    let Some((violating_table, foreign_key_id)) = violation else {
+        return Ok(None);
+    };
+
+    let mut columns = Vec::new();
+    let pragma = format!("PRAGMA foreign_key_list(\"{}\")", violating_table);
+    let mut rows = conn.query(&pragma, ()).await?;
+    while let Some(row) = rows.next().await? {
+        if row.get::<i64>(0)? == *foreign_key_id {
+            columns.push(row.get::<String>(3)?);
+        }
+    }
+
+    Ok(Some(Constraint { table: violating_table.clone(), columns }))
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_query::{Expr, Query};
+
+    use super::*;
+    use crate::{
+        adapters::outgoing::database::DatabaseAdapter,
+        core::{
+            commands::tests::setup_service,
+            models::{auth::user_model::Users, catalog::item_model::Items},
+        },
+    };
+
+    #[test]
+    fn test_unique_constraint() {
+        let constraint = unique_constraint("prices.item_id, prices.currency`");
+
+        assert_eq!(constraint.table, "prices");
+        assert_eq!(constraint.columns, ["item_id", "currency"]);
+    }
+
+    #[test]
+    fn test_written_table() {
+        assert_eq!(written_table("INSERT INTO \"items\" (\"id\") VALUES ('1')").as_deref(), Some("items"));
+        assert_eq!(written_table("DELETE FROM \"items\" WHERE 1").as_deref(), Some("items"));
+        assert_eq!(written_table("SELECT 1"), None);
+    }
+
+    #[tokio::test]
+    async fn test_unique_violation() {
+        let service = setup_service().await;
+        let insert = |id: &str| {
+            Query::insert()
+                .into_table(Users::Table)
+                .columns([Users::Id, Users::Username, Users::PinHash, Users::FullName, Users::State])
+                .values_panic([id.into(), "cashier".into(), "x".into(), "Cashier".into(), "active".into()])
+                .to_owned()
+        };
+        service.db_adapter.insert_many(&insert("u1")).await.unwrap();
+
+        let err = service.db_adapter.insert_many(&insert("u2")).await.unwrap_err();
+
+        match err {
+            Error::UniqueConstraintError(constraint) => {
+                assert_eq!(constraint, Constraint::new("users", "username"))
+            }
+            err => panic!("Expected a unique constraint error, got {}", err),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_foreign_key_violation() {
+        let service = setup_service().await;
+        let insert = Query::insert()
+            .into_table(Items::Table)
+            .columns([Items::Id, Items::CategoryId, Items::Name, Items::Nature, Items::State, Items::Price])
+            .values_panic(["i1".into(), "missing".into(), "Tea".into(), "goods".into(), "active".into(), 100.into()])
+            .to_owned();
+        let err = service.db_adapter.insert_many(&insert).await.unwrap_err();
+
+        match err {
+            Error::ForeignKeyConstraintError(constraint) => {
+                assert_eq!(constraint, Constraint::new("items", "category_id"))
+            }
+            err => panic!("Expected a foreign key constraint error, got {}", err),
+        }
+
+        // The diagnosis leaves nothing behind
+        let count = Query::select()
+            .expr(Expr::col(Items::Id).count())
+            .from(Items::Table)
+            .to_owned();
+        assert_eq!(service.db_adapter.query_one::<i64>(&count).await.unwrap(), 0);
+    }
+}
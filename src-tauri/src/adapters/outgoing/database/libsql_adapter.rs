@@ -1,12 +1,13 @@
 use std::future::Future;
-use std::pin::Pin;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::sync::{Mutex, OwnedMutexGuard};
 
 use sea_query::{SelectStatement, InsertStatement, UpdateStatement, DeleteStatement, SqliteQueryBuilder};
 
 use crate::{
-    adapters::outgoing::database::{FromRow, DatabaseAdapter, DatabaseRow},
+    adapters::outgoing::database::{constraints::write_error, FromRow, DatabaseAdapter, DatabaseRow},
     error::{Error, Result},
 };
 
@@ -17,18 +18,136 @@ pub trait FromLibsqlValue: Sized + Send {
     fn from_libsql_value(value: libsql::Value) -> Result<Option<Self>>;
 }
 
+tokio::task_local! {
+    // The write connection held by the transaction running on this task
+    static TRANSACTION: Arc<Mutex<OwnedMutexGuard<libsql::Connection>>>;
+}
+
+/// A connection locked for one statement
+enum Locked {
+    Pool(OwnedMutexGuard<libsql::Connection>),
+    Transaction(OwnedMutexGuard<OwnedMutexGuard<libsql::Connection>>),
+}
+
+impl Deref for Locked {
+    type Target = libsql::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Locked::Pool(conn) => conn,
+            Locked::Transaction(conn) => conn,
+        }
+    }
+}
+
+/// The database and the connections opened on it
+struct Connections {
+    db: libsql::Database,
+    // All inserts, updates and deletes go through this connection
+    writer: Arc<Mutex<libsql::Connection>>,
+    // Selects are spread over these; when empty they use the writer
+    readers: Vec<Arc<Mutex<libsql::Connection>>>,
+}
+
+impl Connections {
+    fn new(db: libsql::Database, conn: libsql::Connection, readers: Vec<libsql::Connection>) -> Self {
+        Self {
+            db,
+            writer: Arc::new(Mutex::new(conn)),
+            readers: readers.into_iter().map(|conn| Arc::new(Mutex::new(conn))).collect(),
+        }
+    }
+
+    async fn sync(&self) {
+        if let Err(e) = self.db.sync().await {
+            eprintln!("Failed to sync database: {}", e);
+        }
+    }
+}
+
 /// LibSQLAdapter implements the DatabaseAdapter trait for LibSQL
+///
+/// Writes go through one connection while selects run on a small pool of read connections,
+/// so a long report does not hold up a sale. The connections can be swapped with `replace`
+/// while the adapter is in use.
 pub struct LibSqlAdapter {
-    db: libsql::Database,
-    conn: Arc<Mutex<libsql::Connection>>,
+    connections: RwLock<Arc<Connections>>,
+    next_reader: AtomicUsize,
+    // Number of select queries run, to check that GraphQL lookups are batched
+    query_count: AtomicU64,
 }
 
 impl LibSqlAdapter {
-    /// Create a new LibSQLAdapter with the given connection
+    /// Create a new LibSQLAdapter with the given connection, used for both reads and writes
     pub fn new(db: libsql::Database, conn: libsql::Connection) -> Self {
+        Self::with_readers(db, conn, Vec::new())
+    }
+
+    /// Create a new LibSQLAdapter that writes on `conn` and reads on `readers`
+    pub fn with_readers(
+        db: libsql::Database,
+        conn: libsql::Connection,
+        readers: Vec<libsql::Connection>,
+    ) -> Self {
         Self {
-            db,
-            conn: Arc::new(Mutex::new(conn))
+            connections: RwLock::new(Arc::new(Connections::new(db, conn, readers))),
+            next_reader: AtomicUsize::new(0),
+            query_count: AtomicU64::new(0),
+        }
+    }
+
+    /// Switch to another database. Queries already running finish on the old connections.
+    pub fn replace(
+        &self,
+        db: libsql::Database,
+        conn: libsql::Connection,
+        readers: Vec<libsql::Connection>,
+    ) {
+        let connections = Arc::new(Connections::new(db, conn, readers));
+        *self.connections.write().expect("Connections lock poisoned") = connections;
+    }
+
+    /// Number of select queries this adapter has run
+    pub fn query_count(&self) -> u64 {
+        self.query_count.load(Ordering::Relaxed)
+    }
+
+    fn connections(&self) -> Arc<Connections> {
+        Arc::clone(&self.connections.read().expect("Connections lock poisoned"))
+    }
+
+    // The connection of the transaction running on this task, if there is one
+    async fn in_transaction() -> Option<Locked> {
+        let held = TRANSACTION.try_with(Arc::clone).ok()?;
+        Some(Locked::Transaction(held.lock_owned().await))
+    }
+
+    // Picks an idle read connection, or the next one in turn when all of them are busy
+    async fn reader(&self) -> Locked {
+        if let Some(conn) = Self::in_transaction().await {
+            return conn;
+        }
+
+        let connections = self.connections();
+        if connections.readers.is_empty() {
+            return Locked::Pool(Arc::clone(&connections.writer).lock_owned().await);
+        }
+
+        let count = connections.readers.len();
+        let start = self.next_reader.fetch_add(1, Ordering::Relaxed);
+        let idle = (0..count)
+            .map(|offset| &connections.readers[(start + offset) % count])
+            .find(|reader| reader.try_lock().is_ok());
+
+        let reader = Arc::clone(idle.unwrap_or(&connections.readers[start % count]));
+        Locked::Pool(reader.lock_owned().await)
+    }
+
+    // The write connection, waiting for a transaction of another task to finish
+    async fn writer(&self, connections: &Connections) -> Locked {
+        match Self::in_transaction().await {
+            Some(conn) => conn,
+            None => Locked::Pool(Arc::clone(&connections.writer).lock_owned().await),
         }
     }
 
@@ -48,9 +167,10 @@ impl DatabaseAdapter for LibSqlAdapter {
     {
         // Convert the SelectStatement to SQL string
         let sql = query.to_string(SqliteQueryBuilder);
+        self.query_count.fetch_add(1, Ordering::Relaxed);
 
-        // Get a lock on the connection
-        let conn = self.conn.lock().await;
+        // Get a lock on a read connection
+        let conn = self.reader().await;
 
         // Execute the query
         let mut stmt = conn.prepare(&sql).await
@@ -80,9 +200,10 @@ impl DatabaseAdapter for LibSqlAdapter {
     {
         // Convert the SelectStatement to SQL string
         let sql = query.to_string(SqliteQueryBuilder);
+        self.query_count.fetch_add(1, Ordering::Relaxed);
 
-        // Get a lock on the connection
-        let conn = self.conn.lock().await;
+        // Get a lock on a read connection
+        let conn = self.reader().await;
 
         // Execute the query
         let mut stmt = conn.prepare(&sql).await
@@ -113,9 +234,10 @@ impl DatabaseAdapter for LibSqlAdapter {
     {
         // Convert the SelectStatement to SQL string
         let sql = query.to_string(SqliteQueryBuilder);
+        self.query_count.fetch_add(1, Ordering::Relaxed);
 
-        // Get a lock on the connection
-        let conn = self.conn.lock().await;
+        // Get a lock on a read connection
+        let conn = self.reader().await;
 
         // Execute the query
         let mut stmt = conn.prepare(&sql).await
@@ -150,24 +272,26 @@ impl DatabaseAdapter for LibSqlAdapter {
         // This ensures we get columns in a predictable order
         sql = format!("{} RETURNING *", sql);
 
-        // Get a lock on the connection
-        let conn = self.conn.lock().await;
+        // Get a lock on the write connection
+        let connections = self.connections();
+        let conn = self.writer(&connections).await;
 
         // Execute the query with RETURNING clause
         let mut stmt = conn.prepare(&sql).await
             .map_err(|e| Error::DatabaseError(format!("Failed to prepare statement: {}", e)))?;
 
-        let mut rows = stmt.query(()).await
-            .map_err(|e| Error::DatabaseError(format!("Failed to execute query: {}", e)))?;
+        let mut rows = match stmt.query(()).await {
+            Ok(rows) => rows,
+            Err(e) => return Err(write_error(&conn, &sql, e, "Failed to execute query").await),
+        };
 
-        let sync_res = self.db.sync().await;
-        if sync_res.is_err() {
-            eprintln!("Failed to sync database: {}", sync_res.unwrap_err());
-        }
+        connections.sync().await;
 
         // Get the first row
-        let row = rows.next().await
-            .map_err(|e| Error::DatabaseError(format!("Failed to get next row: {}", e)))?;
+        let row = match rows.next().await {
+            Ok(row) => row,
+            Err(e) => return Err(write_error(&conn, &sql, e, "Failed to get next row").await),
+        };
 
         match row {
             Some(row) => {
@@ -184,10 +308,7 @@ impl DatabaseAdapter for LibSqlAdapter {
         // Execute the query and return the number of affected rows
         let result = self.execute(&sql).await;
 
-        let sync_res = self.db.sync().await;
-        if sync_res.is_err() {
-            eprintln!("Failed to sync database: {}", sync_res.unwrap_err());
-        }
+        self.connections().sync().await;
 
         result
     }
@@ -204,19 +325,24 @@ impl DatabaseAdapter for LibSqlAdapter {
         // This ensures we get columns in a predictable order
         sql = format!("{} RETURNING *", sql);
 
-        // Get a lock on the connection
-        let conn = self.conn.lock().await;
+        // Get a lock on the write connection
+        let connections = self.connections();
+        let conn = self.writer(&connections).await;
 
         // Execute the query with RETURNING clause
         let mut stmt = conn.prepare(&sql).await
             .map_err(|e| Error::DatabaseError(format!("Failed to prepare statement: {}", e)))?;
 
-        let mut rows = stmt.query(()).await
-            .map_err(|e| Error::DatabaseError(format!("Failed to execute query: {}", e)))?;
+        let mut rows = match stmt.query(()).await {
+            Ok(rows) => rows,
+            Err(e) => return Err(write_error(&conn, &sql, e, "Failed to execute query").await),
+        };
 
         // Get the first row
-        let row = rows.next().await
-            .map_err(|e| Error::DatabaseError(format!("Failed to get next row: {}", e)))?;
+        let row = match rows.next().await {
+            Ok(row) => row,
+            Err(e) => return Err(write_error(&conn, &sql, e, "Failed to get next row").await),
+        };
 
         // Check if we got a row
         match row {
@@ -235,10 +361,7 @@ impl DatabaseAdapter for LibSqlAdapter {
         // Execute the query and return the number of affected rows
         let result = self.execute(&sql).await;
 
-        let sync_res = self.db.sync().await;
-        if sync_res.is_err() {
-            eprintln!("Failed to sync database: {}", sync_res.unwrap_err());
-        }
+        self.connections().sync().await;
 
         result
     }
@@ -255,24 +378,26 @@ impl DatabaseAdapter for LibSqlAdapter {
         // Since we're mapping columns by name in LibsqlFromRow, we can use RETURNING *
         sql = format!("{} RETURNING *", sql);
 
-        // Get a lock on the connection
-        let conn = self.conn.lock().await;
+        // Get a lock on the write connection
+        let connections = self.connections();
+        let conn = self.writer(&connections).await;
 
         // Execute the query with RETURNING clause
         let mut stmt = conn.prepare(&sql).await
             .map_err(|e| Error::DatabaseError(format!("Failed to prepare statement: {}", e)))?;
 
-        let mut rows = stmt.query(()).await
-            .map_err(|e| Error::DatabaseError(format!("Failed to execute query: {}", e)))?;
+        let mut rows = match stmt.query(()).await {
+            Ok(rows) => rows,
+            Err(e) => return Err(write_error(&conn, &sql, e, "Failed to execute query").await),
+        };
 
-        let sync_res = self.db.sync().await;
-        if sync_res.is_err() {
-            eprintln!("Failed to sync database: {}", sync_res.unwrap_err());
-        }
+        connections.sync().await;
 
         // Get the first row
-        let row = rows.next().await
-            .map_err(|e| Error::DatabaseError(format!("Failed to get next row: {}", e)))?;
+        let row = match rows.next().await {
+            Ok(row) => row,
+            Err(e) => return Err(write_error(&conn, &sql, e, "Failed to get next row").await),
+        };
 
         // Check if we got a row
         match row {
@@ -290,38 +415,45 @@ impl DatabaseAdapter for LibSqlAdapter {
         // Execute the query and return the number of affected rows
         let result = self.execute(&sql).await;
 
-        let sync_res = self.db.sync().await;
-        if sync_res.is_err() {
-            eprintln!("Failed to sync database: {}", sync_res.unwrap_err());
-        }
+        self.connections().sync().await;
 
         result
     }
 
-    async fn transaction<F, R>(&self, f: F) -> Result<R>
+    async fn transaction<F, Fut, R>(&self, f: F) -> Result<R>
     where
-        F: FnOnce(&Self) -> Pin<Box<dyn Future<Output = Result<R>> + Send>> + Send,
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = Result<R>> + Send,
         R: Send,
     {
-        // Get a lock on the connection
-        let conn = self.conn.lock().await;
+        if TRANSACTION.try_with(|_| ()).is_ok() {
+            return Err(Error::DatabaseError("Transactions cannot be nested".to_string()));
+        }
+
+        // Hold the write connection so that no other task writes into the transaction
+        let conn = Arc::clone(&self.connections().writer).lock_owned().await;
+
+        // A transaction whose task was dropped before it finished is still open
+        if !conn.is_autocommit() {
+            conn.execute("ROLLBACK", ()).await
+                .map_err(|e| Error::DatabaseError(format!("Failed to rollback transaction: {}", e)))?;
+        }
 
-        // Begin transaction
-        conn.execute("BEGIN TRANSACTION", Vec::<libsql::Value>::new()).await
+        conn.execute("BEGIN TRANSACTION", ()).await
             .map_err(|e| Error::DatabaseError(format!("Failed to begin transaction: {}", e)))?;
 
-        // Execute the function
-        let future = f(self);
-        let result = future.await;
+        let held = Arc::new(Mutex::new(conn));
+        let result = TRANSACTION.scope(Arc::clone(&held), f()).await;
+        let conn = held.lock().await;
 
         // Commit or rollback transaction based on the result
         match &result {
             Ok(_) => {
-                conn.execute("COMMIT", Vec::<libsql::Value>::new()).await
+                conn.execute("COMMIT", ()).await
                     .map_err(|e| Error::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
             },
             Err(_) => {
-                conn.execute("ROLLBACK", Vec::<libsql::Value>::new()).await
+                conn.execute("ROLLBACK", ()).await
                     .map_err(|e| Error::DatabaseError(format!("Failed to rollback transaction: {}", e)))?;
             },
         }
@@ -330,15 +462,18 @@ impl DatabaseAdapter for LibSqlAdapter {
     }
 
     async fn execute(&self, query: &str) -> Result<u64> {
-        // Get a lock on the connection
-        let conn = self.conn.lock().await;
+        // Get a lock on the write connection
+        let connections = self.connections();
+        let conn = self.writer(&connections).await;
 
         // Extract parameters from the query
         let params = self.extract_params(query).await?;
 
         // Execute the query
-        let result = conn.execute(query, params).await
-            .map_err(|e| Error::DatabaseError(format!("Failed to execute statement: {}", e)))?;
+        let result = match conn.execute(query, params).await {
+            Ok(result) => result,
+            Err(e) => return Err(write_error(&conn, query, e, "Failed to execute statement").await),
+        };
 
         // Return the number of affected rows
         Ok(result)
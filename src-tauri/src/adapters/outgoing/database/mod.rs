@@ -1,12 +1,12 @@
 mod libsql_adapter;
 mod from_row_impls;
+mod constraints;
 
 pub use libsql_adapter::LibSqlAdapter;
 
 use sea_query::{SelectStatement, InsertStatement, UpdateStatement, DeleteStatement};
 
 use std::future::Future;
-use std::pin::Pin;
 
 use crate::{
     core::types::db_uuid::DbUuid,
@@ -69,10 +69,14 @@ pub trait DatabaseAdapter: Send + Sync {
     /// Delete rows matching the filter and return the number of rows affected
     fn delete(&self, query: &DeleteStatement) -> impl Future<Output = Result<u64>> + Send;
 
-    /// Execute a transaction
-    fn transaction<F, R>(&self, f: F) -> impl Future<Output = Result<R>> + Send
+    /// Runs `f` in a transaction, committing when it returns `Ok` and rolling back otherwise.
+    /// The write connection is held until then: the queries `f` makes run on it and see its
+    /// writes, while every other query keeps to the read connections and sees only what
+    /// was committed.
+    fn transaction<F, Fut, R>(&self, f: F) -> impl Future<Output = Result<R>> + Send
     where
-        F: FnOnce(&Self) -> Pin<Box<dyn Future<Output = Result<R>> + Send>> + Send,
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = Result<R>> + Send,
         R: Send;
 
     /// Execute a query that doesn't return rows but returns the number of affected rows
@@ -0,0 +1,434 @@
+//! Client for the IAM service, which signs a shop in and hands out the credentials of the
+//! database its replica syncs with
+//!
+//! Those credentials expire. Signing in also gives a refresh token, which is spent on a new
+//! session shortly before they do; the service answers with the next refresh token, so each
+//! one works once. The last session the service gave is kept in the secret store. While the
+//! service cannot be reached that session keeps the app working for a grace period, counted
+//! from when the service last vouched for it.
+//!
+//! A refresh that went unanswered may still have been spent, so it is never sent twice, and
+//! the service refusing the token after one is not taken as the end of the session.
+
+mod session;
+
+#[cfg(test)]
+pub mod mock;
+
+pub use session::Session;
+
+use std::time::Duration;
+
+use chrono::Utc;
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    adapters::outgoing::secrets::{self, Secret, SecretName, SecretStore},
+    error::{Error, Result},
+};
+
+/// Where the IAM service is when the secret store does not say
+pub const DEFAULT_IAM_URL: &str = "http://localhost:7001";
+
+/// How long a request may take, unless the `iam-timeout-secs` secret says
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How many times a failed request is tried again, unless the `iam-retries` secret says
+const DEFAULT_RETRIES: u32 = 2;
+
+/// The wait before the first retry. It doubles with each one after.
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// How long a session works without the service, unless the `iam-offline-grace-hours` secret
+/// says
+const DEFAULT_GRACE_HOURS: i64 = 72;
+
+/// How the IAM service is reached, and how long the app works without it
+#[derive(Debug, Clone)]
+pub struct IamConfig {
+    pub base_url: String,
+    pub timeout: Duration,
+    pub retries: u32,
+    pub retry_delay: Duration,
+    pub grace_period: chrono::Duration,
+}
+
+impl Default for IamConfig {
+    fn default() -> Self {
+        IamConfig {
+            base_url: DEFAULT_IAM_URL.to_string(),
+            timeout: DEFAULT_TIMEOUT,
+            retries: DEFAULT_RETRIES,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            grace_period: chrono::Duration::hours(DEFAULT_GRACE_HOURS),
+        }
+    }
+}
+
+impl IamConfig {
+    /// The settings kept in the secret store, `iam-url`, `iam-timeout-secs`, `iam-retries` and
+    /// `iam-offline-grace-hours`, each falling back to its default when missing or invalid
+    pub fn load(secrets: &dyn SecretStore) -> Result<Self> {
+        let defaults = IamConfig::default();
+        let base_url = match secrets.get(SecretName::IamUrl)? {
+            Some(url) => url.expose().trim_end_matches('/').to_string(),
+            None => defaults.base_url,
+        };
+        Ok(IamConfig {
+            base_url,
+            timeout: secrets::get_number::<u64>(secrets, SecretName::IamTimeoutSecs)?
+                .filter(|secs| *secs > 0)
+                .map_or(defaults.timeout, Duration::from_secs),
+            retries: secrets::get_number(secrets, SecretName::IamRetries)?.unwrap_or(defaults.retries),
+            retry_delay: defaults.retry_delay,
+            grace_period: secrets::get_number::<i64>(secrets, SecretName::IamOfflineGraceHours)?
+                .filter(|hours| *hours >= 0)
+                .map_or(defaults.grace_period, chrono::Duration::hours),
+        })
+    }
+}
+
+/// How `IamClient::current_session` came by its session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionStatus {
+    /// The cached session, which is not about to expire
+    Current,
+    /// A new session, with a new token to sync with
+    Refreshed,
+    /// The cached session, which is due for a refresh, while the service cannot be reached
+    Offline,
+}
+
+#[derive(Serialize)]
+struct LoginRequest<'a> {
+    username: &'a str,
+    password: &'a str,
+}
+
+#[derive(Serialize)]
+struct RefreshRequest<'a> {
+    refresh_token: &'a Secret,
+}
+
+/// What the service answers a login or a refresh with
+#[derive(Deserialize)]
+struct SessionResponse {
+    turso_url: Secret,
+    turso_token: Secret,
+    #[serde(default)]
+    refresh_token: Option<Secret>,
+    /// Seconds until `turso_token` expires
+    #[serde(default)]
+    expires_in: Option<i64>,
+}
+
+impl SessionResponse {
+    fn into_session(self, previous: Option<&Session>) -> Session {
+        let verified_at = Utc::now().naive_utc();
+        Session {
+            turso_url: self.turso_url,
+            turso_token: self.turso_token,
+            // A service that does not rotate refresh tokens leaves out the one still in use
+            refresh_token: self.refresh_token.or_else(|| previous.and_then(|session| session.refresh_token.clone())),
+            expires_at: self.expires_in.map(|secs| verified_at + chrono::Duration::seconds(secs)),
+            verified_at,
+        }
+    }
+}
+
+pub struct IamClient {
+    config: IamConfig,
+    http: reqwest::Client,
+    // Refreshes run one at a time, as each spends the refresh token the next one needs. Set
+    // while the last refresh went unanswered, when the service may have spent the token.
+    refreshing: tokio::sync::Mutex<bool>,
+}
+
+impl IamClient {
+    pub fn new(config: IamConfig) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .expect("Failed to build the IAM HTTP client");
+        IamClient { config, http, refreshing: tokio::sync::Mutex::new(false) }
+    }
+
+    /// Signs in with the shop's credentials. Wrong ones are an `AuthenticationError`.
+    pub async fn login(&self, username: &str, password: &str) -> Result<Session> {
+        let request = LoginRequest { username, password };
+        let response: SessionResponse = self.post("/users/login", &request, true).await?;
+        *self.refreshing.lock().await = false;
+        Ok(response.into_session(None))
+    }
+
+    /// Spends the session's refresh token on a new session. A token the service no longer
+    /// accepts, because it was already spent or revoked, is an `AuthenticationError`. The
+    /// request is only tried again when it could not be sent.
+    pub async fn refresh(&self, session: &Session) -> Result<Session> {
+        let refresh_token = session.refresh_token.as_ref().ok_or(Error::AuthenticationError)?;
+        let response: SessionResponse = self.post("/users/refresh", &RefreshRequest { refresh_token }, false).await?;
+        Ok(response.into_session(Some(session)))
+    }
+
+    /// The session to sync with: the cached one until it is about to expire, then a refreshed
+    /// one, which replaces it in `secrets`. A session the service refuses to refresh is
+    /// removed, unless the refresh before went unanswered and may have spent its token. While
+    /// the service cannot be reached the cached session is used until the grace period is
+    /// over. Without a usable session this is an `AuthenticationError`.
+    pub async fn current_session(&self, secrets: &dyn SecretStore) -> Result<(Session, SessionStatus)> {
+        let mut unanswered = self.refreshing.lock().await;
+        // Read after taking the lock, so a refresh that ran meanwhile is seen
+        let session = Session::load(secrets)?.ok_or(Error::AuthenticationError)?;
+        let now = Utc::now().naive_utc();
+        if !session.needs_refresh(now) {
+            return Ok((session, SessionStatus::Current));
+        }
+        if session.refresh_token.is_none() {
+            return match session.is_expired(now) {
+                false => Ok((session, SessionStatus::Current)),
+                true => Err(Error::AuthenticationError),
+            };
+        }
+
+        let result = match self.refresh(&session).await {
+            Err(Error::AuthenticationError) if *unanswered => Err(Error::IamUnavailableError(
+                "The refresh token was refused after a refresh that went unanswered".to_string(),
+            )),
+            result => result,
+        };
+        *unanswered = matches!(result, Err(Error::IamUnavailableError(_)));
+
+        match result {
+            Ok(refreshed) => {
+                refreshed.save(secrets)?;
+                Ok((refreshed, SessionStatus::Refreshed))
+            }
+            Err(Error::AuthenticationError) => {
+                Session::clear(secrets)?;
+                Err(Error::AuthenticationError)
+            }
+            Err(Error::IamUnavailableError(_)) if now - session.verified_at <= self.config.grace_period => {
+                Ok((session, SessionStatus::Offline))
+            }
+            Err(Error::IamUnavailableError(_)) => Err(Error::AuthenticationError),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Posts `body` as JSON, trying again after failures that can pass: no answer, a timeout,
+    // a server error or being asked to slow down. Unless `resend`, only a request that could
+    // not be sent is tried again, as one the service saw may have taken effect.
+    async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B, resend: bool) -> Result<T> {
+        let url = format!("{}{}", self.config.base_url, path);
+        let mut attempt = 0;
+        loop {
+            let response = self.http.post(&url).json(body).send().await;
+            let sent = !matches!(&response, Err(e) if e.is_connect());
+            let failure = match response {
+                Ok(response) if response.status().is_success() => {
+                    return response
+                        .json::<T>()
+                        .await
+                        .map_err(|e| Error::IamUnavailableError(format!("Unexpected answer from {}: {}", url, e)));
+                }
+                Ok(response) => match response.status() {
+                    StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => return Err(Error::AuthenticationError),
+                    status @ (StatusCode::REQUEST_TIMEOUT | StatusCode::TOO_MANY_REQUESTS) => {
+                        format!("{} answered {}", url, status)
+                    }
+                    status if status.is_client_error() => {
+                        return Err(Error::ValidationError(format!("The IAM service refused the request ({})", status)));
+                    }
+                    status => format!("{} answered {}", url, status),
+                },
+                Err(e) => format!("Failed to reach {}: {}", url, e),
+            };
+
+            if attempt >= self.config.retries || (sent && !resend) {
+                return Err(Error::IamUnavailableError(failure));
+            }
+            tokio::time::sleep(self.config.retry_delay * 2u32.saturating_pow(attempt)).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    use serde_json::json;
+
+    use super::{mock::MockIam, *};
+    use crate::adapters::outgoing::secrets::MemorySecretStore;
+
+    fn client(url: &str) -> IamClient {
+        IamClient::new(IamConfig {
+            base_url: url.to_string(),
+            timeout: Duration::from_millis(200),
+            retries: 2,
+            retry_delay: Duration::from_millis(10),
+            ..IamConfig::default()
+        })
+    }
+
+    fn cached_session(expires_in: i64, verified_hours_ago: i64) -> Session {
+        let now = Utc::now().naive_utc();
+        Session {
+            turso_url: Secret::new("libsql://shop.turso.io"),
+            turso_token: Secret::new("token-0"),
+            refresh_token: Some(Secret::new("refresh-0")),
+            expires_at: Some(now + chrono::Duration::seconds(expires_in)),
+            verified_at: now - chrono::Duration::hours(verified_hours_ago),
+        }
+    }
+
+    #[test]
+    fn test_config_from_secret_store() {
+        let secrets = MemorySecretStore::default();
+        let config = IamConfig::load(&secrets).unwrap();
+        assert_eq!(config.base_url, DEFAULT_IAM_URL);
+        assert_eq!(config.timeout, DEFAULT_TIMEOUT);
+        assert_eq!(config.retries, DEFAULT_RETRIES);
+        assert_eq!(config.grace_period, chrono::Duration::hours(DEFAULT_GRACE_HOURS));
+
+        secrets.set(SecretName::IamUrl, &Secret::new("https://iam.example.com/")).unwrap();
+        secrets.set(SecretName::IamTimeoutSecs, &Secret::new("30")).unwrap();
+        secrets.set(SecretName::IamRetries, &Secret::new(" 5\n")).unwrap();
+        secrets.set(SecretName::IamOfflineGraceHours, &Secret::new("-1")).unwrap();
+        let config = IamConfig::load(&secrets).unwrap();
+        assert_eq!(config.base_url, "https://iam.example.com");
+        assert_eq!(config.timeout, Duration::from_secs(30));
+        assert_eq!(config.retries, 5);
+        assert_eq!(config.grace_period, chrono::Duration::hours(DEFAULT_GRACE_HOURS));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_token_rotation() {
+        // Like the service, only the refresh token given last is accepted
+        let latest = Arc::new(Mutex::new(0));
+        let state = Arc::clone(&latest);
+        let mock = MockIam::start(move |path, body| {
+            let mut latest = state.lock().unwrap();
+            let presented = body["refresh_token"].as_str().and_then(|token| token.strip_prefix("refresh-"));
+            match path {
+                "/users/login" if body["password"] == "secret" => *latest = 1,
+                "/users/refresh" if *latest > 0 && presented == Some(&latest.to_string()) => *latest += 1,
+                _ => return (401, json!({ "error": "unauthorized" })),
+            }
+            let n = *latest;
+            let expires_in = if n == 1 { 60 } else { 3600 };
+            (200, json!({ "turso_url": "libsql://shop.turso.io", "turso_token": format!("token-{n}"),
+                          "refresh_token": format!("refresh-{n}"), "expires_in": expires_in }))
+        });
+        let client = client(&mock.url);
+        let secrets = MemorySecretStore::default();
+
+        assert!(matches!(client.login("shop", "wrong").await, Err(Error::AuthenticationError)));
+        assert!(matches!(client.current_session(&secrets).await, Err(Error::AuthenticationError)));
+
+        let session = client.login("shop", "secret").await.unwrap();
+        session.save(&secrets).unwrap();
+
+        // Expiring within the refresh margin, so it is refreshed
+        let (session, status) = client.current_session(&secrets).await.unwrap();
+        assert_eq!(status, SessionStatus::Refreshed);
+        assert_eq!(session.turso_token.expose(), "token-2");
+        let cached = Session::load(&secrets).unwrap().unwrap();
+        assert_eq!(cached.refresh_token, Some(Secret::new("refresh-2")));
+
+        let (session, status) = client.current_session(&secrets).await.unwrap();
+        assert_eq!(status, SessionStatus::Current);
+        assert_eq!(session.turso_token.expose(), "token-2");
+        assert_eq!(mock.requests().len(), 3);
+
+        // A spent refresh token ends the session
+        let mut replayed = cached_session(-60, 1);
+        replayed.refresh_token = Some(Secret::new("refresh-1"));
+        replayed.save(&secrets).unwrap();
+        assert!(matches!(client.current_session(&secrets).await, Err(Error::AuthenticationError)));
+        assert!(Session::load(&secrets).unwrap().is_none());
+
+        // So does one that expired with nothing to refresh it with
+        replayed.refresh_token = None;
+        replayed.save(&secrets).unwrap();
+        assert!(matches!(client.current_session(&secrets).await, Err(Error::AuthenticationError)));
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_refresh_keeps_the_session() {
+        // The service spends the refresh token, but answers after the client gave up
+        let spent = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&spent);
+        let mock = MockIam::start(move |path, body| {
+            if path == "/users/login" {
+                return (200, json!({ "turso_url": "libsql://shop.turso.io", "turso_token": "token-9" }));
+            }
+            if body["refresh_token"] != "refresh-0" || counted.fetch_add(1, Ordering::SeqCst) > 0 {
+                return (401, json!({ "error": "unauthorized" }));
+            }
+            std::thread::sleep(Duration::from_millis(500));
+            (200, json!({ "turso_url": "libsql://shop.turso.io", "turso_token": "token-1",
+                          "refresh_token": "refresh-1", "expires_in": 3600 }))
+        });
+        let client = client(&mock.url);
+        let secrets = MemorySecretStore::default();
+
+        cached_session(-60, 1).save(&secrets).unwrap();
+        let (_, status) = client.current_session(&secrets).await.unwrap();
+        assert_eq!(status, SessionStatus::Offline);
+        assert_eq!(mock.requests().len(), 1);
+
+        // The spent token is refused next time, which does not end the session
+        let (session, status) = client.current_session(&secrets).await.unwrap();
+        assert_eq!(status, SessionStatus::Offline);
+        assert_eq!(session.turso_token.expose(), "token-0");
+        assert_eq!(mock.requests().len(), 2);
+        assert!(Session::load(&secrets).unwrap().is_some());
+
+        // After a login a refused token ends the session again
+        client.login("shop", "secret").await.unwrap();
+        assert!(matches!(client.current_session(&secrets).await, Err(Error::AuthenticationError)));
+        assert!(Session::load(&secrets).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_offline_grace_period() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&calls);
+        let mock = MockIam::start(move |_, _| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            (503, json!({ "error": "unavailable" }))
+        });
+        let client = client(&mock.url);
+        let secrets = MemorySecretStore::default();
+
+        // A login is tried once, then twice more
+        assert!(matches!(client.login("shop", "secret").await, Err(Error::IamUnavailableError(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // A refresh the service answered is not sent again, and the cached session is used
+        cached_session(-60, 1).save(&secrets).unwrap();
+        let (session, status) = client.current_session(&secrets).await.unwrap();
+        assert_eq!(status, SessionStatus::Offline);
+        assert_eq!(session.turso_token.expose(), "token-0");
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        // Past the grace period the session is not used, but kept for when the service is back
+        cached_session(-60, DEFAULT_GRACE_HOURS + 1).save(&secrets).unwrap();
+        assert!(matches!(client.current_session(&secrets).await, Err(Error::AuthenticationError)));
+        assert!(Session::load(&secrets).unwrap().is_some());
+
+        // A service that does not answer in time is unavailable too
+        let slow = MockIam::start(|_, _| {
+            std::thread::sleep(Duration::from_millis(500));
+            (200, json!({}))
+        });
+        let result = self::client(&slow.url).login("shop", "secret").await;
+        assert!(matches!(result, Err(Error::IamUnavailableError(_))));
+    }
+}
@@ -0,0 +1,50 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::{
+    adapters::outgoing::secrets::{Secret, SecretName, SecretStore},
+    error::Result,
+};
+
+/// How long before its token expires a session is refreshed
+const REFRESH_MARGIN: chrono::Duration = chrono::Duration::minutes(10);
+
+/// What the IAM service gave on the last login or refresh, kept as the `iam-session` secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    /// The database the local replica syncs with
+    pub turso_url: Secret,
+    pub turso_token: Secret,
+    pub refresh_token: Option<Secret>,
+    /// When `turso_token` expires, if it does
+    pub expires_at: Option<NaiveDateTime>,
+    /// When the service gave this session
+    pub verified_at: NaiveDateTime,
+}
+
+impl Session {
+    pub fn load(secrets: &dyn SecretStore) -> Result<Option<Session>> {
+        match secrets.get(SecretName::IamSession)? {
+            Some(stored) => Ok(Some(serde_json::from_str(stored.expose())?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save(&self, secrets: &dyn SecretStore) -> Result<()> {
+        let stored = Zeroizing::new(serde_json::to_string(self)?);
+        secrets.set(SecretName::IamSession, &Secret::new(stored.as_str()))
+    }
+
+    pub fn clear(secrets: &dyn SecretStore) -> Result<()> {
+        secrets.delete(SecretName::IamSession)
+    }
+
+    pub fn needs_refresh(&self, now: NaiveDateTime) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at - now <= REFRESH_MARGIN)
+    }
+
+    pub fn is_expired(&self, now: NaiveDateTime) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
@@ -1 +1,3 @@
 pub mod database;
+pub mod iam;
+pub mod secrets;
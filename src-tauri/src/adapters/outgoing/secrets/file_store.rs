@@ -0,0 +1,205 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use zeroize::{Zeroize, Zeroizing};
+
+use super::{Secret, SecretName, SecretStore};
+use crate::{
+    core::utils::crypto,
+    error::{Error, Result},
+};
+
+// Every secrets file starts with this
+const MAGIC: &[u8] = b"MNLSEC01";
+
+/// The variable naming the key file of the secrets file next to the database
+const KEY_FILE_VAR: &str = "SECRETS_KEY_FILE";
+
+/// Secrets sealed in one file, under a random key kept in a second file that only the owner
+/// can read. The key file is made with the first secret. It must be outside the directory of
+/// the secrets file, for example on another volume or mounted from a credential store, so a
+/// copy of the data directory alone gives nothing away.
+pub struct FileSecretStore {
+    path: PathBuf,
+    key_path: PathBuf,
+    // Each write replaces the whole file
+    lock: Mutex<()>,
+}
+
+impl FileSecretStore {
+    /// Secrets in `path`, sealed under the key in `key_path`, which may not be in the
+    /// directory of `path` or below it
+    pub fn new(path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Result<Self> {
+        let (path, key_path) = (path.into(), key_path.into());
+        let data_dir = absolute_dir(&path);
+        if absolute_dir(&key_path).starts_with(&data_dir) {
+            return Err(Error::SecretStoreError(format!(
+                "The secrets key {} must be kept outside {}",
+                key_path.display(),
+                data_dir.display()
+            )));
+        }
+        Ok(FileSecretStore { path, key_path, lock: Mutex::new(()) })
+    }
+
+    /// `secrets.enc` in `dir`, sealed under the key file named by `SECRETS_KEY_FILE`. Without
+    /// it no secrets can be kept or read.
+    pub fn in_dir(dir: &Path) -> Result<Self> {
+        let key_path = std::env::var_os(KEY_FILE_VAR).filter(|path| !path.is_empty()).ok_or_else(|| {
+            Error::SecretStoreError(format!(
+                "Set {} to the file the secrets key is kept in, outside {}",
+                KEY_FILE_VAR,
+                absolute_dir(&dir.join("secrets.enc")).display()
+            ))
+        })?;
+        Self::new(dir.join("secrets.enc"), PathBuf::from(key_path))
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, String>> {
+        let sealed = match fs::read(&self.path) {
+            Ok(sealed) => sealed,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let key = self.key()?.ok_or_else(|| {
+            Error::SecretStoreError(format!("{} is missing, so {} cannot be read", self.key_path.display(), self.path.display()))
+        })?;
+        let plain = Zeroizing::new(crypto::open(&sealed, key.expose(), MAGIC)?);
+        Ok(serde_json::from_slice(&plain)?)
+    }
+
+    fn write(&self, secrets: &BTreeMap<String, String>) -> Result<()> {
+        let key = match self.key()? {
+            Some(key) => key,
+            None => self.create_key()?,
+        };
+        let plain = Zeroizing::new(serde_json::to_vec(secrets)?);
+        let sealed = crypto::seal(&plain, key.expose(), MAGIC)?;
+
+        let partial = self.path.with_extension("partial");
+        write_private(&partial, &sealed)?;
+        fs::rename(&partial, &self.path)?;
+        Ok(())
+    }
+
+    fn update(&self, change: impl FnOnce(&mut BTreeMap<String, String>)) -> Result<()> {
+        let _guard = self.lock.lock().expect("Secrets file lock poisoned");
+        let mut secrets = self.read()?;
+        change(&mut secrets);
+        let result = self.write(&secrets);
+        secrets.values_mut().for_each(Zeroize::zeroize);
+        result
+    }
+
+    fn key(&self) -> Result<Option<Secret>> {
+        match fs::read_to_string(&self.key_path) {
+            Ok(key) => Ok(Some(Secret::new(key.trim()))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn create_key(&self) -> Result<Secret> {
+        let key = Secret::new(STANDARD.encode(crypto::random_bytes(32)?));
+        write_private(&self.key_path, key.expose().as_bytes())?;
+        Ok(key)
+    }
+}
+
+impl SecretStore for FileSecretStore {
+    fn get(&self, name: SecretName) -> Result<Option<Secret>> {
+        let _guard = self.lock.lock().expect("Secrets file lock poisoned");
+        let mut secrets = self.read()?;
+        let secret = secrets.get(name.key()).map(Secret::new);
+        secrets.values_mut().for_each(Zeroize::zeroize);
+        Ok(secret)
+    }
+
+    fn set(&self, name: SecretName, value: &Secret) -> Result<()> {
+        self.update(|secrets| {
+            secrets.insert(name.key().to_string(), value.expose().to_string());
+        })
+    }
+
+    fn delete(&self, name: SecretName) -> Result<()> {
+        self.update(|secrets| {
+            secrets.remove(name.key());
+        })
+    }
+}
+
+// The directory `path` is in, with links and `..` resolved when it exists
+fn absolute_dir(path: &Path) -> PathBuf {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    fs::canonicalize(dir)
+        .or_else(|_| std::path::absolute(dir))
+        .unwrap_or_else(|_| dir.to_path_buf())
+}
+
+// Writes a file only its owner can read
+fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_file_secret_store() {
+        let dir = std::env::temp_dir().join(format!("minnal-secrets-{}", Uuid::now_v7()));
+        let key_dir = std::env::temp_dir().join(format!("minnal-secrets-key-{}", Uuid::now_v7()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::create_dir_all(&key_dir).unwrap();
+        let open = || FileSecretStore::new(dir.join("secrets.enc"), key_dir.join("secrets.key")).unwrap();
+        let store = open();
+        assert_eq!(store.get(SecretName::IamSession).unwrap(), None);
+
+        store.set(SecretName::IamSession, &Secret::new("token-1")).unwrap();
+        store.set(SecretName::BackupPassphrase, &Secret::new("passphrase")).unwrap();
+        store.set(SecretName::IamSession, &Secret::new("token-2")).unwrap();
+        store.delete(SecretName::BackupPassphrase).unwrap();
+        store.delete(SecretName::IamUrl).unwrap();
+
+        let reopened = open();
+        assert_eq!(reopened.get(SecretName::IamSession).unwrap(), Some(Secret::new("token-2")));
+        assert_eq!(reopened.get(SecretName::BackupPassphrase).unwrap(), None);
+        let sealed = fs::read(dir.join("secrets.enc")).unwrap();
+        assert!(!sealed.windows(7).any(|window| window == b"token-2"));
+
+        fs::remove_file(key_dir.join("secrets.key")).unwrap();
+        assert!(matches!(reopened.get(SecretName::IamSession), Err(Error::SecretStoreError(_))));
+
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(&key_dir).unwrap();
+    }
+
+    #[test]
+    fn test_key_is_kept_outside_the_data_directory() {
+        let dir = std::env::temp_dir().join(format!("minnal-secrets-{}", Uuid::now_v7()));
+        fs::create_dir_all(dir.join("keys")).unwrap();
+
+        for key_path in [dir.join("secrets.key"), dir.join("keys/secrets.key"), dir.join("keys/../secrets.key")] {
+            let result = FileSecretStore::new(dir.join("secrets.enc"), key_path);
+            assert!(matches!(result, Err(Error::SecretStoreError(_))));
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,244 @@
+use super::{Secret, SecretName, SecretStore};
+use crate::error::Result;
+
+/// Secrets in the OS keyring: the Keychain on macOS, the Credential Manager on Windows and
+/// the Secret Service (GNOME Keyring, KWallet) on Linux, through `secret-tool`
+pub struct KeyringSecretStore {
+    service: String,
+}
+
+impl KeyringSecretStore {
+    pub fn new(service: &str) -> Self {
+        KeyringSecretStore { service: service.to_string() }
+    }
+}
+
+impl SecretStore for KeyringSecretStore {
+    fn get(&self, name: SecretName) -> Result<Option<Secret>> {
+        platform::get(&self.service, name.key())
+    }
+
+    fn set(&self, name: SecretName, value: &Secret) -> Result<()> {
+        platform::set(&self.service, name.key(), value)
+    }
+
+    fn delete(&self, name: SecretName) -> Result<()> {
+        platform::delete(&self.service, name.key())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use security_framework::{
+        base::Error as KeychainError,
+        passwords::{delete_generic_password, get_generic_password, set_generic_password},
+    };
+
+    use super::Secret;
+    use crate::error::{Error, Result};
+
+    // errSecItemNotFound
+    const ITEM_NOT_FOUND: i32 = -25300;
+
+    pub fn get(service: &str, key: &str) -> Result<Option<Secret>> {
+        match get_generic_password(service, key) {
+            Ok(bytes) => {
+                let value = String::from_utf8(bytes).map_err(|_| keychain_error(key, "is not text"))?;
+                Ok(Some(Secret::new(value)))
+            }
+            Err(e) if e.code() == ITEM_NOT_FOUND => Ok(None),
+            Err(e) => Err(error(key, e)),
+        }
+    }
+
+    pub fn set(service: &str, key: &str, value: &Secret) -> Result<()> {
+        set_generic_password(service, key, value.expose().as_bytes()).map_err(|e| error(key, e))
+    }
+
+    pub fn delete(service: &str, key: &str) -> Result<()> {
+        match delete_generic_password(service, key) {
+            Ok(()) => Ok(()),
+            Err(e) if e.code() == ITEM_NOT_FOUND => Ok(()),
+            Err(e) => Err(error(key, e)),
+        }
+    }
+
+    fn error(key: &str, e: KeychainError) -> Error {
+        keychain_error(key, &e.to_string())
+    }
+
+    fn keychain_error(key: &str, message: &str) -> Error {
+        Error::SecretStoreError(format!("Keychain item {}: {}", key, message))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::{ptr, slice};
+
+    use windows_sys::Win32::{
+        Foundation::{GetLastError, ERROR_NOT_FOUND},
+        Security::Credentials::{
+            CredDeleteW, CredFree, CredReadW, CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE, CRED_TYPE_GENERIC,
+        },
+    };
+
+    use super::Secret;
+    use crate::error::{Error, Result};
+
+    pub fn get(service: &str, key: &str) -> Result<Option<Secret>> {
+        let target = target_name(service, key);
+        let mut credential: *mut CREDENTIALW = ptr::null_mut();
+        // SAFETY: `target` is a NUL terminated UTF-16 string and `credential` is only read
+        // when the call succeeds, then freed with `CredFree`
+        unsafe {
+            if CredReadW(target.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) == 0 {
+                return match GetLastError() {
+                    ERROR_NOT_FOUND => Ok(None),
+                    code => Err(error(key, code)),
+                };
+            }
+            let blob = slice::from_raw_parts((*credential).CredentialBlob, (*credential).CredentialBlobSize as usize);
+            let value = String::from_utf8(blob.to_vec());
+            CredFree(credential as *const _);
+            match value {
+                Ok(value) => Ok(Some(Secret::new(value))),
+                Err(_) => Err(Error::SecretStoreError(format!("Credential {} is not text", key))),
+            }
+        }
+    }
+
+    pub fn set(service: &str, key: &str, value: &Secret) -> Result<()> {
+        let mut target = target_name(service, key);
+        let blob = value.expose().as_bytes();
+        // SAFETY: every pointer in `credential` outlives the call, which copies what it needs
+        unsafe {
+            let mut credential: CREDENTIALW = std::mem::zeroed();
+            credential.Type = CRED_TYPE_GENERIC;
+            credential.TargetName = target.as_mut_ptr();
+            credential.CredentialBlobSize = blob.len() as u32;
+            credential.CredentialBlob = blob.as_ptr() as *mut u8;
+            credential.Persist = CRED_PERSIST_LOCAL_MACHINE;
+            if CredWriteW(&credential, 0) == 0 {
+                return Err(error(key, GetLastError()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn delete(service: &str, key: &str) -> Result<()> {
+        let target = target_name(service, key);
+        // SAFETY: `target` is a NUL terminated UTF-16 string
+        unsafe {
+            if CredDeleteW(target.as_ptr(), CRED_TYPE_GENERIC, 0) == 0 {
+                return match GetLastError() {
+                    ERROR_NOT_FOUND => Ok(()),
+                    code => Err(error(key, code)),
+                };
+            }
+        }
+        Ok(())
+    }
+
+    fn target_name(service: &str, key: &str) -> Vec<u16> {
+        format!("{}/{}", service, key).encode_utf16().chain(Some(0)).collect()
+    }
+
+    fn error(key: &str, code: u32) -> Error {
+        Error::SecretStoreError(format!("Credential {}: Windows error {}", key, code))
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use std::{
+        io::{ErrorKind, Write},
+        process::{Command, Output, Stdio},
+    };
+
+    use zeroize::Zeroizing;
+
+    use super::Secret;
+    use crate::error::{Error, Result};
+
+    pub fn get(service: &str, key: &str) -> Result<Option<Secret>> {
+        let output = secret_tool(&["lookup", "service", service, "key", key], None)?;
+        let stdout = Zeroizing::new(output.stdout);
+        // `lookup` fails without a message when there is no such secret
+        if !output.status.success() {
+            return match String::from_utf8_lossy(&output.stderr).trim() {
+                "" => Ok(None),
+                message => Err(error(key, message)),
+            };
+        }
+        let value = std::str::from_utf8(&stdout).map_err(|_| error(key, "is not text"))?;
+        Ok(Some(Secret::new(value)))
+    }
+
+    pub fn set(service: &str, key: &str, value: &Secret) -> Result<()> {
+        let label = format!("{} {}", service, key);
+        let args = ["store", "--label", &label, "service", service, "key", key];
+        let output = secret_tool(&args, Some(value))?;
+        check(key, output)
+    }
+
+    pub fn delete(service: &str, key: &str) -> Result<()> {
+        let output = secret_tool(&["clear", "service", service, "key", key], None)?;
+        check(key, output)
+    }
+
+    // Runs `secret-tool`, writing `input` to it rather than passing it as an argument, where
+    // other users could see it
+    fn secret_tool(args: &[&str], input: Option<&Secret>) -> Result<Output> {
+        let mut child = Command::new("secret-tool")
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                ErrorKind::NotFound => Error::SecretStoreError(
+                    "secret-tool is not installed. Install libsecret-tools to keep secrets in the keyring.".to_string(),
+                ),
+                _ => e.into(),
+            })?;
+
+        if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+            stdin.write_all(input.expose().as_bytes())?;
+        }
+        Ok(child.wait_with_output()?)
+    }
+
+    fn check(key: &str, output: Output) -> Result<()> {
+        if output.status.success() {
+            return Ok(());
+        }
+        Err(error(key, String::from_utf8_lossy(&output.stderr).trim()))
+    }
+
+    fn error(key: &str, message: &str) -> Error {
+        Error::SecretStoreError(format!("Keyring secret {}: {}", key, message))
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use super::Secret;
+    use crate::error::{Error, Result};
+
+    pub fn get(_service: &str, _key: &str) -> Result<Option<Secret>> {
+        Err(unsupported())
+    }
+
+    pub fn set(_service: &str, _key: &str, _value: &Secret) -> Result<()> {
+        Err(unsupported())
+    }
+
+    pub fn delete(_service: &str, _key: &str) -> Result<()> {
+        Err(unsupported())
+    }
+
+    fn unsupported() -> Error {
+        Error::SecretStoreError("There is no keyring on this platform".to_string())
+    }
+}
@@ -0,0 +1,26 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use super::{Secret, SecretName, SecretStore};
+use crate::error::Result;
+
+/// Secrets kept in memory only, so tests never touch the keyring or the disk
+#[derive(Default)]
+pub struct MemorySecretStore {
+    secrets: Mutex<HashMap<&'static str, Secret>>,
+}
+
+impl SecretStore for MemorySecretStore {
+    fn get(&self, name: SecretName) -> Result<Option<Secret>> {
+        Ok(self.secrets.lock().expect("Secrets lock poisoned").get(name.key()).cloned())
+    }
+
+    fn set(&self, name: SecretName, value: &Secret) -> Result<()> {
+        self.secrets.lock().expect("Secrets lock poisoned").insert(name.key(), value.clone());
+        Ok(())
+    }
+
+    fn delete(&self, name: SecretName) -> Result<()> {
+        self.secrets.lock().expect("Secrets lock poisoned").remove(name.key());
+        Ok(())
+    }
+}
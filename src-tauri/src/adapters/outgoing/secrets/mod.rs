@@ -0,0 +1,137 @@
+//! Where credentials and keys are kept, so none of them is read from the environment or left
+//! on disk in plaintext
+//!
+//! The desktop app keeps them in the OS keyring. Headless builds, where there may be no
+//! keyring, keep them in a sealed file next to the database, with its key kept elsewhere, see
+//! [`FileSecretStore`].
+
+mod file_store;
+mod keyring_store;
+#[cfg(test)]
+mod memory_store;
+
+pub use file_store::FileSecretStore;
+pub use keyring_store::KeyringSecretStore;
+#[cfg(test)]
+pub use memory_store::MemorySecretStore;
+
+use std::{fmt, path::Path, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use zeroize::Zeroizing;
+
+use crate::error::Result;
+
+/// The keyring service secrets are filed under
+const KEYRING_SERVICE: &str = "minnal";
+
+/// What a secret is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretName {
+    /// The last session the IAM service gave, with the database the local replica syncs
+    /// with, see `adapters::outgoing::iam::Session`
+    IamSession,
+    /// Where the IAM service is
+    IamUrl,
+    /// How many seconds a request to the IAM service may take
+    IamTimeoutSecs,
+    /// How many times a failed request to the IAM service is tried again
+    IamRetries,
+    /// How many hours a session works while the IAM service cannot be reached
+    IamOfflineGraceHours,
+    /// How many hours an open cart may go untouched before it is purged
+    CartMaxAgeHours,
+    /// The key the local database is encrypted with
+    DatabaseKey,
+    /// The passphrase backups are encrypted with
+    BackupPassphrase,
+}
+
+impl SecretName {
+    pub const ALL: [SecretName; 8] = [
+        SecretName::IamSession,
+        SecretName::IamUrl,
+        SecretName::IamTimeoutSecs,
+        SecretName::IamRetries,
+        SecretName::IamOfflineGraceHours,
+        SecretName::CartMaxAgeHours,
+        SecretName::DatabaseKey,
+        SecretName::BackupPassphrase,
+    ];
+
+    /// The name the secret is stored under
+    pub fn key(self) -> &'static str {
+        match self {
+            SecretName::IamSession => "iam-session",
+            SecretName::IamUrl => "iam-url",
+            SecretName::IamTimeoutSecs => "iam-timeout-secs",
+            SecretName::IamRetries => "iam-retries",
+            SecretName::IamOfflineGraceHours => "iam-offline-grace-hours",
+            SecretName::CartMaxAgeHours => "cart-max-age-hours",
+            SecretName::DatabaseKey => "database-key",
+            SecretName::BackupPassphrase => "backup-passphrase",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|name| name.key() == key)
+    }
+}
+
+/// A secret value. It is wiped from memory when dropped and never printed.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(Zeroizing<String>);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Secret(Zeroizing::new(value.into()))
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(..)")
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.expose())
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Secret::new)
+    }
+}
+
+/// A place to keep secrets. Implementations only hold them in memory while they are used.
+pub trait SecretStore: Send + Sync {
+    fn get(&self, name: SecretName) -> Result<Option<Secret>>;
+
+    fn set(&self, name: SecretName, value: &Secret) -> Result<()>;
+
+    /// Removes the secret. Removing one that is not there is not an error.
+    fn delete(&self, name: SecretName) -> Result<()>;
+}
+
+/// A setting kept in the store as a number, or `None` when it is missing or is not one
+pub fn get_number<T: FromStr>(secrets: &dyn SecretStore, name: SecretName) -> Result<Option<T>> {
+    Ok(secrets.get(name)?.and_then(|value| value.expose().trim().parse().ok()))
+}
+
+/// The store for the database at `db_path`: the OS keyring in the desktop app, otherwise the
+/// secrets file next to the database. That fails when its key file is not configured.
+pub fn default_store(db_path: &str) -> Result<Box<dyn SecretStore>> {
+    if cfg!(feature = "desktop") {
+        Ok(Box::new(KeyringSecretStore::new(KEYRING_SERVICE)))
+    } else {
+        let dir = Path::new(db_path).parent().unwrap_or(Path::new(""));
+        Ok(Box::new(FileSecretStore::in_dir(dir)?))
+    }
+}
@@ -0,0 +1,88 @@
+//! Lists, takes and restores backups of the local database
+//!
+//! ```text
+//! lightning-backup list
+//! lightning-backup create [<label>]
+//! lightning-backup restore <file>
+//! ```
+//!
+//! Works on the database at `DATABASE_PATH` (default `minnal.db`), with the backup settings
+//! the app uses: `BACKUP_DIR`, `BACKUP_KEEP` and the backup passphrase in the secret store.
+//! Stop the app before `restore`. The database it replaces is kept next to it, and migrations the backup is
+//! missing are applied when the app next starts.
+
+use std::path::Path;
+
+use lightning_pos::{
+    adapters::outgoing::secrets,
+    core::{
+        commands::{common::backup_commands::CreateBackupCommand, AppService, Command},
+        db::backup::{self, BackupConfig},
+        models::common::backup_model::BackupKind,
+    },
+};
+
+const DEFAULT_DATABASE_PATH: &str = "minnal.db";
+
+const USAGE: &str = "Usage: lightning-backup list | create [<label>] | restore <file>";
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let db_path = std::env::var("DATABASE_PATH").unwrap_or_else(|_| DEFAULT_DATABASE_PATH.to_string());
+    let secrets = match secrets::default_store(&db_path) {
+        Ok(secrets) => secrets,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let config = match BackupConfig::load(&db_path, secrets.as_ref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let result = match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["list"] => backup::list(&config, &db_path).map(|backups| {
+            for backup in backups {
+                let label = backup.label.as_deref().unwrap_or("");
+                let encrypted = if backup.encrypted { " (encrypted)" } else { "" };
+                let kind = format!("{:?}", backup.kind).to_lowercase();
+                println!("{}  {:<9} {:<24} {}{}", backup.created_at, kind, label, backup.path, encrypted);
+            }
+        }),
+        ["create", label @ ..] if label.len() <= 1 => {
+            // Opened without migrating, so the backup holds the database as it is
+            let service = AppService::new(&db_path).await;
+            let command = CreateBackupCommand {
+                config,
+                kind: BackupKind::Snapshot,
+                label: label.first().map(|label| label.to_string()),
+            };
+            command.exec(&service).await.map(|backup| println!("{}", backup.path))
+        }
+        ["restore", file] => {
+            let passphrase = config.passphrase.as_deref();
+            backup::restore(&db_path, Path::new(file), passphrase, secrets.as_ref()).await.map(|moved| {
+                println!("Restored {} from {}", db_path, file);
+                if let Some(moved) = moved {
+                    println!("The replaced database was moved to {}", moved.display());
+                }
+            })
+        }
+        _ => exit_with_usage(),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
@@ -0,0 +1,81 @@
+//! Applies and reverts the migrations built into the app, for developers
+//!
+//! ```text
+//! lightning-migrate status
+//! lightning-migrate up [--to <migration>]
+//! lightning-migrate down [--to <migration>]
+//! ```
+//!
+//! Works on the database at `DATABASE_PATH` (default `minnal.db`). `<migration>` is a file name
+//! from `migrations/` or a prefix of one, such as `0009`. `up` applies every pending migration
+//! up to and including it, `down` reverts every migration after it, or only the newest one.
+
+use lightning_pos::{
+    adapters::outgoing::secrets,
+    core::db::{
+        encryption,
+        migrations::{self, MigrationState},
+    },
+};
+
+const DEFAULT_DATABASE_PATH: &str = "minnal.db";
+
+const USAGE: &str = "Usage: lightning-migrate status | up [--to <migration>] | down [--to <migration>]";
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let to = match args.get(1..) {
+        Some([]) | None => None,
+        Some([flag, to]) if flag == "--to" => Some(to.as_str()),
+        Some(_) => exit_with_usage(),
+    };
+
+    let db_path = std::env::var("DATABASE_PATH").unwrap_or_else(|_| DEFAULT_DATABASE_PATH.to_string());
+    let secrets = match secrets::default_store(&db_path) {
+        Ok(secrets) => secrets,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let db = encryption::open_local(&db_path, secrets.as_ref())
+        .await
+        .expect("Failed to open database");
+    let conn = db.connect().expect("Failed to connect to database");
+
+    let result = match args.first().map(String::as_str) {
+        Some("status") if to.is_none() => print_status(&conn).await,
+        Some("up") => migrations::migrate_up(&conn, to).await.map(|applied| {
+            println!("Applied {} migration(s)", applied.len());
+        }),
+        Some("down") => migrations::migrate_down(&conn, to).await.map(|reverted| {
+            println!("Reverted {} migration(s)", reverted.len());
+        }),
+        _ => exit_with_usage(),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn print_status(conn: &libsql::Connection) -> lightning_pos::error::Result<()> {
+    for status in migrations::status(conn).await? {
+        let state = match status.state {
+            MigrationState::Applied => "applied",
+            MigrationState::Pending => "pending",
+            MigrationState::Changed => "changed since applied",
+            MigrationState::Unknown => "applied, not in this build",
+        };
+        let down = if status.reversible { "" } else { " (no down script)" };
+        println!("{:<40} {}{}", status.id, state, down);
+    }
+    Ok(())
+}
+
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+    std::process::exit(2);
+}
@@ -0,0 +1,133 @@
+//! Headless GraphQL server, built with `--no-default-features --features server`
+//!
+//! `lightning-server` serves the schema on `SERVER_ADDR` (default `0.0.0.0:7100`) from the
+//! database at `DATABASE_PATH` (default `minnal.db`). Pending migrations, which are built into
+//! the binary, are applied on start.
+//!
+//! `lightning-server create-token <username> <name>` issues an API token for a user and
+//! prints it.
+//!
+//! `lightning-server set-secret <name>` keeps the value read from stdin in the secrets file
+//! next to the database, and `lightning-server delete-secret <name>` removes it. The names are
+//! `iam-session`, `iam-url`, `iam-timeout-secs`, `iam-retries`, `iam-offline-grace-hours`,
+//! `cart-max-age-hours`, `database-key` and `backup-passphrase`. The file's key is kept in
+//! `SECRETS_KEY_FILE`, which must be outside the database's directory; without it the server
+//! does not start.
+
+use std::{io::Read, sync::Arc};
+
+use lightning_pos::{
+    adapters::{
+        graphql::endpoint::GraphQLEndpoint,
+        incoming::http,
+        outgoing::secrets::{self, Secret, SecretName},
+    },
+    core::{
+        commands::{auth::api_token_commands::CreateApiTokenCommand, AppService, Command},
+        repositories::user_repository,
+    },
+    spawn_background_jobs, AppState,
+};
+use tokio::net::TcpListener;
+
+const DEFAULT_ADDR: &str = "0.0.0.0:7100";
+const DEFAULT_DATABASE_PATH: &str = "minnal.db";
+
+#[tokio::main]
+async fn main() {
+    let db_path = std::env::var("DATABASE_PATH").unwrap_or_else(|_| DEFAULT_DATABASE_PATH.to_string());
+
+    // Before the database is opened, which can need the key kept as a secret
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() > 1 && (args[1] == "set-secret" || args[1] == "delete-secret") {
+        change_secret(&db_path, &args[1], &args[2..]);
+        return;
+    }
+
+    let service = AppService::open(&db_path).await;
+
+    if args.len() > 1 && args[1] == "create-token" {
+        create_token(&service, &args[2..]).await;
+        return;
+    }
+
+    let addr = std::env::var("SERVER_ADDR").unwrap_or_else(|_| DEFAULT_ADDR.to_string());
+    let listener = TcpListener::bind(&addr).await.expect("Failed to bind server address");
+    let endpoint = GraphQLEndpoint::from_env();
+    let state = AppState::new(service);
+    spawn_background_jobs(Arc::clone(&state.service));
+
+    println!("Serving GraphQL on http://{}/graphql", listener.local_addr().unwrap());
+    tokio::select! {
+        result = http::serve(listener, state, endpoint) => {
+            if let Err(e) = result {
+                eprintln!("Server stopped: {}", e);
+            }
+        }
+        _ = tokio::signal::ctrl_c() => println!("Shutting down"),
+    }
+}
+
+async fn create_token(service: &AppService, args: &[String]) {
+    let [username, name] = args else {
+        eprintln!("Usage: lightning-server create-token <username> <name>");
+        std::process::exit(2);
+    };
+
+    let user = match user_repository::get_user_by_username(service, username).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            eprintln!("No user named {}", username);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Failed to look up user: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let command = CreateApiTokenCommand { user_id: user.id, name: name.clone(), issued_by: None };
+    match command.exec(service).await {
+        Ok(created) => println!("{}", created.token),
+        Err(e) => {
+            eprintln!("Failed to create token: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn change_secret(db_path: &str, command: &str, args: &[String]) {
+    let name = match args {
+        [name] => SecretName::from_key(name),
+        _ => None,
+    };
+    let Some(name) = name else {
+        let names: Vec<_> = SecretName::ALL.iter().map(|name| name.key()).collect();
+        eprintln!("Usage: lightning-server {} <{}>", command, names.join(" | "));
+        std::process::exit(2);
+    };
+
+    let store = match secrets::default_store(db_path) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let result = if command == "set-secret" {
+        let mut value = String::new();
+        if let Err(e) = std::io::stdin().read_to_string(&mut value) {
+            eprintln!("Failed to read the secret from stdin: {}", e);
+            std::process::exit(1);
+        }
+        let value = Secret::new(value.trim_end_matches(['\r', '\n']));
+        store.set(name, &value)
+    } else {
+        store.delete(name)
+    };
+
+    if let Err(e) = result {
+        eprintln!("Failed to change {}: {}", name.key(), e);
+        std::process::exit(1);
+    }
+}
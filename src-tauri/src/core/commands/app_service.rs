@@ -1,75 +1,196 @@
+use std::{ops::Deref, sync::RwLock, time::Duration};
+
 use libsql::Connection;
+use tokio::sync::{Mutex, MutexGuard};
 
 use crate::{
-    adapters::outgoing::database::{DatabaseAdapter, LibSqlAdapter},
-    core::{db::migrations, types::db_uuid::DbUuid},
+    adapters::outgoing::{
+        database::{DatabaseAdapter, LibSqlAdapter},
+        iam::{IamClient, IamConfig, Session},
+        secrets::{self, SecretStore},
+    },
+    core::{
+        db::{encryption, migrations},
+        events::{Event, EventBus, SyncFinished},
+        types::db_uuid::DbUuid,
+    },
+    error::{Error, Result},
 };
 
+/// Read connections opened next to the write connection of a file database
+const READ_CONNECTIONS: usize = 4;
+
+/// How long a connection waits for another one to release the database
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Shared by every request. Queries use it directly, while mutations take `write()` first
+/// so that their reads and writes are not interleaved with another mutation.
 pub struct AppService<DB: DatabaseAdapter = LibSqlAdapter> {
     pub conn_path: String,
     pub db_adapter: DB,
-    pub state: SessionState,
+    pub state: RwLock<SessionState>,
+    pub events: EventBus,
+    /// Credentials and keys, see `adapters::outgoing::secrets`
+    pub secrets: Box<dyn SecretStore>,
+    pub iam: IamClient,
+    writes: Mutex<()>,
 }
 
 pub struct SessionState {
     pub current_user: Option<DbUuid>,
 }
 
+/// Exclusive access for one mutation, see `AppService::write`
+pub struct WriteGuard<'a, DB: DatabaseAdapter = LibSqlAdapter> {
+    service: &'a AppService<DB>,
+    _lock: MutexGuard<'a, ()>,
+}
+
+impl<DB: DatabaseAdapter> Deref for WriteGuard<'_, DB> {
+    type Target = AppService<DB>;
+
+    fn deref(&self) -> &Self::Target {
+        self.service
+    }
+}
+
+impl<DB: DatabaseAdapter> AppService<DB> {
+    /// Waits for the mutations before this one to finish. Queries never wait here.
+    pub async fn write(&self) -> WriteGuard<'_, DB> {
+        WriteGuard {
+            service: self,
+            _lock: self.writes.lock().await,
+        }
+    }
+
+    pub fn current_user(&self) -> Option<DbUuid> {
+        self.state.read().expect("Session state lock poisoned").current_user
+    }
+
+    pub fn set_current_user(&self, user: Option<DbUuid>) {
+        self.state.write().expect("Session state lock poisoned").current_user = user;
+    }
+}
+
 impl AppService {
     pub async fn new(conn_path: &str) -> Self {
         let state = SessionState { current_user: None };
 
+        let secrets = secrets::default_store(conn_path).expect("Failed to open the secret store");
+
         // Temporary in memory database until user logins
-        let db = libsql::Builder::new_local(conn_path)
-            .build()
+        let db = encryption::open_local(conn_path, secrets.as_ref())
             .await
             .expect("Failed to build synced libsql database");
 
-        let conn = db.connect().expect("Failed to connect to libsql database");
+        let (conn, readers) = Self::connect(&db, conn_path).await;
 
-        let db_adapter = LibSqlAdapter::new(db, conn);
+        let db_adapter = LibSqlAdapter::with_readers(db, conn, readers);
 
         Self {
             conn_path: conn_path.to_string(),
             db_adapter,
-            state,
+            state: RwLock::new(state),
+            events: EventBus::default(),
+            iam: Self::iam_client(secrets.as_ref()),
+            secrets,
+            writes: Mutex::new(()),
         }
     }
 
-    // Update the database adapter with the synced database once the user logins
-    pub async fn update_adapter(&mut self, turso_url: String, turso_token: String) {
-        let db = libsql::Builder::new_remote_replica(self.conn_path.clone(), turso_url, turso_token)
-                .build()
-                .await
-                .expect("Failed to build synced libsql database");
+    // Update the database adapter with the synced database once the user logins, using the
+    // Turso credentials of the session in the secret store
+    // Queries already running finish on the old connections
+    pub async fn update_adapter(&self) -> Result<()> {
+        let session = Session::load(self.secrets.as_ref())?.ok_or(Error::AuthenticationError)?;
+
+        let db = encryption::open_replica(
+            &self.conn_path,
+            &session.turso_url,
+            &session.turso_token,
+            self.secrets.as_ref(),
+        )
+        .await?;
 
-        db.sync().await.expect("Failed to sync database");
+        db.sync().await.map_err(|e| Error::DatabaseError(format!("Failed to sync database: {}", e)))?;
 
-        let conn = db.connect().expect("Failed to connect to libsql database");
+        let (conn, readers) = Self::connect(&db, &self.conn_path).await;
 
         Self::apply_migrations(&conn).await;
 
-        self.db_adapter = LibSqlAdapter::new(db, conn);
+        self.db_adapter.replace(db, conn, readers);
+
+        self.events.publish(Event::SyncFinished(SyncFinished {
+            finished_at: chrono::Utc::now().naive_utc(),
+        }));
+
+        Ok(())
     }
 
-    #[cfg(test)]
-    pub async fn new_test(conn_path: &str) -> Self {
-        let db = libsql::Builder::new_local(&conn_path)
-            .build()
+    /// A local database with every migration applied, for the headless server and tests
+    pub async fn open(conn_path: &str) -> Self {
+        let secrets = secrets::default_store(conn_path).expect("Failed to open the secret store");
+        Self::open_with(conn_path, secrets).await
+    }
+
+    async fn open_with(conn_path: &str, secrets: Box<dyn SecretStore>) -> Self {
+        let db = encryption::open_local(conn_path, secrets.as_ref())
             .await
-            .expect("Failed to build test database from template");
+            .expect("Failed to build local libsql database");
 
-        let conn = db.connect().expect("Failed to connect to test database");
+        let (conn, readers) = Self::connect(&db, conn_path).await;
 
         Self::apply_migrations(&conn).await;
 
-        let db_adapter = LibSqlAdapter::new(db, conn);
+        let db_adapter = LibSqlAdapter::with_readers(db, conn, readers);
 
         Self {
             conn_path: conn_path.to_string(),
             db_adapter,
-            state: SessionState { current_user: None },
+            state: RwLock::new(SessionState { current_user: None }),
+            events: EventBus::default(),
+            iam: Self::iam_client(secrets.as_ref()),
+            secrets,
+            writes: Mutex::new(()),
+        }
+    }
+
+    #[cfg(test)]
+    pub async fn new_test(conn_path: &str) -> Self {
+        Self::open_with(conn_path, Box::new(secrets::MemorySecretStore::default())).await
+    }
+
+    fn iam_client(secrets: &dyn SecretStore) -> IamClient {
+        let config = IamConfig::load(secrets).unwrap_or_else(|e| {
+            eprintln!("Using the default IAM settings, as the configured ones could not be read: {}", e);
+            IamConfig::default()
+        });
+        IamClient::new(config)
+    }
+
+    // Opens the write connection and, for a file database, the read connections
+    async fn connect(db: &libsql::Database, conn_path: &str) -> (Connection, Vec<Connection>) {
+        let open = || {
+            let conn = db.connect().expect("Failed to connect to libsql database");
+            conn.busy_timeout(BUSY_TIMEOUT).expect("Failed to set busy timeout");
+            conn
+        };
+
+        let conn = open();
+
+        // Every connection to ":memory:" opens a database of its own
+        if conn_path == ":memory:" {
+            return (conn, Vec::new());
         }
+
+        // In WAL mode readers keep reading while the writer commits
+        conn.query("PRAGMA journal_mode = WAL", ())
+            .await
+            .expect("Failed to enable WAL mode");
+
+        let readers = (0..READ_CONNECTIONS).map(|_| open()).collect();
+
+        (conn, readers)
     }
 
     pub async fn apply_migrations(conn: &Connection) {
@@ -92,9 +213,132 @@ impl AppService {
 
 #[cfg(test)]
 pub mod tests {
+    use std::env;
+
+    use sea_query::Query;
+    use uuid::Uuid;
+
     use super::*;
+    use crate::core::{
+        commands::{sales::customer_commands::CreateCustomerCommand, Command},
+        models::sales::customer_model::{Customer, CustomerNewInput, Customers},
+    };
 
     pub async fn setup_service() -> AppService {
         AppService::new_test(":memory:").await
     }
+
+    async fn create_customer(service: &AppService, name: &str) {
+        let command = CreateCustomerCommand {
+            customer: CustomerNewInput {
+                full_name: name.to_string(),
+                email: None,
+                phone: None,
+                address: None,
+                customer_group_id: None,
+            },
+        };
+        command.exec(service).await.unwrap();
+    }
+
+    async fn customers(service: &AppService) -> Vec<Customer> {
+        let query = Query::select()
+            .from(Customers::Table)
+            .columns(Customers::all_columns())
+            .to_owned();
+        service.db_adapter.query_many::<Customer>(&query).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_queries_run_during_a_mutation() {
+        let path = env::temp_dir().join(format!("lightning-{}.db", Uuid::now_v7()));
+        let path = path.to_str().unwrap().to_string();
+        let service = AppService::new_test(&path).await;
+
+        let write = service.write().await;
+        create_customer(&write, "Alice").await;
+
+        // Reads go to the read connections and see the committed row
+        let names: Vec<String> = customers(&service).await.into_iter().map(|c| c.full_name).collect();
+        assert_eq!(names, ["Alice"]);
+
+        drop(write);
+        drop(service);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transaction_writes_are_hidden_until_commit() {
+        let path = env::temp_dir().join(format!("lightning-{}.db", Uuid::now_v7()));
+        let path = path.to_str().unwrap().to_string();
+        let service = AppService::new_test(&path).await;
+        let service = &service;
+
+        let (inserted, was_inserted) = tokio::sync::oneshot::channel();
+        let (read, was_read) = tokio::sync::oneshot::channel();
+        let transaction = service.db_adapter.transaction(move || async move {
+            create_customer(service, "Alice").await;
+            // The transaction reads its own writes
+            assert_eq!(customers(service).await.len(), 1);
+            inserted.send(()).unwrap();
+            was_read.await.unwrap();
+            Ok(())
+        });
+        let outside = async {
+            was_inserted.await.unwrap();
+            let seen = customers(service).await.len();
+            read.send(()).unwrap();
+            seen
+        };
+        let (result, seen) = tokio::join!(transaction, outside);
+        result.unwrap();
+        assert_eq!(seen, 0);
+        assert_eq!(customers(service).await.len(), 1);
+
+        // An error rolls the transaction back
+        let result = service
+            .db_adapter
+            .transaction(|| async {
+                create_customer(service, "Bob").await;
+                Err::<(), _>(Error::ValidationError("Undo".to_string()))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(customers(service).await.len(), 1);
+
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mutations_run_one_at_a_time() {
+        let service = setup_service().await;
+
+        let write = service.write().await;
+        let second = tokio::time::timeout(Duration::from_millis(20), service.write()).await;
+        assert!(second.is_err());
+
+        drop(write);
+        let second = tokio::time::timeout(Duration::from_millis(20), service.write()).await;
+        assert!(second.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_replace_connections_while_shared() {
+        let service = setup_service().await;
+        create_customer(&service, "Alice").await;
+        assert_eq!(customers(&service).await.len(), 1);
+
+        let db = libsql::Builder::new_local(":memory:").build().await.unwrap();
+        let conn = db.connect().unwrap();
+        AppService::apply_migrations(&conn).await;
+        service.db_adapter.replace(db, conn, Vec::new());
+
+        assert!(customers(&service).await.is_empty());
+        create_customer(&service, "Bob").await;
+        assert_eq!(customers(&service).await.len(), 1);
+    }
 }
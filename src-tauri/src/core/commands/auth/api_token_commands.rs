@@ -0,0 +1,317 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use juniper::GraphQLObject;
+use ring::digest::{digest, SHA256};
+use sea_query::{Expr, Query};
+use uuid::Uuid;
+
+use crate::{
+    adapters::outgoing::database::DatabaseAdapter,
+    core::{
+        commands::{app_service::AppService, Command},
+        models::auth::{
+            api_token_model::{ApiToken, ApiTokens},
+            user_model::{User, UserRole, UserState},
+        },
+        repositories::user_repository,
+        types::db_uuid::DbUuid,
+    },
+    error::{Error, Result},
+    AppState,
+};
+
+/// Marks the tokens issued here, so a leaked one is easy to recognise
+const TOKEN_PREFIX: &str = "lpos_";
+
+/// A newly created token. `token` is only ever returned here; the database keeps its hash.
+#[derive(Debug, GraphQLObject)]
+#[graphql(context = AppState)]
+pub struct ApiTokenSecret {
+    pub api_token: ApiToken,
+    pub token: String,
+}
+
+// Commands
+pub struct CreateApiTokenCommand {
+    pub user_id: DbUuid,
+    pub name: String,
+    /// Who asked for the token. Only supervisors may issue tokens for other users; `None` is
+    /// the server's own `create-token`.
+    pub issued_by: Option<DbUuid>,
+}
+
+pub struct RevokeApiTokenCommand {
+    pub id: DbUuid,
+}
+
+pub struct ListApiTokensCommand {
+    pub user_id: DbUuid,
+}
+
+/// Resolves a bearer token to the active user it was issued to
+pub struct AuthenticateApiTokenCommand {
+    pub token: String,
+}
+
+// Command Implementations
+impl Command for CreateApiTokenCommand {
+    type Output = ApiTokenSecret;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        if self.name.trim().is_empty() {
+            return Err(Error::ValidationError("Token name is required".to_string()));
+        }
+        if let Some(issued_by) = self.issued_by.filter(|issued_by| *issued_by != self.user_id) {
+            let issuer = user_repository::get_user_by_id(service, issued_by).await?;
+            if !issuer.is_some_and(|issuer| issuer.role == UserRole::Supervisor) {
+                return Err(Error::AuthorizationError);
+            }
+        }
+        if user_repository::get_user_by_id(service, self.user_id).await?.is_none() {
+            return Err(Error::NotFoundError);
+        }
+
+        let token = format!("{}{}", TOKEN_PREFIX, URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>()));
+        let now = Utc::now().naive_utc();
+        let api_token = ApiToken {
+            id: Uuid::now_v7().into(),
+            user_id: self.user_id,
+            name: self.name.trim().to_string(),
+            token_hash: hash_token(&token),
+            last_used_at: None,
+            revoked_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let mut insert_query = Query::insert();
+        let insert_stmt = insert_query
+            .into_table(ApiTokens::Table)
+            .columns(ApiTokens::all_columns())
+            .values_panic([
+                api_token.id.into(),
+                api_token.user_id.into(),
+                api_token.name.clone().into(),
+                api_token.token_hash.clone().into(),
+                api_token.last_used_at.into(),
+                api_token.revoked_at.into(),
+                api_token.created_at.into(),
+                api_token.updated_at.into(),
+            ]);
+
+        service.db_adapter.insert_many(insert_stmt).await?;
+
+        Ok(ApiTokenSecret { api_token, token })
+    }
+}
+
+impl Command for RevokeApiTokenCommand {
+    type Output = ApiToken;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let now = Utc::now().naive_utc();
+
+        // Revoking twice keeps the time of the first revocation
+        let mut update_query = Query::update();
+        let update_stmt = update_query
+            .table(ApiTokens::Table)
+            .value(ApiTokens::RevokedAt, Expr::col(ApiTokens::RevokedAt).if_null(now))
+            .value(ApiTokens::UpdatedAt, now)
+            .and_where(Expr::col(ApiTokens::Id).eq(self.id));
+
+        service.db_adapter.update_one::<ApiToken>(update_stmt).await
+    }
+}
+
+impl Command for ListApiTokensCommand {
+    type Output = Vec<ApiToken>;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let mut query = Query::select();
+        let stmt = query
+            .from(ApiTokens::Table)
+            .columns(ApiTokens::all_columns())
+            .and_where(Expr::col(ApiTokens::UserId).eq(self.user_id))
+            .order_by(ApiTokens::CreatedAt, sea_query::Order::Desc);
+
+        service.db_adapter.query_many::<ApiToken>(stmt).await
+    }
+}
+
+impl Command for AuthenticateApiTokenCommand {
+    type Output = User;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let mut query = Query::select();
+        let stmt = query
+            .from(ApiTokens::Table)
+            .columns(ApiTokens::all_columns())
+            .and_where(Expr::col(ApiTokens::TokenHash).eq(hash_token(&self.token)))
+            .and_where(Expr::col(ApiTokens::RevokedAt).is_null());
+
+        let api_token = service
+            .db_adapter
+            .query_optional::<ApiToken>(stmt)
+            .await?
+            .ok_or(Error::AuthenticationError)?;
+
+        let user = user_repository::get_user_by_id(service, api_token.user_id)
+            .await?
+            .ok_or(Error::AuthenticationError)?;
+        if user.state != UserState::Active {
+            return Err(Error::AuthorizationError);
+        }
+
+        // A single statement, so it does not need to wait for `AppService::write`
+        let mut update_query = Query::update();
+        let update_stmt = update_query
+            .table(ApiTokens::Table)
+            .value(ApiTokens::LastUsedAt, Utc::now().naive_utc())
+            .and_where(Expr::col(ApiTokens::Id).eq(api_token.id));
+        service.db_adapter.update_many(update_stmt).await?;
+
+        Ok(user)
+    }
+}
+
+// Tokens are long and random, so a fast unsalted hash is enough to keep them out of the database
+fn hash_token(token: &str) -> String {
+    digest(&SHA256, token.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        commands::{
+            auth::user_commands::AddUserCommand, sales::sales_order_commands::tests::create_test_user_with_role,
+            tests::setup_service,
+        },
+        models::auth::user_model::{UserNewInput, UserRole, UserUpdateInput},
+    };
+
+    async fn create_user(service: &AppService) -> User {
+        AddUserCommand {
+            user: UserNewInput {
+                username: "backoffice".to_string(),
+                pin_hash: "1234".to_string(),
+                full_name: "Back Office".to_string(),
+                state: UserState::Active,
+                role: UserRole::Cashier,
+                last_login_at: None,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_create_and_authenticate_api_token() {
+        let service = setup_service().await;
+        let user = create_user(&service).await;
+
+        let created = CreateApiTokenCommand { user_id: user.id, name: "Kitchen tablet".to_string(), issued_by: None }
+            .exec(&service)
+            .await
+            .unwrap();
+        assert!(created.token.starts_with(TOKEN_PREFIX));
+        assert_ne!(created.api_token.token_hash, created.token);
+
+        let authenticated = AuthenticateApiTokenCommand { token: created.token.clone() }
+            .exec(&service)
+            .await
+            .unwrap();
+        assert_eq!(authenticated.id, user.id);
+
+        let tokens = ListApiTokensCommand { user_id: user.id }.exec(&service).await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert!(tokens[0].last_used_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_token_is_rejected() {
+        let service = setup_service().await;
+
+        let result = AuthenticateApiTokenCommand { token: "lpos_unknown".to_string() }
+            .exec(&service)
+            .await;
+        assert!(matches!(result, Err(Error::AuthenticationError)));
+    }
+
+    #[tokio::test]
+    async fn test_revoked_token_is_rejected() {
+        let service = setup_service().await;
+        let user = create_user(&service).await;
+        let created = CreateApiTokenCommand { user_id: user.id, name: "Script".to_string(), issued_by: None }
+            .exec(&service)
+            .await
+            .unwrap();
+
+        let revoked = RevokeApiTokenCommand { id: created.api_token.id }.exec(&service).await.unwrap();
+        assert!(revoked.revoked_at.is_some());
+
+        let result = AuthenticateApiTokenCommand { token: created.token }.exec(&service).await;
+        assert!(matches!(result, Err(Error::AuthenticationError)));
+    }
+
+    #[tokio::test]
+    async fn test_token_of_inactive_user_is_rejected() {
+        let service = setup_service().await;
+        let user = create_user(&service).await;
+        let created = CreateApiTokenCommand { user_id: user.id, name: "Script".to_string(), issued_by: None }
+            .exec(&service)
+            .await
+            .unwrap();
+
+        user_repository::update_user(
+            &service,
+            UserUpdateInput {
+                id: user.id,
+                username: None,
+                pin_hash: None,
+                full_name: None,
+                state: Some(UserState::Locked),
+                role: None,
+                last_login_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let result = AuthenticateApiTokenCommand { token: created.token }.exec(&service).await;
+        assert!(matches!(result, Err(Error::AuthorizationError)));
+    }
+
+    #[tokio::test]
+    async fn test_only_supervisors_issue_tokens_for_others() {
+        let service = setup_service().await;
+        let cashier = create_user(&service).await;
+        let supervisor = create_test_user_with_role(&service, UserRole::Supervisor).await;
+
+        let result = CreateApiTokenCommand { user_id: supervisor, name: "Script".to_string(), issued_by: Some(cashier.id) }
+            .exec(&service)
+            .await;
+        assert!(matches!(result, Err(Error::AuthorizationError)));
+
+        let created = CreateApiTokenCommand { user_id: cashier.id, name: "Script".to_string(), issued_by: Some(supervisor) }
+            .exec(&service)
+            .await
+            .unwrap();
+        assert_eq!(created.api_token.user_id, cashier.id);
+    }
+
+    #[tokio::test]
+    async fn test_create_api_token_for_unknown_user() {
+        let service = setup_service().await;
+
+        let result = CreateApiTokenCommand { user_id: Uuid::now_v7().into(), name: "Script".to_string(), issued_by: None }
+            .exec(&service)
+            .await;
+        assert!(matches!(result, Err(Error::NotFoundError)));
+    }
+}
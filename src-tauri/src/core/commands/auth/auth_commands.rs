@@ -1,14 +1,10 @@
 use juniper::{GraphQLInputObject, GraphQLObject};
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
-    core::{
-        commands::{app_service::AppService, Command},
-        models::auth::user_model::{self, User}, repositories::user_repository,
-    },
-    error::{Error, Result},
+    adapters::outgoing::iam::SessionStatus,
+    core::commands::{app_service::AppService, Command},
+    error::Result,
 };
 
 #[derive(Debug, Serialize, GraphQLInputObject)]
@@ -17,7 +13,7 @@ pub struct LoginCommand {
     pub password: String,
 }
 
-#[derive(Debug, Deserialize, GraphQLObject)]
+#[derive(Debug, GraphQLObject)]
 pub struct LoginResponse {
     turso_url: String,
     turso_token: String,
@@ -25,45 +21,36 @@ pub struct LoginResponse {
 
 pub struct LogoutCommand;
 
+/// Refreshes the IAM session once its token is about to expire, see
+/// `IamClient::current_session`. Reconnecting to the synced database is left to the caller,
+/// so the write lock is not held while the IAM service is waited on.
+pub struct RefreshSessionCommand;
+
 impl Command for LoginCommand {
     type Output = LoginResponse;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        let client = Client::new();
-
-        // TODO: Make the IAM service URL configurable
-        let response = client
-            .post("http://localhost:7001/users/login")
-            .json(&LoginCommand { username: self.username.clone(), password: self.password.clone() })
-            .send()
-            .await
-            .map_err(|e| format!("Failed to connect to IAM service: {}", e))?;
-
-        // Check if the request was successful
-        if !response.status().is_success() {
-            return Err(Error::AuthenticationError);
-        }
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let session = service.iam.login(&self.username, &self.password).await?;
 
-        // Parse the response
-        let login_response = response
-            .json::<LoginResponse>()
-            .await
-            .map_err(|e| format!("Failed to parse IAM service response: {}", e))?;
+        // Kept for the next start, so the replica opens without signing in again
+        session.save(service.secrets.as_ref())?;
 
+        let login_response = LoginResponse {
+            turso_url: session.turso_url.expose().to_string(),
+            turso_token: session.turso_token.expose().to_string(),
+        };
 
         #[cfg(not(test))]
-        {
-            let turso_url = login_response.turso_url.clone();
-            let turso_token = login_response.turso_token.clone();
-            service.update_adapter(turso_url, turso_token).await;
-        }
+        service.update_adapter().await?;
 
         #[cfg(test)]
         {
+            use crate::{core::repositories::user_repository, error::Error};
+
             let user = user_repository::get_user_by_username(service, &self.username).await?;
             match user {
                 Some(user) => {
-                    service.state.current_user = Some(user.id);
+                    service.set_current_user(Some(user.id));
                     Ok(login_response)
                 }
                 None => Err(Error::NotFoundError)
@@ -76,20 +63,31 @@ impl Command for LoginCommand {
     }
 }
 
+impl Command for RefreshSessionCommand {
+    type Output = SessionStatus;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let (_, status) = service.iam.current_session(service.secrets.as_ref()).await?;
+        Ok(status)
+    }
+}
+
 impl Command for LogoutCommand {
     type Output = ();
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        service.state.current_user = None;
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        service.set_current_user(None);
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use serde_json::json;
     use uuid::Uuid;
 
     use crate::{
+        adapters::outgoing::iam::{mock::MockIam, IamClient, IamConfig, Session},
         core::{
             commands::{
                 auth::{
@@ -97,25 +95,35 @@ mod tests {
                     user_commands::AddUserCommand,
                 }, tests::setup_service, Command
             },
-            models::auth::user_model::{UserNewInput, UserState},
+            models::auth::user_model::{UserNewInput, UserRole, UserState},
         },
         error::Error,
     };
 
     #[tokio::test]
     async fn test_login_command() {
+        let iam = MockIam::start(|path, body| match path {
+            "/users/login" if body["password"] == "test" => (
+                200,
+                json!({ "turso_url": "libsql://shop.turso.io", "turso_token": "token-1",
+                        "refresh_token": "refresh-1", "expires_in": 3600 }),
+            ),
+            _ => (401, json!({ "error": "unauthorized" })),
+        });
         let mut service = setup_service().await;
+        service.iam = IamClient::new(IamConfig { base_url: iam.url.clone(), ..IamConfig::default() });
         let add_user_command = AddUserCommand {
             user: UserNewInput {
                 username: "test".to_string(),
                 pin_hash: "test".to_string(),
                 full_name: "Test User".to_string(),
                 state: UserState::Active,
+                role: UserRole::Cashier,
                 last_login_at: None,
             },
         };
 
-        let result = add_user_command.exec(&mut service).await;
+        let result = add_user_command.exec(&service).await;
         assert!(result.is_ok());
         let user = result.unwrap();
 
@@ -124,29 +132,32 @@ mod tests {
             password: "test".to_string(),
         };
 
-        let result = login_command.exec(&mut service).await;
+        let result = login_command.exec(&service).await;
         assert!(result.is_ok());
-        assert!(service.state.current_user.is_some());
-        assert_eq!(service.state.current_user.unwrap(), user.id);
+        assert!(service.current_user().is_some());
+        assert_eq!(service.current_user().unwrap(), user.id);
+        let session = Session::load(service.secrets.as_ref()).unwrap().unwrap();
+        assert_eq!(session.turso_token.expose(), "token-1");
+        assert_eq!(iam.requests()[0].0, "/users/login");
 
         // Test login with non-existent user
         let invalid_login = LoginCommand {
             username: "nonexistent".to_string(),
             password: "password".to_string(),
         };
-        let result = invalid_login.exec(&mut service).await;
+        let result = invalid_login.exec(&service).await;
         assert!(matches!(result, Err(Error::AuthenticationError)));
     }
 
     #[tokio::test]
     async fn test_logout_command() {
-        let mut service = setup_service().await;
-        service.state.current_user = Some(Uuid::now_v7().into());
+        let service = setup_service().await;
+        service.set_current_user(Some(Uuid::now_v7().into()));
 
         let logout_command = LogoutCommand;
-        let result = logout_command.exec(&mut service).await;
+        let result = logout_command.exec(&service).await;
 
         assert!(result.is_ok());
-        assert_eq!(service.state.current_user, None);
+        assert_eq!(service.current_user(), None);
     }
 }
@@ -1,2 +1,3 @@
+pub mod api_token_commands;
 pub mod auth_commands;
 pub mod user_commands;
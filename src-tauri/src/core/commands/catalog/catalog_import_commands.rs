@@ -0,0 +1,666 @@
+use std::collections::{HashMap, HashSet};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sea_query::{Expr, Query};
+
+use crate::{
+    adapters::outgoing::database::DatabaseAdapter,
+    core::{
+        commands::{
+            app_service::AppService,
+            catalog::{
+                item_commands::{CreateItemCommand, UpdateItemCommand},
+                item_group_commands::CreateItemGroupCommand,
+                item_variant_commands::{CreateItemVariantCommand, UpdateItemVariantCommand},
+                stock_movement_commands::RecordStockMovementCommand,
+                variant_type_commands::CreateVariantTypeCommand,
+                variant_value_commands::CreateVariantValueCommand,
+            },
+            common::tax_commands::{AssignTaxToItemCommand, CreateTaxCommand},
+            Command,
+        },
+        models::{
+            catalog::{
+                catalog_import_model::{CatalogImportInput, CatalogImportReport, ImportFormat, ImportRowError},
+                item_group_model::{ItemCategories, ItemCategory, ItemCategoryNew},
+                item_model::{Item, ItemNature, ItemState, Items, NewItem, UpdateItem},
+                item_variant_model::{ItemVariant, ItemVariantNewInput, ItemVariantUpdateInput, ItemVariants},
+                stock_movement_model::{StockMovementNewInput, StockMovementReason},
+                variant_type_model::{VariantType, VariantTypeNewInput, VariantTypes},
+                variant_value_model::{VariantValue, VariantValueNewInput, VariantValues},
+            },
+            common::tax_model::{ItemTaxNewInput, ItemTaxes, Tax, TaxNewInput, Taxes},
+        },
+        types::{db_uuid::DbUuid, money::Money, percentage::Percentage},
+        utils::{csv, xlsx},
+    },
+    error::{Error, Result},
+};
+
+/// The columns a catalog file may have. Headers are matched ignoring case, with spaces read
+/// as underscores.
+const COLUMNS: [&str; 12] = [
+    "name",
+    "category",
+    "description",
+    "nature",
+    "state",
+    "price",
+    "taxes",
+    "sku",
+    "barcode",
+    "variant",
+    "price_adjustment",
+    "opening_stock",
+];
+
+// Commands
+
+/// Creates or updates items from a spreadsheet. Each row is an item, or a variant of one:
+/// a row whose `sku` belongs to a variant updates that variant, otherwise the item is looked
+/// up by name and created when there is none. Categories, taxes and variant values are
+/// created as they are first named.
+///
+/// All rows are saved in one transaction, and only when none of them has an error.
+pub struct ImportCatalogCommand {
+    pub import: CatalogImportInput,
+    pub created_by: Option<DbUuid>,
+}
+
+// A row whose import failed, and the column to blame
+struct RowError {
+    column: Option<&'static str>,
+    error: Error,
+}
+
+fn at(column: &'static str) -> impl FnOnce(Error) -> RowError {
+    move |error| RowError { column: Some(column), error }
+}
+
+fn invalid(column: &'static str, message: String) -> RowError {
+    RowError { column: Some(column), error: Error::ValidationError(message) }
+}
+
+// The fields of one record, by column
+struct Row<'a> {
+    fields: &'a [String],
+    columns: &'a HashMap<&'static str, usize>,
+}
+
+impl Row<'_> {
+    fn get(&self, column: &str) -> Option<&str> {
+        let index = *self.columns.get(column)?;
+        self.fields.get(index).map(|field| field.trim()).filter(|field| !field.is_empty())
+    }
+}
+
+// Command Implementations
+impl Command for ImportCatalogCommand {
+    type Output = CatalogImportReport;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let records = match self.import.format {
+            ImportFormat::Csv => csv::read(&self.import.contents)?,
+            ImportFormat::Xlsx => {
+                let bytes = STANDARD
+                    .decode(self.import.contents.trim())
+                    .map_err(|_| Error::ValidationError("XLSX contents must be base64".to_string()))?;
+                xlsx::read(&bytes)?
+            }
+        };
+        let Some((header, rows)) = records.split_first() else {
+            return Err(Error::ValidationError("The file is empty".to_string()));
+        };
+
+        let mut report = CatalogImportReport::default();
+        let mut columns = HashMap::new();
+        for (index, title) in header.iter().enumerate() {
+            let title = title.trim().to_lowercase().replace(' ', "_");
+            if title.is_empty() {
+                continue;
+            }
+            match COLUMNS.iter().find(|column| **column == title) {
+                Some(column) => {
+                    columns.insert(*column, index);
+                }
+                None => report.errors.push(ImportRowError {
+                    row: 1,
+                    column: Some(title),
+                    message: "Unknown column".to_string(),
+                }),
+            }
+        }
+        if !columns.contains_key("name") {
+            return Err(Error::ValidationError("The header row must have a name column".to_string()));
+        }
+
+        let dry_run = self.import.dry_run.unwrap_or(false);
+        let (columns, counts) = (&columns, &mut report);
+        let committed = service
+            .db_adapter
+            .transaction(move || async move {
+                // Rows are undone with a savepoint so that the report is still returned
+                service.db_adapter.execute("SAVEPOINT import").await?;
+                self.import_rows(service, rows, columns, counts).await?;
+                let committed = counts.errors.is_empty() && !dry_run;
+                if !committed {
+                    service.db_adapter.execute("ROLLBACK TO import").await?;
+                }
+                service.db_adapter.execute("RELEASE import").await?;
+                Ok(committed)
+            })
+            .await?;
+
+        report.committed = committed;
+        Ok(report)
+    }
+}
+
+impl ImportCatalogCommand {
+    async fn import_rows(
+        &self,
+        service: &AppService,
+        rows: &[Vec<String>],
+        columns: &HashMap<&'static str, usize>,
+        report: &mut CatalogImportReport,
+    ) -> Result<()> {
+        let mut created_items = HashSet::new();
+
+        for (index, fields) in rows.iter().enumerate() {
+            if fields.iter().all(|field| field.trim().is_empty()) {
+                continue;
+            }
+            report.rows += 1;
+
+            // A failed row is undone on its own so that the rows after it are still checked
+            service.db_adapter.execute("SAVEPOINT import_row").await?;
+            let row = Row { fields, columns };
+            let mut counts = CatalogImportReport::default();
+            match self.import_row(service, &row, &mut counts, &mut created_items).await {
+                Ok(()) => {
+                    report.items_created += counts.items_created;
+                    report.items_updated += counts.items_updated;
+                    report.categories_created += counts.categories_created;
+                    report.taxes_created += counts.taxes_created;
+                    report.variants_created += counts.variants_created;
+                    report.variants_updated += counts.variants_updated;
+                }
+                Err(RowError { column, error }) => {
+                    service.db_adapter.execute("ROLLBACK TO import_row").await?;
+                    report.errors.push(ImportRowError {
+                        row: index as i32 + 2,
+                        column: column.map(str::to_string),
+                        message: error.message(),
+                    });
+                }
+            }
+            service.db_adapter.execute("RELEASE import_row").await?;
+        }
+
+        Ok(())
+    }
+
+    async fn import_row(
+        &self,
+        service: &AppService,
+        row: &Row<'_>,
+        report: &mut CatalogImportReport,
+        created_items: &mut HashSet<DbUuid>,
+    ) -> std::result::Result<(), RowError> {
+        // Check every field before writing anything
+        let name = row.get("name").ok_or_else(|| invalid("name", "Name is required".to_string()))?;
+        let nature = row
+            .get("nature")
+            .map(|nature| match nature.to_lowercase().as_str() {
+                "goods" => Ok(ItemNature::Goods),
+                "service" => Ok(ItemNature::Service),
+                _ => Err(invalid("nature", format!("Nature must be goods or service, not {}", nature))),
+            })
+            .transpose()?;
+        let state = row
+            .get("state")
+            .map(|state| match state.to_lowercase().as_str() {
+                "active" => Ok(ItemState::Active),
+                "inactive" => Ok(ItemState::Inactive),
+                _ => Err(invalid("state", format!("State must be active or inactive, not {}", state))),
+            })
+            .transpose()?;
+        let price = row
+            .get("price")
+            .map(|price| Money::from_str(price).map_err(|e| invalid("price", format!("Invalid price: {}", e))))
+            .transpose()?;
+        let price_adjustment = row
+            .get("price_adjustment")
+            .map(|price| {
+                Money::from_str(price).map_err(|e| invalid("price_adjustment", format!("Invalid price adjustment: {}", e)))
+            })
+            .transpose()?;
+        let opening_stock = row
+            .get("opening_stock")
+            .map(|quantity| {
+                quantity
+                    .parse::<i32>()
+                    .map_err(|_| invalid("opening_stock", format!("Opening stock must be a whole number, not {}", quantity)))
+            })
+            .transpose()?;
+        let taxes = row.get("taxes").map(parse_taxes).transpose()?.unwrap_or_default();
+        let variant_values = row.get("variant").map(parse_variant).transpose()?.unwrap_or_default();
+        let sku = row.get("sku");
+
+        // A known SKU names the variant, and with it the item
+        let variant = match sku {
+            Some(sku) => {
+                let mut query = ItemVariants::select();
+                query.and_where(Expr::col(ItemVariants::Sku).eq(sku));
+                service.db_adapter.query_optional::<ItemVariant>(&query).await.map_err(at("sku"))?
+            }
+            None => None,
+        };
+        let item = match &variant {
+            Some(variant) => Some(
+                service
+                    .db_adapter
+                    .query_one::<Item>(&Items::find_by_id(variant.item_id))
+                    .await
+                    .map_err(at("sku"))?,
+            ),
+            None => {
+                let mut query = Items::select();
+                query.and_where(Expr::col(Items::Name).eq(name)).and_where(Expr::col(Items::DeletedAt).is_null());
+                service.db_adapter.query_optional::<Item>(&query).await.map_err(at("name"))?
+            }
+        };
+
+        let category_id = match row.get("category") {
+            Some(category) => Some(find_or_create_category(service, category, report).await.map_err(at("category"))?),
+            None => None,
+        };
+        let mut tax_ids = Vec::new();
+        for (tax, rate) in taxes {
+            tax_ids.push(find_or_create_tax(service, &tax, rate, report).await.map_err(at("taxes"))?);
+        }
+
+        let item = match item {
+            None => {
+                let category_id =
+                    category_id.ok_or_else(|| invalid("category", "Category is required for new items".to_string()))?;
+                let price = price.ok_or_else(|| invalid("price", "Price is required for new items".to_string()))?;
+                let command = CreateItemCommand {
+                    item: NewItem {
+                        name: name.to_string(),
+                        description: row.get("description").map(str::to_string),
+                        nature: nature.unwrap_or(ItemNature::Goods),
+                        state: state.unwrap_or(ItemState::Active),
+                        price,
+                        category_id,
+                        tax_ids: (!tax_ids.is_empty()).then_some(tax_ids),
+                    },
+                };
+                let item = command.exec(service).await.map_err(|error| RowError { column: None, error })?;
+                report.items_created += 1;
+                created_items.insert(item.id);
+                item
+            }
+            Some(item) => {
+                let command = UpdateItemCommand {
+                    item: UpdateItem {
+                        id: item.id,
+                        version: item.version,
+                        name: None,
+                        description: row.get("description").map(|description| Some(description.to_string())),
+                        nature,
+                        state,
+                        price,
+                        category_id,
+                    },
+                };
+                let updated = command.exec(service).await.map_err(|error| RowError { column: None, error })?;
+
+                let mut query = Query::select();
+                query
+                    .column(ItemTaxes::TaxId)
+                    .from(ItemTaxes::Table)
+                    .and_where(Expr::col(ItemTaxes::ItemId).eq(item.id.to_string()));
+                let assigned = service.db_adapter.query_many::<DbUuid>(&query).await.map_err(at("taxes"))?;
+                for tax_id in tax_ids.into_iter().filter(|tax_id| !assigned.contains(tax_id)) {
+                    let command = AssignTaxToItemCommand { item_tax: ItemTaxNewInput { item_id: item.id, tax_id } };
+                    command.exec(service).await.map_err(at("taxes"))?;
+                }
+
+                if !created_items.contains(&item.id) {
+                    report.items_updated += 1;
+                }
+                updated
+            }
+        };
+
+        let variant_id = match variant {
+            Some(variant) => {
+                let command = UpdateItemVariantCommand {
+                    item_variant: ItemVariantUpdateInput {
+                        id: variant.id,
+                        sku: None,
+                        barcode: row.get("barcode").map(|barcode| Some(barcode.to_string())),
+                        price_adjustment: price_adjustment.map(Some),
+                        is_default: None,
+                        updated_at: None,
+                    },
+                };
+                command.exec(service).await.map_err(at("barcode"))?;
+                report.variants_updated += 1;
+                Some(variant.id)
+            }
+            None if sku.is_some() || row.get("barcode").is_some() || !variant_values.is_empty() => {
+                let mut variant_value_ids = Vec::new();
+                for (variant_type, value) in &variant_values {
+                    variant_value_ids
+                        .push(find_or_create_variant_value(service, variant_type, value).await.map_err(at("variant"))?);
+                }
+                let command = CreateItemVariantCommand {
+                    item_variant: ItemVariantNewInput {
+                        item_id: item.id,
+                        sku: sku.map(str::to_string),
+                        barcode: row.get("barcode").map(str::to_string),
+                        price_adjustment,
+                        is_default: None,
+                        variant_value_ids,
+                    },
+                };
+                let variant = command.exec(service).await.map_err(at("variant"))?;
+                report.variants_created += 1;
+                Some(variant.id)
+            }
+            None => None,
+        };
+
+        if let Some(quantity) = opening_stock {
+            let command = RecordStockMovementCommand {
+                movement: StockMovementNewInput {
+                    item_id: item.id,
+                    item_variant_id: variant_id,
+                    quantity,
+                    reason: StockMovementReason::Opening,
+                },
+                created_by: self.created_by,
+            };
+            command.exec(service).await.map_err(at("opening_stock"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Taxes are separated by `;`, each a name with an optional rate: `VAT:15; Service`
+fn parse_taxes(taxes: &str) -> std::result::Result<Vec<(String, Option<Percentage>)>, RowError> {
+    let mut parsed = Vec::new();
+    for tax in taxes.split(';').map(str::trim).filter(|tax| !tax.is_empty()) {
+        let (name, rate) = match tax.split_once(':') {
+            Some((name, rate)) => {
+                let rate = Percentage::from_str(rate.trim().trim_end_matches('%'))
+                    .map_err(|e| invalid("taxes", format!("Invalid rate for {}: {}", name.trim(), e)))?;
+                (name.trim(), Some(rate))
+            }
+            None => (tax, None),
+        };
+        parsed.push((name.to_string(), rate));
+    }
+    Ok(parsed)
+}
+
+/// Variant values are separated by `;`, each a type and a value: `Size: Large; Color: Red`
+fn parse_variant(variant: &str) -> std::result::Result<Vec<(String, String)>, RowError> {
+    variant
+        .split(';')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| match value.split_once(':') {
+            Some((variant_type, value)) if !variant_type.trim().is_empty() && !value.trim().is_empty() => {
+                Ok((variant_type.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(invalid("variant", format!("Expected type: value, not {}", value))),
+        })
+        .collect()
+}
+
+async fn find_or_create_category(service: &AppService, name: &str, report: &mut CatalogImportReport) -> Result<DbUuid> {
+    let mut query = ItemCategories::select();
+    query
+        .and_where(Expr::col(ItemCategories::Name).eq(name))
+        .and_where(Expr::col(ItemCategories::DeletedAt).is_null());
+    if let Some(category) = service.db_adapter.query_optional::<ItemCategory>(&query).await? {
+        return Ok(category.id);
+    }
+
+    let command = CreateItemGroupCommand { category: ItemCategoryNew { name: name.to_string(), description: None } };
+    let category = command.exec(service).await?;
+    report.categories_created += 1;
+    Ok(category.id)
+}
+
+async fn find_or_create_tax(
+    service: &AppService,
+    name: &str,
+    rate: Option<Percentage>,
+    report: &mut CatalogImportReport,
+) -> Result<DbUuid> {
+    let mut query = Taxes::select();
+    query.and_where(Expr::col(Taxes::Name).eq(name));
+    match (service.db_adapter.query_optional::<Tax>(&query).await?, rate) {
+        (Some(tax), Some(rate)) if tax.rate != rate => Err(Error::ValidationError(format!(
+            "{} is already a tax at {}%",
+            name,
+            tax.rate.to_string()
+        ))),
+        (Some(tax), _) => Ok(tax.id),
+        (None, Some(rate)) => {
+            let command = CreateTaxCommand {
+                tax: TaxNewInput { name: name.to_string(), rate, description: None, item_ids: None },
+            };
+            let tax = command.exec(service).await?;
+            report.taxes_created += 1;
+            Ok(tax.id)
+        }
+        (None, None) => Err(Error::ValidationError(format!("Unknown tax {}; give its rate as {}:rate", name, name))),
+    }
+}
+
+async fn find_or_create_variant_value(service: &AppService, variant_type: &str, value: &str) -> Result<DbUuid> {
+    let mut query = VariantTypes::select();
+    query.and_where(Expr::col(VariantTypes::Name).eq(variant_type));
+    let variant_type = match service.db_adapter.query_optional::<VariantType>(&query).await? {
+        Some(variant_type) => variant_type,
+        None => {
+            let command = CreateVariantTypeCommand {
+                variant_type: VariantTypeNewInput { name: variant_type.to_string(), description: None },
+            };
+            command.exec(service).await?
+        }
+    };
+
+    let mut query = VariantValues::select();
+    query
+        .and_where(Expr::col(VariantValues::VariantTypeId).eq(variant_type.id.to_string()))
+        .and_where(Expr::col(VariantValues::Value).eq(value));
+    if let Some(value) = service.db_adapter.query_optional::<VariantValue>(&query).await? {
+        return Ok(value.id);
+    }
+
+    let command = CreateVariantValueCommand {
+        variant_value: VariantValueNewInput {
+            variant_type_id: variant_type.id,
+            value: value.to_string(),
+            display_order: None,
+        },
+    };
+    Ok(command.exec(service).await?.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        commands::{catalog::stock_movement_commands::GetStockOnHandCommand, tests::setup_service},
+        models::common::tax_model::ItemTax,
+    };
+
+    fn csv_import(contents: &str, dry_run: bool) -> ImportCatalogCommand {
+        ImportCatalogCommand {
+            import: CatalogImportInput {
+                format: ImportFormat::Csv,
+                contents: contents.to_string(),
+                dry_run: Some(dry_run),
+            },
+            created_by: None,
+        }
+    }
+
+    async fn items(service: &AppService) -> Vec<Item> {
+        let mut query = Items::select();
+        query.order_by(Items::Name, sea_query::Order::Asc);
+        service.db_adapter.query_many::<Item>(&query).await.unwrap()
+    }
+
+    const CATALOG: &str = "\
+Name,Category,Price,Taxes,SKU,Barcode,Variant,Price Adjustment,Opening Stock
+Baker's Bread,Bakery,2.50,VAT:15,,,,,12
+T-Shirt,Clothing,20,VAT; Service:2.5,TS-S,1001,Size: Small,,5
+T-Shirt,Clothing,,,TS-L,1002,Size: Large,1.50,3
+";
+
+    #[tokio::test]
+    async fn test_import_creates_catalog() {
+        let service = setup_service().await;
+
+        let report = csv_import(CATALOG, false).exec(&service).await.unwrap();
+        assert!(report.committed, "{:?}", report.errors);
+        assert_eq!(report.rows, 3);
+        assert_eq!(report.items_created, 2);
+        assert_eq!(report.items_updated, 0);
+        assert_eq!(report.categories_created, 2);
+        assert_eq!(report.taxes_created, 2);
+        assert_eq!(report.variants_created, 2);
+
+        let items = items(&service).await;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].name, "Baker's Bread");
+        assert_eq!(items[0].price, Money::from(250));
+        let shirt = &items[1];
+        assert_eq!(shirt.price, Money::from(2000));
+
+        let mut query = Query::select();
+        query
+            .columns([ItemTaxes::ItemId, ItemTaxes::TaxId])
+            .from(ItemTaxes::Table)
+            .and_where(Expr::col(ItemTaxes::ItemId).eq(shirt.id.to_string()));
+        let taxes = service.db_adapter.query_many::<ItemTax>(&query).await.unwrap();
+        assert_eq!(taxes.len(), 2);
+
+        let mut query = ItemVariants::select();
+        query.and_where(Expr::col(ItemVariants::Sku).eq("TS-L"));
+        let large = service.db_adapter.query_one::<ItemVariant>(&query).await.unwrap();
+        assert_eq!(large.item_id, shirt.id);
+        assert_eq!(large.barcode.as_deref(), Some("1002"));
+        assert_eq!(large.price_adjustment, Some(Money::from(150)));
+
+        let stock = |item_id, item_variant_id| GetStockOnHandCommand { item_id, item_variant_id };
+        assert_eq!(stock(items[0].id, None).exec(&service).await.unwrap(), 12);
+        assert_eq!(stock(shirt.id, None).exec(&service).await.unwrap(), 8);
+        assert_eq!(stock(shirt.id, Some(large.id)).exec(&service).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_saves_nothing() {
+        let service = setup_service().await;
+
+        let report = csv_import(CATALOG, true).exec(&service).await.unwrap();
+        assert!(!report.committed);
+        assert!(report.errors.is_empty());
+        assert_eq!(report.items_created, 2);
+        assert!(items(&service).await.is_empty());
+
+        // The dry run left no transaction open behind it
+        let report = csv_import(CATALOG, false).exec(&service).await.unwrap();
+        assert!(report.committed);
+        assert_eq!(items(&service).await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rows_with_errors_save_nothing() {
+        let service = setup_service().await;
+        let contents = "\
+name,category,price,taxes,colour
+Cola,Drinks,1.20,,
+Tea,Drinks,cheap,,
+
+Coffee,,3,,
+Water,Drinks,1,Sales Tax,
+";
+
+        let report = csv_import(contents, false).exec(&service).await.unwrap();
+        assert!(!report.committed);
+        assert_eq!(report.rows, 4);
+        let errors: Vec<_> = report.errors.iter().map(|e| (e.row, e.column.as_deref())).collect();
+        assert_eq!(
+            errors,
+            [(1, Some("colour")), (3, Some("price")), (5, Some("category")), (6, Some("taxes"))]
+        );
+        assert!(report.errors[3].message.contains("Sales Tax"));
+        assert!(items(&service).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_import_updates_items_by_name_and_variants_by_sku() {
+        let service = setup_service().await;
+        csv_import(CATALOG, false).exec(&service).await.unwrap();
+
+        let contents = "\
+name,price,sku,barcode,opening_stock
+Baker's Bread,2.75,,,
+Renamed in the file,,TS-L,2002,2
+";
+        let report = csv_import(contents, false).exec(&service).await.unwrap();
+        assert!(report.committed, "{:?}", report.errors);
+        assert_eq!(report.items_created, 0);
+        assert_eq!(report.items_updated, 2);
+        assert_eq!(report.variants_updated, 1);
+
+        let items = items(&service).await;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].price, Money::from(275));
+        assert_eq!(items[0].version, 2);
+        assert_eq!(items[1].name, "T-Shirt");
+
+        let mut query = ItemVariants::select();
+        query.and_where(Expr::col(ItemVariants::Sku).eq("TS-L"));
+        let large = service.db_adapter.query_one::<ItemVariant>(&query).await.unwrap();
+        assert_eq!(large.barcode.as_deref(), Some("2002"));
+        let command = GetStockOnHandCommand { item_id: items[1].id, item_variant_id: Some(large.id) };
+        assert_eq!(command.exec(&service).await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_import_xlsx() {
+        let service = setup_service().await;
+        let bytes = xlsx::tests::workbook(
+            &["Name", "Category", "Price", "Nature", "Consulting", "Services"],
+            r#"<row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c><c r="C1" t="s"><v>2</v></c><c r="D1" t="s"><v>3</v></c></row>
+               <row r="2"><c r="A2" t="s"><v>4</v></c><c r="B2" t="s"><v>5</v></c><c r="C2"><v>45.5</v></c><c r="D2" t="inlineStr"><is><t>Service</t></is></c></row>"#,
+        );
+        let command = ImportCatalogCommand {
+            import: CatalogImportInput {
+                format: ImportFormat::Xlsx,
+                contents: STANDARD.encode(bytes),
+                dry_run: None,
+            },
+            created_by: None,
+        };
+
+        let report = command.exec(&service).await.unwrap();
+        assert!(report.committed, "{:?}", report.errors);
+        let items = items(&service).await;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, "Consulting");
+        assert_eq!(items[0].price, Money::from(4550));
+        assert!(matches!(items[0].nature, ItemNature::Service));
+    }
+}
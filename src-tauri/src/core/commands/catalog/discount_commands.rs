@@ -6,13 +6,13 @@ use crate::{
     adapters::outgoing::database::DatabaseAdapter,
     core::{
         commands::{app_service::AppService, Command},
-        db::SeaQueryCrudTrait,
+        db::{row_version::update_versioned, SeaQueryCrudTrait},
         models::catalog::discount_model::{
             Discount, DiscountNewInput, DiscountState, DiscountUpdateInput, Discounts,
         },
         types::db_uuid::DbUuid,
     },
-    error::{Error, Result},
+    error::{Constraint, Error, Result},
 };
 
 // --- Command Structs ---
@@ -35,12 +35,15 @@ pub struct GetDiscountCommand {
 
 pub struct ListDiscountsCommand;
 
+/// Persists the state transitions implied by each discount's start and end dates
+pub struct RefreshDiscountStatesCommand;
+
 // --- Command Implementations ---
 
 impl Command for CreateDiscountCommand {
     type Output = Discount;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Check for uniqueness by name using SeaQuery
         let mut select_query = Query::select();
         let check_stmt = select_query
@@ -50,7 +53,7 @@ impl Command for CreateDiscountCommand {
 
         let existing = service.db_adapter.query_optional::<DbUuid>(&check_stmt).await?;
         if existing.is_some() {
-            return Err(Error::UniqueConstraintError);
+            return Err(Error::UniqueConstraintError(Constraint::new("discounts", "name")));
         }
 
         // Create new discount
@@ -70,6 +73,7 @@ impl Command for CreateDiscountCommand {
             end_date: self.discount.end_date,
             created_at: now,
             updated_at: now,
+            version: 1,
         };
 
         // Generate and execute the insert query
@@ -83,104 +87,55 @@ impl Command for CreateDiscountCommand {
 impl Command for UpdateDiscountCommand {
     type Output = Discount;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        // Check if discount exists using SeaQuery
-        let mut select_query = Query::select();
-        let check_stmt = select_query
-            .from(Discounts::Table)
-            .columns([
-                Discounts::Id,
-                Discounts::Name,
-                Discounts::Description,
-                Discounts::DiscountType,
-                Discounts::Value,
-                Discounts::Scope,
-                Discounts::State,
-                Discounts::StartDate,
-                Discounts::EndDate,
-                Discounts::CreatedAt,
-                Discounts::UpdatedAt,
-            ])
-            .and_where(Expr::col(Discounts::Id).eq(self.discount.id.to_string()));
-
-        let existing_discount = service.db_adapter.query_optional::<Discount>(&check_stmt).await?;
-        if existing_discount.is_none() {
-            return Err(Error::NotFoundError);
-        }
-
-        // Get the existing discount data and update it
-        let mut discount = existing_discount.unwrap();
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
 
-        // Update fields if they exist
+        let mut update_query = Query::update();
+        let update_stmt = update_query
+            .table(Discounts::Table)
+            .value(Discounts::UpdatedAt, now.to_string());
+
+        // Only set fields that are provided in the update input
         if let Some(name) = &self.discount.name {
-            discount.name = name.clone();
+            update_stmt.value(Discounts::Name, name.clone());
         }
 
         if let Some(description) = &self.discount.description {
-            discount.description = description.clone();
+            update_stmt.value(Discounts::Description, description.clone());
         }
 
         if let Some(discount_type) = &self.discount.discount_type {
-            discount.discount_type = *discount_type;
+            update_stmt.value(Discounts::DiscountType, discount_type.to_string());
         }
 
         if let Some(value) = &self.discount.value {
-            discount.value = *value;
+            update_stmt.value(Discounts::Value, value.to_base_unit());
         }
 
         if let Some(scope) = &self.discount.scope {
-            discount.scope = *scope;
+            update_stmt.value(Discounts::Scope, scope.to_string());
         }
 
         if let Some(state) = &self.discount.state {
-            discount.state = *state;
+            update_stmt.value(Discounts::State, state.to_string());
         }
 
         if let Some(start_date) = &self.discount.start_date {
-            discount.start_date = start_date.clone();
+            update_stmt.value(Discounts::StartDate, start_date.map(|date| date.to_string()));
         }
 
         if let Some(end_date) = &self.discount.end_date {
-            discount.end_date = end_date.clone();
+            update_stmt.value(Discounts::EndDate, end_date.map(|date| date.to_string()));
         }
 
-        // Always update the updated_at timestamp
-        discount.updated_at = now;
-
-        // Generate and execute the update query
-        let update_stmt = discount.update();
-        service.db_adapter.update_one::<Discount>(&update_stmt).await?;
-
-        // Retrieve the updated discount
-        let mut select_query = Query::select();
-        let select_stmt = select_query
-            .from(Discounts::Table)
-            .columns([
-                Discounts::Id,
-                Discounts::Name,
-                Discounts::Description,
-                Discounts::DiscountType,
-                Discounts::Value,
-                Discounts::Scope,
-                Discounts::State,
-                Discounts::StartDate,
-                Discounts::EndDate,
-                Discounts::CreatedAt,
-                Discounts::UpdatedAt,
-            ])
-            .and_where(Expr::col(Discounts::Id).eq(self.discount.id.to_string()));
-
-        let updated_discount = service.db_adapter.query_one::<Discount>(&select_stmt).await?;
-
-        Ok(updated_discount)
+        update_versioned(&service.db_adapter, update_stmt, self.discount.id, self.discount.version).await
     }
 }
 
 impl Command for DeleteDiscountCommand {
     type Output = usize;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Use the delete_by_id helper method from SeaQueryCrudTrait
         let delete_stmt = Discount::delete_by_id(self.id);
         let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
@@ -196,7 +151,7 @@ impl Command for DeleteDiscountCommand {
 impl Command for GetDiscountCommand {
     type Output = Discount;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Build select query with SeaQuery
         let mut select_query = Query::select();
         let select_stmt = select_query
@@ -213,20 +168,21 @@ impl Command for GetDiscountCommand {
                 Discounts::EndDate,
                 Discounts::CreatedAt,
                 Discounts::UpdatedAt,
+                Discounts::Version,
             ])
             .and_where(Expr::col(Discounts::Id).eq(self.id.to_string()));
 
         // Execute the select query
         let discount = service.db_adapter.query_one::<Discount>(&select_stmt).await?;
 
-        Ok(discount)
+        Ok(discount.with_effective_state(Utc::now().naive_utc()))
     }
 }
 
 impl Command for ListDiscountsCommand {
     type Output = Vec<Discount>;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Build select query with SeaQuery
         let mut select_query = Query::select();
         let select_stmt = select_query
@@ -243,24 +199,68 @@ impl Command for ListDiscountsCommand {
                 Discounts::EndDate,
                 Discounts::CreatedAt,
                 Discounts::UpdatedAt,
+                Discounts::Version,
             ]);
 
         // Execute the select query
         let discounts = service.db_adapter.query_many::<Discount>(&select_stmt).await?;
 
+        // Report the state derived from the dates rather than the stored one
+        let now = Utc::now().naive_utc();
+        let discounts = discounts
+            .into_iter()
+            .map(|discount| discount.with_effective_state(now))
+            .collect();
+
         Ok(discounts)
     }
 }
 
+impl Command for RefreshDiscountStatesCommand {
+    type Output = usize;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let mut select_query = Query::select();
+        let select_stmt = select_query
+            .from(Discounts::Table)
+            .columns(Discounts::all_columns())
+            .and_where(Expr::col(Discounts::State).ne(DiscountState::Inactive.to_string()));
+
+        let discounts = service.db_adapter.query_many::<Discount>(&select_stmt).await?;
+
+        let now = Utc::now().naive_utc();
+        let mut updated = 0;
+
+        for discount in discounts {
+            let effective_state = discount.effective_state(now);
+            if effective_state == discount.state {
+                continue;
+            }
+
+            let mut update_query = Query::update();
+            let update_stmt = update_query
+                .table(Discounts::Table)
+                .value(Discounts::State, effective_state.to_string())
+                .value(Discounts::UpdatedAt, now.to_string())
+                .and_where(Expr::col(Discounts::Id).eq(discount.id.to_string()));
+
+            updated += service.db_adapter.update_many(&update_stmt).await? as usize;
+        }
+
+        Ok(updated)
+    }
+}
+
 // --- Tests ---
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::{Duration, NaiveDateTime};
     use crate::core::{
         commands::tests::setup_service, models::catalog::discount_model::{DiscountScope, DiscountType}, types::money::Money
     };
 
-    async fn create_basic_discount(service: &mut AppService) -> Discount {
+    async fn create_basic_discount(service: &AppService) -> Discount {
         let new_discount_info = DiscountNewInput {
             name: "Test Discount".to_string(),
             description: Some("Basic test discount".to_string()),
@@ -282,8 +282,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_discount() {
-        let mut service = setup_service().await;
-        let created = create_basic_discount(&mut service).await;
+        let service = setup_service().await;
+        let created = create_basic_discount(&service).await;
 
         assert_eq!(created.name, "Test Discount");
         assert_eq!(created.discount_type, DiscountType::Percentage);
@@ -293,8 +293,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_discount_unique_constraint() {
-        let mut service = setup_service().await;
-        create_basic_discount(&mut service).await; // Create first one
+        let service = setup_service().await;
+        create_basic_discount(&service).await; // Create first one
 
         // Try creating another with the same name
         let new_discount_info = DiscountNewInput {
@@ -310,18 +310,18 @@ mod tests {
         let create_cmd = CreateDiscountCommand {
             discount: new_discount_info,
         };
-        let result = create_cmd.exec(&mut service).await;
+        let result = create_cmd.exec(&service).await;
 
-        assert!(matches!(result, Err(Error::UniqueConstraintError)));
+        assert!(matches!(result, Err(Error::UniqueConstraintError(_))));
     }
 
     #[tokio::test]
     async fn test_get_discount() {
-        let mut service = setup_service().await;
-        let created = create_basic_discount(&mut service).await;
+        let service = setup_service().await;
+        let created = create_basic_discount(&service).await;
 
         let get_cmd = GetDiscountCommand { id: created.id };
-        let fetched = get_cmd.exec(&mut service).await.expect("Failed to get discount");
+        let fetched = get_cmd.exec(&service).await.expect("Failed to get discount");
 
         assert_eq!(fetched.id, created.id);
         assert_eq!(fetched.name, "Test Discount");
@@ -329,24 +329,24 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_discount_not_found() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let non_existent_id = Uuid::now_v7().into();
         let get_cmd = GetDiscountCommand {
             id: non_existent_id,
         };
-        let result = get_cmd.exec(&mut service).await;
+        let result = get_cmd.exec(&service).await;
 
         assert!(matches!(result, Err(Error::NotFoundError)));
     }
 
     #[tokio::test]
     async fn test_list_discounts() {
-        let mut service = setup_service().await;
-        let created1 = create_basic_discount(&mut service).await;
+        let service = setup_service().await;
+        let created1 = create_basic_discount(&service).await;
 
         let list_cmd = ListDiscountsCommand;
         let list1 = list_cmd
-            .exec(&mut service)
+            .exec(&service)
             .await
             .expect("Failed to list discounts");
         assert_eq!(list1.len(), 1);
@@ -367,12 +367,12 @@ mod tests {
             discount: new_discount_info,
         };
         let created2 = create_cmd
-            .exec(&mut service)
+            .exec(&service)
             .await
             .expect("Failed to create second discount");
 
         let list2 = list_cmd
-            .exec(&mut service)
+            .exec(&service)
             .await
             .expect("Failed to list discounts again");
         assert_eq!(list2.len(), 2);
@@ -383,11 +383,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_discount() {
-        let mut service = setup_service().await;
-        let created = create_basic_discount(&mut service).await;
+        let service = setup_service().await;
+        let created = create_basic_discount(&service).await;
 
         let update_info = DiscountUpdateInput {
             id: created.id,
+            version: created.version,
             name: Some("Updated Discount Name".to_string()),
             description: Some(Some("Updated description".to_string())), // Update description
             discount_type: Some(DiscountType::FixedAmount),
@@ -401,7 +402,7 @@ mod tests {
             discount: update_info,
         };
         let updated = update_cmd
-            .exec(&mut service)
+            .exec(&service)
             .await
             .expect("Failed to update discount");
 
@@ -415,10 +416,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_discount_not_found() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let non_existent_id = Uuid::now_v7().into();
         let update_info = DiscountUpdateInput {
             id: non_existent_id,
+            version: 1,
             name: Some("Does not matter".to_string()),
             description: None,
             discount_type: None,
@@ -431,29 +433,168 @@ mod tests {
         let update_cmd = UpdateDiscountCommand {
             discount: update_info,
         };
-        let result = update_cmd.exec(&mut service).await;
+        let result = update_cmd.exec(&service).await;
         assert!(matches!(result, Err(Error::NotFoundError)));
     }
 
+    #[tokio::test]
+    async fn test_update_discount_from_stale_version() {
+        let service = setup_service().await;
+        let created = create_basic_discount(&service).await;
+
+        let update = |state: DiscountState| DiscountUpdateInput {
+            id: created.id,
+            version: created.version,
+            name: None,
+            description: None,
+            discount_type: None,
+            value: None,
+            scope: None,
+            state: Some(state),
+            start_date: None,
+            end_date: None,
+        };
+
+        UpdateDiscountCommand { discount: update(DiscountState::Inactive) }.exec(&service).await.unwrap();
+        let result = UpdateDiscountCommand { discount: update(DiscountState::Expired) }.exec(&service).await;
+
+        match result {
+            Err(Error::ConflictError(conflict)) => {
+                assert_eq!(conflict.table, "discounts");
+                assert_eq!(conflict.current["state"], "INACTIVE");
+                assert_eq!(conflict.current["discountType"], "PERCENTAGE");
+                assert_eq!(conflict.current["version"], created.version + 1);
+            }
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+    }
+
+    async fn create_dated_discount(
+        service: &AppService,
+        name: &str,
+        start_date: Option<NaiveDateTime>,
+        end_date: Option<NaiveDateTime>,
+    ) -> Discount {
+        let create_cmd = CreateDiscountCommand {
+            discount: DiscountNewInput {
+                name: name.to_string(),
+                description: None,
+                discount_type: DiscountType::Percentage,
+                value: Money::from_float(10.0),
+                scope: DiscountScope::AllItems,
+                state: Some(DiscountState::Active),
+                start_date,
+                end_date,
+            },
+        };
+        create_cmd
+            .exec(service)
+            .await
+            .expect("Failed to create dated discount")
+    }
+
+    #[tokio::test]
+    async fn test_list_discounts_derives_state_from_dates() {
+        let service = setup_service().await;
+        let now = Utc::now().naive_utc();
+        let expired = create_dated_discount(
+            &service,
+            "Expired Discount",
+            Some(now - Duration::days(10)),
+            Some(now - Duration::days(1)),
+        )
+        .await;
+        let scheduled =
+            create_dated_discount(&service, "Scheduled Discount", Some(now + Duration::days(1)), None)
+                .await;
+        let running = create_dated_discount(
+            &service,
+            "Running Discount",
+            Some(now - Duration::days(1)),
+            Some(now + Duration::days(1)),
+        )
+        .await;
+
+        let list = ListDiscountsCommand.exec(&service).await.unwrap();
+        let state_of = |id: DbUuid| list.iter().find(|d| d.id == id).unwrap().state;
+
+        assert_eq!(state_of(expired.id), DiscountState::Expired);
+        assert_eq!(state_of(scheduled.id), DiscountState::Scheduled);
+        assert_eq!(state_of(running.id), DiscountState::Active);
+
+        let fetched = GetDiscountCommand { id: expired.id }.exec(&service).await.unwrap();
+        assert_eq!(fetched.state, DiscountState::Expired);
+    }
+
+    #[tokio::test]
+    async fn test_effective_state_keeps_inactive_and_undated_states() {
+        let service = setup_service().await;
+        let now = Utc::now().naive_utc();
+        let mut discount = create_dated_discount(
+            &service,
+            "Paused Discount",
+            None,
+            Some(now - Duration::days(1)),
+        )
+        .await;
+
+        discount.state = DiscountState::Inactive;
+        assert_eq!(discount.effective_state(now), DiscountState::Inactive);
+
+        discount.end_date = None;
+        discount.state = DiscountState::Expired;
+        assert_eq!(discount.effective_state(now), DiscountState::Expired);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_discount_states() {
+        let service = setup_service().await;
+        let now = Utc::now().naive_utc();
+        let expired = create_dated_discount(
+            &service,
+            "Expired Discount",
+            None,
+            Some(now - Duration::hours(1)),
+        )
+        .await;
+        create_basic_discount(&service).await;
+
+        let updated = RefreshDiscountStatesCommand.exec(&service).await.unwrap();
+        assert_eq!(updated, 1);
+
+        // The transition is persisted, not just derived on read
+        let select_stmt = Query::select()
+            .from(Discounts::Table)
+            .columns(Discounts::all_columns())
+            .and_where(Expr::col(Discounts::Id).eq(expired.id.to_string()))
+            .to_owned();
+        let stored = service.db_adapter.query_one::<Discount>(&select_stmt).await.unwrap();
+        assert_eq!(stored.state, DiscountState::Expired);
+
+        // Nothing left to transition on the next run
+        let updated = RefreshDiscountStatesCommand.exec(&service).await.unwrap();
+        assert_eq!(updated, 0);
+    }
+
     #[tokio::test]
     async fn test_delete_discount() {
-        let mut service = setup_service().await;
-        let created = create_basic_discount(&mut service).await;
+        let service = setup_service().await;
+        let created = create_basic_discount(&service).await;
 
         // Delete
         let delete_cmd = DeleteDiscountCommand { id: created.id };
-        let delete_result = delete_cmd.exec(&mut service).await.expect("Delete failed");
+        let delete_result = delete_cmd.exec(&service).await.expect("Delete failed");
         assert_eq!(delete_result, 1); // 1 row deleted
 
         // Verify deletion
         let get_cmd = GetDiscountCommand { id: created.id };
-        let get_result = get_cmd.exec(&mut service).await;
+        let get_result = get_cmd.exec(&service).await;
         assert!(matches!(get_result, Err(Error::NotFoundError)));
 
         // Verify list is empty
         let list_cmd = ListDiscountsCommand;
         let list = list_cmd
-            .exec(&mut service)
+            .exec(&service)
             .await
             .expect("List failed after delete");
         assert!(list.is_empty());
@@ -461,13 +602,13 @@ mod tests {
 
     #[tokio::test]
     async fn test_delete_discount_not_found() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let non_existent_id = Uuid::now_v7().into();
 
         let delete_cmd = DeleteDiscountCommand {
             id: non_existent_id,
         };
-        let result = delete_cmd.exec(&mut service).await;
+        let result = delete_cmd.exec(&service).await;
 
         assert!(matches!(result, Err(Error::NotFoundError)));
     }
@@ -6,12 +6,17 @@ use crate::{
     adapters::outgoing::database::DatabaseAdapter,
     core::{
         commands::{app_service::AppService, Command},
+        db::{
+            SeaQueryCrudTrait,
+            row_version::update_versioned,
+            soft_delete::{purge, restore, soft_delete},
+        },
         models::{
             catalog::{
                 item_group_model::{ItemCategories, ItemCategory},
                 item_model::{Item, Items, NewItem, UpdateItem},
             },
-            common::tax_model::{Tax, Taxes},
+            common::tax_model::{ItemTaxes, Tax, Taxes},
         }, types::db_uuid::DbUuid,
     },
     error::{Error, Result},
@@ -28,13 +33,22 @@ pub struct UpdateItemCommand {
 
 pub struct DeleteItemCommand {
     pub id: DbUuid,
+    pub deleted_by: Option<DbUuid>,
+}
+
+pub struct RestoreItemCommand {
+    pub id: DbUuid,
+}
+
+pub struct PurgeItemCommand {
+    pub id: DbUuid,
 }
 
 // Command Implementations
 impl Command for CreateItemCommand {
     type Output = Item;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Verify category exists
         let mut select_query = Query::select();
         let category_stmt = select_query
@@ -66,41 +80,6 @@ impl Command for CreateItemCommand {
         let now = Utc::now().naive_utc();
         let item_id: DbUuid = Uuid::now_v7().into();
 
-        // Build the insert query
-        let insert_sql = format!(
-            "INSERT INTO items (id, name, description, nature, state, price, category_id, created_at, updated_at) \
-             VALUES ('{}', '{}', {}, '{}', '{}', '{}', '{}', '{}', '{}')",
-            item_id.to_string(),
-            self.item.name.clone(),
-            match &self.item.description {
-                Some(desc) => format!("'{}'", desc),
-                None => "NULL".to_string(),
-            },
-            self.item.nature.to_string(),
-            self.item.state.to_string(),
-            self.item.price.to_string(),
-            self.item.category_id.to_string(),
-            now.to_string(),
-            now.to_string()
-        );
-
-        // Execute the insert query
-        service.db_adapter.execute(&insert_sql).await?;
-
-        // Create item-tax associations if tax_ids are provided
-        if let Some(tax_ids) = &self.item.tax_ids {
-            for tax_id in tax_ids {
-                let item_tax_sql = format!(
-                    "INSERT INTO item_taxes (item_id, tax_id) VALUES ('{}', '{}')",
-                    item_id.to_string(),
-                    tax_id.to_string()
-                );
-
-                service.db_adapter.execute(&item_tax_sql).await?;
-            }
-        }
-
-        // Create and return the new item object
         let new_item = Item {
             id: item_id,
             name: self.item.name.clone(),
@@ -108,10 +87,27 @@ impl Command for CreateItemCommand {
             nature: self.item.nature,
             state: self.item.state,
             price: self.item.price,
-            category_id: self.item.category_id.clone(),
+            category_id: self.item.category_id,
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
+            version: 1,
         };
+        service.db_adapter.insert_many(&new_item.insert()).await?;
+
+        // Create item-tax associations if tax_ids are provided
+        if let Some(tax_ids) = &self.item.tax_ids {
+            for tax_id in tax_ids {
+                let mut insert_query = Query::insert();
+                let item_tax_stmt = insert_query
+                    .into_table(ItemTaxes::Table)
+                    .columns([ItemTaxes::ItemId, ItemTaxes::TaxId])
+                    .values_panic([item_id.to_string().into(), tax_id.to_string().into()]);
+
+                service.db_adapter.insert_many(item_tax_stmt).await?;
+            }
+        }
 
         Ok(new_item)
     }
@@ -120,7 +116,7 @@ impl Command for CreateItemCommand {
 impl Command for UpdateItemCommand {
     type Output = Item;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Verify category exists if provided
         if let Some(cat_id) = self.item.category_id {
             let mut select_query = Query::select();
@@ -135,29 +131,6 @@ impl Command for UpdateItemCommand {
             }
         }
 
-        // Verify item exists
-        let mut item_select_query = Query::select();
-        let item_stmt = item_select_query
-            .from(Items::Table)
-            .columns([
-                Items::Id,
-                Items::Name,
-                Items::Description,
-                Items::Nature,
-                Items::State,
-                Items::Price,
-                Items::CategoryId,
-                Items::CreatedAt,
-                Items::UpdatedAt,
-            ])
-            .and_where(Expr::col(Items::Id).eq(self.item.id.to_string()));
-
-        let existing_item = service.db_adapter.query_optional::<Item>(&item_stmt).await?;
-        if existing_item.is_none() {
-            return Err(Error::NotFoundError);
-        }
-
-        let _existing_item = existing_item.unwrap();
         let now = Utc::now().naive_utc();
 
         // Build update query with SeaQuery
@@ -186,58 +159,42 @@ impl Command for UpdateItemCommand {
         }
 
         if let Some(price) = &self.item.price {
-            update_stmt.value(Items::Price, price.to_string());
+            update_stmt.value(Items::Price, price.to_base_unit());
         }
 
         if let Some(category_id) = &self.item.category_id {
             update_stmt.value(Items::CategoryId, category_id.to_string());
         }
 
-        // Add WHERE condition
-        update_stmt.and_where(Expr::col(Items::Id).eq(self.item.id.to_string()));
+        update_versioned(&service.db_adapter, update_stmt, self.item.id, self.item.version).await
+    }
+}
+
+impl Command for DeleteItemCommand {
+    type Output = i32;
 
-        // Execute the update
-        service.db_adapter.update_many(&update_stmt).await?;
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        soft_delete::<Item, _>(&service.db_adapter, self.id, self.deleted_by).await?;
 
-        // Retrieve the updated item
-        let mut select_query = Query::select();
-        let select_stmt = select_query
-            .from(Items::Table)
-            .columns([
-                Items::Id,
-                Items::Name,
-                Items::Description,
-                Items::Nature,
-                Items::State,
-                Items::Price,
-                Items::CategoryId,
-                Items::CreatedAt,
-                Items::UpdatedAt,
-            ])
-            .and_where(Expr::col(Items::Id).eq(self.item.id.to_string()));
+        Ok(1)
+    }
+}
 
-        let updated_item = service.db_adapter.query_one::<Item>(&select_stmt).await?;
+impl Command for RestoreItemCommand {
+    type Output = Item;
 
-        Ok(updated_item)
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        restore(&service.db_adapter, self.id).await
     }
 }
 
-impl Command for DeleteItemCommand {
+impl Command for PurgeItemCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        let mut delete_query = Query::delete();
-        let delete_stmt = delete_query
-            .from_table(Items::Table)
-            .and_where(Expr::col(Items::Id).eq(self.id.to_string()));
-
-        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        purge::<Item, _>(&service.db_adapter, self.id).await?;
 
-        if affected_rows == 0 {
-            Err(Error::NotFoundError)
-        } else {
-            Ok(affected_rows as i32)
-        }
+        Ok(1)
     }
 }
 
@@ -248,7 +205,7 @@ mod tests {
     use super::*;
 
     // Helper function to create a test category
-    async fn create_test_category(service: &mut AppService) -> ItemCategory {
+    async fn create_test_category(service: &AppService) -> ItemCategory {
         let category_id: DbUuid = Uuid::now_v7().into();
         let now = Utc::now().naive_utc();
 
@@ -271,11 +228,13 @@ mod tests {
             state: ItemCategoryState::Active,
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
         }
     }
 
     // Helper function to create a test tax
-    async fn create_test_tax(service: &mut AppService) -> Tax {
+    async fn create_test_tax(service: &AppService) -> Tax {
         let tax_id: DbUuid = Uuid::now_v7().into();
         let now = Utc::now().naive_utc();
         let rate = Percentage::from_float(10.0);
@@ -304,10 +263,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_item() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a test category first
-        let category = create_test_category(&mut service).await;
+        let category = create_test_category(&service).await;
 
         let command = CreateItemCommand {
             item: NewItem {
@@ -321,21 +280,21 @@ mod tests {
             },
         };
 
-        let item = command.exec(&mut service).await.unwrap();
+        let item = command.exec(&service).await.unwrap();
         assert_eq!(item.name, "Test Item");
         assert_eq!(item.category_id, category.id);
     }
 
     #[tokio::test]
     async fn test_create_item_with_taxes() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create test category
-        let category = create_test_category(&mut service).await;
+        let category = create_test_category(&service).await;
 
         // Create test taxes
-        let tax1 = create_test_tax(&mut service).await;
-        let tax2 = create_test_tax(&mut service).await;
+        let tax1 = create_test_tax(&service).await;
+        let tax2 = create_test_tax(&service).await;
 
         let command = CreateItemCommand {
             item: NewItem {
@@ -349,7 +308,7 @@ mod tests {
             },
         };
 
-        let item = command.exec(&mut service).await.unwrap();
+        let item = command.exec(&service).await.unwrap();
         assert_eq!(item.name, "Test Item");
 
         // Verify tax associations were created
@@ -371,10 +330,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_item_with_nonexistent_tax() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create only category
-        let category = create_test_category(&mut service).await;
+        let category = create_test_category(&service).await;
 
         let command = CreateItemCommand {
             item: NewItem {
@@ -388,16 +347,16 @@ mod tests {
             },
         };
 
-        let result = command.exec(&mut service).await;
+        let result = command.exec(&service).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_update_item() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a test category first
-        let category = create_test_category(&mut service).await;
+        let category = create_test_category(&service).await;
 
         let command = CreateItemCommand {
             item: NewItem {
@@ -411,10 +370,11 @@ mod tests {
             },
         };
 
-        let item = command.exec(&mut service).await.unwrap();
+        let item = command.exec(&service).await.unwrap();
 
         let updated_item = UpdateItem {
             id: item.id,
+            version: item.version,
             name: Some("Test Item 2".to_string()),
             description: None,
             nature: None,
@@ -424,16 +384,17 @@ mod tests {
         };
 
         let update_command = UpdateItemCommand { item: updated_item };
-        let result = update_command.exec(&mut service).await;
+        let result = update_command.exec(&service).await;
         assert!(result.is_ok());
     }
 
     #[tokio::test]
     async fn test_update_item_does_not_exist() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let item = UpdateItem {
             id: Uuid::now_v7().into(),
+            version: 1,
             name: Some("Test Item".to_string()),
             description: None,
             nature: None,
@@ -443,17 +404,68 @@ mod tests {
         };
 
         let command = UpdateItemCommand { item };
-        let result = command.exec(&mut service).await;
+        let result = command.exec(&service).await;
 
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_update_item_from_stale_version() {
+        let service = setup_service().await;
+        let category = create_test_category(&service).await;
+
+        let command = CreateItemCommand {
+            item: NewItem {
+                name: "Test Item".to_string(),
+                description: None,
+                nature: ItemNature::Goods,
+                state: ItemState::Active,
+                price: Money::from(1000),
+                category_id: category.id,
+                tax_ids: None,
+            },
+        };
+        let item = command.exec(&service).await.unwrap();
+
+        let update = |name: &str, price: Option<Money>| UpdateItem {
+            id: item.id,
+            version: item.version,
+            name: Some(name.to_string()),
+            description: None,
+            nature: None,
+            state: None,
+            price,
+            category_id: None,
+        };
+
+        let first = UpdateItemCommand { item: update("Renamed", None) }.exec(&service).await.unwrap();
+        assert_eq!(first.version, item.version + 1);
+
+        // A second terminal still holding the original version
+        let result = UpdateItemCommand { item: update("Repriced", Some(Money::from(1200))) }
+            .exec(&service)
+            .await;
+        match result {
+            Err(Error::ConflictError(conflict)) => {
+                assert_eq!(conflict.table, "items");
+                assert_eq!(conflict.current["name"], "Renamed");
+                assert_eq!(conflict.current["price"], "10");
+                assert_eq!(conflict.current["version"], first.version);
+            }
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+
+        let current = service.db_adapter.query_one::<Item>(&Items::find_by_id(item.id)).await.unwrap();
+        assert_eq!(current.name, "Renamed");
+        assert_eq!(current.price, Money::from(1000));
+    }
+
     #[tokio::test]
     async fn test_delete_item() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a test category first
-        let category = create_test_category(&mut service).await;
+        let category = create_test_category(&service).await;
 
         let command = CreateItemCommand {
             item: NewItem {
@@ -467,21 +479,22 @@ mod tests {
             },
         };
 
-        let item = command.exec(&mut service).await.unwrap();
+        let item = command.exec(&service).await.unwrap();
 
-        let delete_command = DeleteItemCommand { id: item.id };
-        let result = delete_command.exec(&mut service).await;
+        let delete_command = DeleteItemCommand { id: item.id, deleted_by: None };
+        let result = delete_command.exec(&service).await;
         assert!(result.is_ok());
     }
 
     #[tokio::test]
     async fn test_delete_item_does_not_exist() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let command = DeleteItemCommand {
             id: Uuid::now_v7().into(),
+            deleted_by: None,
         };
-        let result = command.exec(&mut service).await;
+        let result = command.exec(&service).await;
         assert!(matches!(result, Err(Error::NotFoundError)));
     }
 }
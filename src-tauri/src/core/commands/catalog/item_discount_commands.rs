@@ -30,7 +30,7 @@ pub struct GetDiscountItemsCommand {
 impl Command for AddItemDiscountCommand {
     type Output = ItemDiscount;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Check if the relationship already exists
         let mut select_query = Query::select();
         let select_query = select_query
@@ -75,7 +75,7 @@ impl Command for AddItemDiscountCommand {
 impl Command for RemoveItemDiscountCommand {
     type Output = usize;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let mut delete_query = Query::delete();
         let delete_query = delete_query
             .from_table(ItemDiscounts::Table)
@@ -91,7 +91,7 @@ impl Command for RemoveItemDiscountCommand {
 impl Command for GetItemDiscountsCommand {
     type Output = Vec<ItemDiscount>;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let mut select_query = Query::select();
         let select_query = select_query
             .from(ItemDiscounts::Table)
@@ -110,7 +110,7 @@ impl Command for GetItemDiscountsCommand {
 impl Command for GetDiscountItemsCommand {
     type Output = Vec<ItemDiscount>;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let mut select_query = Query::select();
         let select_query = select_query
             .from(ItemDiscounts::Table)
@@ -142,7 +142,7 @@ mod tests {
     use uuid::Uuid;
     use tokio;
 
-    async fn create_test_item_category(service: &mut AppService) -> DbUuid {
+    async fn create_test_item_category(service: &AppService) -> DbUuid {
         let category_name = format!("Test Category {}", Uuid::now_v7());
         let command = CreateItemGroupCommand {
             category: ItemCategoryNew {
@@ -154,7 +154,7 @@ mod tests {
         category.id
     }
 
-    async fn create_test_item(service: &mut AppService) -> Item {
+    async fn create_test_item(service: &AppService) -> Item {
         let now = Utc::now().naive_utc();
         let category_id = create_test_item_category(service).await;
         let item_id = Uuid::now_v7().into();
@@ -169,6 +169,9 @@ mod tests {
             price: Money::from_float(100.0),
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
+            version: 1,
         };
 
         // Use SeaQuery to insert the item
@@ -205,7 +208,7 @@ mod tests {
         item
     }
 
-    async fn create_test_discount(service: &mut AppService, name: Option<String>) -> Discount {
+    async fn create_test_discount(service: &AppService, name: Option<String>) -> Discount {
         let discount_input = DiscountNewInput {
             name: name.unwrap_or_else(|| format!("Test Discount {}", Uuid::now_v7())),
             description: Some("Test Discount Description".to_string()),
@@ -224,9 +227,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_add_item_discount() {
-        let mut service = setup_service().await;
-        let item = create_test_item(&mut service).await;
-        let discount = create_test_discount(&mut service, None).await;
+        let service = setup_service().await;
+        let item = create_test_item(&service).await;
+        let discount = create_test_discount(&service, None).await;
 
         let item_discount_input = ItemDiscountNewInput {
             item_id: item.id,
@@ -235,7 +238,7 @@ mod tests {
         let add_cmd = AddItemDiscountCommand {
             item_discount: item_discount_input,
         };
-        let result = add_cmd.exec(&mut service).await.unwrap();
+        let result = add_cmd.exec(&service).await.unwrap();
 
         assert_eq!(result.item_id, item.id);
         assert_eq!(result.discount_id, discount.id);
@@ -243,9 +246,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_add_duplicate_item_discount() {
-        let mut service = setup_service().await;
-        let item = create_test_item(&mut service).await;
-        let discount = create_test_discount(&mut service, None).await;
+        let service = setup_service().await;
+        let item = create_test_item(&service).await;
+        let discount = create_test_discount(&service, None).await;
 
         // Add the relationship first time
         let item_discount_input = ItemDiscountNewInput {
@@ -255,13 +258,13 @@ mod tests {
         let add_cmd = AddItemDiscountCommand {
             item_discount: item_discount_input.clone(),
         };
-        add_cmd.exec(&mut service).await.unwrap();
+        add_cmd.exec(&service).await.unwrap();
 
         // Try to add the same relationship again
         let add_cmd = AddItemDiscountCommand {
             item_discount: item_discount_input,
         };
-        let result = add_cmd.exec(&mut service).await.unwrap();
+        let result = add_cmd.exec(&service).await.unwrap();
 
         // Should return the existing relationship without error
         assert_eq!(result.item_id, item.id);
@@ -269,16 +272,16 @@ mod tests {
 
         // Verify only one relationship exists
         let get_cmd = GetItemDiscountsCommand { item_id: item.id };
-        let result = get_cmd.exec(&mut service).await.unwrap();
+        let result = get_cmd.exec(&service).await.unwrap();
         assert_eq!(result.len(), 1);
     }
 
     #[tokio::test]
     async fn test_get_item_discounts() {
-        let mut service = setup_service().await;
-        let item = create_test_item(&mut service).await;
-        let discount1 = create_test_discount(&mut service, None).await;
-        let discount2 = create_test_discount(&mut service, None).await;
+        let service = setup_service().await;
+        let item = create_test_item(&service).await;
+        let discount1 = create_test_discount(&service, None).await;
+        let discount2 = create_test_discount(&service, None).await;
 
         // Add two discounts to the same item
         let add_cmd1 = AddItemDiscountCommand {
@@ -287,7 +290,7 @@ mod tests {
                 discount_id: discount1.id,
             },
         };
-        add_cmd1.exec(&mut service).await.unwrap();
+        add_cmd1.exec(&service).await.unwrap();
 
         let add_cmd2 = AddItemDiscountCommand {
             item_discount: ItemDiscountNewInput {
@@ -295,11 +298,11 @@ mod tests {
                 discount_id: discount2.id,
             },
         };
-        add_cmd2.exec(&mut service).await.unwrap();
+        add_cmd2.exec(&service).await.unwrap();
 
         // Get item discounts
         let get_cmd = GetItemDiscountsCommand { item_id: item.id };
-        let result = get_cmd.exec(&mut service).await.unwrap();
+        let result = get_cmd.exec(&service).await.unwrap();
 
         assert_eq!(result.len(), 2);
         assert!(result.iter().any(|d| d.discount_id == discount1.id));
@@ -308,15 +311,15 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_discount_items() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a unique discount first
         let discount_name = format!("Discount for Items {}", Uuid::now_v7());
-        let discount = create_test_discount(&mut service, Some(discount_name)).await;
+        let discount = create_test_discount(&service, Some(discount_name)).await;
 
         // Create items after the discount
-        let item1 = create_test_item(&mut service).await;
-        let item2 = create_test_item(&mut service).await;
+        let item1 = create_test_item(&service).await;
+        let item2 = create_test_item(&service).await;
 
         // Add the same discount to two items
         let add_cmd1 = AddItemDiscountCommand {
@@ -325,7 +328,7 @@ mod tests {
                 discount_id: discount.id,
             },
         };
-        add_cmd1.exec(&mut service).await.unwrap();
+        add_cmd1.exec(&service).await.unwrap();
 
         let add_cmd2 = AddItemDiscountCommand {
             item_discount: ItemDiscountNewInput {
@@ -333,13 +336,13 @@ mod tests {
                 discount_id: discount.id,
             },
         };
-        add_cmd2.exec(&mut service).await.unwrap();
+        add_cmd2.exec(&service).await.unwrap();
 
         // Get discount items
         let get_cmd = GetDiscountItemsCommand {
             discount_id: discount.id,
         };
-        let result = get_cmd.exec(&mut service).await.unwrap();
+        let result = get_cmd.exec(&service).await.unwrap();
 
         assert_eq!(result.len(), 2);
         assert!(result.iter().any(|d| d.item_id == item1.id));
@@ -348,9 +351,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_remove_item_discount() {
-        let mut service = setup_service().await;
-        let item = create_test_item(&mut service).await;
-        let discount = create_test_discount(&mut service, None).await;
+        let service = setup_service().await;
+        let item = create_test_item(&service).await;
+        let discount = create_test_discount(&service, None).await;
 
         // Add the relationship
         let add_cmd = AddItemDiscountCommand {
@@ -359,11 +362,11 @@ mod tests {
                 discount_id: discount.id,
             },
         };
-        add_cmd.exec(&mut service).await.unwrap();
+        add_cmd.exec(&service).await.unwrap();
 
         // Verify it exists
         let get_cmd = GetItemDiscountsCommand { item_id: item.id };
-        let result = get_cmd.exec(&mut service).await.unwrap();
+        let result = get_cmd.exec(&service).await.unwrap();
         assert_eq!(result.len(), 1);
 
         // Remove the relationship
@@ -371,27 +374,27 @@ mod tests {
             item_id: item.id,
             discount_id: discount.id,
         };
-        let deleted_count = remove_cmd.exec(&mut service).await.unwrap();
+        let deleted_count = remove_cmd.exec(&service).await.unwrap();
         assert_eq!(deleted_count, 1);
 
         // Verify it's gone
         let get_cmd = GetItemDiscountsCommand { item_id: item.id };
-        let result = get_cmd.exec(&mut service).await.unwrap();
+        let result = get_cmd.exec(&service).await.unwrap();
         assert_eq!(result.len(), 0);
     }
 
     #[tokio::test]
     async fn test_remove_nonexistent_item_discount() {
-        let mut service = setup_service().await;
-        let item = create_test_item(&mut service).await;
-        let discount = create_test_discount(&mut service, None).await;
+        let service = setup_service().await;
+        let item = create_test_item(&service).await;
+        let discount = create_test_discount(&service, None).await;
 
         // Try to remove a relationship that doesn't exist
         let remove_cmd = RemoveItemDiscountCommand {
             item_id: item.id,
             discount_id: discount.id,
         };
-        let deleted_count = remove_cmd.exec(&mut service).await.unwrap();
+        let deleted_count = remove_cmd.exec(&service).await.unwrap();
         assert_eq!(deleted_count, 0); // Should return 0 rows affected
     }
 }
@@ -5,11 +5,12 @@ use uuid::Uuid;
 use crate::{
     adapters::outgoing::database::DatabaseAdapter, core::{
         commands::{app_service::AppService, Command},
+        db::soft_delete::{purge, restore, soft_delete},
         models::catalog::item_group_model::{
             ItemCategory, ItemCategories, ItemCategoryNew, ItemCategoryState, ItemCategoryUpdate,
         },
         types::db_uuid::DbUuid,
-    }, error::{Error, Result}
+    }, error::{Constraint, Error, Result}
 };
 
 // Commands
@@ -23,13 +24,22 @@ pub struct UpdateItemGroupCommand {
 
 pub struct DeleteItemGroupCommand {
     pub id: DbUuid,
+    pub deleted_by: Option<DbUuid>,
+}
+
+pub struct RestoreItemGroupCommand {
+    pub id: DbUuid,
+}
+
+pub struct PurgeItemGroupCommand {
+    pub id: DbUuid,
 }
 
 // Command Implementations
 impl Command for CreateItemGroupCommand {
     type Output = ItemCategory;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Check if a category with the same name already exists
         let mut select_query = Query::select();
         let select_stmt = select_query
@@ -40,7 +50,7 @@ impl Command for CreateItemGroupCommand {
         let existing = service.db_adapter.query_optional::<DbUuid>(&select_stmt).await?;
 
         if existing.is_some() {
-            return Err(Error::UniqueConstraintError);
+            return Err(Error::UniqueConstraintError(Constraint::new("item_categories", "name")));
         }
 
         let now = Utc::now().naive_utc();
@@ -53,6 +63,8 @@ impl Command for CreateItemGroupCommand {
             state: ItemCategoryState::Inactive,
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
         };
 
         // Insert the new category
@@ -85,7 +97,7 @@ impl Command for CreateItemGroupCommand {
 impl Command for UpdateItemGroupCommand {
     type Output = ItemCategory;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Check if the category exists
         let mut select_query = Query::select();
         let select_stmt = select_query
@@ -155,32 +167,45 @@ impl Command for UpdateItemGroupCommand {
 impl Command for DeleteItemGroupCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        // Check if category has items
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        // Check if category has items that are not deleted
         use crate::core::models::catalog::item_model::Items;
 
         let mut count_query = Query::select();
         let count_stmt = count_query
             .from(Items::Table)
             .expr(Expr::count(Expr::col(Items::Id)))
-            .and_where(Expr::col(Items::CategoryId).eq(self.id.to_string()));
+            .and_where(Expr::col(Items::CategoryId).eq(self.id.to_string()))
+            .and_where(Expr::col(Items::DeletedAt).is_null());
 
         // Execute the count query
         let count_result = service.db_adapter.query_one::<i64>(&count_stmt).await?;
 
         if count_result > 0 {
-            return Err(Error::ForeignKeyConstraintError);
+            return Err(Error::ForeignKeyConstraintError(Constraint::new("items", "category_id")));
         }
 
-        // Delete the category
-        let mut delete_query = Query::delete();
-        let delete_stmt = delete_query
-            .from_table(ItemCategories::Table)
-            .and_where(Expr::col(ItemCategories::Id).eq(self.id.to_string()));
+        soft_delete::<ItemCategory, _>(&service.db_adapter, self.id, self.deleted_by).await?;
+
+        Ok(1)
+    }
+}
+
+impl Command for RestoreItemGroupCommand {
+    type Output = ItemCategory;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        restore(&service.db_adapter, self.id).await
+    }
+}
+
+impl Command for PurgeItemGroupCommand {
+    type Output = i32;
 
-        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        purge::<ItemCategory, _>(&service.db_adapter, self.id).await?;
 
-        Ok(affected_rows as i32)
+        Ok(1)
     }
 }
 
@@ -193,19 +218,19 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_item_category() {
-        let mut app_service = setup_service().await;
+        let app_service = setup_service().await;
         let new_cat = ItemCategoryNew {
             name: "test".to_string(),
             description: Some("test description".to_string()),
         };
         let command = CreateItemGroupCommand { category: new_cat };
-        let result = command.exec(&mut app_service).await;
+        let result = command.exec(&app_service).await;
         assert!(result.is_ok());
     }
 
     #[tokio::test]
     async fn test_create_item_category_already_exists() {
-        let mut app_service = setup_service().await;
+        let app_service = setup_service().await;
         let new_cat = ItemCategoryNew {
             name: "test".to_string(),
             description: Some("test description".to_string()),
@@ -213,25 +238,25 @@ mod tests {
         let command = CreateItemGroupCommand {
             category: new_cat.clone(),
         };
-        let result = command.exec(&mut app_service).await;
+        let result = command.exec(&app_service).await;
 
         assert!(result.is_ok());
 
         let command = CreateItemGroupCommand { category: new_cat };
-        let result = command.exec(&mut app_service).await;
+        let result = command.exec(&app_service).await;
 
-        assert!(matches!(result, Err(Error::UniqueConstraintError)));
+        assert!(matches!(result, Err(Error::UniqueConstraintError(_))));
     }
 
     #[tokio::test]
     async fn test_update_item_category() {
-        let mut app_service = setup_service().await;
+        let app_service = setup_service().await;
         let new_cat = ItemCategoryNew {
             name: "test".to_string(),
             description: Some("test description".to_string()),
         };
         let create_command = CreateItemGroupCommand { category: new_cat };
-        let category = create_command.exec(&mut app_service).await.unwrap();
+        let category = create_command.exec(&app_service).await.unwrap();
 
         let updated_category = ItemCategoryUpdate {
             id: category.id,
@@ -243,14 +268,14 @@ mod tests {
         let update_command = UpdateItemGroupCommand {
             category: updated_category,
         };
-        let result = update_command.exec(&mut app_service).await;
+        let result = update_command.exec(&app_service).await;
         assert!(result.is_ok());
         assert!(result.unwrap().name == "updated test");
     }
 
     #[tokio::test]
     async fn test_update_item_category_does_not_exist() {
-        let mut app_service = setup_service().await;
+        let app_service = setup_service().await;
         let category = ItemCategoryUpdate {
             id: Uuid::now_v7().into(),
             name: Some("test".to_string()),
@@ -259,23 +284,59 @@ mod tests {
         };
 
         let command = UpdateItemGroupCommand { category };
-        let result = command.exec(&mut app_service).await;
+        let result = command.exec(&app_service).await;
         assert!(matches!(result, Err(Error::NotFoundError)))
     }
 
     #[tokio::test]
     async fn test_delete_item_category() {
-        let mut app_service = setup_service().await;
+        let app_service = setup_service().await;
         let new_cat = ItemCategoryNew {
             name: "test".to_string(),
             description: Some("test description".to_string()),
         };
 
         let create_command = CreateItemGroupCommand { category: new_cat };
-        let cat = create_command.exec(&mut app_service).await.unwrap();
+        let cat = create_command.exec(&app_service).await.unwrap();
+
+        let delete_command = DeleteItemGroupCommand { id: cat.id, deleted_by: None };
+        let result = delete_command.exec(&app_service).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_delete_item_category_with_items() {
+        use crate::core::{
+            commands::catalog::item_commands::{CreateItemCommand, DeleteItemCommand},
+            models::catalog::item_model::{ItemNature, ItemState, NewItem},
+            types::money::Money,
+        };
+
+        let app_service = setup_service().await;
+        let create_command = CreateItemGroupCommand {
+            category: ItemCategoryNew { name: "test".to_string(), description: None },
+        };
+        let cat = create_command.exec(&app_service).await.unwrap();
+        let create_item = CreateItemCommand {
+            item: NewItem {
+                name: "test item".to_string(),
+                description: None,
+                nature: ItemNature::Goods,
+                state: ItemState::Active,
+                price: Money::from(1000),
+                category_id: cat.id,
+                tax_ids: None,
+            },
+        };
+        let item = create_item.exec(&app_service).await.unwrap();
+
+        let delete_command = DeleteItemGroupCommand { id: cat.id, deleted_by: None };
+        let result = delete_command.exec(&app_service).await;
+        assert!(matches!(result, Err(Error::ForeignKeyConstraintError(_))));
 
-        let delete_command = DeleteItemGroupCommand { id: cat.id };
-        let result = delete_command.exec(&mut app_service).await;
+        // Deleted items no longer keep their category
+        DeleteItemCommand { id: item.id, deleted_by: None }.exec(&app_service).await.unwrap();
+        let result = delete_command.exec(&app_service).await;
         assert!(result.is_ok());
     }
 }
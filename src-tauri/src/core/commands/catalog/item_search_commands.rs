@@ -0,0 +1,220 @@
+//! Item search for the register, backed by the `item_search` FTS5 table
+//!
+//! Every word typed is matched as a prefix against the item's name, description and the SKUs
+//! and barcodes of its variants, so "coc col" finds "Coca Cola". Results are ranked by bm25,
+//! with name matches weighted above codes and codes above descriptions.
+
+use sea_query::{Alias, Expr, Order, Query};
+
+use crate::{
+    adapters::outgoing::database::DatabaseAdapter,
+    core::{
+        commands::{app_service::AppService, Command},
+        models::catalog::item_model::{Item, ItemState, Items},
+        types::db_uuid::DbUuid,
+    },
+    error::Result,
+};
+
+/// Results returned when no limit is given
+const DEFAULT_LIMIT: i32 = 20;
+
+/// Most results returned for one search
+const MAX_LIMIT: i32 = 100;
+
+/// Column weights for bm25: item_id, name, description, codes
+const RANK: &str = "bm25(item_search, 0.0, 10.0, 1.0, 5.0)";
+
+pub struct SearchItemsCommand {
+    pub query: String,
+    pub category_id: Option<DbUuid>,
+    pub limit: Option<i32>,
+}
+
+impl Command for SearchItemsCommand {
+    type Output = Vec<Item>;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let Some(fts_query) = fts_query(&self.query) else {
+            return Ok(Vec::new());
+        };
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let item_search = Alias::new("item_search");
+
+        let mut query = Query::select();
+        query
+            .columns(Items::all_columns().into_iter().map(|column| (Items::Table, column)))
+            .from(item_search.clone())
+            .inner_join(
+                Items::Table,
+                Expr::col((Items::Table, Items::Id)).equals((item_search, Alias::new("item_id"))),
+            )
+            .and_where(Expr::cust_with_values("item_search MATCH ?", [fts_query]))
+            .and_where(Expr::col((Items::Table, Items::State)).ne(ItemState::Deleted))
+            .and_where(Expr::col((Items::Table, Items::DeletedAt)).is_null())
+            .order_by_expr(Expr::cust(RANK), Order::Asc)
+            .limit(limit as u64);
+
+        if let Some(category_id) = self.category_id {
+            query.and_where(Expr::col((Items::Table, Items::CategoryId)).eq(category_id.to_string()));
+        }
+
+        service.db_adapter.query_many::<Item>(&query).await
+    }
+}
+
+// Quotes every word of what was typed as an FTS5 prefix query, so that input such as `"`,
+// `-` or `OR` is searched for rather than read as query syntax
+fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        commands::{
+            catalog::{
+                item_commands::{CreateItemCommand, DeleteItemCommand, UpdateItemCommand},
+                item_group_commands::CreateItemGroupCommand,
+                item_variant_commands::{CreateItemVariantCommand, GetItemVariantByBarcodeCommand},
+            },
+            tests::setup_service,
+        },
+        models::catalog::{
+            item_group_model::ItemCategoryNew,
+            item_model::{ItemNature, NewItem, UpdateItem},
+            item_variant_model::ItemVariantNewInput,
+        },
+        types::money::Money,
+    };
+
+    async fn create_category(service: &AppService, name: &str) -> DbUuid {
+        let command = CreateItemGroupCommand {
+            category: ItemCategoryNew { name: name.to_string(), description: None },
+        };
+        command.exec(service).await.unwrap().id
+    }
+
+    async fn create_item(service: &AppService, category_id: DbUuid, name: &str, description: &str) -> Item {
+        let command = CreateItemCommand {
+            item: NewItem {
+                name: name.to_string(),
+                description: Some(description.to_string()),
+                nature: ItemNature::Goods,
+                state: ItemState::Active,
+                price: Money::from(1000),
+                category_id,
+                tax_ids: None,
+            },
+        };
+        command.exec(service).await.unwrap()
+    }
+
+    async fn search(service: &AppService, query: &str, category_id: Option<DbUuid>) -> Vec<String> {
+        let command = SearchItemsCommand { query: query.to_string(), category_id, limit: None };
+        command.exec(service).await.unwrap().into_iter().map(|item| item.name).collect()
+    }
+
+    #[test]
+    fn test_fts_query_quotes_input() {
+        assert_eq!(fts_query("coca col").as_deref(), Some("\"coca\"* \"col\"*"));
+        assert_eq!(fts_query("12\" OR pizza").as_deref(), Some("\"12\"\"\"* \"OR\"* \"pizza\"*"));
+        assert_eq!(fts_query("   "), None);
+    }
+
+    #[tokio::test]
+    async fn test_search_ranks_name_matches_first() {
+        let service = setup_service().await;
+        let drinks = create_category(&service, "Drinks").await;
+        create_item(&service, drinks, "Lemon Soda", "Fizzy drink with a hint of cola").await;
+        create_item(&service, drinks, "Coca Cola", "Classic cola").await;
+        create_item(&service, drinks, "Orange Juice", "Fresh").await;
+
+        assert_eq!(search(&service, "cola", None).await, ["Coca Cola", "Lemon Soda"]);
+        assert_eq!(search(&service, "coc co", None).await, ["Coca Cola"]);
+        assert!(search(&service, "\"", None).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_by_category_and_codes() {
+        let service = setup_service().await;
+        let drinks = create_category(&service, "Drinks").await;
+        let snacks = create_category(&service, "Snacks").await;
+        let cola = create_item(&service, drinks, "Cola", "Can").await;
+        create_item(&service, snacks, "Cola Gummies", "Sweets").await;
+
+        let command = CreateItemVariantCommand {
+            item_variant: ItemVariantNewInput {
+                item_id: cola.id,
+                sku: Some("COLA-330".to_string()),
+                barcode: Some("5449000000996".to_string()),
+                price_adjustment: None,
+                is_default: Some(true),
+                variant_value_ids: vec![],
+            },
+        };
+        command.exec(&service).await.unwrap();
+
+        assert_eq!(search(&service, "cola", Some(snacks)).await, ["Cola Gummies"]);
+        assert_eq!(search(&service, "5449000000996", None).await, ["Cola"]);
+        assert_eq!(search(&service, "cola-33", None).await, ["Cola"]);
+    }
+
+    #[tokio::test]
+    async fn test_barcode_lookup_skips_deleted_items() {
+        let service = setup_service().await;
+        let drinks = create_category(&service, "Drinks").await;
+        let cola = create_item(&service, drinks, "Cola", "Can").await;
+
+        let command = CreateItemVariantCommand {
+            item_variant: ItemVariantNewInput {
+                item_id: cola.id,
+                sku: None,
+                barcode: Some("5449000000996".to_string()),
+                price_adjustment: None,
+                is_default: Some(true),
+                variant_value_ids: vec![],
+            },
+        };
+        let variant = command.exec(&service).await.unwrap();
+
+        let lookup = GetItemVariantByBarcodeCommand { barcode: "5449000000996".to_string() };
+        assert_eq!(lookup.exec(&service).await.unwrap().map(|found| found.id), Some(variant.id));
+
+        DeleteItemCommand { id: cola.id, deleted_by: None }.exec(&service).await.unwrap();
+        assert!(lookup.exec(&service).await.unwrap().is_none());
+        assert!(search(&service, "5449000000996", None).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_follows_item_changes() {
+        let service = setup_service().await;
+        let drinks = create_category(&service, "Drinks").await;
+        let item = create_item(&service, drinks, "Iced Tea", "Peach").await;
+
+        let command = UpdateItemCommand {
+            item: UpdateItem {
+                id: item.id,
+                version: item.version,
+                name: Some("Iced Coffee".to_string()),
+                description: None,
+                nature: None,
+                state: None,
+                price: None,
+                category_id: None,
+            },
+        };
+        command.exec(&service).await.unwrap();
+        assert!(search(&service, "tea", None).await.is_empty());
+        assert_eq!(search(&service, "coffee", None).await, ["Iced Coffee"]);
+
+        DeleteItemCommand { id: item.id, deleted_by: None }.exec(&service).await.unwrap();
+        assert!(search(&service, "coffee", None).await.is_empty());
+    }
+}
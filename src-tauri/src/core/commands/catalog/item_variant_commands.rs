@@ -8,7 +8,7 @@ use crate::{
     core::{
         commands::{app_service::AppService, Command},
         models::catalog::{
-            item_model::{Item, Items}, item_variant_model::{ItemVariant, ItemVariantNewInput, ItemVariantUpdateInput, ItemVariants}, item_variant_value_model::{ItemVariantValue, ItemVariantValues}, variant_value_model::{VariantValue, VariantValues}
+            item_model::{Item, ItemState, Items}, item_variant_model::{ItemVariant, ItemVariantNewInput, ItemVariantUpdateInput, ItemVariants}, item_variant_value_model::{ItemVariantValue, ItemVariantValues}, variant_value_model::{VariantValue, VariantValues}
         },
         types::db_uuid::DbUuid,
     }, error::{Error, Result}
@@ -31,6 +31,11 @@ pub struct GetItemVariantCommand {
     pub id: DbUuid,
 }
 
+/// Looks up the variant a scanned barcode belongs to
+pub struct GetItemVariantByBarcodeCommand {
+    pub barcode: String,
+}
+
 pub struct ListItemVariantsCommand {
     pub item_id: Option<DbUuid>,
 }
@@ -49,7 +54,7 @@ pub struct RemoveVariantValueCommand {
 impl Command for CreateItemVariantCommand {
     type Output = ItemVariant;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Verify item exists
         let mut item_query = Query::select();
         let item_stmt = item_query
@@ -128,6 +133,7 @@ impl Command for CreateItemVariantCommand {
             id: variant_id,
             item_id: self.item_variant.item_id,
             sku: self.item_variant.sku.clone(),
+            barcode: self.item_variant.barcode.clone(),
             price_adjustment: self.item_variant.price_adjustment,
             is_default,
             created_at: now,
@@ -142,6 +148,7 @@ impl Command for CreateItemVariantCommand {
                 ItemVariants::Id,
                 ItemVariants::ItemId,
                 ItemVariants::Sku,
+                ItemVariants::Barcode,
                 ItemVariants::PriceAdjustment,
                 ItemVariants::IsDefault,
                 ItemVariants::CreatedAt,
@@ -150,8 +157,9 @@ impl Command for CreateItemVariantCommand {
             .values_panic([
                 new_item_variant.id.to_string().into(),
                 new_item_variant.item_id.to_string().into(),
-                new_item_variant.sku.clone().map_or_else(|| "NULL".into(), |s| s.into()),
-                new_item_variant.price_adjustment.map_or_else(|| "NULL".into(), |p| p.to_base_unit().into()),
+                new_item_variant.sku.clone().into(),
+                new_item_variant.barcode.clone().into(),
+                new_item_variant.price_adjustment.map(|p| p.to_base_unit()).into(),
                 new_item_variant.is_default.to_string().into(),
                 new_item_variant.created_at.to_string().into(),
                 new_item_variant.updated_at.to_string().into(),
@@ -185,7 +193,7 @@ impl Command for CreateItemVariantCommand {
 impl Command for UpdateItemVariantCommand {
     type Output = ItemVariant;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Verify item variant exists
         let mut variant_query = Query::select();
         let variant_stmt = variant_query
@@ -194,6 +202,7 @@ impl Command for UpdateItemVariantCommand {
                 ItemVariants::Id,
                 ItemVariants::ItemId,
                 ItemVariants::Sku,
+                ItemVariants::Barcode,
                 ItemVariants::PriceAdjustment,
                 ItemVariants::IsDefault,
                 ItemVariants::CreatedAt,
@@ -220,14 +229,18 @@ impl Command for UpdateItemVariantCommand {
         if let Some(sku) = &self.item_variant.sku {
             match sku {
                 Some(s) => update_stmt = update_stmt.value(ItemVariants::Sku, s.clone()),
-                None => update_stmt = update_stmt.value(ItemVariants::Sku, "NULL"),
+                None => update_stmt = update_stmt.value(ItemVariants::Sku, Option::<String>::None),
             };
         }
 
+        if let Some(barcode) = &self.item_variant.barcode {
+            update_stmt = update_stmt.value(ItemVariants::Barcode, barcode.clone());
+        }
+
         if let Some(price_adjustment) = &self.item_variant.price_adjustment {
             match price_adjustment {
                 Some(p) => update_stmt = update_stmt.value(ItemVariants::PriceAdjustment, p.to_base_unit()),
-                None => update_stmt = update_stmt.value(ItemVariants::PriceAdjustment, "NULL"),
+                None => update_stmt = update_stmt.value(ItemVariants::PriceAdjustment, Option::<i64>::None),
             };
         }
 
@@ -260,6 +273,7 @@ impl Command for UpdateItemVariantCommand {
                 ItemVariants::Id,
                 ItemVariants::ItemId,
                 ItemVariants::Sku,
+                ItemVariants::Barcode,
                 ItemVariants::PriceAdjustment,
                 ItemVariants::IsDefault,
                 ItemVariants::CreatedAt,
@@ -275,7 +289,7 @@ impl Command for UpdateItemVariantCommand {
 impl Command for DeleteItemVariantCommand {
     type Output = usize;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Get the variant to check if it's default and get its item_id
         let mut variant_query = Query::select();
         let variant_stmt = variant_query
@@ -284,6 +298,7 @@ impl Command for DeleteItemVariantCommand {
                 ItemVariants::Id,
                 ItemVariants::ItemId,
                 ItemVariants::Sku,
+                ItemVariants::Barcode,
                 ItemVariants::PriceAdjustment,
                 ItemVariants::IsDefault,
                 ItemVariants::CreatedAt,
@@ -324,6 +339,7 @@ impl Command for DeleteItemVariantCommand {
                     ItemVariants::Id,
                     ItemVariants::ItemId,
                     ItemVariants::Sku,
+                    ItemVariants::Barcode,
                     ItemVariants::PriceAdjustment,
                     ItemVariants::IsDefault,
                     ItemVariants::CreatedAt,
@@ -357,7 +373,7 @@ impl Command for DeleteItemVariantCommand {
 impl Command for GetItemVariantCommand {
     type Output = ItemVariant;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let mut query = Query::select();
         let stmt = query
             .from(ItemVariants::Table)
@@ -365,6 +381,7 @@ impl Command for GetItemVariantCommand {
                 ItemVariants::Id,
                 ItemVariants::ItemId,
                 ItemVariants::Sku,
+                ItemVariants::Barcode,
                 ItemVariants::PriceAdjustment,
                 ItemVariants::IsDefault,
                 ItemVariants::CreatedAt,
@@ -380,10 +397,42 @@ impl Command for GetItemVariantCommand {
     }
 }
 
+impl Command for GetItemVariantByBarcodeCommand {
+    type Output = Option<ItemVariant>;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let mut query = Query::select();
+        let stmt = query
+            .from(ItemVariants::Table)
+            .columns(
+                [
+                    ItemVariants::Id,
+                    ItemVariants::ItemId,
+                    ItemVariants::Sku,
+                    ItemVariants::Barcode,
+                    ItemVariants::PriceAdjustment,
+                    ItemVariants::IsDefault,
+                    ItemVariants::CreatedAt,
+                    ItemVariants::UpdatedAt,
+                ]
+                .map(|column| (ItemVariants::Table, column)),
+            )
+            .inner_join(
+                Items::Table,
+                Expr::col((Items::Table, Items::Id)).equals((ItemVariants::Table, ItemVariants::ItemId)),
+            )
+            .and_where(Expr::col((ItemVariants::Table, ItemVariants::Barcode)).eq(self.barcode.trim()))
+            .and_where(Expr::col((Items::Table, Items::State)).ne(ItemState::Deleted))
+            .and_where(Expr::col((Items::Table, Items::DeletedAt)).is_null());
+
+        service.db_adapter.query_optional::<ItemVariant>(&stmt).await
+    }
+}
+
 impl Command for ListItemVariantsCommand {
     type Output = Vec<ItemVariant>;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let mut query_builder = Query::select();
         let mut query = query_builder
             .from(ItemVariants::Table)
@@ -391,6 +440,7 @@ impl Command for ListItemVariantsCommand {
                 ItemVariants::Id,
                 ItemVariants::ItemId,
                 ItemVariants::Sku,
+                ItemVariants::Barcode,
                 ItemVariants::PriceAdjustment,
                 ItemVariants::IsDefault,
                 ItemVariants::CreatedAt,
@@ -409,7 +459,7 @@ impl Command for ListItemVariantsCommand {
 impl Command for AssignVariantValueCommand {
     type Output = usize;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Verify item variant exists
         let mut variant_query = Query::select();
         let variant_stmt = variant_query
@@ -511,7 +561,7 @@ impl Command for AssignVariantValueCommand {
 impl Command for RemoveVariantValueCommand {
     type Output = usize;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Delete the association
         let mut delete_query = Query::delete();
         let delete_stmt = delete_query
@@ -543,7 +593,7 @@ mod tests {
     use sea_query::{Expr, Query};
     use tokio;
 
-    async fn create_test_item_category(service: &mut AppService) -> DbUuid {
+    async fn create_test_item_category(service: &AppService) -> DbUuid {
         let command = CreateItemGroupCommand {
             category: ItemCategoryNew {
                 name: "Test Category".to_string(),
@@ -554,7 +604,7 @@ mod tests {
         category.id
     }
 
-    async fn create_test_item(service: &mut AppService) -> Item {
+    async fn create_test_item(service: &AppService) -> Item {
         let category_id = create_test_item_category(service).await;
         let command = CreateItemCommand {
             item: NewItem {
@@ -571,7 +621,7 @@ mod tests {
     }
 
     async fn create_test_variant_type(
-        service: &mut AppService,
+        service: &AppService,
     ) -> crate::core::models::catalog::variant_type_model::VariantType {
         let command = CreateVariantTypeCommand {
             variant_type: VariantTypeNewInput {
@@ -583,7 +633,7 @@ mod tests {
     }
 
     async fn create_test_variant_value(
-        service: &mut AppService,
+        service: &AppService,
         variant_type_id: DbUuid,
     ) -> VariantValue {
         let command = CreateVariantValueCommand {
@@ -596,7 +646,7 @@ mod tests {
         command.exec(service).await.unwrap()
     }
 
-    async fn create_test_item_variant(service: &mut AppService) -> ItemVariant {
+    async fn create_test_item_variant(service: &AppService) -> ItemVariant {
         let item = create_test_item(service).await;
         let variant_type = create_test_variant_type(service).await;
         let variant_value = create_test_variant_value(service, variant_type.id).await;
@@ -605,6 +655,7 @@ mod tests {
             item_variant: ItemVariantNewInput {
                 item_id: item.id,
                 sku: Some("TEST-SKU-001".to_string()),
+                barcode: Some("4006381333931".to_string()),
                 price_adjustment: Some(Money::from(100)),
                 is_default: Some(true),
                 variant_value_ids: vec![variant_value.id],
@@ -615,22 +666,23 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_item_variant() {
-        let mut service = setup_service().await;
-        let item = create_test_item(&mut service).await;
-        let variant_type = create_test_variant_type(&mut service).await;
-        let variant_value = create_test_variant_value(&mut service, variant_type.id).await;
+        let service = setup_service().await;
+        let item = create_test_item(&service).await;
+        let variant_type = create_test_variant_type(&service).await;
+        let variant_value = create_test_variant_value(&service, variant_type.id).await;
 
         let command = CreateItemVariantCommand {
             item_variant: ItemVariantNewInput {
                 item_id: item.id,
                 sku: Some("TEST-SKU-001".to_string()),
+                barcode: None,
                 price_adjustment: Some(Money::from(100)),
                 is_default: Some(true),
                 variant_value_ids: vec![variant_value.id],
             },
         };
 
-        let item_variant = command.exec(&mut service).await.unwrap();
+        let item_variant = command.exec(&service).await.unwrap();
         assert_eq!(item_variant.item_id, item.id);
         assert_eq!(item_variant.sku, Some("TEST-SKU-001".to_string()));
         assert_eq!(item_variant.price_adjustment, Some(Money::from(100)));
@@ -654,20 +706,21 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_item_variant() {
-        let mut service = setup_service().await;
-        let created = create_test_item_variant(&mut service).await;
+        let service = setup_service().await;
+        let created = create_test_item_variant(&service).await;
 
         let update_command = UpdateItemVariantCommand {
             item_variant: ItemVariantUpdateInput {
                 id: created.id,
                 sku: Some(Some("UPDATED-SKU".to_string())),
+                barcode: None,
                 price_adjustment: Some(Some(Money::from(200))),
                 is_default: Some(true),
                 updated_at: None,
             },
         };
 
-        let updated = update_command.exec(&mut service).await.unwrap();
+        let updated = update_command.exec(&service).await.unwrap();
         assert_eq!(updated.sku, Some("UPDATED-SKU".to_string()));
         assert_eq!(updated.price_adjustment, Some(Money::from(200)));
         assert!(updated.is_default);
@@ -675,56 +728,90 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_item_variant() {
-        let mut service = setup_service().await;
-        let created = create_test_item_variant(&mut service).await;
+        let service = setup_service().await;
+        let created = create_test_item_variant(&service).await;
 
         let get_command = GetItemVariantCommand { id: created.id };
-        let retrieved = get_command.exec(&mut service).await.unwrap();
+        let retrieved = get_command.exec(&service).await.unwrap();
         assert_eq!(retrieved.id, created.id);
         assert_eq!(retrieved.sku, created.sku);
     }
 
+    #[tokio::test]
+    async fn test_get_item_variant_by_barcode() {
+        let service = setup_service().await;
+        let created = create_test_item_variant(&service).await;
+
+        let command = GetItemVariantByBarcodeCommand { barcode: " 4006381333931\n".to_string() };
+        let found = command.exec(&service).await.unwrap();
+        assert_eq!(found.map(|variant| variant.id), Some(created.id));
+
+        let command = GetItemVariantByBarcodeCommand { barcode: "0000000000000".to_string() };
+        assert!(command.exec(&service).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_barcodes_are_unique() {
+        let service = setup_service().await;
+        let created = create_test_item_variant(&service).await;
+
+        let command = CreateItemVariantCommand {
+            item_variant: ItemVariantNewInput {
+                item_id: created.item_id,
+                sku: None,
+                barcode: created.barcode.clone(),
+                price_adjustment: None,
+                is_default: Some(false),
+                variant_value_ids: vec![],
+            },
+        };
+        let result = command.exec(&service).await;
+        assert!(matches!(result, Err(Error::UniqueConstraintError(_))));
+    }
+
     #[tokio::test]
     async fn test_list_item_variants() {
-        let mut service = setup_service().await;
-        let item = create_test_item(&mut service).await;
-        let variant_type = create_test_variant_type(&mut service).await;
-        let variant_value1 = create_test_variant_value(&mut service, variant_type.id).await;
-        let variant_value2 = create_test_variant_value(&mut service, variant_type.id).await;
+        let service = setup_service().await;
+        let item = create_test_item(&service).await;
+        let variant_type = create_test_variant_type(&service).await;
+        let variant_value1 = create_test_variant_value(&service, variant_type.id).await;
+        let variant_value2 = create_test_variant_value(&service, variant_type.id).await;
 
         // Create multiple variants for the same item
         let command1 = CreateItemVariantCommand {
             item_variant: ItemVariantNewInput {
                 item_id: item.id,
                 sku: Some("SKU-1".to_string()),
+                barcode: None,
                 price_adjustment: Some(Money::from(100)),
                 is_default: Some(true),
                 variant_value_ids: vec![variant_value1.id],
             },
         };
-        let variant1 = command1.exec(&mut service).await.unwrap();
+        let variant1 = command1.exec(&service).await.unwrap();
 
         let command2 = CreateItemVariantCommand {
             item_variant: ItemVariantNewInput {
                 item_id: item.id,
                 sku: Some("SKU-2".to_string()),
+                barcode: None,
                 price_adjustment: Some(Money::from(200)),
                 is_default: Some(false),
                 variant_value_ids: vec![variant_value2.id],
             },
         };
-        let variant2 = command2.exec(&mut service).await.unwrap();
+        let variant2 = command2.exec(&service).await.unwrap();
 
         // List all variants
         let list_command = ListItemVariantsCommand { item_id: None };
-        let all_variants = list_command.exec(&mut service).await.unwrap();
+        let all_variants = list_command.exec(&service).await.unwrap();
         assert!(all_variants.len() >= 2);
 
         // List variants for specific item
         let list_command = ListItemVariantsCommand {
             item_id: Some(item.id),
         };
-        let item_variants = list_command.exec(&mut service).await.unwrap();
+        let item_variants = list_command.exec(&service).await.unwrap();
         assert_eq!(item_variants.len(), 2);
         assert!(item_variants.iter().any(|v| v.id == variant1.id));
         assert!(item_variants.iter().any(|v| v.id == variant2.id));
@@ -732,16 +819,16 @@ mod tests {
 
     #[tokio::test]
     async fn test_delete_item_variant() {
-        let mut service = setup_service().await;
-        let created = create_test_item_variant(&mut service).await;
+        let service = setup_service().await;
+        let created = create_test_item_variant(&service).await;
 
         let delete_command = DeleteItemVariantCommand { id: created.id };
-        let result = delete_command.exec(&mut service).await.unwrap();
+        let result = delete_command.exec(&service).await.unwrap();
         assert_eq!(result, 1);
 
         // Verify it's gone
         let get_command = GetItemVariantCommand { id: created.id };
-        let result = get_command.exec(&mut service).await;
+        let result = get_command.exec(&service).await;
         assert!(result.is_err());
 
         // Verify associations are gone
@@ -761,17 +848,17 @@ mod tests {
 
     #[tokio::test]
     async fn test_assign_and_remove_variant_value() {
-        let mut service = setup_service().await;
-        let item_variant = create_test_item_variant(&mut service).await;
-        let variant_type = create_test_variant_type(&mut service).await;
-        let new_variant_value = create_test_variant_value(&mut service, variant_type.id).await;
+        let service = setup_service().await;
+        let item_variant = create_test_item_variant(&service).await;
+        let variant_type = create_test_variant_type(&service).await;
+        let new_variant_value = create_test_variant_value(&service, variant_type.id).await;
 
         // Assign new variant value
         let assign_command = AssignVariantValueCommand {
             item_variant_id: item_variant.id,
             variant_value_id: new_variant_value.id,
         };
-        let result = assign_command.exec(&mut service).await.unwrap();
+        let result = assign_command.exec(&service).await.unwrap();
         assert_eq!(result, 1);
 
         // Verify association exists
@@ -794,7 +881,7 @@ mod tests {
             item_variant_id: item_variant.id,
             variant_value_id: new_variant_value.id,
         };
-        let result = remove_command.exec(&mut service).await.unwrap();
+        let result = remove_command.exec(&service).await.unwrap();
         assert_eq!(result, 1);
 
         // Verify association is gone
@@ -815,17 +902,18 @@ mod tests {
 
     #[tokio::test]
     async fn test_prevent_duplicate_variant_types_in_create() {
-        let mut service = setup_service().await;
-        let item = create_test_item(&mut service).await;
-        let variant_type = create_test_variant_type(&mut service).await;
-        let variant_value1 = create_test_variant_value(&mut service, variant_type.id).await;
-        let variant_value2 = create_test_variant_value(&mut service, variant_type.id).await;
+        let service = setup_service().await;
+        let item = create_test_item(&service).await;
+        let variant_type = create_test_variant_type(&service).await;
+        let variant_value1 = create_test_variant_value(&service, variant_type.id).await;
+        let variant_value2 = create_test_variant_value(&service, variant_type.id).await;
 
         // Try to create a variant with two values from the same type
         let command = CreateItemVariantCommand {
             item_variant: ItemVariantNewInput {
                 item_id: item.id,
                 sku: Some("TEST-SKU-001".to_string()),
+                barcode: None,
                 price_adjustment: Some(Money::from(100)),
                 is_default: Some(true),
                 variant_value_ids: vec![variant_value1.id, variant_value2.id],
@@ -833,7 +921,7 @@ mod tests {
         };
 
         // This should fail with AlreadyExistsError
-        let result = command.exec(&mut service).await;
+        let result = command.exec(&service).await;
         assert!(result.is_err());
         match result {
             Err(Error::AlreadyExistsError) => {} // Expected error
@@ -843,12 +931,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_prevent_duplicate_variant_types_in_assign() {
-        let mut service = setup_service().await;
-        let item = create_test_item(&mut service).await;
+        let service = setup_service().await;
+        let item = create_test_item(&service).await;
         println!("Created item: {:?}", item);
-        let variant_type1 = create_test_variant_type(&mut service).await;
+        let variant_type1 = create_test_variant_type(&service).await;
         println!("Created variant type: {:?}", variant_type1);
-        let variant_value1 = create_test_variant_value(&mut service, variant_type1.id).await;
+        let variant_value1 = create_test_variant_value(&service, variant_type1.id).await;
         println!("Created variant value: {:?}", variant_value1);
 
         // Create a variant with one value
@@ -856,17 +944,18 @@ mod tests {
             item_variant: ItemVariantNewInput {
                 item_id: item.id,
                 sku: Some("TEST-SKU-001".to_string()),
+                barcode: None,
                 price_adjustment: Some(Money::from(100)),
                 is_default: Some(true),
                 variant_value_ids: vec![variant_value1.id],
             },
         };
 
-        let item_variant = command.exec(&mut service).await.unwrap();
+        let item_variant = command.exec(&service).await.unwrap();
         println!("Created item variant: {:?}", item_variant);
 
         // Try to assign another value from the same type
-        let variant_value2 = create_test_variant_value(&mut service, variant_type1.id).await;
+        let variant_value2 = create_test_variant_value(&service, variant_type1.id).await;
         println!("Created variant value 2: {:?}", variant_value2);
         let assign_command = AssignVariantValueCommand {
             item_variant_id: item_variant.id,
@@ -874,7 +963,7 @@ mod tests {
         };
 
         // This should fail with AlreadyExistsError
-        let result = assign_command.exec(&mut service).await;
+        let result = assign_command.exec(&service).await;
         println!("Result: {:?}", result);
         assert!(result.is_err());
         match result {
@@ -1,7 +1,11 @@
+pub mod catalog_import_commands;
 pub mod discount_commands;
 pub mod item_commands;
 pub mod item_discount_commands;
 pub mod item_group_commands;
+pub mod item_search_commands;
 pub mod item_variant_commands;
+pub mod price_list_commands;
+pub mod stock_movement_commands;
 pub mod variant_type_commands;
 pub mod variant_value_commands;
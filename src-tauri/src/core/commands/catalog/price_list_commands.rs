@@ -0,0 +1,908 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_query::{Cond, Expr, Query};
+use uuid::Uuid;
+
+use crate::{
+    adapters::outgoing::database::DatabaseAdapter,
+    core::{
+        commands::{app_service::AppService, Command},
+        models::{
+            catalog::{
+                item_model::{Item, Items},
+                item_variant_model::{ItemVariant, ItemVariants},
+                price_list_model::{
+                    PriceContext, PriceList, PriceListAssignment, PriceListAssignmentInput,
+                    PriceListAssignments, PriceListItem, PriceListItemInput, PriceListItems,
+                    PriceListNewInput, PriceListUpdateInput, PriceLists,
+                },
+            },
+            sales::customer_model::{Customer, Customers},
+        },
+        types::{db_uuid::DbUuid, money::Money},
+    },
+    error::{Constraint, Error, Result},
+};
+
+/// Builds the pricing context for an order from its channel, location and customer
+pub async fn price_context_for(
+    service: &AppService,
+    channel_id: Option<DbUuid>,
+    location_id: Option<DbUuid>,
+    customer_id: Option<DbUuid>,
+) -> Result<PriceContext> {
+    let customer_group_id = match customer_id {
+        Some(customer_id) => {
+            let mut customer_query = Query::select();
+            let customer_stmt = customer_query
+                .from(Customers::Table)
+                .columns(Customers::all_columns())
+                .and_where(Expr::col(Customers::Id).eq(customer_id.to_string()));
+
+            let customer = service.db_adapter.query_optional::<Customer>(&customer_stmt).await?;
+            customer.and_then(|customer| customer.customer_group_id)
+        }
+        None => None,
+    };
+
+    Ok(PriceContext {
+        channel_id,
+        location_id,
+        customer_group_id,
+    })
+}
+
+/// Resolves the price of `quantity` units of an item (or variant) in the given context.
+///
+/// See `price_list_model` for how competing price lists are ranked.
+pub async fn resolve_item_price(
+    service: &AppService,
+    item_id: DbUuid,
+    variant_id: Option<DbUuid>,
+    quantity: i32,
+    context: &PriceContext,
+    now: NaiveDateTime,
+) -> Result<Money> {
+    let db = &service.db_adapter;
+
+    let mut item_query = Query::select();
+    let item_stmt = item_query
+        .from(Items::Table)
+        .columns(Items::all_columns())
+        .and_where(Expr::col(Items::Id).eq(item_id.to_string()));
+
+    let item = db.query_optional::<Item>(&item_stmt).await?.ok_or(Error::NotFoundError)?;
+
+    let mut catalog_price = item.price;
+    if let Some(variant_id) = variant_id {
+        let mut variant_query = Query::select();
+        let variant_stmt = variant_query
+            .from(ItemVariants::Table)
+            .columns(ItemVariants::all_columns())
+            .and_where(Expr::col(ItemVariants::Id).eq(variant_id.to_string()))
+            .and_where(Expr::col(ItemVariants::ItemId).eq(item_id.to_string()));
+
+        let variant = db.query_optional::<ItemVariant>(&variant_stmt).await?.ok_or(Error::NotFoundError)?;
+        if let Some(adjustment) = variant.price_adjustment {
+            catalog_price = catalog_price + adjustment;
+        }
+    }
+
+    // Item wide prices and prices for this variant, up to the ordered quantity
+    let mut variant_cond = Cond::any().add(Expr::col(PriceListItems::VariantId).is_null());
+    if let Some(variant_id) = variant_id {
+        variant_cond = variant_cond.add(Expr::col(PriceListItems::VariantId).eq(variant_id.to_string()));
+    }
+
+    let mut price_query = Query::select();
+    let price_stmt = price_query
+        .from(PriceListItems::Table)
+        .columns(PriceListItems::all_columns())
+        .and_where(Expr::col(PriceListItems::ItemId).eq(item_id.to_string()))
+        .and_where(Expr::col(PriceListItems::MinQuantity).lte(quantity))
+        .cond_where(variant_cond);
+
+    let prices = db.query_many::<PriceListItem>(&price_stmt).await?;
+    if prices.is_empty() {
+        return Ok(catalog_price);
+    }
+
+    let mut list_ids: Vec<String> = prices.iter().map(|price| price.price_list_id.to_string()).collect();
+    list_ids.sort();
+    list_ids.dedup();
+
+    let mut list_query = Query::select();
+    let list_stmt = list_query
+        .from(PriceLists::Table)
+        .columns(PriceLists::all_columns())
+        .and_where(Expr::col(PriceLists::Id).is_in(list_ids.clone()));
+
+    let mut assignment_query = Query::select();
+    let assignment_stmt = assignment_query
+        .from(PriceListAssignments::Table)
+        .columns(PriceListAssignments::all_columns())
+        .and_where(Expr::col(PriceListAssignments::PriceListId).is_in(list_ids));
+
+    let assignments = db.query_many::<PriceListAssignment>(&assignment_stmt).await?;
+    let applicable_lists: Vec<PriceList> = db
+        .query_many::<PriceList>(&list_stmt)
+        .await?
+        .into_iter()
+        .filter(|list| list.is_valid_at(now))
+        .filter(|list| {
+            assignments
+                .iter()
+                .any(|assignment| assignment.price_list_id == list.id && assignment.matches(context))
+        })
+        .collect();
+
+    let best_price = prices
+        .iter()
+        .filter_map(|price| {
+            applicable_lists
+                .iter()
+                .find(|list| list.id == price.price_list_id)
+                .map(|list| ((list.priority, price.variant_id.is_some(), price.min_quantity), price.price))
+        })
+        .max_by_key(|(rank, _)| *rank)
+        .map(|(_, price)| price);
+
+    Ok(best_price.unwrap_or(catalog_price))
+}
+
+// Commands
+pub struct CreatePriceListCommand {
+    pub price_list: PriceListNewInput,
+}
+
+pub struct UpdatePriceListCommand {
+    pub price_list: PriceListUpdateInput,
+}
+
+pub struct DeletePriceListCommand {
+    pub id: DbUuid,
+}
+
+pub struct SetPriceListItemCommand {
+    pub price: PriceListItemInput,
+}
+
+pub struct RemovePriceListItemCommand {
+    pub id: DbUuid,
+}
+
+pub struct AssignPriceListCommand {
+    pub assignment: PriceListAssignmentInput,
+}
+
+pub struct UnassignPriceListCommand {
+    pub id: DbUuid,
+}
+
+pub struct ResolveItemPriceCommand {
+    pub item_id: DbUuid,
+    pub variant_id: Option<DbUuid>,
+    pub quantity: i32,
+    pub channel_id: Option<DbUuid>,
+    pub location_id: Option<DbUuid>,
+    pub customer_id: Option<DbUuid>,
+}
+
+fn validate_validity(valid_from: Option<NaiveDateTime>, valid_to: Option<NaiveDateTime>) -> Result<()> {
+    match (valid_from, valid_to) {
+        (Some(from), Some(to)) if from >= to => Err(Error::ValidationError(
+            "Price list must start before it ends".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Command Implementations
+impl Command for CreatePriceListCommand {
+    type Output = PriceList;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        validate_validity(self.price_list.valid_from, self.price_list.valid_to)?;
+
+        let mut check_query = Query::select();
+        let check_stmt = check_query
+            .from(PriceLists::Table)
+            .column(PriceLists::Id)
+            .and_where(Expr::col(PriceLists::Name).eq(self.price_list.name.clone()));
+
+        let existing = service.db_adapter.query_optional::<DbUuid>(&check_stmt).await?;
+        if existing.is_some() {
+            return Err(Error::UniqueConstraintError(Constraint::new("price_lists", "name")));
+        }
+
+        let now = Utc::now().naive_utc();
+        let new_price_list = PriceList {
+            id: Uuid::now_v7().into(),
+            name: self.price_list.name.clone(),
+            description: self.price_list.description.clone(),
+            priority: self.price_list.priority.unwrap_or(0),
+            valid_from: self.price_list.valid_from,
+            valid_to: self.price_list.valid_to,
+            is_active: self.price_list.is_active.unwrap_or(true),
+            created_at: now,
+            updated_at: now,
+        };
+
+        // Build the insert query with SeaQuery
+        let mut insert_query = Query::insert();
+        let insert_stmt = insert_query
+            .into_table(PriceLists::Table)
+            .columns([
+                PriceLists::Id,
+                PriceLists::Name,
+                PriceLists::Description,
+                PriceLists::Priority,
+                PriceLists::ValidFrom,
+                PriceLists::ValidTo,
+                PriceLists::IsActive,
+                PriceLists::CreatedAt,
+                PriceLists::UpdatedAt,
+            ])
+            .values_panic([
+                new_price_list.id.to_string().into(),
+                new_price_list.name.clone().into(),
+                new_price_list.description.clone().into(),
+                new_price_list.priority.into(),
+                new_price_list.valid_from.map(|d| d.to_string()).into(),
+                new_price_list.valid_to.map(|d| d.to_string()).into(),
+                new_price_list.is_active.to_string().into(),
+                now.to_string().into(),
+                now.to_string().into(),
+            ]);
+
+        service.db_adapter.insert_many(&insert_stmt).await?;
+
+        Ok(new_price_list)
+    }
+}
+
+impl Command for UpdatePriceListCommand {
+    type Output = PriceList;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let now = Utc::now().naive_utc();
+        let price_list_id = self.price_list.id;
+
+        // First, check if the price list exists
+        let mut select_query = Query::select();
+        let select_stmt = select_query
+            .from(PriceLists::Table)
+            .columns(PriceLists::all_columns())
+            .and_where(Expr::col(PriceLists::Id).eq(price_list_id.to_string()));
+
+        let existing = service
+            .db_adapter
+            .query_optional::<PriceList>(&select_stmt)
+            .await?
+            .ok_or(Error::NotFoundError)?;
+
+        validate_validity(
+            self.price_list.valid_from.unwrap_or(existing.valid_from),
+            self.price_list.valid_to.unwrap_or(existing.valid_to),
+        )?;
+
+        // Build the update query with SeaQuery
+        let mut update_query = Query::update();
+        let update_stmt = update_query.table(PriceLists::Table);
+
+        if let Some(name) = &self.price_list.name {
+            update_stmt.value(PriceLists::Name, name.clone());
+        }
+
+        if let Some(description) = &self.price_list.description {
+            match description {
+                Some(desc) => update_stmt.value(PriceLists::Description, desc.clone()),
+                None => update_stmt.value(PriceLists::Description, sea_query::Value::String(None)),
+            };
+        }
+
+        if let Some(priority) = self.price_list.priority {
+            update_stmt.value(PriceLists::Priority, priority);
+        }
+
+        if let Some(valid_from) = &self.price_list.valid_from {
+            match valid_from {
+                Some(date) => update_stmt.value(PriceLists::ValidFrom, date.to_string()),
+                None => update_stmt.value(PriceLists::ValidFrom, sea_query::Value::String(None)),
+            };
+        }
+
+        if let Some(valid_to) = &self.price_list.valid_to {
+            match valid_to {
+                Some(date) => update_stmt.value(PriceLists::ValidTo, date.to_string()),
+                None => update_stmt.value(PriceLists::ValidTo, sea_query::Value::String(None)),
+            };
+        }
+
+        if let Some(is_active) = self.price_list.is_active {
+            update_stmt.value(PriceLists::IsActive, is_active.to_string());
+        }
+
+        // Always update the updated_at timestamp
+        update_stmt.value(PriceLists::UpdatedAt, now.to_string());
+
+        update_stmt.and_where(Expr::col(PriceLists::Id).eq(price_list_id.to_string()));
+
+        service.db_adapter.update_one::<PriceList>(&update_stmt).await?;
+
+        let updated_price_list = service.db_adapter.query_one::<PriceList>(&select_stmt).await?;
+
+        Ok(updated_price_list)
+    }
+}
+
+impl Command for DeletePriceListCommand {
+    type Output = bool;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        // Prices and assignments go with the list through ON DELETE CASCADE
+        let mut delete_query = Query::delete();
+        let delete_stmt = delete_query
+            .from_table(PriceLists::Table)
+            .and_where(Expr::col(PriceLists::Id).eq(self.id.to_string()));
+
+        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+
+        Ok(affected_rows > 0)
+    }
+}
+
+impl Command for SetPriceListItemCommand {
+    type Output = PriceListItem;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let min_quantity = self.price.min_quantity.unwrap_or(1);
+        if min_quantity < 1 {
+            return Err(Error::ValidationError("Minimum quantity must be at least 1".to_string()));
+        }
+
+        let mut list_query = Query::select();
+        let list_stmt = list_query
+            .from(PriceLists::Table)
+            .column(PriceLists::Id)
+            .and_where(Expr::col(PriceLists::Id).eq(self.price.price_list_id.to_string()));
+
+        if service.db_adapter.query_optional::<DbUuid>(&list_stmt).await?.is_none() {
+            return Err(Error::NotFoundError);
+        }
+
+        // The variant, when given, has to belong to the item
+        let mut item_query = Query::select();
+        let item_stmt = match self.price.variant_id {
+            Some(variant_id) => item_query
+                .from(ItemVariants::Table)
+                .column(ItemVariants::Id)
+                .and_where(Expr::col(ItemVariants::Id).eq(variant_id.to_string()))
+                .and_where(Expr::col(ItemVariants::ItemId).eq(self.price.item_id.to_string())),
+            None => item_query
+                .from(Items::Table)
+                .column(Items::Id)
+                .and_where(Expr::col(Items::Id).eq(self.price.item_id.to_string())),
+        };
+
+        if service.db_adapter.query_optional::<DbUuid>(&item_stmt).await?.is_none() {
+            return Err(Error::NotFoundError);
+        }
+
+        // Setting the same tier twice replaces its price
+        let mut existing_query = Query::select();
+        let existing_stmt = existing_query
+            .from(PriceListItems::Table)
+            .columns(PriceListItems::all_columns())
+            .and_where(Expr::col(PriceListItems::PriceListId).eq(self.price.price_list_id.to_string()))
+            .and_where(Expr::col(PriceListItems::ItemId).eq(self.price.item_id.to_string()))
+            .and_where(Expr::col(PriceListItems::MinQuantity).eq(min_quantity));
+
+        match self.price.variant_id {
+            Some(variant_id) => existing_stmt.and_where(Expr::col(PriceListItems::VariantId).eq(variant_id.to_string())),
+            None => existing_stmt.and_where(Expr::col(PriceListItems::VariantId).is_null()),
+        };
+
+        let now = Utc::now().naive_utc();
+        let existing = service.db_adapter.query_optional::<PriceListItem>(&existing_stmt).await?;
+
+        if let Some(existing) = existing {
+            let mut update_query = Query::update();
+            let update_stmt = update_query
+                .table(PriceListItems::Table)
+                .value(PriceListItems::Price, self.price.price.to_base_unit())
+                .value(PriceListItems::UpdatedAt, now.to_string())
+                .and_where(Expr::col(PriceListItems::Id).eq(existing.id.to_string()));
+
+            service.db_adapter.update_many(&update_stmt).await?;
+
+            return Ok(PriceListItem {
+                price: self.price.price,
+                updated_at: now,
+                ..existing
+            });
+        }
+
+        let new_price = PriceListItem {
+            id: Uuid::now_v7().into(),
+            price_list_id: self.price.price_list_id,
+            item_id: self.price.item_id,
+            variant_id: self.price.variant_id,
+            min_quantity,
+            price: self.price.price,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let mut insert_query = Query::insert();
+        let insert_stmt = insert_query
+            .into_table(PriceListItems::Table)
+            .columns([
+                PriceListItems::Id,
+                PriceListItems::PriceListId,
+                PriceListItems::ItemId,
+                PriceListItems::VariantId,
+                PriceListItems::MinQuantity,
+                PriceListItems::Price,
+                PriceListItems::CreatedAt,
+                PriceListItems::UpdatedAt,
+            ])
+            .values_panic([
+                new_price.id.to_string().into(),
+                new_price.price_list_id.to_string().into(),
+                new_price.item_id.to_string().into(),
+                new_price.variant_id.map(|id| id.to_string()).into(),
+                new_price.min_quantity.into(),
+                new_price.price.to_base_unit().into(),
+                now.to_string().into(),
+                now.to_string().into(),
+            ]);
+
+        service.db_adapter.insert_many(&insert_stmt).await?;
+
+        Ok(new_price)
+    }
+}
+
+impl Command for RemovePriceListItemCommand {
+    type Output = bool;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let mut delete_query = Query::delete();
+        let delete_stmt = delete_query
+            .from_table(PriceListItems::Table)
+            .and_where(Expr::col(PriceListItems::Id).eq(self.id.to_string()));
+
+        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+
+        Ok(affected_rows > 0)
+    }
+}
+
+impl Command for AssignPriceListCommand {
+    type Output = PriceListAssignment;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let assignment = &self.assignment;
+        if assignment.channel_id.is_none() && assignment.location_id.is_none() && assignment.customer_group_id.is_none() {
+            return Err(Error::ValidationError(
+                "Assign the price list to a channel, location or customer group".to_string(),
+            ));
+        }
+
+        let mut list_query = Query::select();
+        let list_stmt = list_query
+            .from(PriceLists::Table)
+            .column(PriceLists::Id)
+            .and_where(Expr::col(PriceLists::Id).eq(assignment.price_list_id.to_string()));
+
+        if service.db_adapter.query_optional::<DbUuid>(&list_stmt).await?.is_none() {
+            return Err(Error::NotFoundError);
+        }
+
+        let now = Utc::now().naive_utc();
+        let new_assignment = PriceListAssignment {
+            id: Uuid::now_v7().into(),
+            price_list_id: assignment.price_list_id,
+            channel_id: assignment.channel_id,
+            location_id: assignment.location_id,
+            customer_group_id: assignment.customer_group_id,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let mut insert_query = Query::insert();
+        let insert_stmt = insert_query
+            .into_table(PriceListAssignments::Table)
+            .columns([
+                PriceListAssignments::Id,
+                PriceListAssignments::PriceListId,
+                PriceListAssignments::ChannelId,
+                PriceListAssignments::LocationId,
+                PriceListAssignments::CustomerGroupId,
+                PriceListAssignments::CreatedAt,
+                PriceListAssignments::UpdatedAt,
+            ])
+            .values_panic([
+                new_assignment.id.to_string().into(),
+                new_assignment.price_list_id.to_string().into(),
+                new_assignment.channel_id.map(|id| id.to_string()).into(),
+                new_assignment.location_id.map(|id| id.to_string()).into(),
+                new_assignment.customer_group_id.map(|id| id.to_string()).into(),
+                now.to_string().into(),
+                now.to_string().into(),
+            ]);
+
+        service.db_adapter.insert_many(&insert_stmt).await?;
+
+        Ok(new_assignment)
+    }
+}
+
+impl Command for UnassignPriceListCommand {
+    type Output = bool;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let mut delete_query = Query::delete();
+        let delete_stmt = delete_query
+            .from_table(PriceListAssignments::Table)
+            .and_where(Expr::col(PriceListAssignments::Id).eq(self.id.to_string()));
+
+        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+
+        Ok(affected_rows > 0)
+    }
+}
+
+impl Command for ResolveItemPriceCommand {
+    type Output = Money;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let context = price_context_for(service, self.channel_id, self.location_id, self.customer_id).await?;
+        let now = Utc::now().naive_utc();
+
+        resolve_item_price(service, self.item_id, self.variant_id, self.quantity, &context, now).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        commands::{
+            catalog::{
+                item_commands::CreateItemCommand, item_group_commands::CreateItemGroupCommand,
+                item_variant_commands::CreateItemVariantCommand,
+            },
+            common::channel_commands::CreateChannelCommand,
+            sales::{customer_commands::CreateCustomerCommand, customer_group_commands::CreateCustomerGroupCommand},
+            tests::setup_service,
+        },
+        models::{
+            catalog::{
+                item_group_model::ItemCategoryNew,
+                item_model::{ItemNature, ItemState, NewItem},
+                item_variant_model::ItemVariantNewInput,
+            },
+            common::channel_model::{Channel, ChannelNewInput},
+            sales::{customer_group_model::CustomerGroupNewInput, customer_model::CustomerNewInput},
+        },
+    };
+    use chrono::Duration;
+
+    async fn create_test_item(service: &AppService) -> Item {
+        let category = CreateItemGroupCommand {
+            category: ItemCategoryNew {
+                name: "Test Category".to_string(),
+                description: None,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+
+        let item = CreateItemCommand {
+            item: NewItem {
+                name: "Test Item".to_string(),
+                description: None,
+                nature: ItemNature::Goods,
+                state: ItemState::Active,
+                price: Money::from(1000),
+                category_id: category.id,
+                tax_ids: None,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+
+        // Read the item back so the catalog price is exactly what the resolver sees
+        let mut item_query = Query::select();
+        let item_stmt = item_query
+            .from(Items::Table)
+            .columns(Items::all_columns())
+            .and_where(Expr::col(Items::Id).eq(item.id.to_string()));
+        service.db_adapter.query_one::<Item>(&item_stmt).await.unwrap()
+    }
+
+    async fn create_test_channel(service: &AppService, name: &str) -> Channel {
+        CreateChannelCommand {
+            channel: ChannelNewInput {
+                name: name.to_string(),
+                description: None,
+                is_active: Some(true),
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap()
+    }
+
+    async fn create_test_price_list(service: &AppService, name: &str, priority: i32) -> PriceList {
+        CreatePriceListCommand {
+            price_list: PriceListNewInput {
+                name: name.to_string(),
+                description: None,
+                priority: Some(priority),
+                valid_from: None,
+                valid_to: None,
+                is_active: None,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap()
+    }
+
+    async fn set_price(service: &AppService, price_list_id: DbUuid, item_id: DbUuid, min_quantity: i32, price: i64) {
+        SetPriceListItemCommand {
+            price: PriceListItemInput {
+                price_list_id,
+                item_id,
+                variant_id: None,
+                min_quantity: Some(min_quantity),
+                price: Money::from(price),
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+    }
+
+    async fn assign_to_channel(service: &AppService, price_list_id: DbUuid, channel_id: DbUuid) {
+        AssignPriceListCommand {
+            assignment: PriceListAssignmentInput {
+                price_list_id,
+                channel_id: Some(channel_id),
+                location_id: None,
+                customer_group_id: None,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+    }
+
+    fn channel_context(channel_id: DbUuid) -> PriceContext {
+        PriceContext {
+            channel_id: Some(channel_id),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_price_per_channel() {
+        let service = setup_service().await;
+        let item = create_test_item(&service).await;
+        let delivery = create_test_channel(&service, "Delivery").await;
+        let counter = create_test_channel(&service, "Counter").await;
+
+        let list = create_test_price_list(&service, "Delivery Prices", 0).await;
+        set_price(&service, list.id, item.id, 1, 1200).await;
+        assign_to_channel(&service, list.id, delivery.id).await;
+
+        let now = Utc::now().naive_utc();
+        let delivery_price = resolve_item_price(&service, item.id, None, 1, &channel_context(delivery.id), now)
+            .await
+            .unwrap();
+        let counter_price = resolve_item_price(&service, item.id, None, 1, &channel_context(counter.id), now)
+            .await
+            .unwrap();
+        let no_context_price = resolve_item_price(&service, item.id, None, 1, &PriceContext::default(), now)
+            .await
+            .unwrap();
+
+        assert_eq!(delivery_price, Money::from(1200));
+        assert_eq!(counter_price, item.price);
+        assert_eq!(no_context_price, item.price);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_price_quantity_tiers_and_priority() {
+        let service = setup_service().await;
+        let item = create_test_item(&service).await;
+        let channel = create_test_channel(&service, "Wholesale Counter").await;
+
+        let standard = create_test_price_list(&service, "Standard", 0).await;
+        set_price(&service, standard.id, item.id, 1, 900).await;
+        set_price(&service, standard.id, item.id, 10, 800).await;
+        assign_to_channel(&service, standard.id, channel.id).await;
+
+        let now = Utc::now().naive_utc();
+        let context = channel_context(channel.id);
+        assert_eq!(resolve_item_price(&service, item.id, None, 9, &context, now).await.unwrap(), Money::from(900));
+        assert_eq!(resolve_item_price(&service, item.id, None, 10, &context, now).await.unwrap(), Money::from(800));
+
+        // Setting an existing tier replaces its price
+        set_price(&service, standard.id, item.id, 10, 750).await;
+        assert_eq!(resolve_item_price(&service, item.id, None, 12, &context, now).await.unwrap(), Money::from(750));
+
+        // A higher priority list wins over any tier of a lower one
+        let promo = create_test_price_list(&service, "Promo", 10).await;
+        set_price(&service, promo.id, item.id, 1, 850).await;
+        assign_to_channel(&service, promo.id, channel.id).await;
+        assert_eq!(resolve_item_price(&service, item.id, None, 12, &context, now).await.unwrap(), Money::from(850));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_price_respects_validity_dates() {
+        let service = setup_service().await;
+        let item = create_test_item(&service).await;
+        let channel = create_test_channel(&service, "Delivery").await;
+        let now = Utc::now().naive_utc();
+
+        let list = CreatePriceListCommand {
+            price_list: PriceListNewInput {
+                name: "Next Week".to_string(),
+                description: None,
+                priority: None,
+                valid_from: Some(now + Duration::days(7)),
+                valid_to: None,
+                is_active: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        set_price(&service, list.id, item.id, 1, 1500).await;
+        assign_to_channel(&service, list.id, channel.id).await;
+
+        let context = channel_context(channel.id);
+        assert_eq!(resolve_item_price(&service, item.id, None, 1, &context, now).await.unwrap(), item.price);
+        assert_eq!(
+            resolve_item_price(&service, item.id, None, 1, &context, now + Duration::days(8)).await.unwrap(),
+            Money::from(1500)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_price_for_customer_group_and_variant() {
+        let service = setup_service().await;
+        let item = create_test_item(&service).await;
+
+        let group = CreateCustomerGroupCommand {
+            group: CustomerGroupNewInput {
+                name: "Wholesale".to_string(),
+                description: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let customer = CreateCustomerCommand {
+            customer: CustomerNewInput {
+                full_name: "Corner Shop".to_string(),
+                email: None,
+                phone: None,
+                address: None,
+                customer_group_id: Some(group.id),
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let variant = CreateItemVariantCommand {
+            item_variant: ItemVariantNewInput {
+                item_id: item.id,
+                sku: None,
+                barcode: None,
+                price_adjustment: Some(Money::from(200)),
+                is_default: Some(false),
+                variant_value_ids: vec![],
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let list = create_test_price_list(&service, "Wholesale Prices", 0).await;
+        set_price(&service, list.id, item.id, 1, 700).await;
+        SetPriceListItemCommand {
+            price: PriceListItemInput {
+                price_list_id: list.id,
+                item_id: item.id,
+                variant_id: Some(variant.id),
+                min_quantity: None,
+                price: Money::from(650),
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        AssignPriceListCommand {
+            assignment: PriceListAssignmentInput {
+                price_list_id: list.id,
+                channel_id: None,
+                location_id: None,
+                customer_group_id: Some(group.id),
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let resolve = |variant_id: Option<DbUuid>, customer_id: Option<DbUuid>| ResolveItemPriceCommand {
+            item_id: item.id,
+            variant_id,
+            quantity: 1,
+            channel_id: None,
+            location_id: None,
+            customer_id,
+        };
+
+        assert_eq!(resolve(None, Some(customer.id)).exec(&service).await.unwrap(), Money::from(700));
+        assert_eq!(resolve(Some(variant.id), Some(customer.id)).exec(&service).await.unwrap(), Money::from(650));
+        assert_eq!(
+            resolve(Some(variant.id), None).exec(&service).await.unwrap(),
+            item.price + Money::from(200)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_price_list_validation() {
+        let service = setup_service().await;
+        let now = Utc::now().naive_utc();
+
+        let result = CreatePriceListCommand {
+            price_list: PriceListNewInput {
+                name: "Backwards".to_string(),
+                description: None,
+                priority: None,
+                valid_from: Some(now),
+                valid_to: Some(now - Duration::days(1)),
+                is_active: None,
+            },
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+
+        let list = create_test_price_list(&service, "Unassigned", 0).await;
+        let result = AssignPriceListCommand {
+            assignment: PriceListAssignmentInput {
+                price_list_id: list.id,
+                channel_id: None,
+                location_id: None,
+                customer_group_id: None,
+            },
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+
+        let duplicate = CreatePriceListCommand {
+            price_list: PriceListNewInput {
+                name: "Unassigned".to_string(),
+                description: None,
+                priority: None,
+                valid_from: None,
+                valid_to: None,
+                is_active: None,
+            },
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(duplicate, Err(Error::UniqueConstraintError(_))));
+    }
+}
@@ -0,0 +1,187 @@
+use chrono::Utc;
+use sea_query::{Alias, Expr, Func, Query};
+use uuid::Uuid;
+
+use crate::{
+    adapters::outgoing::database::DatabaseAdapter,
+    core::{
+        commands::{app_service::AppService, Command},
+        db::SeaQueryCrudTrait,
+        models::catalog::{
+            item_model::{Item, Items},
+            item_variant_model::{ItemVariant, ItemVariants},
+            stock_movement_model::{StockMovement, StockMovementNewInput, StockMovements},
+        },
+        types::db_uuid::DbUuid,
+    },
+    error::{Error, Result},
+};
+
+// Commands
+pub struct RecordStockMovementCommand {
+    pub movement: StockMovementNewInput,
+    pub created_by: Option<DbUuid>,
+}
+
+/// The quantity on hand of an item, or of one of its variants
+pub struct GetStockOnHandCommand {
+    pub item_id: DbUuid,
+    pub item_variant_id: Option<DbUuid>,
+}
+
+// Command Implementations
+impl Command for RecordStockMovementCommand {
+    type Output = StockMovement;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        if self.movement.quantity == 0 {
+            return Err(Error::ValidationError("Quantity must not be zero".to_string()));
+        }
+
+        service.db_adapter.query_one::<Item>(&Items::find_by_id(self.movement.item_id)).await?;
+        if let Some(variant_id) = self.movement.item_variant_id {
+            let variant = service.db_adapter.query_one::<ItemVariant>(&ItemVariants::find_by_id(variant_id)).await?;
+            if variant.item_id != self.movement.item_id {
+                return Err(Error::ValidationError("The variant belongs to another item".to_string()));
+            }
+        }
+
+        let movement = StockMovement {
+            id: Uuid::now_v7().into(),
+            item_id: self.movement.item_id,
+            item_variant_id: self.movement.item_variant_id,
+            quantity: self.movement.quantity,
+            reason: self.movement.reason,
+            created_by: self.created_by,
+            created_at: Utc::now().naive_utc(),
+        };
+        service.db_adapter.insert_many(&movement.insert()).await?;
+
+        Ok(movement)
+    }
+}
+
+impl Command for GetStockOnHandCommand {
+    type Output = i32;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let mut query = Query::select();
+        query
+            .expr_as(
+                Func::coalesce([Func::sum(Expr::col(StockMovements::Quantity)).into(), Expr::val(0).into()]),
+                Alias::new("quantity"),
+            )
+            .from(StockMovements::Table)
+            .and_where(Expr::col(StockMovements::ItemId).eq(self.item_id.to_string()));
+
+        if let Some(variant_id) = self.item_variant_id {
+            query.and_where(Expr::col(StockMovements::ItemVariantId).eq(variant_id.to_string()));
+        }
+
+        let quantity = service.db_adapter.query_one::<i64>(&query).await?;
+        Ok(quantity as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        commands::{
+            catalog::{
+                item_commands::CreateItemCommand, item_group_commands::CreateItemGroupCommand,
+                item_variant_commands::CreateItemVariantCommand,
+            },
+            tests::setup_service,
+        },
+        models::catalog::{
+            item_group_model::ItemCategoryNew,
+            item_model::{ItemNature, ItemState, NewItem},
+            item_variant_model::ItemVariantNewInput,
+            stock_movement_model::StockMovementReason,
+        },
+        types::money::Money,
+    };
+
+    async fn create_item(service: &AppService, name: &str) -> Item {
+        let command = CreateItemGroupCommand {
+            category: ItemCategoryNew { name: format!("{} category", name), description: None },
+        };
+        let category = command.exec(service).await.unwrap();
+        let command = CreateItemCommand {
+            item: NewItem {
+                name: name.to_string(),
+                description: None,
+                nature: ItemNature::Goods,
+                state: ItemState::Active,
+                price: Money::from(1000),
+                category_id: category.id,
+                tax_ids: None,
+            },
+        };
+        command.exec(service).await.unwrap()
+    }
+
+    fn opening(item_id: DbUuid, item_variant_id: Option<DbUuid>, quantity: i32) -> RecordStockMovementCommand {
+        RecordStockMovementCommand {
+            movement: StockMovementNewInput {
+                item_id,
+                item_variant_id,
+                quantity,
+                reason: StockMovementReason::Opening,
+            },
+            created_by: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stock_on_hand_sums_movements() {
+        let service = setup_service().await;
+        let item = create_item(&service, "Cola").await;
+        let command = CreateItemVariantCommand {
+            item_variant: ItemVariantNewInput {
+                item_id: item.id,
+                sku: Some("COLA-330".to_string()),
+                barcode: None,
+                price_adjustment: None,
+                is_default: None,
+                variant_value_ids: vec![],
+            },
+        };
+        let variant = command.exec(&service).await.unwrap();
+
+        let stock = |item_variant_id| GetStockOnHandCommand { item_id: item.id, item_variant_id };
+        assert_eq!(stock(None).exec(&service).await.unwrap(), 0);
+
+        opening(item.id, None, 10).exec(&service).await.unwrap();
+        opening(item.id, Some(variant.id), 24).exec(&service).await.unwrap();
+
+        assert_eq!(stock(None).exec(&service).await.unwrap(), 34);
+        assert_eq!(stock(Some(variant.id)).exec(&service).await.unwrap(), 24);
+    }
+
+    #[tokio::test]
+    async fn test_record_stock_movement_validates_input() {
+        let service = setup_service().await;
+        let cola = create_item(&service, "Cola").await;
+        let tea = create_item(&service, "Tea").await;
+        let command = CreateItemVariantCommand {
+            item_variant: ItemVariantNewInput {
+                item_id: tea.id,
+                sku: None,
+                barcode: None,
+                price_adjustment: None,
+                is_default: None,
+                variant_value_ids: vec![],
+            },
+        };
+        let tea_variant = command.exec(&service).await.unwrap();
+
+        let result = opening(cola.id, None, 0).exec(&service).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+        let result = opening(cola.id, Some(tea_variant.id), 5).exec(&service).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+        let result = opening(Uuid::now_v7().into(), None, 5).exec(&service).await;
+        assert!(matches!(result, Err(Error::NotFoundError)));
+    }
+}
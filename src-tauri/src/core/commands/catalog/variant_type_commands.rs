@@ -35,7 +35,7 @@ pub struct ListVariantTypesCommand;
 impl Command for CreateVariantTypeCommand {
     type Output = VariantType;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let new_variant_type = VariantType {
             id: Uuid::now_v7().into(),
@@ -72,7 +72,7 @@ impl Command for CreateVariantTypeCommand {
 impl Command for UpdateVariantTypeCommand {
     type Output = VariantType;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Verify variant type exists
         let mut select_query = Query::select();
         let select_stmt = select_query
@@ -134,7 +134,7 @@ impl Command for UpdateVariantTypeCommand {
 impl Command for DeleteVariantTypeCommand {
     type Output = usize;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Check if there are any variant values using this type
         let mut count_query = Query::select();
         let count_stmt = count_query
@@ -167,7 +167,7 @@ impl Command for DeleteVariantTypeCommand {
 impl Command for GetVariantTypeCommand {
     type Output = VariantType;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let mut query = Query::select();
         let stmt = query
             .from(VariantTypes::Table)
@@ -191,7 +191,7 @@ impl Command for GetVariantTypeCommand {
 impl Command for ListVariantTypesCommand {
     type Output = Vec<VariantType>;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let mut query = Query::select();
         let stmt = query
             .from(VariantTypes::Table)
@@ -214,7 +214,7 @@ mod tests {
     use crate::core::commands::app_service::tests::setup_service;
     use tokio;
 
-    async fn create_test_variant_type(service: &mut AppService) -> VariantType {
+    async fn create_test_variant_type(service: &AppService) -> VariantType {
         let command = CreateVariantTypeCommand {
             variant_type: VariantTypeNewInput {
                 name: "Test Variant Type".to_string(),
@@ -226,16 +226,16 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_variant_type() {
-        let mut service = setup_service().await;
-        let variant_type = create_test_variant_type(&mut service).await;
+        let service = setup_service().await;
+        let variant_type = create_test_variant_type(&service).await;
         assert_eq!(variant_type.name, "Test Variant Type");
         assert_eq!(variant_type.description, Some("Test Description".to_string()));
     }
 
     #[tokio::test]
     async fn test_update_variant_type() {
-        let mut service = setup_service().await;
-        let created = create_test_variant_type(&mut service).await;
+        let service = setup_service().await;
+        let created = create_test_variant_type(&service).await;
 
         let update_command = UpdateVariantTypeCommand {
             variant_type: VariantTypeUpdateInput {
@@ -246,45 +246,45 @@ mod tests {
             },
         };
 
-        let updated = update_command.exec(&mut service).await.unwrap();
+        let updated = update_command.exec(&service).await.unwrap();
         assert_eq!(updated.name, "Updated Name");
         assert_eq!(updated.description, Some("Updated Description".to_string()));
     }
 
     #[tokio::test]
     async fn test_get_variant_type() {
-        let mut service = setup_service().await;
-        let created = create_test_variant_type(&mut service).await;
+        let service = setup_service().await;
+        let created = create_test_variant_type(&service).await;
 
         let get_command = GetVariantTypeCommand { id: created.id };
-        let retrieved = get_command.exec(&mut service).await.unwrap();
+        let retrieved = get_command.exec(&service).await.unwrap();
         assert_eq!(retrieved.id, created.id);
         assert_eq!(retrieved.name, created.name);
     }
 
     #[tokio::test]
     async fn test_list_variant_types() {
-        let mut service = setup_service().await;
-        let created = create_test_variant_type(&mut service).await;
+        let service = setup_service().await;
+        let created = create_test_variant_type(&service).await;
 
         let list_command = ListVariantTypesCommand;
-        let list = list_command.exec(&mut service).await.unwrap();
+        let list = list_command.exec(&service).await.unwrap();
         assert!(!list.is_empty());
         assert!(list.iter().any(|vt| vt.id == created.id));
     }
 
     #[tokio::test]
     async fn test_delete_variant_type() {
-        let mut service = setup_service().await;
-        let created = create_test_variant_type(&mut service).await;
+        let service = setup_service().await;
+        let created = create_test_variant_type(&service).await;
 
         let delete_command = DeleteVariantTypeCommand { id: created.id };
-        let result = delete_command.exec(&mut service).await.unwrap();
+        let result = delete_command.exec(&service).await.unwrap();
         assert_eq!(result, 1);
 
         // Verify it's gone
         let get_command = GetVariantTypeCommand { id: created.id };
-        let result = get_command.exec(&mut service).await;
+        let result = get_command.exec(&service).await;
         assert!(result.is_err());
     }
 }
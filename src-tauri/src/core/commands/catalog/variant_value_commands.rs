@@ -40,7 +40,7 @@ pub struct ListVariantValuesCommand {
 impl Command for CreateVariantValueCommand {
     type Output = VariantValue;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Verify variant type exists
         let mut type_query = Query::select();
         let type_stmt = type_query
@@ -114,7 +114,7 @@ impl Command for CreateVariantValueCommand {
 impl Command for UpdateVariantValueCommand {
     type Output = VariantValue;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Verify variant value exists
         let mut select_query = Query::select();
         let select_stmt = select_query
@@ -176,7 +176,7 @@ impl Command for UpdateVariantValueCommand {
 impl Command for DeleteVariantValueCommand {
     type Output = usize;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Check if this variant value is used in any item variants
         let mut count_query = Query::select();
         let count_stmt = count_query
@@ -209,7 +209,7 @@ impl Command for DeleteVariantValueCommand {
 impl Command for GetVariantValueCommand {
     type Output = VariantValue;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let mut query = Query::select();
         let stmt = query
             .from(VariantValues::Table)
@@ -234,7 +234,7 @@ impl Command for GetVariantValueCommand {
 impl Command for ListVariantValuesCommand {
     type Output = Vec<VariantValue>;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let mut query_builder = Query::select();
         let mut query = query_builder
             .from(VariantValues::Table)
@@ -268,7 +268,7 @@ mod tests {
     use tokio;
 
     async fn create_test_variant_type(
-        service: &mut AppService,
+        service: &AppService,
     ) -> crate::core::models::catalog::variant_type_model::VariantType {
         let command = CreateVariantTypeCommand {
             variant_type: VariantTypeNewInput {
@@ -279,7 +279,7 @@ mod tests {
         command.exec(service).await.unwrap()
     }
 
-    async fn create_test_variant_value(service: &mut AppService) -> VariantValue {
+    async fn create_test_variant_value(service: &AppService) -> VariantValue {
         let variant_type = create_test_variant_type(service).await;
         let command = CreateVariantValueCommand {
             variant_value: VariantValueNewInput {
@@ -293,8 +293,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_variant_value() {
-        let mut service = setup_service().await;
-        let variant_type = create_test_variant_type(&mut service).await;
+        let service = setup_service().await;
+        let variant_type = create_test_variant_type(&service).await;
 
         let command = CreateVariantValueCommand {
             variant_value: VariantValueNewInput {
@@ -304,7 +304,7 @@ mod tests {
             },
         };
 
-        let variant_value = command.exec(&mut service).await.unwrap();
+        let variant_value = command.exec(&service).await.unwrap();
         assert_eq!(variant_value.value, "Test Value");
         assert_eq!(variant_value.display_order, 1);
         assert_eq!(variant_value.variant_type_id, variant_type.id);
@@ -312,8 +312,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_variant_value() {
-        let mut service = setup_service().await;
-        let created = create_test_variant_value(&mut service).await;
+        let service = setup_service().await;
+        let created = create_test_variant_value(&service).await;
 
         let update_command = UpdateVariantValueCommand {
             variant_value: VariantValueUpdateInput {
@@ -324,26 +324,26 @@ mod tests {
             },
         };
 
-        let updated = update_command.exec(&mut service).await.unwrap();
+        let updated = update_command.exec(&service).await.unwrap();
         assert_eq!(updated.value, "Updated Value");
         assert_eq!(updated.display_order, 2);
     }
 
     #[tokio::test]
     async fn test_get_variant_value() {
-        let mut service = setup_service().await;
-        let created = create_test_variant_value(&mut service).await;
+        let service = setup_service().await;
+        let created = create_test_variant_value(&service).await;
 
         let get_command = GetVariantValueCommand { id: created.id };
-        let retrieved = get_command.exec(&mut service).await.unwrap();
+        let retrieved = get_command.exec(&service).await.unwrap();
         assert_eq!(retrieved.id, created.id);
         assert_eq!(retrieved.value, created.value);
     }
 
     #[tokio::test]
     async fn test_list_variant_values() {
-        let mut service = setup_service().await;
-        let variant_type = create_test_variant_type(&mut service).await;
+        let service = setup_service().await;
+        let variant_type = create_test_variant_type(&service).await;
 
         // Create multiple values
         let command1 = CreateVariantValueCommand {
@@ -353,7 +353,7 @@ mod tests {
                 display_order: Some(1),
             },
         };
-        let value1 = command1.exec(&mut service).await.unwrap();
+        let value1 = command1.exec(&service).await.unwrap();
 
         let command2 = CreateVariantValueCommand {
             variant_value: VariantValueNewInput {
@@ -362,20 +362,20 @@ mod tests {
                 display_order: Some(2),
             },
         };
-        let value2 = command2.exec(&mut service).await.unwrap();
+        let value2 = command2.exec(&service).await.unwrap();
 
         // List all values
         let list_command = ListVariantValuesCommand {
             variant_type_id: None,
         };
-        let all_values = list_command.exec(&mut service).await.unwrap();
+        let all_values = list_command.exec(&service).await.unwrap();
         assert!(all_values.len() >= 2);
 
         // List values for specific type
         let list_command = ListVariantValuesCommand {
             variant_type_id: Some(variant_type.id),
         };
-        let type_values = list_command.exec(&mut service).await.unwrap();
+        let type_values = list_command.exec(&service).await.unwrap();
         assert_eq!(type_values.len(), 2);
         assert!(type_values.iter().any(|v| v.id == value1.id));
         assert!(type_values.iter().any(|v| v.id == value2.id));
@@ -383,16 +383,16 @@ mod tests {
 
     #[tokio::test]
     async fn test_delete_variant_value() {
-        let mut service = setup_service().await;
-        let created = create_test_variant_value(&mut service).await;
+        let service = setup_service().await;
+        let created = create_test_variant_value(&service).await;
 
         let delete_command = DeleteVariantValueCommand { id: created.id };
-        let result = delete_command.exec(&mut service).await.unwrap();
+        let result = delete_command.exec(&service).await.unwrap();
         assert_eq!(result, 1);
 
         // Verify it's gone
         let get_command = GetVariantValueCommand { id: created.id };
-        let result = get_command.exec(&mut service).await;
+        let result = get_command.exec(&service).await;
         assert!(result.is_err());
     }
 }
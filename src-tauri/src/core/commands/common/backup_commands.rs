@@ -0,0 +1,214 @@
+use std::{fs, path::Path};
+
+use chrono::Utc;
+
+use crate::{
+    adapters::outgoing::database::DatabaseAdapter,
+    core::{
+        commands::{app_service::AppService, Command},
+        db::backup::{self, BackupConfig},
+        models::common::backup_model::{Backup, BackupKind},
+    },
+    error::Result,
+};
+
+// Commands
+pub struct CreateBackupCommand {
+    pub config: BackupConfig,
+    pub kind: BackupKind,
+    pub label: Option<String>,
+}
+
+pub struct ListBackupsCommand {
+    pub config: BackupConfig,
+}
+
+// Command Implementations
+impl Command for CreateBackupCommand {
+    type Output = Backup;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let config = &self.config;
+        fs::create_dir_all(&config.dir)?;
+
+        let label = self.label.as_deref().and_then(backup::clean_label);
+        let created_at = Utc::now().naive_utc();
+        let encrypted = config.passphrase.is_some();
+        let name = backup::file_name(&service.conn_path, self.kind, label.as_deref(), created_at, encrypted);
+        let path = config.dir.join(name);
+
+        // Nothing is left under the backup's own name until it is complete
+        let copy = config.dir.join(format!("{}.copy", created_at.format("%Y%m%d%H%M%S%9f")));
+        let result = write_backup(service, config, &copy, &path).await;
+        let _ = fs::remove_file(&copy);
+        result?;
+
+        backup::rotate(config, &service.conn_path, self.kind)?;
+
+        Ok(Backup {
+            path: path.to_string_lossy().into_owned(),
+            kind: self.kind,
+            label,
+            created_at,
+            encrypted,
+        })
+    }
+}
+
+impl Command for ListBackupsCommand {
+    type Output = Vec<Backup>;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        backup::list(&self.config, &service.conn_path)
+    }
+}
+
+// Copies the database to `copy`, then moves it, sealed if there is a passphrase, to `path`
+async fn write_backup(service: &AppService, config: &BackupConfig, copy: &Path, path: &Path) -> Result<()> {
+    let target = copy.to_string_lossy().replace('\'', "''");
+    service.db_adapter.execute(&format!("VACUUM INTO '{}'", target)).await?;
+
+    match &config.passphrase {
+        Some(passphrase) => {
+            let sealed = backup::encrypt(&fs::read(copy)?, passphrase)?;
+            let partial = path.with_extension("enc.partial");
+            if let Err(e) = fs::write(&partial, sealed).and_then(|_| fs::rename(&partial, path)) {
+                let _ = fs::remove_file(&partial);
+                return Err(e.into());
+            }
+        }
+        None => fs::rename(copy, path)?,
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{
+        adapters::outgoing::secrets::MemorySecretStore,
+        core::{
+            commands::{sales::customer_commands::CreateCustomerCommand, tests::setup_service},
+            models::sales::customer_model::CustomerNewInput,
+        },
+        error::Error,
+    };
+
+    fn config(passphrase: Option<&str>) -> BackupConfig {
+        BackupConfig {
+            dir: std::env::temp_dir().join(format!("minnal-backups-{}", Uuid::now_v7())),
+            keep: 2,
+            interval: None,
+            passphrase: passphrase.map(str::to_string),
+        }
+    }
+
+    async fn create_customer(service: &AppService, name: &str) {
+        let command = CreateCustomerCommand {
+            customer: CustomerNewInput {
+                full_name: name.to_string(),
+                email: None,
+                phone: None,
+                address: None,
+                customer_group_id: None,
+            },
+        };
+        command.exec(service).await.unwrap();
+    }
+
+    async fn customer_names(db_path: &Path) -> Vec<String> {
+        let db = libsql::Builder::new_local(db_path).build().await.unwrap();
+        let conn = db.connect().unwrap();
+        let mut rows = conn.query("SELECT full_name FROM customers ORDER BY full_name", ()).await.unwrap();
+        let mut names = Vec::new();
+        while let Some(row) = rows.next().await.unwrap() {
+            names.push(row.get::<String>(0).unwrap());
+        }
+        names
+    }
+
+    #[tokio::test]
+    async fn test_create_backup_keeps_the_newest_of_each_kind() {
+        let service = setup_service().await;
+        let config = config(None);
+
+        let snapshot = |label: &str| CreateBackupCommand {
+            config: config.clone(),
+            kind: BackupKind::Snapshot,
+            label: Some(label.to_string()),
+        };
+        let first = snapshot("First").exec(&service).await.unwrap();
+        let scheduled = CreateBackupCommand { config: config.clone(), kind: BackupKind::Scheduled, label: None };
+        let scheduled = scheduled.exec(&service).await.unwrap();
+        let second = snapshot("second").exec(&service).await.unwrap();
+        let third = snapshot("third").exec(&service).await.unwrap();
+
+        assert_eq!(first.label.as_deref(), Some("first"));
+        assert!(!Path::new(&first.path).exists());
+
+        let backups = ListBackupsCommand { config: config.clone() }.exec(&service).await.unwrap();
+        let paths: Vec<_> = backups.iter().map(|backup| backup.path.as_str()).collect();
+        assert_eq!(paths, [third.path.as_str(), second.path.as_str(), scheduled.path.as_str()]);
+        assert_eq!(fs::read_dir(&config.dir).unwrap().count(), 3);
+
+        fs::remove_dir_all(&config.dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore_encrypted_backup() {
+        let service = setup_service().await;
+        let config = config(Some("correct horse"));
+        create_customer(&service, "Asha").await;
+        let command = CreateBackupCommand { config: config.clone(), kind: BackupKind::Snapshot, label: None };
+        let backup = command.exec(&service).await.unwrap();
+        assert!(backup.encrypted);
+        assert!(backup.path.ends_with(".db.enc"));
+
+        let secrets = MemorySecretStore::default();
+        let db_path = config.dir.join("restored.db");
+        let db_path_str = db_path.to_string_lossy().into_owned();
+        fs::write(&db_path, b"the database being replaced").unwrap();
+
+        let result = backup::restore(&db_path_str, Path::new(&backup.path), None, &secrets).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+        let result = backup::restore(&db_path_str, Path::new(&backup.path), Some("wrong horse"), &secrets).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+
+        let moved = backup::restore(&db_path_str, Path::new(&backup.path), Some("correct horse"), &secrets);
+        let moved = moved.await.unwrap();
+        let moved = moved.unwrap();
+        assert_eq!(fs::read(&moved).unwrap(), b"the database being replaced");
+        assert_eq!(customer_names(&db_path).await, ["Asha"]);
+
+        fs::remove_dir_all(&config.dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore_rejects_a_newer_schema() {
+        let service = setup_service().await;
+        let config = config(None);
+        service
+            .db_adapter
+            .execute("INSERT INTO libsql_migrations (id, status, exec_time, checksum) VALUES ('9999_from_the_future.sql', 1, CURRENT_TIMESTAMP, '')")
+            .await
+            .unwrap();
+        let command = CreateBackupCommand { config: config.clone(), kind: BackupKind::Snapshot, label: None };
+        let backup = command.exec(&service).await.unwrap();
+
+        let db_path: PathBuf = config.dir.join("current.db");
+        fs::write(&db_path, b"the current database").unwrap();
+        let secrets = MemorySecretStore::default();
+        let result = backup::restore(&db_path.to_string_lossy(), Path::new(&backup.path), None, &secrets).await;
+
+        assert!(matches!(result, Err(Error::ValidationError(message)) if message.contains("9999_from_the_future")));
+        assert_eq!(fs::read(&db_path).unwrap(), b"the current database");
+        assert_eq!(fs::read_dir(&config.dir).unwrap().count(), 2);
+
+        fs::remove_dir_all(&config.dir).unwrap();
+    }
+}
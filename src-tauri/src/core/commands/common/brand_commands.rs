@@ -6,6 +6,7 @@ use crate::{
     adapters::outgoing::database::DatabaseAdapter,
     core::{
         commands::{app_service::AppService, Command},
+        db::soft_delete::{purge, restore, soft_delete},
         models::common::brand_model::{
             Brand, BrandNewInput, BrandUpdateInput, Brands,
         },
@@ -25,13 +26,22 @@ pub struct UpdateBrandCommand {
 
 pub struct DeleteBrandCommand {
     pub id: DbUuid,
+    pub deleted_by: Option<DbUuid>,
+}
+
+pub struct RestoreBrandCommand {
+    pub id: DbUuid,
+}
+
+pub struct PurgeBrandCommand {
+    pub id: DbUuid,
 }
 
 // Command Implementations
 impl Command for CreateBrandCommand {
     type Output = Brand;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let new_brand = Brand {
             id: Uuid::now_v7().into(),
@@ -40,6 +50,8 @@ impl Command for CreateBrandCommand {
             is_active: self.brand.is_active.unwrap_or(true),
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
         };
 
         // Insert the brand
@@ -76,7 +88,7 @@ impl Command for CreateBrandCommand {
 impl Command for UpdateBrandCommand {
     type Output = Brand;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Get the existing brand
         let mut query_builder = Query::select();
         let select_stmt = query_builder
@@ -136,6 +148,8 @@ impl Command for UpdateBrandCommand {
             is_active: self.brand.is_active.unwrap_or(brand.is_active),
             created_at: brand.created_at,
             updated_at: now,
+            deleted_at: brand.deleted_at,
+            deleted_by: brand.deleted_by,
         };
 
         Ok(updated_brand)
@@ -145,18 +159,27 @@ impl Command for UpdateBrandCommand {
 impl Command for DeleteBrandCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        let mut delete_query = Query::delete();
-        let delete_stmt = delete_query
-            .from_table(Brands::Table)
-            .and_where(Expr::col(Brands::Id).eq(self.id.to_string()));
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        soft_delete::<Brand, _>(&service.db_adapter, self.id, self.deleted_by).await?;
 
-        let result = service.db_adapter.delete(&delete_stmt).await?;
+        Ok(1)
+    }
+}
 
-        if result == 0 {
-            return Err(Error::NotFoundError);
-        }
+impl Command for RestoreBrandCommand {
+    type Output = Brand;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        restore(&service.db_adapter, self.id).await
+    }
+}
+
+impl Command for PurgeBrandCommand {
+    type Output = i32;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        purge::<Brand, _>(&service.db_adapter, self.id).await?;
 
-        Ok(result as i32)
+        Ok(1)
     }
 }
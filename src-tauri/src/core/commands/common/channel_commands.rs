@@ -6,6 +6,7 @@ use crate::{
     adapters::outgoing::database::DatabaseAdapter,
     core::{
         commands::{app_service::AppService, Command},
+        db::soft_delete::{purge, restore, soft_delete},
         models::common::channel_model::{
             Channel, ChannelNewInput, ChannelUpdateInput, Channels,
         },
@@ -25,13 +26,22 @@ pub struct UpdateChannelCommand {
 
 pub struct DeleteChannelCommand {
     pub id: DbUuid,
+    pub deleted_by: Option<DbUuid>,
+}
+
+pub struct RestoreChannelCommand {
+    pub id: DbUuid,
+}
+
+pub struct PurgeChannelCommand {
+    pub id: DbUuid,
 }
 
 // Command Implementations
 impl Command for CreateChannelCommand {
     type Output = Channel;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let new_channel = Channel {
             id: Uuid::now_v7().into(),
@@ -40,6 +50,8 @@ impl Command for CreateChannelCommand {
             is_active: self.channel.is_active.unwrap_or(true),
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
         };
 
         // Insert the channel
@@ -76,7 +88,7 @@ impl Command for CreateChannelCommand {
 impl Command for UpdateChannelCommand {
     type Output = Channel;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Get the existing channel
         let mut query_builder = Query::select();
         let select_stmt = query_builder
@@ -136,6 +148,8 @@ impl Command for UpdateChannelCommand {
             is_active: self.channel.is_active.unwrap_or(channel.is_active),
             created_at: channel.created_at,
             updated_at: now,
+            deleted_at: channel.deleted_at,
+            deleted_by: channel.deleted_by,
         };
 
         Ok(updated_channel)
@@ -145,18 +159,27 @@ impl Command for UpdateChannelCommand {
 impl Command for DeleteChannelCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        let mut delete_query = Query::delete();
-        let delete_stmt = delete_query
-            .from_table(Channels::Table)
-            .and_where(Expr::col(Channels::Id).eq(self.id.to_string()));
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        soft_delete::<Channel, _>(&service.db_adapter, self.id, self.deleted_by).await?;
 
-        let result = service.db_adapter.delete(&delete_stmt).await?;
+        Ok(1)
+    }
+}
 
-        if result == 0 {
-            return Err(Error::NotFoundError);
-        }
+impl Command for RestoreChannelCommand {
+    type Output = Channel;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        restore(&service.db_adapter, self.id).await
+    }
+}
+
+impl Command for PurgeChannelCommand {
+    type Output = i32;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        purge::<Channel, _>(&service.db_adapter, self.id).await?;
 
-        Ok(result as i32)
+        Ok(1)
     }
 }
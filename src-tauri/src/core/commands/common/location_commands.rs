@@ -6,6 +6,7 @@ use crate::{
     adapters::outgoing::database::DatabaseAdapter,
     core::{
         commands::{app_service::AppService, Command},
+        db::soft_delete::{purge, restore, soft_delete},
         models::common::location_model::{Location, LocationNewInput, LocationUpdateInput, Locations},
         types::db_uuid::DbUuid,
     },
@@ -22,12 +23,21 @@ pub struct UpdateLocationCommand {
 
 pub struct DeleteLocationCommand {
     pub id: DbUuid,
+    pub deleted_by: Option<DbUuid>,
+}
+
+pub struct RestoreLocationCommand {
+    pub id: DbUuid,
+}
+
+pub struct PurgeLocationCommand {
+    pub id: DbUuid,
 }
 
 impl Command for CreateLocationCommand {
     type Output = Location;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let is_active = self.location.is_active.unwrap_or(true);
 
@@ -39,6 +49,8 @@ impl Command for CreateLocationCommand {
             is_active,
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
         };
 
         // Insert the location
@@ -79,7 +91,7 @@ impl Command for CreateLocationCommand {
 impl Command for UpdateLocationCommand {
     type Output = Location;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // First, get the existing location
         let mut query_builder = Query::select();
         let query = query_builder
@@ -152,6 +164,8 @@ impl Command for UpdateLocationCommand {
             is_active: self.location.is_active.unwrap_or(existing_location.is_active),
             created_at: existing_location.created_at,
             updated_at: now,
+            deleted_at: existing_location.deleted_at,
+            deleted_by: existing_location.deleted_by,
         };
 
         Ok(updated_location)
@@ -161,13 +175,26 @@ impl Command for UpdateLocationCommand {
 impl Command for DeleteLocationCommand {
     type Output = ();
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        let query = Query::delete()
-            .from_table(Locations::Table)
-            .and_where(Expr::col(Locations::Id).eq(self.id.to_string()))
-            .to_string(SqliteQueryBuilder);
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        soft_delete::<Location, _>(&service.db_adapter, self.id, self.deleted_by).await?;
 
-        service.db_adapter.execute(&query).await?;
+        Ok(())
+    }
+}
+
+impl Command for RestoreLocationCommand {
+    type Output = Location;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        restore(&service.db_adapter, self.id).await
+    }
+}
+
+impl Command for PurgeLocationCommand {
+    type Output = ();
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        purge::<Location, _>(&service.db_adapter, self.id).await?;
 
         Ok(())
     }
@@ -1,9 +1,11 @@
+pub mod backup_commands;
 pub mod brand_commands;
 pub mod channel_commands;
 pub mod location_commands;
 pub mod tax_commands;
 pub mod tax_group_commands;
 
+pub use backup_commands::*;
 pub use brand_commands::*;
 pub use channel_commands::*;
 pub use location_commands::*;
@@ -41,7 +41,7 @@ pub struct RemoveTaxFromItemCommand {
 impl Command for CreateTaxCommand {
     type Output = Tax;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Verify all items exist if item_ids are provided
         if let Some(item_ids) = &self.tax.item_ids {
             for item_id in item_ids {
@@ -83,7 +83,7 @@ impl Command for CreateTaxCommand {
             .values_panic([
                 new_tax.id.to_string().into(),
                 new_tax.name.clone().into(),
-                new_tax.rate.to_string().into(),
+                new_tax.rate.basis_points().into(),
                 match &new_tax.description {
                     Some(desc) => desc.clone().into(),
                     None => sea_query::Value::String(None).into(),
@@ -125,7 +125,7 @@ impl Command for CreateTaxCommand {
 impl Command for UpdateTaxCommand {
     type Output = Tax;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Get the existing tax
         let mut query_builder = Query::select();
         let select_stmt = query_builder
@@ -160,7 +160,7 @@ impl Command for UpdateTaxCommand {
         }
 
         if let Some(rate) = self.tax.rate {
-            update_stmt.value(Taxes::Rate, rate.to_string());
+            update_stmt.value(Taxes::Rate, rate.basis_points());
         }
 
         if let Some(description) = &self.tax.description {
@@ -186,7 +186,7 @@ impl Command for UpdateTaxCommand {
 impl Command for DeleteTaxCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let mut delete_query = Query::delete();
         let delete_stmt = delete_query
             .from_table(Taxes::Table)
@@ -205,7 +205,7 @@ impl Command for DeleteTaxCommand {
 impl Command for AssignTaxToItemCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Verify item exists
         let mut item_query_builder = Query::select();
         let item_query = item_query_builder
@@ -270,7 +270,7 @@ impl Command for AssignTaxToItemCommand {
 impl Command for RemoveTaxFromItemCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let mut delete_query = Query::delete();
         let delete_stmt = delete_query
             .from_table(ItemTaxes::Table)
@@ -298,7 +298,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_tax() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let command = CreateTaxCommand {
             tax: TaxNewInput {
@@ -309,7 +309,7 @@ mod tests {
             },
         };
 
-        let tax = command.exec(&mut service).await.unwrap();
+        let tax = command.exec(&service).await.unwrap();
         assert_eq!(tax.name, "GST");
         assert_eq!(tax.rate, Percentage::from_float(18.0));
         assert_eq!(tax.description, Some("Goods and Services Tax".to_string()));
@@ -317,11 +317,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_tax_with_items() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create test items
-        let item1 = create_test_item(&mut service).await;
-        let item2 = create_test_item(&mut service).await;
+        let item1 = create_test_item(&service).await;
+        let item2 = create_test_item(&service).await;
 
         let command = CreateTaxCommand {
             tax: TaxNewInput {
@@ -332,7 +332,7 @@ mod tests {
             },
         };
 
-        let tax = command.exec(&mut service).await.unwrap();
+        let tax = command.exec(&service).await.unwrap();
 
         // Verify tax was created
         assert_eq!(tax.name, "GST");
@@ -354,7 +354,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_tax_with_nonexistent_item() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let command = CreateTaxCommand {
             tax: TaxNewInput {
@@ -365,13 +365,13 @@ mod tests {
             },
         };
 
-        let result = command.exec(&mut service).await;
+        let result = command.exec(&service).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_update_tax() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // First create a tax
         let create_command = CreateTaxCommand {
@@ -382,7 +382,7 @@ mod tests {
                 item_ids: None,
             },
         };
-        let created_tax = create_command.exec(&mut service).await.unwrap();
+        let created_tax = create_command.exec(&service).await.unwrap();
 
         // Then update it
         let update_command = UpdateTaxCommand {
@@ -394,7 +394,7 @@ mod tests {
             },
         };
 
-        let updated_tax = update_command.exec(&mut service).await.unwrap();
+        let updated_tax = update_command.exec(&service).await.unwrap();
         assert_eq!(updated_tax.name, "Updated GST");
         assert_eq!(updated_tax.rate, Percentage::from_float(20.0));
         assert_eq!(
@@ -405,7 +405,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_tax_does_not_exist() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let command = UpdateTaxCommand {
             tax: TaxUpdateInput {
@@ -416,13 +416,13 @@ mod tests {
             },
         };
 
-        let result = command.exec(&mut service).await;
+        let result = command.exec(&service).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_delete_tax() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // First create a tax
         let create_command = CreateTaxCommand {
@@ -433,28 +433,28 @@ mod tests {
                 item_ids: None,
             },
         };
-        let created_tax = create_command.exec(&mut service).await.unwrap();
+        let created_tax = create_command.exec(&service).await.unwrap();
 
         // Then delete it
         let delete_command = DeleteTaxCommand { id: created_tax.id };
 
-        let result = delete_command.exec(&mut service).await.unwrap();
+        let result = delete_command.exec(&service).await.unwrap();
         assert_eq!(result, 1);
     }
 
     #[tokio::test]
     async fn test_delete_tax_does_not_exist() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let command = DeleteTaxCommand {
             id: Uuid::now_v7().into(),
         };
 
-        let result = command.exec(&mut service).await;
+        let result = command.exec(&service).await;
         assert!(result.is_err());
     }
 
-    async fn create_test_item_category(service: &mut AppService) -> DbUuid {
+    async fn create_test_item_category(service: &AppService) -> DbUuid {
         let category_name = format!("Test Category {}", Uuid::now_v7());
         let command = CreateItemGroupCommand {
             category: ItemCategoryNew {
@@ -466,7 +466,7 @@ mod tests {
         category.id
     }
 
-    async fn create_test_item(service: &mut AppService) -> Item {
+    async fn create_test_item(service: &AppService) -> Item {
         let now = Utc::now().naive_utc();
         let category_id = create_test_item_category(service).await;
 
@@ -480,6 +480,9 @@ mod tests {
             price: 1000.into(),
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
+            version: 1,
         };
 
         let mut insert_query = Query::insert();
@@ -518,7 +521,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_assign_tax_to_item() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a tax and an item
         let tax = CreateTaxCommand {
@@ -529,11 +532,11 @@ mod tests {
                 item_ids: None,
             },
         }
-        .exec(&mut service)
+        .exec(&service)
         .await
         .unwrap();
 
-        let item = create_test_item(&mut service).await;
+        let item = create_test_item(&service).await;
 
         // Assign tax to item
         let command = AssignTaxToItemCommand {
@@ -543,13 +546,13 @@ mod tests {
             },
         };
 
-        let result = command.exec(&mut service).await.unwrap();
+        let result = command.exec(&service).await.unwrap();
         assert_eq!(result, 1);
     }
 
     #[tokio::test]
     async fn test_assign_tax_to_nonexistent_item() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create only a tax
         let tax = CreateTaxCommand {
@@ -560,7 +563,7 @@ mod tests {
                 item_ids: None,
             },
         }
-        .exec(&mut service)
+        .exec(&service)
         .await
         .unwrap();
 
@@ -572,16 +575,16 @@ mod tests {
             },
         };
 
-        let result = command.exec(&mut service).await;
+        let result = command.exec(&service).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_assign_nonexistent_tax_to_item() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create only an item
-        let item = create_test_item(&mut service).await;
+        let item = create_test_item(&service).await;
 
         // Try to assign non-existent tax to item
         let command = AssignTaxToItemCommand {
@@ -591,13 +594,13 @@ mod tests {
             },
         };
 
-        let result = command.exec(&mut service).await;
+        let result = command.exec(&service).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_assign_same_tax_twice() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a tax and an item
         let tax = CreateTaxCommand {
@@ -608,11 +611,11 @@ mod tests {
                 item_ids: None,
             },
         }
-        .exec(&mut service)
+        .exec(&service)
         .await
         .unwrap();
 
-        let item = create_test_item(&mut service).await;
+        let item = create_test_item(&service).await;
 
         // Assign tax to item first time
         let command = AssignTaxToItemCommand {
@@ -621,16 +624,16 @@ mod tests {
                 tax_id: tax.id,
             },
         };
-        command.exec(&mut service).await.unwrap();
+        command.exec(&service).await.unwrap();
 
         // Try to assign same tax again
-        let result = command.exec(&mut service).await;
+        let result = command.exec(&service).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_remove_tax_from_item() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a tax and an item
         let tax = CreateTaxCommand {
@@ -641,11 +644,11 @@ mod tests {
                 item_ids: None,
             },
         }
-        .exec(&mut service)
+        .exec(&service)
         .await
         .unwrap();
 
-        let item = create_test_item(&mut service).await;
+        let item = create_test_item(&service).await;
 
         // First assign tax to item
         AssignTaxToItemCommand {
@@ -654,7 +657,7 @@ mod tests {
                 tax_id: tax.id,
             },
         }
-        .exec(&mut service)
+        .exec(&service)
         .await
         .unwrap();
 
@@ -664,20 +667,20 @@ mod tests {
             tax_id: tax.id,
         };
 
-        let result = command.exec(&mut service).await.unwrap();
+        let result = command.exec(&service).await.unwrap();
         assert_eq!(result, 1);
     }
 
     #[tokio::test]
     async fn test_remove_nonexistent_tax_assignment() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let command = RemoveTaxFromItemCommand {
             item_id: Uuid::now_v7().into(),
             tax_id: Uuid::now_v7().into(),
         };
 
-        let result = command.exec(&mut service).await;
+        let result = command.exec(&service).await;
         assert!(result.is_err());
     }
 }
@@ -42,7 +42,7 @@ pub struct RemoveTaxFromGroupCommand {
 impl Command for CreateTaxGroupCommand {
     type Output = TaxGroup;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Verify all taxes exist if tax_ids are provided
         if let Some(tax_ids) = &self.tax_group.tax_ids {
             for tax_id in tax_ids {
@@ -123,7 +123,7 @@ impl Command for CreateTaxGroupCommand {
 impl Command for UpdateTaxGroupCommand {
     type Output = TaxGroup;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Check if the tax group exists
         let mut query_builder = Query::select();
         let select_stmt = query_builder
@@ -185,7 +185,7 @@ impl Command for UpdateTaxGroupCommand {
 impl Command for DeleteTaxGroupCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Check if the tax group is used in any sales order charges
         // Define a temporary Iden for the sales_order_charges table
         #[derive(Iden)]
@@ -229,7 +229,7 @@ impl Command for DeleteTaxGroupCommand {
 impl Command for AssignTaxToGroupCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Verify the tax group exists
         let mut tax_group_query_builder = Query::select();
         let tax_group_stmt = tax_group_query_builder
@@ -295,7 +295,7 @@ impl Command for AssignTaxToGroupCommand {
 impl Command for RemoveTaxFromGroupCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let mut delete_query = Query::delete();
         let delete_stmt = delete_query
             .from_table(TaxGroupTaxes::Table)
@@ -322,7 +322,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_tax_group() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a tax first
         let tax_input = TaxNewInput {
@@ -332,7 +332,7 @@ mod tests {
             item_ids: None,
         };
         let tax = CreateTaxCommand { tax: tax_input }
-            .exec(&mut service)
+            .exec(&service)
             .await
             .unwrap();
 
@@ -346,7 +346,7 @@ mod tests {
         let tax_group = CreateTaxGroupCommand {
             tax_group: tax_group_input,
         }
-        .exec(&mut service)
+        .exec(&service)
         .await
         .unwrap();
 
@@ -359,7 +359,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_tax_group() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a tax group first
         let tax_group_input = TaxGroupNewInput {
@@ -371,7 +371,7 @@ mod tests {
         let tax_group = CreateTaxGroupCommand {
             tax_group: tax_group_input,
         }
-        .exec(&mut service)
+        .exec(&service)
         .await
         .unwrap();
 
@@ -385,7 +385,7 @@ mod tests {
         let updated_tax_group = UpdateTaxGroupCommand {
             tax_group: update_input,
         }
-        .exec(&mut service)
+        .exec(&service)
         .await
         .unwrap();
 
@@ -398,7 +398,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_delete_tax_group() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a tax group first
         let tax_group_input = TaxGroupNewInput {
@@ -410,13 +410,13 @@ mod tests {
         let tax_group = CreateTaxGroupCommand {
             tax_group: tax_group_input,
         }
-        .exec(&mut service)
+        .exec(&service)
         .await
         .unwrap();
 
         // Delete the tax group
         let result = DeleteTaxGroupCommand { id: tax_group.id }
-            .exec(&mut service)
+            .exec(&service)
             .await
             .unwrap();
 
@@ -425,7 +425,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_assign_tax_to_group() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a tax
         let tax_input = TaxNewInput {
@@ -435,7 +435,7 @@ mod tests {
             item_ids: None,
         };
         let tax = CreateTaxCommand { tax: tax_input }
-            .exec(&mut service)
+            .exec(&service)
             .await
             .unwrap();
 
@@ -449,7 +449,7 @@ mod tests {
         let tax_group = CreateTaxGroupCommand {
             tax_group: tax_group_input,
         }
-        .exec(&mut service)
+        .exec(&service)
         .await
         .unwrap();
 
@@ -458,7 +458,7 @@ mod tests {
             tax_group_id: tax_group.id,
             tax_id: tax.id,
         }
-        .exec(&mut service)
+        .exec(&service)
         .await
         .unwrap();
 
@@ -467,7 +467,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_remove_tax_from_group() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a tax
         let tax_input = TaxNewInput {
@@ -477,7 +477,7 @@ mod tests {
             item_ids: None,
         };
         let tax = CreateTaxCommand { tax: tax_input }
-            .exec(&mut service)
+            .exec(&service)
             .await
             .unwrap();
 
@@ -491,7 +491,7 @@ mod tests {
         let tax_group = CreateTaxGroupCommand {
             tax_group: tax_group_input,
         }
-        .exec(&mut service)
+        .exec(&service)
         .await
         .unwrap();
 
@@ -500,7 +500,7 @@ mod tests {
             tax_group_id: tax_group.id,
             tax_id: tax.id,
         }
-        .exec(&mut service)
+        .exec(&service)
         .await
         .unwrap();
 
@@ -0,0 +1,197 @@
+use chrono::Utc;
+use sea_query::{Expr, Iden, Order, Query};
+use uuid::Uuid;
+
+use crate::{
+    adapters::outgoing::database::DatabaseAdapter,
+    core::{
+        commands::{app_service::AppService, Command},
+        db::SeaQueryCrudTrait,
+        models::{
+            catalog::item_group_model::ItemCategories,
+            common::tax_model::Taxes,
+            finance::{
+                account_mapping_model::{AccountMapping, AccountMappingInput, AccountMappingKind, AccountMappings},
+                payment_method_model::PaymentMethods,
+            },
+            purchases::purchase_category_model::PurchaseCategories,
+            sales::sales_charge_type_model::SalesChargeTypes,
+        },
+        types::db_uuid::DbUuid,
+    },
+    error::{Error, Result},
+};
+
+// Commands
+pub struct SetAccountMappingCommand {
+    pub mapping: AccountMappingInput,
+}
+
+pub struct DeleteAccountMappingCommand {
+    pub id: DbUuid,
+}
+
+pub struct ListAccountMappingsCommand;
+
+// Command Implementations
+impl Command for SetAccountMappingCommand {
+    type Output = AccountMapping;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let input = &self.mapping;
+        if input.account_code.trim().is_empty() || input.account_name.trim().is_empty() {
+            return Err(Error::ValidationError("An account needs a code and a name".to_string()));
+        }
+        if let Some(source_id) = input.source_id {
+            let query = match input.kind {
+                AccountMappingKind::Revenue => exists(ItemCategories::Table, ItemCategories::Id, source_id),
+                AccountMappingKind::TaxPayable => exists(Taxes::Table, Taxes::Id, source_id),
+                AccountMappingKind::Charges => exists(SalesChargeTypes::Table, SalesChargeTypes::Id, source_id),
+                AccountMappingKind::PaymentClearing => exists(PaymentMethods::Table, PaymentMethods::Id, source_id),
+                AccountMappingKind::Expense => exists(PurchaseCategories::Table, PurchaseCategories::Id, source_id),
+                kind => {
+                    return Err(Error::ValidationError(format!("{} accounts are mapped for the whole kind", kind)));
+                }
+            };
+            if service.db_adapter.query_optional::<DbUuid>(&query).await?.is_none() {
+                return Err(Error::NotFoundError);
+            }
+        }
+
+        let mut query = Query::select();
+        query
+            .from(AccountMappings::Table)
+            .columns(AccountMappings::all_columns())
+            .and_where(Expr::col(AccountMappings::Kind).eq(input.kind.to_string()));
+        match input.source_id {
+            Some(source_id) => query.and_where(Expr::col(AccountMappings::SourceId).eq(source_id.to_string())),
+            None => query.and_where(Expr::col(AccountMappings::SourceId).is_null()),
+        };
+        let existing = service.db_adapter.query_optional::<AccountMapping>(&query).await?;
+
+        let now = Utc::now().naive_utc();
+        let mapping = match existing {
+            Some(existing) => {
+                let mapping = AccountMapping {
+                    account_code: input.account_code.trim().to_string(),
+                    account_name: input.account_name.trim().to_string(),
+                    updated_at: now,
+                    ..existing
+                };
+                service.db_adapter.update_many(&mapping.update()).await?;
+                mapping
+            }
+            None => {
+                let mapping = AccountMapping {
+                    id: Uuid::now_v7().into(),
+                    kind: input.kind,
+                    source_id: input.source_id,
+                    account_code: input.account_code.trim().to_string(),
+                    account_name: input.account_name.trim().to_string(),
+                    created_at: now,
+                    updated_at: now,
+                };
+                service.db_adapter.insert_many(&mapping.insert()).await?;
+                mapping
+            }
+        };
+
+        Ok(mapping)
+    }
+}
+
+impl Command for DeleteAccountMappingCommand {
+    type Output = i32;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let mut delete_query = Query::delete();
+        let delete_stmt = delete_query
+            .from_table(AccountMappings::Table)
+            .and_where(Expr::col(AccountMappings::Id).eq(self.id.to_string()));
+
+        let result = service.db_adapter.delete(delete_stmt).await?;
+        if result == 0 {
+            return Err(Error::NotFoundError);
+        }
+
+        Ok(result as i32)
+    }
+}
+
+impl Command for ListAccountMappingsCommand {
+    type Output = Vec<AccountMapping>;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let mut query = Query::select();
+        query
+            .from(AccountMappings::Table)
+            .columns(AccountMappings::all_columns())
+            .order_by(AccountMappings::Kind, Order::Asc)
+            .order_by(AccountMappings::AccountCode, Order::Asc);
+
+        service.db_adapter.query_many::<AccountMapping>(&query).await
+    }
+}
+
+fn exists<T: Iden + 'static, C: Iden + Copy + 'static>(table: T, id: C, value: DbUuid) -> sea_query::SelectStatement {
+    Query::select().column(id).from(table).and_where(Expr::col(id).eq(value.to_string())).to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        commands::{finance::payment_method_commands::CreatePaymentMethodCommand, tests::setup_service},
+        models::finance::payment_method_model::PaymentMethodNewInput,
+    };
+
+    fn mapping(kind: AccountMappingKind, source_id: Option<DbUuid>, code: &str) -> SetAccountMappingCommand {
+        SetAccountMappingCommand {
+            mapping: AccountMappingInput {
+                kind,
+                source_id,
+                account_code: code.to_string(),
+                account_name: format!("Account {}", code),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_account_mapping_replaces_the_account() {
+        let service = setup_service().await;
+        let command = CreatePaymentMethodCommand {
+            payment_method: PaymentMethodNewInput {
+                name: "Card".to_string(),
+                code: "CARD".to_string(),
+                description: None,
+                state: None,
+            },
+        };
+        let card = command.exec(&service).await.unwrap();
+
+        let default = mapping(AccountMappingKind::PaymentClearing, None, "1000").exec(&service).await.unwrap();
+        let first = mapping(AccountMappingKind::PaymentClearing, Some(card.id), "1020").exec(&service).await.unwrap();
+        let second = mapping(AccountMappingKind::PaymentClearing, Some(card.id), "1030").exec(&service).await.unwrap();
+        assert_eq!(first.id, second.id);
+
+        let mappings = ListAccountMappingsCommand.exec(&service).await.unwrap();
+        let codes: Vec<_> = mappings.iter().map(|mapping| (mapping.id, mapping.account_code.as_str())).collect();
+        assert_eq!(codes, [(default.id, "1000"), (second.id, "1030")]);
+
+        assert_eq!(DeleteAccountMappingCommand { id: first.id }.exec(&service).await.unwrap(), 1);
+        let result = DeleteAccountMappingCommand { id: first.id }.exec(&service).await;
+        assert!(matches!(result, Err(Error::NotFoundError)));
+    }
+
+    #[tokio::test]
+    async fn test_set_account_mapping_validates_input() {
+        let service = setup_service().await;
+
+        let result = mapping(AccountMappingKind::Receivable, Some(Uuid::now_v7().into()), "1100").exec(&service).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+        let result = mapping(AccountMappingKind::TaxPayable, Some(Uuid::now_v7().into()), "2200").exec(&service).await;
+        assert!(matches!(result, Err(Error::NotFoundError)));
+        let result = mapping(AccountMappingKind::Revenue, None, " ").exec(&service).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+}
@@ -6,6 +6,7 @@ use crate::{
     adapters::outgoing::database::DatabaseAdapter,
     core::{
         commands::{app_service::AppService, Command},
+        db::soft_delete::{purge, restore, soft_delete},
         models::finance::cost_center_model::{
             CostCenter, CostCenterNewInput, CostCenterState, CostCenterUpdateInput, CostCenters,
         },
@@ -25,13 +26,22 @@ pub struct UpdateCostCenterCommand {
 
 pub struct DeleteCostCenterCommand {
     pub id: DbUuid,
+    pub deleted_by: Option<DbUuid>,
+}
+
+pub struct RestoreCostCenterCommand {
+    pub id: DbUuid,
+}
+
+pub struct PurgeCostCenterCommand {
+    pub id: DbUuid,
 }
 
 // Command Implementations
 impl Command for CreateCostCenterCommand {
     type Output = CostCenter;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let new_id = Uuid::now_v7();
 
@@ -43,6 +53,8 @@ impl Command for CreateCostCenterCommand {
             state: self.cost_center.state.unwrap_or(CostCenterState::Active),
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
         };
 
         // Build the insert query with SeaQuery
@@ -79,7 +91,7 @@ impl Command for CreateCostCenterCommand {
 impl Command for UpdateCostCenterCommand {
     type Output = CostCenter;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let cost_center_id = self.cost_center.id;
 
@@ -141,30 +153,41 @@ impl Command for UpdateCostCenterCommand {
 impl Command for DeleteCostCenterCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        // Build the delete query with SeaQuery
-        let mut delete_query = Query::delete();
-        let delete_stmt = delete_query
-            .from_table(CostCenters::Table)
-            .and_where(Expr::col(CostCenters::Id).eq(self.id.to_string()));
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        soft_delete::<CostCenter, _>(&service.db_adapter, self.id, self.deleted_by).await?;
 
-        // Execute the query
-        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+        Ok(1)
+    }
+}
+
+impl Command for RestoreCostCenterCommand {
+    type Output = CostCenter;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        restore(&service.db_adapter, self.id).await
+    }
+}
+
+impl Command for PurgeCostCenterCommand {
+    type Output = i32;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        purge::<CostCenter, _>(&service.db_adapter, self.id).await?;
 
-        Ok(affected_rows as i32)
+        Ok(1)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::core::commands::tests::setup_service;
+    use crate::{core::commands::tests::setup_service, error::Error};
 
     use super::*;
     use sea_query::{Expr, Func, Query};
 
     #[tokio::test]
     async fn test_create_cost_center() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let command = CreateCostCenterCommand {
             cost_center: CostCenterNewInput {
@@ -175,7 +198,7 @@ mod tests {
             },
         };
 
-        let cost_center = command.exec(&mut service).await.unwrap();
+        let cost_center = command.exec(&service).await.unwrap();
         assert_eq!(cost_center.name, "Operations");
         assert_eq!(cost_center.code, "OPS");
         assert_eq!(
@@ -187,7 +210,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_cost_center_minimal() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let command = CreateCostCenterCommand {
             cost_center: CostCenterNewInput {
@@ -198,7 +221,7 @@ mod tests {
             },
         };
 
-        let cost_center = command.exec(&mut service).await.unwrap();
+        let cost_center = command.exec(&service).await.unwrap();
         assert_eq!(cost_center.name, "IT");
         assert_eq!(cost_center.code, "IT");
         assert_eq!(cost_center.description, None);
@@ -207,7 +230,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_cost_center() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create cost center
         let create_command = CreateCostCenterCommand {
@@ -219,7 +242,7 @@ mod tests {
             },
         };
 
-        let cost_center = create_command.exec(&mut service).await.unwrap();
+        let cost_center = create_command.exec(&service).await.unwrap();
 
         // Update cost center
         let update_command = UpdateCostCenterCommand {
@@ -232,7 +255,7 @@ mod tests {
             },
         };
 
-        let updated_cost_center = update_command.exec(&mut service).await.unwrap();
+        let updated_cost_center = update_command.exec(&service).await.unwrap();
         assert_eq!(updated_cost_center.name, "Sales & Marketing");
         assert_eq!(updated_cost_center.code, "S&M");
         assert_eq!(
@@ -244,7 +267,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_cost_center_remove_field() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create cost center
         let create_command = CreateCostCenterCommand {
@@ -256,7 +279,7 @@ mod tests {
             },
         };
 
-        let cost_center = create_command.exec(&mut service).await.unwrap();
+        let cost_center = create_command.exec(&service).await.unwrap();
 
         // Update cost center - remove description
         let update_command = UpdateCostCenterCommand {
@@ -269,7 +292,7 @@ mod tests {
             },
         };
 
-        let updated_cost_center = update_command.exec(&mut service).await.unwrap();
+        let updated_cost_center = update_command.exec(&service).await.unwrap();
         assert_eq!(updated_cost_center.name, "Marketing"); // Unchanged
         assert_eq!(updated_cost_center.code, "MKT"); // Unchanged
         assert_eq!(updated_cost_center.description, None); // Removed
@@ -278,7 +301,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_nonexistent_cost_center() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let update_command = UpdateCostCenterCommand {
             cost_center: CostCenterUpdateInput {
@@ -290,13 +313,13 @@ mod tests {
             },
         };
 
-        let result = update_command.exec(&mut service).await;
+        let result = update_command.exec(&service).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_delete_cost_center() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create cost center
         let create_command = CreateCostCenterCommand {
@@ -308,19 +331,20 @@ mod tests {
             },
         };
 
-        let cost_center = create_command.exec(&mut service).await.unwrap();
+        let cost_center = create_command.exec(&service).await.unwrap();
 
         // Delete cost center
-        let delete_command = DeleteCostCenterCommand { id: cost_center.id };
-        let result = delete_command.exec(&mut service).await.unwrap();
+        let delete_command = DeleteCostCenterCommand { id: cost_center.id, deleted_by: None };
+        let result = delete_command.exec(&service).await.unwrap();
         assert_eq!(result, 1);
 
-        // Verify cost center no longer exists
+        // Verify cost center is no longer listed
         let mut count_query_builder = Query::select();
         let count_stmt = count_query_builder
             .from(CostCenters::Table)
             .expr(Func::count(Expr::col(CostCenters::Id)))
-            .and_where(Expr::col(CostCenters::Id).eq(cost_center.id.to_string()));
+            .and_where(Expr::col(CostCenters::Id).eq(cost_center.id.to_string()))
+            .and_where(Expr::col(CostCenters::DeletedAt).is_null());
 
         let count: i64 = service.db_adapter.query_one(&count_stmt).await.unwrap();
         assert_eq!(count, 0);
@@ -328,13 +352,14 @@ mod tests {
 
     #[tokio::test]
     async fn test_delete_nonexistent_cost_center() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Delete non-existent cost center
         let delete_command = DeleteCostCenterCommand {
             id: Uuid::now_v7().into(),
+            deleted_by: None,
         };
-        let result = delete_command.exec(&mut service).await.unwrap();
-        assert_eq!(result, 0); // No rows affected
+        let result = delete_command.exec(&service).await;
+        assert!(matches!(result, Err(Error::NotFoundError)));
     }
 }
@@ -0,0 +1,453 @@
+use chrono::Utc;
+use sea_query::{Alias, Expr, Query};
+use uuid::Uuid;
+
+use crate::{
+    adapters::outgoing::database::DatabaseAdapter,
+    core::{
+        commands::{app_service::AppService, Command},
+        models::{
+            finance::{
+                currency_model::{Currencies, Currency, CurrencyNewInput, CurrencyUpdateInput},
+                exchange_rate_model::ExchangeRates,
+                sales_order_payment_model::SalesOrderPayments,
+            },
+            sales::sales_order_model::SalesOrders,
+        },
+        types::db_uuid::DbUuid,
+    },
+    error::{Constraint, Error, Result},
+};
+
+/// Returns the currency that orders are priced and reported in
+pub async fn base_currency(service: &AppService) -> Result<Currency> {
+    let mut query = Query::select();
+    let stmt = query
+        .from(Currencies::Table)
+        .columns(Currencies::all_columns())
+        .and_where(Expr::col(Currencies::IsBase).eq(true.to_string()));
+
+    service.db_adapter.query_one::<Currency>(&stmt).await
+}
+
+/// Looks up an active currency by its ISO code
+pub async fn currency_by_code(service: &AppService, code: &str) -> Result<Currency> {
+    let currency = find_currency_by_code(service, code).await?;
+    if !currency.is_active {
+        return Err(Error::ValidationError(format!("Currency '{}' is not active", code)));
+    }
+    Ok(currency)
+}
+
+/// Looks up a currency by its ISO code whether or not it is active, for reading what was
+/// recorded in it
+pub async fn find_currency_by_code(service: &AppService, code: &str) -> Result<Currency> {
+    let mut query = Query::select();
+    let stmt = query
+        .from(Currencies::Table)
+        .columns(Currencies::all_columns())
+        .and_where(Expr::col(Currencies::Code).eq(code.to_uppercase()));
+
+    service.db_adapter.query_optional::<Currency>(&stmt).await?.ok_or(Error::NotFoundError)
+}
+
+// Commands
+pub struct CreateCurrencyCommand {
+    pub currency: CurrencyNewInput,
+}
+
+pub struct UpdateCurrencyCommand {
+    pub currency: CurrencyUpdateInput,
+}
+
+pub struct DeleteCurrencyCommand {
+    pub id: DbUuid,
+}
+
+/// Makes another currency the base currency. Orders and exchange rates are recorded against
+/// the base currency, so it is refused once there are any.
+pub struct SetBaseCurrencyCommand {
+    pub id: DbUuid,
+}
+
+// Command Implementations
+impl Command for CreateCurrencyCommand {
+    type Output = Currency;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let code = self.currency.code.trim().to_uppercase();
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(Error::ValidationError(format!("'{}' is not a three letter currency code", code)));
+        }
+
+        if !(0..=4).contains(&self.currency.minor_units) {
+            return Err(Error::ValidationError("Minor units must be between 0 and 4".to_string()));
+        }
+
+        let mut check_query = Query::select();
+        let check_stmt = check_query
+            .from(Currencies::Table)
+            .column(Currencies::Id)
+            .and_where(Expr::col(Currencies::Code).eq(code.clone()));
+
+        let existing = service.db_adapter.query_optional::<DbUuid>(&check_stmt).await?;
+        if existing.is_some() {
+            return Err(Error::UniqueConstraintError(Constraint::new("currencies", "code")));
+        }
+
+        let now = Utc::now().naive_utc();
+        let new_currency = Currency {
+            id: Uuid::now_v7().into(),
+            code,
+            name: self.currency.name.clone(),
+            symbol: self.currency.symbol.clone(),
+            minor_units: self.currency.minor_units,
+            is_base: false,
+            is_active: self.currency.is_active.unwrap_or(true),
+            created_at: now,
+            updated_at: now,
+        };
+
+        // Build the insert query with SeaQuery
+        let mut insert_query = Query::insert();
+        let insert_stmt = insert_query
+            .into_table(Currencies::Table)
+            .columns([
+                Currencies::Id,
+                Currencies::Code,
+                Currencies::Name,
+                Currencies::Symbol,
+                Currencies::MinorUnits,
+                Currencies::IsBase,
+                Currencies::IsActive,
+                Currencies::CreatedAt,
+                Currencies::UpdatedAt,
+            ])
+            .values_panic([
+                new_currency.id.to_string().into(),
+                new_currency.code.clone().into(),
+                new_currency.name.clone().into(),
+                new_currency.symbol.clone().into(),
+                new_currency.minor_units.into(),
+                new_currency.is_base.to_string().into(),
+                new_currency.is_active.to_string().into(),
+                now.to_string().into(),
+                now.to_string().into(),
+            ]);
+
+        service.db_adapter.insert_many(&insert_stmt).await?;
+
+        Ok(new_currency)
+    }
+}
+
+impl Command for UpdateCurrencyCommand {
+    type Output = Currency;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let now = Utc::now().naive_utc();
+        let currency_id = self.currency.id;
+
+        // First, check if the currency exists
+        let mut select_query = Query::select();
+        let select_stmt = select_query
+            .from(Currencies::Table)
+            .columns(Currencies::all_columns())
+            .and_where(Expr::col(Currencies::Id).eq(currency_id.to_string()));
+
+        let existing = service
+            .db_adapter
+            .query_optional::<Currency>(&select_stmt)
+            .await?
+            .ok_or(Error::NotFoundError)?;
+
+        if existing.is_base && self.currency.is_active == Some(false) {
+            return Err(Error::ValidationError("The base currency cannot be deactivated".to_string()));
+        }
+
+        // Build the update query with SeaQuery
+        let mut update_query = Query::update();
+        let update_stmt = update_query.table(Currencies::Table);
+
+        if let Some(name) = &self.currency.name {
+            update_stmt.value(Currencies::Name, name.clone());
+        }
+
+        if let Some(symbol) = &self.currency.symbol {
+            match symbol {
+                Some(sym) => update_stmt.value(Currencies::Symbol, sym.clone()),
+                None => update_stmt.value(Currencies::Symbol, sea_query::Value::String(None)),
+            };
+        }
+
+        if let Some(is_active) = self.currency.is_active {
+            update_stmt.value(Currencies::IsActive, is_active.to_string());
+        }
+
+        // Always update the updated_at timestamp
+        update_stmt.value(Currencies::UpdatedAt, now.to_string());
+
+        update_stmt.and_where(Expr::col(Currencies::Id).eq(currency_id.to_string()));
+
+        service.db_adapter.update_one::<Currency>(&update_stmt).await?;
+
+        let updated_currency = service.db_adapter.query_one::<Currency>(&select_stmt).await?;
+
+        Ok(updated_currency)
+    }
+}
+
+impl Command for DeleteCurrencyCommand {
+    type Output = bool;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let mut select_query = Query::select();
+        let select_stmt = select_query
+            .from(Currencies::Table)
+            .columns(Currencies::all_columns())
+            .and_where(Expr::col(Currencies::Id).eq(self.id.to_string()));
+
+        let currency = match service.db_adapter.query_optional::<Currency>(&select_stmt).await? {
+            Some(currency) => currency,
+            None => return Ok(false),
+        };
+
+        if currency.is_base {
+            return Err(Error::ValidationError("The base currency cannot be deleted".to_string()));
+        }
+
+        // Currencies that orders or payments were recorded in are kept; deactivate them instead
+        let mut order_count_query = Query::select();
+        let order_count_stmt = order_count_query
+            .from(SalesOrders::Table)
+            .expr_as(Expr::col(SalesOrders::Id).count(), Alias::new("count"))
+            .and_where(Expr::col(SalesOrders::CurrencyCode).eq(currency.code.clone()));
+
+        let mut payment_count_query = Query::select();
+        let payment_count_stmt = payment_count_query
+            .from(SalesOrderPayments::Table)
+            .expr_as(Expr::col(SalesOrderPayments::Id).count(), Alias::new("count"))
+            .and_where(Expr::col(SalesOrderPayments::CurrencyCode).eq(currency.code.clone()));
+
+        let order_count = service.db_adapter.query_one::<i64>(&order_count_stmt).await?;
+        let payment_count = service.db_adapter.query_one::<i64>(&payment_count_stmt).await?;
+        if order_count > 0 || payment_count > 0 {
+            return Err(Error::HasChildrenError);
+        }
+
+        let mut delete_query = Query::delete();
+        let delete_stmt = delete_query
+            .from_table(Currencies::Table)
+            .and_where(Expr::col(Currencies::Id).eq(self.id.to_string()));
+
+        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+
+        Ok(affected_rows > 0)
+    }
+}
+
+impl Command for SetBaseCurrencyCommand {
+    type Output = Currency;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let mut select_query = Query::select();
+        let select_stmt = select_query
+            .from(Currencies::Table)
+            .columns(Currencies::all_columns())
+            .and_where(Expr::col(Currencies::Id).eq(self.id.to_string()));
+
+        let currency = service
+            .db_adapter
+            .query_optional::<Currency>(select_stmt)
+            .await?
+            .ok_or(Error::NotFoundError)?;
+        if currency.is_base {
+            return Ok(currency);
+        }
+        if !currency.is_active {
+            return Err(Error::ValidationError(format!("Currency '{}' is not active", currency.code)));
+        }
+
+        let mut order_count_query = Query::select();
+        let order_count_stmt = order_count_query
+            .from(SalesOrders::Table)
+            .expr_as(Expr::col(SalesOrders::Id).count(), Alias::new("count"));
+        if service.db_adapter.query_one::<i64>(order_count_stmt).await? > 0 {
+            return Err(Error::ValidationError(
+                "The base currency cannot be changed once orders have been recorded".to_string(),
+            ));
+        }
+
+        let mut rate_count_query = Query::select();
+        let rate_count_stmt = rate_count_query
+            .from(ExchangeRates::Table)
+            .expr_as(Expr::col(ExchangeRates::Id).count(), Alias::new("count"));
+        if service.db_adapter.query_one::<i64>(rate_count_stmt).await? > 0 {
+            return Err(Error::ValidationError(
+                "Exchange rates are quoted in the base currency; delete them before changing it".to_string(),
+            ));
+        }
+
+        let now = Utc::now().naive_utc();
+        service
+            .db_adapter
+            .transaction(|| async {
+                let mut clear_query = Query::update();
+                let clear_stmt = clear_query
+                    .table(Currencies::Table)
+                    .value(Currencies::IsBase, false.to_string())
+                    .value(Currencies::UpdatedAt, now.to_string())
+                    .and_where(Expr::col(Currencies::IsBase).eq(true.to_string()));
+                service.db_adapter.update_many(clear_stmt).await?;
+
+                let mut set_query = Query::update();
+                let set_stmt = set_query
+                    .table(Currencies::Table)
+                    .value(Currencies::IsBase, true.to_string())
+                    .value(Currencies::UpdatedAt, now.to_string())
+                    .and_where(Expr::col(Currencies::Id).eq(self.id.to_string()));
+                service.db_adapter.update_one::<Currency>(set_stmt).await?;
+                Ok(())
+            })
+            .await?;
+
+        service.db_adapter.query_one::<Currency>(select_stmt).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::commands::{
+        finance::sales_order_payment_commands::tests::create_test_sales_order, tests::setup_service,
+    };
+
+    fn yen_input() -> CurrencyNewInput {
+        CurrencyNewInput {
+            code: "jpy".to_string(),
+            name: "Japanese Yen".to_string(),
+            symbol: Some("¥".to_string()),
+            minor_units: 0,
+            is_active: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_base_currency_is_seeded() {
+        let service = setup_service().await;
+
+        let base = base_currency(&service).await.unwrap();
+
+        assert_eq!(base.code, "INR");
+        assert_eq!(base.precision(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_create_currency() {
+        let service = setup_service().await;
+
+        let currency = CreateCurrencyCommand { currency: yen_input() }
+            .exec(&service)
+            .await
+            .unwrap();
+
+        assert_eq!(currency.code, "JPY");
+        assert_eq!(currency.minor_units, 0);
+        assert!(!currency.is_base);
+
+        let duplicate = CreateCurrencyCommand { currency: yen_input() }.exec(&service).await;
+        assert!(matches!(duplicate, Err(Error::UniqueConstraintError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_currency_validation() {
+        let service = setup_service().await;
+
+        let bad_code = CreateCurrencyCommand {
+            currency: CurrencyNewInput {
+                code: "YEN1".to_string(),
+                ..yen_input()
+            },
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(bad_code, Err(Error::ValidationError(_))));
+
+        let bad_precision = CreateCurrencyCommand {
+            currency: CurrencyNewInput {
+                minor_units: 6,
+                ..yen_input()
+            },
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(bad_precision, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_base_currency_cannot_be_deactivated_or_deleted() {
+        let service = setup_service().await;
+        let base = base_currency(&service).await.unwrap();
+
+        let deactivate = UpdateCurrencyCommand {
+            currency: CurrencyUpdateInput {
+                id: base.id,
+                name: None,
+                symbol: None,
+                is_active: Some(false),
+            },
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(deactivate, Err(Error::ValidationError(_))));
+
+        let delete = DeleteCurrencyCommand { id: base.id }.exec(&service).await;
+        assert!(matches!(delete, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_inactive_currency_is_rejected() {
+        let service = setup_service().await;
+
+        let currency = CreateCurrencyCommand { currency: yen_input() }
+            .exec(&service)
+            .await
+            .unwrap();
+        UpdateCurrencyCommand {
+            currency: CurrencyUpdateInput {
+                id: currency.id,
+                name: None,
+                symbol: None,
+                is_active: Some(false),
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let result = currency_by_code(&service, "JPY").await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+        // What was recorded in it can still be read
+        assert_eq!(find_currency_by_code(&service, "JPY").await.unwrap().precision(), 0);
+
+        assert!(DeleteCurrencyCommand { id: currency.id }.exec(&service).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_set_base_currency_until_orders_are_recorded() {
+        let service = setup_service().await;
+        let rupee = base_currency(&service).await.unwrap();
+        let yen = CreateCurrencyCommand { currency: yen_input() }.exec(&service).await.unwrap();
+
+        let base = SetBaseCurrencyCommand { id: yen.id }.exec(&service).await.unwrap();
+        assert!(base.is_base);
+        assert_eq!(base_currency(&service).await.unwrap().code, "JPY");
+
+        let order = create_test_sales_order(&service).await;
+        assert_eq!(order.currency_code, "JPY");
+
+        let result = SetBaseCurrencyCommand { id: rupee.id }.exec(&service).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+        assert_eq!(base_currency(&service).await.unwrap().code, "JPY");
+    }
+}
@@ -0,0 +1,1033 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use chrono::NaiveDateTime;
+use lightning_macros::LibsqlFromRow;
+use sea_query::{Alias, Cond, Expr, Order, Query, SelectStatement};
+
+use crate::{
+    adapters::outgoing::database::{DatabaseAdapter, FromLibsqlValue, FromRow},
+    core::{
+        commands::{app_service::AppService, Command},
+        models::{
+            common::{channel_model::Channels, location_model::Locations},
+            finance::{
+                cost_center_model::CostCenters,
+                data_export_model::{DataExport, DataExportInput, ExportDataset, ExportFormat},
+                payment_method_model::PaymentMethods,
+                sales_order_payment_model::{SalesOrderPaymentState as PaymentState, SalesOrderPayments},
+            },
+            purchases::{expense_model::Expenses, purchase_category_model::PurchaseCategories},
+            sales::{
+                sales_order_charge_model::SalesOrderCharges,
+                sales_order_item_model::SalesOrderItems,
+                sales_order_model::{SalesOrderPaymentState, SalesOrderState, SalesOrders},
+            },
+        },
+        types::{db_uuid::DbUuid, local_date_time, money::Money, rate::Rate},
+        utils::{
+            csv,
+            export::ExportConfig,
+            xlsx::{self, Cell},
+        },
+    },
+    error::{Error, Result},
+};
+
+/// Rows read from the database at a time, so that long ranges are never held in memory.
+/// Small in tests so that paging is exercised.
+#[cfg(not(test))]
+const BATCH_SIZE: u64 = 500;
+#[cfg(test)]
+const BATCH_SIZE: u64 = 2;
+
+// Commands
+
+/// Writes the orders, payments or expenses of a date range to a file. The file appears once
+/// it is complete; until then it is written next to it with a `.partial` suffix.
+pub struct ExportDataCommand {
+    pub export: DataExportInput,
+    /// The directory `export.path` is in
+    pub config: ExportConfig,
+}
+
+// Command Implementations
+impl Command for ExportDataCommand {
+    type Output = DataExport;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let export = &self.export;
+        if export.from >= export.to {
+            return Err(Error::ValidationError("The range must end after it starts".to_string()));
+        }
+        if export.dataset == ExportDataset::Expenses && export.location_id.is_some() {
+            return Err(Error::ValidationError(
+                "Expenses are not recorded by location; filter them by cost center".to_string(),
+            ));
+        }
+        let extension = match export.format {
+            ExportFormat::Csv => "csv",
+            ExportFormat::JsonLines => "jsonl",
+            ExportFormat::Xlsx => "xlsx",
+        };
+        let path = Path::new(&export.path);
+        if path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() != Some(extension) {
+            return Err(Error::ValidationError(format!("The file name must end in .{}", extension)));
+        }
+        let path = self.config.resolve(&export.path)?;
+
+        let partial = path.with_extension(format!("{}.partial", extension));
+        let written = match export.dataset {
+            ExportDataset::SalesOrders => write::<OrderRow>(service, export, &partial).await,
+            ExportDataset::SalesOrderItems => write::<OrderItemRow>(service, export, &partial).await,
+            ExportDataset::SalesOrderCharges => write::<OrderChargeRow>(service, export, &partial).await,
+            ExportDataset::SalesOrderPayments => write::<PaymentRow>(service, export, &partial).await,
+            ExportDataset::Expenses => write::<ExpenseRow>(service, export, &partial).await,
+        };
+        let rows = match written {
+            Ok(rows) => rows,
+            Err(err) => {
+                let _ = fs::remove_file(&partial);
+                return Err(err);
+            }
+        };
+        fs::rename(&partial, &path)?;
+
+        Ok(DataExport { path: path.to_string_lossy().into_owned(), rows })
+    }
+}
+
+/// A row of an export, read with the statement it is selected by
+trait ExportRow: FromRow<libsql::Row> {
+    const SHEET: &'static str;
+    const COLUMNS: &'static [&'static str];
+
+    /// Every row, before the range and filters are applied
+    fn select() -> SelectStatement;
+    fn date() -> Expr;
+    fn id() -> Expr;
+    fn location() -> Option<Expr>;
+    fn cost_center() -> Expr;
+
+    /// Where the row is in the export, which is ordered by date and then id
+    fn key(&self) -> (NaiveDateTime, DbUuid);
+    fn cells(&self) -> Vec<Cell>;
+}
+
+async fn write<T: ExportRow>(service: &AppService, export: &DataExportInput, path: &Path) -> Result<i32> {
+    let mut sink = Sink::create(export.format, path, T::SHEET)?;
+    sink.header(T::COLUMNS)?;
+
+    let mut count = 0;
+    let mut after: Option<(NaiveDateTime, DbUuid)> = None;
+    loop {
+        let mut query = T::select();
+        query
+            .and_where(T::date().gte(export.from.to_string()))
+            .and_where(T::date().lt(export.to.to_string()))
+            .order_by_expr(T::date().into(), Order::Asc)
+            .order_by_expr(T::id().into(), Order::Asc)
+            .limit(BATCH_SIZE);
+        if let (Some(location_id), Some(location)) = (export.location_id, T::location()) {
+            query.and_where(location.eq(location_id.to_string()));
+        }
+        if let Some(cost_center_id) = export.cost_center_id {
+            query.and_where(T::cost_center().eq(cost_center_id.to_string()));
+        }
+        if let Some((date, id)) = after {
+            query.cond_where(
+                Cond::any().add(T::date().gt(date.to_string())).add(
+                    Cond::all()
+                        .add(T::date().eq(date.to_string()))
+                        .add(T::id().gt(id.to_string())),
+                ),
+            );
+        }
+
+        let rows = service.db_adapter.query_many::<T>(&query).await?;
+        for row in &rows {
+            sink.row(T::COLUMNS, row.cells())?;
+        }
+        count += rows.len() as i32;
+
+        match rows.last() {
+            Some(last) if rows.len() as u64 == BATCH_SIZE => after = Some(last.key()),
+            _ => break,
+        }
+    }
+
+    sink.finish()?;
+    Ok(count)
+}
+
+enum Sink {
+    Csv(BufWriter<File>),
+    JsonLines(BufWriter<File>),
+    Xlsx(Box<xlsx::Writer<BufWriter<File>>>),
+}
+
+impl Sink {
+    fn create(format: ExportFormat, path: &Path, sheet: &str) -> Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(match format {
+            ExportFormat::Csv => Sink::Csv(file),
+            ExportFormat::JsonLines => Sink::JsonLines(file),
+            ExportFormat::Xlsx => Sink::Xlsx(Box::new(xlsx::Writer::new(file, sheet)?)),
+        })
+    }
+
+    fn header(&mut self, columns: &[&str]) -> Result<()> {
+        match self {
+            Sink::Csv(out) => csv::write_record(out, columns),
+            // Every line names its columns
+            Sink::JsonLines(_) => Ok(()),
+            Sink::Xlsx(writer) => {
+                let cells: Vec<_> = columns.iter().map(|column| Cell::Text(column.to_string())).collect();
+                writer.write_row(&cells)
+            }
+        }
+    }
+
+    fn row(&mut self, columns: &[&str], cells: Vec<Cell>) -> Result<()> {
+        match self {
+            Sink::Csv(out) => {
+                let fields: Vec<_> = cells
+                    .iter()
+                    .map(|cell| match cell {
+                        Cell::Empty => "",
+                        Cell::Text(value) | Cell::Number(value) => value.as_str(),
+                    })
+                    .collect();
+                csv::write_record(out, &fields)
+            }
+            Sink::JsonLines(out) => {
+                let object: serde_json::Map<_, _> = columns
+                    .iter()
+                    .zip(cells)
+                    .map(|(column, cell)| {
+                        let value = match cell {
+                            Cell::Empty => serde_json::Value::Null,
+                            Cell::Text(text) => serde_json::Value::String(text),
+                            Cell::Number(number) => match number.parse() {
+                                Ok(number) => serde_json::Value::Number(number),
+                                Err(_) => serde_json::Value::String(number),
+                            },
+                        };
+                        (column.to_string(), value)
+                    })
+                    .collect();
+                serde_json::to_writer(&mut *out, &object)?;
+                out.write_all(b"\n")?;
+                Ok(())
+            }
+            Sink::Xlsx(writer) => writer.write_row(&cells),
+        }
+    }
+
+    fn finish(self) -> Result<()> {
+        let mut out = match self {
+            Sink::Csv(out) | Sink::JsonLines(out) => out,
+            Sink::Xlsx(writer) => writer.finish()?,
+        };
+        out.flush()?;
+        Ok(())
+    }
+}
+
+fn text(value: impl ToString) -> Cell {
+    Cell::Text(value.to_string())
+}
+
+fn optional(value: &Option<String>) -> Cell {
+    value.as_ref().map_or(Cell::Empty, text)
+}
+
+fn money(value: Money) -> Cell {
+    Cell::Number(value.to_string())
+}
+
+fn date(value: NaiveDateTime) -> Cell {
+    Cell::Text(value.format(local_date_time::FORMAT).to_string())
+}
+
+fn col<T: sea_query::Iden + 'static, C: sea_query::Iden + 'static>(table: T, column: C) -> Expr {
+    Expr::col((table, column))
+}
+
+#[derive(LibsqlFromRow)]
+struct OrderRow {
+    id: DbUuid,
+    order_number: String,
+    order_date: NaiveDateTime,
+    order_state: SalesOrderState,
+    payment_state: SalesOrderPaymentState,
+    customer_name: Option<String>,
+    customer_phone_number: Option<String>,
+    channel: Option<String>,
+    location: Option<String>,
+    cost_center: Option<String>,
+    currency_code: String,
+    net_amount: Money,
+    disc_amount: Money,
+    taxable_amount: Money,
+    tax_amount: Money,
+    total_amount: Money,
+    notes: Option<String>,
+}
+
+impl ExportRow for OrderRow {
+    const SHEET: &'static str = "Sales orders";
+    const COLUMNS: &'static [&'static str] = &[
+        "order_id",
+        "order_number",
+        "order_date",
+        "order_state",
+        "payment_state",
+        "customer_name",
+        "customer_phone_number",
+        "channel",
+        "location",
+        "cost_center",
+        "currency_code",
+        "net_amount",
+        "disc_amount",
+        "taxable_amount",
+        "tax_amount",
+        "total_amount",
+        "notes",
+    ];
+
+    fn select() -> SelectStatement {
+        let mut query = Query::select();
+        query
+            .from(SalesOrders::Table)
+            .columns([
+                (SalesOrders::Table, SalesOrders::Id),
+                (SalesOrders::Table, SalesOrders::OrderDate),
+                (SalesOrders::Table, SalesOrders::OrderState),
+                (SalesOrders::Table, SalesOrders::PaymentState),
+                (SalesOrders::Table, SalesOrders::CustomerName),
+                (SalesOrders::Table, SalesOrders::CustomerPhoneNumber),
+                (SalesOrders::Table, SalesOrders::CurrencyCode),
+                (SalesOrders::Table, SalesOrders::NetAmount),
+                (SalesOrders::Table, SalesOrders::DiscAmount),
+                (SalesOrders::Table, SalesOrders::TaxableAmount),
+                (SalesOrders::Table, SalesOrders::TaxAmount),
+                (SalesOrders::Table, SalesOrders::TotalAmount),
+                (SalesOrders::Table, SalesOrders::Notes),
+            ])
+            .expr_as(col(SalesOrders::Table, SalesOrders::OrderReadableId), Alias::new("order_number"))
+            .expr_as(col(Channels::Table, Channels::Name), Alias::new("channel"))
+            .expr_as(col(Locations::Table, Locations::Name), Alias::new("location"))
+            .expr_as(col(CostCenters::Table, CostCenters::Name), Alias::new("cost_center"))
+            .left_join(Channels::Table, col(Channels::Table, Channels::Id).equals((SalesOrders::Table, SalesOrders::ChannelId)))
+            .left_join(Locations::Table, col(Locations::Table, Locations::Id).equals((SalesOrders::Table, SalesOrders::LocationId)))
+            .left_join(
+                CostCenters::Table,
+                col(CostCenters::Table, CostCenters::Id).equals((SalesOrders::Table, SalesOrders::CostCenterId)),
+            );
+        query
+    }
+
+    fn date() -> Expr {
+        col(SalesOrders::Table, SalesOrders::OrderDate)
+    }
+
+    fn id() -> Expr {
+        col(SalesOrders::Table, SalesOrders::Id)
+    }
+
+    fn location() -> Option<Expr> {
+        Some(col(SalesOrders::Table, SalesOrders::LocationId))
+    }
+
+    fn cost_center() -> Expr {
+        col(SalesOrders::Table, SalesOrders::CostCenterId)
+    }
+
+    fn key(&self) -> (NaiveDateTime, DbUuid) {
+        (self.order_date, self.id)
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        vec![
+            text(self.id),
+            text(&self.order_number),
+            date(self.order_date),
+            text(self.order_state),
+            text(self.payment_state),
+            optional(&self.customer_name),
+            optional(&self.customer_phone_number),
+            optional(&self.channel),
+            optional(&self.location),
+            optional(&self.cost_center),
+            text(&self.currency_code),
+            money(self.net_amount),
+            money(self.disc_amount),
+            money(self.taxable_amount),
+            money(self.tax_amount),
+            money(self.total_amount),
+            optional(&self.notes),
+        ]
+    }
+}
+
+/// Selects the order a line is on, for the date, number and location of its lines
+fn from_order_lines<T: sea_query::Iden + Copy + 'static, C: sea_query::Iden + 'static>(
+    table: T,
+    order_id: C,
+) -> SelectStatement {
+    let mut query = Query::select();
+    query
+        .from(table)
+        .expr_as(col(SalesOrders::Table, SalesOrders::OrderReadableId), Alias::new("order_number"))
+        .expr_as(col(SalesOrders::Table, SalesOrders::OrderDate), Alias::new("order_date"))
+        .expr_as(col(Locations::Table, Locations::Name), Alias::new("location"))
+        .inner_join(SalesOrders::Table, col(SalesOrders::Table, SalesOrders::Id).equals((table, order_id)))
+        .left_join(Locations::Table, col(Locations::Table, Locations::Id).equals((SalesOrders::Table, SalesOrders::LocationId)));
+    query
+}
+
+#[derive(LibsqlFromRow)]
+struct OrderItemRow {
+    order_id: DbUuid,
+    order_number: String,
+    order_date: NaiveDateTime,
+    location: Option<String>,
+    id: DbUuid,
+    item_id: Option<DbUuid>,
+    item_name: String,
+    sku: Option<String>,
+    quantity: i32,
+    price_amount: Money,
+    disc_amount: Money,
+    taxable_amount: Money,
+    tax_amount: Money,
+    total_amount: Money,
+}
+
+impl ExportRow for OrderItemRow {
+    const SHEET: &'static str = "Sales order items";
+    const COLUMNS: &'static [&'static str] = &[
+        "order_id",
+        "order_number",
+        "order_date",
+        "location",
+        "line_id",
+        "item_id",
+        "item_name",
+        "sku",
+        "quantity",
+        "price_amount",
+        "disc_amount",
+        "taxable_amount",
+        "tax_amount",
+        "total_amount",
+    ];
+
+    fn select() -> SelectStatement {
+        let mut query = from_order_lines(SalesOrderItems::Table, SalesOrderItems::OrderId);
+        query.columns([
+            (SalesOrderItems::Table, SalesOrderItems::OrderId),
+            (SalesOrderItems::Table, SalesOrderItems::Id),
+            (SalesOrderItems::Table, SalesOrderItems::ItemId),
+            (SalesOrderItems::Table, SalesOrderItems::ItemName),
+            (SalesOrderItems::Table, SalesOrderItems::Sku),
+            (SalesOrderItems::Table, SalesOrderItems::Quantity),
+            (SalesOrderItems::Table, SalesOrderItems::PriceAmount),
+            (SalesOrderItems::Table, SalesOrderItems::DiscAmount),
+            (SalesOrderItems::Table, SalesOrderItems::TaxableAmount),
+            (SalesOrderItems::Table, SalesOrderItems::TaxAmount),
+            (SalesOrderItems::Table, SalesOrderItems::TotalAmount),
+        ]);
+        query
+    }
+
+    fn date() -> Expr {
+        col(SalesOrders::Table, SalesOrders::OrderDate)
+    }
+
+    fn id() -> Expr {
+        col(SalesOrderItems::Table, SalesOrderItems::Id)
+    }
+
+    fn location() -> Option<Expr> {
+        Some(col(SalesOrders::Table, SalesOrders::LocationId))
+    }
+
+    fn cost_center() -> Expr {
+        col(SalesOrders::Table, SalesOrders::CostCenterId)
+    }
+
+    fn key(&self) -> (NaiveDateTime, DbUuid) {
+        (self.order_date, self.id)
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        vec![
+            text(self.order_id),
+            text(&self.order_number),
+            date(self.order_date),
+            optional(&self.location),
+            text(self.id),
+            self.item_id.map_or(Cell::Empty, text),
+            text(&self.item_name),
+            optional(&self.sku),
+            Cell::Number(self.quantity.to_string()),
+            money(self.price_amount),
+            money(self.disc_amount),
+            money(self.taxable_amount),
+            money(self.tax_amount),
+            money(self.total_amount),
+        ]
+    }
+}
+
+#[derive(LibsqlFromRow)]
+struct OrderChargeRow {
+    order_id: DbUuid,
+    order_number: String,
+    order_date: NaiveDateTime,
+    location: Option<String>,
+    id: DbUuid,
+    charge_type_name: String,
+    amount: Money,
+    tax_amount: Money,
+}
+
+impl ExportRow for OrderChargeRow {
+    const SHEET: &'static str = "Sales order charges";
+    const COLUMNS: &'static [&'static str] = &[
+        "order_id",
+        "order_number",
+        "order_date",
+        "location",
+        "charge_id",
+        "charge_type",
+        "amount",
+        "tax_amount",
+    ];
+
+    fn select() -> SelectStatement {
+        let mut query = from_order_lines(SalesOrderCharges::Table, SalesOrderCharges::OrderId);
+        query.columns([
+            (SalesOrderCharges::Table, SalesOrderCharges::OrderId),
+            (SalesOrderCharges::Table, SalesOrderCharges::Id),
+            (SalesOrderCharges::Table, SalesOrderCharges::ChargeTypeName),
+            (SalesOrderCharges::Table, SalesOrderCharges::Amount),
+            (SalesOrderCharges::Table, SalesOrderCharges::TaxAmount),
+        ]);
+        query
+    }
+
+    fn date() -> Expr {
+        col(SalesOrders::Table, SalesOrders::OrderDate)
+    }
+
+    fn id() -> Expr {
+        col(SalesOrderCharges::Table, SalesOrderCharges::Id)
+    }
+
+    fn location() -> Option<Expr> {
+        Some(col(SalesOrders::Table, SalesOrders::LocationId))
+    }
+
+    fn cost_center() -> Expr {
+        col(SalesOrders::Table, SalesOrders::CostCenterId)
+    }
+
+    fn key(&self) -> (NaiveDateTime, DbUuid) {
+        (self.order_date, self.id)
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        vec![
+            text(self.order_id),
+            text(&self.order_number),
+            date(self.order_date),
+            optional(&self.location),
+            text(self.id),
+            text(&self.charge_type_name),
+            money(self.amount),
+            money(self.tax_amount),
+        ]
+    }
+}
+
+#[derive(LibsqlFromRow)]
+struct PaymentRow {
+    id: DbUuid,
+    order_id: DbUuid,
+    order_number: String,
+    payment_date: NaiveDateTime,
+    location: Option<String>,
+    payment_method: Option<String>,
+    state: PaymentState,
+    amount: Money,
+    currency_code: String,
+    original_amount: Money,
+    exchange_rate: Rate,
+    reference_number: Option<String>,
+}
+
+impl ExportRow for PaymentRow {
+    const SHEET: &'static str = "Payments";
+    const COLUMNS: &'static [&'static str] = &[
+        "payment_id",
+        "order_id",
+        "order_number",
+        "payment_date",
+        "location",
+        "payment_method",
+        "state",
+        "amount",
+        "currency_code",
+        "original_amount",
+        "exchange_rate",
+        "reference_number",
+    ];
+
+    fn select() -> SelectStatement {
+        let mut query = from_order_lines(SalesOrderPayments::Table, SalesOrderPayments::OrderId);
+        query
+            .columns([
+                (SalesOrderPayments::Table, SalesOrderPayments::Id),
+                (SalesOrderPayments::Table, SalesOrderPayments::OrderId),
+                (SalesOrderPayments::Table, SalesOrderPayments::PaymentDate),
+                (SalesOrderPayments::Table, SalesOrderPayments::State),
+                (SalesOrderPayments::Table, SalesOrderPayments::Amount),
+                (SalesOrderPayments::Table, SalesOrderPayments::CurrencyCode),
+                (SalesOrderPayments::Table, SalesOrderPayments::OriginalAmount),
+                (SalesOrderPayments::Table, SalesOrderPayments::ExchangeRate),
+                (SalesOrderPayments::Table, SalesOrderPayments::ReferenceNumber),
+            ])
+            .expr_as(col(PaymentMethods::Table, PaymentMethods::Name), Alias::new("payment_method"))
+            .left_join(
+                PaymentMethods::Table,
+                col(PaymentMethods::Table, PaymentMethods::Id)
+                    .equals((SalesOrderPayments::Table, SalesOrderPayments::PaymentMethodId)),
+            );
+        query
+    }
+
+    fn date() -> Expr {
+        col(SalesOrderPayments::Table, SalesOrderPayments::PaymentDate)
+    }
+
+    fn id() -> Expr {
+        col(SalesOrderPayments::Table, SalesOrderPayments::Id)
+    }
+
+    fn location() -> Option<Expr> {
+        Some(col(SalesOrders::Table, SalesOrders::LocationId))
+    }
+
+    fn cost_center() -> Expr {
+        col(SalesOrders::Table, SalesOrders::CostCenterId)
+    }
+
+    fn key(&self) -> (NaiveDateTime, DbUuid) {
+        (self.payment_date, self.id)
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        vec![
+            text(self.id),
+            text(self.order_id),
+            text(&self.order_number),
+            date(self.payment_date),
+            optional(&self.location),
+            optional(&self.payment_method),
+            text(self.state),
+            money(self.amount),
+            text(&self.currency_code),
+            money(self.original_amount),
+            Cell::Number(self.exchange_rate.to_string()),
+            optional(&self.reference_number),
+        ]
+    }
+}
+
+#[derive(LibsqlFromRow)]
+struct ExpenseRow {
+    id: DbUuid,
+    expense_date: NaiveDateTime,
+    title: String,
+    category: Option<String>,
+    cost_center: Option<String>,
+    amount: Money,
+    description: Option<String>,
+}
+
+impl ExportRow for ExpenseRow {
+    const SHEET: &'static str = "Expenses";
+    const COLUMNS: &'static [&'static str] =
+        &["expense_id", "expense_date", "title", "category", "cost_center", "amount", "description"];
+
+    fn select() -> SelectStatement {
+        let mut query = Query::select();
+        query
+            .from(Expenses::Table)
+            .columns([
+                (Expenses::Table, Expenses::Id),
+                (Expenses::Table, Expenses::ExpenseDate),
+                (Expenses::Table, Expenses::Title),
+                (Expenses::Table, Expenses::Amount),
+                (Expenses::Table, Expenses::Description),
+            ])
+            .expr_as(col(PurchaseCategories::Table, PurchaseCategories::Name), Alias::new("category"))
+            .expr_as(col(CostCenters::Table, CostCenters::Name), Alias::new("cost_center"))
+            .left_join(
+                PurchaseCategories::Table,
+                col(PurchaseCategories::Table, PurchaseCategories::Id).equals((Expenses::Table, Expenses::CategoryId)),
+            )
+            .left_join(CostCenters::Table, col(CostCenters::Table, CostCenters::Id).equals((Expenses::Table, Expenses::CostCenterId)));
+        query
+    }
+
+    fn date() -> Expr {
+        col(Expenses::Table, Expenses::ExpenseDate)
+    }
+
+    fn id() -> Expr {
+        col(Expenses::Table, Expenses::Id)
+    }
+
+    fn location() -> Option<Expr> {
+        None
+    }
+
+    fn cost_center() -> Expr {
+        col(Expenses::Table, Expenses::CostCenterId)
+    }
+
+    fn key(&self) -> (NaiveDateTime, DbUuid) {
+        (self.expense_date, self.id)
+    }
+
+    fn cells(&self) -> Vec<Cell> {
+        vec![
+            text(self.id),
+            date(self.expense_date),
+            text(&self.title),
+            optional(&self.category),
+            optional(&self.cost_center),
+            money(self.amount),
+            optional(&self.description),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::core::{
+        commands::{
+            auth::user_commands::AddUserCommand,
+            common::{channel_commands::CreateChannelCommand, location_commands::CreateLocationCommand},
+            finance::{
+                cost_center_commands::CreateCostCenterCommand, payment_method_commands::CreatePaymentMethodCommand,
+                sales_order_payment_commands::CreateSalesOrderPaymentCommand,
+            },
+            purchases::{expense_commands::CreateExpenseCommand, purchase_category_commands::CreatePurchaseCategoryCommand},
+            sales::{sales_charge_type_commands::CreateSalesChargeTypeCommand, sales_order_commands::CreateSalesOrderCommand},
+            tests::setup_service,
+        },
+        models::{
+            auth::user_model::{UserNewInput, UserRole, UserState},
+            common::{channel_model::ChannelNewInput, location_model::LocationNewInput},
+            finance::{
+                cost_center_model::{CostCenterNewInput, CostCenterState},
+                payment_method_model::PaymentMethodNewInput,
+                sales_order_payment_model::SalesOrderPaymentNewInput,
+            },
+            purchases::{expense_model::ExpenseNewInput, purchase_category_model::PurchaseCategoryNew},
+            sales::{
+                sales_charge_type_model::SalesChargeTypeNewInput, sales_order_charge_model::SalesOrderChargeNewInput,
+                sales_order_item_model::SalesOrderItemInput, sales_order_model::SalesOrderNewInput,
+            },
+        },
+    };
+
+    struct Fixture {
+        day: NaiveDateTime,
+        main: DbUuid,
+        kiosk: DbUuid,
+        cost_center: DbUuid,
+        orders: Vec<DbUuid>,
+    }
+
+    fn at(hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, 14).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    /// Three orders at the main location, one of them on the next day, and one at the kiosk
+    async fn setup(service: &AppService) -> Fixture {
+        let user_id = AddUserCommand {
+            user: UserNewInput {
+                username: "exporter".to_string(),
+                pin_hash: "1234".to_string(),
+                full_name: "Exporter".to_string(),
+                state: UserState::Active,
+                role: UserRole::Cashier,
+                last_login_at: None,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap()
+        .id;
+        let cost_center = CreateCostCenterCommand {
+            cost_center: CostCenterNewInput {
+                name: "Kitchen".to_string(),
+                code: "KIT".to_string(),
+                description: None,
+                state: Some(CostCenterState::Active),
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+        let channel = CreateChannelCommand {
+            channel: ChannelNewInput { name: "Counter".to_string(), description: None, is_active: Some(true) },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+        let mut locations = vec![];
+        for name in ["Main", "Kiosk"] {
+            let command = CreateLocationCommand {
+                location: LocationNewInput {
+                    name: name.to_string(),
+                    description: None,
+                    address: None,
+                    is_active: Some(true),
+                },
+            };
+            locations.push(command.exec(service).await.unwrap().id);
+        }
+        let charge_type = CreateSalesChargeTypeCommand {
+            charge_type: SalesChargeTypeNewInput { name: "Delivery".to_string(), description: None },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+
+        let mut orders = vec![];
+        for (order_date, location_id) in
+            [(at(10), locations[0]), (at(10), locations[0]), (at(10), locations[1]), (at(10) + Duration::days(1), locations[0])]
+        {
+            let command = CreateSalesOrderCommand {
+                sales_order: SalesOrderNewInput {
+                    order_date,
+                    customer_id: None,
+                    customer_name: Some("Doe, Jane".to_string()),
+                    customer_phone_number: None,
+                    billing_address: None,
+                    shipping_address: None,
+                    net_amount: 500.into(),
+                    disc_amount: 0.into(),
+                    taxable_amount: 500.into(),
+                    tax_amount: 50.into(),
+                    total_amount: 600.into(),
+                    notes: None,
+                    channel_id: channel.id,
+                    location_id,
+                    cost_center_id: cost_center.id,
+                    discount_id: None,
+                    items: vec![SalesOrderItemInput {
+                        item_id: None,
+                        variant_id: None,
+                        item_name: "Tea".to_string(),
+                        quantity: 2,
+                        sku: None,
+                        price_amount: 250.into(),
+                        disc_amount: 0.into(),
+                        taxable_amount: 500.into(),
+                        tax_amount: 50.into(),
+                        total_amount: 550.into(),
+                        price_override: None,
+                    }],
+                    charges: Some(vec![SalesOrderChargeNewInput {
+                        charge_type_id: charge_type.id,
+                        charge_type_name: charge_type.name.clone(),
+                        amount: 50.into(),
+                        tax_amount: 0.into(),
+                        tax_group_id: None,
+                    }]),
+                },
+                created_by_user_id: user_id,
+            };
+            orders.push(command.exec(service).await.unwrap().id);
+        }
+
+        Fixture { day: at(0), main: locations[0], kiosk: locations[1], cost_center: cost_center.id, orders }
+    }
+
+    fn export(fixture: &Fixture, dataset: ExportDataset, format: ExportFormat, name: &str) -> ExportDataCommand {
+        ExportDataCommand {
+            export: DataExportInput {
+                dataset,
+                format,
+                from: fixture.day,
+                to: fixture.day + Duration::days(1),
+                location_id: None,
+                cost_center_id: None,
+                path: name.to_string(),
+            },
+            config: ExportConfig { dir: std::env::temp_dir().join(format!("minnal-exports-{}", Uuid::now_v7())) },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_orders_to_csv() {
+        let service = setup_service().await;
+        let fixture = setup(&service).await;
+
+        let command = export(&fixture, ExportDataset::SalesOrders, ExportFormat::Csv, "orders.csv");
+        let result = command.exec(&service).await.unwrap();
+        assert_eq!(result.rows, 3);
+
+        let rows = csv::read(&fs::read_to_string(&result.path).unwrap()).unwrap();
+        fs::remove_file(&result.path).unwrap();
+        assert_eq!(rows[0], OrderRow::COLUMNS);
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[1][2], "2025-03-14 10:00:00");
+        assert_eq!(rows[1][5], "Doe, Jane");
+        assert_eq!(rows[1][15], "6");
+        let mut exported: Vec<_> = rows[1..].iter().map(|row| row[0].clone()).collect();
+        exported.sort();
+        let mut expected: Vec<_> = fixture.orders[..3].iter().map(|id| id.to_string()).collect();
+        expected.sort();
+        assert_eq!(exported, expected);
+        assert!(!Path::new(&format!("{}.partial", result.path)).exists());
+    }
+
+    #[tokio::test]
+    async fn test_export_lines_by_location() {
+        let service = setup_service().await;
+        let fixture = setup(&service).await;
+
+        let mut command = export(&fixture, ExportDataset::SalesOrderItems, ExportFormat::Xlsx, "items.xlsx");
+        command.export.location_id = Some(fixture.main);
+        command.export.cost_center_id = Some(fixture.cost_center);
+        let result = command.exec(&service).await.unwrap();
+        assert_eq!(result.rows, 2);
+        let rows = xlsx::read(&fs::read(&result.path).unwrap()).unwrap();
+        fs::remove_file(&result.path).unwrap();
+        assert_eq!(rows[0], OrderItemRow::COLUMNS);
+        assert!(rows[1..].iter().all(|row| row[3] == "Main" && row[6] == "Tea" && row[8] == "2"));
+
+        let mut command = export(&fixture, ExportDataset::SalesOrderCharges, ExportFormat::Csv, "charges.csv");
+        command.export.location_id = Some(fixture.kiosk);
+        let result = command.exec(&service).await.unwrap();
+        let rows = csv::read(&fs::read_to_string(&result.path).unwrap()).unwrap();
+        fs::remove_file(&result.path).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1][0], fixture.orders[2].to_string());
+        assert_eq!(rows[1][5], "Delivery");
+    }
+
+    #[tokio::test]
+    async fn test_export_payments_and_expenses_to_json_lines() {
+        let service = setup_service().await;
+        let fixture = setup(&service).await;
+        let payment_method = CreatePaymentMethodCommand {
+            payment_method: PaymentMethodNewInput {
+                name: "Cash".to_string(),
+                code: "CASH".to_string(),
+                description: None,
+                state: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        for order_id in [fixture.orders[0], fixture.orders[3]] {
+            let command = CreateSalesOrderPaymentCommand {
+                payment: SalesOrderPaymentNewInput {
+                    order_id,
+                    payment_method_id: payment_method.id,
+                    payment_date: at(11),
+                    amount: Some(600.into()),
+                    reference_number: None,
+                    notes: None,
+                    state: None,
+                    foreign_amount: None,
+                },
+            };
+            command.exec(&service).await.unwrap();
+        }
+        let category = CreatePurchaseCategoryCommand {
+            category: PurchaseCategoryNew { name: "Supplies".to_string(), description: None, state: None },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        let command = CreateExpenseCommand {
+            expense: ExpenseNewInput {
+                title: "Napkins".to_string(),
+                amount: Money::from_float(12.5),
+                expense_date: at(9),
+                category_id: category.id,
+                cost_center_id: fixture.cost_center,
+                description: None,
+            },
+        };
+        command.exec(&service).await.unwrap();
+
+        let command = export(&fixture, ExportDataset::SalesOrderPayments, ExportFormat::JsonLines, "payments.jsonl");
+        let result = command.exec(&service).await.unwrap();
+        let text = fs::read_to_string(&result.path).unwrap();
+        fs::remove_file(&result.path).unwrap();
+        let lines: Vec<serde_json::Value> = text.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().any(|line| line["order_id"] == fixture.orders[0].to_string()));
+        assert_eq!(lines[0]["payment_method"], "Cash");
+        assert_eq!(lines[0]["amount"], 6);
+        assert_eq!(lines[0]["reference_number"], serde_json::Value::Null);
+
+        let command = export(&fixture, ExportDataset::Expenses, ExportFormat::JsonLines, "expenses.jsonl");
+        let result = command.exec(&service).await.unwrap();
+        let text = fs::read_to_string(&result.path).unwrap();
+        fs::remove_file(&result.path).unwrap();
+        let line: serde_json::Value = serde_json::from_str(text.trim_end()).unwrap();
+        assert_eq!(line["title"], "Napkins");
+        assert_eq!(line["category"], "Supplies");
+        assert_eq!(line["cost_center"], "Kitchen");
+        assert_eq!(line["amount"], 12.5);
+    }
+
+    #[tokio::test]
+    async fn test_export_validates_input() {
+        let service = setup_service().await;
+        let fixture = setup(&service).await;
+
+        let mut command = export(&fixture, ExportDataset::Expenses, ExportFormat::Csv, "expenses.csv");
+        command.export.location_id = Some(fixture.main);
+        assert!(matches!(command.exec(&service).await, Err(Error::ValidationError(_))));
+
+        let command = export(&fixture, ExportDataset::SalesOrders, ExportFormat::Xlsx, "orders.csv");
+        assert!(matches!(command.exec(&service).await, Err(Error::ValidationError(_))));
+
+        let mut command = export(&fixture, ExportDataset::SalesOrders, ExportFormat::Csv, "orders.csv");
+        command.export.to = command.export.from;
+        assert!(matches!(command.exec(&service).await, Err(Error::ValidationError(_))));
+        assert!(!command.config.dir.join("orders.csv").exists());
+
+        // Nothing is written outside the export directory
+        let outside = std::env::temp_dir().join(format!("{}-orders.csv", Uuid::now_v7()));
+        for path in [outside.to_string_lossy().to_string(), "../orders.csv".to_string()] {
+            let mut command = export(&fixture, ExportDataset::SalesOrders, ExportFormat::Csv, "orders.csv");
+            command.export.path = path;
+            assert!(matches!(command.exec(&service).await, Err(Error::ValidationError(_))));
+        }
+        assert!(!outside.exists());
+    }
+}
@@ -0,0 +1,226 @@
+use chrono::{NaiveDateTime, Utc};
+use sea_query::{Expr, Order, Query};
+use uuid::Uuid;
+
+use crate::{
+    adapters::outgoing::database::DatabaseAdapter,
+    core::{
+        commands::{app_service::AppService, finance::currency_commands::currency_by_code, Command},
+        models::finance::exchange_rate_model::{ExchangeRate, ExchangeRateNewInput, ExchangeRates},
+        types::{db_uuid::DbUuid, rate::Rate},
+    },
+    error::{Error, Result},
+};
+
+/// Returns the base currency units per unit of `currency_code` in effect at `at`
+pub async fn rate_at(service: &AppService, currency_code: &str, at: NaiveDateTime) -> Result<Rate> {
+    let currency = currency_by_code(service, currency_code).await?;
+    if currency.is_base {
+        return Ok(Rate::ONE);
+    }
+
+    let mut query = Query::select();
+    let stmt = query
+        .from(ExchangeRates::Table)
+        .columns(ExchangeRates::all_columns())
+        .and_where(Expr::col(ExchangeRates::CurrencyCode).eq(currency.code.clone()))
+        .and_where(Expr::col(ExchangeRates::EffectiveFrom).lte(at.to_string()))
+        .order_by(ExchangeRates::EffectiveFrom, Order::Desc)
+        .limit(1);
+
+    match service.db_adapter.query_optional::<ExchangeRate>(&stmt).await? {
+        Some(exchange_rate) => Ok(exchange_rate.rate),
+        None => Err(Error::ValidationError(format!(
+            "No exchange rate for {} is in effect",
+            currency.code
+        ))),
+    }
+}
+
+// Commands
+pub struct CreateExchangeRateCommand {
+    pub exchange_rate: ExchangeRateNewInput,
+}
+
+pub struct DeleteExchangeRateCommand {
+    pub id: DbUuid,
+}
+
+// Command Implementations
+impl Command for CreateExchangeRateCommand {
+    type Output = ExchangeRate;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let currency = currency_by_code(service, &self.exchange_rate.currency_code).await?;
+        if currency.is_base {
+            return Err(Error::ValidationError(
+                "The base currency always converts at a rate of 1".to_string(),
+            ));
+        }
+
+        if self.exchange_rate.rate.value() <= 0 {
+            return Err(Error::ValidationError("Exchange rate must be positive".to_string()));
+        }
+
+        let now = Utc::now().naive_utc();
+        let new_rate = ExchangeRate {
+            id: Uuid::now_v7().into(),
+            currency_code: currency.code,
+            rate: self.exchange_rate.rate,
+            effective_from: self.exchange_rate.effective_from.unwrap_or(now),
+            created_at: now,
+            updated_at: now,
+        };
+
+        // Build the insert query with SeaQuery
+        let mut insert_query = Query::insert();
+        let insert_stmt = insert_query
+            .into_table(ExchangeRates::Table)
+            .columns([
+                ExchangeRates::Id,
+                ExchangeRates::CurrencyCode,
+                ExchangeRates::Rate,
+                ExchangeRates::EffectiveFrom,
+                ExchangeRates::CreatedAt,
+                ExchangeRates::UpdatedAt,
+            ])
+            .values_panic([
+                new_rate.id.to_string().into(),
+                new_rate.currency_code.clone().into(),
+                new_rate.rate.value().into(),
+                new_rate.effective_from.to_string().into(),
+                now.to_string().into(),
+                now.to_string().into(),
+            ]);
+
+        service.db_adapter.insert_many(&insert_stmt).await?;
+
+        Ok(new_rate)
+    }
+}
+
+impl Command for DeleteExchangeRateCommand {
+    type Output = bool;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let mut delete_query = Query::delete();
+        let delete_stmt = delete_query
+            .from_table(ExchangeRates::Table)
+            .and_where(Expr::col(ExchangeRates::Id).eq(self.id.to_string()));
+
+        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+
+        Ok(affected_rows > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::core::{
+        commands::{finance::currency_commands::CreateCurrencyCommand, tests::setup_service},
+        models::finance::currency_model::CurrencyNewInput,
+    };
+    use chrono::Duration;
+
+    async fn create_usd(service: &AppService) {
+        CreateCurrencyCommand {
+            currency: CurrencyNewInput {
+                code: "USD".to_string(),
+                name: "US Dollar".to_string(),
+                symbol: Some("$".to_string()),
+                minor_units: 2,
+                is_active: None,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+    }
+
+    async fn create_rate(service: &AppService, rate: &str, effective_from: NaiveDateTime) -> ExchangeRate {
+        CreateExchangeRateCommand {
+            exchange_rate: ExchangeRateNewInput {
+                currency_code: "USD".to_string(),
+                rate: Rate::from_str(rate).unwrap(),
+                effective_from: Some(effective_from),
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rate_at_uses_latest_effective_rate() {
+        let service = setup_service().await;
+        let now = Utc::now().naive_utc();
+        create_usd(&service).await;
+
+        create_rate(&service, "82.9", now - Duration::days(2)).await;
+        create_rate(&service, "83.25", now - Duration::days(1)).await;
+        create_rate(&service, "84", now + Duration::days(1)).await;
+
+        assert_eq!(rate_at(&service, "USD", now).await.unwrap(), Rate::from_str("83.25").unwrap());
+        assert_eq!(
+            rate_at(&service, "usd", now - Duration::hours(36)).await.unwrap(),
+            Rate::from_str("82.9").unwrap()
+        );
+        assert_eq!(rate_at(&service, "INR", now).await.unwrap(), Rate::ONE);
+    }
+
+    #[tokio::test]
+    async fn test_rate_at_without_rate() {
+        let service = setup_service().await;
+        let now = Utc::now().naive_utc();
+        create_usd(&service).await;
+
+        let result = rate_at(&service, "USD", now).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+
+        let unknown = rate_at(&service, "XYZ", now).await;
+        assert!(matches!(unknown, Err(Error::NotFoundError)));
+    }
+
+    #[tokio::test]
+    async fn test_create_exchange_rate_validation() {
+        let service = setup_service().await;
+        create_usd(&service).await;
+
+        let base = CreateExchangeRateCommand {
+            exchange_rate: ExchangeRateNewInput {
+                currency_code: "INR".to_string(),
+                rate: Rate::ONE,
+                effective_from: None,
+            },
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(base, Err(Error::ValidationError(_))));
+
+        let negative = CreateExchangeRateCommand {
+            exchange_rate: ExchangeRateNewInput {
+                currency_code: "USD".to_string(),
+                rate: Rate::from_str("-1").unwrap(),
+                effective_from: None,
+            },
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(negative, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_exchange_rate() {
+        let service = setup_service().await;
+        let now = Utc::now().naive_utc();
+        create_usd(&service).await;
+        let rate = create_rate(&service, "83.25", now).await;
+
+        let delete_cmd = DeleteExchangeRateCommand { id: rate.id };
+        assert!(delete_cmd.exec(&service).await.unwrap());
+        assert!(!delete_cmd.exec(&service).await.unwrap());
+    }
+}
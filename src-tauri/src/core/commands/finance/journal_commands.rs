@@ -0,0 +1,1215 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use chrono::NaiveDateTime;
+use lightning_macros::LibsqlFromRow;
+use quick_xml::escape::escape;
+use sea_query::{Alias, Cond, Expr, Order, Query, SelectStatement};
+
+use crate::{
+    adapters::outgoing::database::{DatabaseAdapter, FromLibsqlValue, FromRow},
+    core::{
+        commands::{app_service::AppService, Command},
+        models::{
+            catalog::{item_group_model::ItemCategories, item_model::Items},
+            common::{
+                location_model::Locations,
+                tax_group_model::TaxGroupTaxes,
+                tax_model::{ItemTaxes, Taxes},
+            },
+            finance::{
+                account_mapping_model::{AccountMapping, AccountMappingKind, AccountMappings},
+                cost_center_model::CostCenters,
+                data_export_model::DataExport,
+                journal_model::{JournalEntry, JournalEntryKind, JournalExportInput, JournalFormat, JournalLine},
+                payment_method_model::PaymentMethods,
+                sales_order_payment_model::{SalesOrderPaymentState as PaymentState, SalesOrderPayments},
+            },
+            purchases::{expense_model::Expenses, purchase_category_model::PurchaseCategories},
+            sales::{
+                sales_order_charge_model::SalesOrderCharges,
+                sales_order_item_model::SalesOrderItems,
+                sales_order_model::{SalesOrderState, SalesOrders},
+            },
+        },
+        types::{db_uuid::DbUuid, local_date_time, money::Money, percentage::Percentage},
+        utils::{csv, export::ExportConfig},
+    },
+    error::{Error, Result},
+};
+
+// Commands
+
+/// Turns the orders, voided orders, payments and expenses of a date range into balanced
+/// journal entries, posted to the accounts in the account mappings
+pub struct GenerateJournalCommand {
+    pub from: NaiveDateTime,
+    pub to: NaiveDateTime,
+    pub cost_center_id: Option<DbUuid>,
+}
+
+/// Writes the journal of a date range to a file for an accounting package. The file appears
+/// once it is complete; until then it is written next to it with a `.partial` suffix.
+pub struct ExportJournalCommand {
+    pub export: JournalExportInput,
+    /// The directory `export.path` is in
+    pub config: ExportConfig,
+}
+
+// Command Implementations
+impl Command for GenerateJournalCommand {
+    type Output = Vec<JournalEntry>;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        if self.from >= self.to {
+            return Err(Error::ValidationError("The range must end after it starts".to_string()));
+        }
+
+        let mut query = Query::select();
+        query.from(AccountMappings::Table).columns(AccountMappings::all_columns());
+        let accounts = Accounts(
+            service
+                .db_adapter
+                .query_many::<AccountMapping>(&query)
+                .await?
+                .into_iter()
+                .map(|mapping| ((mapping.kind, mapping.source_id), mapping))
+                .collect(),
+        );
+
+        let mut entries = vec![];
+        self.sales(service, &accounts, &mut entries).await?;
+        self.payments(service, &accounts, &mut entries).await?;
+        self.expenses(service, &accounts, &mut entries).await?;
+
+        // Stable, so entries of the same date stay in the order they were generated in
+        entries.sort_by_key(|entry| entry.date);
+        Ok(entries)
+    }
+}
+
+impl Command for ExportJournalCommand {
+    type Output = DataExport;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let export = &self.export;
+        let extension = match export.format {
+            JournalFormat::Csv => "csv",
+            JournalFormat::QuickbooksIif => "iif",
+            JournalFormat::TallyXml => "xml",
+        };
+        let path = Path::new(&export.path);
+        if path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() != Some(extension) {
+            return Err(Error::ValidationError(format!("The file name must end in .{}", extension)));
+        }
+        let path = self.config.resolve(&export.path)?;
+
+        let command = GenerateJournalCommand {
+            from: export.from,
+            to: export.to,
+            cost_center_id: export.cost_center_id,
+        };
+        let entries = command.exec(service).await?;
+
+        let partial = path.with_extension(format!("{}.partial", extension));
+        if let Err(err) = write(export.format, &entries, &partial) {
+            let _ = fs::remove_file(&partial);
+            return Err(err);
+        }
+        fs::rename(&partial, &path)?;
+
+        Ok(DataExport { path: path.to_string_lossy().into_owned(), rows: entries.len() as i32 })
+    }
+}
+
+/// The account mappings by kind and source
+struct Accounts(HashMap<(AccountMappingKind, Option<DbUuid>), AccountMapping>);
+
+impl Accounts {
+    /// The account mapped for the source, or else for its whole kind
+    fn get(&self, kind: AccountMappingKind, source: Option<(DbUuid, &str)>) -> Result<&AccountMapping> {
+        source
+            .and_then(|(id, _)| self.0.get(&(kind, Some(id))))
+            .or_else(|| self.0.get(&(kind, None)))
+            .ok_or_else(|| {
+                Error::ValidationError(match source {
+                    Some((_, name)) => format!("No {} account is mapped for {}", kind, name),
+                    None => format!("No {} account is mapped", kind),
+                })
+            })
+    }
+}
+
+/// The lines of an entry, one per account and side
+struct Postings {
+    lines: Vec<JournalLine>,
+    cost_center: Option<String>,
+    location: Option<String>,
+}
+
+impl Postings {
+    fn new(cost_center: &Option<String>, location: &Option<String>) -> Self {
+        Postings { lines: vec![], cost_center: cost_center.clone(), location: location.clone() }
+    }
+
+    fn debit(&mut self, account: &AccountMapping, amount: Money) {
+        self.post(account, amount, Money::from(0));
+    }
+
+    fn credit(&mut self, account: &AccountMapping, amount: Money) {
+        self.post(account, Money::from(0), amount);
+    }
+
+    fn post(&mut self, account: &AccountMapping, debit: Money, credit: Money) {
+        let zero = Money::from(0);
+        // A negative amount is posted to the other side
+        let (debit, credit) = match debit - credit {
+            amount if amount == zero => return,
+            amount if amount > zero => (amount, zero),
+            amount => (zero, zero - amount),
+        };
+        let line = self.lines.iter_mut().find(|line| {
+            line.account_code == account.account_code && (line.debit > zero) == (debit > zero)
+        });
+        match line {
+            Some(line) => {
+                line.debit = line.debit + debit;
+                line.credit = line.credit + credit;
+            }
+            None => self.lines.push(JournalLine {
+                account_code: account.account_code.clone(),
+                account_name: account.account_name.clone(),
+                debit,
+                credit,
+                cost_center: self.cost_center.clone(),
+                location: self.location.clone(),
+            }),
+        }
+    }
+
+    /// Debits less credits
+    fn balance(&self) -> Money {
+        self.lines.iter().map(|line| line.debit - line.credit).sum()
+    }
+
+    fn reversed(&self) -> Vec<JournalLine> {
+        self.lines
+            .iter()
+            .map(|line| JournalLine { debit: line.credit, credit: line.debit, ..line.clone() })
+            .collect()
+    }
+}
+
+#[derive(LibsqlFromRow)]
+struct OrderRow {
+    id: DbUuid,
+    order_number: String,
+    order_date: NaiveDateTime,
+    order_state: SalesOrderState,
+    updated_at: NaiveDateTime,
+    total_amount: Money,
+    cost_center: Option<String>,
+    location: Option<String>,
+}
+
+#[derive(LibsqlFromRow)]
+struct OrderItemRow {
+    order_id: DbUuid,
+    item_id: Option<DbUuid>,
+    category_id: Option<DbUuid>,
+    category: Option<String>,
+    disc_amount: Money,
+    taxable_amount: Money,
+    tax_amount: Money,
+}
+
+#[derive(LibsqlFromRow)]
+struct OrderChargeRow {
+    order_id: DbUuid,
+    charge_type_id: DbUuid,
+    charge_type_name: String,
+    tax_group_id: Option<DbUuid>,
+    amount: Money,
+    tax_amount: Money,
+}
+
+/// A tax of an item or of a tax group
+#[derive(LibsqlFromRow)]
+struct TaxRow {
+    owner_id: DbUuid,
+    tax_id: DbUuid,
+    name: String,
+    rate: Percentage,
+}
+
+#[derive(LibsqlFromRow)]
+struct PaymentRow {
+    id: DbUuid,
+    order_number: String,
+    payment_date: NaiveDateTime,
+    updated_at: NaiveDateTime,
+    state: PaymentState,
+    amount: Money,
+    payment_method_id: DbUuid,
+    payment_method: Option<String>,
+    cost_center: Option<String>,
+    location: Option<String>,
+}
+
+#[derive(LibsqlFromRow)]
+struct ExpenseRow {
+    id: DbUuid,
+    title: String,
+    expense_date: NaiveDateTime,
+    amount: Money,
+    category_id: DbUuid,
+    category: Option<String>,
+    cost_center: Option<String>,
+}
+
+impl GenerateJournalCommand {
+    fn in_range(&self, column: Expr) -> Cond {
+        Cond::all()
+            .add(column.clone().gte(self.from.to_string()))
+            .add(column.lt(self.to.to_string()))
+    }
+
+    /// Orders sold in the range, and orders voided in it
+    fn orders(&self) -> Cond {
+        let mut cond = Cond::all().add(
+            Cond::any()
+                .add(self.in_range(Expr::col((SalesOrders::Table, SalesOrders::OrderDate))).add(
+                    Expr::col((SalesOrders::Table, SalesOrders::OrderState)).is_in([
+                        SalesOrderState::Completed.to_string(),
+                        SalesOrderState::Cancelled.to_string(),
+                    ]),
+                ))
+                .add(self.in_range(Expr::col((SalesOrders::Table, SalesOrders::UpdatedAt))).add(
+                    Expr::col((SalesOrders::Table, SalesOrders::OrderState)).eq(SalesOrderState::Cancelled.to_string()),
+                )),
+        );
+        if let Some(cost_center_id) = self.cost_center_id {
+            cond = cond.add(Expr::col((SalesOrders::Table, SalesOrders::CostCenterId)).eq(cost_center_id.to_string()));
+        }
+        cond
+    }
+
+    async fn sales(&self, service: &AppService, accounts: &Accounts, entries: &mut Vec<JournalEntry>) -> Result<()> {
+        let mut query = Query::select();
+        query
+            .from(SalesOrders::Table)
+            .columns([
+                (SalesOrders::Table, SalesOrders::Id),
+                (SalesOrders::Table, SalesOrders::OrderDate),
+                (SalesOrders::Table, SalesOrders::OrderState),
+                (SalesOrders::Table, SalesOrders::UpdatedAt),
+                (SalesOrders::Table, SalesOrders::TotalAmount),
+            ])
+            .expr_as(Expr::col((SalesOrders::Table, SalesOrders::OrderReadableId)), Alias::new("order_number"))
+            .expr_as(Expr::col((CostCenters::Table, CostCenters::Name)), Alias::new("cost_center"))
+            .expr_as(Expr::col((Locations::Table, Locations::Name)), Alias::new("location"))
+            .left_join(
+                CostCenters::Table,
+                Expr::col((CostCenters::Table, CostCenters::Id)).equals((SalesOrders::Table, SalesOrders::CostCenterId)),
+            )
+            .left_join(
+                Locations::Table,
+                Expr::col((Locations::Table, Locations::Id)).equals((SalesOrders::Table, SalesOrders::LocationId)),
+            )
+            .cond_where(self.orders())
+            .order_by((SalesOrders::Table, SalesOrders::OrderDate), Order::Asc)
+            .order_by((SalesOrders::Table, SalesOrders::Id), Order::Asc);
+        let orders = service.db_adapter.query_many::<OrderRow>(&query).await?;
+        if orders.is_empty() {
+            return Ok(());
+        }
+
+        let mut query = Query::select();
+        query
+            .from(SalesOrderItems::Table)
+            .columns([
+                (SalesOrderItems::Table, SalesOrderItems::OrderId),
+                (SalesOrderItems::Table, SalesOrderItems::ItemId),
+                (SalesOrderItems::Table, SalesOrderItems::DiscAmount),
+                (SalesOrderItems::Table, SalesOrderItems::TaxableAmount),
+                (SalesOrderItems::Table, SalesOrderItems::TaxAmount),
+            ])
+            .expr_as(Expr::col((Items::Table, Items::CategoryId)), Alias::new("category_id"))
+            .expr_as(Expr::col((ItemCategories::Table, ItemCategories::Name)), Alias::new("category"))
+            .left_join(Items::Table, Expr::col((Items::Table, Items::Id)).equals((SalesOrderItems::Table, SalesOrderItems::ItemId)))
+            .left_join(
+                ItemCategories::Table,
+                Expr::col((ItemCategories::Table, ItemCategories::Id)).equals((Items::Table, Items::CategoryId)),
+            )
+            .and_where(Expr::col((SalesOrderItems::Table, SalesOrderItems::OrderId)).in_subquery(self.order_ids()));
+        let mut items: HashMap<DbUuid, Vec<OrderItemRow>> = HashMap::new();
+        for item in service.db_adapter.query_many::<OrderItemRow>(&query).await? {
+            items.entry(item.order_id).or_default().push(item);
+        }
+
+        let mut query = Query::select();
+        query
+            .from(SalesOrderCharges::Table)
+            .columns([
+                SalesOrderCharges::OrderId,
+                SalesOrderCharges::ChargeTypeId,
+                SalesOrderCharges::ChargeTypeName,
+                SalesOrderCharges::TaxGroupId,
+                SalesOrderCharges::Amount,
+                SalesOrderCharges::TaxAmount,
+            ])
+            .and_where(Expr::col(SalesOrderCharges::OrderId).in_subquery(self.order_ids()));
+        let mut charges: HashMap<DbUuid, Vec<OrderChargeRow>> = HashMap::new();
+        for charge in service.db_adapter.query_many::<OrderChargeRow>(&query).await? {
+            charges.entry(charge.order_id).or_default().push(charge);
+        }
+
+        let item_taxes = taxes(service, taxes_of(ItemTaxes::Table, ItemTaxes::ItemId, ItemTaxes::TaxId)).await?;
+        let group_taxes =
+            taxes(service, taxes_of(TaxGroupTaxes::Table, TaxGroupTaxes::TaxGroupId, TaxGroupTaxes::TaxId)).await?;
+
+        for order in orders {
+            let mut postings = Postings::new(&order.cost_center, &order.location);
+            postings.debit(accounts.get(AccountMappingKind::Receivable, None)?, order.total_amount);
+
+            for item in items.get(&order.id).into_iter().flatten() {
+                let category = item.category_id.map(|id| (id, item.category.as_deref().unwrap_or_default()));
+                postings.credit(
+                    accounts.get(AccountMappingKind::Revenue, category)?,
+                    item.taxable_amount + item.disc_amount,
+                );
+                postings.debit(accounts.get(AccountMappingKind::Discounts, None)?, item.disc_amount);
+                let taxes = item.item_id.and_then(|id| item_taxes.get(&id));
+                post_taxes(&mut postings, accounts, item.tax_amount, taxes)?;
+            }
+            for charge in charges.get(&order.id).into_iter().flatten() {
+                let charge_type = Some((charge.charge_type_id, charge.charge_type_name.as_str()));
+                postings.credit(accounts.get(AccountMappingKind::Charges, charge_type)?, charge.amount);
+                let taxes = charge.tax_group_id.and_then(|id| group_taxes.get(&id));
+                post_taxes(&mut postings, accounts, charge.tax_amount, taxes)?;
+            }
+
+            let difference = postings.balance();
+            if difference != Money::from(0) {
+                postings.credit(accounts.get(AccountMappingKind::Rounding, None)?, difference);
+            }
+
+            let order_sold = order.order_date >= self.from && order.order_date < self.to;
+            let order_voided = order.order_state == SalesOrderState::Cancelled
+                && order.updated_at >= self.from
+                && order.updated_at < self.to;
+            if order_sold {
+                entries.push(JournalEntry {
+                    date: order.order_date,
+                    kind: JournalEntryKind::Sale,
+                    source_id: order.id,
+                    reference: order.order_number.clone(),
+                    description: format!("Sale {}", order.order_number),
+                    lines: postings.lines.clone(),
+                });
+            }
+            if order_voided {
+                entries.push(JournalEntry {
+                    date: order.updated_at,
+                    kind: JournalEntryKind::Refund,
+                    source_id: order.id,
+                    reference: order.order_number.clone(),
+                    description: format!("Void of sale {}", order.order_number),
+                    lines: postings.reversed(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn order_ids(&self) -> SelectStatement {
+        Query::select()
+            .column((SalesOrders::Table, SalesOrders::Id))
+            .from(SalesOrders::Table)
+            .cond_where(self.orders())
+            .to_owned()
+    }
+
+    async fn payments(&self, service: &AppService, accounts: &Accounts, entries: &mut Vec<JournalEntry>) -> Result<()> {
+        let state = Expr::col((SalesOrderPayments::Table, SalesOrderPayments::State));
+        let mut query = Query::select();
+        query
+            .from(SalesOrderPayments::Table)
+            .columns([
+                (SalesOrderPayments::Table, SalesOrderPayments::Id),
+                (SalesOrderPayments::Table, SalesOrderPayments::PaymentDate),
+                (SalesOrderPayments::Table, SalesOrderPayments::UpdatedAt),
+                (SalesOrderPayments::Table, SalesOrderPayments::State),
+                (SalesOrderPayments::Table, SalesOrderPayments::Amount),
+                (SalesOrderPayments::Table, SalesOrderPayments::PaymentMethodId),
+            ])
+            .expr_as(Expr::col((SalesOrders::Table, SalesOrders::OrderReadableId)), Alias::new("order_number"))
+            .expr_as(Expr::col((PaymentMethods::Table, PaymentMethods::Name)), Alias::new("payment_method"))
+            .expr_as(Expr::col((CostCenters::Table, CostCenters::Name)), Alias::new("cost_center"))
+            .expr_as(Expr::col((Locations::Table, Locations::Name)), Alias::new("location"))
+            .inner_join(
+                SalesOrders::Table,
+                Expr::col((SalesOrders::Table, SalesOrders::Id)).equals((SalesOrderPayments::Table, SalesOrderPayments::OrderId)),
+            )
+            .left_join(
+                PaymentMethods::Table,
+                Expr::col((PaymentMethods::Table, PaymentMethods::Id))
+                    .equals((SalesOrderPayments::Table, SalesOrderPayments::PaymentMethodId)),
+            )
+            .left_join(
+                CostCenters::Table,
+                Expr::col((CostCenters::Table, CostCenters::Id)).equals((SalesOrders::Table, SalesOrders::CostCenterId)),
+            )
+            .left_join(
+                Locations::Table,
+                Expr::col((Locations::Table, Locations::Id)).equals((SalesOrders::Table, SalesOrders::LocationId)),
+            )
+            .cond_where(
+                Cond::any()
+                    .add(
+                        self.in_range(Expr::col((SalesOrderPayments::Table, SalesOrderPayments::PaymentDate)))
+                            .add(state.clone().is_in([PaymentState::Completed.to_string(), PaymentState::Voided.to_string()])),
+                    )
+                    .add(
+                        self.in_range(Expr::col((SalesOrderPayments::Table, SalesOrderPayments::UpdatedAt)))
+                            .add(state.eq(PaymentState::Voided.to_string())),
+                    ),
+            )
+            .order_by((SalesOrderPayments::Table, SalesOrderPayments::PaymentDate), Order::Asc)
+            .order_by((SalesOrderPayments::Table, SalesOrderPayments::Id), Order::Asc);
+        if let Some(cost_center_id) = self.cost_center_id {
+            query.and_where(Expr::col((SalesOrders::Table, SalesOrders::CostCenterId)).eq(cost_center_id.to_string()));
+        }
+
+        for payment in service.db_adapter.query_many::<PaymentRow>(&query).await? {
+            let method = payment.payment_method.as_deref().unwrap_or_default();
+            let mut postings = Postings::new(&payment.cost_center, &payment.location);
+            postings.debit(
+                accounts.get(AccountMappingKind::PaymentClearing, Some((payment.payment_method_id, method)))?,
+                payment.amount,
+            );
+            postings.credit(accounts.get(AccountMappingKind::Receivable, None)?, payment.amount);
+
+            if payment.payment_date >= self.from && payment.payment_date < self.to {
+                entries.push(JournalEntry {
+                    date: payment.payment_date,
+                    kind: JournalEntryKind::Payment,
+                    source_id: payment.id,
+                    reference: payment.order_number.clone(),
+                    description: format!("{} payment for {}", method, payment.order_number),
+                    lines: postings.lines.clone(),
+                });
+            }
+            if payment.state == PaymentState::Voided && payment.updated_at >= self.from && payment.updated_at < self.to {
+                entries.push(JournalEntry {
+                    date: payment.updated_at,
+                    kind: JournalEntryKind::PaymentVoid,
+                    source_id: payment.id,
+                    reference: payment.order_number.clone(),
+                    description: format!("Void of {} payment for {}", method, payment.order_number),
+                    lines: postings.reversed(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn expenses(&self, service: &AppService, accounts: &Accounts, entries: &mut Vec<JournalEntry>) -> Result<()> {
+        let mut query = Query::select();
+        query
+            .from(Expenses::Table)
+            .columns([
+                (Expenses::Table, Expenses::Id),
+                (Expenses::Table, Expenses::Title),
+                (Expenses::Table, Expenses::ExpenseDate),
+                (Expenses::Table, Expenses::Amount),
+                (Expenses::Table, Expenses::CategoryId),
+            ])
+            .expr_as(Expr::col((PurchaseCategories::Table, PurchaseCategories::Name)), Alias::new("category"))
+            .expr_as(Expr::col((CostCenters::Table, CostCenters::Name)), Alias::new("cost_center"))
+            .left_join(
+                PurchaseCategories::Table,
+                Expr::col((PurchaseCategories::Table, PurchaseCategories::Id)).equals((Expenses::Table, Expenses::CategoryId)),
+            )
+            .left_join(
+                CostCenters::Table,
+                Expr::col((CostCenters::Table, CostCenters::Id)).equals((Expenses::Table, Expenses::CostCenterId)),
+            )
+            .cond_where(self.in_range(Expr::col((Expenses::Table, Expenses::ExpenseDate))))
+            .order_by((Expenses::Table, Expenses::ExpenseDate), Order::Asc)
+            .order_by((Expenses::Table, Expenses::Id), Order::Asc);
+        if let Some(cost_center_id) = self.cost_center_id {
+            query.and_where(Expr::col((Expenses::Table, Expenses::CostCenterId)).eq(cost_center_id.to_string()));
+        }
+
+        for expense in service.db_adapter.query_many::<ExpenseRow>(&query).await? {
+            let category = expense.category.as_deref().unwrap_or_default();
+            let mut postings = Postings::new(&expense.cost_center, &None);
+            postings.debit(
+                accounts.get(AccountMappingKind::Expense, Some((expense.category_id, category)))?,
+                expense.amount,
+            );
+            postings.credit(accounts.get(AccountMappingKind::ExpensePayable, None)?, expense.amount);
+
+            entries.push(JournalEntry {
+                date: expense.expense_date,
+                kind: JournalEntryKind::Expense,
+                source_id: expense.id,
+                reference: expense.title.clone(),
+                description: format!("{}: {}", category, expense.title),
+                lines: postings.lines,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Selects the taxes of every item or tax group, from the table that links them
+fn taxes_of<T, C>(table: T, owner: C, tax: C) -> SelectStatement
+where
+    T: sea_query::Iden + Copy + 'static,
+    C: sea_query::Iden + Copy + 'static,
+{
+    Query::select()
+        .from(table)
+        .expr_as(Expr::col((table, owner)), Alias::new("owner_id"))
+        .expr_as(Expr::col((table, tax)), Alias::new("tax_id"))
+        .columns([(Taxes::Table, Taxes::Name), (Taxes::Table, Taxes::Rate)])
+        .inner_join(Taxes::Table, Expr::col((Taxes::Table, Taxes::Id)).equals((table, tax)))
+        .order_by((Taxes::Table, Taxes::Name), Order::Asc)
+        .to_owned()
+}
+
+async fn taxes(service: &AppService, query: SelectStatement) -> Result<HashMap<DbUuid, Vec<TaxRow>>> {
+    let mut taxes: HashMap<DbUuid, Vec<TaxRow>> = HashMap::new();
+    for tax in service.db_adapter.query_many::<TaxRow>(&query).await? {
+        taxes.entry(tax.owner_id).or_default().push(tax);
+    }
+    Ok(taxes)
+}
+
+/// Credits tax collected to the accounts of the taxes it was charged for, in proportion to
+/// their rates
+fn post_taxes(postings: &mut Postings, accounts: &Accounts, amount: Money, taxes: Option<&Vec<TaxRow>>) -> Result<()> {
+    match taxes {
+        Some(taxes) if !taxes.is_empty() => {
+            let mut weights: Vec<i64> = taxes.iter().map(|tax| tax.rate.basis_points() as i64).collect();
+            if weights.iter().all(|weight| *weight == 0) {
+                weights = vec![1; taxes.len()];
+            }
+            for (tax, share) in taxes.iter().zip(amount.allocate(&weights)) {
+                let account = accounts.get(AccountMappingKind::TaxPayable, Some((tax.tax_id, tax.name.as_str())))?;
+                postings.credit(account, share);
+            }
+        }
+        _ if amount != Money::from(0) => {
+            postings.credit(accounts.get(AccountMappingKind::TaxPayable, None)?, amount);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn write(format: JournalFormat, entries: &[JournalEntry], path: &Path) -> Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    match format {
+        JournalFormat::Csv => write_csv(&mut out, entries)?,
+        JournalFormat::QuickbooksIif => write_iif(&mut out, entries)?,
+        JournalFormat::TallyXml => write_tally(&mut out, entries)?,
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn amount(value: Money) -> String {
+    match value == Money::from(0) {
+        true => String::new(),
+        false => value.to_string(),
+    }
+}
+
+fn write_csv<W: Write>(out: &mut W, entries: &[JournalEntry]) -> Result<()> {
+    csv::write_record(
+        out,
+        &[
+            "entry",
+            "date",
+            "kind",
+            "reference",
+            "description",
+            "account_code",
+            "account_name",
+            "debit",
+            "credit",
+            "cost_center",
+            "location",
+        ],
+    )?;
+    for (number, entry) in entries.iter().enumerate() {
+        for line in &entry.lines {
+            csv::write_record(
+                out,
+                &[
+                    (number + 1).to_string(),
+                    entry.date.format(local_date_time::FORMAT).to_string(),
+                    entry.kind.to_string(),
+                    entry.reference.clone(),
+                    entry.description.clone(),
+                    line.account_code.clone(),
+                    line.account_name.clone(),
+                    amount(line.debit),
+                    amount(line.credit),
+                    line.cost_center.clone().unwrap_or_default(),
+                    line.location.clone().unwrap_or_default(),
+                ],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// IIF is tab separated with no quoting, so tabs and line breaks in text become spaces
+fn iif_text(text: &str) -> String {
+    text.replace(['\t', '\r', '\n'], " ")
+}
+
+/// Each entry is a transaction: the first line on a `TRNS` row and the others on `SPL` rows.
+/// Debits are positive and credits negative, and accounts are matched by name.
+fn write_iif<W: Write>(out: &mut W, entries: &[JournalEntry]) -> Result<()> {
+    write!(out, "!TRNS\tTRNSTYPE\tDATE\tACCNT\tCLASS\tAMOUNT\tDOCNUM\tMEMO\r\n")?;
+    write!(out, "!SPL\tTRNSTYPE\tDATE\tACCNT\tCLASS\tAMOUNT\tDOCNUM\tMEMO\r\n")?;
+    write!(out, "!ENDTRNS\r\n")?;
+    for entry in entries {
+        let date = entry.date.format("%m/%d/%Y");
+        for (index, line) in entry.lines.iter().enumerate() {
+            write!(
+                out,
+                "{}\tGENERAL JOURNAL\t{}\t{}\t{}\t{}\t{}\t{}\r\n",
+                if index == 0 { "TRNS" } else { "SPL" },
+                date,
+                iif_text(&line.account_name),
+                iif_text(line.cost_center.as_deref().unwrap_or_default()),
+                (line.debit - line.credit).to_string(),
+                iif_text(&entry.reference),
+                iif_text(&entry.description),
+            )?;
+        }
+        write!(out, "ENDTRNS\r\n")?;
+    }
+    Ok(())
+}
+
+/// Each entry is a journal voucher. Tally takes debits as negative amounts that are deemed
+/// positive, and credits as positive amounts that are not; ledgers are matched by name.
+fn write_tally<W: Write>(out: &mut W, entries: &[JournalEntry]) -> Result<()> {
+    writeln!(
+        out,
+        "<ENVELOPE><HEADER><TALLYREQUEST>Import Data</TALLYREQUEST></HEADER><BODY><IMPORTDATA>\
+         <REQUESTDESC><REPORTNAME>Vouchers</REPORTNAME></REQUESTDESC><REQUESTDATA>"
+    )?;
+    for entry in entries {
+        write!(
+            out,
+            "<TALLYMESSAGE><VOUCHER VCHTYPE=\"Journal\" ACTION=\"Create\"><DATE>{}</DATE>\
+             <VOUCHERTYPENAME>Journal</VOUCHERTYPENAME><REFERENCE>{}</REFERENCE><NARRATION>{}</NARRATION>",
+            entry.date.format("%Y%m%d"),
+            escape(entry.reference.as_str()),
+            escape(entry.description.as_str()),
+        )?;
+        for line in &entry.lines {
+            let is_debit = line.debit > line.credit;
+            let value = (line.credit - line.debit).to_string();
+            write!(
+                out,
+                "<ALLLEDGERENTRIES.LIST><LEDGERNAME>{}</LEDGERNAME><ISDEEMEDPOSITIVE>{}</ISDEEMEDPOSITIVE>\
+                 <AMOUNT>{}</AMOUNT>",
+                escape(line.account_name.as_str()),
+                if is_debit { "Yes" } else { "No" },
+                value,
+            )?;
+            if let Some(cost_center) = &line.cost_center {
+                write!(
+                    out,
+                    "<CATEGORYALLOCATIONS.LIST><CATEGORY>Primary Cost Category</CATEGORY>\
+                     <COSTCENTREALLOCATIONS.LIST><NAME>{}</NAME><AMOUNT>{}</AMOUNT></COSTCENTREALLOCATIONS.LIST>\
+                     </CATEGORYALLOCATIONS.LIST>",
+                    escape(cost_center.as_str()),
+                    value,
+                )?;
+            }
+            write!(out, "</ALLLEDGERENTRIES.LIST>")?;
+        }
+        writeln!(out, "</VOUCHER></TALLYMESSAGE>")?;
+    }
+    writeln!(out, "</REQUESTDATA></IMPORTDATA></BODY></ENVELOPE>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::core::{
+        commands::{
+            auth::user_commands::AddUserCommand,
+            catalog::{item_commands::CreateItemCommand, item_group_commands::CreateItemGroupCommand},
+            common::{
+                channel_commands::CreateChannelCommand, location_commands::CreateLocationCommand,
+                tax_commands::CreateTaxCommand, tax_group_commands::CreateTaxGroupCommand,
+            },
+            finance::{
+                account_mapping_commands::{DeleteAccountMappingCommand, SetAccountMappingCommand},
+                cost_center_commands::CreateCostCenterCommand,
+                payment_method_commands::CreatePaymentMethodCommand,
+                sales_order_payment_commands::{CreateSalesOrderPaymentCommand, VoidSalesOrderPaymentCommand},
+            },
+            purchases::{expense_commands::CreateExpenseCommand, purchase_category_commands::CreatePurchaseCategoryCommand},
+            sales::{
+                sales_charge_type_commands::CreateSalesChargeTypeCommand,
+                sales_order_commands::{CreateSalesOrderCommand, VoidSalesOrderCommand},
+            },
+            tests::setup_service,
+        },
+        models::{
+            auth::user_model::{UserNewInput, UserRole, UserState},
+            catalog::{
+                item_group_model::ItemCategoryNew,
+                item_model::{ItemNature, ItemState, NewItem},
+            },
+            common::{
+                channel_model::ChannelNewInput, location_model::LocationNewInput, tax_group_model::TaxGroupNewInput,
+                tax_model::TaxNewInput,
+            },
+            finance::{
+                account_mapping_model::AccountMappingInput,
+                cost_center_model::{CostCenterNewInput, CostCenterState},
+                payment_method_model::PaymentMethodNewInput,
+                sales_order_payment_model::SalesOrderPaymentNewInput,
+            },
+            purchases::{expense_model::ExpenseNewInput, purchase_category_model::PurchaseCategoryNew},
+            sales::{
+                sales_charge_type_model::SalesChargeTypeNewInput, sales_order_charge_model::SalesOrderChargeNewInput,
+                sales_order_item_model::SalesOrderItemInput, sales_order_model::SalesOrderNewInput,
+            },
+        },
+    };
+
+    struct Fixture {
+        user_id: DbUuid,
+        orders: Vec<DbUuid>,
+        payment_id: DbUuid,
+        mappings: HashMap<&'static str, DbUuid>,
+    }
+
+    fn at(hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, 14).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn money(value: &str) -> Money {
+        Money::from_str(value).unwrap()
+    }
+
+    fn order(
+        lines: Vec<(Option<DbUuid>, &str, &str, &str)>,
+        charges: Vec<SalesOrderChargeNewInput>,
+        total: &str,
+    ) -> SalesOrderNewInput {
+        let items: Vec<_> = lines
+            .into_iter()
+            .map(|(item_id, disc, taxable, tax)| SalesOrderItemInput {
+                item_id,
+                variant_id: None,
+                item_name: "Tea".to_string(),
+                quantity: 2,
+                sku: None,
+                price_amount: (money(disc) + money(taxable)) / 2,
+                disc_amount: money(disc),
+                taxable_amount: money(taxable),
+                tax_amount: money(tax),
+                total_amount: money(taxable) + money(tax),
+                price_override: None,
+            })
+            .collect();
+        SalesOrderNewInput {
+            order_date: at(10),
+            customer_id: None,
+            customer_name: None,
+            customer_phone_number: None,
+            billing_address: None,
+            shipping_address: None,
+            net_amount: items.iter().map(|item| item.price_amount * item.quantity).sum(),
+            disc_amount: items.iter().map(|item| item.disc_amount).sum(),
+            taxable_amount: items.iter().map(|item| item.taxable_amount).sum(),
+            tax_amount: items.iter().map(|item| item.tax_amount).sum(),
+            total_amount: money(total),
+            notes: None,
+            channel_id: Uuid::nil().into(),
+            location_id: Uuid::nil().into(),
+            cost_center_id: Uuid::nil().into(),
+            discount_id: None,
+            items,
+            charges: Some(charges),
+        }
+    }
+
+    /// A sale of taxed tea with delivery, a sale with an untaxed custom line that does not add
+    /// up to its total, a cash payment for the first and an expense, all on one day
+    async fn setup(service: &AppService) -> Fixture {
+        let user_id = AddUserCommand {
+            user: UserNewInput {
+                username: "accountant".to_string(),
+                pin_hash: "1234".to_string(),
+                full_name: "Accountant".to_string(),
+                state: UserState::Active,
+                role: UserRole::Cashier,
+                last_login_at: None,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap()
+        .id;
+        let cost_center = CreateCostCenterCommand {
+            cost_center: CostCenterNewInput {
+                name: "Kitchen".to_string(),
+                code: "KIT".to_string(),
+                description: None,
+                state: Some(CostCenterState::Active),
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+        let channel = CreateChannelCommand {
+            channel: ChannelNewInput { name: "Counter".to_string(), description: None, is_active: Some(true) },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+        let location = CreateLocationCommand {
+            location: LocationNewInput {
+                name: "Main".to_string(),
+                description: None,
+                address: None,
+                is_active: Some(true),
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+
+        let mut taxes = vec![];
+        for name in ["CGST", "SGST"] {
+            let command = CreateTaxCommand {
+                tax: TaxNewInput {
+                    name: name.to_string(),
+                    rate: Percentage::from_str("2.5").unwrap(),
+                    description: None,
+                    item_ids: None,
+                },
+            };
+            taxes.push(command.exec(service).await.unwrap());
+        }
+        let tax_group = CreateTaxGroupCommand {
+            tax_group: TaxGroupNewInput {
+                name: "GST 5%".to_string(),
+                description: None,
+                tax_ids: Some(taxes.iter().map(|tax| tax.id).collect()),
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+        let category = CreateItemGroupCommand {
+            category: ItemCategoryNew { name: "Drinks".to_string(), description: None },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+        let item = CreateItemCommand {
+            item: NewItem {
+                name: "Tea".to_string(),
+                description: None,
+                nature: ItemNature::Goods,
+                state: ItemState::Active,
+                price: money("100"),
+                category_id: category.id,
+                tax_ids: Some(taxes.iter().map(|tax| tax.id).collect()),
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+        let charge_type = CreateSalesChargeTypeCommand {
+            charge_type: SalesChargeTypeNewInput { name: "Delivery".to_string(), description: None },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+
+        let delivery = SalesOrderChargeNewInput {
+            charge_type_id: charge_type.id,
+            charge_type_name: charge_type.name.clone(),
+            amount: money("30"),
+            tax_amount: money("1.50"),
+            tax_group_id: Some(tax_group.id),
+        };
+        let mut orders = vec![];
+        for mut sales_order in [
+            order(vec![(Some(item.id), "20", "180", "9")], vec![delivery], "220.50"),
+            order(vec![(None, "0", "100", "5")], vec![], "105.02"),
+        ] {
+            sales_order.channel_id = channel.id;
+            sales_order.location_id = location.id;
+            sales_order.cost_center_id = cost_center.id;
+            let command = CreateSalesOrderCommand { sales_order, created_by_user_id: user_id };
+            orders.push(command.exec(service).await.unwrap().id);
+        }
+
+        let cash = CreatePaymentMethodCommand {
+            payment_method: PaymentMethodNewInput {
+                name: "Cash".to_string(),
+                code: "CASH".to_string(),
+                description: None,
+                state: None,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+        let payment_id = CreateSalesOrderPaymentCommand {
+            payment: SalesOrderPaymentNewInput {
+                order_id: orders[0],
+                payment_method_id: cash.id,
+                payment_date: at(11),
+                amount: Some(money("220.50")),
+                reference_number: None,
+                notes: None,
+                state: None,
+                foreign_amount: None,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap()
+        .id;
+
+        let supplies = CreatePurchaseCategoryCommand {
+            category: PurchaseCategoryNew { name: "Supplies".to_string(), description: None, state: None },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+        CreateExpenseCommand {
+            expense: ExpenseNewInput {
+                title: "Napkins".to_string(),
+                amount: money("12.50"),
+                expense_date: at(9),
+                category_id: supplies.id,
+                cost_center_id: cost_center.id,
+                description: None,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+
+        let mut mappings = HashMap::new();
+        for (kind, source_id, code, name) in [
+            (AccountMappingKind::Receivable, None, "1100", "Accounts Receivable"),
+            (AccountMappingKind::Revenue, None, "4000", "Sales"),
+            (AccountMappingKind::Revenue, Some(category.id), "4010", "Sales - Drinks"),
+            (AccountMappingKind::TaxPayable, None, "2200", "Tax Payable"),
+            (AccountMappingKind::TaxPayable, Some(taxes[0].id), "2210", "CGST Payable"),
+            (AccountMappingKind::TaxPayable, Some(taxes[1].id), "2220", "SGST Payable"),
+            (AccountMappingKind::Charges, None, "4100", "Delivery Income"),
+            (AccountMappingKind::Discounts, None, "4500", "Discounts Given"),
+            (AccountMappingKind::PaymentClearing, Some(cash.id), "1010", "Cash in Drawer"),
+            (AccountMappingKind::Expense, Some(supplies.id), "5010", "Supplies"),
+            (AccountMappingKind::ExpensePayable, None, "2000", "Accounts Payable"),
+            (AccountMappingKind::Rounding, None, "4900", "Rounding"),
+        ] {
+            let command = SetAccountMappingCommand {
+                mapping: AccountMappingInput {
+                    kind,
+                    source_id,
+                    account_code: code.to_string(),
+                    account_name: name.to_string(),
+                },
+            };
+            mappings.insert(code, command.exec(service).await.unwrap().id);
+        }
+
+        Fixture { user_id, orders, payment_id, mappings }
+    }
+
+    fn journal(from: NaiveDateTime, to: NaiveDateTime) -> GenerateJournalCommand {
+        GenerateJournalCommand { from, to, cost_center_id: None }
+    }
+
+    /// The debit less the credit on the account
+    fn posted(entry: &JournalEntry, code: &str) -> Money {
+        entry
+            .lines
+            .iter()
+            .filter(|line| line.account_code == code)
+            .map(|line| line.debit - line.credit)
+            .sum()
+    }
+
+    fn assert_balanced(entries: &[JournalEntry]) {
+        for entry in entries {
+            let debits: Money = entry.lines.iter().map(|line| line.debit).sum();
+            let credits: Money = entry.lines.iter().map(|line| line.credit).sum();
+            assert_eq!(debits, credits, "{} is not balanced", entry.description);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_journal_posts_sales_payments_and_expenses() {
+        let service = setup_service().await;
+        let fixture = setup(&service).await;
+
+        let entries = journal(at(0), at(0) + Duration::days(1)).exec(&service).await.unwrap();
+        let kinds: Vec<_> = entries.iter().map(|entry| entry.kind).collect();
+        assert_eq!(
+            kinds,
+            [JournalEntryKind::Expense, JournalEntryKind::Sale, JournalEntryKind::Sale, JournalEntryKind::Payment]
+        );
+        assert_balanced(&entries);
+
+        let sale = entries.iter().find(|entry| entry.source_id == fixture.orders[0]).unwrap();
+        assert_eq!(posted(sale, "1100"), money("220.50"));
+        assert_eq!(posted(sale, "4010"), money("-200"));
+        assert_eq!(posted(sale, "4500"), money("20"));
+        assert_eq!(posted(sale, "2210"), money("-5.25"));
+        assert_eq!(posted(sale, "2220"), money("-5.25"));
+        assert_eq!(posted(sale, "4100"), money("-30"));
+        assert_eq!(sale.lines[0].cost_center.as_deref(), Some("Kitchen"));
+        assert_eq!(sale.lines[0].location.as_deref(), Some("Main"));
+
+        // Without an item or taxes of its own, the defaults are used, and the cent it is off
+        // by goes to rounding
+        let sale = entries.iter().find(|entry| entry.source_id == fixture.orders[1]).unwrap();
+        assert_eq!(posted(sale, "4000"), money("-100"));
+        assert_eq!(posted(sale, "2200"), money("-5"));
+        assert_eq!(posted(sale, "4900"), money("-0.02"));
+
+        assert_eq!(posted(&entries[3], "1010"), money("220.50"));
+        assert_eq!(posted(&entries[3], "1100"), money("-220.50"));
+        assert_eq!(posted(&entries[0], "5010"), money("12.50"));
+        assert_eq!(posted(&entries[0], "2000"), money("-12.50"));
+
+        let mut command = journal(at(0), at(0) + Duration::days(1));
+        command.cost_center_id = Some(Uuid::now_v7().into());
+        assert!(command.exec(&service).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_journal_reverses_voids_when_voided() {
+        let service = setup_service().await;
+        let fixture = setup(&service).await;
+        let command = VoidSalesOrderCommand { id: fixture.orders[0], updated_by_user_id: fixture.user_id };
+        command.exec(&service).await.unwrap();
+        VoidSalesOrderPaymentCommand { id: fixture.payment_id }.exec(&service).await.unwrap();
+
+        // On the day of the sale, it is still posted as it was
+        let entries = journal(at(0), at(0) + Duration::days(1)).exec(&service).await.unwrap();
+        assert_eq!(entries.len(), 4);
+
+        let now = Utc::now().naive_utc();
+        let entries = journal(now - Duration::hours(1), now + Duration::hours(1)).exec(&service).await.unwrap();
+        assert_balanced(&entries);
+        let refund = entries.iter().find(|entry| entry.kind == JournalEntryKind::Refund).unwrap();
+        assert_eq!(refund.source_id, fixture.orders[0]);
+        assert_eq!(posted(refund, "1100"), money("-220.50"));
+        assert_eq!(posted(refund, "4010"), money("200"));
+        let payment_void = entries.iter().find(|entry| entry.kind == JournalEntryKind::PaymentVoid).unwrap();
+        assert_eq!(posted(payment_void, "1010"), money("-220.50"));
+        assert_eq!(entries.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_journal_needs_every_account_mapped() {
+        let service = setup_service().await;
+        let fixture = setup(&service).await;
+        let command = DeleteAccountMappingCommand { id: fixture.mappings["2210"] };
+        command.exec(&service).await.unwrap();
+
+        // Falls back to the tax payable account for taxes without one
+        let entries = journal(at(0), at(0) + Duration::days(1)).exec(&service).await.unwrap();
+        assert_eq!(posted(&entries[1], "2200"), money("-5.25"));
+
+        DeleteAccountMappingCommand { id: fixture.mappings["2200"] }.exec(&service).await.unwrap();
+        match journal(at(0), at(0) + Duration::days(1)).exec(&service).await {
+            Err(Error::ValidationError(message)) => assert_eq!(message, "No TaxPayable account is mapped for CGST"),
+            result => panic!("Unexpected result {:?}", result.map(|entries| entries.len())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_journal_formats() {
+        let service = setup_service().await;
+        setup(&service).await;
+
+        let config = ExportConfig { dir: std::env::temp_dir().join(format!("minnal-exports-{}", Uuid::now_v7())) };
+        let export = |format, path: &str| ExportJournalCommand {
+            export: JournalExportInput {
+                format,
+                from: at(0),
+                to: at(0) + Duration::days(1),
+                cost_center_id: None,
+                path: path.to_string(),
+            },
+            config: config.clone(),
+        };
+
+        let result = export(JournalFormat::Csv, "journal.csv").exec(&service).await.unwrap();
+        assert_eq!(result.rows, 4);
+        let rows = csv::read(&fs::read_to_string(&result.path).unwrap()).unwrap();
+        fs::remove_file(&result.path).unwrap();
+        assert_eq!(rows[0][..3], ["entry", "date", "kind"]);
+        assert_eq!(rows[1][..3], ["1", "2025-03-14 09:00:00", "Expense"]);
+        assert_eq!(rows[1][7..9], ["12.50", ""]);
+        assert_eq!(rows.iter().filter(|row| row[0] == "2").count(), 6);
+
+        let result = export(JournalFormat::QuickbooksIif, "journal.iif").exec(&service).await.unwrap();
+        let text = fs::read_to_string(&result.path).unwrap();
+        fs::remove_file(&result.path).unwrap();
+        let lines: Vec<Vec<&str>> = text.lines().map(|line| line.split('\t').collect()).collect();
+        assert_eq!(lines.iter().filter(|line| line[0] == "TRNS").count(), 4);
+        assert_eq!(lines.iter().filter(|line| line[0] == "ENDTRNS").count(), 4);
+        assert_eq!(lines[3], ["TRNS", "GENERAL JOURNAL", "03/14/2025", "Supplies", "Kitchen", "12.50", "Napkins", "Supplies: Napkins"]);
+        let total: Money = lines
+            .iter()
+            .filter(|line| line[0] == "TRNS" || line[0] == "SPL")
+            .map(|line| money(line[5]))
+            .sum();
+        assert_eq!(total, Money::from(0));
+
+        let result = export(JournalFormat::TallyXml, "journal.xml").exec(&service).await.unwrap();
+        let text = fs::read_to_string(&result.path).unwrap();
+        fs::remove_file(&result.path).unwrap();
+        assert_eq!(text.matches("<VOUCHER ").count(), 4);
+        assert!(text.contains(
+            "<LEDGERNAME>Supplies</LEDGERNAME><ISDEEMEDPOSITIVE>Yes</ISDEEMEDPOSITIVE><AMOUNT>-12.50</AMOUNT>"
+        ));
+
+        let result = export(JournalFormat::TallyXml, "journal.csv").exec(&service).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+
+        // Nothing is written outside the export directory
+        let result = export(JournalFormat::TallyXml, "../journal.xml").exec(&service).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+        assert!(!config.dir.join("../journal.xml").exists());
+    }
+}
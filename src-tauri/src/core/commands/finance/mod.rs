@@ -1,3 +1,8 @@
+pub mod account_mapping_commands;
 pub mod cost_center_commands;
+pub mod currency_commands;
+pub mod data_export_commands;
+pub mod exchange_rate_commands;
+pub mod journal_commands;
 pub mod payment_method_commands;
 pub mod sales_order_payment_commands;
@@ -6,12 +6,13 @@ use crate::{
     adapters::outgoing::database::DatabaseAdapter,
     core::{
         commands::{app_service::AppService, Command},
+        db::soft_delete::{purge, restore, soft_delete},
         models::finance::payment_method_model::{
             PaymentMethod, PaymentMethodNewInput, PaymentMethodState, PaymentMethodUpdateInput, PaymentMethods,
         },
         types::db_uuid::DbUuid,
     },
-    error::{Error, Result},
+    error::{Constraint, Error, Result},
 };
 
 // Commands
@@ -25,13 +26,22 @@ pub struct UpdatePaymentMethodCommand {
 
 pub struct DeletePaymentMethodCommand {
     pub id: DbUuid,
+    pub deleted_by: Option<DbUuid>,
+}
+
+pub struct RestorePaymentMethodCommand {
+    pub id: DbUuid,
+}
+
+pub struct PurgePaymentMethodCommand {
+    pub id: DbUuid,
 }
 
 // Command Implementations
 impl Command for CreatePaymentMethodCommand {
     type Output = PaymentMethod;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Check if a payment method with the same code already exists
         let mut select_query = Query::select();
         let check_stmt = select_query
@@ -42,7 +52,7 @@ impl Command for CreatePaymentMethodCommand {
         let existing = service.db_adapter.query_optional::<DbUuid>(&check_stmt).await?;
 
         if existing.is_some() {
-            return Err(Error::UniqueConstraintError);
+            return Err(Error::UniqueConstraintError(Constraint::new("payment_methods", "code")));
         }
 
         let now = Utc::now().naive_utc();
@@ -56,6 +66,8 @@ impl Command for CreatePaymentMethodCommand {
             state: self.payment_method.state.unwrap_or(PaymentMethodState::Active),
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
         };
 
         // Build the insert query with SeaQuery
@@ -92,7 +104,7 @@ impl Command for CreatePaymentMethodCommand {
 impl Command for UpdatePaymentMethodCommand {
     type Output = PaymentMethod;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Check if the payment method exists
         let mut select_query = Query::select();
         let check_stmt = select_query
@@ -126,7 +138,7 @@ impl Command for UpdatePaymentMethodCommand {
             let duplicate = service.db_adapter.query_optional::<DbUuid>(&duplicate_check_stmt).await?;
 
             if duplicate.is_some() {
-                return Err(Error::UniqueConstraintError);
+                return Err(Error::UniqueConstraintError(Constraint::new("payment_methods", "code")));
             }
         }
 
@@ -175,52 +187,28 @@ impl Command for UpdatePaymentMethodCommand {
 impl Command for DeletePaymentMethodCommand {
     type Output = usize;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        // Check if the payment method exists
-        let mut select_query = Query::select();
-        let check_stmt = select_query
-            .from(PaymentMethods::Table)
-            .columns([PaymentMethods::Id])
-            .and_where(Expr::col(PaymentMethods::Id).eq(self.id.to_string()));
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        soft_delete::<PaymentMethod, _>(&service.db_adapter, self.id, self.deleted_by).await?;
 
-        let existing = service.db_adapter.query_optional::<DbUuid>(&check_stmt).await?;
+        Ok(1)
+    }
+}
 
-        if existing.is_none() {
-            return Err(Error::NotFoundError);
-        }
+impl Command for RestorePaymentMethodCommand {
+    type Output = PaymentMethod;
 
-        // NOTE: This code is currently commented out because the sales_order_payments
-        // table migrations have been created but are not yet applied in the test environment.
-        // When the sales_order_payments table is actually implemented and the migrations
-        // are applied in production, this code should be uncommented.
-
-        /*
-        // Check if payment method is used in any sales_order_payments
-        let count_query = Query::select()
-            .from(SalesOrderPayments::Table)
-            .expr_as(Expr::col(SalesOrderPayments::Id).count(), Alias::new("count"))
-            .and_where(Expr::col(SalesOrderPayments::PaymentMethodId).eq(self.id.to_string()))
-            .to_string(SqliteQueryBuilder);
-
-        let payment_method_in_use = service.db_adapter.query_one::<i64>(&count_query, vec![])?;
-
-        if payment_method_in_use > 0 {
-            return Err(Error::ValidationError(
-                format!("Cannot delete payment method because it is used in {} sales", payment_method_in_use)
-            ));
-        }
-        */
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        restore(&service.db_adapter, self.id).await
+    }
+}
 
-        // Build the delete query with SeaQuery
-        let mut delete_query = Query::delete();
-        let delete_stmt = delete_query
-            .from_table(PaymentMethods::Table)
-            .and_where(Expr::col(PaymentMethods::Id).eq(self.id.to_string()));
+impl Command for PurgePaymentMethodCommand {
+    type Output = usize;
 
-        // Execute the query
-        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        purge::<PaymentMethod, _>(&service.db_adapter, self.id).await?;
 
-        Ok(affected_rows as usize)
+        Ok(1)
     }
 }
 
@@ -230,7 +218,7 @@ mod tests {
 
     use super::*;
 
-    async fn setup_test_db(service: &mut AppService) {
+    async fn setup_test_db(service: &AppService) {
         // Create payment_methods table for testing
         let create_table_sql = "CREATE TABLE IF NOT EXISTS payment_methods (
             id TEXT PRIMARY KEY NOT NULL,
@@ -247,8 +235,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_payment_method() {
-        let mut service = setup_service().await;
-        setup_test_db(&mut service).await;
+        let service = setup_service().await;
+        setup_test_db(&service).await;
 
         let input = PaymentMethodNewInput {
             name: "Cash".to_string(),
@@ -260,7 +248,7 @@ mod tests {
         let cmd = CreatePaymentMethodCommand {
             payment_method: input,
         };
-        let result = cmd.exec(&mut service).await.unwrap();
+        let result = cmd.exec(&service).await.unwrap();
 
         assert_eq!(result.name, "Cash");
         assert_eq!(result.code, "CASH_CREATE_TEST");
@@ -270,8 +258,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_payment_method_default_state() {
-        let mut service = setup_service().await;
-        setup_test_db(&mut service).await;
+        let service = setup_service().await;
+        setup_test_db(&service).await;
 
         let input = PaymentMethodNewInput {
             name: "Credit Card".to_string(),
@@ -283,7 +271,7 @@ mod tests {
         let cmd = CreatePaymentMethodCommand {
             payment_method: input,
         };
-        let result = cmd.exec(&mut service).await.unwrap();
+        let result = cmd.exec(&service).await.unwrap();
 
         assert_eq!(result.name, "Credit Card");
         assert_eq!(result.code, "CC_DEFAULT_STATE_TEST");
@@ -293,8 +281,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_payment_method_duplicate_code() {
-        let mut service = setup_service().await;
-        setup_test_db(&mut service).await;
+        let service = setup_service().await;
+        setup_test_db(&service).await;
 
         // Create first payment method
         let input1 = PaymentMethodNewInput {
@@ -307,7 +295,7 @@ mod tests {
         let cmd1 = CreatePaymentMethodCommand {
             payment_method: input1,
         };
-        let _ = cmd1.exec(&mut service).await.unwrap();
+        let _ = cmd1.exec(&service).await.unwrap();
 
         // Try to create with same code
         let input2 = PaymentMethodNewInput {
@@ -320,15 +308,15 @@ mod tests {
         let cmd2 = CreatePaymentMethodCommand {
             payment_method: input2,
         };
-        let result = cmd2.exec(&mut service).await;
+        let result = cmd2.exec(&service).await;
 
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_update_payment_method() {
-        let mut service = setup_service().await;
-        setup_test_db(&mut service).await;
+        let service = setup_service().await;
+        setup_test_db(&service).await;
 
         // Create payment method first
         let input = PaymentMethodNewInput {
@@ -341,7 +329,7 @@ mod tests {
         let cmd = CreatePaymentMethodCommand {
             payment_method: input,
         };
-        let created = cmd.exec(&mut service).await.unwrap();
+        let created = cmd.exec(&service).await.unwrap();
 
         // Now update it
         let update_input = PaymentMethodUpdateInput {
@@ -355,7 +343,7 @@ mod tests {
         let update_cmd = UpdatePaymentMethodCommand {
             payment_method: update_input,
         };
-        let updated = update_cmd.exec(&mut service).await.unwrap();
+        let updated = update_cmd.exec(&service).await.unwrap();
 
         assert_eq!(updated.name, "Cash Updated");
         assert_eq!(updated.code, "CASH_UPDATE_TEST"); // Unchanged
@@ -365,8 +353,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_payment_method_duplicate_code() {
-        let mut service = setup_service().await;
-        setup_test_db(&mut service).await;
+        let service = setup_service().await;
+        setup_test_db(&service).await;
 
         // Create first payment method
         let input1 = PaymentMethodNewInput {
@@ -379,7 +367,7 @@ mod tests {
         let cmd1 = CreatePaymentMethodCommand {
             payment_method: input1,
         };
-        let _ = cmd1.exec(&mut service).await.unwrap();
+        let _ = cmd1.exec(&service).await.unwrap();
 
         // Create second payment method
         let input2 = PaymentMethodNewInput {
@@ -392,7 +380,7 @@ mod tests {
         let cmd2 = CreatePaymentMethodCommand {
             payment_method: input2,
         };
-        let created2 = cmd2.exec(&mut service).await.unwrap();
+        let created2 = cmd2.exec(&service).await.unwrap();
 
         // Try to update second payment method with code of first
         let update_input = PaymentMethodUpdateInput {
@@ -406,15 +394,15 @@ mod tests {
         let update_cmd = UpdatePaymentMethodCommand {
             payment_method: update_input,
         };
-        let result = update_cmd.exec(&mut service).await;
+        let result = update_cmd.exec(&service).await;
 
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_update_nonexistent_payment_method() {
-        let mut service = setup_service().await;
-        setup_test_db(&mut service).await;
+        let service = setup_service().await;
+        setup_test_db(&service).await;
 
         let update_input = PaymentMethodUpdateInput {
             id: Uuid::now_v7().into(),
@@ -427,15 +415,15 @@ mod tests {
         let update_cmd = UpdatePaymentMethodCommand {
             payment_method: update_input,
         };
-        let result = update_cmd.exec(&mut service).await;
+        let result = update_cmd.exec(&service).await;
 
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_delete_payment_method() {
-        let mut service = setup_service().await;
-        setup_test_db(&mut service).await;
+        let service = setup_service().await;
+        setup_test_db(&service).await;
 
         // Create payment method first
         let input = PaymentMethodNewInput {
@@ -448,24 +436,25 @@ mod tests {
         let cmd = CreatePaymentMethodCommand {
             payment_method: input,
         };
-        let created = cmd.exec(&mut service).await.unwrap();
+        let created = cmd.exec(&service).await.unwrap();
 
         // Now delete it
-        let delete_cmd = DeletePaymentMethodCommand { id: created.id };
-        let result = delete_cmd.exec(&mut service).await.unwrap();
+        let delete_cmd = DeletePaymentMethodCommand { id: created.id, deleted_by: None };
+        let result = delete_cmd.exec(&service).await.unwrap();
 
         assert_eq!(result, 1); // 1 row affected
     }
 
     #[tokio::test]
     async fn test_delete_nonexistent_payment_method() {
-        let mut service = setup_service().await;
-        setup_test_db(&mut service).await;
+        let service = setup_service().await;
+        setup_test_db(&service).await;
 
         let delete_cmd = DeletePaymentMethodCommand {
             id: Uuid::now_v7().into(),
+            deleted_by: None,
         };
-        let result = delete_cmd.exec(&mut service).await;
+        let result = delete_cmd.exec(&service).await;
 
         assert!(result.is_err());
     }
@@ -5,7 +5,15 @@ use uuid::Uuid;
 use crate::{
     adapters::outgoing::database::DatabaseAdapter,
     core::{
-        commands::{app_service::AppService, Command},
+        commands::{
+            app_service::AppService,
+            finance::{
+                currency_commands::{base_currency, currency_by_code, find_currency_by_code},
+                exchange_rate_commands::rate_at,
+            },
+            Command,
+        },
+        events::Event,
         models::{
             finance::sales_order_payment_model::{
                 SalesOrderPayment, SalesOrderPaymentNewInput, SalesOrderPaymentState,
@@ -13,7 +21,7 @@ use crate::{
             },
             sales::sales_order_model::{SalesOrder, SalesOrderState, SalesOrders},
         },
-        types::db_uuid::DbUuid,
+        types::{db_uuid::DbUuid, money::Money, rate::Rate},
     },
     error::{Error, Result},
 };
@@ -39,7 +47,7 @@ pub struct GetSalesOrderPaymentsCommand {
 impl Command for CreateSalesOrderPaymentCommand {
     type Output = SalesOrderPayment;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
 
         // Check if the order exists and is in Completed state
@@ -55,6 +63,31 @@ impl Command for CreateSalesOrderPaymentCommand {
             return Err(Error::NotFoundError);
         }
 
+        // Foreign tenders are converted into the base currency at the rate on the payment date
+        let base = base_currency(service).await?;
+        let (currency_code, original_amount, exchange_rate, amount) = match &self.payment.foreign_amount {
+            Some(foreign) => {
+                let currency = currency_by_code(service, &foreign.currency_code).await?;
+                let original_amount = Money::from_str_with_minor_units(&foreign.amount, currency.precision())
+                    .map_err(Error::ValidationError)?;
+                let rate = rate_at(service, &currency.code, self.payment.payment_date).await?;
+                let amount = original_amount.convert(rate, currency.precision(), base.precision());
+                if self.payment.amount.is_some_and(|given| given != amount) {
+                    return Err(Error::ValidationError(format!(
+                        "Amount does not match {} {} at the rate of {}",
+                        foreign.amount, currency.code, rate
+                    )));
+                }
+                (currency.code, original_amount, rate, amount)
+            }
+            None => {
+                let amount = self.payment.amount.ok_or_else(|| {
+                    Error::ValidationError("Either amount or foreignAmount is required".to_string())
+                })?;
+                (base.code, amount, Rate::ONE, amount)
+            }
+        };
+
         // Create a new payment ID
         let payment_id: DbUuid = Uuid::now_v7().into();
 
@@ -64,13 +97,16 @@ impl Command for CreateSalesOrderPaymentCommand {
             order_id: self.payment.order_id,
             payment_method_id: self.payment.payment_method_id,
             payment_date: self.payment.payment_date,
-            amount: self.payment.amount,
+            amount,
             reference_number: self.payment.reference_number.clone(),
             notes: self.payment.notes.clone(),
             state: self
                 .payment
                 .state
                 .unwrap_or(SalesOrderPaymentState::Completed),
+            currency_code,
+            original_amount,
+            exchange_rate,
             created_at: now,
             updated_at: now,
         };
@@ -88,6 +124,9 @@ impl Command for CreateSalesOrderPaymentCommand {
                 SalesOrderPayments::ReferenceNumber,
                 SalesOrderPayments::Notes,
                 SalesOrderPayments::State,
+                SalesOrderPayments::CurrencyCode,
+                SalesOrderPayments::OriginalAmount,
+                SalesOrderPayments::ExchangeRate,
                 SalesOrderPayments::CreatedAt,
                 SalesOrderPayments::UpdatedAt,
             ])
@@ -96,7 +135,7 @@ impl Command for CreateSalesOrderPaymentCommand {
                 self.payment.order_id.to_string().into(),
                 self.payment.payment_method_id.to_string().into(),
                 self.payment.payment_date.to_string().into(),
-                self.payment.amount.to_base_unit().into(),
+                new_payment.amount.to_base_unit().into(),
                 match &self.payment.reference_number {
                     Some(ref_num) => ref_num.clone().into(),
                     None => sea_query::Value::String(None).into(),
@@ -106,6 +145,9 @@ impl Command for CreateSalesOrderPaymentCommand {
                     None => sea_query::Value::String(None).into(),
                 },
                 new_payment.state.to_string().into(),
+                new_payment.currency_code.clone().into(),
+                new_payment.original_amount.to_base_unit().into(),
+                new_payment.exchange_rate.value().into(),
                 now.to_string().into(),
                 now.to_string().into(),
             ]);
@@ -113,6 +155,8 @@ impl Command for CreateSalesOrderPaymentCommand {
         // Execute the insert query
         service.db_adapter.insert_many(&insert_stmt).await?;
 
+        service.events.publish(Event::PaymentRecorded(new_payment.clone()));
+
         Ok(new_payment)
     }
 }
@@ -120,7 +164,7 @@ impl Command for CreateSalesOrderPaymentCommand {
 impl Command for UpdateSalesOrderPaymentCommand {
     type Output = SalesOrderPayment;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
 
         // Check if the payment exists and is in Completed state
@@ -136,6 +180,9 @@ impl Command for UpdateSalesOrderPaymentCommand {
                 SalesOrderPayments::ReferenceNumber,
                 SalesOrderPayments::Notes,
                 SalesOrderPayments::State,
+                SalesOrderPayments::CurrencyCode,
+                SalesOrderPayments::OriginalAmount,
+                SalesOrderPayments::ExchangeRate,
                 SalesOrderPayments::CreatedAt,
                 SalesOrderPayments::UpdatedAt,
             ])
@@ -143,9 +190,10 @@ impl Command for UpdateSalesOrderPaymentCommand {
             .and_where(Expr::col(SalesOrderPayments::State).eq(SalesOrderPaymentState::Completed.to_string()));
 
         let payment = service.db_adapter.query_optional::<SalesOrderPayment>(&check_stmt).await?;
-        if payment.is_none() {
-            return Err(Error::NotFoundError);
-        }
+        let payment = match payment {
+            Some(payment) => payment,
+            None => return Err(Error::NotFoundError),
+        };
 
         // Create the changeset
         let changeset = SalesOrderPaymentUpdateInput {
@@ -168,12 +216,31 @@ impl Command for UpdateSalesOrderPaymentCommand {
             update_stmt.value(SalesOrderPayments::PaymentMethodId, payment_method_id.to_string());
         }
 
+        let base = base_currency(service).await?;
+        let is_foreign = payment.currency_code != base.code;
+
         if let Some(payment_date) = &changeset.payment_date {
             update_stmt.value(SalesOrderPayments::PaymentDate, payment_date.to_string());
+
+            // A foreign tender is converted again at the rate on its new date
+            if is_foreign {
+                let currency = find_currency_by_code(service, &payment.currency_code).await?;
+                let rate = rate_at(service, &currency.code, *payment_date).await?;
+                let amount = payment.original_amount.convert(rate, currency.precision(), base.precision());
+                update_stmt.value(SalesOrderPayments::ExchangeRate, rate.value());
+                update_stmt.value(SalesOrderPayments::Amount, amount.to_base_unit());
+            }
         }
 
         if let Some(amount) = &changeset.amount {
+            // A foreign tender's amount follows from its original amount and rate
+            if is_foreign {
+                return Err(Error::ValidationError(
+                    "The amount of a foreign currency payment cannot be changed; void it instead".to_string(),
+                ));
+            }
             update_stmt.value(SalesOrderPayments::Amount, amount.to_base_unit());
+            update_stmt.value(SalesOrderPayments::OriginalAmount, amount.to_base_unit());
         }
 
         if let Some(reference_number) = &changeset.reference_number {
@@ -213,6 +280,9 @@ impl Command for UpdateSalesOrderPaymentCommand {
                 SalesOrderPayments::ReferenceNumber,
                 SalesOrderPayments::Notes,
                 SalesOrderPayments::State,
+                SalesOrderPayments::CurrencyCode,
+                SalesOrderPayments::OriginalAmount,
+                SalesOrderPayments::ExchangeRate,
                 SalesOrderPayments::CreatedAt,
                 SalesOrderPayments::UpdatedAt,
             ])
@@ -227,7 +297,7 @@ impl Command for UpdateSalesOrderPaymentCommand {
 impl Command for VoidSalesOrderPaymentCommand {
     type Output = SalesOrderPayment;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
 
         // Check if the payment exists and is in Completed state
@@ -243,6 +313,9 @@ impl Command for VoidSalesOrderPaymentCommand {
                 SalesOrderPayments::ReferenceNumber,
                 SalesOrderPayments::Notes,
                 SalesOrderPayments::State,
+                SalesOrderPayments::CurrencyCode,
+                SalesOrderPayments::OriginalAmount,
+                SalesOrderPayments::ExchangeRate,
                 SalesOrderPayments::CreatedAt,
                 SalesOrderPayments::UpdatedAt,
             ])
@@ -278,6 +351,9 @@ impl Command for VoidSalesOrderPaymentCommand {
                 SalesOrderPayments::ReferenceNumber,
                 SalesOrderPayments::Notes,
                 SalesOrderPayments::State,
+                SalesOrderPayments::CurrencyCode,
+                SalesOrderPayments::OriginalAmount,
+                SalesOrderPayments::ExchangeRate,
                 SalesOrderPayments::CreatedAt,
                 SalesOrderPayments::UpdatedAt,
             ])
@@ -292,7 +368,7 @@ impl Command for VoidSalesOrderPaymentCommand {
 impl Command for GetSalesOrderPaymentsCommand {
     type Output = Vec<SalesOrderPayment>;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Check if the order exists
         let mut check_query_builder = Query::select();
         let check_stmt = check_query_builder
@@ -318,6 +394,9 @@ impl Command for GetSalesOrderPaymentsCommand {
                 SalesOrderPayments::ReferenceNumber,
                 SalesOrderPayments::Notes,
                 SalesOrderPayments::State,
+                SalesOrderPayments::CurrencyCode,
+                SalesOrderPayments::OriginalAmount,
+                SalesOrderPayments::ExchangeRate,
                 SalesOrderPayments::CreatedAt,
                 SalesOrderPayments::UpdatedAt,
             ])
@@ -330,24 +409,34 @@ impl Command for GetSalesOrderPaymentsCommand {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
+    use std::str::FromStr;
+
     use super::*;
     use crate::core::commands::tests::setup_service;
-    use crate::core::models::auth::user_model::UserState;
+    use crate::core::models::auth::user_model::{UserRole, UserState};
     use crate::core::models::finance::sales_order_payment_model::SalesOrderPaymentState;
     use crate::core::{
         commands::{
             auth::user_commands::AddUserCommand,
             common::channel_commands::CreateChannelCommand,
             common::location_commands::CreateLocationCommand,
-            finance::payment_method_commands::CreatePaymentMethodCommand,
+            finance::{
+                currency_commands::CreateCurrencyCommand, exchange_rate_commands::CreateExchangeRateCommand,
+                payment_method_commands::CreatePaymentMethodCommand,
+            },
             sales::sales_order_commands::CreateSalesOrderCommand,
         },
         models::{
             auth::user_model::UserNewInput,
             common::channel_model::{Channel, ChannelNewInput},
             common::location_model::{Location, LocationNewInput},
-            finance::payment_method_model::{PaymentMethodNewInput, PaymentMethodState},
+            finance::{
+                currency_model::CurrencyNewInput,
+                exchange_rate_model::ExchangeRateNewInput,
+                payment_method_model::{PaymentMethodNewInput, PaymentMethodState},
+                sales_order_payment_model::ForeignAmountInput,
+            },
             sales::{
                 sales_order_item_model::SalesOrderItemInput, sales_order_model::SalesOrderNewInput,
             },
@@ -357,7 +446,7 @@ mod tests {
     use sea_query::{Expr, Query};
 
     async fn create_test_payment_method(
-        service: &mut AppService,
+        service: &AppService,
     ) -> crate::core::models::finance::payment_method_model::PaymentMethod {
         let command = CreatePaymentMethodCommand {
             payment_method: PaymentMethodNewInput {
@@ -371,7 +460,7 @@ mod tests {
     }
 
     async fn create_test_cost_center(
-        service: &mut AppService,
+        service: &AppService,
     ) -> crate::core::models::finance::cost_center_model::CostCenter {
         use crate::core::{
             commands::{finance::cost_center_commands::CreateCostCenterCommand, Command},
@@ -389,7 +478,7 @@ mod tests {
         command.exec(service).await.unwrap()
     }
 
-    async fn create_test_user(service: &mut AppService) -> DbUuid {
+    async fn create_test_user(service: &AppService) -> DbUuid {
         let random_suffix = rand::thread_rng().gen_range(1000..9999).to_string();
         let command = AddUserCommand {
             user: UserNewInput {
@@ -397,13 +486,14 @@ mod tests {
                 pin_hash: "1234".to_string(),
                 full_name: format!("Test User {}", random_suffix),
                 state: UserState::Active,
+                role: UserRole::Cashier,
                 last_login_at: None,
             },
         };
         command.exec(service).await.unwrap().id
     }
 
-    async fn create_test_channel(service: &mut AppService) -> Channel {
+    async fn create_test_channel(service: &AppService) -> Channel {
         let command = CreateChannelCommand {
             channel: ChannelNewInput {
                 name: format!("Test Channel {}", rand::thread_rng().gen_range(1..999)),
@@ -414,7 +504,7 @@ mod tests {
         command.exec(service).await.unwrap()
     }
 
-    async fn create_test_location(service: &mut AppService) -> Location {
+    async fn create_test_location(service: &AppService) -> Location {
         let command = CreateLocationCommand {
             location: LocationNewInput {
                 name: format!("Test Location {}", rand::thread_rng().gen_range(1..999)),
@@ -426,7 +516,7 @@ mod tests {
         command.exec(service).await.unwrap()
     }
 
-    async fn create_test_sales_order(service: &mut AppService) -> SalesOrder {
+    pub(crate) async fn create_test_sales_order(service: &AppService) -> SalesOrder {
         let now = Utc::now().naive_utc();
         let cost_center = create_test_cost_center(service).await;
         let user_id = create_test_user(service).await;
@@ -452,6 +542,7 @@ mod tests {
             discount_id: None,
             items: vec![SalesOrderItemInput {
                 item_id: None, // No item needed for this test
+                variant_id: None,
                 item_name: "Item 1".to_string(),
                 quantity: 2,
                 sku: None,
@@ -460,6 +551,7 @@ mod tests {
                 taxable_amount: 450.into(),
                 tax_amount: 50.into(),
                 total_amount: 990.into(),
+                price_override: None,
             }],
             charges: None,
         };
@@ -473,64 +565,78 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_sales_order_payment() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let now = Utc::now().naive_utc();
-        let order = create_test_sales_order(&mut service).await;
-        let payment_method = create_test_payment_method(&mut service).await;
+        let order = create_test_sales_order(&service).await;
+        let payment_method = create_test_payment_method(&service).await;
 
         let input = SalesOrderPaymentNewInput {
             order_id: order.id,
             payment_method_id: payment_method.id,
             payment_date: now,
-            amount: 500.into(),
+            amount: Some(500.into()),
             reference_number: Some("REF123".to_string()),
             notes: Some("First payment".to_string()),
             state: None,
+            foreign_amount: None,
         };
 
+        // Without a foreign amount the base amount is required
+        let without_amount = SalesOrderPaymentNewInput { amount: None, ..input.clone() };
+        let result = CreateSalesOrderPaymentCommand { payment: without_amount }.exec(&service).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+
+        let mut events = service.events.subscribe();
         let cmd = CreateSalesOrderPaymentCommand { payment: input };
-        let result = cmd.exec(&mut service).await.unwrap();
+        let result = cmd.exec(&service).await.unwrap();
 
         assert_eq!(result.order_id, order.id);
         assert_eq!(result.payment_method_id, payment_method.id);
         assert_eq!(result.amount, 500.into());
         assert_eq!(result.state, SalesOrderPaymentState::Completed);
+
+        match events.recv().await.unwrap() {
+            Event::PaymentRecorded(payment) => assert_eq!(payment.id, result.id),
+            event => panic!("Unexpected event {:?}", event),
+        }
     }
 
     #[tokio::test]
     async fn test_create_multiple_payments_for_order() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let now = Utc::now().naive_utc();
-        let order = create_test_sales_order(&mut service).await;
-        let payment_method = create_test_payment_method(&mut service).await;
+        let order = create_test_sales_order(&service).await;
+        let payment_method = create_test_payment_method(&service).await;
 
         // First payment
         let input1 = SalesOrderPaymentNewInput {
             order_id: order.id,
             payment_method_id: payment_method.id,
             payment_date: now,
-            amount: 500.into(),
+            amount: Some(500.into()),
             reference_number: Some("REF123".to_string()),
             notes: Some("First payment".to_string()),
             state: None,
+            foreign_amount: None,
         };
 
         let cmd1 = CreateSalesOrderPaymentCommand { payment: input1 };
-        let result1 = cmd1.exec(&mut service).await.unwrap();
+        let result1 = cmd1.exec(&service).await.unwrap();
 
         // Second payment
         let input2 = SalesOrderPaymentNewInput {
             order_id: order.id,
             payment_method_id: payment_method.id,
             payment_date: now,
-            amount: 490.into(),
+            amount: Some(490.into()),
             reference_number: Some("REF124".to_string()),
             notes: Some("Second payment".to_string()),
             state: None,
+            foreign_amount: None,
         };
 
         let cmd2 = CreateSalesOrderPaymentCommand { payment: input2 };
-        let result2 = cmd2.exec(&mut service).await.unwrap();
+        let result2 = cmd2.exec(&service).await.unwrap();
 
         assert_eq!(result1.order_id, order.id);
         assert_eq!(result1.amount, 500.into());
@@ -551,6 +657,9 @@ mod tests {
                 SalesOrderPayments::ReferenceNumber,
                 SalesOrderPayments::Notes,
                 SalesOrderPayments::State,
+                SalesOrderPayments::CurrencyCode,
+                SalesOrderPayments::OriginalAmount,
+                SalesOrderPayments::ExchangeRate,
                 SalesOrderPayments::CreatedAt,
                 SalesOrderPayments::UpdatedAt,
             ])
@@ -565,24 +674,25 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_sales_order_payment() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let now = Utc::now().naive_utc();
-        let order = create_test_sales_order(&mut service).await;
-        let payment_method = create_test_payment_method(&mut service).await;
+        let order = create_test_sales_order(&service).await;
+        let payment_method = create_test_payment_method(&service).await;
 
         // Create a payment
         let input = SalesOrderPaymentNewInput {
             order_id: order.id,
             payment_method_id: payment_method.id,
             payment_date: now,
-            amount: 500.into(),
+            amount: Some(500.into()),
             reference_number: Some("REF123".to_string()),
             notes: Some("First payment".to_string()),
             state: None,
+            foreign_amount: None,
         };
 
         let cmd = CreateSalesOrderPaymentCommand { payment: input };
-        let payment = cmd.exec(&mut service).await.unwrap();
+        let payment = cmd.exec(&service).await.unwrap();
 
         // Update the payment
         let update_input = SalesOrderPaymentUpdateInput {
@@ -598,7 +708,7 @@ mod tests {
         let update_cmd = UpdateSalesOrderPaymentCommand {
             payment: update_input,
         };
-        let updated_payment = update_cmd.exec(&mut service).await.unwrap();
+        let updated_payment = update_cmd.exec(&service).await.unwrap();
 
         assert_eq!(updated_payment.id, payment.id);
         assert_eq!(updated_payment.amount, 600.into());
@@ -609,28 +719,190 @@ mod tests {
         assert_eq!(updated_payment.notes, Some("First payment".to_string())); // Unchanged
     }
 
+    #[tokio::test]
+    async fn test_create_foreign_currency_payment() {
+        let service = setup_service().await;
+        let now = Utc::now().naive_utc();
+        let order = create_test_sales_order(&service).await;
+        let payment_method = create_test_payment_method(&service).await;
+
+        CreateCurrencyCommand {
+            currency: CurrencyNewInput {
+                code: "USD".to_string(),
+                name: "US Dollar".to_string(),
+                symbol: Some("$".to_string()),
+                minor_units: 2,
+                is_active: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        CreateExchangeRateCommand {
+            exchange_rate: ExchangeRateNewInput {
+                currency_code: "USD".to_string(),
+                rate: Rate::from_str("83.25").unwrap(),
+                effective_from: Some(now - chrono::Duration::days(1)),
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let input = |amount: Option<Money>| SalesOrderPaymentNewInput {
+            order_id: order.id,
+            payment_method_id: payment_method.id,
+            payment_date: now,
+            amount,
+            reference_number: None,
+            notes: None,
+            state: None,
+            foreign_amount: Some(ForeignAmountInput {
+                currency_code: "USD".to_string(),
+                amount: "10".to_string(),
+            }),
+        };
+
+        // A base amount given alongside must match the conversion
+        let result = CreateSalesOrderPaymentCommand { payment: input(Some(0.into())) }.exec(&service).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+
+        let payment = CreateSalesOrderPaymentCommand { payment: input(None) }
+            .exec(&service)
+            .await
+            .unwrap();
+
+        assert_eq!(payment.currency_code, "USD");
+        assert_eq!(payment.original_amount, Money::from_str("10").unwrap());
+        assert_eq!(payment.exchange_rate, Rate::from_str("83.25").unwrap());
+        assert_eq!(payment.amount, Money::from_str("832.5").unwrap());
+
+        // The stored row matches what was returned
+        let payments = GetSalesOrderPaymentsCommand { order_id: order.id }
+            .exec(&service)
+            .await
+            .unwrap();
+        assert_eq!(payments[0].currency_code, "USD");
+        assert_eq!(payments[0].amount, payment.amount);
+
+        // The converted amount can't drift from the original amount
+        let result = UpdateSalesOrderPaymentCommand {
+            payment: SalesOrderPaymentUpdateInput {
+                id: payment.id,
+                payment_method_id: None,
+                payment_date: None,
+                amount: Some(800.into()),
+                reference_number: None,
+                notes: None,
+                state: None,
+            },
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+
+        let matching = CreateSalesOrderPaymentCommand { payment: input(Some(payment.amount)) }
+            .exec(&service)
+            .await
+            .unwrap();
+        assert_eq!(matching.amount, payment.amount);
+    }
+
+    #[tokio::test]
+    async fn test_changing_the_date_of_a_foreign_payment_converts_it_again() {
+        let service = setup_service().await;
+        let now = Utc::now().naive_utc();
+        let order = create_test_sales_order(&service).await;
+        let payment_method = create_test_payment_method(&service).await;
+
+        CreateCurrencyCommand {
+            currency: CurrencyNewInput {
+                code: "USD".to_string(),
+                name: "US Dollar".to_string(),
+                symbol: Some("$".to_string()),
+                minor_units: 2,
+                is_active: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        for (rate, days_ago) in [("80", 10), ("83.25", 1)] {
+            CreateExchangeRateCommand {
+                exchange_rate: ExchangeRateNewInput {
+                    currency_code: "USD".to_string(),
+                    rate: Rate::from_str(rate).unwrap(),
+                    effective_from: Some(now - chrono::Duration::days(days_ago)),
+                },
+            }
+            .exec(&service)
+            .await
+            .unwrap();
+        }
+
+        let payment = CreateSalesOrderPaymentCommand {
+            payment: SalesOrderPaymentNewInput {
+                order_id: order.id,
+                payment_method_id: payment_method.id,
+                payment_date: now,
+                amount: None,
+                reference_number: None,
+                notes: None,
+                state: None,
+                foreign_amount: Some(ForeignAmountInput {
+                    currency_code: "USD".to_string(),
+                    amount: "10".to_string(),
+                }),
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        assert_eq!(payment.amount, Money::from_str("832.5").unwrap());
+
+        let updated = UpdateSalesOrderPaymentCommand {
+            payment: SalesOrderPaymentUpdateInput {
+                id: payment.id,
+                payment_method_id: None,
+                payment_date: Some(now - chrono::Duration::days(5)),
+                amount: None,
+                reference_number: None,
+                notes: None,
+                state: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        assert_eq!(updated.original_amount, payment.original_amount);
+        assert_eq!(updated.exchange_rate, Rate::from_str("80").unwrap());
+        assert_eq!(updated.amount, Money::from_str("800").unwrap());
+    }
+
     #[tokio::test]
     async fn test_void_sales_order_payment() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let now = Utc::now().naive_utc();
-        let order = create_test_sales_order(&mut service).await;
-        let payment_method = create_test_payment_method(&mut service).await;
+        let order = create_test_sales_order(&service).await;
+        let payment_method = create_test_payment_method(&service).await;
 
         let input = SalesOrderPaymentNewInput {
             order_id: order.id,
             payment_method_id: payment_method.id,
             payment_date: now,
-            amount: 500.into(),
+            amount: Some(500.into()),
             reference_number: Some("REF123".to_string()),
             notes: Some("First payment".to_string()),
             state: None,
+            foreign_amount: None,
         };
 
         let cmd = CreateSalesOrderPaymentCommand { payment: input };
-        let payment = cmd.exec(&mut service).await.unwrap();
+        let payment = cmd.exec(&service).await.unwrap();
 
         let void_cmd = VoidSalesOrderPaymentCommand { id: payment.id };
-        let voided_payment = void_cmd.exec(&mut service).await.unwrap();
+        let voided_payment = void_cmd.exec(&service).await.unwrap();
 
         assert_eq!(voided_payment.id, payment.id);
         assert_eq!(voided_payment.state, SalesOrderPaymentState::Voided);
@@ -638,43 +910,44 @@ mod tests {
 
     #[tokio::test]
     async fn test_void_already_voided_payment() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let now = Utc::now().naive_utc();
-        let order = create_test_sales_order(&mut service).await;
-        let payment_method = create_test_payment_method(&mut service).await;
+        let order = create_test_sales_order(&service).await;
+        let payment_method = create_test_payment_method(&service).await;
 
         let input = SalesOrderPaymentNewInput {
             order_id: order.id,
             payment_method_id: payment_method.id,
             payment_date: now,
-            amount: 500.into(),
+            amount: Some(500.into()),
             reference_number: Some("REF123".to_string()),
             notes: Some("First payment".to_string()),
             state: None,
+            foreign_amount: None,
         };
 
         let cmd = CreateSalesOrderPaymentCommand { payment: input };
-        let payment = cmd.exec(&mut service).await.unwrap();
+        let payment = cmd.exec(&service).await.unwrap();
         let void_cmd = VoidSalesOrderPaymentCommand { id: payment.id };
-        let _ = void_cmd.exec(&mut service).await.unwrap(); // First void
-        let result = void_cmd.exec(&mut service).await; // Second void
+        let _ = void_cmd.exec(&service).await.unwrap(); // First void
+        let result = void_cmd.exec(&service).await; // Second void
                                                   // Expect NotFoundError because the payment is no longer in Completed state
         assert!(matches!(result, Err(Error::NotFoundError)));
     }
 
     #[tokio::test]
     async fn test_void_non_existent_payment() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let id = Uuid::now_v7().into();
 
         let void_cmd = VoidSalesOrderPaymentCommand { id };
-        let result = void_cmd.exec(&mut service).await;
+        let result = void_cmd.exec(&service).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_update_non_existent_payment() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let id = Uuid::now_v7().into();
 
         let update_input = SalesOrderPaymentUpdateInput {
@@ -690,47 +963,49 @@ mod tests {
         let update_cmd = UpdateSalesOrderPaymentCommand {
             payment: update_input,
         };
-        let result = update_cmd.exec(&mut service).await;
+        let result = update_cmd.exec(&service).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_get_sales_order_payments() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let now = Utc::now().naive_utc();
-        let order = create_test_sales_order(&mut service).await;
-        let payment_method = create_test_payment_method(&mut service).await;
+        let order = create_test_sales_order(&service).await;
+        let payment_method = create_test_payment_method(&service).await;
 
         // Create two payments
         let input1 = SalesOrderPaymentNewInput {
             order_id: order.id,
             payment_method_id: payment_method.id,
             payment_date: now,
-            amount: 500.into(),
+            amount: Some(500.into()),
             reference_number: Some("REF123".to_string()),
             notes: Some("First payment".to_string()),
             state: None,
+            foreign_amount: None,
         };
 
         let input2 = SalesOrderPaymentNewInput {
             order_id: order.id,
             payment_method_id: payment_method.id,
             payment_date: now,
-            amount: 490.into(),
+            amount: Some(490.into()),
             reference_number: Some("REF124".to_string()),
             notes: Some("Second payment".to_string()),
             state: None,
+            foreign_amount: None,
         };
 
         let cmd1 = CreateSalesOrderPaymentCommand { payment: input1 };
         let cmd2 = CreateSalesOrderPaymentCommand { payment: input2 };
 
-        let _result1 = cmd1.exec(&mut service).await.unwrap();
-        let _result2 = cmd2.exec(&mut service).await.unwrap();
+        let _result1 = cmd1.exec(&service).await.unwrap();
+        let _result2 = cmd2.exec(&service).await.unwrap();
 
         // Get all payments for the order
         let get_cmd = GetSalesOrderPaymentsCommand { order_id: order.id };
-        let payments = get_cmd.exec(&mut service).await.unwrap();
+        let payments = get_cmd.exec(&service).await.unwrap();
 
         assert_eq!(payments.len(), 2);
         assert!(payments.iter().any(|p| p.amount == 500.into()));
@@ -739,11 +1014,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_payments_for_nonexistent_order() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let id = Uuid::now_v7().into();
 
         let get_cmd = GetSalesOrderPaymentsCommand { order_id: id };
-        let result = get_cmd.exec(&mut service).await;
+        let result = get_cmd.exec(&service).await;
 
         assert!(result.is_err());
     }
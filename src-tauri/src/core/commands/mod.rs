@@ -20,5 +20,5 @@ use crate::error::Result;
 
 pub trait Command {
     type Output;
-    fn exec(&self, service: &mut AppService) -> impl Future<Output = Result<Self::Output>> + Send;
+    fn exec(&self, service: &AppService) -> impl Future<Output = Result<Self::Output>> + Send;
 }
@@ -31,7 +31,7 @@ pub struct DeleteExpenseCommand {
 impl Command for CreateExpenseCommand {
     type Output = Expense;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let new_id = Uuid::now_v7();
 
@@ -87,7 +87,7 @@ impl Command for CreateExpenseCommand {
 impl Command for UpdateExpenseCommand {
     type Output = Expense;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let expense_id = self.expense.id;
 
@@ -158,7 +158,7 @@ impl Command for UpdateExpenseCommand {
 impl Command for DeleteExpenseCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Build the delete query with SeaQuery
         let mut query = Query::delete();
         let stmt = query
@@ -185,7 +185,7 @@ mod tests {
     use sea_query::{Alias, Expr, Query};
     use tokio;
 
-    async fn create_test_category(service: &mut AppService) -> PurchaseCategory {
+    async fn create_test_category(service: &AppService) -> PurchaseCategory {
         let command = CreatePurchaseCategoryCommand {
             category: PurchaseCategoryNew {
                 name: "Test Category".to_string(),
@@ -198,13 +198,13 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_expense() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a category first
-        let category = create_test_category(&mut service).await;
+        let category = create_test_category(&service).await;
 
         // Create a cost center
-        let cost_center = create_test_cost_center(&mut service).await;
+        let cost_center = create_test_cost_center(&service).await;
 
         // Create an expense
         let now = Utc::now().naive_utc();
@@ -219,7 +219,7 @@ mod tests {
             },
         };
 
-        let expense = command.exec(&mut service).await.unwrap();
+        let expense = command.exec(&service).await.unwrap();
         assert_eq!(expense.title, "Test Expense");
         assert_eq!(expense.amount, 1000.into());
         assert_eq!(expense.category_id, category.id);
@@ -229,13 +229,13 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_expense() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a category first
-        let category = create_test_category(&mut service).await;
+        let category = create_test_category(&service).await;
 
         // Create a cost center
-        let cost_center = create_test_cost_center(&mut service).await;
+        let cost_center = create_test_cost_center(&service).await;
 
         // Create an expense
         let now = Utc::now().naive_utc();
@@ -250,7 +250,7 @@ mod tests {
             },
         };
 
-        let expense = create_command.exec(&mut service).await.unwrap();
+        let expense = create_command.exec(&service).await.unwrap();
 
         // Update the expense
         let update_command = UpdateExpenseCommand {
@@ -265,7 +265,7 @@ mod tests {
             },
         };
 
-        let updated_expense = update_command.exec(&mut service).await.unwrap();
+        let updated_expense = update_command.exec(&service).await.unwrap();
         assert_eq!(updated_expense.title, "Updated Expense");
         assert_eq!(updated_expense.amount, 2000.into());
         assert_eq!(updated_expense.category_id, category.id);
@@ -278,13 +278,13 @@ mod tests {
 
     #[tokio::test]
     async fn test_delete_expense() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a category first
-        let category = create_test_category(&mut service).await;
+        let category = create_test_category(&service).await;
 
         // Create a cost center
-        let cost_center = create_test_cost_center(&mut service).await;
+        let cost_center = create_test_cost_center(&service).await;
 
         // Create an expense
         let now = Utc::now().naive_utc();
@@ -299,11 +299,11 @@ mod tests {
             },
         };
 
-        let expense = create_command.exec(&mut service).await.unwrap();
+        let expense = create_command.exec(&service).await.unwrap();
 
         // Delete the expense
         let delete_command = DeleteExpenseCommand { id: expense.id };
-        let result = delete_command.exec(&mut service).await.unwrap();
+        let result = delete_command.exec(&service).await.unwrap();
         assert_eq!(result, 1);
 
         // Verify expense no longer exists
@@ -319,15 +319,15 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_expense_cost_center() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a category
-        let category = create_test_category(&mut service).await;
+        let category = create_test_category(&service).await;
 
         // Create two cost centers
-        let cost_center1 = create_test_cost_center(&mut service).await;
+        let cost_center1 = create_test_cost_center(&service).await;
         let cost_center2 =
-            create_test_cost_center_with_name(&mut service, "Second Cost Center", "CC002").await;
+            create_test_cost_center_with_name(&service, "Second Cost Center", "CC002").await;
 
         // Create an expense with the first cost center
         let now = Utc::now().naive_utc();
@@ -342,7 +342,7 @@ mod tests {
             },
         };
 
-        let expense = create_command.exec(&mut service).await.unwrap();
+        let expense = create_command.exec(&service).await.unwrap();
         assert_eq!(expense.cost_center_id, cost_center1.id);
 
         // Update only the cost center
@@ -358,7 +358,7 @@ mod tests {
             },
         };
 
-        let updated_expense = update_command.exec(&mut service).await.unwrap();
+        let updated_expense = update_command.exec(&service).await.unwrap();
 
         // Verify that only the cost center was updated
         assert_eq!(updated_expense.title, "Test Expense");
@@ -371,7 +371,7 @@ mod tests {
         );
     }
 
-    async fn create_test_cost_center(service: &mut AppService) -> CostCenter {
+    async fn create_test_cost_center(service: &AppService) -> CostCenter {
         use crate::core::{
             commands::{finance::cost_center_commands::CreateCostCenterCommand, Command},
             models::finance::cost_center_model::{CostCenterNewInput, CostCenterState},
@@ -389,7 +389,7 @@ mod tests {
     }
 
     async fn create_test_cost_center_with_name(
-        service: &mut AppService,
+        service: &AppService,
         name: &str,
         code: &str,
     ) -> CostCenter {
@@ -6,12 +6,13 @@ use crate::{
     adapters::outgoing::database::DatabaseAdapter,
     core::{
         commands::{app_service::AppService, Command},
+        db::soft_delete::{purge, restore, soft_delete},
         models::purchases::purchase_category_model::{
             PurchaseCategory, PurchaseCategoryNew, PurchaseCategoryState, PurchaseCategoryUpdate, PurchaseCategories,
         },
         types::db_uuid::DbUuid,
     },
-    error::{Error, Result},
+    error::{Constraint, Error, Result},
 };
 
 // Commands
@@ -25,13 +26,22 @@ pub struct UpdatePurchaseCategoryCommand {
 
 pub struct DeletePurchaseCategoryCommand {
     pub id: DbUuid,
+    pub deleted_by: Option<DbUuid>,
+}
+
+pub struct RestorePurchaseCategoryCommand {
+    pub id: DbUuid,
+}
+
+pub struct PurgePurchaseCategoryCommand {
+    pub id: DbUuid,
 }
 
 // Command Implementations
 impl Command for CreatePurchaseCategoryCommand {
     type Output = PurchaseCategory;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Check if a category with the same name already exists
         let mut check_query = Query::select();
         let check_stmt = check_query
@@ -42,7 +52,7 @@ impl Command for CreatePurchaseCategoryCommand {
         let existing = service.db_adapter.query_optional::<DbUuid>(&check_stmt).await?;
 
         if existing.is_some() {
-            return Err(Error::UniqueConstraintError);
+            return Err(Error::UniqueConstraintError(Constraint::new("purchase_categories", "name")));
         }
 
         let now = Utc::now().naive_utc();
@@ -55,6 +65,8 @@ impl Command for CreatePurchaseCategoryCommand {
             state: self.category.state.unwrap_or(PurchaseCategoryState::Active),
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
         };
 
         // Build the insert query with SeaQuery
@@ -89,7 +101,7 @@ impl Command for CreatePurchaseCategoryCommand {
 impl Command for UpdatePurchaseCategoryCommand {
     type Output = PurchaseCategory;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         // Check if the category exists
         let mut check_query = Query::select();
         let check_stmt = check_query
@@ -151,30 +163,28 @@ impl Command for UpdatePurchaseCategoryCommand {
 impl Command for DeletePurchaseCategoryCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        // Check if the category exists
-        let mut check_query = Query::select();
-        let check_stmt = check_query
-            .from(PurchaseCategories::Table)
-            .columns([PurchaseCategories::Id])
-            .and_where(Expr::col(PurchaseCategories::Id).eq(self.id.to_string()));
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        soft_delete::<PurchaseCategory, _>(&service.db_adapter, self.id, self.deleted_by).await?;
 
-        let existing = service.db_adapter.query_optional::<DbUuid>(&check_stmt).await?;
+        Ok(1)
+    }
+}
 
-        if existing.is_none() {
-            return Err(Error::NotFoundError);
-        }
+impl Command for RestorePurchaseCategoryCommand {
+    type Output = PurchaseCategory;
 
-        // Build the delete query with SeaQuery
-        let mut delete_query = Query::delete();
-        let delete_stmt = delete_query
-            .from_table(PurchaseCategories::Table)
-            .and_where(Expr::col(PurchaseCategories::Id).eq(self.id.to_string()));
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        restore(&service.db_adapter, self.id).await
+    }
+}
 
-        // Execute the query
-        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+impl Command for PurgePurchaseCategoryCommand {
+    type Output = i32;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        purge::<PurchaseCategory, _>(&service.db_adapter, self.id).await?;
 
-        Ok(affected_rows as i32)
+        Ok(1)
     }
 }
 
@@ -188,7 +198,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_purchase_category() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let command = CreatePurchaseCategoryCommand {
             category: PurchaseCategoryNew {
@@ -198,7 +208,7 @@ mod tests {
             },
         };
 
-        let category = command.exec(&mut service).await.unwrap();
+        let category = command.exec(&service).await.unwrap();
         assert_eq!(category.name, "Test Category");
         assert_eq!(
             category.description,
@@ -209,7 +219,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_duplicate_category() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create first category
         let command1 = CreatePurchaseCategoryCommand {
@@ -219,7 +229,7 @@ mod tests {
                 state: None,
             },
         };
-        command1.exec(&mut service).await.unwrap();
+        command1.exec(&service).await.unwrap();
 
         // Try to create duplicate
         let command2 = CreatePurchaseCategoryCommand {
@@ -229,13 +239,13 @@ mod tests {
                 state: None,
             },
         };
-        let result = command2.exec(&mut service).await;
-        assert!(matches!(result, Err(Error::UniqueConstraintError)));
+        let result = command2.exec(&service).await;
+        assert!(matches!(result, Err(Error::UniqueConstraintError(_))));
     }
 
     #[tokio::test]
     async fn test_update_purchase_category() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create category
         let command = CreatePurchaseCategoryCommand {
@@ -245,7 +255,7 @@ mod tests {
                 state: None,
             },
         };
-        let category = command.exec(&mut service).await.unwrap();
+        let category = command.exec(&service).await.unwrap();
 
         // Update category
         let update_command = UpdatePurchaseCategoryCommand {
@@ -258,7 +268,7 @@ mod tests {
             },
         };
 
-        let updated_category = update_command.exec(&mut service).await.unwrap();
+        let updated_category = update_command.exec(&service).await.unwrap();
         assert_eq!(updated_category.name, "Updated Category");
         assert_eq!(
             updated_category.description,
@@ -269,7 +279,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_nonexistent_category() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let update_command = UpdatePurchaseCategoryCommand {
             category: PurchaseCategoryUpdate {
@@ -281,13 +291,13 @@ mod tests {
             },
         };
 
-        let result = update_command.exec(&mut service).await;
+        let result = update_command.exec(&service).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_delete_purchase_category() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create category
         let command = CreatePurchaseCategoryCommand {
@@ -297,19 +307,20 @@ mod tests {
                 state: None,
             },
         };
-        let category = command.exec(&mut service).await.unwrap();
+        let category = command.exec(&service).await.unwrap();
 
         // Delete category
-        let delete_command = DeletePurchaseCategoryCommand { id: category.id };
-        let result = delete_command.exec(&mut service).await.unwrap();
+        let delete_command = DeletePurchaseCategoryCommand { id: category.id, deleted_by: None };
+        let result = delete_command.exec(&service).await.unwrap();
         assert_eq!(result, 1);
 
-        // Verify category no longer exists
+        // Verify category is no longer listed
         let mut count_query = Query::select();
         let count_stmt = count_query
             .from(PurchaseCategories::Table)
             .expr_as(Expr::col(PurchaseCategories::Id).count(), Alias::new("count"))
-            .and_where(Expr::col(PurchaseCategories::Id).eq(category.id.to_string()));
+            .and_where(Expr::col(PurchaseCategories::Id).eq(category.id.to_string()))
+            .and_where(Expr::col(PurchaseCategories::DeletedAt).is_null());
 
         let count = service.db_adapter.query_one::<i64>(&count_stmt).await.unwrap();
         assert_eq!(count, 0);
@@ -317,13 +328,14 @@ mod tests {
 
     #[tokio::test]
     async fn test_delete_nonexistent_category() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let delete_command = DeletePurchaseCategoryCommand {
             id: Uuid::now_v7().into(),
+            deleted_by: None,
         };
 
-        let result = delete_command.exec(&mut service).await;
+        let result = delete_command.exec(&service).await;
         assert!(result.is_err());
     }
 }
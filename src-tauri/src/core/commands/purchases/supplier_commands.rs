@@ -6,6 +6,7 @@ use crate::{
     adapters::outgoing::database::DatabaseAdapter,
     core::{
         commands::{app_service::AppService, Command},
+        db::soft_delete::{purge, restore, soft_delete},
         models::purchases::supplier_model::{
             Supplier, SupplierNewInput, SupplierUpdateInput, Suppliers,
         },
@@ -25,13 +26,22 @@ pub struct UpdateSupplierCommand {
 
 pub struct DeleteSupplierCommand {
     pub id: DbUuid,
+    pub deleted_by: Option<DbUuid>,
+}
+
+pub struct RestoreSupplierCommand {
+    pub id: DbUuid,
+}
+
+pub struct PurgeSupplierCommand {
+    pub id: DbUuid,
 }
 
 // Command Implementations
 impl Command for CreateSupplierCommand {
     type Output = Supplier;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let new_id = Uuid::now_v7();
 
@@ -42,6 +52,8 @@ impl Command for CreateSupplierCommand {
             phone: self.supplier.phone.clone(),
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
         };
 
         // Build the insert query with SeaQuery
@@ -76,7 +88,7 @@ impl Command for CreateSupplierCommand {
 impl Command for UpdateSupplierCommand {
     type Output = Supplier;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let supplier_id = self.supplier.id;
 
@@ -142,17 +154,28 @@ impl Command for UpdateSupplierCommand {
 impl Command for DeleteSupplierCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        // Build the delete query with SeaQuery
-        let mut delete_query = Query::delete();
-        let delete_stmt = delete_query
-            .from_table(Suppliers::Table)
-            .and_where(Expr::col(Suppliers::Id).eq(self.id.to_string()));
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        soft_delete::<Supplier, _>(&service.db_adapter, self.id, self.deleted_by).await?;
 
-        // Execute the query
-        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+        Ok(1)
+    }
+}
 
-        Ok(affected_rows as i32)
+impl Command for RestoreSupplierCommand {
+    type Output = Supplier;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        restore(&service.db_adapter, self.id).await
+    }
+}
+
+impl Command for PurgeSupplierCommand {
+    type Output = i32;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        purge::<Supplier, _>(&service.db_adapter, self.id).await?;
+
+        Ok(1)
     }
 }
 
@@ -166,7 +189,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_supplier() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let command = CreateSupplierCommand {
             supplier: SupplierNewInput {
@@ -176,7 +199,7 @@ mod tests {
             },
         };
 
-        let supplier = command.exec(&mut service).await.unwrap();
+        let supplier = command.exec(&service).await.unwrap();
         assert_eq!(supplier.name, "Test Supplier");
         assert_eq!(supplier.address, Some("123 Test St".to_string()));
         assert_eq!(supplier.phone, Some("123-456-7890".to_string()));
@@ -184,7 +207,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_supplier_minimal() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let command = CreateSupplierCommand {
             supplier: SupplierNewInput {
@@ -194,7 +217,7 @@ mod tests {
             },
         };
 
-        let supplier = command.exec(&mut service).await.unwrap();
+        let supplier = command.exec(&service).await.unwrap();
         assert_eq!(supplier.name, "Test Supplier");
         assert_eq!(supplier.address, None);
         assert_eq!(supplier.phone, None);
@@ -202,7 +225,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_supplier() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create supplier
         let create_command = CreateSupplierCommand {
@@ -213,7 +236,7 @@ mod tests {
             },
         };
 
-        let supplier = create_command.exec(&mut service).await.unwrap();
+        let supplier = create_command.exec(&service).await.unwrap();
 
         // Update supplier
         let update_command = UpdateSupplierCommand {
@@ -225,7 +248,7 @@ mod tests {
             },
         };
 
-        let updated_supplier = update_command.exec(&mut service).await.unwrap();
+        let updated_supplier = update_command.exec(&service).await.unwrap();
         assert_eq!(updated_supplier.name, "Updated Supplier");
         assert_eq!(updated_supplier.address, Some("456 New St".to_string()));
         assert_eq!(updated_supplier.phone, Some("123-456-7890".to_string())); // Unchanged
@@ -233,7 +256,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_supplier_remove_field() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create supplier
         let create_command = CreateSupplierCommand {
@@ -244,7 +267,7 @@ mod tests {
             },
         };
 
-        let supplier = create_command.exec(&mut service).await.unwrap();
+        let supplier = create_command.exec(&service).await.unwrap();
 
         // Update supplier - remove address
         let update_command = UpdateSupplierCommand {
@@ -256,7 +279,7 @@ mod tests {
             },
         };
 
-        let updated_supplier = update_command.exec(&mut service).await.unwrap();
+        let updated_supplier = update_command.exec(&service).await.unwrap();
         assert_eq!(updated_supplier.name, "Test Supplier"); // Unchanged
         assert_eq!(updated_supplier.address, None); // Removed
         assert_eq!(updated_supplier.phone, Some("123-456-7890".to_string())); // Unchanged
@@ -264,7 +287,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_nonexistent_supplier() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let update_command = UpdateSupplierCommand {
             supplier: SupplierUpdateInput {
@@ -275,13 +298,13 @@ mod tests {
             },
         };
 
-        let result = update_command.exec(&mut service).await;
+        let result = update_command.exec(&service).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_delete_supplier() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create supplier
         let create_command = CreateSupplierCommand {
@@ -292,19 +315,20 @@ mod tests {
             },
         };
 
-        let supplier = create_command.exec(&mut service).await.unwrap();
+        let supplier = create_command.exec(&service).await.unwrap();
 
         // Delete supplier
-        let delete_command = DeleteSupplierCommand { id: supplier.id };
-        let result = delete_command.exec(&mut service).await.unwrap();
+        let delete_command = DeleteSupplierCommand { id: supplier.id, deleted_by: None };
+        let result = delete_command.exec(&service).await.unwrap();
         assert_eq!(result, 1);
 
-        // Verify supplier no longer exists
+        // Verify supplier is no longer listed
         let mut count_query = Query::select();
         let count_stmt = count_query
             .from(Suppliers::Table)
             .expr_as(Expr::col(Suppliers::Id).count(), Alias::new("count"))
-            .and_where(Expr::col(Suppliers::Id).eq(supplier.id.to_string()));
+            .and_where(Expr::col(Suppliers::Id).eq(supplier.id.to_string()))
+            .and_where(Expr::col(Suppliers::DeletedAt).is_null());
 
         let count = service.db_adapter.query_one::<i64>(&count_stmt).await.unwrap();
         assert_eq!(count, 0);
@@ -312,15 +336,14 @@ mod tests {
 
     #[tokio::test]
     async fn test_delete_nonexistent_supplier() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Delete non-existent supplier
         let delete_command = DeleteSupplierCommand {
             id: Uuid::now_v7().into(),
+            deleted_by: None,
         };
-        let result = delete_command.exec(&mut service).await.unwrap();
-
-        // This should return 0 rows affected, not an error
-        assert_eq!(result, 0);
+        let result = delete_command.exec(&service).await;
+        assert!(matches!(result, Err(Error::NotFoundError)));
     }
 }
@@ -1,17 +1,407 @@
-use chrono::Utc;
+use chrono::{Duration, NaiveDateTime, Utc};
 use sea_query::{Expr, Query};
 use uuid::Uuid;
 
 use crate::{
-    adapters::outgoing::database::DatabaseAdapter,
+    adapters::outgoing::{
+        database::DatabaseAdapter,
+        secrets::{self, SecretName, SecretStore},
+    },
     core::{
-        commands::{app_service::AppService, Command},
-        models::sales::cart_model::{Cart, CartNewInput, CartUpdateInput, Carts},
-        types::db_uuid::DbUuid,
+        commands::{
+            app_service::AppService,
+            catalog::price_list_commands::{price_context_for, resolve_item_price},
+            sales::sales_order_commands::{insert_sales_order, publish_order_created, verify_price_override},
+            Command,
+        },
+        models::{
+            catalog::{
+                discount_model::{Discount, DiscountScope, DiscountState, DiscountType, Discounts},
+                item_discount_model::ItemDiscounts,
+                item_model::{Item, ItemState, Items},
+                item_variant_model::{ItemVariant, ItemVariants},
+                price_list_model::PriceContext,
+            },
+            common::{
+                tax_group_model::TaxGroupTaxes,
+                tax_model::{ItemTaxes, Tax, Taxes},
+            },
+            sales::{
+                cart_model::{
+                    Cart, CartCharge, CartChargeNewInput, CartCharges, CartLine, CartLineNewInput,
+                    CartLineOverrideInput, CartLineUpdateInput, CartLines, CartNewInput, CartStatus,
+                    CartUpdateInput, Carts, CheckoutCartInput, ParkCartInput, RecallCartInput,
+                },
+                customer_model::{Customer, Customers},
+                sales_charge_type_model::{SalesChargeType, SalesChargeTypes},
+                sales_order_charge_model::SalesOrderChargeNewInput,
+                sales_order_item_model::{SalesOrderItemInput, SalesOrderItemOverrideInput},
+                sales_order_model::{SalesOrder, SalesOrderNewInput},
+            },
+        },
+        types::{db_uuid::DbUuid, money::Money, percentage::Percentage},
     },
     error::{Error, Result},
 };
 
+async fn load_cart(service: &AppService, id: DbUuid) -> Result<Cart> {
+    let mut query = Query::select();
+    let stmt = query
+        .from(Carts::Table)
+        .columns(Carts::all_columns())
+        .and_where(Expr::col(Carts::Id).eq(id.to_string()));
+
+    service
+        .db_adapter
+        .query_optional::<Cart>(&stmt)
+        .await?
+        .ok_or(Error::NotFoundError)
+}
+
+// Parked carts are frozen until a terminal recalls them
+async fn load_open_cart(service: &AppService, id: DbUuid) -> Result<Cart> {
+    let cart = load_cart(service, id).await?;
+    if cart.status == CartStatus::Parked {
+        return Err(Error::ValidationError("Cart is parked, recall it before changing it".to_string()));
+    }
+    Ok(cart)
+}
+
+async fn load_cart_line(service: &AppService, id: DbUuid) -> Result<CartLine> {
+    let mut query = Query::select();
+    let stmt = query
+        .from(CartLines::Table)
+        .columns(CartLines::all_columns())
+        .and_where(Expr::col(CartLines::Id).eq(id.to_string()));
+
+    service
+        .db_adapter
+        .query_optional::<CartLine>(&stmt)
+        .await?
+        .ok_or(Error::NotFoundError)
+}
+
+/// Lines of a cart in the order they were added
+pub async fn cart_lines(service: &AppService, cart_id: DbUuid) -> Result<Vec<CartLine>> {
+    let mut query = Query::select();
+    let stmt = query
+        .from(CartLines::Table)
+        .columns(CartLines::all_columns())
+        .and_where(Expr::col(CartLines::CartId).eq(cart_id.to_string()))
+        .order_by(CartLines::CreatedAt, sea_query::Order::Asc)
+        .order_by(CartLines::Id, sea_query::Order::Asc);
+
+    service.db_adapter.query_many::<CartLine>(&stmt).await
+}
+
+pub async fn cart_charges(service: &AppService, cart_id: DbUuid) -> Result<Vec<CartCharge>> {
+    let mut query = Query::select();
+    let stmt = query
+        .from(CartCharges::Table)
+        .columns(CartCharges::all_columns())
+        .and_where(Expr::col(CartCharges::CartId).eq(cart_id.to_string()))
+        .order_by(CartCharges::CreatedAt, sea_query::Order::Asc)
+        .order_by(CartCharges::Id, sea_query::Order::Asc);
+
+    service.db_adapter.query_many::<CartCharge>(&stmt).await
+}
+
+// Any change to a line or charge counts as a change to the cart
+async fn touch_cart(service: &AppService, cart_id: DbUuid, now: NaiveDateTime) -> Result<()> {
+    let mut update_query = Query::update();
+    let update_stmt = update_query
+        .table(Carts::Table)
+        .value(Carts::UpdatedAt, now.to_string())
+        .and_where(Expr::col(Carts::Id).eq(cart_id.to_string()));
+
+    service.db_adapter.update_many(&update_stmt).await?;
+    Ok(())
+}
+
+// Only active items can be sold, and a variant has to belong to its item
+async fn check_item(service: &AppService, item_id: DbUuid, variant_id: Option<DbUuid>) -> Result<(Item, Option<ItemVariant>)> {
+    let db = &service.db_adapter;
+
+    let mut item_query = Query::select();
+    let item_stmt = item_query
+        .from(Items::Table)
+        .columns(Items::all_columns())
+        .and_where(Expr::col(Items::Id).eq(item_id.to_string()));
+
+    let item = db.query_optional::<Item>(&item_stmt).await?.ok_or(Error::NotFoundError)?;
+    if !matches!(item.state, ItemState::Active) {
+        return Err(Error::ValidationError(format!("Item '{}' is not available for sale", item.name)));
+    }
+
+    let variant = match variant_id {
+        Some(variant_id) => {
+            let mut variant_query = Query::select();
+            let variant_stmt = variant_query
+                .from(ItemVariants::Table)
+                .columns(ItemVariants::all_columns())
+                .and_where(Expr::col(ItemVariants::Id).eq(variant_id.to_string()))
+                .and_where(Expr::col(ItemVariants::ItemId).eq(item_id.to_string()));
+
+            let variant = db.query_optional::<ItemVariant>(&variant_stmt).await?.ok_or_else(|| {
+                Error::ValidationError(format!("Variant does not belong to item '{}'", item.name))
+            })?;
+            Some(variant)
+        }
+        None => None,
+    };
+
+    Ok((item, variant))
+}
+
+// A promotion has to be running, and item specific promotions only apply to their items
+async fn check_discount(
+    service: &AppService,
+    discount_id: DbUuid,
+    item_id: Option<DbUuid>,
+    now: NaiveDateTime,
+) -> Result<Discount> {
+    let db = &service.db_adapter;
+
+    let mut discount_query = Query::select();
+    let discount_stmt = discount_query
+        .from(Discounts::Table)
+        .columns(Discounts::all_columns())
+        .and_where(Expr::col(Discounts::Id).eq(discount_id.to_string()));
+
+    let discount = db.query_optional::<Discount>(&discount_stmt).await?.ok_or(Error::NotFoundError)?;
+
+    let state = discount.effective_state(now);
+    if state != DiscountState::Active {
+        return Err(Error::ValidationError(format!(
+            "Discount '{}' cannot be applied because it is {}",
+            discount.name, state
+        )));
+    }
+
+    if let Some(item_id) = item_id {
+        if !discount_applies_to(service, &discount, item_id).await? {
+            return Err(Error::ValidationError(format!(
+                "Discount '{}' does not apply to this item",
+                discount.name
+            )));
+        }
+    }
+
+    Ok(discount)
+}
+
+async fn discount_applies_to(service: &AppService, discount: &Discount, item_id: DbUuid) -> Result<bool> {
+    if discount.scope == DiscountScope::AllItems {
+        return Ok(true);
+    }
+
+    let mut link_query = Query::select();
+    let link_stmt = link_query
+        .from(ItemDiscounts::Table)
+        .expr(Expr::col(ItemDiscounts::ItemId).count())
+        .and_where(Expr::col(ItemDiscounts::ItemId).eq(item_id.to_string()))
+        .and_where(Expr::col(ItemDiscounts::DiscountId).eq(discount.id.to_string()));
+
+    let links = service.db_adapter.query_one::<i64>(&link_stmt).await?;
+    Ok(links > 0)
+}
+
+// Percentage discounts store the percentage in `value`; fixed ones never exceed the amount
+fn discount_amount(discount: &Discount, amount: Money) -> Result<Money> {
+    match discount.discount_type {
+        DiscountType::Percentage => {
+            let percentage = Percentage::from_str(&discount.value.to_string()).map_err(Error::ValidationError)?;
+            Ok(amount * percentage)
+        }
+        DiscountType::FixedAmount => Ok(discount.value.min(amount)),
+    }
+}
+
+// Combined rate of the taxes assigned to an item
+async fn item_tax_rate(service: &AppService, item_id: DbUuid) -> Result<Percentage> {
+    let mut tax_query = Query::select();
+    let tax_stmt = tax_query
+        .from(Taxes::Table)
+        .columns(Taxes::all_columns())
+        .and_where(
+            Expr::col(Taxes::Id).in_subquery(
+                Query::select()
+                    .column(ItemTaxes::TaxId)
+                    .from(ItemTaxes::Table)
+                    .and_where(Expr::col(ItemTaxes::ItemId).eq(item_id.to_string()))
+                    .to_owned(),
+            ),
+        );
+
+    let taxes = service.db_adapter.query_many::<Tax>(&tax_stmt).await?;
+    Ok(taxes.into_iter().map(|tax| tax.rate).sum())
+}
+
+// Combined rate of the taxes in a tax group
+async fn tax_group_rate(service: &AppService, tax_group_id: DbUuid) -> Result<Percentage> {
+    let mut tax_query = Query::select();
+    let tax_stmt = tax_query
+        .from(Taxes::Table)
+        .columns(Taxes::all_columns())
+        .and_where(
+            Expr::col(Taxes::Id).in_subquery(
+                Query::select()
+                    .column(TaxGroupTaxes::TaxId)
+                    .from(TaxGroupTaxes::Table)
+                    .and_where(Expr::col(TaxGroupTaxes::TaxGroupId).eq(tax_group_id.to_string()))
+                    .to_owned(),
+            ),
+        );
+
+    let taxes = service.db_adapter.query_many::<Tax>(&tax_stmt).await?;
+    Ok(taxes.into_iter().map(|tax| tax.rate).sum())
+}
+
+/// Prices a cart into order lines and charges.
+///
+/// Each line starts from its manual price or the resolved list price. The line's own
+/// promotion comes off first, then the cart promotion is split across the lines it applies
+/// to in proportion to what is left of them, so the parts add up to the promotion exactly.
+/// Taxes are charged on what remains after discounts.
+async fn price_cart(
+    service: &AppService,
+    cart: &Cart,
+    lines: &[CartLine],
+    charges: &[CartCharge],
+    price_context: &PriceContext,
+    now: NaiveDateTime,
+) -> Result<(Vec<SalesOrderItemInput>, Vec<SalesOrderChargeNewInput>)> {
+    struct PricedLine {
+        item: Item,
+        variant: Option<ItemVariant>,
+        price: Money,
+        gross: Money,
+        line_discount: Money,
+        cart_discount_applies: bool,
+    }
+
+    let cart_discount = match cart.discount_id {
+        Some(discount_id) => Some(check_discount(service, discount_id, None, now).await?),
+        None => None,
+    };
+
+    let mut priced = Vec::with_capacity(lines.len());
+    for line in lines {
+        let (item, variant) = check_item(service, line.item_id, line.variant_id).await?;
+
+        let price = match line.override_price {
+            Some(price) => price,
+            None => resolve_item_price(service, line.item_id, line.variant_id, line.quantity, price_context, now).await?,
+        };
+        let gross = price * line.quantity;
+
+        let line_discount = match line.discount_id {
+            Some(discount_id) => {
+                let discount = check_discount(service, discount_id, Some(line.item_id), now).await?;
+                discount_amount(&discount, gross)?
+            }
+            None => Money::from(0),
+        };
+
+        let cart_discount_applies = match &cart_discount {
+            Some(discount) => discount_applies_to(service, discount, line.item_id).await?,
+            None => false,
+        };
+
+        priced.push(PricedLine {
+            item,
+            variant,
+            price,
+            gross,
+            line_discount,
+            cart_discount_applies,
+        });
+    }
+
+    // Split the cart promotion over the lines it covers
+    let weights: Vec<i64> = priced
+        .iter()
+        .map(|line| match line.cart_discount_applies {
+            true => (line.gross - line.line_discount).to_base_unit(),
+            false => 0,
+        })
+        .collect();
+    let cart_discount_shares = match &cart_discount {
+        Some(discount) if weights.iter().any(|weight| *weight > 0) => {
+            let base = Money::from(weights.iter().sum::<i64>());
+            discount_amount(discount, base)?.allocate(&weights)
+        }
+        _ => vec![Money::from(0); priced.len()],
+    };
+
+    let mut items = Vec::with_capacity(priced.len());
+    for ((line, priced_line), cart_share) in lines.iter().zip(priced).zip(cart_discount_shares) {
+        let disc_amount = priced_line.line_discount + cart_share;
+        let taxable_amount = priced_line.gross - disc_amount;
+        let tax_amount = taxable_amount * item_tax_rate(service, line.item_id).await?;
+
+        items.push(SalesOrderItemInput {
+            item_id: Some(line.item_id),
+            variant_id: line.variant_id,
+            item_name: priced_line.item.name,
+            quantity: line.quantity,
+            sku: priced_line.variant.and_then(|variant| variant.sku),
+            price_amount: priced_line.price,
+            disc_amount,
+            taxable_amount,
+            tax_amount,
+            total_amount: taxable_amount + tax_amount,
+            price_override: line.override_reason_id.map(|reason_id| SalesOrderItemOverrideInput {
+                reason_id,
+                note: line.override_note.clone(),
+                approved_by: line.override_approved_by,
+                approval_pin: None,
+            }),
+        });
+    }
+
+    let mut order_charges = Vec::with_capacity(charges.len());
+    for charge in charges {
+        let mut charge_type_query = Query::select();
+        let charge_type_stmt = charge_type_query
+            .from(SalesChargeTypes::Table)
+            .columns(SalesChargeTypes::all_columns())
+            .and_where(Expr::col(SalesChargeTypes::Id).eq(charge.charge_type_id.to_string()));
+        let charge_type = service.db_adapter.query_one::<SalesChargeType>(&charge_type_stmt).await?;
+
+        let tax_amount = match charge.tax_group_id {
+            Some(tax_group_id) => charge.amount * tax_group_rate(service, tax_group_id).await?,
+            None => Money::from(0),
+        };
+
+        order_charges.push(SalesOrderChargeNewInput {
+            charge_type_id: charge.charge_type_id,
+            charge_type_name: charge_type.name,
+            amount: charge.amount,
+            tax_amount,
+            tax_group_id: charge.tax_group_id,
+        });
+    }
+
+    Ok((items, order_charges))
+}
+
+// Overrides are approved when they are put on the line, so parking a cart never stores a PIN
+async fn check_line_override(service: &AppService, item_name: &str, price_override: &CartLineOverrideInput) -> Result<()> {
+    if price_override.price < Money::from(0) {
+        return Err(Error::ValidationError("Override price cannot be negative".to_string()));
+    }
+
+    verify_price_override(
+        service,
+        item_name,
+        price_override.reason_id,
+        price_override.approved_by,
+        price_override.approval_pin.as_deref(),
+    )
+    .await
+}
+
 // Commands
 pub struct CreateCartCommand {
     pub cart: CartNewInput,
@@ -25,18 +415,96 @@ pub struct DeleteCartCommand {
     pub id: DbUuid,
 }
 
+pub struct AddCartLineCommand {
+    pub line: CartLineNewInput,
+}
+
+pub struct UpdateCartLineCommand {
+    pub line: CartLineUpdateInput,
+}
+
+pub struct RemoveCartLineCommand {
+    pub id: DbUuid,
+}
+
+pub struct AddCartChargeCommand {
+    pub charge: CartChargeNewInput,
+}
+
+pub struct RemoveCartChargeCommand {
+    pub id: DbUuid,
+}
+
+pub struct CheckoutCartCommand {
+    pub checkout: CheckoutCartInput,
+    pub created_by_user_id: DbUuid,
+}
+
+pub struct ParkCartCommand {
+    pub cart: ParkCartInput,
+    pub parked_by_user_id: DbUuid,
+}
+
+pub struct RecallCartCommand {
+    pub cart: RecallCartInput,
+}
+
+/// Open carts nobody touched for this many hours are purged, unless the `cart-max-age-hours`
+/// setting says otherwise
+const DEFAULT_CART_MAX_AGE_HOURS: i64 = 72;
+
+/// Deletes open carts that nobody has touched for `max_age`. Parked carts stay until they
+/// are recalled, however long the customer takes.
+pub struct PurgeAbandonedCartsCommand {
+    pub max_age: Duration,
+}
+
+impl Default for PurgeAbandonedCartsCommand {
+    fn default() -> Self {
+        PurgeAbandonedCartsCommand {
+            max_age: Duration::hours(DEFAULT_CART_MAX_AGE_HOURS),
+        }
+    }
+}
+
+impl PurgeAbandonedCartsCommand {
+    /// Purges carts older than the `cart-max-age-hours` setting, kept with the secrets, or the
+    /// default when it is missing or invalid
+    pub fn load(secrets: &dyn SecretStore) -> Result<Self> {
+        let hours = secrets::get_number::<i64>(secrets, SecretName::CartMaxAgeHours)?
+            .filter(|hours| *hours > 0)
+            .unwrap_or(DEFAULT_CART_MAX_AGE_HOURS);
+        Ok(PurgeAbandonedCartsCommand {
+            max_age: Duration::hours(hours),
+        })
+    }
+}
+
 // Command Implementations
 impl Command for CreateCartCommand {
     type Output = Cart;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let new_id = Uuid::now_v7();
 
+        if let Some(discount_id) = self.cart.discount_id {
+            check_discount(service, discount_id, None, now).await?;
+        }
+
         let new_cart = Cart {
             id: new_id.into(),
             customer_id: self.cart.customer_id,
-            cart_data: self.cart.cart_data.clone(),
+            discount_id: self.cart.discount_id,
+            notes: self.cart.notes.clone(),
+            legacy_cart_data: None,
+            status: CartStatus::Open,
+            label: None,
+            hold_reason: None,
+            parked_by: None,
+            terminal_id: self.cart.terminal_id.clone(),
+            location_id: self.cart.location_id,
+            held_since: None,
             created_at: now,
             updated_at: now,
         };
@@ -48,14 +516,22 @@ impl Command for CreateCartCommand {
             .columns([
                 Carts::Id,
                 Carts::CustomerId,
-                Carts::CartData,
+                Carts::DiscountId,
+                Carts::Notes,
+                Carts::Status,
+                Carts::TerminalId,
+                Carts::LocationId,
                 Carts::CreatedAt,
                 Carts::UpdatedAt,
             ])
             .values_panic([
                 new_id.to_string().into(),
                 self.cart.customer_id.map(|id| id.to_string()).into(),
-                self.cart.cart_data.clone().into(),
+                self.cart.discount_id.map(|id| id.to_string()).into(),
+                self.cart.notes.clone().into(),
+                CartStatus::Open.to_string().into(),
+                self.cart.terminal_id.clone().into(),
+                self.cart.location_id.map(|id| id.to_string()).into(),
                 now.to_string().into(),
                 now.to_string().into(),
             ]);
@@ -71,27 +547,15 @@ impl Command for CreateCartCommand {
 impl Command for UpdateCartCommand {
     type Output = Cart;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let cart_id = self.cart.id;
 
-        // First, check if the cart exists
-        let mut check_query = Query::select();
-        let check_stmt = check_query
-            .from(Carts::Table)
-            .columns([
-                Carts::Id,
-                Carts::CustomerId,
-                Carts::CartData,
-                Carts::CreatedAt,
-                Carts::UpdatedAt,
-            ])
-            .and_where(Expr::col(Carts::Id).eq(cart_id.to_string()));
-
-        let existing = service.db_adapter.query_optional::<Cart>(&check_stmt).await?;
+        // First, check if the cart exists and is not parked
+        load_open_cart(service, cart_id).await?;
 
-        if existing.is_none() {
-            return Err(Error::NotFoundError);
+        if let Some(Some(discount_id)) = self.cart.discount_id {
+            check_discount(service, discount_id, None, now).await?;
         }
 
         // Build the update query with SeaQuery
@@ -99,8 +563,14 @@ impl Command for UpdateCartCommand {
         let mut update_stmt = update_query.table(Carts::Table);
 
         // Only set fields that are provided in the update input
-        if let Some(cart_data) = &self.cart.cart_data {
-            update_stmt = update_stmt.value(Carts::CartData, cart_data.clone());
+        if let Some(customer_id) = &self.cart.customer_id {
+            update_stmt = update_stmt.value(Carts::CustomerId, customer_id.map(|id| id.to_string()));
+        }
+        if let Some(discount_id) = &self.cart.discount_id {
+            update_stmt = update_stmt.value(Carts::DiscountId, discount_id.map(|id| id.to_string()));
+        }
+        if let Some(notes) = &self.cart.notes {
+            update_stmt = update_stmt.value(Carts::Notes, notes.clone());
         }
 
         // Always update the updated_at timestamp
@@ -113,16 +583,27 @@ impl Command for UpdateCartCommand {
         service.db_adapter.update_many(&update_stmt).await?;
 
         // Get the updated cart
-        let updated_cart = service.db_adapter.query_one::<Cart>(&check_stmt).await?;
-
-        Ok(updated_cart)
+        load_cart(service, cart_id).await
     }
 }
 
 impl Command for DeleteCartCommand {
     type Output = i32;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        // Lines and charges go with the cart
+        let mut delete_lines_query = Query::delete();
+        let delete_lines_stmt = delete_lines_query
+            .from_table(CartLines::Table)
+            .and_where(Expr::col(CartLines::CartId).eq(self.id.to_string()));
+        service.db_adapter.delete(&delete_lines_stmt).await?;
+
+        let mut delete_charges_query = Query::delete();
+        let delete_charges_stmt = delete_charges_query
+            .from_table(CartCharges::Table)
+            .and_where(Expr::col(CartCharges::CartId).eq(self.id.to_string()));
+        service.db_adapter.delete(&delete_charges_stmt).await?;
+
         // Build the delete query with SeaQuery
         let mut delete_query = Query::delete();
         let delete_stmt = delete_query
@@ -140,20 +621,473 @@ impl Command for DeleteCartCommand {
     }
 }
 
+impl Command for AddCartLineCommand {
+    type Output = CartLine;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let now = Utc::now().naive_utc();
+        let line = &self.line;
+
+        load_open_cart(service, line.cart_id).await?;
+        if line.quantity <= 0 {
+            return Err(Error::ValidationError("Quantity must be greater than zero".to_string()));
+        }
+        let (item, _) = check_item(service, line.item_id, line.variant_id).await?;
+        if let Some(discount_id) = line.discount_id {
+            check_discount(service, discount_id, Some(line.item_id), now).await?;
+        }
+        if let Some(price_override) = &line.price_override {
+            check_line_override(service, &item.name, price_override).await?;
+        }
+
+        let price_override = line.price_override.as_ref();
+        let new_id: DbUuid = Uuid::now_v7().into();
+
+        let mut insert_query = Query::insert();
+        let insert_stmt = insert_query
+            .into_table(CartLines::Table)
+            .columns([
+                CartLines::Id,
+                CartLines::CartId,
+                CartLines::ItemId,
+                CartLines::VariantId,
+                CartLines::Quantity,
+                CartLines::DiscountId,
+                CartLines::Note,
+                CartLines::OverridePrice,
+                CartLines::OverrideReasonId,
+                CartLines::OverrideNote,
+                CartLines::OverrideApprovedBy,
+                CartLines::CreatedAt,
+                CartLines::UpdatedAt,
+            ])
+            .values_panic([
+                new_id.to_string().into(),
+                line.cart_id.to_string().into(),
+                line.item_id.to_string().into(),
+                line.variant_id.map(|id| id.to_string()).into(),
+                line.quantity.into(),
+                line.discount_id.map(|id| id.to_string()).into(),
+                line.note.clone().into(),
+                price_override.map(|o| o.price.to_base_unit()).into(),
+                price_override.map(|o| o.reason_id.to_string()).into(),
+                price_override.and_then(|o| o.note.clone()).into(),
+                price_override.and_then(|o| o.approved_by).map(|id| id.to_string()).into(),
+                now.to_string().into(),
+                now.to_string().into(),
+            ]);
+
+        service.db_adapter.insert_one::<CartLine>(&insert_stmt).await?;
+        touch_cart(service, line.cart_id, now).await?;
+
+        load_cart_line(service, new_id).await
+    }
+}
+
+impl Command for UpdateCartLineCommand {
+    type Output = CartLine;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let now = Utc::now().naive_utc();
+        let existing = load_cart_line(service, self.line.id).await?;
+        load_open_cart(service, existing.cart_id).await?;
+
+        let mut update_query = Query::update();
+        let mut update_stmt = update_query.table(CartLines::Table);
+
+        if let Some(quantity) = self.line.quantity {
+            if quantity <= 0 {
+                return Err(Error::ValidationError("Quantity must be greater than zero".to_string()));
+            }
+            update_stmt = update_stmt.value(CartLines::Quantity, quantity);
+        }
+        if let Some(discount_id) = &self.line.discount_id {
+            if let Some(discount_id) = discount_id {
+                check_discount(service, *discount_id, Some(existing.item_id), now).await?;
+            }
+            update_stmt = update_stmt.value(CartLines::DiscountId, discount_id.map(|id| id.to_string()));
+        }
+        if let Some(note) = &self.line.note {
+            update_stmt = update_stmt.value(CartLines::Note, note.clone());
+        }
+        match &self.line.price_override {
+            Some(Some(price_override)) => {
+                let (item, _) = check_item(service, existing.item_id, existing.variant_id).await?;
+                check_line_override(service, &item.name, price_override).await?;
+
+                update_stmt = update_stmt
+                    .value(CartLines::OverridePrice, price_override.price.to_base_unit())
+                    .value(CartLines::OverrideReasonId, price_override.reason_id.to_string())
+                    .value(CartLines::OverrideNote, price_override.note.clone())
+                    .value(CartLines::OverrideApprovedBy, price_override.approved_by.map(|id| id.to_string()));
+            }
+            Some(None) => {
+                update_stmt = update_stmt
+                    .value(CartLines::OverridePrice, sea_query::Value::BigInt(None))
+                    .value(CartLines::OverrideReasonId, sea_query::Value::String(None))
+                    .value(CartLines::OverrideNote, sea_query::Value::String(None))
+                    .value(CartLines::OverrideApprovedBy, sea_query::Value::String(None));
+            }
+            None => {}
+        }
+
+        update_stmt = update_stmt
+            .value(CartLines::UpdatedAt, now.to_string())
+            .and_where(Expr::col(CartLines::Id).eq(self.line.id.to_string()));
+
+        service.db_adapter.update_many(&update_stmt).await?;
+        touch_cart(service, existing.cart_id, now).await?;
+
+        load_cart_line(service, self.line.id).await
+    }
+}
+
+impl Command for RemoveCartLineCommand {
+    type Output = CartLine;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let now = Utc::now().naive_utc();
+        let existing = load_cart_line(service, self.id).await?;
+        load_open_cart(service, existing.cart_id).await?;
+
+        let mut delete_query = Query::delete();
+        let delete_stmt = delete_query
+            .from_table(CartLines::Table)
+            .and_where(Expr::col(CartLines::Id).eq(self.id.to_string()));
+
+        service.db_adapter.delete(&delete_stmt).await?;
+        touch_cart(service, existing.cart_id, now).await?;
+
+        Ok(existing)
+    }
+}
+
+impl Command for AddCartChargeCommand {
+    type Output = CartCharge;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let now = Utc::now().naive_utc();
+        let charge = &self.charge;
+
+        load_open_cart(service, charge.cart_id).await?;
+        if charge.amount < Money::from(0) {
+            return Err(Error::ValidationError("Charge amount cannot be negative".to_string()));
+        }
+
+        let new_id: DbUuid = Uuid::now_v7().into();
+
+        let mut insert_query = Query::insert();
+        let insert_stmt = insert_query
+            .into_table(CartCharges::Table)
+            .columns([
+                CartCharges::Id,
+                CartCharges::CartId,
+                CartCharges::ChargeTypeId,
+                CartCharges::Amount,
+                CartCharges::TaxGroupId,
+                CartCharges::CreatedAt,
+                CartCharges::UpdatedAt,
+            ])
+            .values_panic([
+                new_id.to_string().into(),
+                charge.cart_id.to_string().into(),
+                charge.charge_type_id.to_string().into(),
+                charge.amount.to_base_unit().into(),
+                charge.tax_group_id.map(|id| id.to_string()).into(),
+                now.to_string().into(),
+                now.to_string().into(),
+            ]);
+
+        service.db_adapter.insert_one::<CartCharge>(&insert_stmt).await?;
+        touch_cart(service, charge.cart_id, now).await?;
+
+        Ok(CartCharge {
+            id: new_id,
+            cart_id: charge.cart_id,
+            charge_type_id: charge.charge_type_id,
+            amount: charge.amount,
+            tax_group_id: charge.tax_group_id,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+}
+
+impl Command for RemoveCartChargeCommand {
+    type Output = DbUuid;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let now = Utc::now().naive_utc();
+
+        let mut query = Query::select();
+        let stmt = query
+            .from(CartCharges::Table)
+            .columns(CartCharges::all_columns())
+            .and_where(Expr::col(CartCharges::Id).eq(self.id.to_string()));
+        let existing = service
+            .db_adapter
+            .query_optional::<CartCharge>(&stmt)
+            .await?
+            .ok_or(Error::NotFoundError)?;
+        load_open_cart(service, existing.cart_id).await?;
+
+        let mut delete_query = Query::delete();
+        let delete_stmt = delete_query
+            .from_table(CartCharges::Table)
+            .and_where(Expr::col(CartCharges::Id).eq(self.id.to_string()));
+
+        service.db_adapter.delete(&delete_stmt).await?;
+        touch_cart(service, existing.cart_id, now).await?;
+
+        Ok(self.id)
+    }
+}
+
+impl Command for CheckoutCartCommand {
+    type Output = SalesOrder;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        // The cart is loaded in the transaction too, so a second checkout of it waits for this
+        // one and then finds it gone
+        let (order, items) = service.db_adapter.transaction(|| self.checkout(service)).await?;
+        publish_order_created(service, &order, &items);
+        Ok(order)
+    }
+}
+
+impl CheckoutCartCommand {
+    async fn checkout(&self, service: &AppService) -> Result<(SalesOrder, Vec<SalesOrderItemInput>)> {
+        let now = Utc::now().naive_utc();
+        let checkout = &self.checkout;
+
+        let cart = load_open_cart(service, checkout.cart_id).await?;
+        let lines = cart_lines(service, cart.id).await?;
+        if lines.is_empty() {
+            return Err(Error::ValidationError("Cannot check out an empty cart".to_string()));
+        }
+        let charges = cart_charges(service, cart.id).await?;
+
+        let price_context = price_context_for(
+            service,
+            Some(checkout.channel_id),
+            Some(checkout.location_id),
+            cart.customer_id,
+        )
+        .await?;
+        let (items, charges) = price_cart(service, &cart, &lines, &charges, &price_context, now).await?;
+
+        let customer = match cart.customer_id {
+            Some(customer_id) => {
+                let mut customer_query = Query::select();
+                let customer_stmt = customer_query
+                    .from(Customers::Table)
+                    .columns(Customers::all_columns())
+                    .and_where(Expr::col(Customers::Id).eq(customer_id.to_string()));
+                service.db_adapter.query_optional::<Customer>(&customer_stmt).await?
+            }
+            None => None,
+        };
+
+        let net_amount: Money = items.iter().map(|item| item.price_amount * item.quantity).sum();
+        let disc_amount: Money = items.iter().map(|item| item.disc_amount).sum();
+        let taxable_amount: Money = items.iter().map(|item| item.taxable_amount).sum();
+        let charges_amount: Money = charges.iter().map(|charge| charge.amount).sum();
+        let tax_amount: Money = items.iter().map(|item| item.tax_amount).sum::<Money>()
+            + charges.iter().map(|charge| charge.tax_amount).sum::<Money>();
+
+        let sales_order = SalesOrderNewInput {
+            order_date: checkout.order_date.unwrap_or(now),
+            customer_id: cart.customer_id,
+            customer_name: customer.as_ref().map(|customer| customer.full_name.clone()),
+            customer_phone_number: customer.as_ref().and_then(|customer| customer.phone.clone()),
+            billing_address: checkout
+                .billing_address
+                .clone()
+                .or_else(|| customer.as_ref().and_then(|customer| customer.address.clone())),
+            shipping_address: checkout.shipping_address.clone(),
+            net_amount,
+            disc_amount,
+            taxable_amount,
+            tax_amount,
+            total_amount: taxable_amount + tax_amount + charges_amount,
+            notes: cart.notes.clone(),
+            channel_id: checkout.channel_id,
+            location_id: checkout.location_id,
+            cost_center_id: checkout.cost_center_id,
+            discount_id: cart.discount_id,
+            items,
+            charges: if charges.is_empty() { None } else { Some(charges) },
+        };
+
+        // Overrides on cart lines were approved when they were set
+        let order = insert_sales_order(service, &sales_order, self.created_by_user_id, true).await?;
+
+        DeleteCartCommand { id: cart.id }.exec(service).await?;
+
+        Ok((order, sales_order.items))
+    }
+}
+
+impl Command for ParkCartCommand {
+    type Output = Cart;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let now = Utc::now().naive_utc();
+        let input = &self.cart;
+
+        let cart = load_open_cart(service, input.id).await?;
+        if let Some(terminal_id) = cart.terminal_id.as_ref().filter(|id| **id != input.terminal_id) {
+            return Err(Error::ValidationError(format!("Cart is open on terminal '{}'", terminal_id)));
+        }
+        if cart_lines(service, cart.id).await?.is_empty() {
+            return Err(Error::ValidationError("Cannot park an empty cart".to_string()));
+        }
+
+        let mut update_query = Query::update();
+        let update_stmt = update_query
+            .table(Carts::Table)
+            .value(Carts::Status, CartStatus::Parked.to_string())
+            .value(Carts::Label, input.label.clone())
+            .value(Carts::HoldReason, input.hold_reason.clone())
+            .value(Carts::ParkedBy, self.parked_by_user_id.to_string())
+            .value(Carts::TerminalId, input.terminal_id.clone())
+            .value(Carts::LocationId, input.location_id.to_string())
+            .value(Carts::HeldSince, now.to_string())
+            .value(Carts::UpdatedAt, now.to_string())
+            .and_where(Expr::col(Carts::Id).eq(input.id.to_string()))
+            .and_where(Expr::col(Carts::Status).eq(CartStatus::Open.to_string()));
+
+        // Another terminal may have parked or checked out the cart since it was loaded
+        let affected_rows = service.db_adapter.update_many(&update_stmt).await?;
+
+        let cart = load_cart(service, input.id).await?;
+        if affected_rows == 0 {
+            return Err(Error::ValidationError(format!(
+                "Cart was already parked on terminal '{}'",
+                cart.terminal_id.unwrap_or_default()
+            )));
+        }
+
+        Ok(cart)
+    }
+}
+
+impl Command for RecallCartCommand {
+    type Output = Cart;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let now = Utc::now().naive_utc();
+        let input = &self.cart;
+
+        // Taking the cart only succeeds while it is still parked, so two terminals
+        // recalling at the same time cannot both get it
+        let mut update_query = Query::update();
+        let update_stmt = update_query
+            .table(Carts::Table)
+            .value(Carts::Status, CartStatus::Open.to_string())
+            .value(Carts::TerminalId, input.terminal_id.clone())
+            .value(Carts::HeldSince, sea_query::Value::String(None))
+            .value(Carts::UpdatedAt, now.to_string())
+            .and_where(Expr::col(Carts::Id).eq(input.id.to_string()))
+            .and_where(Expr::col(Carts::Status).eq(CartStatus::Parked.to_string()));
+
+        let affected_rows = service.db_adapter.update_many(&update_stmt).await?;
+
+        let cart = load_cart(service, input.id).await?;
+        if affected_rows == 0 {
+            return Err(Error::ValidationError(format!(
+                "Cart is already open on terminal '{}'",
+                cart.terminal_id.unwrap_or_default()
+            )));
+        }
+
+        Ok(cart)
+    }
+}
+
+impl Command for PurgeAbandonedCartsCommand {
+    type Output = u64;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let cutoff = Utc::now().naive_utc() - self.max_age;
+
+        let abandoned = Query::select()
+            .column(Carts::Id)
+            .from(Carts::Table)
+            .and_where(Expr::col(Carts::Status).eq(CartStatus::Open.to_string()))
+            .and_where(Expr::col(Carts::UpdatedAt).lt(cutoff.to_string()))
+            .to_owned();
+
+        let mut delete_lines_query = Query::delete();
+        let delete_lines_stmt = delete_lines_query
+            .from_table(CartLines::Table)
+            .and_where(Expr::col(CartLines::CartId).in_subquery(abandoned.clone()));
+        service.db_adapter.delete(&delete_lines_stmt).await?;
+
+        let mut delete_charges_query = Query::delete();
+        let delete_charges_stmt = delete_charges_query
+            .from_table(CartCharges::Table)
+            .and_where(Expr::col(CartCharges::CartId).in_subquery(abandoned));
+        service.db_adapter.delete(&delete_charges_stmt).await?;
+
+        let mut delete_query = Query::delete();
+        let delete_stmt = delete_query
+            .from_table(Carts::Table)
+            .and_where(Expr::col(Carts::Status).eq(CartStatus::Open.to_string()))
+            .and_where(Expr::col(Carts::UpdatedAt).lt(cutoff.to_string()));
+
+        service.db_adapter.delete(&delete_stmt).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
+    use crate::adapters::outgoing::secrets::{MemorySecretStore, Secret};
+    use crate::core::events::Event;
+    use chrono::{Duration, Utc};
     use uuid::Uuid;
     use crate::core::{
-        commands::{sales::customer_commands::CreateCustomerCommand, tests::setup_service},
-        models::sales::customer_model::CustomerNewInput,
+        commands::{
+            auth::user_commands::AddUserCommand,
+            catalog::{
+                discount_commands::CreateDiscountCommand, item_commands::CreateItemCommand,
+                item_group_commands::CreateItemGroupCommand,
+            },
+            common::{
+                channel_commands::CreateChannelCommand, location_commands::CreateLocationCommand,
+                tax_commands::CreateTaxCommand,
+            },
+            finance::cost_center_commands::CreateCostCenterCommand,
+            sales::{
+                customer_commands::CreateCustomerCommand,
+                price_override_reason_commands::CreatePriceOverrideReasonCommand,
+                sales_charge_type_commands::CreateSalesChargeTypeCommand,
+                sales_order_commands::tests::create_test_user,
+            },
+            tests::setup_service,
+        },
+        models::{
+            auth::user_model::{UserNewInput, UserRole, UserState},
+            catalog::{
+                discount_model::DiscountNewInput, item_group_model::ItemCategoryNew,
+                item_model::{ItemNature, NewItem},
+            },
+            common::{channel_model::ChannelNewInput, location_model::LocationNewInput, tax_model::TaxNewInput},
+            finance::cost_center_model::{CostCenterNewInput, CostCenterState},
+            sales::{
+                customer_model::CustomerNewInput,
+                price_override_reason_model::PriceOverrideReasonNewInput,
+                sales_charge_type_model::SalesChargeTypeNewInput,
+                sales_order_item_model::{SalesOrderItem, SalesOrderItems},
+            },
+        },
     };
     use rand::Rng;
     use sea_query::{Alias, Expr, Query};
     use tokio;
 
-    async fn create_test_customer(service: &mut AppService) -> DbUuid {
+    async fn create_test_customer(service: &AppService) -> DbUuid {
         let random_suffix = rand::thread_rng().gen_range(1000..9999).to_string();
         let command = CreateCustomerCommand {
             customer: CustomerNewInput {
@@ -161,116 +1095,228 @@ mod tests {
                 email: Some(format!("test{}@example.com", random_suffix)),
                 phone: Some(format!("+1234567{}", random_suffix)),
                 address: None,
+                customer_group_id: None,
             },
         };
         command.exec(service).await.unwrap().id
     }
 
+    async fn create_test_item(service: &AppService, price: &str, tax_rate: Option<&str>) -> DbUuid {
+        let category = CreateItemGroupCommand {
+            category: ItemCategoryNew {
+                name: format!("Test Category {}", rand::thread_rng().gen_range(1..99999)),
+                description: None,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+
+        let tax_ids = match tax_rate {
+            Some(rate) => {
+                let tax = CreateTaxCommand {
+                    tax: TaxNewInput {
+                        name: format!("Tax {}", rand::thread_rng().gen_range(1..99999)),
+                        rate: Percentage::from_str(rate).unwrap(),
+                        description: None,
+                        item_ids: None,
+                    },
+                }
+                .exec(service)
+                .await
+                .unwrap();
+                Some(vec![tax.id])
+            }
+            None => None,
+        };
+
+        CreateItemCommand {
+            item: NewItem {
+                name: "Test Item".to_string(),
+                description: None,
+                nature: ItemNature::Goods,
+                state: ItemState::Active,
+                price: Money::from_str(price).unwrap(),
+                category_id: category.id,
+                tax_ids,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap()
+        .id
+    }
+
+    async fn create_test_cart(service: &AppService) -> Cart {
+        CreateCartCommand {
+            cart: CartNewInput {
+                customer_id: None,
+                discount_id: None,
+                notes: None,
+                terminal_id: None,
+                location_id: None,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap()
+    }
+
+    async fn add_line(service: &AppService, cart_id: DbUuid, item_id: DbUuid, quantity: i32) -> CartLine {
+        AddCartLineCommand {
+            line: CartLineNewInput {
+                cart_id,
+                item_id,
+                variant_id: None,
+                quantity,
+                discount_id: None,
+                note: None,
+                price_override: None,
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap()
+    }
+
+    async fn checkout_input(service: &AppService, cart_id: DbUuid) -> CheckoutCartInput {
+        let suffix = rand::thread_rng().gen_range(1..99999);
+        let channel = CreateChannelCommand {
+            channel: ChannelNewInput {
+                name: format!("Test Channel {}", suffix),
+                description: None,
+                is_active: Some(true),
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+        let location = CreateLocationCommand {
+            location: LocationNewInput {
+                name: format!("Test Location {}", suffix),
+                description: None,
+                address: None,
+                is_active: Some(true),
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+        let cost_center = CreateCostCenterCommand {
+            cost_center: CostCenterNewInput {
+                name: "Test Cost Center".to_string(),
+                code: format!("TCC{:05}", suffix),
+                description: None,
+                state: Some(CostCenterState::Active),
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap();
+
+        CheckoutCartInput {
+            cart_id,
+            channel_id: channel.id,
+            location_id: location.id,
+            cost_center_id: cost_center.id,
+            order_date: None,
+            billing_address: None,
+            shipping_address: None,
+        }
+    }
+
     #[tokio::test]
     async fn test_create_cart_with_customer() {
-        let mut app_service = setup_service().await;
-        let customer_id = Some(create_test_customer(&mut app_service).await);
-        let cart_data = r#"{"items": []}"#.to_string();
+        let app_service = setup_service().await;
+        let customer_id = Some(create_test_customer(&app_service).await);
 
         let command = CreateCartCommand {
             cart: CartNewInput {
                 customer_id,
-                cart_data: cart_data.clone(),
+                discount_id: None,
+                notes: Some("Table 4".to_string()),
+                terminal_id: None,
+                location_id: None,
             },
         };
 
-        let result = command.exec(&mut app_service).await.unwrap();
+        let result = command.exec(&app_service).await.unwrap();
 
         assert_eq!(result.customer_id, customer_id);
-        assert_eq!(result.cart_data, cart_data);
+        assert_eq!(result.notes, Some("Table 4".to_string()));
+        assert_eq!(result.legacy_cart_data, None);
         assert!(result.created_at <= Utc::now().naive_utc());
         assert_eq!(result.created_at, result.updated_at);
     }
 
     #[tokio::test]
     async fn test_create_cart_without_customer() {
-        let mut app_service = setup_service().await;
-        let cart_data = r#"{"items": []}"#.to_string();
+        let app_service = setup_service().await;
 
-        let command = CreateCartCommand {
-            cart: CartNewInput {
-                customer_id: None,
-                cart_data: cart_data.clone(),
-            },
-        };
-
-        let result = command.exec(&mut app_service).await.unwrap();
+        let result = create_test_cart(&app_service).await;
 
         assert_eq!(result.customer_id, None);
-        assert_eq!(result.cart_data, cart_data);
+        assert_eq!(result.notes, None);
         assert!(result.created_at <= Utc::now().naive_utc());
         assert_eq!(result.created_at, result.updated_at);
     }
 
     #[tokio::test]
     async fn test_update_cart() {
-        let mut app_service = setup_service().await;
+        let app_service = setup_service().await;
+        let customer_id = create_test_customer(&app_service).await;
 
         // First create a cart without customer
-        let initial_cart_data = r#"{"items": []}"#.to_string();
-        let create_command = CreateCartCommand {
-            cart: CartNewInput {
-                customer_id: None,
-                cart_data: initial_cart_data,
-            },
-        };
-        let created_cart = create_command.exec(&mut app_service).await.unwrap();
+        let created_cart = create_test_cart(&app_service).await;
 
         // Then update it
-        let updated_cart_data = r#"{"items": [{"id": "123", "quantity": 1}]}"#.to_string();
         let update_command = UpdateCartCommand {
             cart: CartUpdateInput {
                 id: created_cart.id,
-                cart_data: Some(updated_cart_data.clone()),
+                customer_id: Some(Some(customer_id)),
+                discount_id: None,
+                notes: Some(Some("Deliver after 5pm".to_string())),
             },
         };
 
-        let result = update_command.exec(&mut app_service).await.unwrap();
+        let result = update_command.exec(&app_service).await.unwrap();
 
         assert_eq!(result.id, created_cart.id);
-        assert_eq!(result.cart_data, updated_cart_data);
+        assert_eq!(result.customer_id, Some(customer_id));
+        assert_eq!(result.notes, Some("Deliver after 5pm".to_string()));
     }
 
     #[tokio::test]
     async fn test_update_cart_does_not_exist() {
-        let mut app_service = setup_service().await;
+        let app_service = setup_service().await;
 
         let nonexistent_id = Uuid::now_v7().into();
         let command = UpdateCartCommand {
             cart: CartUpdateInput {
                 id: nonexistent_id,
-                cart_data: Some("{}".to_string()),
+                customer_id: None,
+                discount_id: None,
+                notes: Some(None),
             },
         };
 
-        let result = command.exec(&mut app_service).await;
+        let result = command.exec(&app_service).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_delete_cart() {
-        let mut app_service = setup_service().await;
+        let app_service = setup_service().await;
 
         // First create a cart
-        let create_command = CreateCartCommand {
-            cart: CartNewInput {
-                customer_id: None,
-                cart_data: "{}".to_string(),
-            },
-        };
-        let created_cart = create_command.exec(&mut app_service).await.unwrap();
+        let created_cart = create_test_cart(&app_service).await;
 
         // Then delete it
         let delete_command = DeleteCartCommand {
             id: created_cart.id,
         };
 
-        let result = delete_command.exec(&mut app_service).await.unwrap();
+        let result = delete_command.exec(&app_service).await.unwrap();
         assert_eq!(result, 1); // Should return 1 for successful deletion
 
         // Verify deletion by checking if cart exists
@@ -286,12 +1332,452 @@ mod tests {
 
     #[tokio::test]
     async fn test_delete_cart_does_not_exist() {
-        let mut app_service = setup_service().await;
+        let app_service = setup_service().await;
 
         let nonexistent_id = Uuid::now_v7().into();
         let command = DeleteCartCommand { id: nonexistent_id };
 
-        let result = command.exec(&mut app_service).await;
+        let result = command.exec(&app_service).await;
         assert!(matches!(result.unwrap_err(), Error::NotFoundError));
     }
+
+    #[tokio::test]
+    async fn test_cart_lines() {
+        let service = setup_service().await;
+        let cart = create_test_cart(&service).await;
+        let item_id = create_test_item(&service, "10", None).await;
+
+        let line = add_line(&service, cart.id, item_id, 2).await;
+        assert_eq!(line.cart_id, cart.id);
+        assert_eq!(line.quantity, 2);
+
+        // Quantities have to be positive
+        let result = AddCartLineCommand {
+            line: CartLineNewInput {
+                cart_id: cart.id,
+                item_id,
+                variant_id: None,
+                quantity: 0,
+                discount_id: None,
+                note: None,
+                price_override: None,
+            },
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+
+        let updated = UpdateCartLineCommand {
+            line: CartLineUpdateInput {
+                id: line.id,
+                quantity: Some(5),
+                discount_id: None,
+                note: Some(Some("No onions".to_string())),
+                price_override: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        assert_eq!(updated.quantity, 5);
+        assert_eq!(updated.note, Some("No onions".to_string()));
+
+        let second = add_line(&service, cart.id, item_id, 1).await;
+        let lines = cart_lines(&service, cart.id).await.unwrap();
+        assert_eq!(lines.iter().map(|line| line.id).collect::<Vec<_>>(), vec![line.id, second.id]);
+
+        RemoveCartLineCommand { id: line.id }.exec(&service).await.unwrap();
+        let lines = cart_lines(&service, cart.id).await.unwrap();
+        assert_eq!(lines.len(), 1);
+
+        let result = RemoveCartLineCommand { id: line.id }.exec(&service).await;
+        assert!(matches!(result, Err(Error::NotFoundError)));
+    }
+
+    #[tokio::test]
+    async fn test_cart_line_override_needs_approval() {
+        let service = setup_service().await;
+        let cart = create_test_cart(&service).await;
+        let item_id = create_test_item(&service, "10", None).await;
+        let supervisor_id = AddUserCommand {
+            user: UserNewInput {
+                username: format!("supervisor{}", rand::thread_rng().gen_range(1000..9999)),
+                pin_hash: "4321".to_string(),
+                full_name: "Supervisor".to_string(),
+                state: UserState::Active,
+                role: UserRole::Supervisor,
+                last_login_at: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap()
+        .id;
+        let reason = CreatePriceOverrideReasonCommand {
+            reason: PriceOverrideReasonNewInput {
+                code: "MATCH".to_string(),
+                name: "Price match".to_string(),
+                description: None,
+                requires_approval: Some(true),
+                is_active: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let line_input = |approval_pin: Option<&str>| CartLineNewInput {
+            cart_id: cart.id,
+            item_id,
+            variant_id: None,
+            quantity: 1,
+            discount_id: None,
+            note: None,
+            price_override: Some(CartLineOverrideInput {
+                price: Money::from(800),
+                reason_id: reason.id,
+                note: None,
+                approved_by: approval_pin.map(|_| supervisor_id),
+                approval_pin: approval_pin.map(|pin| pin.to_string()),
+            }),
+        };
+
+        let result = AddCartLineCommand { line: line_input(None) }.exec(&service).await;
+        assert!(matches!(result, Err(Error::AuthorizationError)));
+
+        let line = AddCartLineCommand { line: line_input(Some("4321")) }.exec(&service).await.unwrap();
+        assert_eq!(line.override_price, Some(Money::from(800)));
+        assert_eq!(line.override_approved_by, Some(supervisor_id));
+
+        // The supervisor cannot ring up the sale they approved
+        let checkout = checkout_input(&service, cart.id).await;
+        let result = CheckoutCartCommand {
+            checkout: checkout.clone(),
+            created_by_user_id: supervisor_id,
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(result, Err(Error::AuthorizationError)));
+
+        // Checkout keeps the approved price without asking for the PIN again
+        let order = CheckoutCartCommand {
+            checkout,
+            created_by_user_id: create_test_user(&service).await,
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        assert_eq!(order.total_amount, Money::from(800));
+
+        let mut item_query = Query::select();
+        let item_stmt = item_query
+            .from(SalesOrderItems::Table)
+            .columns(SalesOrderItems::all_columns())
+            .and_where(Expr::col(SalesOrderItems::OrderId).eq(order.id.to_string()));
+        let order_item = service.db_adapter.query_one::<SalesOrderItem>(&item_stmt).await.unwrap();
+        assert_eq!(order_item.original_price_amount, Some(Money::from(1000)));
+        assert_eq!(order_item.override_approved_by, Some(supervisor_id));
+    }
+
+    #[tokio::test]
+    async fn test_checkout_cart() {
+        let service = setup_service().await;
+        let now = Utc::now().naive_utc();
+        let customer_id = create_test_customer(&service).await;
+        let taxed_item = create_test_item(&service, "30", Some("10")).await;
+        let plain_item = create_test_item(&service, "25", None).await;
+
+        // 10.00 off the whole cart
+        let discount = CreateDiscountCommand {
+            discount: DiscountNewInput {
+                name: format!("Ten Off {}", rand::thread_rng().gen_range(1..99999)),
+                description: None,
+                discount_type: DiscountType::FixedAmount,
+                value: Money::from_str("10").unwrap(),
+                scope: DiscountScope::AllItems,
+                state: Some(DiscountState::Active),
+                start_date: Some(now - Duration::days(1)),
+                end_date: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let cart = CreateCartCommand {
+            cart: CartNewInput {
+                customer_id: Some(customer_id),
+                discount_id: Some(discount.id),
+                notes: Some("Gift wrap".to_string()),
+                terminal_id: None,
+                location_id: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        add_line(&service, cart.id, taxed_item, 2).await;
+        add_line(&service, cart.id, plain_item, 1).await;
+
+        let charge_type = CreateSalesChargeTypeCommand {
+            charge_type: SalesChargeTypeNewInput {
+                name: "Delivery".to_string(),
+                description: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        AddCartChargeCommand {
+            charge: CartChargeNewInput {
+                cart_id: cart.id,
+                charge_type_id: charge_type.id,
+                amount: Money::from_str("5").unwrap(),
+                tax_group_id: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let checkout = checkout_input(&service, cart.id).await;
+        let order = CheckoutCartCommand {
+            checkout,
+            created_by_user_id: create_test_user(&service).await,
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        // Lines worth 60.00 and 25.00 share the 10.00 discount as 7.06 and 2.94;
+        // tax is 10% of the 52.94 left on the taxed line
+        assert_eq!(order.net_amount, Money::from_str("85").unwrap());
+        assert_eq!(order.disc_amount, Money::from_str("10").unwrap());
+        assert_eq!(order.taxable_amount, Money::from_str("75").unwrap());
+        assert_eq!(order.tax_amount, Money::from_str("5.29").unwrap());
+        assert_eq!(order.total_amount, Money::from_str("85.29").unwrap());
+        assert_eq!(order.customer_id, Some(customer_id));
+        assert!(order.customer_name.is_some());
+        assert_eq!(order.notes, Some("Gift wrap".to_string()));
+        assert_eq!(order.discount_id, Some(discount.id));
+
+        // The cart is gone once it becomes an order
+        let result = load_cart(&service, cart.id).await;
+        assert!(matches!(result, Err(Error::NotFoundError)));
+    }
+
+    #[tokio::test]
+    async fn test_checkout_publishes_order_and_stock_events() {
+        let service = setup_service().await;
+        let item_id = create_test_item(&service, "12", None).await;
+        let cart = create_test_cart(&service).await;
+        add_line(&service, cart.id, item_id, 3).await;
+
+        let mut events = service.events.subscribe();
+        let checkout = checkout_input(&service, cart.id).await;
+        let order = CheckoutCartCommand {
+            checkout,
+            created_by_user_id: create_test_user(&service).await,
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        match events.recv().await.unwrap() {
+            Event::OrderCreated(created) => assert_eq!(created.id, order.id),
+            event => panic!("Unexpected event {:?}", event),
+        }
+        match events.recv().await.unwrap() {
+            Event::StockChanged(change) => {
+                assert_eq!(change.item_id, item_id);
+                assert_eq!(change.order_id, order.id);
+                assert_eq!(change.quantity, -3);
+            }
+            event => panic!("Unexpected event {:?}", event),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_checkout_empty_cart() {
+        let service = setup_service().await;
+        let cart = create_test_cart(&service).await;
+
+        let checkout = checkout_input(&service, cart.id).await;
+        let result = CheckoutCartCommand {
+            checkout,
+            created_by_user_id: Uuid::nil().into(),
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    async fn create_test_location(service: &AppService) -> DbUuid {
+        CreateLocationCommand {
+            location: LocationNewInput {
+                name: format!("Test Location {}", rand::thread_rng().gen_range(1..99999)),
+                description: None,
+                address: None,
+                is_active: Some(true),
+            },
+        }
+        .exec(service)
+        .await
+        .unwrap()
+        .id
+    }
+
+    fn park_input(cart_id: DbUuid, terminal_id: &str, location_id: DbUuid) -> ParkCartInput {
+        ParkCartInput {
+            id: cart_id,
+            terminal_id: terminal_id.to_string(),
+            location_id,
+            label: Some("Blue jacket".to_string()),
+            hold_reason: Some("Fetching wallet".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_park_and_recall_cart() {
+        let service = setup_service().await;
+        let location_id = create_test_location(&service).await;
+        let item_id = create_test_item(&service, "10", None).await;
+        let cart = create_test_cart(&service).await;
+        let line = add_line(&service, cart.id, item_id, 1).await;
+        let cashier_id: DbUuid = Uuid::now_v7().into();
+
+        let parked = ParkCartCommand {
+            cart: park_input(cart.id, "till-1", location_id),
+            parked_by_user_id: cashier_id,
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        assert_eq!(parked.status, CartStatus::Parked);
+        assert_eq!(parked.label, Some("Blue jacket".to_string()));
+        assert_eq!(parked.hold_reason, Some("Fetching wallet".to_string()));
+        assert_eq!(parked.parked_by, Some(cashier_id));
+        assert_eq!(parked.terminal_id, Some("till-1".to_string()));
+        assert_eq!(parked.location_id, Some(location_id));
+        assert!(parked.held_since.is_some());
+
+        // A parked cart cannot be changed
+        let result = RemoveCartLineCommand { id: line.id }.exec(&service).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+
+        // Another terminal recalls it
+        let recalled = RecallCartCommand {
+            cart: RecallCartInput {
+                id: cart.id,
+                terminal_id: "till-2".to_string(),
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        assert_eq!(recalled.status, CartStatus::Open);
+        assert_eq!(recalled.terminal_id, Some("till-2".to_string()));
+        assert_eq!(recalled.held_since, None);
+
+        // Only one terminal gets it
+        let result = RecallCartCommand {
+            cart: RecallCartInput {
+                id: cart.id,
+                terminal_id: "till-1".to_string(),
+            },
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+
+        // And the first terminal cannot park it while it is open on the other one
+        let result = ParkCartCommand {
+            cart: park_input(cart.id, "till-1", location_id),
+            parked_by_user_id: cashier_id,
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_park_empty_cart() {
+        let service = setup_service().await;
+        let location_id = create_test_location(&service).await;
+        let cart = create_test_cart(&service).await;
+
+        let result = ParkCartCommand {
+            cart: park_input(cart.id, "till-1", location_id),
+            parked_by_user_id: Uuid::now_v7().into(),
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_purge_abandoned_carts() {
+        let service = setup_service().await;
+        let item_id = create_test_item(&service, "10", None).await;
+        let recent = create_test_cart(&service).await;
+
+        // Adding a line touches the cart, so the stale carts are written directly
+        let abandoned_id: DbUuid = Uuid::now_v7().into();
+        let parked_id: DbUuid = Uuid::now_v7().into();
+        let three_days_ago = (Utc::now().naive_utc() - Duration::days(3)).to_string();
+        let mut insert_query = Query::insert();
+        let insert_stmt = insert_query
+            .into_table(Carts::Table)
+            .columns([Carts::Id, Carts::Status, Carts::CreatedAt, Carts::UpdatedAt])
+            .values_panic([
+                abandoned_id.to_string().into(),
+                CartStatus::Open.to_string().into(),
+                three_days_ago.clone().into(),
+                three_days_ago.clone().into(),
+            ])
+            .values_panic([
+                parked_id.to_string().into(),
+                CartStatus::Parked.to_string().into(),
+                three_days_ago.clone().into(),
+                three_days_ago.into(),
+            ]);
+        service.db_adapter.insert_many(&insert_stmt).await.unwrap();
+
+        let mut line_query = Query::insert();
+        let line_stmt = line_query
+            .into_table(CartLines::Table)
+            .columns([CartLines::Id, CartLines::CartId, CartLines::ItemId, CartLines::Quantity])
+            .values_panic([
+                Uuid::now_v7().to_string().into(),
+                abandoned_id.to_string().into(),
+                item_id.to_string().into(),
+                1.into(),
+            ]);
+        service.db_adapter.insert_many(&line_stmt).await.unwrap();
+
+        let purged = PurgeAbandonedCartsCommand {
+            max_age: Duration::days(1),
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        assert_eq!(purged, 1);
+
+        assert!(matches!(load_cart(&service, abandoned_id).await, Err(Error::NotFoundError)));
+        assert!(cart_lines(&service, abandoned_id).await.unwrap().is_empty());
+        assert!(load_cart(&service, recent.id).await.is_ok());
+        assert!(load_cart(&service, parked_id).await.is_ok());
+    }
+
+    #[test]
+    fn test_purge_age_from_secret_store() {
+        let secrets = MemorySecretStore::default();
+        assert_eq!(PurgeAbandonedCartsCommand::load(&secrets).unwrap().max_age, Duration::hours(72));
+
+        secrets.set(SecretName::CartMaxAgeHours, &Secret::new("24")).unwrap();
+        assert_eq!(PurgeAbandonedCartsCommand::load(&secrets).unwrap().max_age, Duration::hours(24));
+
+        secrets.set(SecretName::CartMaxAgeHours, &Secret::new("0")).unwrap();
+        assert_eq!(PurgeAbandonedCartsCommand::load(&secrets).unwrap().max_age, Duration::hours(72));
+    }
 }
@@ -1,17 +1,21 @@
 use chrono::Utc;
-use sea_query::{Expr, Query};
+use sea_query::Query;
 use uuid::Uuid;
 
 use crate::{
     adapters::outgoing::database::DatabaseAdapter,
     core::{
         commands::{app_service::AppService, Command},
+        db::{
+            row_version::update_versioned,
+            soft_delete::{purge, restore, soft_delete},
+        },
         models::sales::customer_model::{
             Customer, CustomerNewInput, CustomerUpdateInput, Customers,
         },
         types::db_uuid::DbUuid,
     },
-    error::{Error, Result},
+    error::Result,
 };
 
 // Commands
@@ -25,13 +29,22 @@ pub struct UpdateCustomerCommand {
 
 pub struct DeleteCustomerCommand {
     pub id: DbUuid,
+    pub deleted_by: Option<DbUuid>,
+}
+
+pub struct RestoreCustomerCommand {
+    pub id: DbUuid,
+}
+
+pub struct PurgeCustomerCommand {
+    pub id: DbUuid,
 }
 
 // Command Implementations
 impl Command for CreateCustomerCommand {
     type Output = Customer;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let new_id = Uuid::now_v7();
 
@@ -41,8 +54,12 @@ impl Command for CreateCustomerCommand {
             email: self.customer.email.clone(),
             phone: self.customer.phone.clone(),
             address: self.customer.address.clone(),
+            customer_group_id: self.customer.customer_group_id,
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
+            version: 1,
         };
 
         // Build the insert query with SeaQuery
@@ -55,6 +72,7 @@ impl Command for CreateCustomerCommand {
                 Customers::Email,
                 Customers::Phone,
                 Customers::Address,
+                Customers::CustomerGroupId,
                 Customers::CreatedAt,
                 Customers::UpdatedAt,
             ])
@@ -64,6 +82,7 @@ impl Command for CreateCustomerCommand {
                 self.customer.email.clone().into(),
                 self.customer.phone.clone().into(),
                 self.customer.address.clone().into(),
+                self.customer.customer_group_id.map(|id| id.to_string()).into(),
                 now.to_string().into(),
                 now.to_string().into(),
             ]);
@@ -79,31 +98,10 @@ impl Command for CreateCustomerCommand {
 impl Command for UpdateCustomerCommand {
     type Output = Customer;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let customer_id = self.customer.id;
 
-        // First, check if the customer exists
-        let mut select_query = Query::select();
-        let select_stmt = select_query
-            .from(Customers::Table)
-            .columns([
-                Customers::Id,
-                Customers::FullName,
-                Customers::Email,
-                Customers::Phone,
-                Customers::Address,
-                Customers::CreatedAt,
-                Customers::UpdatedAt,
-            ])
-            .and_where(Expr::col(Customers::Id).eq(customer_id.to_string()));
-
-        let existing = service.db_adapter.query_optional::<Customer>(&select_stmt).await?;
-
-        if existing.is_none() {
-            return Err(Error::NotFoundError);
-        }
-
         // Build the update query with SeaQuery
         let mut update_query = Query::update();
         let update_stmt = update_query.table(Customers::Table);
@@ -134,46 +132,51 @@ impl Command for UpdateCustomerCommand {
             };
         }
 
+        if let Some(customer_group_id) = &self.customer.customer_group_id {
+            match customer_group_id {
+                Some(id) => update_stmt.value(Customers::CustomerGroupId, id.to_string()),
+                None => update_stmt.value(Customers::CustomerGroupId, sea_query::Value::String(None)),
+            };
+        }
+
         // Always update the updated_at timestamp
         update_stmt.value(Customers::UpdatedAt, now.to_string());
 
-        // Add the WHERE clause
-        update_stmt.and_where(Expr::col(Customers::Id).eq(customer_id.to_string()));
+        update_versioned(&service.db_adapter, update_stmt, customer_id, self.customer.version).await
+    }
+}
 
-        // Execute the query
-        service.db_adapter.update_one::<Customer>(&update_stmt).await?;
+impl Command for DeleteCustomerCommand {
+    type Output = i32;
 
-        // Get the updated customer
-        let updated_customer = service.db_adapter.query_one::<Customer>(&select_stmt).await?;
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        soft_delete::<Customer, _>(&service.db_adapter, self.id, self.deleted_by).await?;
 
-        Ok(updated_customer)
+        Ok(1)
     }
 }
 
-impl Command for DeleteCustomerCommand {
-    type Output = i32;
+impl Command for RestoreCustomerCommand {
+    type Output = Customer;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        // Build the delete query with SeaQuery
-        let mut delete_query = Query::delete();
-        let delete_stmt = delete_query
-            .from_table(Customers::Table)
-            .and_where(Expr::col(Customers::Id).eq(self.id.to_string()));
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        restore(&service.db_adapter, self.id).await
+    }
+}
 
-        // Execute the query
-        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+impl Command for PurgeCustomerCommand {
+    type Output = i32;
 
-        if affected_rows == 0 {
-            return Err(Error::NotFoundError);
-        }
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        purge::<Customer, _>(&service.db_adapter, self.id).await?;
 
-        Ok(affected_rows as i32)
+        Ok(1)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::core::commands::tests::setup_service;
+    use crate::{core::commands::tests::setup_service, error::Error};
 
     use super::*;
     use uuid::Uuid;
@@ -182,17 +185,18 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_customer() {
-        let mut app_service = setup_service().await;
+        let app_service = setup_service().await;
         let new_customer = CustomerNewInput {
             full_name: String::from("John Doe"),
             email: Some(String::from("john@example.com")),
             phone: Some(String::from("+1234567890")),
             address: Some(String::from("123 Main St")),
+            customer_group_id: None,
         };
         let command = CreateCustomerCommand {
             customer: new_customer,
         };
-        let result = command.exec(&mut app_service).await;
+        let result = command.exec(&app_service).await;
 
         assert!(result.is_ok());
         let customer = result.unwrap();
@@ -202,31 +206,34 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_customer() {
-        let mut app_service = setup_service().await;
+        let app_service = setup_service().await;
         let new_customer = CustomerNewInput {
             full_name: String::from("John Doe"),
             email: Some(String::from("john@example.com")),
             phone: Some(String::from("+1234567890")),
             address: Some(String::from("123 Main St")),
+            customer_group_id: None,
         };
 
         let create_command = CreateCustomerCommand {
             customer: new_customer,
         };
-        let customer = create_command.exec(&mut app_service).await.unwrap();
+        let customer = create_command.exec(&app_service).await.unwrap();
 
         let updated_customer = CustomerUpdateInput {
             id: customer.id,
+            version: customer.version,
             full_name: Some(String::from("John Smith")),
             email: Some(None),
             phone: None,
             address: Some(Some(String::from("456 Oak Ave"))),
+            customer_group_id: None,
         };
 
         let update_command = UpdateCustomerCommand {
             customer: updated_customer,
         };
-        let result = update_command.exec(&mut app_service).await;
+        let result = update_command.exec(&app_service).await;
         assert!(result.is_ok());
         let updated = result.unwrap();
         assert_eq!(updated.full_name, "John Smith");
@@ -236,45 +243,87 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_customer_does_not_exist() {
-        let mut app_service = setup_service().await;
+        let app_service = setup_service().await;
         let customer = CustomerUpdateInput {
             id: Uuid::now_v7().into(),
+            version: 1,
             full_name: Some(String::from("John Smith")),
             email: None,
             phone: None,
             address: None,
+            customer_group_id: None,
         };
 
         let command = UpdateCustomerCommand { customer };
-        let result = command.exec(&mut app_service).await;
+        let result = command.exec(&app_service).await;
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_update_customer_from_stale_version() {
+        let app_service = setup_service().await;
+        let create_command = CreateCustomerCommand {
+            customer: CustomerNewInput {
+                full_name: String::from("John Doe"),
+                email: None,
+                phone: None,
+                address: None,
+                customer_group_id: None,
+            },
+        };
+        let customer = create_command.exec(&app_service).await.unwrap();
+
+        let update = |phone: &str| CustomerUpdateInput {
+            id: customer.id,
+            version: customer.version,
+            full_name: None,
+            email: None,
+            phone: Some(Some(phone.to_string())),
+            address: None,
+            customer_group_id: None,
+        };
+
+        UpdateCustomerCommand { customer: update("+111") }.exec(&app_service).await.unwrap();
+        let result = UpdateCustomerCommand { customer: update("+222") }.exec(&app_service).await;
+
+        match result {
+            Err(Error::ConflictError(conflict)) => {
+                assert_eq!(conflict.table, "customers");
+                assert_eq!(conflict.current["fullName"], "John Doe");
+                assert_eq!(conflict.current["phone"], "+111");
+                assert_eq!(conflict.current["version"], customer.version + 1);
+            }
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+    }
+
     #[tokio::test]
     async fn test_delete_customer() {
-        let mut app_service = setup_service().await;
+        let app_service = setup_service().await;
         let new_customer = CustomerNewInput {
             full_name: String::from("John Doe"),
             email: Some(String::from("john@example.com")),
             phone: Some(String::from("+1234567890")),
             address: Some(String::from("123 Main St")),
+            customer_group_id: None,
         };
 
         let create_command = CreateCustomerCommand {
             customer: new_customer,
         };
-        let customer = create_command.exec(&mut app_service).await.unwrap();
+        let customer = create_command.exec(&app_service).await.unwrap();
 
-        let delete_command = DeleteCustomerCommand { id: customer.id };
-        let result = delete_command.exec(&mut app_service).await;
+        let delete_command = DeleteCustomerCommand { id: customer.id, deleted_by: None };
+        let result = delete_command.exec(&app_service).await;
         assert!(result.is_ok());
 
-        // Verify customer no longer exists
+        // Verify customer is no longer listed
         let mut count_query = Query::select();
         let count_stmt = count_query
             .from(Customers::Table)
             .expr_as(Expr::col(Customers::Id).count(), Alias::new("count"))
-            .and_where(Expr::col(Customers::Id).eq(customer.id.to_string()));
+            .and_where(Expr::col(Customers::Id).eq(customer.id.to_string()))
+            .and_where(Expr::col(Customers::DeletedAt).is_null());
 
         let count = app_service.db_adapter.query_one::<i64>(&count_stmt).await.unwrap();
         assert_eq!(count, 0);
@@ -282,11 +331,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_delete_customer_does_not_exist() {
-        let mut app_service = setup_service().await;
+        let app_service = setup_service().await;
         let command = DeleteCustomerCommand {
             id: Uuid::now_v7().into(),
+            deleted_by: None,
         };
-        let result = command.exec(&mut app_service).await;
+        let result = command.exec(&app_service).await;
         assert!(result.is_err());
     }
 }
@@ -0,0 +1,215 @@
+use chrono::Utc;
+use sea_query::{Expr, Query};
+use uuid::Uuid;
+
+use crate::{
+    adapters::outgoing::database::DatabaseAdapter,
+    core::{
+        commands::{app_service::AppService, Command},
+        models::sales::customer_group_model::{
+            CustomerGroup, CustomerGroupNewInput, CustomerGroupUpdateInput, CustomerGroups,
+        },
+        types::db_uuid::DbUuid,
+    },
+    error::{Constraint, Error, Result},
+};
+
+// Commands
+pub struct CreateCustomerGroupCommand {
+    pub group: CustomerGroupNewInput,
+}
+
+pub struct UpdateCustomerGroupCommand {
+    pub group: CustomerGroupUpdateInput,
+}
+
+pub struct DeleteCustomerGroupCommand {
+    pub id: DbUuid,
+}
+
+// Command Implementations
+impl Command for CreateCustomerGroupCommand {
+    type Output = CustomerGroup;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let mut check_query = Query::select();
+        let check_stmt = check_query
+            .from(CustomerGroups::Table)
+            .column(CustomerGroups::Id)
+            .and_where(Expr::col(CustomerGroups::Name).eq(self.group.name.clone()));
+
+        let existing = service.db_adapter.query_optional::<DbUuid>(&check_stmt).await?;
+        if existing.is_some() {
+            return Err(Error::UniqueConstraintError(Constraint::new("customer_groups", "name")));
+        }
+
+        let now = Utc::now().naive_utc();
+        let new_group = CustomerGroup {
+            id: Uuid::now_v7().into(),
+            name: self.group.name.clone(),
+            description: self.group.description.clone(),
+            created_at: now,
+            updated_at: now,
+        };
+
+        // Build the insert query with SeaQuery
+        let mut insert_query = Query::insert();
+        let insert_stmt = insert_query
+            .into_table(CustomerGroups::Table)
+            .columns([
+                CustomerGroups::Id,
+                CustomerGroups::Name,
+                CustomerGroups::Description,
+                CustomerGroups::CreatedAt,
+                CustomerGroups::UpdatedAt,
+            ])
+            .values_panic([
+                new_group.id.to_string().into(),
+                new_group.name.clone().into(),
+                new_group.description.clone().into(),
+                now.to_string().into(),
+                now.to_string().into(),
+            ]);
+
+        service.db_adapter.insert_many(&insert_stmt).await?;
+
+        Ok(new_group)
+    }
+}
+
+impl Command for UpdateCustomerGroupCommand {
+    type Output = CustomerGroup;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let now = Utc::now().naive_utc();
+        let group_id = self.group.id;
+
+        // First, check if the group exists
+        let mut select_query = Query::select();
+        let select_stmt = select_query
+            .from(CustomerGroups::Table)
+            .columns(CustomerGroups::all_columns())
+            .and_where(Expr::col(CustomerGroups::Id).eq(group_id.to_string()));
+
+        let existing = service.db_adapter.query_optional::<CustomerGroup>(&select_stmt).await?;
+
+        if existing.is_none() {
+            return Err(Error::NotFoundError);
+        }
+
+        // Build the update query with SeaQuery
+        let mut update_query = Query::update();
+        let update_stmt = update_query.table(CustomerGroups::Table);
+
+        if let Some(name) = &self.group.name {
+            update_stmt.value(CustomerGroups::Name, name.clone());
+        }
+
+        if let Some(description) = &self.group.description {
+            match description {
+                Some(desc) => update_stmt.value(CustomerGroups::Description, desc.clone()),
+                None => update_stmt.value(CustomerGroups::Description, sea_query::Value::String(None)),
+            };
+        }
+
+        // Always update the updated_at timestamp
+        update_stmt.value(CustomerGroups::UpdatedAt, now.to_string());
+
+        update_stmt.and_where(Expr::col(CustomerGroups::Id).eq(group_id.to_string()));
+
+        service.db_adapter.update_one::<CustomerGroup>(&update_stmt).await?;
+
+        let updated_group = service.db_adapter.query_one::<CustomerGroup>(&select_stmt).await?;
+
+        Ok(updated_group)
+    }
+}
+
+impl Command for DeleteCustomerGroupCommand {
+    type Output = bool;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        // Customers in the group fall back to no group through ON DELETE SET NULL
+        let mut delete_query = Query::delete();
+        let delete_stmt = delete_query
+            .from_table(CustomerGroups::Table)
+            .and_where(Expr::col(CustomerGroups::Id).eq(self.id.to_string()));
+
+        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+
+        Ok(affected_rows > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::commands::tests::setup_service;
+
+    fn wholesale_input() -> CustomerGroupNewInput {
+        CustomerGroupNewInput {
+            name: "Wholesale".to_string(),
+            description: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_customer_group() {
+        let service = setup_service().await;
+
+        let cmd = CreateCustomerGroupCommand { group: wholesale_input() };
+        let result = cmd.exec(&service).await.unwrap();
+
+        assert_eq!(result.name, "Wholesale");
+        assert_eq!(result.description, None);
+    }
+
+    #[tokio::test]
+    async fn test_create_duplicate_customer_group() {
+        let service = setup_service().await;
+
+        let cmd = CreateCustomerGroupCommand { group: wholesale_input() };
+        cmd.exec(&service).await.unwrap();
+        let result = cmd.exec(&service).await;
+
+        assert!(matches!(result, Err(Error::UniqueConstraintError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_customer_group() {
+        let service = setup_service().await;
+
+        let created = CreateCustomerGroupCommand { group: wholesale_input() }
+            .exec(&service)
+            .await
+            .unwrap();
+
+        let updated = UpdateCustomerGroupCommand {
+            group: CustomerGroupUpdateInput {
+                id: created.id,
+                name: Some("Trade".to_string()),
+                description: Some(Some("Registered trade accounts".to_string())),
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        assert_eq!(updated.name, "Trade");
+        assert_eq!(updated.description, Some("Registered trade accounts".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_delete_customer_group() {
+        let service = setup_service().await;
+
+        let created = CreateCustomerGroupCommand { group: wholesale_input() }
+            .exec(&service)
+            .await
+            .unwrap();
+
+        let delete_cmd = DeleteCustomerGroupCommand { id: created.id };
+        assert!(delete_cmd.exec(&service).await.unwrap());
+        assert!(!delete_cmd.exec(&service).await.unwrap());
+    }
+}
@@ -1,4 +1,6 @@
 pub mod cart_commands;
+pub mod customer_group_commands;
 pub mod customer_commands;
+pub mod price_override_reason_commands;
 pub mod sales_charge_type_commands;
 pub mod sales_order_commands;
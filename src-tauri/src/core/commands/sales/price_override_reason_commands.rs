@@ -0,0 +1,281 @@
+use chrono::Utc;
+use sea_query::{Alias, Expr, Query};
+use uuid::Uuid;
+
+use crate::{
+    adapters::outgoing::database::DatabaseAdapter,
+    core::{
+        commands::{app_service::AppService, Command},
+        models::sales::{
+            price_override_reason_model::{
+                PriceOverrideReason, PriceOverrideReasonNewInput, PriceOverrideReasonUpdateInput,
+                PriceOverrideReasons,
+            },
+            sales_order_item_model::SalesOrderItems,
+        },
+        types::db_uuid::DbUuid,
+    },
+    error::{Constraint, Error, Result},
+};
+
+// Commands
+pub struct CreatePriceOverrideReasonCommand {
+    pub reason: PriceOverrideReasonNewInput,
+}
+
+pub struct UpdatePriceOverrideReasonCommand {
+    pub reason: PriceOverrideReasonUpdateInput,
+}
+
+pub struct DeletePriceOverrideReasonCommand {
+    pub id: DbUuid,
+}
+
+// Command Implementations
+impl Command for CreatePriceOverrideReasonCommand {
+    type Output = PriceOverrideReason;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        // Reason codes are what cashiers pick from, so they must be unique
+        let mut check_query = Query::select();
+        let check_stmt = check_query
+            .from(PriceOverrideReasons::Table)
+            .column(PriceOverrideReasons::Id)
+            .and_where(Expr::col(PriceOverrideReasons::Code).eq(self.reason.code.clone()));
+
+        let existing = service.db_adapter.query_optional::<DbUuid>(&check_stmt).await?;
+        if existing.is_some() {
+            return Err(Error::UniqueConstraintError(Constraint::new("price_override_reasons", "code")));
+        }
+
+        let now = Utc::now().naive_utc();
+        let new_reason = PriceOverrideReason {
+            id: Uuid::now_v7().into(),
+            code: self.reason.code.clone(),
+            name: self.reason.name.clone(),
+            description: self.reason.description.clone(),
+            requires_approval: self.reason.requires_approval.unwrap_or(false),
+            is_active: self.reason.is_active.unwrap_or(true),
+            created_at: now,
+            updated_at: now,
+        };
+
+        // Build the insert query with SeaQuery
+        let mut insert_query = Query::insert();
+        let insert_stmt = insert_query
+            .into_table(PriceOverrideReasons::Table)
+            .columns([
+                PriceOverrideReasons::Id,
+                PriceOverrideReasons::Code,
+                PriceOverrideReasons::Name,
+                PriceOverrideReasons::Description,
+                PriceOverrideReasons::RequiresApproval,
+                PriceOverrideReasons::IsActive,
+                PriceOverrideReasons::CreatedAt,
+                PriceOverrideReasons::UpdatedAt,
+            ])
+            .values_panic([
+                new_reason.id.to_string().into(),
+                new_reason.code.clone().into(),
+                new_reason.name.clone().into(),
+                new_reason.description.clone().into(),
+                new_reason.requires_approval.to_string().into(),
+                new_reason.is_active.to_string().into(),
+                now.to_string().into(),
+                now.to_string().into(),
+            ]);
+
+        service.db_adapter.insert_many(&insert_stmt).await?;
+
+        Ok(new_reason)
+    }
+}
+
+impl Command for UpdatePriceOverrideReasonCommand {
+    type Output = PriceOverrideReason;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let now = Utc::now().naive_utc();
+        let reason_id = self.reason.id;
+
+        // First, check if the reason exists
+        let mut select_query = Query::select();
+        let select_stmt = select_query
+            .from(PriceOverrideReasons::Table)
+            .columns(PriceOverrideReasons::all_columns())
+            .and_where(Expr::col(PriceOverrideReasons::Id).eq(reason_id.to_string()));
+
+        let existing = service.db_adapter.query_optional::<PriceOverrideReason>(&select_stmt).await?;
+
+        if existing.is_none() {
+            return Err(Error::NotFoundError);
+        }
+
+        // Build the update query with SeaQuery
+        let mut update_query = Query::update();
+        let update_stmt = update_query.table(PriceOverrideReasons::Table);
+
+        // Only set fields that are provided in the update input
+        if let Some(code) = &self.reason.code {
+            update_stmt.value(PriceOverrideReasons::Code, code.clone());
+        }
+
+        if let Some(name) = &self.reason.name {
+            update_stmt.value(PriceOverrideReasons::Name, name.clone());
+        }
+
+        if let Some(description) = &self.reason.description {
+            match description {
+                Some(desc) => update_stmt.value(PriceOverrideReasons::Description, desc.clone()),
+                None => update_stmt.value(PriceOverrideReasons::Description, sea_query::Value::String(None)),
+            };
+        }
+
+        if let Some(requires_approval) = self.reason.requires_approval {
+            update_stmt.value(PriceOverrideReasons::RequiresApproval, requires_approval.to_string());
+        }
+
+        if let Some(is_active) = self.reason.is_active {
+            update_stmt.value(PriceOverrideReasons::IsActive, is_active.to_string());
+        }
+
+        // Always update the updated_at timestamp
+        update_stmt.value(PriceOverrideReasons::UpdatedAt, now.to_string());
+
+        // Add the WHERE clause
+        update_stmt.and_where(Expr::col(PriceOverrideReasons::Id).eq(reason_id.to_string()));
+
+        service.db_adapter.update_one::<PriceOverrideReason>(&update_stmt).await?;
+
+        // Get the updated reason
+        let updated_reason = service.db_adapter.query_one::<PriceOverrideReason>(&select_stmt).await?;
+
+        Ok(updated_reason)
+    }
+}
+
+impl Command for DeletePriceOverrideReasonCommand {
+    type Output = bool;
+
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        // Reasons recorded on order lines are kept for reporting; deactivate them instead
+        let mut count_query = Query::select();
+        let count_stmt = count_query
+            .from(SalesOrderItems::Table)
+            .expr_as(Expr::col(SalesOrderItems::Id).count(), Alias::new("count"))
+            .and_where(Expr::col(SalesOrderItems::OverrideReasonId).eq(self.id.to_string()));
+
+        let count = service.db_adapter.query_one::<i64>(&count_stmt).await?;
+
+        if count > 0 {
+            return Err(Error::HasChildrenError);
+        }
+
+        // Build the delete query with SeaQuery
+        let mut delete_query = Query::delete();
+        let delete_stmt = delete_query
+            .from_table(PriceOverrideReasons::Table)
+            .and_where(Expr::col(PriceOverrideReasons::Id).eq(self.id.to_string()));
+
+        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+
+        Ok(affected_rows > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::commands::tests::setup_service;
+    use crate::core::models::sales::price_override_reason_model::PriceOverrideReasonFilter;
+
+    fn damaged_goods_input() -> PriceOverrideReasonNewInput {
+        PriceOverrideReasonNewInput {
+            code: "DAMAGED".to_string(),
+            name: "Damaged goods".to_string(),
+            description: None,
+            requires_approval: Some(true),
+            is_active: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_price_override_reason() {
+        let service = setup_service().await;
+
+        let cmd = CreatePriceOverrideReasonCommand { reason: damaged_goods_input() };
+        let result = cmd.exec(&service).await.unwrap();
+
+        assert_eq!(result.code, "DAMAGED");
+        assert!(result.requires_approval);
+        assert!(result.is_active);
+    }
+
+    #[tokio::test]
+    async fn test_create_duplicate_price_override_reason() {
+        let service = setup_service().await;
+
+        let cmd = CreatePriceOverrideReasonCommand { reason: damaged_goods_input() };
+        cmd.exec(&service).await.unwrap();
+        let result = cmd.exec(&service).await;
+
+        assert!(matches!(result, Err(Error::UniqueConstraintError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_price_override_reason() {
+        let service = setup_service().await;
+
+        let cmd = CreatePriceOverrideReasonCommand { reason: damaged_goods_input() };
+        let created = cmd.exec(&service).await.unwrap();
+
+        let update_cmd = UpdatePriceOverrideReasonCommand {
+            reason: PriceOverrideReasonUpdateInput {
+                id: created.id,
+                code: None,
+                name: Some("Damaged packaging".to_string()),
+                description: Some(Some("Box is dented".to_string())),
+                requires_approval: Some(false),
+                is_active: Some(false),
+            },
+        };
+        let updated = update_cmd.exec(&service).await.unwrap();
+
+        assert_eq!(updated.code, "DAMAGED");
+        assert_eq!(updated.name, "Damaged packaging");
+        assert_eq!(updated.description, Some("Box is dented".to_string()));
+        assert!(!updated.requires_approval);
+        assert!(!updated.is_active);
+    }
+
+    #[tokio::test]
+    async fn test_filter_active_price_override_reasons() {
+        let service = setup_service().await;
+
+        let cmd = CreatePriceOverrideReasonCommand { reason: damaged_goods_input() };
+        cmd.exec(&service).await.unwrap();
+        let mut inactive = damaged_goods_input();
+        inactive.code = "EXPIRED".to_string();
+        inactive.is_active = Some(false);
+        CreatePriceOverrideReasonCommand { reason: inactive }.exec(&service).await.unwrap();
+
+        let filter = PriceOverrideReasonFilter { is_active: Some(true), ..Default::default() };
+        let query = PriceOverrideReasons::list(Some(&filter), None, None, None);
+        let active = service.db_adapter.query_many::<PriceOverrideReason>(&query).await.unwrap();
+
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].code, "DAMAGED");
+    }
+
+    #[tokio::test]
+    async fn test_delete_price_override_reason() {
+        let service = setup_service().await;
+
+        let cmd = CreatePriceOverrideReasonCommand { reason: damaged_goods_input() };
+        let created = cmd.exec(&service).await.unwrap();
+
+        let delete_cmd = DeletePriceOverrideReasonCommand { id: created.id };
+        assert!(delete_cmd.exec(&service).await.unwrap());
+        assert!(!delete_cmd.exec(&service).await.unwrap());
+    }
+}
@@ -1,14 +1,14 @@
 use chrono::Utc;
-use sea_query::{Alias, Expr, Query};
+use sea_query::{Expr, Query};
 use uuid::Uuid;
 
 use crate::{
     adapters::outgoing::database::DatabaseAdapter,
     core::{
         commands::{app_service::AppService, Command},
-        models::sales::{
-            sales_charge_type_model::{SalesChargeType, SalesChargeTypeNewInput, SalesChargeTypeUpdateInput, SalesChargeTypes},
-            sales_order_charge_model::SalesOrderCharges,
+        db::soft_delete::{purge, restore, soft_delete},
+        models::sales::sales_charge_type_model::{
+            SalesChargeType, SalesChargeTypeNewInput, SalesChargeTypeUpdateInput, SalesChargeTypes,
         },
         types::db_uuid::DbUuid,
     },
@@ -26,13 +26,22 @@ pub struct UpdateSalesChargeTypeCommand {
 
 pub struct DeleteSalesChargeTypeCommand {
     pub id: DbUuid,
+    pub deleted_by: Option<DbUuid>,
+}
+
+pub struct RestoreSalesChargeTypeCommand {
+    pub id: DbUuid,
+}
+
+pub struct PurgeSalesChargeTypeCommand {
+    pub id: DbUuid,
 }
 
 // Command Implementations
 impl Command for CreateSalesChargeTypeCommand {
     type Output = SalesChargeType;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let new_id = Uuid::now_v7();
 
@@ -42,6 +51,8 @@ impl Command for CreateSalesChargeTypeCommand {
             description: self.charge_type.description.clone(),
             created_at: now,
             updated_at: now,
+            deleted_at: None,
+            deleted_by: None,
         };
 
         // Build the insert query with SeaQuery
@@ -74,7 +85,7 @@ impl Command for CreateSalesChargeTypeCommand {
 impl Command for UpdateSalesChargeTypeCommand {
     type Output = SalesChargeType;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let now = Utc::now().naive_utc();
         let charge_type_id = self.charge_type.id;
 
@@ -132,30 +143,28 @@ impl Command for UpdateSalesChargeTypeCommand {
 impl Command for DeleteSalesChargeTypeCommand {
     type Output = bool;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        // Check if the charge type is used in any sales order charges
-        let mut count_query = Query::select();
-        let count_stmt = count_query
-            .from(SalesOrderCharges::Table)
-            .expr_as(Expr::col(SalesOrderCharges::Id).count(), Alias::new("count"))
-            .and_where(Expr::col(SalesOrderCharges::ChargeTypeId).eq(self.id.to_string()));
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        soft_delete::<SalesChargeType, _>(&service.db_adapter, self.id, self.deleted_by).await?;
 
-        let count = service.db_adapter.query_one::<i64>(&count_stmt).await?;
+        Ok(true)
+    }
+}
 
-        if count > 0 {
-            return Err(Error::HasChildrenError);
-        }
+impl Command for RestoreSalesChargeTypeCommand {
+    type Output = SalesChargeType;
 
-        // Build the delete query with SeaQuery
-        let mut delete_query = Query::delete();
-        let delete_stmt = delete_query
-            .from_table(SalesChargeTypes::Table)
-            .and_where(Expr::col(SalesChargeTypes::Id).eq(self.id.to_string()));
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        restore(&service.db_adapter, self.id).await
+    }
+}
 
-        // Execute the query
-        let affected_rows = service.db_adapter.delete(&delete_stmt).await?;
+impl Command for PurgeSalesChargeTypeCommand {
+    type Output = bool;
 
-        Ok(affected_rows > 0)
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        purge::<SalesChargeType, _>(&service.db_adapter, self.id).await?;
+
+        Ok(true)
     }
 }
 
@@ -163,12 +172,11 @@ impl Command for DeleteSalesChargeTypeCommand {
 mod tests {
     use super::*;
     use crate::core::commands::tests::setup_service;
-    use sea_query::{Expr, Query};
     use tokio;
 
     #[tokio::test]
     async fn test_create_sales_charge_type() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let input = SalesChargeTypeNewInput {
             name: "Service Charge".to_string(),
@@ -176,7 +184,7 @@ mod tests {
         };
 
         let cmd = CreateSalesChargeTypeCommand { charge_type: input };
-        let result = cmd.exec(&mut service).await.unwrap();
+        let result = cmd.exec(&service).await.unwrap();
 
         assert_eq!(result.name, "Service Charge");
         assert_eq!(result.description, Some("A charge for service".to_string()));
@@ -184,7 +192,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_sales_charge_type() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create first
         let input = SalesChargeTypeNewInput {
@@ -192,7 +200,7 @@ mod tests {
             description: None,
         };
         let cmd = CreateSalesChargeTypeCommand { charge_type: input };
-        let created = cmd.exec(&mut service).await.unwrap();
+        let created = cmd.exec(&service).await.unwrap();
 
         // Update
         let update_input = SalesChargeTypeUpdateInput {
@@ -203,7 +211,7 @@ mod tests {
         let update_cmd = UpdateSalesChargeTypeCommand {
             charge_type: update_input,
         };
-        let updated = update_cmd.exec(&mut service).await.unwrap();
+        let updated = update_cmd.exec(&service).await.unwrap();
 
         assert_eq!(updated.id, created.id);
         assert_eq!(updated.name, "Updated Charge");
@@ -218,7 +226,7 @@ mod tests {
         let update_cmd2 = UpdateSalesChargeTypeCommand {
             charge_type: update_input2,
         };
-        let updated2 = update_cmd2.exec(&mut service).await.unwrap();
+        let updated2 = update_cmd2.exec(&service).await.unwrap();
 
         assert_eq!(updated2.name, "Updated Charge");
         assert!(updated2.description.is_none());
@@ -226,7 +234,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_update_non_existent_charge_type() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let update_input = SalesChargeTypeUpdateInput {
             id: Uuid::now_v7().into(),
@@ -236,14 +244,14 @@ mod tests {
         let update_cmd = UpdateSalesChargeTypeCommand {
             charge_type: update_input,
         };
-        let result = update_cmd.exec(&mut service).await;
+        let result = update_cmd.exec(&service).await;
 
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_delete_sales_charge_type() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         // Create a charge type first
         let input = SalesChargeTypeNewInput {
@@ -251,22 +259,20 @@ mod tests {
             description: None,
         };
         let cmd = CreateSalesChargeTypeCommand { charge_type: input };
-        let created = cmd.exec(&mut service).await.unwrap();
+        let created = cmd.exec(&service).await.unwrap();
 
         // Delete it
-        let delete_cmd = DeleteSalesChargeTypeCommand { id: created.id };
-        let result = delete_cmd.exec(&mut service).await.unwrap();
+        let delete_cmd = DeleteSalesChargeTypeCommand { id: created.id, deleted_by: None };
+        let result = delete_cmd.exec(&service).await.unwrap();
 
         assert!(result);
 
-        // Verify it's gone
-        let mut check_query = Query::select();
-        let count_stmt = check_query
-            .from(SalesChargeTypes::Table)
-            .expr_as(Expr::col(SalesChargeTypes::Id).count(), Alias::new("count"))
-            .and_where(Expr::col(SalesChargeTypes::Id).eq(created.id.to_string()));
-
-        let count = service.db_adapter.query_one::<i64>(&count_stmt).await.unwrap();
+        // It is no longer listed but can still be looked up
+        let count = service.db_adapter.query_one::<i64>(&SalesChargeTypes::count(None)).await.unwrap();
         assert_eq!(count, 0);
+
+        let query = SalesChargeTypes::find_by_id(created.id);
+        let deleted = service.db_adapter.query_one::<SalesChargeType>(&query).await.unwrap();
+        assert!(deleted.deleted_at.is_some());
     }
 }
@@ -1,19 +1,34 @@
-use chrono::Utc;
+use chrono::{NaiveDateTime, Utc};
 use sea_query::{Expr, Query};
 use rand::Rng;
 use uuid::Uuid;
 
 use crate::{
     adapters::outgoing::database::DatabaseAdapter, core::{
-        commands::{app_service::AppService, Command},
-        models::sales::{
-            sales_order_charge_model::{SalesOrderCharge, SalesOrderCharges},
-            sales_order_item_model::{SalesOrderItem, SalesOrderItems},
-            sales_order_model::{
-                SalesOrder, SalesOrderNewInput, SalesOrderPaymentState, SalesOrderState, SalesOrders
+        commands::{
+            app_service::AppService,
+            catalog::price_list_commands::{price_context_for, resolve_item_price},
+            finance::currency_commands::base_currency,
+            Command,
+        },
+        events::{Event, StockChange},
+        models::{
+            auth::user_model::{User, UserRole, UserState, Users},
+            catalog::{
+                discount_model::{Discount, DiscountState, Discounts},
+                price_list_model::PriceContext,
+            },
+            sales::{
+                price_override_reason_model::{PriceOverrideReason, PriceOverrideReasons},
+                sales_order_charge_model::{SalesOrderCharge, SalesOrderCharges},
+                sales_order_item_model::{SalesOrderItem, SalesOrderItemInput, SalesOrderItems},
+                sales_order_model::{
+                    SalesOrder, SalesOrderNewInput, SalesOrderPaymentState, SalesOrderState, SalesOrders
+                },
             },
         },
-        types::db_uuid::DbUuid,
+        types::{db_uuid::DbUuid, money::Money},
+        utils::crypto::verify_pin,
     },
     error::{Error, Result}
 };
@@ -31,6 +46,105 @@ fn generate_readable_order_id() -> String {
     format!("ORD-{}-{}", date_str, random_part)
 }
 
+/// Checks that an override uses an active reason code and, when the reason asks for it,
+/// that an active user with the supervisor role confirmed it with their PIN
+pub(crate) async fn verify_price_override(
+    service: &AppService,
+    item_name: &str,
+    reason_id: DbUuid,
+    approved_by: Option<DbUuid>,
+    approval_pin: Option<&str>,
+) -> Result<()> {
+    let db = &service.db_adapter;
+
+    let mut reason_query = Query::select();
+    let reason_stmt = reason_query
+        .from(PriceOverrideReasons::Table)
+        .columns(PriceOverrideReasons::all_columns())
+        .and_where(Expr::col(PriceOverrideReasons::Id).eq(reason_id.to_string()));
+
+    let reason = db.query_optional::<PriceOverrideReason>(&reason_stmt).await?;
+    let reason = match reason {
+        Some(reason) if reason.is_active => reason,
+        _ => {
+            return Err(Error::ValidationError(format!(
+                "Override reason for '{}' is not an active reason code",
+                item_name
+            )))
+        }
+    };
+
+    // A supervisor confirms the override with their PIN
+    match (approved_by, approval_pin) {
+        (Some(approver_id), Some(pin)) => {
+            let mut approver_query = Query::select();
+            let approver_stmt = approver_query
+                .from(Users::Table)
+                .columns(Users::all_columns())
+                .and_where(Expr::col(Users::Id).eq(approver_id.to_string()));
+
+            let approver = db.query_optional::<User>(&approver_stmt).await?;
+            match approver {
+                Some(approver)
+                    if approver.state == UserState::Active
+                        && approver.role == UserRole::Supervisor
+                        && verify_pin(pin, &approver.pin_hash) => {}
+                _ => return Err(Error::AuthorizationError),
+            }
+        }
+        (None, None) if !reason.requires_approval => {}
+        _ => return Err(Error::AuthorizationError),
+    }
+
+    Ok(())
+}
+
+// Validates the price of an order line and returns the list or catalog price an override
+// replaces. A line priced away from that price needs an override, and nobody approves their
+// own override. Lines coming from a cart were priced there and their overrides verified when
+// they were set on the line.
+async fn check_line_price(
+    service: &AppService,
+    item: &SalesOrderItemInput,
+    user_id: DbUuid,
+    price_context: &PriceContext,
+    now: NaiveDateTime,
+    from_cart: bool,
+) -> Result<Option<Money>> {
+    if let Some(price_override) = &item.price_override {
+        if price_override.approved_by == Some(user_id) {
+            return Err(Error::AuthorizationError);
+        }
+
+        if !from_cart {
+            verify_price_override(
+                service,
+                &item.item_name,
+                price_override.reason_id,
+                price_override.approved_by,
+                price_override.approval_pin.as_deref(),
+            )
+            .await?;
+        }
+    }
+
+    let item_id = match item.item_id {
+        Some(item_id) => item_id,
+        None => return Ok(None),
+    };
+    let price = resolve_item_price(service, item_id, item.variant_id, item.quantity, price_context, now).await?;
+
+    match &item.price_override {
+        // Keep the price the line would have had so reports can show how far it was moved
+        Some(_) => Ok(Some(price)),
+        None if !from_cart && item.price_amount != price => Err(Error::ValidationError(format!(
+            "The price of '{}' differs from its list price and needs an override reason",
+            item.item_name
+        ))),
+        None => Ok(None),
+    }
+}
+
 // Commands
 pub struct CreateSalesOrderCommand {
     pub sales_order: SalesOrderNewInput,
@@ -46,246 +160,325 @@ pub struct VoidSalesOrderCommand {
 impl Command for CreateSalesOrderCommand {
     type Output = SalesOrder;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
-        let db = &service.db_adapter;
-        let now = Utc::now().naive_utc();
-        let user_id = self.created_by_user_id;
-        let order_id: DbUuid = Uuid::now_v7().into();
-
-        let new_sales_order = SalesOrder {
-            id: order_id,
-            order_readable_id: generate_readable_order_id(),
-            customer_id: self.sales_order.customer_id,
-            customer_name: self.sales_order.customer_name.clone(),
-            customer_phone_number: self.sales_order.customer_phone_number.clone(),
-            billing_address: self.sales_order.billing_address.clone(),
-            shipping_address: self.sales_order.shipping_address.clone(),
-            order_date: self.sales_order.order_date,
-            net_amount: self.sales_order.net_amount,
-            disc_amount: self.sales_order.disc_amount,
-            taxable_amount: self.sales_order.taxable_amount,
-            tax_amount: self.sales_order.tax_amount,
-            total_amount: self.sales_order.total_amount,
-            order_state: SalesOrderState::Completed,
-            payment_state: SalesOrderPaymentState::Pending,
-            notes: self.sales_order.notes.clone(),
-            channel_id: self.sales_order.channel_id,
-            location_id: self.sales_order.location_id,
-            cost_center_id: self.sales_order.cost_center_id,
-            discount_id: self.sales_order.discount_id,
-            created_by: user_id,
-            updated_by: user_id,
-            created_at: now,
-            updated_at: now,
-        };
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
+        let order = service
+            .db_adapter
+            .transaction(|| insert_sales_order(service, &self.sales_order, self.created_by_user_id, false))
+            .await?;
+        publish_order_created(service, &order, &self.sales_order.items);
+        Ok(order)
+    }
+}
+
+/// Validates and stores an order with its lines and charges.
+/// Shared by order creation and cart checkout so both follow the same rules. It writes several
+/// tables, so callers run it in a transaction and publish the order once that commits.
+pub(crate) async fn insert_sales_order(
+    service: &AppService,
+    sales_order: &SalesOrderNewInput,
+    user_id: DbUuid,
+    from_cart: bool,
+) -> Result<SalesOrder> {
+    let db = &service.db_adapter;
+    let now = Utc::now().naive_utc();
+    let order_id: DbUuid = Uuid::now_v7().into();
+
+    // Only discounts that are in effect right now can be attached to an order
+    if let Some(discount_id) = sales_order.discount_id {
+        let mut discount_query = Query::select();
+        let discount_stmt = discount_query
+            .from(Discounts::Table)
+            .columns(Discounts::all_columns())
+            .and_where(Expr::col(Discounts::Id).eq(discount_id.to_string()));
+
+        let discount = db
+            .query_optional::<Discount>(&discount_stmt)
+            .await?
+            .ok_or(Error::NotFoundError)?;
+
+        let state = discount.effective_state(now);
+        if state != DiscountState::Active {
+            return Err(Error::ValidationError(format!(
+                "Discount '{}' cannot be applied because it is {}",
+                discount.name, state
+            )));
+        }
+    }
+
+    // Validate the price of each line
+    let price_context = price_context_for(
+        service,
+        Some(sales_order.channel_id),
+        Some(sales_order.location_id),
+        sales_order.customer_id,
+    )
+    .await?;
+    let mut original_prices = Vec::with_capacity(sales_order.items.len());
+    for item in &sales_order.items {
+        original_prices.push(check_line_price(service, item, user_id, &price_context, now, from_cart).await?);
+    }
+
+    // Orders are priced in the base currency
+    let currency = base_currency(service).await?;
+
+    let new_sales_order = SalesOrder {
+        id: order_id,
+        order_readable_id: generate_readable_order_id(),
+        customer_id: sales_order.customer_id,
+        customer_name: sales_order.customer_name.clone(),
+        customer_phone_number: sales_order.customer_phone_number.clone(),
+        billing_address: sales_order.billing_address.clone(),
+        shipping_address: sales_order.shipping_address.clone(),
+        order_date: sales_order.order_date,
+        net_amount: sales_order.net_amount,
+        disc_amount: sales_order.disc_amount,
+        taxable_amount: sales_order.taxable_amount,
+        tax_amount: sales_order.tax_amount,
+        total_amount: sales_order.total_amount,
+        currency_code: currency.code,
+        order_state: SalesOrderState::Completed,
+        payment_state: SalesOrderPaymentState::Pending,
+        notes: sales_order.notes.clone(),
+        channel_id: sales_order.channel_id,
+        location_id: sales_order.location_id,
+        cost_center_id: sales_order.cost_center_id,
+        discount_id: sales_order.discount_id,
+        created_by: user_id,
+        updated_by: user_id,
+        created_at: now,
+        updated_at: now,
+    };
 
-        // Insert the sales order
-        let mut insert_stmt = Query::insert();
-        let insert_stmt = insert_stmt
-            .into_table(SalesOrders::Table)
+    // Insert the sales order
+    let mut insert_stmt = Query::insert();
+    let insert_stmt = insert_stmt
+        .into_table(SalesOrders::Table)
+        .columns([
+            SalesOrders::Id,
+            SalesOrders::OrderReadableId,
+            SalesOrders::OrderDate,
+            SalesOrders::CustomerId,
+            SalesOrders::CustomerName,
+            SalesOrders::CustomerPhoneNumber,
+            SalesOrders::BillingAddress,
+            SalesOrders::ShippingAddress,
+            SalesOrders::NetAmount,
+            SalesOrders::DiscAmount,
+            SalesOrders::TaxableAmount,
+            SalesOrders::TaxAmount,
+            SalesOrders::TotalAmount,
+            SalesOrders::CurrencyCode,
+            SalesOrders::OrderState,
+            SalesOrders::PaymentState,
+            SalesOrders::Notes,
+            SalesOrders::ChannelId,
+            SalesOrders::LocationId,
+            SalesOrders::CostCenterId,
+            SalesOrders::CreatedBy,
+            SalesOrders::UpdatedBy,
+            SalesOrders::DiscountId,
+            SalesOrders::CreatedAt,
+            SalesOrders::UpdatedAt,
+        ])
+        .values_panic([
+            new_sales_order.id.to_string().into(),
+            new_sales_order.order_readable_id.clone().into(),
+            new_sales_order.order_date.to_string().into(),
+            match new_sales_order.customer_id {
+                Some(id) => id.to_string().into(),
+                None => sea_query::Value::String(None).into(),
+            },
+            match &new_sales_order.customer_name {
+                Some(name) => name.clone().into(),
+                None => sea_query::Value::String(None).into(),
+            },
+            match &new_sales_order.customer_phone_number {
+                Some(phone) => phone.clone().into(),
+                None => sea_query::Value::String(None).into(),
+            },
+            match &new_sales_order.billing_address {
+                Some(addr) => addr.clone().into(),
+                None => sea_query::Value::String(None).into(),
+            },
+            match &new_sales_order.shipping_address {
+                Some(addr) => addr.clone().into(),
+                None => sea_query::Value::String(None).into(),
+            },
+            new_sales_order.net_amount.to_base_unit().into(),
+            new_sales_order.disc_amount.to_base_unit().into(),
+            new_sales_order.taxable_amount.to_base_unit().into(),
+            new_sales_order.tax_amount.to_base_unit().into(),
+            new_sales_order.total_amount.to_base_unit().into(),
+            new_sales_order.currency_code.clone().into(),
+            new_sales_order.order_state.to_string().into(),
+            new_sales_order.payment_state.to_string().into(),
+            match &new_sales_order.notes {
+                Some(notes) => notes.clone().into(),
+                None => sea_query::Value::String(None).into(),
+            },
+            new_sales_order.channel_id.to_string().into(),
+            new_sales_order.location_id.to_string().into(),
+            new_sales_order.cost_center_id.to_string().into(),
+            new_sales_order.created_by.to_string().into(),
+            new_sales_order.updated_by.to_string().into(),
+            match new_sales_order.discount_id {
+                Some(id) => id.to_string().into(),
+                None => sea_query::Value::String(None).into(),
+            },
+            new_sales_order.created_at.to_string().into(),
+            new_sales_order.updated_at.to_string().into(),
+        ]);
+
+    db.insert_one::<SalesOrder>(&insert_stmt).await?;
+
+    // Insert order items
+    for (item, original_price) in sales_order.items.iter().zip(original_prices) {
+        let item_id: DbUuid = Uuid::now_v7().into();
+        let price_override = item.price_override.as_ref();
+
+        let mut item_insert_stmt = Query::insert();
+        let item_insert_stmt = item_insert_stmt
+            .into_table(SalesOrderItems::Table)
             .columns([
-                SalesOrders::Id,
-                SalesOrders::OrderReadableId,
-                SalesOrders::OrderDate,
-                SalesOrders::CustomerId,
-                SalesOrders::CustomerName,
-                SalesOrders::CustomerPhoneNumber,
-                SalesOrders::BillingAddress,
-                SalesOrders::ShippingAddress,
-                SalesOrders::NetAmount,
-                SalesOrders::DiscAmount,
-                SalesOrders::TaxableAmount,
-                SalesOrders::TaxAmount,
-                SalesOrders::TotalAmount,
-                SalesOrders::OrderState,
-                SalesOrders::PaymentState,
-                SalesOrders::Notes,
-                SalesOrders::ChannelId,
-                SalesOrders::LocationId,
-                SalesOrders::CostCenterId,
-                SalesOrders::CreatedBy,
-                SalesOrders::UpdatedBy,
-                SalesOrders::DiscountId,
-                SalesOrders::CreatedAt,
-                SalesOrders::UpdatedAt,
+                SalesOrderItems::Id,
+                SalesOrderItems::OrderId,
+                SalesOrderItems::ItemId,
+                SalesOrderItems::ItemName,
+                SalesOrderItems::Quantity,
+                SalesOrderItems::Sku,
+                SalesOrderItems::PriceAmount,
+                SalesOrderItems::DiscAmount,
+                SalesOrderItems::TaxableAmount,
+                SalesOrderItems::TaxAmount,
+                SalesOrderItems::TotalAmount,
+                SalesOrderItems::OriginalPriceAmount,
+                SalesOrderItems::OverrideReasonId,
+                SalesOrderItems::OverrideNote,
+                SalesOrderItems::OverrideApprovedBy,
+                SalesOrderItems::CreatedAt,
+                SalesOrderItems::UpdatedAt,
             ])
             .values_panic([
-                new_sales_order.id.to_string().into(),
-                new_sales_order.order_readable_id.clone().into(),
-                new_sales_order.order_date.to_string().into(),
-                match new_sales_order.customer_id {
+                item_id.to_string().into(),
+                order_id.to_string().into(),
+                match item.item_id {
                     Some(id) => id.to_string().into(),
                     None => sea_query::Value::String(None).into(),
                 },
-                match &new_sales_order.customer_name {
-                    Some(name) => name.clone().into(),
-                    None => sea_query::Value::String(None).into(),
-                },
-                match &new_sales_order.customer_phone_number {
-                    Some(phone) => phone.clone().into(),
-                    None => sea_query::Value::String(None).into(),
-                },
-                match &new_sales_order.billing_address {
-                    Some(addr) => addr.clone().into(),
-                    None => sea_query::Value::String(None).into(),
-                },
-                match &new_sales_order.shipping_address {
-                    Some(addr) => addr.clone().into(),
-                    None => sea_query::Value::String(None).into(),
-                },
-                new_sales_order.net_amount.to_base_unit().into(),
-                new_sales_order.disc_amount.to_base_unit().into(),
-                new_sales_order.taxable_amount.to_base_unit().into(),
-                new_sales_order.tax_amount.to_base_unit().into(),
-                new_sales_order.total_amount.to_base_unit().into(),
-                new_sales_order.order_state.to_string().into(),
-                new_sales_order.payment_state.to_string().into(),
-                match &new_sales_order.notes {
-                    Some(notes) => notes.clone().into(),
-                    None => sea_query::Value::String(None).into(),
-                },
-                new_sales_order.channel_id.to_string().into(),
-                new_sales_order.location_id.to_string().into(),
-                new_sales_order.cost_center_id.to_string().into(),
-                new_sales_order.created_by.to_string().into(),
-                new_sales_order.updated_by.to_string().into(),
-                match new_sales_order.discount_id {
-                    Some(id) => id.to_string().into(),
+                item.item_name.clone().into(),
+                item.quantity.into(),
+                match &item.sku {
+                    Some(sku) => sku.clone().into(),
                     None => sea_query::Value::String(None).into(),
                 },
-                new_sales_order.created_at.to_string().into(),
-                new_sales_order.updated_at.to_string().into(),
+                item.price_amount.to_base_unit().into(),
+                item.disc_amount.to_base_unit().into(),
+                item.taxable_amount.to_base_unit().into(),
+                item.tax_amount.to_base_unit().into(),
+                item.total_amount.to_base_unit().into(),
+                original_price.map(|price| price.to_base_unit()).into(),
+                price_override.map(|o| o.reason_id.to_string()).into(),
+                price_override.and_then(|o| o.note.clone()).into(),
+                price_override.and_then(|o| o.approved_by).map(|id| id.to_string()).into(),
+                now.to_string().into(),
+                now.to_string().into(),
             ]);
 
-        db.insert_one::<SalesOrder>(&insert_stmt).await?;
+        db.insert_one::<SalesOrderItem>(&item_insert_stmt).await?;
+    }
 
-        // Insert order items
-        for item in &self.sales_order.items {
-            let item_id: DbUuid = Uuid::now_v7().into();
+    // Insert order charges if any
+    if let Some(charges_input) = &sales_order.charges {
+        for charge in charges_input {
+            let charge_id: DbUuid = Uuid::now_v7().into();
 
-            let mut item_insert_stmt = Query::insert();
-            let item_insert_stmt = item_insert_stmt
-                .into_table(SalesOrderItems::Table)
+            let mut charge_insert_stmt = Query::insert();
+            let charge_insert_stmt = charge_insert_stmt
+                .into_table(SalesOrderCharges::Table)
                 .columns([
-                    SalesOrderItems::Id,
-                    SalesOrderItems::OrderId,
-                    SalesOrderItems::ItemId,
-                    SalesOrderItems::ItemName,
-                    SalesOrderItems::Quantity,
-                    SalesOrderItems::Sku,
-                    SalesOrderItems::PriceAmount,
-                    SalesOrderItems::DiscAmount,
-                    SalesOrderItems::TaxableAmount,
-                    SalesOrderItems::TaxAmount,
-                    SalesOrderItems::TotalAmount,
-                    SalesOrderItems::CreatedAt,
-                    SalesOrderItems::UpdatedAt,
+                    SalesOrderCharges::Id,
+                    SalesOrderCharges::OrderId,
+                    SalesOrderCharges::ChargeTypeId,
+                    SalesOrderCharges::ChargeTypeName,
+                    SalesOrderCharges::Amount,
+                    SalesOrderCharges::TaxAmount,
+                    SalesOrderCharges::TaxGroupId,
+                    SalesOrderCharges::CreatedAt,
+                    SalesOrderCharges::UpdatedAt,
                 ])
                 .values_panic([
-                    item_id.to_string().into(),
+                    charge_id.to_string().into(),
                     order_id.to_string().into(),
-                    match item.item_id {
+                    charge.charge_type_id.to_string().into(),
+                    charge.charge_type_name.clone().into(),
+                    charge.amount.to_base_unit().into(),
+                    charge.tax_amount.to_base_unit().into(),
+                    match charge.tax_group_id {
                         Some(id) => id.to_string().into(),
                         None => sea_query::Value::String(None).into(),
                     },
-                    item.item_name.clone().into(),
-                    item.quantity.into(),
-                    match &item.sku {
-                        Some(sku) => sku.clone().into(),
-                        None => sea_query::Value::String(None).into(),
-                    },
-                    item.price_amount.to_base_unit().into(),
-                    item.disc_amount.to_base_unit().into(),
-                    item.taxable_amount.to_base_unit().into(),
-                    item.tax_amount.to_base_unit().into(),
-                    item.total_amount.to_base_unit().into(),
                     now.to_string().into(),
                     now.to_string().into(),
                 ]);
 
-            db.insert_one::<SalesOrderItem>(&item_insert_stmt).await?;
+            db.insert_one::<SalesOrderCharge>(&charge_insert_stmt).await?;
         }
+    }
 
-        // Insert order charges if any
-        if let Some(charges_input) = &self.sales_order.charges {
-            for charge in charges_input {
-                let charge_id: DbUuid = Uuid::now_v7().into();
-
-                let mut charge_insert_stmt = Query::insert();
-                let charge_insert_stmt = charge_insert_stmt
-                    .into_table(SalesOrderCharges::Table)
-                    .columns([
-                        SalesOrderCharges::Id,
-                        SalesOrderCharges::OrderId,
-                        SalesOrderCharges::ChargeTypeId,
-                        SalesOrderCharges::ChargeTypeName,
-                        SalesOrderCharges::Amount,
-                        SalesOrderCharges::TaxAmount,
-                        SalesOrderCharges::TaxGroupId,
-                        SalesOrderCharges::CreatedAt,
-                        SalesOrderCharges::UpdatedAt,
-                    ])
-                    .values_panic([
-                        charge_id.to_string().into(),
-                        order_id.to_string().into(),
-                        charge.charge_type_id.to_string().into(),
-                        charge.charge_type_name.clone().into(),
-                        charge.amount.to_base_unit().into(),
-                        charge.tax_amount.to_base_unit().into(),
-                        match charge.tax_group_id {
-                            Some(id) => id.to_string().into(),
-                            None => sea_query::Value::String(None).into(),
-                        },
-                        now.to_string().into(),
-                        now.to_string().into(),
-                    ]);
-
-                db.insert_one::<SalesOrderCharge>(&charge_insert_stmt).await?;
-            }
-        }
-
-        // Retrieve the created order
-        let mut select_stmt = Query::select();
-        let select_stmt = select_stmt
-            .from(SalesOrders::Table)
-            .columns([
-                SalesOrders::Id,
-                SalesOrders::OrderReadableId,
-                SalesOrders::OrderDate,
-                SalesOrders::CustomerId,
-                SalesOrders::CustomerName,
-                SalesOrders::CustomerPhoneNumber,
-                SalesOrders::BillingAddress,
-                SalesOrders::ShippingAddress,
-                SalesOrders::NetAmount,
-                SalesOrders::DiscAmount,
-                SalesOrders::TaxableAmount,
-                SalesOrders::TaxAmount,
-                SalesOrders::TotalAmount,
-                SalesOrders::OrderState,
-                SalesOrders::PaymentState,
-                SalesOrders::Notes,
-                SalesOrders::ChannelId,
-                SalesOrders::LocationId,
-                SalesOrders::CostCenterId,
-                SalesOrders::CreatedBy,
-                SalesOrders::UpdatedBy,
-                SalesOrders::DiscountId,
-                SalesOrders::CreatedAt,
-                SalesOrders::UpdatedAt,
-            ])
-            .and_where(Expr::col(SalesOrders::Id).eq(order_id.to_string()));
-
-        let created_order = db.query_one::<SalesOrder>(&select_stmt).await?;
+    // Retrieve the created order
+    let mut select_stmt = Query::select();
+    let select_stmt = select_stmt
+        .from(SalesOrders::Table)
+        .columns([
+            SalesOrders::Id,
+            SalesOrders::OrderReadableId,
+            SalesOrders::OrderDate,
+            SalesOrders::CustomerId,
+            SalesOrders::CustomerName,
+            SalesOrders::CustomerPhoneNumber,
+            SalesOrders::BillingAddress,
+            SalesOrders::ShippingAddress,
+            SalesOrders::NetAmount,
+            SalesOrders::DiscAmount,
+            SalesOrders::TaxableAmount,
+            SalesOrders::TaxAmount,
+            SalesOrders::TotalAmount,
+            SalesOrders::CurrencyCode,
+            SalesOrders::OrderState,
+            SalesOrders::PaymentState,
+            SalesOrders::Notes,
+            SalesOrders::ChannelId,
+            SalesOrders::LocationId,
+            SalesOrders::CostCenterId,
+            SalesOrders::CreatedBy,
+            SalesOrders::UpdatedBy,
+            SalesOrders::DiscountId,
+            SalesOrders::CreatedAt,
+            SalesOrders::UpdatedAt,
+        ])
+        .and_where(Expr::col(SalesOrders::Id).eq(order_id.to_string()));
+
+    db.query_one::<SalesOrder>(&select_stmt).await
+}
 
-        Ok(created_order)
+/// Announces a committed order and the stock its lines took
+pub(crate) fn publish_order_created(service: &AppService, order: &SalesOrder, items: &[SalesOrderItemInput]) {
+    service.events.publish(Event::OrderCreated(order.clone()));
+    for item in items {
+        if let Some(item_id) = item.item_id {
+            service.events.publish(Event::StockChanged(StockChange {
+                item_id,
+                order_id: order.id,
+                quantity: -item.quantity,
+            }));
+        }
     }
 }
 
 impl Command for VoidSalesOrderCommand {
     type Output = SalesOrder;
 
-    async fn exec(&self, service: &mut AppService) -> Result<Self::Output> {
+    async fn exec(&self, service: &AppService) -> Result<Self::Output> {
         let db = &service.db_adapter;
         let now = Utc::now().naive_utc();
         let user_id = self.updated_by_user_id;
@@ -308,6 +501,7 @@ impl Command for VoidSalesOrderCommand {
                 SalesOrders::TaxableAmount,
                 SalesOrders::TaxAmount,
                 SalesOrders::TotalAmount,
+                SalesOrders::CurrencyCode,
                 SalesOrders::OrderState,
                 SalesOrders::PaymentState,
                 SalesOrders::Notes,
@@ -358,6 +552,7 @@ impl Command for VoidSalesOrderCommand {
                 SalesOrders::TaxableAmount,
                 SalesOrders::TaxAmount,
                 SalesOrders::TotalAmount,
+                SalesOrders::CurrencyCode,
                 SalesOrders::OrderState,
                 SalesOrders::PaymentState,
                 SalesOrders::Notes,
@@ -374,13 +569,51 @@ impl Command for VoidSalesOrderCommand {
 
         let updated_order = db.query_one::<SalesOrder>(&select_stmt).await?;
 
+        // The voided items go back into stock
+        let items_stmt = Query::select()
+            .from(SalesOrderItems::Table)
+            .columns(SalesOrderItems::all_columns())
+            .and_where(Expr::col(SalesOrderItems::OrderId).eq(self.id.to_string()))
+            .to_owned();
+        let items = db.query_many::<SalesOrderItem>(&items_stmt).await?;
+
+        service.events.publish(Event::OrderVoided(updated_order.clone()));
+        for item in items {
+            if let Some(item_id) = item.item_id {
+                service.events.publish(Event::StockChanged(StockChange {
+                    item_id,
+                    order_id: self.id,
+                    quantity: item.quantity,
+                }));
+            }
+        }
+
         Ok(updated_order)
     }
 }
 
 #[cfg(test)]
-mod tests {
-    use crate::core::{commands::sales::sales_charge_type_commands::CreateSalesChargeTypeCommand, models::auth::user_model::UserState};
+pub(crate) mod tests {
+    use crate::core::{commands::sales::sales_charge_type_commands::CreateSalesChargeTypeCommand, models::auth::user_model::{UserRole, UserState}};
+    use crate::core::{
+        commands::{
+            catalog::{
+                discount_commands::CreateDiscountCommand, item_commands::CreateItemCommand,
+                item_group_commands::CreateItemGroupCommand, item_variant_commands::CreateItemVariantCommand,
+            },
+            sales::price_override_reason_commands::CreatePriceOverrideReasonCommand,
+        },
+        models::{
+            catalog::{
+                discount_model::{DiscountNewInput, DiscountScope, DiscountType},
+                item_group_model::ItemCategoryNew,
+                item_model::{ItemNature, ItemState, NewItem},
+                item_variant_model::ItemVariantNewInput,
+            },
+            sales::price_override_reason_model::PriceOverrideReasonNewInput,
+        },
+    };
+    use chrono::Duration;
     use crate::core::commands::tests::setup_service;
     use tokio;
     use crate::{
@@ -415,7 +648,7 @@ mod tests {
 
     use super::*;
 
-    async fn create_test_cost_center(service: &mut AppService) -> CostCenter {
+    async fn create_test_cost_center(service: &AppService) -> CostCenter {
         let command = CreateCostCenterCommand {
             cost_center: CostCenterNewInput {
                 name: "Test Cost Center".to_string(),
@@ -427,7 +660,11 @@ mod tests {
         command.exec(service).await.unwrap()
     }
 
-    async fn create_test_user(service: &mut AppService) -> DbUuid {
+    pub(crate) async fn create_test_user(service: &AppService) -> DbUuid {
+        create_test_user_with_role(service, UserRole::Cashier).await
+    }
+
+    pub(crate) async fn create_test_user_with_role(service: &AppService, role: UserRole) -> DbUuid {
         let random_suffix = rand::thread_rng().gen_range(1000..9999).to_string();
         let command = AddUserCommand {
             user: UserNewInput {
@@ -435,13 +672,14 @@ mod tests {
                 pin_hash: "1234".to_string(),
                 full_name: format!("Test User {}", random_suffix),
                 state: UserState::Active,
+                role,
                 last_login_at: None,
             },
         };
         command.exec(service).await.unwrap().id
     }
 
-    async fn create_test_channel(service: &mut AppService) -> Channel {
+    async fn create_test_channel(service: &AppService) -> Channel {
         let command = CreateChannelCommand {
             channel: ChannelNewInput {
                 name: format!("Test Channel {}", rand::thread_rng().gen_range(1..999)),
@@ -452,7 +690,7 @@ mod tests {
         command.exec(service).await.unwrap()
     }
 
-    async fn create_test_location(service: &mut AppService) -> Location {
+    async fn create_test_location(service: &AppService) -> Location {
         let command = CreateLocationCommand {
             location: LocationNewInput {
                 name: format!("Test Location {}", rand::thread_rng().gen_range(1..999)),
@@ -464,7 +702,7 @@ mod tests {
         command.exec(service).await.unwrap()
     }
 
-    async fn create_test_charge_type(service: &mut AppService, name: &str) -> SalesChargeType {
+    async fn create_test_charge_type(service: &AppService, name: &str) -> SalesChargeType {
         let cmd = CreateSalesChargeTypeCommand {
             charge_type: SalesChargeTypeNewInput {
                 name: name.to_string(),
@@ -476,12 +714,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_sales_order() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let now = Utc::now().naive_utc();
-        let cost_center = create_test_cost_center(&mut service).await;
-        let user_id = create_test_user(&mut service).await;
-        let channel = create_test_channel(&mut service).await;
-        let location = create_test_location(&mut service).await;
+        let cost_center = create_test_cost_center(&service).await;
+        let user_id = create_test_user(&service).await;
+        let channel = create_test_channel(&service).await;
+        let location = create_test_location(&service).await;
 
         // Print IDs for debugging
         println!("Test IDs: user_id={:?}, channel_id={:?}, location_id={:?}, cost_center_id={:?}",
@@ -511,6 +749,7 @@ mod tests {
             items: vec![
                 SalesOrderItemInput {
                     item_id: None, // Don't use item_id to avoid foreign key constraint issues
+                    variant_id: None,
                     item_name: "Item 1".to_string(),
                     quantity: 2,
                     sku: Some("SKU001".to_string()),
@@ -519,9 +758,11 @@ mod tests {
                     taxable_amount: 450.into(),
                     tax_amount: 45.into(),
                     total_amount: 495.into(),
+                    price_override: None,
                 },
                 SalesOrderItemInput {
                     item_id: None, // Don't use item_id to avoid foreign key constraint issues
+                    variant_id: None,
                     item_name: "Item 2".to_string(),
                     quantity: 1,
                     sku: None,
@@ -530,6 +771,7 @@ mod tests {
                     taxable_amount: 100.into(),
                     tax_amount: 10.into(),
                     total_amount: 110.into(),
+                    price_override: None,
                 },
             ],
             charges: None,
@@ -540,7 +782,7 @@ mod tests {
             created_by_user_id: user_id,
         };
 
-        let result = match cmd.exec(&mut service).await {
+        let result = match cmd.exec(&service).await {
             Ok(r) => r,
             Err(e) => {
                 println!("Error creating sales order: {:?}", e);
@@ -550,6 +792,7 @@ mod tests {
         assert_eq!(result.customer_name, Some("John Doe".to_string()));
         assert_eq!(result.order_state, SalesOrderState::Completed);
         assert_eq!(result.payment_state, SalesOrderPaymentState::Pending);
+        assert_eq!(result.currency_code, "INR");
         assert_eq!(result.cost_center_id, cost_center.id);
         assert_eq!(result.created_by, user_id);
         assert_eq!(result.updated_by, user_id);
@@ -604,14 +847,14 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_sales_order_with_charges() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let now = Utc::now().naive_utc();
-        let cost_center = create_test_cost_center(&mut service).await;
-        let user_id = create_test_user(&mut service).await;
-        let channel = create_test_channel(&mut service).await;
-        let location = create_test_location(&mut service).await;
-        let charge_type1 = create_test_charge_type(&mut service, "Service Charge").await;
-        let charge_type2 = create_test_charge_type(&mut service, "Delivery Fee").await;
+        let cost_center = create_test_cost_center(&service).await;
+        let user_id = create_test_user(&service).await;
+        let channel = create_test_channel(&service).await;
+        let location = create_test_location(&service).await;
+        let charge_type1 = create_test_charge_type(&service, "Service Charge").await;
+        let charge_type2 = create_test_charge_type(&service, "Delivery Fee").await;
 
         let input = SalesOrderNewInput {
             customer_id: None,
@@ -632,6 +875,7 @@ mod tests {
             discount_id: None,
             items: vec![SalesOrderItemInput {
                 item_id: None,
+                variant_id: None,
                 item_name: "Custom Item".to_string(),
                 quantity: 1,
                 sku: None,
@@ -640,6 +884,7 @@ mod tests {
                 taxable_amount: 500.into(),
                 tax_amount: 50.into(),
                 total_amount: 550.into(),
+                price_override: None,
             }],
             charges: Some(vec![
                 SalesOrderChargeNewInput {
@@ -663,7 +908,7 @@ mod tests {
             sales_order: input,
             created_by_user_id: user_id,
         };
-        let result = cmd.exec(&mut service).await.unwrap();
+        let result = cmd.exec(&service).await.unwrap();
 
         // Query charges using SeaQuery
         let mut charges_query = Query::select();
@@ -695,14 +940,365 @@ mod tests {
         assert_eq!(result.total_amount, 550.into());
     }
 
+    #[tokio::test]
+    async fn test_failed_order_leaves_nothing_behind() {
+        let service = setup_service().await;
+        let cost_center = create_test_cost_center(&service).await;
+        let user_id = create_test_user(&service).await;
+        let channel = create_test_channel(&service).await;
+        let location = create_test_location(&service).await;
+
+        // The charge fails on its foreign key once the order and its line are written
+        let input = SalesOrderNewInput {
+            customer_id: None,
+            customer_name: None,
+            customer_phone_number: None,
+            billing_address: None,
+            shipping_address: None,
+            order_date: Utc::now().naive_utc(),
+            net_amount: 500.into(),
+            disc_amount: 0.into(),
+            taxable_amount: 500.into(),
+            tax_amount: 0.into(),
+            total_amount: 550.into(),
+            notes: None,
+            channel_id: channel.id,
+            location_id: location.id,
+            cost_center_id: cost_center.id,
+            discount_id: None,
+            items: vec![SalesOrderItemInput {
+                item_id: None,
+                variant_id: None,
+                item_name: "Custom Item".to_string(),
+                quantity: 1,
+                sku: None,
+                price_amount: 500.into(),
+                disc_amount: 0.into(),
+                taxable_amount: 500.into(),
+                tax_amount: 0.into(),
+                total_amount: 500.into(),
+                price_override: None,
+            }],
+            charges: Some(vec![SalesOrderChargeNewInput {
+                charge_type_id: Uuid::now_v7().into(),
+                charge_type_name: "Gone".to_string(),
+                amount: 50.into(),
+                tax_amount: 0.into(),
+                tax_group_id: None,
+            }]),
+        };
+
+        let mut events = service.events.subscribe();
+        let result = CreateSalesOrderCommand { sales_order: input, created_by_user_id: user_id }
+            .exec(&service)
+            .await;
+        assert!(result.is_err());
+
+        let mut orders_query = Query::select();
+        let orders_stmt = orders_query.from(SalesOrders::Table).columns(SalesOrders::all_columns());
+        assert!(service.db_adapter.query_many::<SalesOrder>(orders_stmt).await.unwrap().is_empty());
+        let mut items_query = Query::select();
+        let items_stmt = items_query.from(SalesOrderItems::Table).columns(SalesOrderItems::all_columns());
+        assert!(service.db_adapter.query_many::<SalesOrderItem>(items_stmt).await.unwrap().is_empty());
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_sales_order_rejects_expired_discount() {
+        let service = setup_service().await;
+        let now = Utc::now().naive_utc();
+        let cost_center = create_test_cost_center(&service).await;
+        let user_id = create_test_user(&service).await;
+        let channel = create_test_channel(&service).await;
+        let location = create_test_location(&service).await;
+
+        let discount = CreateDiscountCommand {
+            discount: DiscountNewInput {
+                name: "Last Week Sale".to_string(),
+                description: None,
+                discount_type: DiscountType::Percentage,
+                value: 10.into(),
+                scope: DiscountScope::AllItems,
+                state: Some(DiscountState::Active),
+                start_date: Some(now - Duration::days(7)),
+                end_date: Some(now - Duration::days(1)),
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let input = SalesOrderNewInput {
+            customer_id: None,
+            customer_name: None,
+            customer_phone_number: None,
+            billing_address: None,
+            shipping_address: None,
+            order_date: now,
+            net_amount: 1000.into(),
+            disc_amount: 100.into(),
+            taxable_amount: 900.into(),
+            tax_amount: 0.into(),
+            total_amount: 900.into(),
+            notes: None,
+            channel_id: channel.id,
+            location_id: location.id,
+            cost_center_id: cost_center.id,
+            discount_id: Some(discount.id),
+            items: vec![SalesOrderItemInput {
+                item_id: None,
+                variant_id: None,
+                item_name: "Item 1".to_string(),
+                quantity: 1,
+                sku: None,
+                price_amount: 1000.into(),
+                disc_amount: 100.into(),
+                taxable_amount: 900.into(),
+                tax_amount: 0.into(),
+                total_amount: 900.into(),
+                price_override: None,
+            }],
+            charges: None,
+        };
+
+        let cmd = CreateSalesOrderCommand {
+            sales_order: input,
+            created_by_user_id: user_id,
+        };
+        let result = cmd.exec(&service).await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_sales_order_with_price_override() {
+        let service = setup_service().await;
+        let now = Utc::now().naive_utc();
+        let cost_center = create_test_cost_center(&service).await;
+        let user_id = create_test_user(&service).await;
+        let cashier_id = create_test_user(&service).await;
+        let supervisor_id = create_test_user_with_role(&service, UserRole::Supervisor).await;
+        let channel = create_test_channel(&service).await;
+        let location = create_test_location(&service).await;
+
+        let reason = CreatePriceOverrideReasonCommand {
+            reason: PriceOverrideReasonNewInput {
+                code: "DAMAGED".to_string(),
+                name: "Damaged goods".to_string(),
+                description: None,
+                requires_approval: Some(true),
+                is_active: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let order_input = |price_override: SalesOrderItemOverrideInput| SalesOrderNewInput {
+            customer_id: None,
+            customer_name: None,
+            customer_phone_number: None,
+            billing_address: None,
+            shipping_address: None,
+            order_date: now,
+            net_amount: 800.into(),
+            disc_amount: 0.into(),
+            taxable_amount: 800.into(),
+            tax_amount: 0.into(),
+            total_amount: 800.into(),
+            notes: None,
+            channel_id: channel.id,
+            location_id: location.id,
+            cost_center_id: cost_center.id,
+            discount_id: None,
+            items: vec![SalesOrderItemInput {
+                item_id: None,
+                variant_id: None,
+                item_name: "Dented Tin".to_string(),
+                quantity: 1,
+                sku: None,
+                price_amount: 800.into(),
+                disc_amount: 0.into(),
+                taxable_amount: 800.into(),
+                tax_amount: 0.into(),
+                total_amount: 800.into(),
+                price_override: Some(price_override),
+            }],
+            charges: None,
+        };
+
+        // The reason needs a supervisor, so an unapproved override is refused
+        let result = CreateSalesOrderCommand {
+            sales_order: order_input(SalesOrderItemOverrideInput {
+                reason_id: reason.id,
+                note: None,
+                approved_by: None,
+                approval_pin: None,
+            }),
+            created_by_user_id: user_id,
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(result, Err(Error::AuthorizationError)));
+
+        // A wrong PIN is refused as well
+        let result = CreateSalesOrderCommand {
+            sales_order: order_input(SalesOrderItemOverrideInput {
+                reason_id: reason.id,
+                note: None,
+                approved_by: Some(supervisor_id),
+                approval_pin: Some("0000".to_string()),
+            }),
+            created_by_user_id: user_id,
+        }
+        .exec(&service)
+        .await;
+        assert!(matches!(result, Err(Error::AuthorizationError)));
+
+        // Only a supervisor approves, and never their own order
+        for (approved_by, created_by) in [(cashier_id, user_id), (supervisor_id, supervisor_id)] {
+            let result = CreateSalesOrderCommand {
+                sales_order: order_input(SalesOrderItemOverrideInput {
+                    reason_id: reason.id,
+                    note: None,
+                    approved_by: Some(approved_by),
+                    approval_pin: Some("1234".to_string()),
+                }),
+                created_by_user_id: created_by,
+            }
+            .exec(&service)
+            .await;
+            assert!(matches!(result, Err(Error::AuthorizationError)));
+        }
+
+        let order = CreateSalesOrderCommand {
+            sales_order: order_input(SalesOrderItemOverrideInput {
+                reason_id: reason.id,
+                note: Some("Dent on the lid".to_string()),
+                approved_by: Some(supervisor_id),
+                approval_pin: Some("1234".to_string()),
+            }),
+            created_by_user_id: user_id,
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let items_stmt = Query::select()
+            .from(SalesOrderItems::Table)
+            .columns(SalesOrderItems::all_columns())
+            .and_where(Expr::col(SalesOrderItems::OrderId).eq(order.id.to_string()))
+            .to_owned();
+        let items = service.db_adapter.query_many::<SalesOrderItem>(&items_stmt).await.unwrap();
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].override_reason_id, Some(reason.id));
+        assert_eq!(items[0].override_note, Some("Dent on the lid".to_string()));
+        assert_eq!(items[0].override_approved_by, Some(supervisor_id));
+    }
+
+    #[tokio::test]
+    async fn test_repriced_line_needs_an_override() {
+        let service = setup_service().await;
+        let now = Utc::now().naive_utc();
+        let cost_center = create_test_cost_center(&service).await;
+        let user_id = create_test_user(&service).await;
+        let channel = create_test_channel(&service).await;
+        let location = create_test_location(&service).await;
+
+        let category = CreateItemGroupCommand {
+            category: ItemCategoryNew { name: "Tins".to_string(), description: None },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+        let item = CreateItemCommand {
+            item: NewItem {
+                name: "Tomato Tin".to_string(),
+                description: None,
+                nature: ItemNature::Goods,
+                state: ItemState::Active,
+                price: Money::from(1000),
+                category_id: category.id,
+                tax_ids: None,
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let variant = CreateItemVariantCommand {
+            item_variant: ItemVariantNewInput {
+                item_id: item.id,
+                sku: Some("TIN-800G".to_string()),
+                barcode: None,
+                price_adjustment: Some(Money::from(500)),
+                is_default: Some(false),
+                variant_value_ids: vec![],
+            },
+        }
+        .exec(&service)
+        .await
+        .unwrap();
+
+        let order_input = |price: i64, variant_id: Option<DbUuid>| SalesOrderNewInput {
+            customer_id: None,
+            customer_name: None,
+            customer_phone_number: None,
+            billing_address: None,
+            shipping_address: None,
+            order_date: now,
+            net_amount: price.into(),
+            disc_amount: 0.into(),
+            taxable_amount: price.into(),
+            tax_amount: 0.into(),
+            total_amount: price.into(),
+            notes: None,
+            channel_id: channel.id,
+            location_id: location.id,
+            cost_center_id: cost_center.id,
+            discount_id: None,
+            items: vec![SalesOrderItemInput {
+                item_id: Some(item.id),
+                variant_id,
+                item_name: item.name.clone(),
+                quantity: 1,
+                sku: None,
+                price_amount: price.into(),
+                disc_amount: 0.into(),
+                taxable_amount: price.into(),
+                tax_amount: 0.into(),
+                total_amount: price.into(),
+                price_override: None,
+            }],
+            charges: None,
+        };
+
+        for (price, variant_id) in [(800, None), (1000, Some(variant.id)), (1500, None)] {
+            let result = CreateSalesOrderCommand { sales_order: order_input(price, variant_id), created_by_user_id: user_id }
+                .exec(&service)
+                .await;
+            assert!(matches!(result, Err(Error::ValidationError(_))), "{}", price);
+        }
+
+        // The variant is priced with its adjustment
+        for (price, variant_id) in [(1000, None), (1500, Some(variant.id))] {
+            let order = CreateSalesOrderCommand { sales_order: order_input(price, variant_id), created_by_user_id: user_id }
+                .exec(&service)
+                .await
+                .unwrap();
+            assert_eq!(order.total_amount, Money::from(price));
+        }
+    }
+
     #[tokio::test]
     async fn test_void_sales_order() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let now = Utc::now().naive_utc();
-        let cost_center = create_test_cost_center(&mut service).await;
-        let user_id = create_test_user(&mut service).await;
-        let channel = create_test_channel(&mut service).await;
-        let location = create_test_location(&mut service).await;
+        let cost_center = create_test_cost_center(&service).await;
+        let user_id = create_test_user(&service).await;
+        let channel = create_test_channel(&service).await;
+        let location = create_test_location(&service).await;
 
         let input = SalesOrderNewInput {
             customer_id: None,
@@ -723,6 +1319,7 @@ mod tests {
             discount_id: None,
             items: vec![SalesOrderItemInput {
                 item_id: None, // Don't use item_id to avoid foreign key constraint issues
+                variant_id: None,
                 item_name: "Item 1".to_string(),
                 quantity: 1,
                 sku: None,
@@ -731,6 +1328,7 @@ mod tests {
                 taxable_amount: 1000.into(),
                 tax_amount: 100.into(),
                 total_amount: 1100.into(),
+                price_override: None,
             }],
             charges: None,
         };
@@ -739,27 +1337,89 @@ mod tests {
             sales_order: input,
             created_by_user_id: user_id,
         };
-        let created = create_cmd.exec(&mut service).await.unwrap();
+        let created = create_cmd.exec(&service).await.unwrap();
 
         let void_cmd = VoidSalesOrderCommand {
             id: created.id,
             updated_by_user_id: user_id,
         };
-        let voided = void_cmd.exec(&mut service).await.unwrap();
+        let voided = void_cmd.exec(&service).await.unwrap();
 
         assert_eq!(voided.order_state, SalesOrderState::Cancelled);
         assert_eq!(voided.payment_state, SalesOrderPaymentState::Voided);
         assert_eq!(voided.updated_by, user_id);
     }
 
+    #[tokio::test]
+    async fn test_void_sales_order_publishes_event() {
+        let service = setup_service().await;
+        let cost_center = create_test_cost_center(&service).await;
+        let user_id = create_test_user(&service).await;
+        let channel = create_test_channel(&service).await;
+        let location = create_test_location(&service).await;
+
+        let input = SalesOrderNewInput {
+            customer_id: None,
+            customer_name: None,
+            customer_phone_number: None,
+            billing_address: None,
+            shipping_address: None,
+            order_date: Utc::now().naive_utc(),
+            net_amount: 1000.into(),
+            disc_amount: 0.into(),
+            taxable_amount: 1000.into(),
+            tax_amount: 0.into(),
+            total_amount: 1000.into(),
+            notes: None,
+            channel_id: channel.id,
+            location_id: location.id,
+            cost_center_id: cost_center.id,
+            discount_id: None,
+            items: vec![SalesOrderItemInput {
+                item_id: None,
+                variant_id: None,
+                item_name: "Item 1".to_string(),
+                quantity: 1,
+                sku: None,
+                price_amount: 1000.into(),
+                disc_amount: 0.into(),
+                taxable_amount: 1000.into(),
+                tax_amount: 0.into(),
+                total_amount: 1000.into(),
+                price_override: None,
+            }],
+            charges: None,
+        };
+        let created = CreateSalesOrderCommand { sales_order: input, created_by_user_id: user_id }
+            .exec(&service)
+            .await
+            .unwrap();
+
+        let mut events = service.events.subscribe();
+        VoidSalesOrderCommand { id: created.id, updated_by_user_id: user_id }
+            .exec(&service)
+            .await
+            .unwrap();
+
+        // The line has no catalog item, so no stock moves
+        match events.recv().await.unwrap() {
+            Event::OrderVoided(order) => {
+                assert_eq!(order.id, created.id);
+                assert_eq!(order.order_state, SalesOrderState::Cancelled);
+            }
+            event => panic!("Unexpected event {:?}", event),
+        }
+        assert!(events.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn test_void_already_cancelled_order() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
         let now = Utc::now().naive_utc();
-        let cost_center = create_test_cost_center(&mut service).await;
-        let user_id = create_test_user(&mut service).await;
-        let channel = create_test_channel(&mut service).await;
-        let location = create_test_location(&mut service).await;
+        let cost_center = create_test_cost_center(&service).await;
+        let user_id = create_test_user(&service).await;
+        let channel = create_test_channel(&service).await;
+        let location = create_test_location(&service).await;
 
         let input = SalesOrderNewInput {
             customer_id: None,
@@ -780,6 +1440,7 @@ mod tests {
             discount_id: None,
             items: vec![SalesOrderItemInput {
                 item_id: None, // Don't use item_id to avoid foreign key constraint issues
+                variant_id: None,
                 item_name: "Item 1".to_string(),
                 quantity: 1,
                 sku: None,
@@ -788,6 +1449,7 @@ mod tests {
                 taxable_amount: 1000.into(),
                 tax_amount: 100.into(),
                 total_amount: 1100.into(),
+                price_override: None,
             }],
             charges: None,
         };
@@ -796,29 +1458,29 @@ mod tests {
             sales_order: input,
             created_by_user_id: user_id,
         };
-        let created = create_cmd.exec(&mut service).await.unwrap();
+        let created = create_cmd.exec(&service).await.unwrap();
 
         let void_cmd = VoidSalesOrderCommand {
             id: created.id,
             updated_by_user_id: user_id,
         };
-        let _ = void_cmd.exec(&mut service).await.unwrap();
+        let _ = void_cmd.exec(&service).await.unwrap();
 
-        let result = void_cmd.exec(&mut service).await;
+        let result = void_cmd.exec(&service).await;
         assert!(matches!(result, Err(Error::NotFoundError)));
     }
 
     #[tokio::test]
     async fn test_void_non_existent_order() {
-        let mut service = setup_service().await;
-        let user_id = create_test_user(&mut service).await;
+        let service = setup_service().await;
+        let user_id = create_test_user(&service).await;
 
         let cmd = VoidSalesOrderCommand {
             id: Uuid::now_v7().into(),
             updated_by_user_id: user_id,
         };
 
-        let result = cmd.exec(&mut service).await;
+        let result = cmd.exec(&service).await;
         assert!(matches!(result, Err(Error::NotFoundError)));
     }
 }
@@ -0,0 +1,326 @@
+//! Backups of the local database
+//!
+//! A backup is a consistent copy taken with `VACUUM INTO` while the app keeps running. It is
+//! saved in the backup directory as `<db>-<kind>-<timestamp>[-<label>].db`, so the backups
+//! of each kind sort oldest first by name. With a passphrase the copy is sealed with
+//! AES-256-GCM under a key derived from the passphrase, and `.enc` is added to the name.
+//!
+//! Restoring replaces the database file, so it is done with the app stopped. The database
+//! being replaced is kept next to it, with `.before-restore-<timestamp>` added to its name.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{NaiveDateTime, Utc};
+
+use crate::{
+    adapters::outgoing::secrets::{SecretName, SecretStore},
+    core::{
+        db::{
+            encryption,
+            migrations::{self, MigrationState},
+        },
+        models::common::backup_model::{Backup, BackupKind},
+        utils::crypto,
+    },
+    error::{Error, Result},
+};
+
+/// Backups of each kind kept when `BACKUP_KEEP` is not set
+const DEFAULT_KEEP: usize = 7;
+
+/// Hours between scheduled backups when `BACKUP_INTERVAL_HOURS` is not set
+const DEFAULT_INTERVAL_HOURS: u64 = 24;
+
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%3f";
+
+// Every encrypted backup starts with this
+const MAGIC: &[u8] = b"MNLBAK01";
+
+/// Where backups go, how many are kept and how often they are taken
+#[derive(Debug, Clone)]
+pub struct BackupConfig {
+    pub dir: PathBuf,
+    /// Backups of each kind to keep. Older ones are deleted after each backup.
+    pub keep: usize,
+    /// Time between scheduled backups, or `None` when they are turned off
+    pub interval: Option<Duration>,
+    /// Encrypts backups when set
+    pub passphrase: Option<String>,
+}
+
+impl BackupConfig {
+    /// Reads `BACKUP_DIR` (default `backups` next to the database), `BACKUP_KEEP` and
+    /// `BACKUP_INTERVAL_HOURS` (0 turns scheduled backups off). The passphrase comes from the
+    /// secret store.
+    pub fn load(db_path: &str, secrets: &dyn SecretStore) -> Result<Self> {
+        let var = |name| std::env::var(name).ok().filter(|value: &String| !value.is_empty());
+
+        let dir = var("BACKUP_DIR").map(PathBuf::from).unwrap_or_else(|| {
+            let parent = Path::new(db_path).parent().unwrap_or(Path::new(""));
+            parent.join("backups")
+        });
+        let keep = var("BACKUP_KEEP")
+            .and_then(|keep| keep.parse::<usize>().ok())
+            .filter(|keep| *keep > 0)
+            .unwrap_or(DEFAULT_KEEP);
+        let hours = var("BACKUP_INTERVAL_HOURS")
+            .and_then(|hours| hours.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_INTERVAL_HOURS);
+        let interval = (hours > 0).then(|| Duration::from_secs(hours * 60 * 60));
+
+        let passphrase = secrets.get(SecretName::BackupPassphrase)?.map(|passphrase| passphrase.expose().to_string());
+
+        Ok(BackupConfig { dir, keep, interval, passphrase })
+    }
+}
+
+/// The file name of a backup of the database at `db_path`
+pub fn file_name(db_path: &str, kind: BackupKind, label: Option<&str>, created_at: NaiveDateTime, encrypted: bool) -> String {
+    let mut name = format!("{}-{}-{}", stem(db_path), kind_name(kind), created_at.format(TIMESTAMP_FORMAT));
+    if let Some(label) = label {
+        name.push('-');
+        name.push_str(label);
+    }
+    name.push_str(if encrypted { ".db.enc" } else { ".db" });
+    name
+}
+
+/// A label cut down to lowercase letters, digits and dashes, so it is safe in a file name
+pub fn clean_label(label: &str) -> Option<String> {
+    let mut clean = String::new();
+    for c in label.trim().chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            clean.push(c);
+        } else if !clean.is_empty() && !clean.ends_with('-') {
+            clean.push('-');
+        }
+    }
+    clean.truncate(40);
+    let clean = clean.trim_end_matches('-');
+    (!clean.is_empty()).then(|| clean.to_string())
+}
+
+/// The backups of the database at `db_path` in the backup directory, newest first
+pub fn list(config: &BackupConfig, db_path: &str) -> Result<Vec<Backup>> {
+    let entries = match fs::read_dir(&config.dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut backups = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if let Some(backup) = parse(db_path, &path) {
+            backups.push(backup);
+        }
+    }
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    Ok(backups)
+}
+
+/// Whether the newest scheduled backup is at least an interval old, or there is none
+pub fn is_due(config: &BackupConfig, db_path: &str) -> Result<bool> {
+    let Some(interval) = config.interval else {
+        return Ok(false);
+    };
+    let interval = chrono::Duration::from_std(interval).unwrap_or(chrono::Duration::MAX);
+    let newest = list(config, db_path)?.into_iter().find(|backup| backup.kind == BackupKind::Scheduled);
+    Ok(newest.is_none_or(|backup| backup.created_at + interval <= Utc::now().naive_utc()))
+}
+
+/// Deletes all but the newest `keep` backups of `kind`. Returns the paths deleted.
+pub fn rotate(config: &BackupConfig, db_path: &str, kind: BackupKind) -> Result<Vec<PathBuf>> {
+    let mut deleted = Vec::new();
+    for backup in list(config, db_path)?.into_iter().filter(|backup| backup.kind == kind).skip(config.keep) {
+        fs::remove_file(&backup.path)?;
+        deleted.push(PathBuf::from(backup.path));
+    }
+    Ok(deleted)
+}
+
+/// Seals `plain` with a key derived from `passphrase`
+pub fn encrypt(plain: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    crypto::seal(plain, passphrase, MAGIC)
+}
+
+/// Opens a backup sealed by [`encrypt`]
+pub fn decrypt(sealed: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    if !is_encrypted(sealed) {
+        return Err(Error::ValidationError("The file is not an encrypted backup".to_string()));
+    }
+    crypto::open(sealed, passphrase, MAGIC)
+}
+
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Replaces the database at `db_path` with `backup`, after checking the backup is sound and
+/// was made by a build this one can migrate forward. Returns where the replaced database was
+/// moved, if there was one.
+pub async fn restore(
+    db_path: &str,
+    backup: &Path,
+    passphrase: Option<&str>,
+    secrets: &dyn SecretStore,
+) -> Result<Option<PathBuf>> {
+    let bytes = fs::read(backup)?;
+    let plain = match (is_encrypted(&bytes), passphrase) {
+        (false, _) => bytes,
+        (true, Some(passphrase)) => decrypt(&bytes, passphrase)?,
+        (true, None) => {
+            return Err(Error::ValidationError(
+                "The backup is encrypted. Put its passphrase in the secret store to restore it.".to_string(),
+            ));
+        }
+    };
+
+    let staged = PathBuf::from(format!("{}.restore", db_path));
+    fs::write(&staged, plain)?;
+    if let Err(e) = check_restorable(&staged, secrets).await {
+        let _ = fs::remove_file(&staged);
+        return Err(e);
+    }
+
+    // The write-ahead log goes along with the database it belongs to
+    let mut moved = None;
+    if Path::new(db_path).exists() {
+        let aside = format!("{}.before-restore-{}", db_path, Utc::now().format(TIMESTAMP_FORMAT));
+        for suffix in ["-wal", "-shm"] {
+            let side = format!("{}{}", db_path, suffix);
+            if Path::new(&side).exists() {
+                fs::rename(&side, format!("{}{}", aside, suffix))?;
+            }
+        }
+        fs::rename(db_path, &aside)?;
+        moved = Some(PathBuf::from(aside));
+    }
+    fs::rename(&staged, db_path)?;
+
+    Ok(moved)
+}
+
+// Fails unless the database at `path` passes an integrity check and has no migrations
+// beyond, or different from, the ones built in. Pending migrations are applied when the
+// app next opens it.
+async fn check_restorable(path: &Path, secrets: &dyn SecretStore) -> Result<()> {
+    let db = encryption::open_local(path, secrets).await?;
+    let conn = db.connect()?;
+
+    let mut rows = conn.query("PRAGMA integrity_check", ()).await?;
+    let result: Option<String> = match rows.next().await? {
+        Some(row) => Some(row.get(0)?),
+        None => None,
+    };
+    if result.as_deref() != Some("ok") {
+        return Err(Error::ValidationError("The backup failed its integrity check".to_string()));
+    }
+
+    let mut rows = conn
+        .query("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'libsql_migrations'", ())
+        .await?;
+    if rows.next().await?.is_none() {
+        return Err(Error::ValidationError("The file is not a backup of this app's database".to_string()));
+    }
+
+    for status in migrations::status(&conn).await? {
+        match status.state {
+            MigrationState::Unknown => {
+                return Err(Error::ValidationError(format!(
+                    "The backup has migration {}, which this build does not. Restore it with the version that made it.",
+                    status.id
+                )));
+            }
+            MigrationState::Changed => {
+                return Err(Error::ValidationError(format!(
+                    "Migration {} in the backup differs from the one in this build",
+                    status.id
+                )));
+            }
+            MigrationState::Applied | MigrationState::Pending => {}
+        }
+    }
+
+    Ok(())
+}
+
+// Reads a backup of the database at `db_path` from its file name
+fn parse(db_path: &str, path: &Path) -> Option<Backup> {
+    let name = path.file_name()?.to_str()?;
+    let (name, encrypted) = match name.strip_suffix(".db.enc") {
+        Some(name) => (name, true),
+        None => (name.strip_suffix(".db")?, false),
+    };
+    let rest = name.strip_prefix(&stem(db_path))?.strip_prefix('-')?;
+    let (kind, rest) = rest.split_once('-')?;
+    let kind = [BackupKind::Scheduled, BackupKind::Snapshot]
+        .into_iter()
+        .find(|candidate| kind_name(*candidate) == kind)?;
+
+    let timestamp_len = "yyyymmdd-hhmmssfff".len();
+    if rest.len() < timestamp_len || !rest.is_char_boundary(timestamp_len) {
+        return None;
+    }
+    let (timestamp, label) = rest.split_at(timestamp_len);
+    let created_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+    let label = match label {
+        "" => None,
+        label => Some(label.strip_prefix('-')?.to_string()),
+    };
+
+    Some(Backup { path: path.to_string_lossy().into_owned(), kind, label, created_at, encrypted })
+}
+
+// The database file name without its extension, kept to characters safe in any file name
+fn stem(db_path: &str) -> String {
+    let stem = Path::new(db_path).file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+    let stem: String = stem.chars().filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_')).collect();
+    if stem.is_empty() { "database".to_string() } else { stem }
+}
+
+fn kind_name(kind: BackupKind) -> &'static str {
+    match kind {
+        BackupKind::Scheduled => "scheduled",
+        BackupKind::Snapshot => "snapshot",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        let sealed = encrypt(b"SQLite format 3", "correct horse").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert_eq!(decrypt(&sealed, "correct horse").unwrap(), b"SQLite format 3");
+
+        let result = decrypt(&sealed, "wrong horse");
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_file_names_round_trip() {
+        let created_at = NaiveDateTime::parse_from_str("2025-03-14 10:15:00.123", "%Y-%m-%d %H:%M:%S%.3f").unwrap();
+        let label = clean_label("  Before Import: items.csv ").unwrap();
+        assert_eq!(label, "before-import-items-csv");
+
+        let name = file_name("data/minnal.db", BackupKind::Snapshot, Some(&label), created_at, true);
+        assert_eq!(name, "minnal-snapshot-20250314-101500123-before-import-items-csv.db.enc");
+
+        let backup = parse("data/minnal.db", Path::new(&name)).unwrap();
+        assert_eq!(backup.kind, BackupKind::Snapshot);
+        assert_eq!(backup.label.as_deref(), Some("before-import-items-csv"));
+        assert_eq!(backup.created_at, created_at);
+        assert!(backup.encrypted);
+
+        assert!(parse("data/minnal.db", Path::new("other-snapshot-20250314-101500123.db")).is_none());
+        assert!(parse("data/minnal.db", Path::new("minnal.db")).is_none());
+        assert_eq!(clean_label(" -- "), None);
+    }
+}
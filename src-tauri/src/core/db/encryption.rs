@@ -0,0 +1,236 @@
+//! Opening the local database, encrypted at rest when the `encryption` feature is built in,
+//! as it is in release builds
+//!
+//! Such a build encrypts the database with a random key kept in the secret store. A database
+//! found in plaintext, such as one made before the upgrade or restored from a plaintext
+//! backup, is encrypted in place the first time it is opened. Builds without the feature
+//! refuse an encrypted database rather than fail on it later.
+
+use std::{fs::File, io::Read, path::Path};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::{
+    adapters::outgoing::secrets::{Secret, SecretName, SecretStore},
+    core::utils::crypto,
+    error::{Error, Result},
+};
+
+// The first bytes of every plaintext SQLite database
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// Opens the local database at `path`
+pub async fn open_local(path: impl AsRef<Path>, secrets: &dyn SecretStore) -> Result<libsql::Database> {
+    let path = path.as_ref();
+    #[cfg(feature = "encryption")]
+    encrypt_plaintext(path, secrets).await?;
+    let builder = libsql::Builder::new_local(path);
+    #[cfg(feature = "encryption")]
+    let builder = match database_key(path, secrets)? {
+        Some(key) => builder.encryption_config(encryption_config(&key)?),
+        None => builder,
+    };
+    #[cfg(not(feature = "encryption"))]
+    refuse_encrypted(path, secrets)?;
+
+    Ok(builder.build().await?)
+}
+
+/// Opens the local database at `path` as a replica of the database at `url`
+pub async fn open_replica(
+    path: impl AsRef<Path>,
+    url: &Secret,
+    token: &Secret,
+    secrets: &dyn SecretStore,
+) -> Result<libsql::Database> {
+    let path = path.as_ref();
+    #[cfg(feature = "encryption")]
+    encrypt_plaintext(path, secrets).await?;
+    let builder = libsql::Builder::new_remote_replica(path, url.expose().to_string(), token.expose().to_string());
+    #[cfg(feature = "encryption")]
+    let builder = match database_key(path, secrets)? {
+        Some(key) => builder.encryption_config(encryption_config(&key)?),
+        None => builder,
+    };
+    #[cfg(not(feature = "encryption"))]
+    refuse_encrypted(path, secrets)?;
+
+    Ok(builder.build().await?)
+}
+
+/// The key the database at `path` is, or is to be, encrypted with. `None` for an in-memory
+/// or plaintext database. A key is made for a new database when encryption is built in.
+pub fn database_key(path: &Path, secrets: &dyn SecretStore) -> Result<Option<Secret>> {
+    if path == Path::new(":memory:") {
+        return Ok(None);
+    }
+    match is_plaintext(path)? {
+        Some(true) => Ok(None),
+        Some(false) => match secrets.get(SecretName::DatabaseKey)? {
+            Some(key) => Ok(Some(key)),
+            None => Err(Error::SecretStoreError(format!(
+                "{} is encrypted, but there is no database key in the secret store",
+                path.display()
+            ))),
+        },
+        None if cfg!(feature = "encryption") => Ok(Some(key_or_new(secrets)?)),
+        None => Ok(None),
+    }
+}
+
+// The database key in `secrets`, made and saved there if there is none
+fn key_or_new(secrets: &dyn SecretStore) -> Result<Secret> {
+    if let Some(key) = secrets.get(SecretName::DatabaseKey)? {
+        return Ok(key);
+    }
+    let key = Secret::new(STANDARD.encode(crypto::random_bytes(32)?));
+    secrets.set(SecretName::DatabaseKey, &key)?;
+    Ok(key)
+}
+
+// Whether the database at `path` is stored in plaintext, or `None` when it is yet to be made
+fn is_plaintext(path: &Path) -> Result<Option<bool>> {
+    let mut header = Vec::with_capacity(SQLITE_HEADER.len());
+    match File::open(path) {
+        Ok(file) => file.take(SQLITE_HEADER.len() as u64).read_to_end(&mut header)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    // SQLite writes nothing to a new database until the first table is made
+    if header.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(header == SQLITE_HEADER))
+}
+
+// The raw bytes of the database key
+#[cfg(feature = "encryption")]
+fn key_bytes(key: &Secret) -> Result<Vec<u8>> {
+    STANDARD
+        .decode(key.expose())
+        .map_err(|_| Error::SecretStoreError("The database key is not base64".to_string()))
+}
+
+#[cfg(feature = "encryption")]
+fn encryption_config(key: &Secret) -> Result<libsql::EncryptionConfig> {
+    Ok(libsql::EncryptionConfig::new(libsql::Cipher::Aes256Cbc, bytes::Bytes::from(key_bytes(key)?)))
+}
+
+// Encrypts a plaintext database at `path` in place, under the database key, which is made if
+// there is none yet. Runs once: afterwards the database is no longer plaintext.
+#[cfg(feature = "encryption")]
+async fn encrypt_plaintext(path: &Path, secrets: &dyn SecretStore) -> Result<()> {
+    if path == Path::new(":memory:") || is_plaintext(path)? != Some(true) {
+        return Ok(());
+    }
+    let hex: String = key_bytes(&key_or_new(secrets)?)?.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    let db = libsql::Builder::new_local(path).build().await?;
+    let conn = db.connect()?;
+    // Rekeying needs a rollback journal; this also folds the WAL into the database file.
+    // A pragma's error only shows once its row is read.
+    let pragmas = [
+        "PRAGMA journal_mode = DELETE".to_string(),
+        "PRAGMA cipher = 'aes256cbc'".to_string(),
+        format!("PRAGMA hexrekey = '{}'", hex),
+    ];
+    for pragma in pragmas {
+        conn.query(&pragma, ()).await?.next().await?;
+    }
+    drop(conn);
+    drop(db);
+
+    if is_plaintext(path)? == Some(true) {
+        return Err(Error::DatabaseError(format!("Failed to encrypt {}", path.display())));
+    }
+    eprintln!("Encrypted {} at rest", path.display());
+    Ok(())
+}
+
+#[cfg(not(feature = "encryption"))]
+fn refuse_encrypted(path: &Path, secrets: &dyn SecretStore) -> Result<()> {
+    match database_key(path, secrets) {
+        Ok(None) => Ok(()),
+        Ok(Some(_)) | Err(Error::SecretStoreError(_)) => Err(Error::DatabaseError(format!(
+            "{} is encrypted, and this build has no encryption support",
+            path.display()
+        ))),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::adapters::outgoing::secrets::MemorySecretStore;
+
+    #[tokio::test]
+    async fn test_database_key() {
+        let dir = std::env::temp_dir().join(format!("minnal-encryption-{}", Uuid::now_v7()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let secrets = MemorySecretStore::default();
+        let path = dir.join("minnal.db");
+
+        assert_eq!(database_key(Path::new(":memory:"), &secrets).unwrap(), None);
+
+        let db = open_local(&path, &secrets).await.unwrap();
+        db.connect().unwrap().execute("CREATE TABLE t (id INTEGER)", ()).await.unwrap();
+        drop(db);
+        let key = database_key(&path, &secrets).unwrap();
+        if cfg!(feature = "encryption") {
+            assert!(key.is_some());
+            assert_eq!(key, secrets.get(SecretName::DatabaseKey).unwrap());
+        } else {
+            assert_eq!(key, None);
+            assert_eq!(secrets.get(SecretName::DatabaseKey).unwrap(), None);
+        }
+
+        // A database made before encryption was turned on stays readable
+        let plaintext = dir.join("plaintext.db");
+        let db = libsql::Builder::new_local(&plaintext).build().await.unwrap();
+        db.connect().unwrap().execute("CREATE TABLE t (id INTEGER)", ()).await.unwrap();
+        drop(db);
+        assert_eq!(database_key(&plaintext, &secrets).unwrap(), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "encryption")]
+    #[tokio::test]
+    async fn test_plaintext_database_is_encrypted_once() {
+        let dir = std::env::temp_dir().join(format!("minnal-encryption-{}", Uuid::now_v7()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let secrets = MemorySecretStore::default();
+        let path = dir.join("minnal.db");
+
+        let db = libsql::Builder::new_local(&path).build().await.unwrap();
+        let conn = db.connect().unwrap();
+        conn.query("PRAGMA journal_mode = WAL", ()).await.unwrap();
+        conn.execute("CREATE TABLE t (name TEXT)", ()).await.unwrap();
+        conn.execute("INSERT INTO t VALUES ('tea')", ()).await.unwrap();
+        drop(conn);
+        drop(db);
+        assert_eq!(is_plaintext(&path).unwrap(), Some(true));
+
+        let db = open_local(&path, &secrets).await.unwrap();
+        assert_eq!(is_plaintext(&path).unwrap(), Some(false));
+        let mut rows = db.connect().unwrap().query("SELECT name FROM t", ()).await.unwrap();
+        let row = rows.next().await.unwrap().unwrap();
+        assert_eq!(row.get::<String>(0).unwrap(), "tea");
+        drop(rows);
+        drop(db);
+
+        // Opened again with the same key, and without it not at all
+        let key = secrets.get(SecretName::DatabaseKey).unwrap();
+        assert!(key.is_some());
+        open_local(&path, &secrets).await.unwrap();
+        assert_eq!(secrets.get(SecretName::DatabaseKey).unwrap(), key);
+        let db = libsql::Builder::new_local(&path).build().await.unwrap();
+        let rows = db.connect().unwrap().query("SELECT name FROM t", ()).await;
+        assert!(rows.is_err() || rows.unwrap().next().await.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
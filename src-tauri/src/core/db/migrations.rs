@@ -1,128 +1,438 @@
+//! Schema migrations, embedded in the binary at compile time
+//!
+//! Every `migrations/NNNN_name.sql` script is built in by `build.rs`, along with the optional
+//! `NNNN_name.down.sql` that reverts it. Applied migrations are recorded in
+//! `libsql_migrations` with a checksum of their script, so a script edited after it was
+//! applied is reported instead of silently diverging from the database.
+
 use libsql::Connection;
-use std::fs;
-use std::io;
-use std::path::PathBuf;
-
-/// Custom migration function that properly handles SQL files with multiple statements
-pub async fn run_migrations(conn: &Connection) -> Result<bool, Box<dyn std::error::Error>> {
-    // Specify the path to the migrations folder
-    let migrations_folder = PathBuf::from("./migrations");
-    
-    // Ensure the migrations table exists
+use ring::digest::{digest, SHA256};
+
+use crate::error::{Error, Result};
+
+/// A migration script and the script that reverts it
+#[derive(Debug)]
+pub struct Migration {
+    /// The file name of the script, which is also its id in `libsql_migrations`
+    pub id: &'static str,
+    pub up: &'static str,
+    pub down: Option<&'static str>,
+}
+
+/// Every migration, in the order they are applied
+pub static MIGRATIONS: &[Migration] = include!(concat!(env!("OUT_DIR"), "/migrations.rs"));
+
+impl Migration {
+    /// Hex SHA-256 of the up script. Carriage returns are left out, so a checkout with
+    /// Windows line endings has the same checksum.
+    pub fn checksum(&self) -> String {
+        checksum(self.up)
+    }
+}
+
+fn checksum(script: &str) -> String {
+    let script = script.replace('\r', "");
+    digest(&SHA256, script.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// Applied, but the script has changed since
+    Changed,
+    /// Applied to the database, but not built into this binary
+    Unknown,
+}
+
+#[derive(Debug)]
+pub struct MigrationStatus {
+    pub id: String,
+    pub state: MigrationState,
+    pub reversible: bool,
+}
+
+/// Applies every pending migration. Returns whether any was applied.
+pub async fn run_migrations(conn: &Connection) -> Result<bool> {
+    let applied = migrate_up(conn, None).await?;
+    Ok(!applied.is_empty())
+}
+
+/// Applies pending migrations up to and including `to`, or all of them. Returns the ids of
+/// the migrations applied.
+pub async fn migrate_up(conn: &Connection, to: Option<&str>) -> Result<Vec<&'static str>> {
+    let count = match to {
+        Some(to) => find(to)? + 1,
+        None => MIGRATIONS.len(),
+    };
+    let applied = applied_migrations(conn).await?;
+    check_checksums(&applied)?;
+
+    let mut migrated = Vec::new();
+    for migration in &MIGRATIONS[..count] {
+        if applied.iter().any(|(id, _)| id == migration.id) {
+            continue;
+        }
+
+        let record = "INSERT INTO libsql_migrations (id, status, exec_time, checksum) \
+                      VALUES (?, 1, CURRENT_TIMESTAMP, ?) \
+                      ON CONFLICT(id) DO UPDATE SET status = 1, exec_time = CURRENT_TIMESTAMP, \
+                      checksum = excluded.checksum";
+        let params = libsql::params![migration.id, migration.checksum()];
+        run_in_transaction(conn, migration.up, record, params)
+            .await
+            .map_err(|e| Error::DatabaseError(format!("Migration {} failed: {}", migration.id, e)))?;
+
+        eprintln!("Applied migration: {}", migration.id);
+        migrated.push(migration.id);
+    }
+
+    Ok(migrated)
+}
+
+/// Reverts applied migrations newest first, down to but not including `to`, or only the
+/// newest one. Fails before reverting anything when one of them has no down script.
+pub async fn migrate_down(conn: &Connection, to: Option<&str>) -> Result<Vec<&'static str>> {
+    let applied = applied_migrations(conn).await?;
+    check_checksums(&applied)?;
+
+    let mut to_revert: Vec<&Migration> = MIGRATIONS
+        .iter()
+        .filter(|migration| applied.iter().any(|(id, _)| id == migration.id))
+        .collect();
+    match to {
+        Some(to) => {
+            let target = &MIGRATIONS[find(to)?];
+            to_revert.retain(|migration| migration.id > target.id);
+        }
+        None => to_revert = to_revert.split_off(to_revert.len().saturating_sub(1)),
+    }
+    to_revert.reverse();
+
+    if let Some(migration) = to_revert.iter().find(|migration| migration.down.is_none()) {
+        return Err(Error::ValidationError(format!("Migration {} has no down script", migration.id)));
+    }
+
+    let mut reverted = Vec::new();
+    for migration in to_revert {
+        let down = migration.down.unwrap_or_default();
+        let record = "DELETE FROM libsql_migrations WHERE id = ?";
+        run_in_transaction(conn, down, record, libsql::params![migration.id])
+            .await
+        .map_err(|e| Error::DatabaseError(format!("Reverting {} failed: {}", migration.id, e)))?;
+
+        eprintln!("Reverted migration: {}", migration.id);
+        reverted.push(migration.id);
+    }
+
+    Ok(reverted)
+}
+
+/// Every built-in migration and every migration recorded in the database, in order
+pub async fn status(conn: &Connection) -> Result<Vec<MigrationStatus>> {
+    let applied = applied_migrations(conn).await?;
+
+    let mut statuses: Vec<MigrationStatus> = MIGRATIONS
+        .iter()
+        .map(|migration| {
+            let state = match applied.iter().find(|(id, _)| id == migration.id) {
+                None => MigrationState::Pending,
+                Some((_, checksum)) if *checksum != migration.checksum() => MigrationState::Changed,
+                Some(_) => MigrationState::Applied,
+            };
+            MigrationStatus { id: migration.id.to_string(), state, reversible: migration.down.is_some() }
+        })
+        .collect();
+
+    for (id, _) in &applied {
+        if !MIGRATIONS.iter().any(|migration| migration.id == id) {
+            statuses.push(MigrationStatus { id: id.clone(), state: MigrationState::Unknown, reversible: false });
+        }
+    }
+    statuses.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Ok(statuses)
+}
+
+// The migration named by `id`, or by a prefix of it such as `0009`
+fn find(id: &str) -> Result<usize> {
+    let matches: Vec<usize> = (0..MIGRATIONS.len())
+        .filter(|&index| MIGRATIONS[index].id.starts_with(id))
+        .collect();
+
+    match matches.as_slice() {
+        [index] => Ok(*index),
+        [] => Err(Error::ValidationError(format!("No migration named {}", id))),
+        _ => Err(Error::ValidationError(format!("More than one migration is named {}", id))),
+    }
+}
+
+fn check_checksums(applied: &[(String, String)]) -> Result<()> {
+    for (id, checksum) in applied {
+        let changed = MIGRATIONS
+            .iter()
+            .find(|migration| migration.id == id)
+            .is_some_and(|migration| migration.checksum() != *checksum);
+        if changed {
+            return Err(Error::ValidationError(format!(
+                "Migration {} was changed after it was applied. Add a new migration instead.",
+                id
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Runs `script` and the `record` statement that keeps `libsql_migrations` up to date in one
+// transaction
+async fn run_in_transaction(
+    conn: &Connection,
+    script: &str,
+    record: &str,
+    params: impl libsql::params::IntoParams,
+) -> libsql::Result<()> {
+    conn.execute_batch("BEGIN TRANSACTION").await?;
+
+    let result = match conn.execute_batch(script).await {
+        Ok(_) => conn.execute(record, params).await.map(|_| ()),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(()) => conn.execute_batch("COMMIT").await.map(|_| ()),
+        Err(e) => {
+            let _ = conn.execute_batch("ROLLBACK").await;
+            Err(e)
+        }
+    }
+}
+
+/// The id and checksum of every applied migration
+async fn applied_migrations(conn: &Connection) -> Result<Vec<(String, String)>> {
     ensure_migrations_table(conn).await?;
-    
-    // Find and sort SQL files
-    let files_to_run = find_sql_files(migrations_folder.clone())?;
-    
-    if files_to_run.is_empty() {
-        return Ok(false);
-    }
-    
-    let mut did_new_migration = false;
-    
-    // Process each migration file
-    for file_path in files_to_run {
-        let file_name = file_path.file_name().unwrap().to_string_lossy().to_string();
-        
-        // Check if migration has already been applied
-        if !is_migration_applied(conn, &file_name).await? {
-            // Read the SQL file content
-            let sql_content = fs::read_to_string(&file_path)?;
-            
-            // Begin transaction
-            conn.execute_batch("BEGIN TRANSACTION").await?;
-            
-            match conn.execute_batch(&sql_content).await {
-                Ok(_) => {
-                    // Record the migration as applied
-                    record_migration(conn, &file_name).await?;
-                    eprintln!("Applied migration: {}", file_name);
-                    did_new_migration = true;
-                    
-                    // Commit transaction
-                    conn.execute_batch("COMMIT").await?;
-                },
-                Err(e) => {
-                    // Rollback on error
-                    let _ = conn.execute_batch("ROLLBACK").await;
-                    return Err(Box::new(e));
-                }
+
+    let mut applied = Vec::new();
+    let mut rows = conn
+        .query("SELECT id, checksum FROM libsql_migrations WHERE status = 1 ORDER BY id", ())
+        .await?;
+    while let Some(row) = rows.next().await? {
+        let id: String = row.get(0)?;
+        let checksum: Option<String> = row.get(1)?;
+        applied.push((id, checksum));
+    }
+
+    // Migrations applied before checksums were kept are taken to match the scripts built in
+    let mut checked = Vec::with_capacity(applied.len());
+    for (id, checksum) in applied {
+        let checksum = match (checksum, MIGRATIONS.iter().find(|migration| migration.id == id)) {
+            (Some(checksum), _) => checksum,
+            (None, Some(migration)) => {
+                conn.execute(
+                    "UPDATE libsql_migrations SET checksum = ? WHERE id = ?",
+                    libsql::params![migration.checksum(), id.clone()],
+                )
+                .await?;
+                migration.checksum()
             }
-        } else {
-            eprintln!("Migration already applied: {}", file_name);
-        }
+            (None, None) => String::new(),
+        };
+        checked.push((id, checksum));
     }
-    
-    Ok(did_new_migration)
+
+    Ok(checked)
 }
 
-/// Ensure the migrations table exists
-async fn ensure_migrations_table(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+/// Ensure the migrations table exists, with the checksum column added after the fact
+async fn ensure_migrations_table(conn: &Connection) -> Result<()> {
     let create_table_sql = r#"
     CREATE TABLE IF NOT EXISTS libsql_migrations (
         id TEXT PRIMARY KEY,
         status INTEGER DEFAULT 0,
-        exec_time TIMESTAMP
+        exec_time TIMESTAMP,
+        checksum TEXT
     );
     "#;
-    
     conn.execute_batch(create_table_sql).await?;
-    Ok(())
-}
 
-/// Check if a migration has already been applied
-async fn is_migration_applied(conn: &Connection, migration_id: &str) -> Result<bool, Box<dyn std::error::Error>> {
-    let mut stmt = conn
-        .prepare("SELECT status FROM libsql_migrations WHERE id = ?")
+    let mut rows = conn
+        .query("SELECT 1 FROM pragma_table_info('libsql_migrations') WHERE name = 'checksum'", ())
         .await?;
-    
-    let mut rows = stmt.query([migration_id]).await?;
-    
-    if let Some(row) = rows.next().await? {
-        let status: i64 = row.get(0)?;
-        Ok(status == 1)
-    } else {
-        Ok(false)
+    if rows.next().await?.is_none() {
+        conn.execute("ALTER TABLE libsql_migrations ADD COLUMN checksum TEXT", ()).await?;
     }
-}
 
-/// Record a migration as applied
-async fn record_migration(conn: &Connection, migration_id: &str) -> Result<(), Box<dyn std::error::Error>> {
-    conn.execute(
-        "INSERT INTO libsql_migrations (id, status, exec_time) VALUES (?, 1, CURRENT_TIMESTAMP) ON CONFLICT(id) DO UPDATE SET status = 1, exec_time = CURRENT_TIMESTAMP",
-        libsql::params![migration_id],
-    )
-    .await?;
-    
     Ok(())
 }
 
-/// Find and sort SQL files in the migrations directory
-fn find_sql_files(dir_path: PathBuf) -> Result<Vec<PathBuf>, io::Error> {
-    let mut file_paths: Vec<PathBuf> = vec![];
-    
-    if !dir_path.exists() {
-        return Err(io::Error::new(
-            io::ErrorKind::NotFound,
-            format!("Migrations directory not found: {:?}", dir_path),
-        ));
-    }
-    
-    for entry in fs::read_dir(dir_path)? {
-        let entry_path = entry?.path();
-        
-        if entry_path.is_file() && 
-           entry_path.extension().map_or(false, |ext| ext == "sql") {
-            file_paths.push(entry_path);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn connect() -> Connection {
+        let db = libsql::Builder::new_local(":memory:").build().await.unwrap();
+        db.connect().unwrap()
+    }
+
+    async fn table_exists(conn: &Connection, name: &str) -> bool {
+        let mut rows = conn
+            .query("SELECT 1 FROM sqlite_master WHERE name = ?", libsql::params![name])
+            .await
+            .unwrap();
+        rows.next().await.unwrap().is_some()
+    }
+
+    #[test]
+    fn test_migrations_are_embedded_in_order() {
+        assert!(MIGRATIONS.len() >= 10);
+        assert_eq!(MIGRATIONS[0].id, "0001_initial_schema.sql");
+        assert!(MIGRATIONS.windows(2).all(|pair| pair[0].id < pair[1].id));
+        assert!(MIGRATIONS.iter().all(|migration| !migration.id.ends_with(".down.sql")));
+        assert_eq!(checksum("SELECT 1;\r\n"), checksum("SELECT 1;\n"));
+    }
+
+    #[tokio::test]
+    async fn test_up_and_down() {
+        let conn = connect().await;
+
+        let applied = migrate_up(&conn, Some("0008")).await.unwrap();
+        assert_eq!(applied.last(), Some(&"0008_parked_carts.sql"));
+        assert!(!table_exists(&conn, "api_tokens").await);
+
+        migrate_up(&conn, None).await.unwrap();
+        assert!(table_exists(&conn, "api_tokens").await);
+        assert!(table_exists(&conn, "item_search").await);
+
+        let reverted = migrate_down(&conn, Some("0008")).await.unwrap();
+        assert_eq!(reverted.len(), MIGRATIONS.len() - 8);
+        assert!(!table_exists(&conn, "api_tokens").await);
+
+        let status = status(&conn).await.unwrap();
+        assert_eq!(status[7].state, MigrationState::Applied);
+        assert_eq!(status[8].state, MigrationState::Pending);
+
+        // Migrations without a down script are not reverted
+        let err = migrate_down(&conn, Some("0001")).await.unwrap_err();
+        assert!(err.to_string().contains("has no down script"));
+        assert!(table_exists(&conn, "users").await);
+    }
+
+    #[tokio::test]
+    async fn test_down_to_0003_keeps_carts() {
+        let conn = connect().await;
+        conn.execute("PRAGMA foreign_keys = ON", ()).await.unwrap();
+        migrate_up(&conn, None).await.unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO item_categories (id, name, state) VALUES ('food', 'Food', 'Active');
+             INSERT INTO items (id, category_id, name, nature, state, price)
+             VALUES ('tea', 'food', 'Tea', 'Goods', 'Active', 12000);
+             INSERT INTO customer_groups (id, name) VALUES ('trade', 'Trade');
+             INSERT INTO customers (id, full_name, customer_group_id) VALUES ('ann', 'Ann', 'trade');
+             INSERT INTO carts (id, customer_id, status) VALUES ('cart', 'ann', 'Parked');
+             INSERT INTO cart_lines (id, cart_id, item_id, quantity) VALUES ('line', 'cart', 'tea', 2);",
+        )
+        .await
+        .unwrap();
+
+        let reverted = migrate_down(&conn, Some("0003")).await.unwrap();
+        assert_eq!(reverted.len(), MIGRATIONS.len() - 3);
+        for table in ["price_override_reasons", "price_lists", "customer_groups", "currencies", "cart_lines"] {
+            assert!(!table_exists(&conn, table).await, "{}", table);
         }
+
+        {
+            let mut rows = conn.query("SELECT customer_id, cart_data FROM carts", ()).await.unwrap();
+            let row = rows.next().await.unwrap().unwrap();
+            assert_eq!(row.get::<String>(0).unwrap(), "ann");
+            assert_eq!(row.get::<String>(1).unwrap(), r#"{"items":[{"itemId":"tea","variantId":null,"quantity":2}]}"#);
+            assert!(rows.next().await.unwrap().is_none());
+        }
+
+        // Going up again imports the line back
+        migrate_up(&conn, None).await.unwrap();
+        let mut rows = conn.query("SELECT item_id, quantity FROM cart_lines WHERE cart_id = 'cart'", ()).await.unwrap();
+        let row = rows.next().await.unwrap().unwrap();
+        assert_eq!(row.get::<String>(0).unwrap(), "tea");
+        assert_eq!(row.get::<i64>(1).unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_prices_are_converted_to_base_units() {
+        use crate::{
+            adapters::outgoing::database::FromLibsqlValue,
+            core::types::{money::Money, percentage::Percentage},
+        };
+
+        let conn = connect().await;
+        migrate_up(&conn, Some("0014")).await.unwrap();
+
+        // As written before prices and rates were stored in base units
+        conn.execute_batch(
+            "INSERT INTO item_categories (id, name, state) VALUES ('food', 'Food', 'Active');
+             INSERT INTO items (id, category_id, name, nature, state, price)
+             VALUES ('tea', 'food', 'Tea', 'Goods', 'Active', '120'),
+                    ('cake', 'food', 'Cake', 'Goods', 'Active', '12.50');
+             INSERT INTO taxes (id, name, rate) VALUES ('gst', 'GST', '18');",
+        )
+        .await
+        .unwrap();
+
+        async fn value(conn: &Connection, sql: &str) -> libsql::Value {
+            conn.query(sql, ()).await.unwrap().next().await.unwrap().unwrap().get_value(0).unwrap()
+        }
+
+        migrate_up(&conn, None).await.unwrap();
+        let tea = value(&conn, "SELECT price FROM items WHERE id = 'tea'").await;
+        assert_eq!(Money::from_libsql_value(tea).unwrap(), Some(Money::from_str("120").unwrap()));
+        let cake = value(&conn, "SELECT price FROM items WHERE id = 'cake'").await;
+        assert_eq!(Money::from_libsql_value(cake).unwrap(), Some(Money::from_str("12.50").unwrap()));
+        let gst = value(&conn, "SELECT rate FROM taxes WHERE id = 'gst'").await;
+        assert_eq!(Percentage::from_libsql_value(gst).unwrap(), Some(Percentage::from_str("18").unwrap()));
+
+        migrate_down(&conn, Some("0014")).await.unwrap();
+        assert_eq!(value(&conn, "SELECT price FROM items WHERE id = 'tea'").await, libsql::Value::Integer(120));
+    }
+
+    #[tokio::test]
+    async fn test_changed_migrations_are_detected() {
+        let conn = connect().await;
+        migrate_up(&conn, None).await.unwrap();
+
+        conn.execute(
+            "UPDATE libsql_migrations SET checksum = 'edited' WHERE id = '0002_fix_foriegn_key.sql'",
+            (),
+        )
+        .await
+        .unwrap();
+
+        let err = run_migrations(&conn).await.unwrap_err();
+        assert!(err.to_string().contains("0002_fix_foriegn_key.sql was changed"));
+        let status = status(&conn).await.unwrap();
+        assert_eq!(status[1].state, MigrationState::Changed);
+    }
+
+    #[tokio::test]
+    async fn test_checksums_are_added_to_existing_databases() {
+        let conn = connect().await;
+        conn.execute_batch(
+            "CREATE TABLE libsql_migrations (id TEXT PRIMARY KEY, status INTEGER DEFAULT 0, exec_time TIMESTAMP);",
+        )
+        .await
+        .unwrap();
+        conn.execute_batch(MIGRATIONS[0].up).await.unwrap();
+        conn.execute(
+            "INSERT INTO libsql_migrations (id, status) VALUES ('0001_initial_schema.sql', 1)",
+            (),
+        )
+        .await
+        .unwrap();
+
+        let applied = migrate_up(&conn, None).await.unwrap();
+        assert_eq!(applied.len(), MIGRATIONS.len() - 1);
+        assert!(status(&conn).await.unwrap().iter().all(|status| status.state == MigrationState::Applied));
     }
-    
-    // Sort files by name to ensure correct order
-    file_paths.sort_by(|a, b| {
-        let a_name = a.file_name().unwrap_or_default();
-        let b_name = b.file_name().unwrap_or_default();
-        a_name.cmp(b_name)
-    });
-    
-    Ok(file_paths)
 }
@@ -1,4 +1,9 @@
 pub mod sea_query_crud;
+pub mod backup;
+pub mod encryption;
 pub mod migrations;
+pub mod pagination;
+pub mod row_version;
+pub mod soft_delete;
 
 pub use sea_query_crud::SeaQueryCrudTrait;
@@ -0,0 +1,421 @@
+//! Cursor pagination for list queries
+//!
+//! Lists are paged Relay style with `first`/`after` or `last`/`before`. A cursor is the
+//! opaque, encoded id of a row. Ids are UUIDv7, so ordering by id follows creation order;
+//! the id is also the tiebreaker when a list is sorted by another column. A page holds
+//! `DEFAULT_PAGE_SIZE` rows unless it asks for up to `MAX_PAGE_SIZE`.
+//!
+//! The filter, order-by and `Node` impls for each model are generated by `SeaQueryModel`.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use juniper::GraphQLEnum;
+use sea_query::{Cond, Expr, Iden, Order, Query, SelectStatement, SimpleExpr};
+use uuid::Uuid;
+
+use crate::{
+    adapters::outgoing::database::{DatabaseAdapter, FromRow},
+    core::{db::soft_delete::DeletedRows, types::db_uuid::DbUuid},
+    error::{Error, Result},
+};
+
+const CURSOR_PREFIX: &str = "cursor:";
+
+/// Rows in a page that gives neither `first` nor `last`
+pub const DEFAULT_PAGE_SIZE: i32 = 50;
+
+/// The most rows a page can ask for
+pub const MAX_PAGE_SIZE: i32 = 500;
+
+/// A row that can be pointed at by a cursor, generated for models with an `id`
+pub trait Node {
+    type Iden: Iden + Copy + 'static;
+
+    fn table() -> Self::Iden;
+    fn id_column() -> Self::Iden;
+    fn columns() -> Vec<Self::Iden>;
+    fn node_id(&self) -> DbUuid;
+
+    /// `deleted_at` for models that are soft deleted, see `soft_delete`
+    fn soft_delete_column() -> Option<Self::Iden> {
+        None
+    }
+}
+
+/// Narrows a list query, generated as `{Model}Filter`
+pub trait Filter {
+    fn apply(&self, query: &mut SelectStatement);
+
+    /// Whether soft deleted rows are listed, see `soft_delete::restrict_deleted`
+    fn deleted_rows(&self) -> DeletedRows {
+        DeletedRows::Exclude
+    }
+}
+
+/// Column and direction to sort a list by, generated as `{Model}OrderBy`
+pub trait Sort<C> {
+    fn sort(&self) -> (C, SortDirection);
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, GraphQLEnum)]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    fn reversed(self) -> Self {
+        match self {
+            SortDirection::Asc => SortDirection::Desc,
+            SortDirection::Desc => SortDirection::Asc,
+        }
+    }
+
+    pub fn order(self) -> Order {
+        match self {
+            SortDirection::Asc => Order::Asc,
+            SortDirection::Desc => Order::Desc,
+        }
+    }
+}
+
+/// The `first`/`after`/`last`/`before` arguments of a list query
+#[derive(Debug, Clone, Default)]
+pub struct PageRequest {
+    pub first: Option<i32>,
+    pub after: Option<String>,
+    pub last: Option<i32>,
+    pub before: Option<String>,
+}
+
+/// One page of rows with their cursors
+pub struct Page<T> {
+    pub edges: Vec<(String, T)>,
+    pub has_next_page: bool,
+    pub has_previous_page: bool,
+    pub total_count: i64,
+}
+
+pub fn encode_cursor(id: DbUuid) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{}{}", CURSOR_PREFIX, id))
+}
+
+pub fn decode_cursor(cursor: &str) -> Result<DbUuid> {
+    let invalid = || Error::ValidationError(format!("Invalid cursor '{}'", cursor));
+
+    let decoded = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+    let id = decoded.strip_prefix(CURSOR_PREFIX).ok_or_else(invalid)?;
+    let id = Uuid::parse_str(id).map_err(|_| invalid())?;
+
+    Ok(id.into())
+}
+
+/// Runs a list query one page at a time.
+///
+/// `query` selects from the table of `T` with any filters applied; paging and ordering are
+/// added here. Rows are sorted by `sort` (if any) and then by id in the same direction.
+pub async fn paginate<T, D>(
+    db: &D,
+    query: SelectStatement,
+    sort: Option<(T::Iden, SortDirection)>,
+    page: &PageRequest,
+) -> Result<Page<T>>
+where
+    T: FromRow<D::Row> + Node + Send,
+    i64: FromRow<D::Row>,
+    D: DatabaseAdapter,
+{
+    let (table, id) = (T::table(), T::id_column());
+
+    if page.first.is_some() && page.last.is_some() {
+        return Err(Error::ValidationError("Use either first or last, not both".to_string()));
+    }
+    if page.first.is_some_and(|first| first < 0) || page.last.is_some_and(|last| last < 0) {
+        return Err(Error::ValidationError("Page size cannot be negative".to_string()));
+    }
+    if page.first.or(page.last).is_some_and(|size| size > MAX_PAGE_SIZE) {
+        return Err(Error::ValidationError(format!("Page size cannot be more than {}", MAX_PAGE_SIZE)));
+    }
+
+    let mut count_query = query.clone();
+    count_query.clear_selects().expr(Expr::col((table, id)).count());
+    let total_count = db.query_one::<i64>(&count_query).await?;
+
+    let direction = sort.map_or(SortDirection::Asc, |(_, direction)| direction);
+    let backward = page.last.is_some();
+
+    let mut query = query;
+    if let Some(after) = &page.after {
+        let cursor = decode_cursor(after)?;
+        query.cond_where(beyond_cursor(table, id, sort, cursor, direction));
+    }
+    if let Some(before) = &page.before {
+        let cursor = decode_cursor(before)?;
+        query.cond_where(beyond_cursor(table, id, sort, cursor, direction.reversed()));
+    }
+
+    // Paging backwards reads the rows nearest to `before` first and flips them afterwards
+    let read_direction = if backward { direction.reversed() } else { direction };
+    if let Some((column, _)) = sort {
+        query.order_by((table, column), read_direction.order());
+    }
+    query.order_by((table, id), read_direction.order());
+
+    // One extra row tells whether there is another page
+    let size = page.first.or(page.last).unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+    query.limit(size as u64 + 1);
+
+    let mut rows = db.query_many::<T>(&query).await?;
+    let has_more = rows.len() > size;
+    rows.truncate(size);
+    if backward {
+        rows.reverse();
+    }
+
+    Ok(Page {
+        edges: rows.into_iter().map(|row| (encode_cursor(row.node_id()), row)).collect(),
+        has_next_page: if backward { page.before.is_some() } else { has_more },
+        has_previous_page: if backward { has_more } else { page.after.is_some() },
+        total_count,
+    })
+}
+
+// Rows that come after the cursor row when reading in `direction`
+fn beyond_cursor<C>(table: C, id: C, sort: Option<(C, SortDirection)>, cursor: DbUuid, direction: SortDirection) -> Cond
+where
+    C: Iden + Copy + 'static,
+{
+    let past = |column: C, value: SimpleExpr| match direction {
+        SortDirection::Asc => Expr::col((table, column)).gt(value),
+        SortDirection::Desc => Expr::col((table, column)).lt(value),
+    };
+    let cursor_id: SimpleExpr = cursor.to_string().into();
+
+    match sort {
+        None => Cond::all().add(past(id, cursor_id)),
+        Some((column, _)) => {
+            let cursor_value = SimpleExpr::SubQuery(
+                None,
+                Box::new(
+                    Query::select()
+                        .column(column)
+                        .from(table)
+                        .and_where(Expr::col(id).eq(cursor.to_string()))
+                        .to_owned()
+                        .into_sub_query_statement(),
+                ),
+            );
+
+            Cond::any().add(past(column, cursor_value.clone())).add(
+                Cond::all()
+                    .add(Expr::col((table, column)).eq(cursor_value))
+                    .add(past(id, cursor_id)),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        commands::{
+            sales::customer_commands::CreateCustomerCommand, tests::setup_service, AppService,
+            Command,
+        },
+        models::sales::customer_model::{
+            Customer, CustomerFilter, CustomerNewInput, CustomerOrderBy, CustomerOrderField,
+            Customers,
+        },
+    };
+
+    async fn create_customers(service: &AppService, names: &[&str]) {
+        for name in names {
+            let command = CreateCustomerCommand {
+                customer: CustomerNewInput {
+                    full_name: name.to_string(),
+                    email: None,
+                    phone: None,
+                    address: None,
+                    customer_group_id: None,
+                },
+            };
+            command.exec(service).await.unwrap();
+        }
+    }
+
+    async fn customer_page(
+        service: &AppService,
+        filter: Option<CustomerFilter>,
+        order_by: Option<CustomerOrderBy>,
+        page: PageRequest,
+    ) -> Result<Page<Customer>> {
+        let mut query = Query::select();
+        query.from(Customers::Table).columns(Customers::all_columns());
+        if let Some(filter) = filter {
+            filter.apply(&mut query);
+        }
+        let sort = order_by.map(|order_by| order_by.sort());
+        paginate(&service.db_adapter, query, sort, &page).await
+    }
+
+    fn names(page: &Page<Customer>) -> Vec<&str> {
+        page.edges.iter().map(|(_, customer)| customer.full_name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let id: DbUuid = Uuid::now_v7().into();
+        let cursor = encode_cursor(id);
+
+        assert!(!cursor.contains(&id.to_string()));
+        assert_eq!(decode_cursor(&cursor).unwrap(), id);
+    }
+
+    #[test]
+    fn test_decode_invalid_cursor() {
+        assert!(matches!(decode_cursor("not a cursor"), Err(Error::ValidationError(_))));
+        assert!(matches!(
+            decode_cursor(&URL_SAFE_NO_PAD.encode("cursor:nope")),
+            Err(Error::ValidationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_paginate_forward_and_backward() {
+        let service = setup_service().await;
+        create_customers(&service, &["A", "B", "C", "D", "E"]).await;
+
+        let first = PageRequest { first: Some(2), ..Default::default() };
+        let page = customer_page(&service, None, None, first).await.unwrap();
+        assert_eq!(names(&page), ["A", "B"]);
+        assert_eq!(page.total_count, 5);
+        assert!(page.has_next_page);
+        assert!(!page.has_previous_page);
+
+        let after = page.edges.last().unwrap().0.clone();
+        let next = PageRequest { first: Some(2), after: Some(after), ..Default::default() };
+        let page = customer_page(&service, None, None, next).await.unwrap();
+        assert_eq!(names(&page), ["C", "D"]);
+        assert!(page.has_next_page);
+        assert!(page.has_previous_page);
+
+        let before = page.edges.first().unwrap().0.clone();
+        let previous = PageRequest { last: Some(1), before: Some(before), ..Default::default() };
+        let page = customer_page(&service, None, None, previous).await.unwrap();
+        assert_eq!(names(&page), ["B"]);
+        assert!(page.has_next_page);
+        assert!(page.has_previous_page);
+
+        let last = PageRequest { last: Some(2), ..Default::default() };
+        let page = customer_page(&service, None, None, last).await.unwrap();
+        assert_eq!(names(&page), ["D", "E"]);
+        assert!(!page.has_next_page);
+        assert!(page.has_previous_page);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_sorted_and_filtered() {
+        let service = setup_service().await;
+        create_customers(&service, &["Bob", "Alice", "Bobby", "Carol", "Bo"]).await;
+
+        let order_by = || {
+            Some(CustomerOrderBy {
+                field: CustomerOrderField::FullName,
+                direction: Some(SortDirection::Desc),
+            })
+        };
+        let page = customer_page(&service, None, order_by(), PageRequest { first: Some(2), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(names(&page), ["Carol", "Bobby"]);
+
+        let after = page.edges.last().unwrap().0.clone();
+        let next = PageRequest { first: Some(2), after: Some(after), ..Default::default() };
+        let page = customer_page(&service, None, order_by(), next).await.unwrap();
+        assert_eq!(names(&page), ["Bob", "Bo"]);
+
+        let filter = CustomerFilter {
+            full_name_contains: Some("bob".to_string()),
+            ..Default::default()
+        };
+        let page = customer_page(&service, Some(filter), None, PageRequest::default()).await.unwrap();
+        assert_eq!(names(&page), ["Bob", "Bobby"]);
+        assert_eq!(page.total_count, 2);
+        assert!(!page.has_next_page);
+    }
+
+    #[tokio::test]
+    async fn test_offset_list_and_count() {
+        let service = setup_service().await;
+        create_customers(&service, &["Bob", "Alice", "Bobby", "Carol", "Bo"]).await;
+
+        let sort = Some((Customers::FullName, SortDirection::Asc));
+        let query = Customers::list(None, sort, Some(2), Some(1));
+        let customers = service.db_adapter.query_many::<Customer>(&query).await.unwrap();
+        let names: Vec<_> = customers.iter().map(|customer| customer.full_name.as_str()).collect();
+        assert_eq!(names, ["Bo", "Bob"]);
+
+        let filter = CustomerFilter {
+            full_name_in: Some(vec!["Bob".to_string(), "Carol".to_string(), "Dave".to_string()]),
+            ..Default::default()
+        };
+        let count = service.db_adapter.query_one::<i64>(&Customers::count(Some(&filter))).await.unwrap();
+        assert_eq!(count, 2);
+        let count = service.db_adapter.query_one::<i64>(&Customers::count(None)).await.unwrap();
+        assert_eq!(count, 5);
+
+        let bob = &customers[1];
+        let found = service.db_adapter.query_one::<Customer>(&Customers::find_by_id(bob.id)).await.unwrap();
+        assert_eq!(found.full_name, "Bob");
+    }
+
+    #[tokio::test]
+    async fn test_filter_by_exact_value_and_membership() {
+        let service = setup_service().await;
+        create_customers(&service, &["Bob", "Bobby", "Carol"]).await;
+
+        let filter = CustomerFilter { full_name: Some("Bob".to_string()), ..Default::default() };
+        let page = customer_page(&service, Some(filter), None, PageRequest::default()).await.unwrap();
+        assert_eq!(names(&page), ["Bob"]);
+
+        let ids = page.edges.iter().map(|(_, customer)| customer.id).collect();
+        let filter = CustomerFilter { id_in: Some(ids), ..Default::default() };
+        let page = customer_page(&service, Some(filter), None, PageRequest::default()).await.unwrap();
+        assert_eq!(names(&page), ["Bob"]);
+
+        let filter = CustomerFilter { id_in: Some(vec![]), ..Default::default() };
+        let page = customer_page(&service, Some(filter), None, PageRequest::default()).await.unwrap();
+        assert_eq!(page.total_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_rejects_first_and_last() {
+        let service = setup_service().await;
+
+        let page = PageRequest { first: Some(1), last: Some(1), ..Default::default() };
+        let result = customer_page(&service, None, None, page).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+
+        let page = PageRequest { first: Some(-1), ..Default::default() };
+        let result = customer_page(&service, None, None, page).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+
+        let page = PageRequest { last: Some(MAX_PAGE_SIZE + 1), ..Default::default() };
+        let result = customer_page(&service, None, None, page).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_paginate_defaults_page_size() {
+        let service = setup_service().await;
+        let names: Vec<String> = (0..=DEFAULT_PAGE_SIZE).map(|n| format!("Customer {:02}", n)).collect();
+        create_customers(&service, &names.iter().map(String::as_str).collect::<Vec<_>>()).await;
+
+        let page = customer_page(&service, None, None, PageRequest::default()).await.unwrap();
+        assert_eq!(page.edges.len(), DEFAULT_PAGE_SIZE as usize);
+        assert!(page.has_next_page);
+        assert_eq!(page.total_count, DEFAULT_PAGE_SIZE as i64 + 1);
+    }
+}
@@ -0,0 +1,52 @@
+//! Optimistic concurrency for rows that are edited from several terminals
+//!
+//! Items, customers and discounts carry a `version` that every update bumps. Their update
+//! inputs name the version the changes are based on, and the write only goes through while
+//! the row is still at that version. Otherwise it fails with a `ConflictError` carrying the
+//! row as it is now, so the client can show what changed and retry with the new version.
+
+use sea_query::{Expr, Iden, Query, UpdateStatement};
+use serde::Serialize;
+
+use crate::{
+    adapters::outgoing::database::{DatabaseAdapter, FromRow},
+    core::{db::pagination::Node, types::db_uuid::DbUuid},
+    error::{Conflict, Error, Result},
+};
+
+/// A model whose updates are checked against its `version`
+pub trait Versioned: Node + Serialize {
+    fn version_column() -> Self::Iden;
+}
+
+/// Runs `query`, an update of the row `id` based on `version` of it, and returns the updated
+/// row. Fails with `ConflictError` when the row has been updated since, and `NotFoundError`
+/// when there is no such row.
+pub async fn update_versioned<T, D>(
+    db: &D,
+    query: &mut UpdateStatement,
+    id: DbUuid,
+    version: i32,
+) -> Result<T>
+where
+    T: Versioned + FromRow<D::Row>,
+    D: DatabaseAdapter,
+{
+    query
+        .value(T::version_column(), Expr::col(T::version_column()).add(1))
+        .and_where(Expr::col(T::id_column()).eq(id.to_string()))
+        .and_where(Expr::col(T::version_column()).eq(version));
+    let updated = db.update_many(query).await?;
+
+    let mut select = Query::select();
+    select
+        .from(T::table())
+        .columns(T::columns())
+        .and_where(Expr::col(T::id_column()).eq(id.to_string()));
+    let current = db.query_optional::<T>(&select).await?.ok_or(Error::NotFoundError)?;
+
+    match updated {
+        0 => Err(Error::ConflictError(Conflict::new(&T::table().to_string(), &current)?)),
+        _ => Ok(current),
+    }
+}
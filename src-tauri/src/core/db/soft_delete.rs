@@ -0,0 +1,286 @@
+//! Soft delete for master data
+//!
+//! Items, categories, customers, suppliers and the other master data are referred to by
+//! historic orders and expenses, so deleting one only stamps its `deleted_at` and `deleted_by`.
+//! Deleted rows are left out of lists unless the filter asks for them (`deleted: INCLUDE` or
+//! `ONLY`), still resolve by id so old orders keep their names, and can be restored. A row
+//! that nothing refers to any more can be purged for good.
+//!
+//! Models opt in with `#[sea_query_model(soft_delete)]`, which implements `SoftDelete`.
+
+use chrono::Utc;
+use juniper::GraphQLEnum;
+use lightning_macros::LibsqlFromRow;
+use sea_query::{Alias, Expr, Func, Iden, Query, SelectStatement};
+
+use crate::{
+    adapters::outgoing::database::{DatabaseAdapter, FromLibsqlValue, FromRow},
+    core::{db::pagination::Node, types::db_uuid::DbUuid},
+    error::{Constraint, Error, Result},
+};
+
+/// Which rows a list of soft deleted models returns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, GraphQLEnum)]
+pub enum DeletedRows {
+    /// Rows that are not deleted
+    #[default]
+    Exclude,
+    /// Deleted rows along with the others
+    Include,
+    /// Deleted rows only
+    Only,
+}
+
+/// A model whose rows are soft deleted, generated by `#[sea_query_model(soft_delete)]`
+pub trait SoftDelete: Node {
+    fn deleted_at_column() -> Self::Iden;
+    fn deleted_by_column() -> Self::Iden;
+}
+
+/// A foreign key of `table_name.column_name` pointing at another table
+#[derive(Debug, LibsqlFromRow)]
+pub struct Reference {
+    pub table_name: String,
+    pub column_name: String,
+}
+
+/// Narrows `query` to the rows `rows` asks for. Does nothing for models that are not soft
+/// deleted.
+pub fn restrict_deleted<T: Node>(query: &mut SelectStatement, rows: DeletedRows) {
+    let Some(column) = T::soft_delete_column() else {
+        return;
+    };
+    let deleted_at = Expr::col((T::table(), column));
+    match rows {
+        DeletedRows::Exclude => {
+            query.and_where(deleted_at.is_null());
+        }
+        DeletedRows::Include => {}
+        DeletedRows::Only => {
+            query.and_where(deleted_at.is_not_null());
+        }
+    }
+}
+
+/// Marks the row `id` as deleted by `deleted_by`. Fails with `NotFoundError` when there is no
+/// such row or it is already deleted.
+pub async fn soft_delete<T, D>(db: &D, id: DbUuid, deleted_by: Option<DbUuid>) -> Result<()>
+where
+    T: SoftDelete,
+    D: DatabaseAdapter,
+{
+    let now = Utc::now().naive_utc();
+
+    let mut query = Query::update();
+    query
+        .table(T::table())
+        .value(T::deleted_at_column(), now.to_string())
+        .value(T::deleted_by_column(), deleted_by.map(|id| id.to_string()))
+        .and_where(Expr::col(T::id_column()).eq(id.to_string()))
+        .and_where(Expr::col(T::deleted_at_column()).is_null());
+
+    match db.update_many(&query).await? {
+        0 => Err(Error::NotFoundError),
+        _ => Ok(()),
+    }
+}
+
+/// Brings back the deleted row `id`. Fails with `NotFoundError` when there is no such row or
+/// it is not deleted.
+pub async fn restore<T, D>(db: &D, id: DbUuid) -> Result<T>
+where
+    T: SoftDelete + FromRow<D::Row>,
+    D: DatabaseAdapter,
+{
+    let mut query = Query::update();
+    query
+        .table(T::table())
+        .value(T::deleted_at_column(), Option::<String>::None)
+        .value(T::deleted_by_column(), Option::<String>::None)
+        .and_where(Expr::col(T::id_column()).eq(id.to_string()))
+        .and_where(Expr::col(T::deleted_at_column()).is_not_null());
+
+    if db.update_many(&query).await? == 0 {
+        return Err(Error::NotFoundError);
+    }
+
+    let mut query = Query::select();
+    query
+        .from(T::table())
+        .columns(T::columns())
+        .and_where(Expr::col(T::id_column()).eq(id.to_string()));
+    db.query_one::<T>(&query).await
+}
+
+/// Deletes the row `id` for good, which is only allowed while no other row refers to it.
+///
+/// Rows whose foreign key cascades (item variants, item taxes, ...) belong to the purged row
+/// and go with it. Any other reference, such as an order line or an expense, fails the purge
+/// with a `ForeignKeyConstraintError` naming the referencing column.
+pub async fn purge<T, D>(db: &D, id: DbUuid) -> Result<()>
+where
+    T: Node,
+    D: DatabaseAdapter,
+    Reference: FromRow<D::Row>,
+    i64: FromRow<D::Row>,
+{
+    for reference in db.query_many::<Reference>(&references_query(&T::table().to_string())).await? {
+        let column = Alias::new(&reference.column_name);
+
+        let mut query = Query::select();
+        query
+            .expr(Expr::col(column.clone()).count())
+            .from(Alias::new(&reference.table_name))
+            .and_where(Expr::col(column).eq(id.to_string()));
+
+        if db.query_one::<i64>(&query).await? > 0 {
+            return Err(Error::ForeignKeyConstraintError(Constraint::new(
+                &reference.table_name,
+                &reference.column_name,
+            )));
+        }
+    }
+
+    let mut query = Query::delete();
+    query
+        .from_table(T::table())
+        .and_where(Expr::col(T::id_column()).eq(id.to_string()));
+
+    match db.delete(&query).await? {
+        0 => Err(Error::NotFoundError),
+        _ => Ok(()),
+    }
+}
+
+// Foreign keys of every table that point at `table` and do not cascade
+fn references_query(table: &str) -> SelectStatement {
+    let (master, keys) = (Alias::new("m"), Alias::new("k"));
+
+    Query::select()
+        .expr_as(Expr::col((master.clone(), Alias::new("name"))), Alias::new("table_name"))
+        .expr_as(Expr::col((keys.clone(), Alias::new("from"))), Alias::new("column_name"))
+        .from_as(Alias::new("sqlite_master"), master.clone())
+        .from_function(
+            Func::cust(Alias::new("pragma_foreign_key_list")).arg(Expr::col((master.clone(), Alias::new("name")))),
+            keys.clone(),
+        )
+        .and_where(Expr::col((master, Alias::new("type"))).eq("table"))
+        .and_where(Expr::col((keys.clone(), Alias::new("table"))).eq(table))
+        .and_where(Expr::col((keys, Alias::new("on_delete"))).ne("CASCADE"))
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        commands::{
+            catalog::{item_commands::CreateItemCommand, item_group_commands::CreateItemGroupCommand},
+            sales::customer_commands::CreateCustomerCommand,
+            tests::setup_service,
+            AppService, Command,
+        },
+        models::{
+            catalog::{
+                item_group_model::{ItemCategory, ItemCategoryNew},
+                item_model::{Item, ItemNature, ItemState, NewItem},
+            },
+            sales::customer_model::{Customer, CustomerFilter, CustomerNewInput, Customers},
+        },
+        types::money::Money,
+    };
+
+    async fn create_customer(service: &AppService, name: &str) -> Customer {
+        let command = CreateCustomerCommand {
+            customer: CustomerNewInput {
+                full_name: name.to_string(),
+                email: None,
+                phone: None,
+                address: None,
+                customer_group_id: None,
+            },
+        };
+        command.exec(service).await.unwrap()
+    }
+
+    async fn customer_names(service: &AppService, deleted: Option<DeletedRows>) -> Vec<String> {
+        let filter = CustomerFilter { deleted, ..Default::default() };
+        let query = Customers::list(Some(&filter), None, None, None);
+        let customers = service.db_adapter.query_many::<Customer>(&query).await.unwrap();
+        customers.into_iter().map(|customer| customer.full_name).collect()
+    }
+
+    #[tokio::test]
+    async fn test_lists_leave_out_deleted_rows() {
+        let service = setup_service().await;
+        let user: DbUuid = uuid::Uuid::now_v7().into();
+        create_customer(&service, "Alice").await;
+        let bob = create_customer(&service, "Bob").await;
+
+        soft_delete::<Customer, _>(&service.db_adapter, bob.id, Some(user)).await.unwrap();
+
+        assert_eq!(customer_names(&service, None).await, ["Alice"]);
+        assert_eq!(customer_names(&service, Some(DeletedRows::Include)).await, ["Alice", "Bob"]);
+        assert_eq!(customer_names(&service, Some(DeletedRows::Only)).await, ["Bob"]);
+        let count = service.db_adapter.query_one::<i64>(&Customers::count(None)).await.unwrap();
+        assert_eq!(count, 1);
+
+        // Still found by id, for the orders that refer to it
+        let deleted = service.db_adapter.query_one::<Customer>(&Customers::find_by_id(bob.id)).await.unwrap();
+        assert!(deleted.deleted_at.is_some());
+        assert_eq!(deleted.deleted_by, Some(user));
+
+        let result = soft_delete::<Customer, _>(&service.db_adapter, bob.id, None).await;
+        assert!(matches!(result, Err(Error::NotFoundError)));
+    }
+
+    #[tokio::test]
+    async fn test_restore() {
+        let service = setup_service().await;
+        let bob = create_customer(&service, "Bob").await;
+
+        let result = restore::<Customer, _>(&service.db_adapter, bob.id).await;
+        assert!(matches!(result, Err(Error::NotFoundError)));
+
+        soft_delete::<Customer, _>(&service.db_adapter, bob.id, None).await.unwrap();
+        let restored = restore::<Customer, _>(&service.db_adapter, bob.id).await.unwrap();
+        assert_eq!(restored.deleted_at, None);
+        assert_eq!(restored.deleted_by, None);
+        assert_eq!(customer_names(&service, None).await, ["Bob"]);
+    }
+
+    #[tokio::test]
+    async fn test_purge_only_unreferenced_rows() {
+        let service = setup_service().await;
+        let command = CreateItemGroupCommand {
+            category: ItemCategoryNew { name: "Drinks".to_string(), description: None },
+        };
+        let category = command.exec(&service).await.unwrap();
+        let command = CreateItemCommand {
+            item: NewItem {
+                name: "Cola".to_string(),
+                description: None,
+                nature: ItemNature::Goods,
+                state: ItemState::Active,
+                price: Money::from(1000),
+                category_id: category.id,
+                tax_ids: None,
+            },
+        };
+        let item = command.exec(&service).await.unwrap();
+
+        let result = purge::<ItemCategory, _>(&service.db_adapter, category.id).await;
+        match result {
+            Err(Error::ForeignKeyConstraintError(constraint)) => {
+                assert_eq!(constraint, Constraint::new("items", "category_id"))
+            }
+            other => panic!("expected a foreign key error, got {:?}", other),
+        }
+
+        purge::<Item, _>(&service.db_adapter, item.id).await.unwrap();
+        purge::<ItemCategory, _>(&service.db_adapter, category.id).await.unwrap();
+
+        let result = purge::<ItemCategory, _>(&service.db_adapter, category.id).await;
+        assert!(matches!(result, Err(Error::NotFoundError)));
+    }
+}
@@ -0,0 +1,96 @@
+//! In-process events published by commands
+//!
+//! Commands publish to the `EventBus` on `AppService` once their writes are done. Anything
+//! that wants to follow along, such as the GraphQL subscriptions, calls `subscribe`. Events
+//! are dropped when nobody is listening, and a subscriber that falls too far behind skips
+//! the events it missed.
+
+use chrono::NaiveDateTime;
+use juniper::GraphQLObject;
+use tokio::sync::broadcast;
+
+use crate::core::{
+    models::{finance::sales_order_payment_model::SalesOrderPayment, sales::sales_order_model::SalesOrder},
+    types::db_uuid::DbUuid,
+};
+
+/// Events kept for subscribers that have not caught up yet
+const EVENT_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    OrderCreated(SalesOrder),
+    OrderVoided(SalesOrder),
+    PaymentRecorded(SalesOrderPayment),
+    StockChanged(StockChange),
+    SyncFinished(SyncFinished),
+}
+
+/// Items leaving or returning to stock because of an order
+#[derive(Debug, Clone, GraphQLObject)]
+pub struct StockChange {
+    pub item_id: DbUuid,
+    pub order_id: DbUuid,
+    /// Negative when items are sold, positive when a sale is voided
+    pub quantity: i32,
+}
+
+/// The local replica caught up with the remote database
+#[derive(Debug, Clone, GraphQLObject)]
+pub struct SyncFinished {
+    pub finished_at: NaiveDateTime,
+}
+
+pub struct EventBus {
+    sender: broadcast::Sender<Event>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl EventBus {
+    pub fn publish(&self, event: Event) {
+        // Sending only fails when there are no subscribers
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_subscribers_receive_published_events() {
+        let bus = EventBus::default();
+
+        // Nobody is listening yet, so this one is dropped
+        bus.publish(Event::SyncFinished(SyncFinished { finished_at: NaiveDateTime::MIN }));
+
+        let mut first = bus.subscribe();
+        let mut second = bus.subscribe();
+        let change = StockChange {
+            item_id: uuid::Uuid::now_v7().into(),
+            order_id: uuid::Uuid::now_v7().into(),
+            quantity: -2,
+        };
+        bus.publish(Event::StockChanged(change.clone()));
+
+        for receiver in [&mut first, &mut second] {
+            match receiver.recv().await.unwrap() {
+                Event::StockChanged(received) => {
+                    assert_eq!(received.item_id, change.item_id);
+                    assert_eq!(received.quantity, -2);
+                }
+                event => panic!("Unexpected event {:?}", event),
+            }
+        }
+    }
+}
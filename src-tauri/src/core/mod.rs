@@ -1,5 +1,6 @@
 pub mod commands;
 pub mod db;
+pub mod events;
 pub mod models;
 pub mod repositories;
 pub mod types;
@@ -0,0 +1,21 @@
+use chrono::NaiveDateTime;
+use lightning_macros::{LibsqlFromRow, SeaQueryModel};
+
+use crate::{
+    adapters::outgoing::database::{FromLibsqlValue, FromRow},
+    core::types::db_uuid::DbUuid,
+};
+
+/// A bearer token that lets a script or another device act as `user_id` over HTTP
+#[derive(Debug, Clone, SeaQueryModel, LibsqlFromRow)]
+pub struct ApiToken {
+    pub id: DbUuid,
+    pub user_id: DbUuid,
+    pub name: String,
+    #[sea_query_model(skip_filter)]
+    pub token_hash: String,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
@@ -1 +1,2 @@
+pub mod api_token_model;
 pub mod user_model;
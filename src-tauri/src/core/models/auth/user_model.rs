@@ -13,9 +13,11 @@ use crate::{
 pub struct User {
     pub id: DbUuid,
     pub username: String,
+    #[sea_query_model(skip_filter)]
     pub pin_hash: String,
     pub full_name: String,
     pub state: UserState,
+    pub role: UserRole,
     pub last_login_at: Option<NaiveDateTime>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -27,3 +29,10 @@ pub enum UserState {
     Inactive,
     Locked,
 }
+
+#[derive(Debug, Clone, Copy, Display, PartialEq, GraphQLEnum, SeaQueryEnum, LibsqlEnum)]
+pub enum UserRole {
+    Cashier,
+    // Approves price overrides made by others
+    Supervisor,
+}
@@ -0,0 +1,40 @@
+use juniper::{GraphQLEnum, GraphQLInputObject, GraphQLObject};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GraphQLEnum)]
+pub enum ImportFormat {
+    Csv,
+    Xlsx,
+}
+
+/// A spreadsheet of items, one row per item or variant, with a header row naming the columns
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct CatalogImportInput {
+    pub format: ImportFormat,
+    /// The text of a CSV file, or an XLSX file encoded as base64
+    pub contents: String,
+    /// Validate every row without saving anything
+    pub dry_run: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, GraphQLObject)]
+pub struct CatalogImportReport {
+    /// Whether the rows were saved. Imports with errors and dry runs save nothing.
+    pub committed: bool,
+    /// The number of data rows read, not counting the header or blank rows
+    pub rows: i32,
+    pub items_created: i32,
+    pub items_updated: i32,
+    pub categories_created: i32,
+    pub taxes_created: i32,
+    pub variants_created: i32,
+    pub variants_updated: i32,
+    pub errors: Vec<ImportRowError>,
+}
+
+#[derive(Debug, Clone, PartialEq, GraphQLObject)]
+pub struct ImportRowError {
+    /// The line of the file, counting the header as line 1
+    pub row: i32,
+    pub column: Option<String>,
+    pub message: String,
+}
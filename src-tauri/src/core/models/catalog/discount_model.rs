@@ -1,10 +1,12 @@
-use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::{db_uuid::DbUuid, money::Money}}};
+use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::{row_version::Versioned, SeaQueryCrudTrait}, types::{db_uuid::DbUuid, local_date_time, money::Money}}};
 use chrono::NaiveDateTime;
 use derive_more::Display;
 use juniper::{GraphQLEnum, GraphQLInputObject};
 use lightning_macros::{LibsqlEnum, LibsqlFromRow, SeaQueryCrud, SeaQueryEnum, SeaQueryModel};
+use serde::Serialize;
 
-#[derive(Debug, SeaQueryModel, LibsqlFromRow, SeaQueryCrud)]
+#[derive(Debug, SeaQueryModel, LibsqlFromRow, SeaQueryCrud, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Discount {
     pub id: DbUuid,
     pub name: String,
@@ -13,10 +15,51 @@ pub struct Discount {
     pub value: Money,
     pub scope: DiscountScope,
     pub state: DiscountState,
+    #[serde(serialize_with = "local_date_time::serialize_option")]
     pub start_date: Option<NaiveDateTime>,
+    #[serde(serialize_with = "local_date_time::serialize_option")]
     pub end_date: Option<NaiveDateTime>,
+    #[serde(serialize_with = "local_date_time::serialize")]
     pub created_at: NaiveDateTime,
+    #[serde(serialize_with = "local_date_time::serialize")]
     pub updated_at: NaiveDateTime,
+    /// Bumped by every update, see `row_version`
+    #[sea_query_model(skip_filter)]
+    pub version: i32,
+}
+
+impl Versioned for Discount {
+    fn version_column() -> Discounts {
+        Discounts::Version
+    }
+}
+
+impl Discount {
+    /// Derives the state the discount is in at `now` from its start and end dates.
+    ///
+    /// `Inactive` is a manual switch and always wins. Discounts without any dates keep
+    /// whatever state was stored, so a manually expired discount stays expired.
+    pub fn effective_state(&self, now: NaiveDateTime) -> DiscountState {
+        if self.state == DiscountState::Inactive {
+            return DiscountState::Inactive;
+        }
+
+        if self.start_date.is_some_and(|start| now < start) {
+            DiscountState::Scheduled
+        } else if self.end_date.is_some_and(|end| now >= end) {
+            DiscountState::Expired
+        } else if self.start_date.is_none() && self.end_date.is_none() {
+            self.state
+        } else {
+            DiscountState::Active
+        }
+    }
+
+    /// Returns the discount with its state replaced by the effective state at `now`
+    pub fn with_effective_state(mut self, now: NaiveDateTime) -> Self {
+        self.state = self.effective_state(now);
+        self
+    }
 }
 
 #[derive(Debug, Clone, GraphQLInputObject)]
@@ -34,6 +77,8 @@ pub struct DiscountNewInput {
 #[derive(Debug, Clone, GraphQLInputObject)]
 pub struct DiscountUpdateInput {
     pub id: DbUuid,
+    /// The version the changes are based on
+    pub version: i32,
     pub name: Option<String>,
     pub description: Option<Option<String>>,
     pub discount_type: Option<DiscountType>,
@@ -47,13 +92,15 @@ pub struct DiscountUpdateInput {
 // Using DbEnum derive for mapping Rust enums to database enum types
 // Make sure these enum types are created in the database via migrations
 
-#[derive(Debug, Clone, Copy, GraphQLEnum, PartialEq, Eq, Display, SeaQueryEnum, LibsqlEnum)]
+#[derive(Debug, Clone, Copy, GraphQLEnum, PartialEq, Eq, Display, SeaQueryEnum, LibsqlEnum, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum DiscountType {
     Percentage,
     FixedAmount,
 }
 
-#[derive(Debug, Clone, Copy, GraphQLEnum, PartialEq, Eq, Display, SeaQueryEnum, LibsqlEnum)]
+#[derive(Debug, Clone, Copy, GraphQLEnum, PartialEq, Eq, Display, SeaQueryEnum, LibsqlEnum, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum DiscountScope {
     AllItems,
     SpecificItems, // Added for item-specific discounts
@@ -61,7 +108,8 @@ pub enum DiscountScope {
                    // SpecificCategories,
 }
 
-#[derive(Debug, Clone, Copy, GraphQLEnum, PartialEq, Eq, Display, SeaQueryEnum, LibsqlEnum)]
+#[derive(Debug, Clone, Copy, GraphQLEnum, PartialEq, Eq, Display, SeaQueryEnum, LibsqlEnum, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum DiscountState {
     Active,
     Inactive,
@@ -7,7 +7,8 @@ use derive_more::Display;
 use juniper::{GraphQLEnum, GraphQLInputObject};
 use lightning_macros::{LibsqlEnum, LibsqlFromRow, SeaQueryCrud, SeaQueryEnum, SeaQueryModel};
 
-#[derive(Debug, SeaQueryModel, LibsqlFromRow, SeaQueryCrud)]
+#[derive(Debug, Clone, SeaQueryModel, LibsqlFromRow, SeaQueryCrud)]
+#[sea_query_model(soft_delete)]
 pub struct ItemCategory {
     pub id: DbUuid,
     pub name: String,
@@ -15,6 +16,8 @@ pub struct ItemCategory {
     pub state: ItemCategoryState,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub deleted_by: Option<DbUuid>,
 }
 
 #[derive(Debug, Clone, GraphQLInputObject)]
@@ -35,5 +38,6 @@ pub struct ItemCategoryUpdate {
 pub enum ItemCategoryState {
     Active,
     Inactive,
+    /// Deleted before rows were soft deleted; such rows now also have `deleted_at` set
     Deleted,
 }
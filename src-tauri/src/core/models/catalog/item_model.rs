@@ -2,10 +2,13 @@ use chrono::NaiveDateTime;
 use derive_more::Display;
 use juniper::{GraphQLEnum, GraphQLInputObject};
 use lightning_macros::{LibsqlFromRow, SeaQueryCrud, SeaQueryEnum, SeaQueryModel, LibsqlEnum};
+use serde::Serialize;
 
-use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::{db_uuid::DbUuid, money::Money}}};
+use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::{row_version::Versioned, SeaQueryCrudTrait}, types::{db_uuid::DbUuid, local_date_time, money::Money}}};
 
-#[derive(Debug, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+#[derive(Debug, SeaQueryModel, SeaQueryCrud, LibsqlFromRow, Serialize)]
+#[sea_query_model(soft_delete)]
+#[serde(rename_all = "camelCase")]
 pub struct Item {
     pub id: DbUuid,
     pub name: String,
@@ -14,8 +17,22 @@ pub struct Item {
     pub state: ItemState,
     pub price: Money,
     pub category_id: DbUuid,
+    #[serde(serialize_with = "local_date_time::serialize")]
     pub created_at: NaiveDateTime,
+    #[serde(serialize_with = "local_date_time::serialize")]
     pub updated_at: NaiveDateTime,
+    #[serde(serialize_with = "local_date_time::serialize_option")]
+    pub deleted_at: Option<NaiveDateTime>,
+    pub deleted_by: Option<DbUuid>,
+    /// Bumped by every update, see `row_version`
+    #[sea_query_model(skip_filter)]
+    pub version: i32,
+}
+
+impl Versioned for Item {
+    fn version_column() -> Items {
+        Items::Version
+    }
 }
 
 #[derive(Debug, Clone, GraphQLInputObject)]
@@ -32,6 +49,8 @@ pub struct NewItem {
 #[derive(Debug, Clone, GraphQLInputObject)]
 pub struct UpdateItem {
     pub id: DbUuid,
+    /// The version the changes are based on
+    pub version: i32,
     pub name: Option<String>,
     pub description: Option<Option<String>>,
     pub nature: Option<ItemNature>,
@@ -40,15 +59,18 @@ pub struct UpdateItem {
     pub category_id: Option<DbUuid>,
 }
 
-#[derive(Debug, Clone, Copy, GraphQLEnum, Display, SeaQueryEnum, LibsqlEnum)]
+#[derive(Debug, Clone, Copy, GraphQLEnum, Display, SeaQueryEnum, LibsqlEnum, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ItemNature {
     Goods,
     Service,
 }
 
-#[derive(Debug, Clone, Copy, GraphQLEnum, Display, SeaQueryEnum, LibsqlEnum)]
+#[derive(Debug, Clone, Copy, GraphQLEnum, Display, SeaQueryEnum, LibsqlEnum, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ItemState {
     Active,
     Inactive,
+    /// Deleted before rows were soft deleted; such rows now also have `deleted_at` set
     Deleted,
 }
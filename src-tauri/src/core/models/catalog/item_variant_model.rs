@@ -42,17 +42,19 @@ use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db:
 /// - `id`: Unique identifier
 /// - `item_id`: Reference to the base item
 /// - `sku`: Optional SKU code specific to this variant
+/// - `barcode`: Optional barcode printed on this variant's label, unique across variants
 /// - `price_adjustment`: Optional price difference from the base item price
 /// - `is_default`: Whether this is the default variant for the item
 ///
 /// # Relationships
 /// - Belongs to one Item
 /// - Has many VariantValues through ItemVariantValues
-#[derive(Debug, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+#[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
 pub struct ItemVariant {
     pub id: DbUuid,
     pub item_id: DbUuid,
     pub sku: Option<String>,
+    pub barcode: Option<String>,
     pub price_adjustment: Option<Money>,
     pub is_default: bool,
     pub created_at: NaiveDateTime,
@@ -71,6 +73,7 @@ pub struct ItemVariant {
 pub struct ItemVariantNewInput {
     pub item_id: DbUuid,
     pub sku: Option<String>,
+    pub barcode: Option<String>,
     pub price_adjustment: Option<Money>,
     pub is_default: Option<bool>,
     pub variant_value_ids: Vec<DbUuid>,
@@ -85,12 +88,14 @@ pub struct ItemVariantNewInput {
 /// # Fields
 /// - `id`: ID of the variant to update
 /// - `sku`: Optional SKU update (Option<Option<String>>)
+/// - `barcode`: Optional barcode update (Option<Option<String>>)
 /// - `price_adjustment`: Optional price adjustment update
 /// - `is_default`: Whether to set this as the default variant
 #[derive(Debug, Clone, GraphQLInputObject)]
 pub struct ItemVariantUpdateInput {
     pub id: DbUuid,
     pub sku: Option<Option<String>>,
+    pub barcode: Option<Option<String>>,
     pub price_adjustment: Option<Option<Money>>,
     pub is_default: Option<bool>,
     pub updated_at: Option<NaiveDateTime>,
@@ -1,8 +1,11 @@
+pub mod catalog_import_model;
 pub mod discount_model;
 pub mod item_discount_model;
 pub mod item_group_model;
 pub mod item_model;
 pub mod item_variant_model;
 pub mod item_variant_value_model;
+pub mod price_list_model;
+pub mod stock_movement_model;
 pub mod variant_type_model;
 pub mod variant_value_model;
@@ -0,0 +1,125 @@
+//! Price List Model
+//!
+//! Price lists let the same item sell at different prices depending on where and to
+//! whom it is sold, e.g. a delivery platform channel or a wholesale customer group.
+//!
+//! ## Resolution
+//! - A list applies when it is active, inside its validity dates, and at least one of
+//!   its assignments matches the order context. Every column set on an assignment must
+//!   match, so an assignment with a channel and a customer group only applies to that
+//!   group on that channel.
+//! - When several lists apply, the one with the highest `priority` wins.
+//! - Within a list, a variant specific price beats an item wide price, and the tier with
+//!   the highest `min_quantity` not above the ordered quantity is used.
+//! - Without any applicable list the catalog price (plus variant adjustment) is used.
+
+use chrono::NaiveDateTime;
+use juniper::GraphQLInputObject;
+use lightning_macros::{LibsqlFromRow, SeaQueryCrud, SeaQueryModel};
+
+use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::{db_uuid::DbUuid, money::Money}}};
+
+#[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+pub struct PriceList {
+    pub id: DbUuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub priority: i32,
+    pub valid_from: Option<NaiveDateTime>,
+    pub valid_to: Option<NaiveDateTime>,
+    pub is_active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl PriceList {
+    /// Whether the list is switched on and `now` falls inside its validity dates
+    pub fn is_valid_at(&self, now: NaiveDateTime) -> bool {
+        self.is_active
+            && self.valid_from.is_none_or(|from| now >= from)
+            && self.valid_to.is_none_or(|to| now < to)
+    }
+}
+
+/// A price for an item, or one of its variants, from a minimum quantity onwards
+#[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+pub struct PriceListItem {
+    pub id: DbUuid,
+    pub price_list_id: DbUuid,
+    pub item_id: DbUuid,
+    pub variant_id: Option<DbUuid>,
+    pub min_quantity: i32,
+    pub price: Money,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// Targets a price list at a channel, location, customer group or a combination of them
+#[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+pub struct PriceListAssignment {
+    pub id: DbUuid,
+    pub price_list_id: DbUuid,
+    pub channel_id: Option<DbUuid>,
+    pub location_id: Option<DbUuid>,
+    pub customer_group_id: Option<DbUuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl PriceListAssignment {
+    pub fn matches(&self, context: &PriceContext) -> bool {
+        fn matches_column(assigned: Option<DbUuid>, actual: Option<DbUuid>) -> bool {
+            assigned.is_none_or(|assigned| actual == Some(assigned))
+        }
+
+        matches_column(self.channel_id, context.channel_id)
+            && matches_column(self.location_id, context.location_id)
+            && matches_column(self.customer_group_id, context.customer_group_id)
+    }
+}
+
+/// Where and to whom an item is being sold
+#[derive(Debug, Clone, Default)]
+pub struct PriceContext {
+    pub channel_id: Option<DbUuid>,
+    pub location_id: Option<DbUuid>,
+    pub customer_group_id: Option<DbUuid>,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct PriceListNewInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub priority: Option<i32>,
+    pub valid_from: Option<NaiveDateTime>,
+    pub valid_to: Option<NaiveDateTime>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct PriceListUpdateInput {
+    pub id: DbUuid,
+    pub name: Option<String>,
+    pub description: Option<Option<String>>,
+    pub priority: Option<i32>,
+    pub valid_from: Option<Option<NaiveDateTime>>,
+    pub valid_to: Option<Option<NaiveDateTime>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct PriceListItemInput {
+    pub price_list_id: DbUuid,
+    pub item_id: DbUuid,
+    pub variant_id: Option<DbUuid>,
+    pub min_quantity: Option<i32>,
+    pub price: Money,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct PriceListAssignmentInput {
+    pub price_list_id: DbUuid,
+    pub channel_id: Option<DbUuid>,
+    pub location_id: Option<DbUuid>,
+    pub customer_group_id: Option<DbUuid>,
+}
@@ -0,0 +1,34 @@
+use chrono::NaiveDateTime;
+use derive_more::Display;
+use juniper::{GraphQLEnum, GraphQLInputObject, GraphQLObject};
+use lightning_macros::{LibsqlEnum, LibsqlFromRow, SeaQueryCrud, SeaQueryEnum, SeaQueryModel};
+
+use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::db_uuid::DbUuid}};
+
+/// Items coming into or going out of stock. The quantity on hand is the sum of the movements.
+#[derive(Debug, Clone, GraphQLObject, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+pub struct StockMovement {
+    pub id: DbUuid,
+    pub item_id: DbUuid,
+    /// Set when the stock of one variant of the item is counted
+    pub item_variant_id: Option<DbUuid>,
+    /// Positive when stock comes in
+    pub quantity: i32,
+    pub reason: StockMovementReason,
+    pub created_by: Option<DbUuid>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct StockMovementNewInput {
+    pub item_id: DbUuid,
+    pub item_variant_id: Option<DbUuid>,
+    pub quantity: i32,
+    pub reason: StockMovementReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GraphQLEnum, Display, SeaQueryEnum, LibsqlEnum)]
+pub enum StockMovementReason {
+    /// Stock on hand when the item was first recorded, such as from a catalog import
+    Opening,
+}
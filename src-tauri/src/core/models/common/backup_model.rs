@@ -0,0 +1,21 @@
+use chrono::NaiveDateTime;
+use juniper::{GraphQLEnum, GraphQLObject};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GraphQLEnum)]
+pub enum BackupKind {
+    /// Taken by the backup schedule
+    Scheduled,
+    /// Taken on request, such as before a bulk import
+    Snapshot,
+}
+
+/// A copy of the database in the backup directory
+#[derive(Debug, Clone, GraphQLObject)]
+pub struct Backup {
+    pub path: String,
+    pub kind: BackupKind,
+    pub label: Option<String>,
+    pub created_at: NaiveDateTime,
+    /// Whether the copy is sealed with the backup passphrase
+    pub encrypted: bool,
+}
@@ -5,6 +5,7 @@ use lightning_macros::{LibsqlFromRow, SeaQueryCrud, SeaQueryModel};
 use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::db_uuid::DbUuid}};
 
 #[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+#[sea_query_model(soft_delete)]
 pub struct Brand {
     pub id: DbUuid,
     pub name: String,
@@ -12,6 +13,8 @@ pub struct Brand {
     pub is_active: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub deleted_by: Option<DbUuid>,
 }
 
 #[derive(Debug, Clone, GraphQLInputObject)]
@@ -1,3 +1,4 @@
+pub mod backup_model;
 pub mod brand_model;
 pub mod channel_model;
 pub mod location_model;
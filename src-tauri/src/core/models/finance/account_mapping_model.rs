@@ -0,0 +1,51 @@
+use chrono::NaiveDateTime;
+use derive_more::Display;
+use juniper::{GraphQLEnum, GraphQLInputObject, GraphQLObject};
+use lightning_macros::{LibsqlEnum, LibsqlFromRow, SeaQueryCrud, SeaQueryEnum, SeaQueryModel};
+
+use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::db_uuid::DbUuid}};
+
+/// The ledger account that postings of one kind, and optionally from one source, go to
+#[derive(Debug, Clone, GraphQLObject, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+pub struct AccountMapping {
+    pub id: DbUuid,
+    pub kind: AccountMappingKind,
+    /// The category, tax, charge type, payment method or purchase category the account is for.
+    /// Without one, the account is used for the whole kind.
+    pub source_id: Option<DbUuid>,
+    pub account_code: String,
+    pub account_name: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// Creates the mapping for the kind and source, or replaces its account
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct AccountMappingInput {
+    pub kind: AccountMappingKind,
+    pub source_id: Option<DbUuid>,
+    pub account_code: String,
+    pub account_name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, GraphQLEnum, Display, SeaQueryEnum, LibsqlEnum)]
+pub enum AccountMappingKind {
+    /// Sales of the items in an item category, before discounts
+    Revenue,
+    /// Tax collected for a tax
+    TaxPayable,
+    /// Charges of a charge type, such as delivery
+    Charges,
+    /// Discounts given on sales
+    Discounts,
+    /// Amounts owed by customers for orders, until they are paid
+    Receivable,
+    /// Payments taken with a payment method, until they are deposited
+    PaymentClearing,
+    /// Expenses of a purchase category
+    Expense,
+    /// Amounts owed for expenses
+    ExpensePayable,
+    /// The difference when the amounts of an order do not add up to its total
+    Rounding,
+}
@@ -6,6 +6,7 @@ use lightning_macros::{LibsqlEnum, LibsqlFromRow, SeaQueryCrud, SeaQueryEnum, Se
 use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::db_uuid::DbUuid}};
 
 #[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+#[sea_query_model(soft_delete)]
 pub struct CostCenter {
     pub id: DbUuid,
     pub name: String,
@@ -14,6 +15,8 @@ pub struct CostCenter {
     pub state: CostCenterState,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub deleted_by: Option<DbUuid>,
 }
 
 #[derive(Debug, Clone, GraphQLInputObject)]
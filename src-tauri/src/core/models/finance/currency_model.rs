@@ -0,0 +1,42 @@
+use chrono::NaiveDateTime;
+use juniper::GraphQLInputObject;
+use lightning_macros::{LibsqlFromRow, SeaQueryCrud, SeaQueryModel};
+
+use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::db_uuid::DbUuid}};
+
+/// A currency and the number of decimal places its amounts are kept in
+#[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+pub struct Currency {
+    pub id: DbUuid,
+    pub code: String,
+    pub name: String,
+    pub symbol: Option<String>,
+    pub minor_units: i32,
+    pub is_base: bool,
+    pub is_active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Currency {
+    pub fn precision(&self) -> u32 {
+        self.minor_units as u32
+    }
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct CurrencyNewInput {
+    pub code: String,
+    pub name: String,
+    pub symbol: Option<String>,
+    pub minor_units: i32,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct CurrencyUpdateInput {
+    pub id: DbUuid,
+    pub name: Option<String>,
+    pub symbol: Option<Option<String>>,
+    pub is_active: Option<bool>,
+}
@@ -0,0 +1,49 @@
+use chrono::NaiveDateTime;
+use juniper::{GraphQLEnum, GraphQLInputObject, GraphQLObject};
+
+use crate::core::types::db_uuid::DbUuid;
+
+/// What an export holds. Each has a fixed set of columns, in a fixed order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GraphQLEnum)]
+pub enum ExportDataset {
+    /// One row per order, with its totals
+    SalesOrders,
+    /// One row per item sold, with the order it is on
+    SalesOrderItems,
+    /// One row per charge on an order, such as delivery
+    SalesOrderCharges,
+    /// One row per payment taken for an order
+    SalesOrderPayments,
+    Expenses,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GraphQLEnum)]
+pub enum ExportFormat {
+    Csv,
+    /// One JSON object per line, keyed by column
+    JsonLines,
+    Xlsx,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct DataExportInput {
+    pub dataset: ExportDataset,
+    pub format: ExportFormat,
+    /// Start of the range, inclusive. Orders are dated by order date, payments by payment date.
+    pub from: NaiveDateTime,
+    /// End of the range, exclusive
+    pub to: NaiveDateTime,
+    /// Only orders taken at this location. Expenses are not recorded by location.
+    pub location_id: Option<DbUuid>,
+    pub cost_center_id: Option<DbUuid>,
+    /// The file to write, relative to `EXPORT_DIR`, ending in `.csv`, `.jsonl` or `.xlsx`
+    pub path: String,
+}
+
+#[derive(Debug, Clone, GraphQLObject)]
+pub struct DataExport {
+    /// Where the file was written on the server
+    pub path: String,
+    /// The number of rows written, not counting the header
+    pub rows: i32,
+}
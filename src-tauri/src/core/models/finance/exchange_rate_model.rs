@@ -0,0 +1,23 @@
+use chrono::NaiveDateTime;
+use juniper::GraphQLInputObject;
+use lightning_macros::{LibsqlFromRow, SeaQueryCrud, SeaQueryModel};
+
+use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::{db_uuid::DbUuid, rate::Rate}}};
+
+/// How many base currency units one unit of `currency_code` is worth from `effective_from` on
+#[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+pub struct ExchangeRate {
+    pub id: DbUuid,
+    pub currency_code: String,
+    pub rate: Rate,
+    pub effective_from: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct ExchangeRateNewInput {
+    pub currency_code: String,
+    pub rate: Rate,
+    pub effective_from: Option<NaiveDateTime>,
+}
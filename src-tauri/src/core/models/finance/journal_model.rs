@@ -0,0 +1,61 @@
+use chrono::NaiveDateTime;
+use derive_more::Display;
+use juniper::{GraphQLEnum, GraphQLInputObject, GraphQLObject};
+
+use crate::core::types::{db_uuid::DbUuid, money::Money};
+
+/// A balanced set of postings for one order, payment or expense
+#[derive(Debug, Clone, GraphQLObject)]
+pub struct JournalEntry {
+    pub date: NaiveDateTime,
+    pub kind: JournalEntryKind,
+    /// The order, payment or expense the entry is for
+    pub source_id: DbUuid,
+    /// The order number, or the title of an expense
+    pub reference: String,
+    pub description: String,
+    pub lines: Vec<JournalLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, GraphQLObject)]
+pub struct JournalLine {
+    pub account_code: String,
+    pub account_name: String,
+    pub debit: Money,
+    pub credit: Money,
+    pub cost_center: Option<String>,
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GraphQLEnum, Display)]
+pub enum JournalEntryKind {
+    Sale,
+    /// Reverses the sale of an order that was voided, on the date it was voided
+    Refund,
+    Payment,
+    /// Reverses a payment that was voided, on the date it was voided
+    PaymentVoid,
+    Expense,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, GraphQLEnum)]
+pub enum JournalFormat {
+    /// One row per line, with the number of the entry it is in
+    Csv,
+    /// A QuickBooks Desktop import file of general journal transactions
+    QuickbooksIif,
+    /// A Tally import file of journal vouchers
+    TallyXml,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct JournalExportInput {
+    pub format: JournalFormat,
+    /// Start of the range, inclusive
+    pub from: NaiveDateTime,
+    /// End of the range, exclusive
+    pub to: NaiveDateTime,
+    pub cost_center_id: Option<DbUuid>,
+    /// The file to write, relative to `EXPORT_DIR`, ending in `.csv`, `.iif` or `.xml`
+    pub path: String,
+}
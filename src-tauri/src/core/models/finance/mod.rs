@@ -1,3 +1,8 @@
+pub mod account_mapping_model;
 pub mod cost_center_model;
+pub mod currency_model;
+pub mod data_export_model;
+pub mod exchange_rate_model;
+pub mod journal_model;
 pub mod payment_method_model;
 pub mod sales_order_payment_model;
@@ -3,7 +3,7 @@ use derive_more::Display;
 use juniper::{GraphQLEnum, GraphQLInputObject};
 use lightning_macros::{LibsqlEnum, LibsqlFromRow, SeaQueryCrud, SeaQueryEnum, SeaQueryModel};
 
-use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::{db_uuid::DbUuid, money::Money}}};
+use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::{db_uuid::DbUuid, money::Money, rate::Rate}}};
 
 #[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
 pub struct SalesOrderPayment {
@@ -15,6 +15,10 @@ pub struct SalesOrderPayment {
     pub reference_number: Option<String>,
     pub notes: Option<String>,
     pub state: SalesOrderPaymentState,
+    // What was tendered, in the minor units of `currency_code`, and the rate it was converted at
+    pub currency_code: String,
+    pub original_amount: Money,
+    pub exchange_rate: Rate,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -24,10 +28,21 @@ pub struct SalesOrderPaymentNewInput {
     pub order_id: DbUuid,
     pub payment_method_id: DbUuid,
     pub payment_date: NaiveDateTime,
-    pub amount: Money,
+    /// Amount in the base currency, required unless `foreign_amount` is given. A foreign
+    /// amount is converted at the exchange rate in effect on the payment date, and `amount`,
+    /// when also given, must match the converted value.
+    pub amount: Option<Money>,
     pub reference_number: Option<String>,
     pub notes: Option<String>,
     pub state: Option<SalesOrderPaymentState>,
+    pub foreign_amount: Option<ForeignAmountInput>,
+}
+
+/// An amount tendered in another currency, as a decimal string in that currency's precision
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct ForeignAmountInput {
+    pub currency_code: String,
+    pub amount: String,
 }
 
 #[derive(Debug, Clone, GraphQLInputObject)]
@@ -1,14 +1,71 @@
+//! Cart Model
+//!
+//! A cart is an order that has not been checked out yet. Lines only record what was
+//! picked (item, variant, quantity, manual price, promotion); prices, discounts and taxes
+//! are worked out by the backend at checkout using the same rules as order creation.
+//!
+//! Carts saved before lines were stored as rows keep their original JSON in
+//! `legacy_cart_data` when it could not be converted.
+//!
+//! A cashier can park a cart to serve the next customer and recall it later, possibly on
+//! another terminal. An open cart belongs to the terminal in `terminal_id`; a parked cart
+//! can be recalled by exactly one terminal.
+
 use chrono::NaiveDateTime;
-use juniper::GraphQLInputObject;
-use lightning_macros::{LibsqlFromRow, SeaQueryCrud, SeaQueryModel};
+use derive_more::Display;
+use juniper::{GraphQLEnum, GraphQLInputObject};
+use lightning_macros::{LibsqlEnum, LibsqlFromRow, SeaQueryCrud, SeaQueryEnum, SeaQueryModel};
 
-use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::db_uuid::DbUuid}};
+use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::{db_uuid::DbUuid, money::Money}}};
 
 #[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
 pub struct Cart {
     pub id: DbUuid,
-    pub cart_data: String,
     pub customer_id: Option<DbUuid>,
+    pub discount_id: Option<DbUuid>,
+    pub notes: Option<String>,
+    pub legacy_cart_data: Option<String>,
+    pub status: CartStatus,
+    pub label: Option<String>,
+    pub hold_reason: Option<String>,
+    pub parked_by: Option<DbUuid>,
+    pub terminal_id: Option<String>,
+    pub location_id: Option<DbUuid>,
+    pub held_since: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, GraphQLEnum, PartialEq, Eq, Display, SeaQueryEnum, LibsqlEnum)]
+pub enum CartStatus {
+    Open,
+    Parked,
+}
+
+#[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+pub struct CartLine {
+    pub id: DbUuid,
+    pub cart_id: DbUuid,
+    pub item_id: DbUuid,
+    pub variant_id: Option<DbUuid>,
+    pub quantity: i32,
+    pub discount_id: Option<DbUuid>,
+    pub note: Option<String>,
+    pub override_price: Option<Money>,
+    pub override_reason_id: Option<DbUuid>,
+    pub override_note: Option<String>,
+    pub override_approved_by: Option<DbUuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+pub struct CartCharge {
+    pub id: DbUuid,
+    pub cart_id: DbUuid,
+    pub charge_type_id: DbUuid,
+    pub amount: Money,
+    pub tax_group_id: Option<DbUuid>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -16,11 +73,82 @@ pub struct Cart {
 #[derive(Debug, Clone, GraphQLInputObject)]
 pub struct CartNewInput {
     pub customer_id: Option<DbUuid>,
-    pub cart_data: String,
+    pub discount_id: Option<DbUuid>,
+    pub notes: Option<String>,
+    pub terminal_id: Option<String>,
+    pub location_id: Option<DbUuid>,
 }
 
 #[derive(Debug, Clone, GraphQLInputObject)]
 pub struct CartUpdateInput {
     pub id: DbUuid,
-    pub cart_data: Option<String>,
+    pub customer_id: Option<Option<DbUuid>>, // Double optional for nullable field
+    pub discount_id: Option<Option<DbUuid>>,
+    pub notes: Option<Option<String>>,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct ParkCartInput {
+    pub id: DbUuid,
+    pub terminal_id: String,
+    pub location_id: DbUuid,
+    pub label: Option<String>,
+    pub hold_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct RecallCartInput {
+    pub id: DbUuid,
+    pub terminal_id: String,
+}
+
+/// A manual price on a cart line, checked against the reason code when it is set
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct CartLineOverrideInput {
+    pub price: Money,
+    pub reason_id: DbUuid,
+    pub note: Option<String>,
+    // Supervisor approval, required when the reason asks for it
+    pub approved_by: Option<DbUuid>,
+    pub approval_pin: Option<String>,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct CartLineNewInput {
+    pub cart_id: DbUuid,
+    pub item_id: DbUuid,
+    pub variant_id: Option<DbUuid>,
+    pub quantity: i32,
+    pub discount_id: Option<DbUuid>,
+    pub note: Option<String>,
+    pub price_override: Option<CartLineOverrideInput>,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct CartLineUpdateInput {
+    pub id: DbUuid,
+    pub quantity: Option<i32>,
+    pub discount_id: Option<Option<DbUuid>>,
+    pub note: Option<Option<String>>,
+    pub price_override: Option<Option<CartLineOverrideInput>>,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct CartChargeNewInput {
+    pub cart_id: DbUuid,
+    pub charge_type_id: DbUuid,
+    pub amount: Money,
+    pub tax_group_id: Option<DbUuid>,
+}
+
+/// Where and how the cart is sold; everything else comes from the cart itself
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct CheckoutCartInput {
+    pub cart_id: DbUuid,
+    pub channel_id: DbUuid,
+    pub location_id: DbUuid,
+    pub cost_center_id: DbUuid,
+    pub order_date: Option<NaiveDateTime>,
+    pub billing_address: Option<String>,
+    pub shipping_address: Option<String>,
 }
@@ -0,0 +1,27 @@
+use chrono::NaiveDateTime;
+use juniper::GraphQLInputObject;
+use lightning_macros::{LibsqlFromRow, SeaQueryCrud, SeaQueryModel};
+
+use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::db_uuid::DbUuid}};
+
+#[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+pub struct CustomerGroup {
+    pub id: DbUuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct CustomerGroupNewInput {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct CustomerGroupUpdateInput {
+    pub id: DbUuid,
+    pub name: Option<String>,
+    pub description: Option<Option<String>>,
+}
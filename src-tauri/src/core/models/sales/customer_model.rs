@@ -1,18 +1,36 @@
 use chrono::NaiveDateTime;
 use juniper::GraphQLInputObject;
 use lightning_macros::{LibsqlFromRow, SeaQueryCrud, SeaQueryModel};
+use serde::Serialize;
 
-use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::db_uuid::DbUuid}};
+use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::{row_version::Versioned, SeaQueryCrudTrait}, types::{db_uuid::DbUuid, local_date_time}}};
 
-#[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+#[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow, Serialize)]
+#[sea_query_model(soft_delete)]
+#[serde(rename_all = "camelCase")]
 pub struct Customer {
     pub id: DbUuid,
     pub full_name: String,
     pub email: Option<String>,
     pub phone: Option<String>,
     pub address: Option<String>,
+    pub customer_group_id: Option<DbUuid>,
+    #[serde(serialize_with = "local_date_time::serialize")]
     pub created_at: NaiveDateTime,
+    #[serde(serialize_with = "local_date_time::serialize")]
     pub updated_at: NaiveDateTime,
+    #[serde(serialize_with = "local_date_time::serialize_option")]
+    pub deleted_at: Option<NaiveDateTime>,
+    pub deleted_by: Option<DbUuid>,
+    /// Bumped by every update, see `row_version`
+    #[sea_query_model(skip_filter)]
+    pub version: i32,
+}
+
+impl Versioned for Customer {
+    fn version_column() -> Customers {
+        Customers::Version
+    }
 }
 
 #[derive(Debug, Clone, GraphQLInputObject)]
@@ -21,13 +39,17 @@ pub struct CustomerNewInput {
     pub email: Option<String>,
     pub phone: Option<String>,
     pub address: Option<String>,
+    pub customer_group_id: Option<DbUuid>,
 }
 
 #[derive(Debug, Clone, GraphQLInputObject)]
 pub struct CustomerUpdateInput {
     pub id: DbUuid,
+    /// The version the changes are based on
+    pub version: i32,
     pub full_name: Option<String>,
     pub email: Option<Option<String>>,
     pub phone: Option<Option<String>>,
     pub address: Option<Option<String>>,
+    pub customer_group_id: Option<Option<DbUuid>>,
 }
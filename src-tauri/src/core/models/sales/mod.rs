@@ -1,5 +1,7 @@
 pub mod cart_model;
+pub mod customer_group_model;
 pub mod customer_model;
+pub mod price_override_reason_model;
 pub mod sales_charge_type_model;
 pub mod sales_order_charge_model;
 pub mod sales_order_item_model;
@@ -0,0 +1,37 @@
+use chrono::NaiveDateTime;
+use juniper::GraphQLInputObject;
+use lightning_macros::{LibsqlFromRow, SeaQueryCrud, SeaQueryModel};
+
+use crate::{adapters::outgoing::database::{FromLibsqlValue, FromRow}, core::{db::SeaQueryCrudTrait, types::db_uuid::DbUuid}};
+
+/// A configurable reason a cashier can pick when overriding a line price or discount
+#[derive(Debug, Clone, SeaQueryModel, SeaQueryCrud, LibsqlFromRow)]
+pub struct PriceOverrideReason {
+    pub id: DbUuid,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub requires_approval: bool,
+    pub is_active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct PriceOverrideReasonNewInput {
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub requires_approval: Option<bool>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct PriceOverrideReasonUpdateInput {
+    pub id: DbUuid,
+    pub code: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<Option<String>>,
+    pub requires_approval: Option<bool>,
+    pub is_active: Option<bool>,
+}
@@ -17,6 +17,10 @@ pub struct SalesOrderItem {
     pub taxable_amount: Money,
     pub tax_amount: Money,
     pub total_amount: Money,
+    pub original_price_amount: Option<Money>,
+    pub override_reason_id: Option<DbUuid>,
+    pub override_note: Option<String>,
+    pub override_approved_by: Option<DbUuid>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -24,6 +28,8 @@ pub struct SalesOrderItem {
 #[derive(Debug, Clone, GraphQLInputObject)]
 pub struct SalesOrderItemInput {
     pub item_id: Option<DbUuid>,
+    // Priced as this variant of the item
+    pub variant_id: Option<DbUuid>,
     pub item_name: String,
     pub quantity: i32,
     pub sku: Option<String>,
@@ -32,4 +38,15 @@ pub struct SalesOrderItemInput {
     pub taxable_amount: Money,
     pub tax_amount: Money,
     pub total_amount: Money,
+    pub price_override: Option<SalesOrderItemOverrideInput>,
+}
+
+/// Marks a line whose price or discount was changed by hand at the register
+#[derive(Debug, Clone, GraphQLInputObject)]
+pub struct SalesOrderItemOverrideInput {
+    pub reason_id: DbUuid,
+    pub note: Option<String>,
+    // Supervisor approval, required when the reason asks for it
+    pub approved_by: Option<DbUuid>,
+    pub approval_pin: Option<String>,
 }
@@ -29,6 +29,7 @@ pub struct SalesOrder {
     pub taxable_amount: Money,
     pub tax_amount: Money,
     pub total_amount: Money,
+    pub currency_code: String,
 
     // State
     pub order_state: SalesOrderState,
@@ -2,8 +2,9 @@ use chrono::Utc;
 use sea_query::{DeleteStatement, Expr, InsertStatement, SelectStatement, UpdateStatement};
 use uuid::Uuid;
 
-use crate::{adapters::outgoing::database::DatabaseAdapter, core::{commands::AppService, models::auth::user_model::{User, UserNewInput, UserUpdateInput, Users}, types::db_uuid::DbUuid}, error::Result};
+use crate::{adapters::outgoing::database::DatabaseAdapter, core::{commands::AppService, models::auth::user_model::{User, UserNewInput, UserUpdateInput, Users}, types::db_uuid::DbUuid, utils::crypto::hash_pin}, error::Result};
 
+/// Stores a user. The PIN given as `pin_hash` is hashed first, as it is by [`update_user`].
 pub async fn insert_user(service: &AppService, user: UserNewInput) -> Result<User> {
     let id: DbUuid = Uuid::now_v7().into();
     let now = Utc::now().naive_utc();
@@ -13,9 +14,10 @@ pub async fn insert_user(service: &AppService, user: UserNewInput) -> Result<Use
         .values_panic([
             id.into(),
             user.username.into(),
-            user.pin_hash.into(),
+            hash_pin(&user.pin_hash)?.into(),
             user.full_name.into(),
             user.state.into(),
+            user.role.into(),
             user.last_login_at.into(),
             now.into(),
             now.into(),
@@ -55,8 +57,8 @@ pub async fn update_user(service: &AppService, user: UserUpdateInput) -> Result<
         update_query.value(Users::Username, username);
     }
 
-    if let Some(pin_hash) = user.pin_hash {
-        update_query.value(Users::PinHash, pin_hash);
+    if let Some(pin) = user.pin_hash {
+        update_query.value(Users::PinHash, hash_pin(&pin)?);
     }
 
     if let Some(full_name) = user.full_name {
@@ -67,6 +69,10 @@ pub async fn update_user(service: &AppService, user: UserUpdateInput) -> Result<
         update_query.value(Users::State, state);
     }
 
+    if let Some(role) = user.role {
+        update_query.value(Users::Role, role);
+    }
+
     if let Some(last_login_at) = user.last_login_at {
         update_query.value(Users::LastLoginAt, last_login_at);
     }
@@ -93,7 +99,7 @@ pub async fn delete_user(service: &AppService, id: DbUuid) -> Result<u64> {
 mod test {
     use crate::core::{
             commands::{tests::setup_service, AppService},
-            models::auth::user_model::{User, UserNewInput, UserState, UserUpdateInput},
+            models::auth::user_model::{User, UserNewInput, UserRole, UserState, UserUpdateInput},
             repositories::user_repository::{delete_user, get_user_by_id, get_user_by_username, insert_user, update_user},
         };
 
@@ -103,6 +109,7 @@ mod test {
             pin_hash: "pinhash".to_string(),
             full_name: "Full Name".to_string(),
             state: UserState::Active,
+            role: UserRole::Cashier,
             last_login_at: None,
         };
 
@@ -120,37 +127,38 @@ mod test {
 
     #[tokio::test]
     async fn test_get_user_by_id() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
-        let user = create_user_helper(&mut service).await;
+        let user = create_user_helper(&service).await;
 
-        let user = get_user_by_id(&mut service, user.id).await.unwrap();
+        let user = get_user_by_id(&service, user.id).await.unwrap();
         assert_eq!(user.unwrap().username, "testuser");
     }
 
     #[tokio::test]
     async fn test_get_user_by_username() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
-        create_user_helper(&mut service).await;
+        create_user_helper(&service).await;
 
-        let user = get_user_by_username(&mut service, "testuser").await.unwrap();
+        let user = get_user_by_username(&service, "testuser").await.unwrap();
         assert!(user.is_some());
         assert_eq!(user.unwrap().username, "testuser");
     }
 
     #[tokio::test]
     async fn test_update_user() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let user = create_user_helper(&service).await;
 
-        let user = update_user(&mut service, UserUpdateInput {
+        let user = update_user(&service, UserUpdateInput {
             id: user.id,
             username: Some("testuser2".to_string()),
             pin_hash: None,
             full_name: None,
             state: None,
+            role: None,
             last_login_at: None,
         }).await.unwrap();
         assert_eq!(user.username, "testuser2");
@@ -158,14 +166,14 @@ mod test {
 
     #[tokio::test]
     async fn test_delete_user() {
-        let mut service = setup_service().await;
+        let service = setup_service().await;
 
         let user = create_user_helper(&service).await;
 
-        let deleted_users = delete_user(&mut service, user.id).await.unwrap();
+        let deleted_users = delete_user(&service, user.id).await.unwrap();
         assert_eq!(deleted_users, 1);
 
-        let user = get_user_by_id(&mut service, user.id).await.unwrap();
+        let user = get_user_by_id(&service, user.id).await.unwrap();
         assert!(user.is_none());
     }
 }
@@ -1,5 +1,6 @@
 use derive_more::derive::Display;
 use juniper::graphql_scalar;
+use serde::{Serialize, Serializer};
 use std::hash::Hash;
 use uuid::Uuid;
 
@@ -29,6 +30,12 @@ impl DbUuid {
     }
 }
 
+impl Serialize for DbUuid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
 impl From<Uuid> for DbUuid {
     fn from(uuid: Uuid) -> Self {
         DbUuid(uuid)
@@ -0,0 +1,28 @@
+//! Serializes `NaiveDateTime` fields as the GraphQL `LocalDateTime` scalar writes them,
+//! `2024-05-01 09:30:00`, for models that are also sent as JSON (see `error::Conflict`)
+//!
+//! ```ignore
+//! #[serde(serialize_with = "local_date_time::serialize")]
+//! pub created_at: NaiveDateTime,
+//! #[serde(serialize_with = "local_date_time::serialize_option")]
+//! pub deleted_at: Option<NaiveDateTime>,
+//! ```
+
+use chrono::NaiveDateTime;
+use serde::Serializer;
+
+pub const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+pub fn serialize<S: Serializer>(value: &NaiveDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&value.format(FORMAT))
+}
+
+pub fn serialize_option<S: Serializer>(
+    value: &Option<NaiveDateTime>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => serialize(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
@@ -1,3 +1,6 @@
 pub mod db_uuid;
+pub mod local_date_time;
 pub mod money;
 pub mod percentage;
+pub mod rate;
+pub mod rounding;
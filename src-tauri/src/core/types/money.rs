@@ -6,15 +6,24 @@ use std::{
 use bigdecimal::{BigDecimal, ToPrimitive};
 use juniper::{graphql_scalar, InputValue, ScalarValue, Value};
 use lightning_macros::{LibsqlType, SeaQueryType};
+use serde::{Serialize, Serializer};
 
 use crate::adapters::outgoing::database::FromLibsqlValue;
 
+use super::{
+    percentage::Percentage,
+    rate::Rate,
+    rounding::{self, parse_scaled, RoundingMode},
+};
+
 #[derive(Debug, Clone, Copy, Eq, Ord, PartialEq, PartialOrd, SeaQueryType, LibsqlType)]
 #[graphql_scalar(parse_token(String))]
 pub struct Money(i64);
 
 impl Money {
     pub const BASE_UNIT: i64 = 100; // For 2 decimal places (cents)
+    pub const MINOR_UNITS: u32 = 2;
+    pub const CASH_INCREMENT: i64 = 5; // Smallest coin in circulation (0.05)
 
     /// Creates a new Money from a float value
     /// Example: 10.99 becomes 1099 cents ($10.99)
@@ -25,29 +34,89 @@ impl Money {
 
     /// Creates a new Money from a string representation
     /// Example: "10.99" becomes 1099 cents ($10.99)
-    /// Rounds to nearest cent
+    /// Parses the digits exactly and rounds half up to the nearest cent
     pub fn from_str(s: &str) -> Result<Self, String> {
-        let value = s.parse::<f64>().map_err(|e| e.to_string())?;
+        Self::from_str_with_minor_units(s, Self::MINOR_UNITS)
+    }
 
-        // Convert to cents with rounding
-        let cents = (value * Self::BASE_UNIT as f64).round() as i64;
-        Ok(Self(cents))
+    /// Creates a new Money for a currency with `minor_units` decimal places
+    /// Example: "1500" in JPY (0 decimals) becomes 1500, "1.234" in KWD (3 decimals) becomes 1234
+    pub fn from_str_with_minor_units(s: &str, minor_units: u32) -> Result<Self, String> {
+        parse_scaled(s, minor_units, RoundingMode::HalfUp).map(Self)
     }
 
     /// Returns the money value as a string without currency symbol
     /// Example: 1099 cents becomes "10.99"
     pub fn to_string(&self) -> String {
-        let dollars = self.0 / Self::BASE_UNIT;
-        let cents = self.0 % Self::BASE_UNIT;
+        self.to_string_with_minor_units(Self::MINOR_UNITS)
+    }
+
+    /// Formats the value for a currency with `minor_units` decimal places
+    /// Example: 1234 in KWD (3 decimals) becomes "1.234"
+    pub fn to_string_with_minor_units(&self, minor_units: u32) -> String {
+        let unit = 10_i64.pow(minor_units);
+        let whole = self.0 / unit;
+        let fraction = self.0 % unit;
 
-        if cents == 0 {
-            dollars.to_string()
+        if fraction == 0 {
+            whole.to_string()
         } else {
-            // Format with 2 decimal places
-            format!("{}.{:02}", dollars, cents.abs())
+            // Keep the sign for values between -1 and 0
+            let sign = if self.0 < 0 && whole == 0 { "-" } else { "" };
+            format!("{}{}.{:0width$}", sign, whole, fraction.abs(), width = minor_units as usize)
         }
     }
 
+    /// Converts an amount in one currency into another at `rate` target units per source unit.
+    /// Rounds half away from zero to the target currency's minor unit.
+    pub fn convert(&self, rate: Rate, from_minor_units: u32, to_minor_units: u32) -> Money {
+        let numerator = self.0 as i128 * rate.value() as i128 * 10_i128.pow(to_minor_units);
+        let denominator = Rate::SCALE as i128 * 10_i128.pow(from_minor_units);
+
+        Money(RoundingMode::HalfUp.div(numerator, denominator) as i64)
+    }
+
+    /// Applies a percentage to the amount, rounding to the nearest cent with `mode`
+    /// Example: 10.05 at 18% with half-up becomes 1.81
+    pub fn percent(&self, percentage: Percentage, mode: RoundingMode) -> Money {
+        let numerator = self.0 as i128 * percentage.basis_points() as i128;
+        let denominator = 100 * Percentage::BASIS_POINTS as i128;
+
+        Money(mode.div(numerator, denominator) as i64)
+    }
+
+    /// Divides the amount, rounding to the nearest cent with `mode`
+    pub fn div_round(&self, divisor: i32, mode: RoundingMode) -> Money {
+        Money(mode.div(self.0 as i128, divisor as i128) as i64)
+    }
+
+    /// Rounds the amount to a multiple of `increment` minor units
+    /// Example: 10.07 to an increment of 5 with half-up becomes 10.05
+    pub fn round_to(&self, increment: i64, mode: RoundingMode) -> Money {
+        Money(mode.div(self.0 as i128, increment as i128) as i64 * increment)
+    }
+
+    /// Rounds a cash total to the smallest coin, ties going up
+    /// Example: 10.03 becomes 10.05, 10.02 becomes 10
+    pub fn cash_round(&self) -> Money {
+        self.round_to(Self::CASH_INCREMENT, RoundingMode::HalfUp)
+    }
+
+    /// Splits the amount in proportion to `weights` so the parts sum exactly to it
+    /// Leftover cents go to the parts with the largest remainders
+    /// Example: 100.00 across weights [1, 1, 1] becomes [33.34, 33.33, 33.33]
+    pub fn allocate(&self, weights: &[i64]) -> Vec<Money> {
+        rounding::allocate(self.0, weights)
+            .into_iter()
+            .map(Money)
+            .collect()
+    }
+
+    /// Splits the amount into `parts` near-equal shares that sum exactly to it
+    pub fn split(&self, parts: usize) -> Vec<Money> {
+        self.allocate(&vec![1; parts])
+    }
+
     pub fn to_base_unit(&self) -> i64 {
         self.0
     }
@@ -67,6 +136,13 @@ impl Money {
     }
 }
 
+// As the GraphQL scalar, a decimal string
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
 impl From<i64> for Money {
     fn from(value: i64) -> Self {
         Money(value)
@@ -119,16 +195,28 @@ impl Mul<i32> for Money {
     }
 }
 
+impl Mul<Percentage> for Money {
+    type Output = Money;
+
+    /// Rounds half up to the nearest cent
+    fn mul(self, other: Percentage) -> Self::Output {
+        self.percent(other, RoundingMode::HalfUp)
+    }
+}
+
 impl Div<i32> for Money {
     type Output = Money;
 
+    /// Rounds half up to the nearest cent
     fn div(self, other: i32) -> Self::Output {
-        Money(self.0 / other as i64)
+        self.div_round(other, RoundingMode::HalfUp)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+
     use super::*;
 
     #[test]
@@ -243,4 +331,96 @@ mod tests {
         let m = Money(-575);
         assert_eq!(m.to_string(), "-5.75");
     }
+
+    #[test]
+    fn test_money_with_minor_units() {
+        // JPY has no minor unit
+        let m = Money::from_str_with_minor_units("1500", 0).unwrap();
+        assert_eq!(m.0, 1500);
+        assert_eq!(m.to_string_with_minor_units(0), "1500");
+
+        // KWD has three decimal places
+        let m = Money::from_str_with_minor_units("1.234", 3).unwrap();
+        assert_eq!(m.0, 1234);
+        assert_eq!(m.to_string_with_minor_units(3), "1.234");
+
+        let m = Money::from_str_with_minor_units("-0.05", 3).unwrap();
+        assert_eq!(m.0, -50);
+        assert_eq!(m.to_string_with_minor_units(3), "-0.050");
+    }
+
+    #[test]
+    fn test_money_exact_parsing() {
+        // Values that drift through f64
+        assert_eq!(Money::from_str("0.1").unwrap().0, 10);
+        assert_eq!(Money::from_str("1.005").unwrap().0, 101);
+        assert_eq!(Money::from_str("90071992547409.93").unwrap().0, 9007199254740993);
+        assert_eq!(Money::from_str("-0.015").unwrap().0, -2);
+
+        assert!(Money::from_str("").is_err());
+        assert!(Money::from_str("92233720368547758.08").is_err());
+    }
+
+    #[test]
+    fn test_money_percent() {
+        let m = Money::from_str("10.05").unwrap();
+        let p = Percentage::from_str("18").unwrap();
+        assert_eq!(m * p, Money(181));
+
+        // 2.5% of 1.00 is exactly half a cent
+        let m = Money(100);
+        let p = Percentage::from_str("2.5").unwrap();
+        assert_eq!(m.percent(p, RoundingMode::HalfUp), Money(3));
+        assert_eq!(m.percent(p, RoundingMode::HalfEven), Money(2));
+        assert_eq!(m.percent(p, RoundingMode::Down), Money(2));
+    }
+
+    #[test]
+    fn test_money_divide_rounds() {
+        assert_eq!(Money(1000) / 3, Money(333));
+        assert_eq!(Money(1001) / 2, Money(501));
+        assert_eq!(Money(1001).div_round(2, RoundingMode::HalfEven), Money(500));
+        assert_eq!(Money(-1001) / 2, Money(-501));
+    }
+
+    #[test]
+    fn test_money_cash_rounding() {
+        assert_eq!(Money(1003).cash_round(), Money(1005));
+        assert_eq!(Money(1002).cash_round(), Money(1000));
+        assert_eq!(Money(1007).cash_round(), Money(1005));
+        assert_eq!(Money(1008).cash_round(), Money(1010));
+        assert_eq!(Money(-1003).cash_round(), Money(-1005));
+        assert_eq!(Money(1049).round_to(100, RoundingMode::Down), Money(1000));
+    }
+
+    #[test]
+    fn test_money_allocate() {
+        let parts = Money(10000).split(3);
+        assert_eq!(parts, vec![Money(3334), Money(3333), Money(3333)]);
+
+        // A 10.00 discount across lines worth 30.00, 45.00 and 25.00
+        let parts = Money(1000).allocate(&[3000, 4500, 2500]);
+        assert_eq!(parts, vec![Money(300), Money(450), Money(250)]);
+
+        let parts = Money(1001).allocate(&[1, 1, 1]);
+        assert_eq!(parts.into_iter().sum::<Money>(), Money(1001));
+    }
+
+    #[test]
+    fn test_money_convert() {
+        // 10.00 USD at 83.25 INR per USD
+        let usd = Money::from_str("10").unwrap();
+        let rate = Rate::from_str("83.25").unwrap();
+        assert_eq!(usd.convert(rate, 2, 2), Money(83250));
+
+        // 1500 JPY at 0.5612 INR per JPY
+        let jpy = Money::from_str_with_minor_units("1500", 0).unwrap();
+        let rate = Rate::from_str("0.5612").unwrap();
+        assert_eq!(jpy.convert(rate, 0, 2), Money(84180));
+
+        // 1.234 KWD at 270.5 INR per KWD rounds to the nearest paisa
+        let kwd = Money::from_str_with_minor_units("1.234", 3).unwrap();
+        let rate = Rate::from_str("270.5").unwrap();
+        assert_eq!(kwd.convert(rate, 3, 2), Money(33380));
+    }
 }
@@ -8,6 +8,8 @@ use sea_query;
 
 use crate::adapters::outgoing::database::FromLibsqlValue;
 
+use super::rounding::{parse_scaled, RoundingMode};
+
 #[derive(Debug, Clone, Copy, Eq, Ord, PartialEq, PartialOrd, SeaQueryType, LibsqlType)]
 #[graphql_scalar(parse_token(String))]
 pub struct Percentage(i32);
@@ -18,19 +20,18 @@ impl Percentage {
     /// Creates a new Percentage from a float value
     /// Example: 2.5 becomes 25000 basis points (2.5%)
     /// Rounds to nearest basis point
-    pub fn from_float(value: f32) -> Self {
-        Self((value * Self::BASIS_POINTS as f32).round() as i32)
+    pub fn from_float(value: f64) -> Self {
+        Self((value * Self::BASIS_POINTS as f64).round() as i32)
     }
 
     /// Creates a new Percentage from a string representation
     /// Example: "2.5" becomes 25000 basis points (2.5%)
-    /// Rounds to nearest basis point
+    /// Parses the digits exactly and rounds half up to the nearest basis point
     pub fn from_str(s: &str) -> std::result::Result<Self, String> {
-        let value = s.parse::<f32>().map_err(|e| e.to_string())?;
-
-        // Convert to basis points with rounding
-        let basis_points = (value * Self::BASIS_POINTS as f32).round() as i32;
-        Ok(Self(basis_points))
+        let basis_points = parse_scaled(s, 4, RoundingMode::HalfUp)?;
+        i32::try_from(basis_points)
+            .map(Self)
+            .map_err(|_| format!("percentage out of range: {}", s))
     }
 
     /// Returns the percentage as a string without % symbol
@@ -43,10 +44,12 @@ impl Percentage {
             whole.to_string()
         } else {
             // Handle up to 4 decimal places
-            let mut frac_str = format!("{:04}", frac);
+            let mut frac_str = format!("{:04}", frac.abs());
             // Trim trailing zeros but keep necessary decimal places
             frac_str = frac_str.trim_end_matches('0').to_string();
-            format!("{}.{}", whole, frac_str)
+            // Keep the sign for values between -1 and 0
+            let sign = if self.0 < 0 && whole == 0 { "-" } else { "" };
+            format!("{}{}.{}", sign, whole, frac_str)
         }
     }
 
@@ -107,8 +110,9 @@ impl Mul<i32> for Percentage {
 impl Div<i32> for Percentage {
     type Output = Self;
 
+    /// Rounds half up to the nearest basis point
     fn div(self, other: i32) -> Self::Output {
-        Self(self.0 / other)
+        Self(RoundingMode::HalfUp.div(self.0 as i128, other as i128) as i32)
     }
 }
 
@@ -236,6 +240,26 @@ mod tests {
         assert_eq!(sum.to_string(), "7");
     }
 
+    #[test]
+    fn test_percentage_exact_parsing() {
+        // Values that drift through f32
+        assert_eq!(Percentage::from_str("0.1").unwrap().0, 1000);
+        assert_eq!(Percentage::from_str("33.3333").unwrap().0, 333333);
+        assert_eq!(Percentage::from_str("18.00005").unwrap().0, 180001);
+        assert_eq!(Percentage::from_str("-2.5").unwrap().0, -25000);
+        assert_eq!(Percentage::from_str("-0.25").unwrap().to_string(), "-0.25");
+
+        assert!(Percentage::from_str("").is_err());
+        assert!(Percentage::from_str("300000").is_err());
+    }
+
+    #[test]
+    fn test_percentage_divide_rounds() {
+        assert_eq!(Percentage(10).to_string(), "0.001");
+        assert_eq!((Percentage(10) / 3).0, 3);
+        assert_eq!((Percentage(11) / 2).0, 6);
+    }
+
     #[test]
     fn test_percentage_decimal_places() {
         assert_eq!(Percentage(47500).to_string(), "4.75");
@@ -0,0 +1,84 @@
+use std::{fmt, str::FromStr};
+
+use juniper::{graphql_scalar, InputValue, ScalarValue, Value};
+use lightning_macros::{LibsqlType, SeaQueryType};
+
+use crate::adapters::outgoing::database::FromLibsqlValue;
+
+use super::rounding::{parse_scaled, RoundingMode};
+
+/// An exchange rate with six decimal places of precision
+#[derive(Debug, Clone, Copy, Eq, Ord, PartialEq, PartialOrd, SeaQueryType, LibsqlType)]
+#[graphql_scalar(parse_token(String))]
+pub struct Rate(i64);
+
+impl Rate {
+    pub const SCALE: i64 = 1_000_000; // 6 decimal places
+    pub const ONE: Rate = Rate(Self::SCALE);
+
+    /// Returns the raw scaled value
+    pub fn value(&self) -> i64 {
+        self.0
+    }
+
+    fn to_output<S: ScalarValue>(&self) -> Value<S> {
+        Value::scalar(self.to_string())
+    }
+
+    fn from_input<S: ScalarValue>(v: &InputValue<S>) -> Result<Self, String> {
+        let s = v.as_string_value().ok_or("Expected a string")?;
+        s.parse()
+    }
+}
+
+impl FromStr for Rate {
+    type Err = String;
+
+    /// Parses a decimal string, rounding to the nearest millionth.
+    /// Example: "83.25" becomes 83250000
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_scaled(s, 6, RoundingMode::HalfUp).map(Self)
+    }
+}
+
+impl fmt::Display for Rate {
+    /// Writes the rate with trailing zeros trimmed.
+    /// Example: 83250000 becomes "83.25"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let whole = self.0 / Self::SCALE;
+        let fraction = self.0 % Self::SCALE;
+
+        if fraction == 0 {
+            write!(f, "{}", whole)
+        } else {
+            let fraction = format!("{:06}", fraction.abs());
+            write!(f, "{}.{}", whole, fraction.trim_end_matches('0'))
+        }
+    }
+}
+
+impl From<i64> for Rate {
+    fn from(value: i64) -> Self {
+        Rate(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_from_str() {
+        assert_eq!(Rate::from_str("1").unwrap(), Rate::ONE);
+        assert_eq!(Rate::from_str("83.25").unwrap().value(), 83_250_000);
+        assert_eq!(Rate::from_str("0.005612").unwrap().value(), 5612);
+        assert!(Rate::from_str("abc").is_err());
+    }
+
+    #[test]
+    fn test_rate_to_string() {
+        assert_eq!(Rate::ONE.to_string(), "1");
+        assert_eq!(Rate(83_250_000).to_string(), "83.25");
+        assert_eq!(Rate(5612).to_string(), "0.005612");
+    }
+}
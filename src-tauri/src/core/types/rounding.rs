@@ -0,0 +1,194 @@
+/// How to resolve a value that falls between two representable units
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum RoundingMode {
+    /// Ties round away from zero: 2.5 -> 3, -2.5 -> -3
+    #[default]
+    HalfUp,
+    /// Ties round to the nearest even unit (banker's rounding): 2.5 -> 2, 3.5 -> 4
+    HalfEven,
+    /// Discards the remainder: 2.9 -> 2, -2.9 -> -2
+    Down,
+}
+
+impl RoundingMode {
+    /// Divides `numerator` by `denominator`, rounding the quotient with this mode
+    pub fn div(&self, numerator: i128, denominator: i128) -> i128 {
+        assert!(denominator != 0, "division by zero");
+
+        let quotient = numerator / denominator;
+        let remainder = numerator % denominator;
+        if remainder == 0 {
+            return quotient;
+        }
+
+        // Direction the quotient moves when rounding away from zero
+        let sign = if (numerator < 0) != (denominator < 0) { -1 } else { 1 };
+        let twice = remainder.abs() * 2;
+        let divisor = denominator.abs();
+
+        let away = match self {
+            RoundingMode::Down => false,
+            RoundingMode::HalfUp => twice >= divisor,
+            RoundingMode::HalfEven => twice > divisor || (twice == divisor && quotient % 2 != 0),
+        };
+
+        if away {
+            quotient + sign
+        } else {
+            quotient
+        }
+    }
+}
+
+/// Parses a plain decimal string into an integer scaled by 10^`scale`
+/// Example: ("10.995", 2) becomes 1100 with half-up rounding
+/// Digits beyond `scale` are rounded with `mode` rather than going through a float
+pub fn parse_scaled(s: &str, scale: u32, mode: RoundingMode) -> Result<i64, String> {
+    let trimmed = s.trim();
+    let (negative, unsigned) = match trimmed.as_bytes().first() {
+        Some(b'-') => (true, &trimmed[1..]),
+        Some(b'+') => (false, &trimmed[1..]),
+        _ => (false, trimmed),
+    };
+
+    let (whole, fraction) = match unsigned.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (unsigned, ""),
+    };
+
+    let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !all_digits(whole) || !all_digits(fraction) {
+        return Err(format!("invalid decimal value: {}", s));
+    }
+
+    // Collect every digit so the excess can be rounded in one division
+    let digits = format!("{}{}", whole, fraction);
+    let digits = digits.trim_start_matches('0');
+    if digits.len() > 36 {
+        return Err(format!("decimal value out of range: {}", s));
+    }
+    let mut value: i128 = if digits.is_empty() {
+        0
+    } else {
+        digits.parse().map_err(|_| format!("invalid decimal value: {}", s))?
+    };
+    if negative {
+        value = -value;
+    }
+
+    let fraction_len = fraction.len() as u32;
+    let scaled = if fraction_len <= scale {
+        value.checked_mul(10_i128.pow(scale - fraction_len))
+    } else {
+        let excess = fraction_len - scale;
+        if excess > 36 {
+            Some(0)
+        } else {
+            Some(mode.div(value, 10_i128.pow(excess)))
+        }
+    };
+
+    scaled
+        .and_then(|v| i64::try_from(v).ok())
+        .ok_or_else(|| format!("decimal value out of range: {}", s))
+}
+
+/// Splits `total` across `weights` so the parts sum exactly to `total`
+/// Each part gets its truncated share; the units left over go one at a time
+/// to the parts with the largest remainders, earlier parts winning ties.
+/// With no positive weight the total is split evenly.
+pub fn allocate(total: i64, weights: &[i64]) -> Vec<i64> {
+    if weights.is_empty() {
+        return Vec::new();
+    }
+
+    let weights: Vec<i128> = if weights.iter().any(|w| *w > 0) {
+        weights.iter().map(|w| (*w).max(0) as i128).collect()
+    } else {
+        vec![1; weights.len()]
+    };
+    let weight_sum: i128 = weights.iter().sum();
+
+    // Allocate the magnitude and restore the sign at the end
+    let magnitude = (total as i128).abs();
+    let mut parts: Vec<i128> = Vec::with_capacity(weights.len());
+    let mut remainders: Vec<(usize, i128)> = Vec::with_capacity(weights.len());
+    for (index, weight) in weights.iter().enumerate() {
+        let share = magnitude * weight;
+        parts.push(share / weight_sum);
+        remainders.push((index, share % weight_sum));
+    }
+
+    let mut leftover = magnitude - parts.iter().sum::<i128>();
+    remainders.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    for (index, _) in remainders {
+        if leftover == 0 {
+            break;
+        }
+        parts[index] += 1;
+        leftover -= 1;
+    }
+
+    let sign = if total < 0 { -1 } else { 1 };
+    parts.into_iter().map(|p| (p * sign) as i64).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rounding_modes() {
+        assert_eq!(RoundingMode::HalfUp.div(25, 10), 3);
+        assert_eq!(RoundingMode::HalfUp.div(-25, 10), -3);
+        assert_eq!(RoundingMode::HalfUp.div(24, 10), 2);
+
+        assert_eq!(RoundingMode::HalfEven.div(25, 10), 2);
+        assert_eq!(RoundingMode::HalfEven.div(35, 10), 4);
+        assert_eq!(RoundingMode::HalfEven.div(-25, 10), -2);
+        assert_eq!(RoundingMode::HalfEven.div(26, 10), 3);
+
+        assert_eq!(RoundingMode::Down.div(29, 10), 2);
+        assert_eq!(RoundingMode::Down.div(-29, 10), -2);
+    }
+
+    #[test]
+    fn test_parse_scaled() {
+        assert_eq!(parse_scaled("0.1", 2, RoundingMode::HalfUp).unwrap(), 10);
+        assert_eq!(parse_scaled("10.995", 2, RoundingMode::HalfUp).unwrap(), 1100);
+        assert_eq!(parse_scaled("10.985", 2, RoundingMode::HalfEven).unwrap(), 1098);
+        assert_eq!(parse_scaled("-0.05", 2, RoundingMode::HalfUp).unwrap(), -5);
+        assert_eq!(parse_scaled(".5", 2, RoundingMode::HalfUp).unwrap(), 50);
+        assert_eq!(parse_scaled("7.", 0, RoundingMode::HalfUp).unwrap(), 7);
+        assert_eq!(
+            parse_scaled("92233720368547758.07", 2, RoundingMode::HalfUp).unwrap(),
+            i64::MAX
+        );
+
+        assert!(parse_scaled("", 2, RoundingMode::HalfUp).is_err());
+        assert!(parse_scaled(".", 2, RoundingMode::HalfUp).is_err());
+        assert!(parse_scaled("1e3", 2, RoundingMode::HalfUp).is_err());
+        assert!(parse_scaled("10.abc", 2, RoundingMode::HalfUp).is_err());
+        assert!(parse_scaled("92233720368547758.08", 2, RoundingMode::HalfUp).is_err());
+    }
+
+    #[test]
+    fn test_allocate() {
+        // 100 split three ways gives the extra unit to the first part
+        assert_eq!(allocate(100, &[1, 1, 1]), vec![34, 33, 33]);
+
+        // Largest remainder wins the leftover unit
+        assert_eq!(allocate(100, &[30, 30, 40]), vec![30, 30, 40]);
+        assert_eq!(allocate(10, &[1, 2, 4]), vec![1, 3, 6]);
+
+        // Negative totals keep their sign
+        assert_eq!(allocate(-100, &[1, 1, 1]), vec![-34, -33, -33]);
+
+        // Zero weights split evenly
+        assert_eq!(allocate(5, &[0, 0]), vec![3, 2]);
+        assert!(allocate(5, &[]).is_empty());
+
+        let parts = allocate(99_999, &[7, 13, 29, 51]);
+        assert_eq!(parts.iter().sum::<i64>(), 99_999);
+    }
+}
@@ -0,0 +1,121 @@
+//! Sealing data with a passphrase, for backups and the secrets file, and hashing user PINs
+//!
+//! Data is sealed with AES-256-GCM under a key derived from the passphrase with
+//! PBKDF2-HMAC-SHA256. A sealed blob is `magic || salt || nonce || ciphertext || tag`. The
+//! magic names what the blob holds and is authenticated along with it.
+//!
+//! PINs are kept as `pbkdf2-sha256$<iterations>$<salt>$<hash>`, in unpadded base64.
+
+use std::num::NonZeroU32;
+
+use base64::{engine::general_purpose::STANDARD_NO_PAD, Engine};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    digest::{digest, SHA256},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+
+use crate::error::{Error, Result};
+
+const SALT_LEN: usize = 16;
+const PBKDF2_ITERATIONS: u32 = 100_000;
+
+const PIN_SCHEME: &str = "pbkdf2-sha256";
+/// A PIN has few digits, so more iterations would only slow down every approval
+const PIN_ITERATIONS: u32 = 10_000;
+const PIN_HASH_LEN: usize = 32;
+
+/// Seals `plain` with a key derived from `passphrase`
+pub fn seal(plain: &[u8], passphrase: &str, magic: &[u8]) -> Result<Vec<u8>> {
+    let salt = random_bytes(SALT_LEN)?;
+    let nonce: [u8; NONCE_LEN] = random_bytes(NONCE_LEN)?.try_into().map_err(|_| crypto_error())?;
+
+    let mut sealed = Vec::with_capacity(magic.len() + SALT_LEN + NONCE_LEN + plain.len() + AES_256_GCM.tag_len());
+    sealed.extend_from_slice(magic);
+    sealed.extend_from_slice(&salt);
+    sealed.extend_from_slice(&nonce);
+
+    let mut in_out = plain.to_vec();
+    key(passphrase, &salt)?
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(magic), &mut in_out)
+        .map_err(|_| crypto_error())?;
+    sealed.append(&mut in_out);
+    Ok(sealed)
+}
+
+/// Opens a blob sealed by [`seal`] with the same magic
+pub fn open(sealed: &[u8], passphrase: &str, magic: &[u8]) -> Result<Vec<u8>> {
+    if !sealed.starts_with(magic) || sealed.len() < magic.len() + SALT_LEN + NONCE_LEN {
+        return Err(Error::ValidationError("The data is not sealed".to_string()));
+    }
+    let (salt, rest) = sealed[magic.len()..].split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| crypto_error())?;
+
+    let mut in_out = ciphertext.to_vec();
+    let plain_len = key(passphrase, salt)?
+        .open_in_place(nonce, Aad::from(magic), &mut in_out)
+        .map_err(|_| Error::ValidationError("The passphrase is wrong or the data is damaged".to_string()))?
+        .len();
+    in_out.truncate(plain_len);
+    Ok(in_out)
+}
+
+/// `len` bytes from the system's secure random number generator
+pub fn random_bytes(len: usize) -> Result<Vec<u8>> {
+    let mut bytes = vec![0u8; len];
+    SystemRandom::new().fill(&mut bytes).map_err(|_| crypto_error())?;
+    Ok(bytes)
+}
+
+/// Hashes a PIN for storage, see [`verify_pin`]
+pub fn hash_pin(pin: &str) -> Result<String> {
+    let salt = random_bytes(SALT_LEN)?;
+    let iterations = NonZeroU32::new(PIN_ITERATIONS).expect("PBKDF2 iterations are not zero");
+    let mut hash = [0u8; PIN_HASH_LEN];
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, &salt, pin.as_bytes(), &mut hash);
+
+    Ok(format!(
+        "{}${}${}${}",
+        PIN_SCHEME,
+        PIN_ITERATIONS,
+        STANDARD_NO_PAD.encode(salt),
+        STANDARD_NO_PAD.encode(hash)
+    ))
+}
+
+/// Whether `pin` is the one `stored` was made from. PINs stored before they were hashed are
+/// compared as they are, in constant time like the hashes.
+pub fn verify_pin(pin: &str, stored: &str) -> bool {
+    let mut parts = stored.split('$');
+    let (Some(PIN_SCHEME), Some(iterations), Some(salt), Some(hash), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        // Digests have the same length whatever the PINs are
+        let (pin, stored) = (digest(&SHA256, pin.as_bytes()), digest(&SHA256, stored.as_bytes()));
+        return pin.as_ref().iter().zip(stored.as_ref()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0;
+    };
+
+    let iterations = iterations.parse().ok().and_then(NonZeroU32::new);
+    let salt = STANDARD_NO_PAD.decode(salt).ok();
+    let hash = STANDARD_NO_PAD.decode(hash).ok();
+    match (iterations, salt, hash) {
+        (Some(iterations), Some(salt), Some(hash)) => {
+            pbkdf2::verify(pbkdf2::PBKDF2_HMAC_SHA256, iterations, &salt, pin.as_bytes(), &hash).is_ok()
+        }
+        _ => false,
+    }
+}
+
+fn key(passphrase: &str, salt: &[u8]) -> Result<LessSafeKey> {
+    let mut key = [0u8; 32];
+    let iterations = NonZeroU32::new(PBKDF2_ITERATIONS).expect("PBKDF2 iterations are not zero");
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key);
+    let key = UnboundKey::new(&AES_256_GCM, &key).map_err(|_| crypto_error())?;
+    Ok(LessSafeKey::new(key))
+}
+
+fn crypto_error() -> Error {
+    Error::ValidationError("Failed to encrypt".to_string())
+}
@@ -0,0 +1,121 @@
+//! Reads and writes comma separated values as spreadsheet apps do (RFC 4180)
+//!
+//! Fields may be quoted with `"`, in which case they can hold commas, line breaks and `""`
+//! for a quote. A leading byte order mark is skipped.
+
+use std::io::Write;
+
+use crate::error::{Error, Result};
+
+/// The records of `text`, each a list of fields. Blank lines are kept as empty records so
+/// that record `n` is line `n` of files without line breaks inside fields.
+pub fn read(text: &str) -> Result<Vec<Vec<String>>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut field));
+                records.push(finish(std::mem::take(&mut record)));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(Error::ValidationError(format!(
+            "Unterminated quote in record {}",
+            records.len() + 1
+        )));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(finish(record));
+    }
+
+    Ok(records)
+}
+
+/// Writes one record, quoting the fields that need it, followed by a CRLF line break
+pub fn write_record<W: Write, S: AsRef<str>>(out: &mut W, fields: &[S]) -> Result<()> {
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            out.write_all(b",")?;
+        }
+        let field = field.as_ref();
+        if field.contains([',', '"', '\r', '\n']) || field.trim() != field {
+            write!(out, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            out.write_all(field.as_bytes())?;
+        }
+    }
+    out.write_all(b"\r\n")?;
+    Ok(())
+}
+
+// A blank line reads as one empty field, which is no fields at all
+fn finish(record: Vec<String>) -> Vec<String> {
+    match record.as_slice() {
+        [field] if field.is_empty() => Vec::new(),
+        _ => record,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_quoted_fields() {
+        let text = "\u{feff}name,description,price\r\n\"Tea, green\",\"Say \"\"hi\"\"\nthen pour\",2.50\n\nCola,,1\n";
+
+        assert_eq!(
+            read(text).unwrap(),
+            vec![
+                vec!["name", "description", "price"],
+                vec!["Tea, green", "Say \"hi\"\nthen pour", "2.50"],
+                vec![],
+                vec!["Cola", "", "1"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_written_records_read_back() {
+        let records = [vec!["name", "note"], vec!["Tea, green", "Say \"hi\"\nthen pour"], vec![" padded", ""]];
+        let mut out = Vec::new();
+        for record in &records {
+            write_record(&mut out, record).unwrap();
+        }
+
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("name,note\r\n\"Tea, green\","));
+        assert_eq!(read(&text).unwrap(), records);
+    }
+
+    #[test]
+    fn test_read_without_trailing_line_break() {
+        assert_eq!(read("a,b\n1,").unwrap(), vec![vec!["a", "b"], vec!["1", ""]]);
+        assert!(read("a,\"b\n").is_err());
+    }
+}
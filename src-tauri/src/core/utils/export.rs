@@ -0,0 +1,72 @@
+//! Where exports are written
+//!
+//! Every export goes to one directory, set with `EXPORT_DIR` (default `exports` next to the
+//! database), under a name given with the request. Names are relative and cannot step out of
+//! the directory with `..`, so a request cannot write anywhere else on the machine.
+
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone)]
+pub struct ExportConfig {
+    pub dir: PathBuf,
+}
+
+impl ExportConfig {
+    /// Reads `EXPORT_DIR`, defaulting to `exports` next to the database
+    pub fn load(db_path: &str) -> Self {
+        let dir = std::env::var("EXPORT_DIR")
+            .ok()
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                let parent = Path::new(db_path).parent().unwrap_or(Path::new(""));
+                parent.join("exports")
+            });
+        ExportConfig { dir }
+    }
+
+    /// Where the export named `name` is written, creating the folders on the way to it.
+    /// `name` may name folders inside the export directory but nothing outside it.
+    pub fn resolve(&self, name: &str) -> Result<PathBuf> {
+        let name = Path::new(name.trim());
+        let inside = name.components().all(|component| matches!(component, Component::Normal(_)));
+        if name.as_os_str().is_empty() || !inside {
+            return Err(Error::ValidationError(
+                "The file name must be relative to the export directory, without ..".to_string(),
+            ));
+        }
+
+        let path = self.dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_names_stay_in_the_export_directory() {
+        let config = ExportConfig { dir: std::env::temp_dir().join(format!("minnal-exports-{}", Uuid::now_v7())) };
+
+        assert_eq!(config.resolve("orders.csv").unwrap(), config.dir.join("orders.csv"));
+        assert_eq!(config.resolve("2025/03/orders.csv").unwrap(), config.dir.join("2025/03/orders.csv"));
+        assert!(config.dir.join("2025/03").is_dir());
+
+        for name in ["", "/etc/passwd.csv", "../orders.csv", "2025/../../orders.csv", "./orders.csv"] {
+            assert!(matches!(config.resolve(name), Err(Error::ValidationError(_))), "{}", name);
+        }
+
+        fs::remove_dir_all(&config.dir).unwrap();
+    }
+}
@@ -1 +1,4 @@
-
+pub mod crypto;
+pub mod csv;
+pub mod export;
+pub mod xlsx;
@@ -0,0 +1,466 @@
+//! Reads the first worksheet of an Excel workbook (.xlsx), and writes workbooks of one sheet
+//!
+//! An .xlsx file is a zip archive of XML parts. Only what a data sheet needs is read: the
+//! shared strings and the cell values of the first sheet, all as text. Formulas read as the
+//! value Excel last computed and dates as the serial number it stores.
+//!
+//! Workbooks come from uploads, so reading stops at Excel's own row and column limits, at
+//! `MAX_PART_SIZE` of XML per part and at `MAX_SHEET_SIZE` of rows read.
+//!
+//! `Writer` streams rows into the sheet as they come, with text in inline strings, so that
+//! large sheets are never held in memory.
+
+use std::{
+    borrow::Cow,
+    io::{Cursor, Read, Seek, Write},
+    mem::size_of,
+};
+
+use quick_xml::{escape::escape, events::Event, Reader};
+use zip::{
+    result::ZipError,
+    write::SimpleFileOptions,
+    CompressionMethod, ZipArchive, ZipWriter,
+};
+
+use crate::error::{Error, Result};
+
+/// The most rows and columns Excel allows in a sheet
+const MAX_ROWS: usize = 1_048_576;
+const MAX_COLUMNS: usize = 16_384;
+
+/// The most a part of the workbook may take once decompressed
+const MAX_PART_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The most memory the rows read may take, counting the empty cells a row skips
+const MAX_SHEET_SIZE: usize = 256 * 1024 * 1024;
+
+/// The rows of the first sheet, each a list of cell values. Row `n` of the sheet is at index
+/// `n - 1`, with rows the sheet skips left empty.
+pub fn read(bytes: &[u8]) -> Result<Vec<Vec<String>>> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(invalid)?;
+    let shared_strings = match part(&mut archive, "xl/sharedStrings.xml")? {
+        Some(xml) => shared_strings(&xml)?,
+        None => Vec::new(),
+    };
+    let sheet = first_sheet(&mut archive)?;
+    let xml = part(&mut archive, &sheet)?.ok_or_else(|| invalid(format!("{} is missing", sheet)))?;
+
+    rows(&xml, &shared_strings, MAX_SHEET_SIZE)
+}
+
+fn invalid(reason: impl std::fmt::Display) -> Error {
+    Error::ValidationError(format!("Not a valid XLSX file: {}", reason))
+}
+
+type Archive<'a> = ZipArchive<Cursor<&'a [u8]>>;
+
+/// The contents of the part `name`, if the archive has it
+fn part(archive: &mut Archive, name: &str) -> Result<Option<String>> {
+    part_within(archive, name, MAX_PART_SIZE)
+}
+
+fn part_within(archive: &mut Archive, name: &str, limit: u64) -> Result<Option<String>> {
+    let file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(invalid(e)),
+    };
+    let too_large = || invalid(format!("{} is too large to read", name));
+    // The size in the directory can understate what the data inflates to
+    if file.size() > limit {
+        return Err(too_large());
+    }
+    let mut contents = String::new();
+    file.take(limit + 1).read_to_string(&mut contents).map_err(invalid)?;
+    if contents.len() as u64 > limit {
+        return Err(too_large());
+    }
+    Ok(Some(contents))
+}
+
+fn attribute<'e>(element: &'e quick_xml::events::BytesStart, name: &[u8]) -> Result<Option<Cow<'e, str>>> {
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(invalid)?;
+        if attribute.key.as_ref() == name {
+            return attribute.unescape_value().map(Some).map_err(invalid);
+        }
+    }
+    Ok(None)
+}
+
+// The path of the first sheet, from the workbook and its relationships
+fn first_sheet(archive: &mut Archive) -> Result<String> {
+    let workbook = part(archive, "xl/workbook.xml")?.ok_or_else(|| invalid("xl/workbook.xml is missing"))?;
+    let mut reader = Reader::from_str(&workbook);
+    let id = loop {
+        match reader.read_event().map_err(invalid)? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"sheet" => {
+                break attribute(&e, b"r:id")?.ok_or_else(|| invalid("sheet without r:id"))?.into_owned();
+            }
+            Event::Eof => return Err(invalid("the workbook has no sheets")),
+            _ => {}
+        }
+    };
+
+    let relationships = part(archive, "xl/_rels/workbook.xml.rels")?
+        .ok_or_else(|| invalid("xl/_rels/workbook.xml.rels is missing"))?;
+    let mut reader = Reader::from_str(&relationships);
+    loop {
+        match reader.read_event().map_err(invalid)? {
+            Event::Start(e) | Event::Empty(e)
+                if e.local_name().as_ref() == b"Relationship" && attribute(&e, b"Id")?.as_deref() == Some(id.as_str()) =>
+            {
+                let target = attribute(&e, b"Target")?.ok_or_else(|| invalid("relationship without Target"))?;
+                return Ok(match target.strip_prefix('/') {
+                    Some(absolute) => absolute.to_string(),
+                    None => format!("xl/{}", target),
+                });
+            }
+            Event::Eof => return Err(invalid(format!("no relationship {}", id))),
+            _ => {}
+        }
+    }
+}
+
+// The text of every `<si>`, leaving out phonetic guides
+fn shared_strings(xml: &str) -> Result<Vec<String>> {
+    let mut reader = Reader::from_str(xml);
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let (mut in_text, mut in_phonetic) = (false, false);
+
+    loop {
+        match reader.read_event().map_err(invalid)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"si" => current.clear(),
+                b"t" => in_text = true,
+                b"rPh" => in_phonetic = true,
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"si" => strings.push(std::mem::take(&mut current)),
+                b"t" => in_text = false,
+                b"rPh" => in_phonetic = false,
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"si" => strings.push(String::new()),
+            Event::Text(text) if in_text && !in_phonetic => current.push_str(&text.unescape().map_err(invalid)?),
+            Event::Eof => return Ok(strings),
+            _ => {}
+        }
+    }
+}
+
+// Stops once the rows would take more than `max_size`
+fn rows(xml: &str, shared_strings: &[String], max_size: usize) -> Result<Vec<Vec<String>>> {
+    let mut reader = Reader::from_str(xml);
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut row = Vec::new();
+    let mut row_number = 0;
+    // The cell being read: its column, type and value
+    let mut cell: Option<(usize, Option<String>, String)> = None;
+    let mut in_value = false;
+    // What the rows read take so far
+    let mut size = 0;
+
+    loop {
+        match reader.read_event().map_err(invalid)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"row" => {
+                    row_number = row_index(attribute(&e, b"r")?, row_number)?;
+                    row.clear();
+                }
+                b"c" => {
+                    let column = match attribute(&e, b"r")? {
+                        Some(reference) => column_index(&reference)?,
+                        None if row.len() < MAX_COLUMNS => row.len(),
+                        None => return Err(invalid(format!("more than {} columns", MAX_COLUMNS))),
+                    };
+                    let kind = attribute(&e, b"t")?.map(Cow::into_owned);
+                    cell = Some((column, kind, String::new()));
+                }
+                b"v" | b"t" => in_value = cell.is_some(),
+                _ => {}
+            },
+            Event::Text(text) if in_value => {
+                if let Some((_, _, value)) = cell.as_mut() {
+                    value.push_str(&text.unescape().map_err(invalid)?);
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"v" | b"t" => in_value = false,
+                b"c" => {
+                    if let Some((column, kind, value)) = cell.take() {
+                        let value = match kind.as_deref() {
+                            Some("s") => value
+                                .trim()
+                                .parse::<usize>()
+                                .ok()
+                                .and_then(|index| shared_strings.get(index))
+                                .ok_or_else(|| invalid(format!("bad shared string {}", value)))?
+                                .clone(),
+                            Some("b") => if value == "1" { "TRUE" } else { "FALSE" }.to_string(),
+                            _ => value,
+                        };
+                        let added = (column + 1).saturating_sub(row.len());
+                        size = within_size(size + added * size_of::<String>() + value.len(), max_size)?;
+                        if added > 0 {
+                            row.resize(column + 1, String::new());
+                        }
+                        row[column] = value;
+                    }
+                }
+                b"row" => {
+                    let added = row_number.saturating_sub(rows.len());
+                    size = within_size(size + added * size_of::<Vec<String>>(), max_size)?;
+                    if added > 0 {
+                        rows.resize(row_number, Vec::new());
+                    }
+                    rows[row_number - 1] = std::mem::take(&mut row);
+                }
+                _ => {}
+            },
+            Event::Empty(e) if e.local_name().as_ref() == b"row" => {
+                row_number = row_index(attribute(&e, b"r")?, row_number)?;
+            }
+            Event::Eof => return Ok(rows),
+            _ => {}
+        }
+    }
+}
+
+// The number of a row, from its `r` or else the row before it
+fn row_index(reference: Option<Cow<str>>, previous: usize) -> Result<usize> {
+    let row = match reference {
+        Some(r) => r.parse().map_err(|_| invalid(format!("bad row number {}", r)))?,
+        None => previous + 1,
+    };
+    match row {
+        1..=MAX_ROWS => Ok(row),
+        _ => Err(invalid(format!("row {} is outside the sheet", row))),
+    }
+}
+
+fn within_size(size: usize, max_size: usize) -> Result<usize> {
+    match size <= max_size {
+        true => Ok(size),
+        false => Err(invalid("the sheet is too large to read")),
+    }
+}
+
+// `C7` is column 2
+fn column_index(reference: &str) -> Result<usize> {
+    let bad = || invalid(format!("bad cell reference {}", reference));
+    let mut column = 0;
+    for letter in reference.chars().take_while(char::is_ascii_alphabetic) {
+        column = column * 26 + (letter.to_ascii_uppercase() as usize - 'A' as usize + 1);
+        // XFD, the last column, has three letters
+        if column > MAX_COLUMNS {
+            return Err(bad());
+        }
+    }
+
+    column.checked_sub(1).ok_or_else(bad)
+}
+
+/// A cell of a written sheet
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Empty,
+    Text(String),
+    /// A number in its decimal form, such as `12.50`
+    Number(String),
+}
+
+/// Writes a workbook of one sheet, a row at a time
+pub struct Writer<W: Write + Seek> {
+    zip: ZipWriter<W>,
+    rows: usize,
+}
+
+impl<W: Write + Seek> Writer<W> {
+    /// Starts a workbook whose only sheet is called `sheet_name`
+    pub fn new(inner: W, sheet_name: &str) -> Result<Self> {
+        let mut zip = ZipWriter::new(inner);
+        let workbook = WORKBOOK.replace("{name}", &escape(sheet_name));
+        let parts = [
+            ("[Content_Types].xml", CONTENT_TYPES),
+            ("_rels/.rels", ROOT_RELATIONSHIPS),
+            ("xl/workbook.xml", workbook.as_str()),
+            ("xl/_rels/workbook.xml.rels", WORKBOOK_RELATIONSHIPS),
+        ];
+        for (name, contents) in parts {
+            zip.start_file(name, SimpleFileOptions::default().compression_method(CompressionMethod::Stored))
+                .map_err(zip_error)?;
+            zip.write_all(contents.as_bytes())?;
+        }
+
+        let sheet = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated).large_file(true);
+        zip.start_file("xl/worksheets/sheet1.xml", sheet).map_err(zip_error)?;
+        zip.write_all(SHEET_START.as_bytes())?;
+        Ok(Self { zip, rows: 0 })
+    }
+
+    pub fn write_row(&mut self, cells: &[Cell]) -> Result<()> {
+        self.rows += 1;
+        let mut xml = format!("<row r=\"{}\">", self.rows);
+        for (index, cell) in cells.iter().enumerate() {
+            let reference = format!("{}{}", column_name(index), self.rows);
+            match cell {
+                Cell::Empty => {}
+                Cell::Text(text) => xml.push_str(&format!(
+                    "<c r=\"{}\" t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+                    reference,
+                    escape(text.as_str())
+                )),
+                Cell::Number(number) => {
+                    xml.push_str(&format!("<c r=\"{}\"><v>{}</v></c>", reference, escape(number.as_str())))
+                }
+            }
+        }
+        xml.push_str("</row>");
+        self.zip.write_all(xml.as_bytes())?;
+        Ok(())
+    }
+
+    /// Completes the workbook and returns what it was written to
+    pub fn finish(mut self) -> Result<W> {
+        self.zip.write_all(SHEET_END.as_bytes())?;
+        self.zip.finish().map_err(zip_error)
+    }
+}
+
+fn zip_error(e: ZipError) -> Error {
+    Error::IoError(e.into())
+}
+
+// `2` is column C
+fn column_name(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.iter().rev().map(|&letter| letter as char).collect()
+}
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#;
+
+const ROOT_RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+const WORKBOOK: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="{name}" sheetId="1" r:id="rId1"/></sheets></workbook>"#;
+
+const WORKBOOK_RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#;
+
+const SHEET_START: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#;
+
+const SHEET_END: &str = "</sheetData></worksheet>";
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// A zip archive of `files`, the first one stored and the others deflated
+    fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (index, (name, contents)) in files.iter().enumerate() {
+            let method = if index == 0 { CompressionMethod::Stored } else { CompressionMethod::Deflated };
+            zip.start_file(*name, SimpleFileOptions::default().compression_method(method)).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    /// A workbook whose first sheet holds `sheet_data`, the XML inside `<sheetData>`
+    pub fn workbook(shared_strings: &[&str], sheet_data: &str) -> Vec<u8> {
+        let strings: String = shared_strings.iter().map(|s| format!("<si><t>{}</t></si>", s)).collect();
+        zip(&[
+            (
+                "xl/workbook.xml",
+                r#"<workbook xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Items" sheetId="1" r:id="rId3"/><sheet name="Notes" sheetId="2" r:id="rId4"/></sheets></workbook>"#,
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships><Relationship Id="rId4" Target="worksheets/sheet2.xml"/><Relationship Id="rId3" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            ),
+            ("xl/sharedStrings.xml", &format!("<sst>{}</sst>", strings)),
+            ("xl/worksheets/sheet1.xml", &format!("<worksheet><sheetData>{}</sheetData></worksheet>", sheet_data)),
+            ("xl/worksheets/sheet2.xml", "<worksheet><sheetData/></worksheet>"),
+        ])
+    }
+
+    #[test]
+    fn test_read_first_sheet() {
+        let bytes = workbook(
+            &["name", "price", "Tea &amp; Biscuits"],
+            r#"<row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c></row>
+               <row r="3"><c r="A3" t="s"><v>2</v></c><c r="C3" t="b"><v>1</v></c></row>
+               <row r="4"><c r="B4"><v>12.5</v></c><c r="AA4" t="inlineStr"><is><t>wide</t></is></c></row>"#,
+        );
+
+        let rows = read(&bytes).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0], ["name", "price"]);
+        assert!(rows[1].is_empty());
+        assert_eq!(rows[2], ["Tea & Biscuits", "", "TRUE"]);
+        assert_eq!(rows[3][1], "12.5");
+        assert_eq!(rows[3][26], "wide");
+    }
+
+    #[test]
+    fn test_written_workbook_reads_back() {
+        let mut writer = Writer::new(Cursor::new(Vec::new()), "Orders & Items").unwrap();
+        writer.write_row(&[Cell::Text("name".to_string()), Cell::Text("price".to_string())]).unwrap();
+        writer.write_row(&[Cell::Text(" Tea <green> ".to_string()), Cell::Number("2.50".to_string())]).unwrap();
+        let mut wide = vec![Cell::Empty; 27];
+        wide[26] = Cell::Text("wide".to_string());
+        writer.write_row(&wide).unwrap();
+
+        let rows = read(&writer.finish().unwrap().into_inner()).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], ["name", "price"]);
+        assert_eq!(rows[1], [" Tea <green> ", "2.50"]);
+        assert_eq!(rows[2][26], "wide");
+        assert_eq!((column_name(25), column_name(26), column_name(702)), ("Z".into(), "AA".into(), "AAA".into()));
+    }
+
+    #[test]
+    fn test_read_rejects_other_files() {
+        assert!(matches!(read(b"name,price\n"), Err(Error::ValidationError(_))));
+        assert!(matches!(read(&zip(&[("hello.txt", "hi")])), Err(Error::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_read_stays_within_limits() {
+        let bad = |sheet_data: &str| read(&workbook(&[], sheet_data));
+        assert!(matches!(bad(r#"<row r="1"><c r="ZZZZZZZZZZZZZZ1"><v>1</v></c></row>"#), Err(Error::ValidationError(_))));
+        assert!(matches!(bad(r#"<row r="1"><c r="XFE1"><v>1</v></c></row>"#), Err(Error::ValidationError(_))));
+        assert!(matches!(bad(r#"<row r="4000000000"><c><v>1</v></c></row>"#), Err(Error::ValidationError(_))));
+        assert!(matches!(bad(r#"<row r="0"/>"#), Err(Error::ValidationError(_))));
+
+        // The last cell Excel allows
+        let last = bad(r#"<row r="1048576"><c r="XFD1048576"><v>1</v></c></row>"#).unwrap();
+        assert_eq!(last[MAX_ROWS - 1][MAX_COLUMNS - 1], "1");
+
+        // Empty cells before a value count towards the size
+        let wide = r#"<row><c r="A1"><v>1</v></c></row><row><c r="Z2"><v>1</v></c></row>"#;
+        assert!(rows(wide, &[], 1024).is_ok());
+        assert!(matches!(rows(wide, &[], 512), Err(Error::ValidationError(_))));
+
+        // A part is not inflated past the limit
+        let bytes = zip(&[("small.xml", "<a/>"), ("large.xml", &"<row/>".repeat(1024))]);
+        let mut archive = ZipArchive::new(Cursor::new(bytes.as_slice())).unwrap();
+        assert_eq!(part_within(&mut archive, "small.xml", 1024).unwrap().as_deref(), Some("<a/>"));
+        assert!(matches!(part_within(&mut archive, "large.xml", 1024), Err(Error::ValidationError(_))));
+        assert_eq!(part_within(&mut archive, "missing.xml", 1024).unwrap(), None);
+    }
+}
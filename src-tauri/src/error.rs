@@ -1,4 +1,8 @@
+use std::fmt;
+
 use derive_more::derive::{Display, From};
+use serde::Serialize;
+#[cfg(feature = "desktop")]
 use tauri::ipc::InvokeError;
 
 pub type Result<T> = core::result::Result<T, Error>;
@@ -9,24 +13,185 @@ pub enum Error {
     NotFoundError,
     AuthenticationError,
     AuthorizationError,
-    ForeignKeyConstraintError,
-    UniqueConstraintError,
+    #[display("ForeignKeyConstraintError({_0})")]
+    #[from(ignore)]
+    ForeignKeyConstraintError(Constraint),
+    #[display("UniqueConstraintError({_0})")]
+    #[from(ignore)]
+    UniqueConstraintError(Constraint),
     HasChildrenError,
     AlreadyExistsError,
+    #[display("ConflictError({_0})")]
+    #[from(ignore)]
+    ConflictError(Conflict),
+    #[from(ignore)]
+    ValidationError(String),
     DatabaseError(String),
+    #[from(ignore)]
+    SecretStoreError(String),
+    /// The IAM service did not answer, or answered with an error of its own
+    #[from(ignore)]
+    IamUnavailableError(String),
 
     // External Errors
     GraphQLError(juniper::GraphQLError),
     ParseError(juniper::ParseError),
     SerdeJsonError(serde_json::Error),
     SeaQueryError(sea_query::error::Error),
+    #[cfg(feature = "desktop")]
     TauriError(tauri::Error),
     UuidError(uuid::Error),
     LibsqlError(libsql::Error),
+    IoError(std::io::Error),
+}
+
+/// The table and columns a constraint was violated on
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Constraint {
+    pub table: String,
+    pub columns: Vec<String>,
+}
+
+impl Constraint {
+    pub fn new(table: &str, column: &str) -> Self {
+        Self { table: table.to_string(), columns: vec![column.to_string()] }
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.columns.as_slice() {
+            [] => write!(f, "{}", self.table),
+            [column] => write!(f, "{}.{}", self.table, column),
+            columns => write!(f, "{}({})", self.table, columns.join(", ")),
+        }
+    }
+}
+
+/// A write based on a stale read of a row that has changed since
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub table: String,
+    /// The row as it is now, with fields named as in GraphQL
+    pub current: serde_json::Value,
+}
+
+impl Conflict {
+    pub fn new<T: Serialize>(table: &str, current: &T) -> Result<Self> {
+        Ok(Self { table: table.to_string(), current: serde_json::to_value(current)? })
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.table)
+    }
 }
 
+impl Error {
+    /// A stable code clients can match on, unlike the message
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::NotFoundError => "NOT_FOUND",
+            Error::AuthenticationError => "UNAUTHENTICATED",
+            Error::AuthorizationError => "FORBIDDEN",
+            Error::ForeignKeyConstraintError(_) => "FOREIGN_KEY_VIOLATION",
+            Error::UniqueConstraintError(_) => "UNIQUE_VIOLATION",
+            Error::HasChildrenError => "HAS_CHILDREN",
+            Error::AlreadyExistsError => "ALREADY_EXISTS",
+            Error::ConflictError(_) => "CONFLICT",
+            Error::ValidationError(_) => "VALIDATION_FAILED",
+            Error::IamUnavailableError(_) => "UNAVAILABLE",
+            Error::GraphQLError(_) | Error::ParseError(_) | Error::UuidError(_) => "BAD_REQUEST",
+            Error::DatabaseError(_)
+            | Error::SecretStoreError(_)
+            | Error::SerdeJsonError(_)
+            | Error::SeaQueryError(_)
+            | Error::LibsqlError(_)
+            | Error::IoError(_) => "INTERNAL",
+            #[cfg(feature = "desktop")]
+            Error::TauriError(_) => "INTERNAL",
+        }
+    }
+
+    /// A message that is safe to show to users. Internal errors are not described, as they
+    /// can include SQL.
+    pub fn message(&self) -> String {
+        match self {
+            Error::NotFoundError => "Not found".to_string(),
+            Error::AuthenticationError => "Authentication failed".to_string(),
+            Error::AuthorizationError => "Not allowed".to_string(),
+            Error::ForeignKeyConstraintError(_) => {
+                "A related record does not exist or is still in use".to_string()
+            }
+            Error::UniqueConstraintError(constraint) => match constraint.columns.as_slice() {
+                [] => "Already exists".to_string(),
+                columns => format!("{} must be unique", columns.join(", ")),
+            },
+            Error::HasChildrenError => "Still in use by other records".to_string(),
+            Error::AlreadyExistsError => "Already exists".to_string(),
+            Error::ConflictError(_) => "Changed by someone else since it was loaded".to_string(),
+            Error::ValidationError(message) => message.clone(),
+            Error::IamUnavailableError(_) => "The sign-in service cannot be reached".to_string(),
+            Error::GraphQLError(e) => e.to_string(),
+            Error::ParseError(e) => e.to_string(),
+            Error::UuidError(_) => "Invalid ID".to_string(),
+            _ => "Internal error".to_string(),
+        }
+    }
+
+    /// The constraint behind a constraint violation
+    pub fn constraint(&self) -> Option<&Constraint> {
+        match self {
+            Error::ForeignKeyConstraintError(constraint) | Error::UniqueConstraintError(constraint) => {
+                Some(constraint)
+            }
+            _ => None,
+        }
+    }
+
+    /// The row behind a conflict, as it is now
+    pub fn conflict(&self) -> Option<&Conflict> {
+        match self {
+            Error::ConflictError(conflict) => Some(conflict),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "desktop")]
 impl From<Error> for InvokeError {
     fn from(err: Error) -> Self {
-        InvokeError::from(err.to_string())
+        InvokeError(serde_json::json!({
+            "code": err.code(),
+            "message": err.message(),
+            "constraint": err.constraint().map(|constraint| serde_json::json!({
+                "table": constraint.table,
+                "columns": constraint.columns,
+            })),
+            "current": err.conflict().map(|conflict| conflict.current.clone()),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_internal_errors_are_not_described() {
+        let err = Error::DatabaseError("Failed to execute query: no such table: items".to_string());
+
+        assert_eq!(err.code(), "INTERNAL");
+        assert_eq!(err.message(), "Internal error");
+    }
+
+    #[test]
+    fn test_constraint_errors() {
+        let err = Error::UniqueConstraintError(Constraint::new("item_categories", "name"));
+
+        assert_eq!(err.code(), "UNIQUE_VIOLATION");
+        assert_eq!(err.message(), "name must be unique");
+        assert_eq!(err.to_string(), "UniqueConstraintError(item_categories.name)");
     }
 }
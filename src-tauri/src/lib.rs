@@ -2,36 +2,202 @@ pub mod adapters;
 pub mod core;
 pub mod error;
 
-use core::commands::app_service::AppService;
-use tokio::sync::Mutex;
+use std::{sync::Arc, time::Duration};
 
+use adapters::graphql::loaders::Loaders;
+#[cfg(feature = "desktop")]
+use adapters::{graphql::endpoint::GraphQLEndpoint, incoming::tauri::Subscriptions};
+use adapters::outgoing::iam::SessionStatus;
+use core::{
+    commands::{
+        app_service::AppService, auth::auth_commands::RefreshSessionCommand,
+        catalog::discount_commands::RefreshDiscountStatesCommand, common::backup_commands::CreateBackupCommand,
+        sales::cart_commands::PurgeAbandonedCartsCommand, Command,
+    },
+    db::backup::{self, BackupConfig},
+    models::common::backup_model::BackupKind,
+    types::db_uuid::DbUuid,
+};
+#[cfg(feature = "desktop")]
+use tauri::Manager;
+
+/// How often scheduled and expiring discounts are moved to their new state
+const DISCOUNT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often abandoned carts are purged
+const CART_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often the backup schedule is checked. A backup is taken once the newest scheduled
+/// one is older than `BACKUP_INTERVAL_HOURS`, so restarts do not reset the schedule.
+const BACKUP_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// How often the IAM session is checked. It is refreshed once its token is within ten
+/// minutes of expiring.
+const SESSION_REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Shared by all requests without a lock of its own; mutations serialise through
+/// `AppService::write`
 pub struct AppState {
-    pub service: Mutex<AppService>,
+    pub service: Arc<AppService>,
+    pub loaders: Arc<Loaders>,
+    /// The user a request was authenticated as, when it came in over HTTP
+    pub user: Option<DbUuid>,
+}
+
+impl AppState {
+    pub fn new(service: AppService) -> Self {
+        AppState {
+            service: Arc::new(service),
+            loaders: Arc::default(),
+            user: None,
+        }
+    }
+
+    /// The context for one GraphQL request: the same service with empty loader caches
+    pub fn for_request(&self) -> Self {
+        AppState {
+            service: Arc::clone(&self.service),
+            loaders: Arc::default(),
+            user: self.user,
+        }
+    }
+
+    /// The context for one request made with a user's API token
+    pub fn for_user(&self, user: DbUuid) -> Self {
+        AppState {
+            user: Some(user),
+            ..self.for_request()
+        }
+    }
 }
 
+#[cfg(feature = "desktop")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub async fn run() {
     // Initialize the database path
     let db_path = "minnal.db";
 
-    // Create the app service - it will automatically use Turso if credentials are available
+    // Create the app service on the local database
     let app_service = AppService::new(db_path).await;
 
     // Create the app state with the service
-    let app_state = AppState {
-        service: app_service.into(),
-    };
+    let app_state = AppState::new(app_service);
+
+    // Switch to the synced replica when an earlier login left a session in the secret store,
+    // without holding up the start when offline
+    spawn_session_refresh(Arc::clone(&app_state.service));
 
     // Build and run the Tauri application
     let app = tauri::Builder::default()
         .manage(app_state)
+        .manage(GraphQLEndpoint::from_env())
+        .manage(Subscriptions::default())
         .invoke_handler(tauri::generate_handler![
             crate::adapters::incoming::tauri::graphql,
+            crate::adapters::incoming::tauri::subscribe,
+            crate::adapters::incoming::tauri::unsubscribe,
             crate::adapters::incoming::tauri::schema
         ])
         .plugin(tauri_plugin_fs::init())
+        .setup(|app| {
+            let service = Arc::clone(&app.state::<AppState>().service);
+            spawn_background_jobs(service);
+            Ok(())
+        })
         .build(tauri::generate_context!())
         .expect("error while building tauri application");
 
     app.run(|_, _| {});
 }
+
+/// Starts the periodic maintenance jobs that run for the lifetime of the app
+pub fn spawn_background_jobs(service: Arc<AppService>) {
+    let cart_service = Arc::clone(&service);
+    let backup_service = Arc::clone(&service);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DISCOUNT_REFRESH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let service = service.write().await;
+            if let Err(e) = RefreshDiscountStatesCommand.exec(&service).await {
+                eprintln!("Failed to refresh discount states: {}", e);
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        let command = PurgeAbandonedCartsCommand::load(cart_service.secrets.as_ref()).unwrap_or_else(|e| {
+            eprintln!("Abandoned carts are purged after the default age, as the setting could not be read: {}", e);
+            PurgeAbandonedCartsCommand::default()
+        });
+        let mut interval = tokio::time::interval(CART_PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let service = cart_service.write().await;
+            if let Err(e) = command.exec(&service).await {
+                eprintln!("Failed to purge abandoned carts: {}", e);
+            }
+        }
+    });
+
+    let config = match BackupConfig::load(&backup_service.conn_path, backup_service.secrets.as_ref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Scheduled backups are off, as their settings could not be read: {}", e);
+            return;
+        }
+    };
+    if config.interval.is_none() || backup_service.conn_path == ":memory:" {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(BACKUP_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            match backup::is_due(&config, &backup_service.conn_path) {
+                Ok(false) => continue,
+                Ok(true) => {}
+                Err(e) => {
+                    eprintln!("Failed to check the backup schedule: {}", e);
+                    continue;
+                }
+            }
+            let service = backup_service.write().await;
+            let command = CreateBackupCommand { config: config.clone(), kind: BackupKind::Scheduled, label: None };
+            if let Err(e) = command.exec(&service).await {
+                eprintln!("Failed to back up the database: {}", e);
+            }
+        }
+    });
+}
+
+/// Keeps the synced replica connected with a valid token: connects it once the IAM session
+/// is usable, at start or when the IAM service is back, and again after each refresh
+pub fn spawn_session_refresh(service: Arc<AppService>) {
+    tokio::spawn(async move {
+        let mut connected = false;
+        let mut interval = tokio::time::interval(SESSION_REFRESH_INTERVAL);
+        loop {
+            interval.tick().await;
+            match RefreshSessionCommand.exec(&service).await {
+                Ok(SessionStatus::Refreshed) => {}
+                Ok(SessionStatus::Current) if !connected => {}
+                Ok(_) => continue,
+                // Not signed in, or offline for longer than the grace period
+                Err(error::Error::AuthenticationError) => {
+                    connected = false;
+                    continue;
+                }
+                Err(e) => {
+                    eprintln!("Failed to refresh the IAM session: {}", e);
+                    continue;
+                }
+            }
+            let service = service.write().await;
+            match service.update_adapter().await {
+                Ok(()) => connected = true,
+                Err(e) => eprintln!("Failed to open the synced database: {}", e),
+            }
+        }
+    });
+}
@@ -8,6 +8,10 @@ const config: CodegenConfig = {
     generates: {
         './lib/graphql/': {
             preset: 'client',
+            presetConfig: {
+                // Writes persisted-documents.json, which the backend loads from PERSISTED_QUERIES
+                persistedDocuments: true,
+            },
             config: {
                 documentMode: 'string',
                 scalars: {
@@ -1,6 +1,19 @@
 import { invoke } from '@tauri-apps/api/core'
 import { TypedDocumentString } from './graphql'
 
+/** An error returned by the backend, with the stable code from its extensions */
+export class GraphQLRequestError extends Error {
+    readonly code: string
+    readonly fields: string[]
+
+    constructor(error: { message: string; extensions?: { code?: string; fields?: string[] } }) {
+        super(error.message)
+        this.name = 'GraphQLRequestError'
+        this.code = error.extensions?.code ?? 'INTERNAL'
+        this.fields = error.extensions?.fields ?? []
+    }
+}
+
 export async function gql<TResult, TVariables>(
     query: TypedDocumentString<TResult, TVariables>,
     ...[vars]: TVariables extends Record<string, never> ? [] : [TVariables]
@@ -9,12 +22,15 @@ export async function gql<TResult, TVariables>(
 
     console.log('yoyo request', query.toString(), vars)
 
-    response = await invoke('graphql', { query, vars })
+    // Documents generated with persistedDocuments carry their hash
+    const hash = (query as { __meta__?: { hash?: string } }).__meta__?.hash
+
+    response = await invoke('graphql', { query, hash, vars })
 
     console.log('yoyo response', response)
 
     if (response[1]?.length > 0) {
-        throw new Error(response[1][0].message)
+        throw new GraphQLRequestError(response[1][0])
     }
 
     return response[0] as TResult